@@ -0,0 +1,191 @@
+#!/usr/bin/env node
+
+/**
+ * Bundle a portable Python for the psychology scripts
+ * Downloads a python-build-standalone "install_only" build for the target
+ * platform, checks it against the release's SHA256SUMS and extracts it to
+ * resources/python/ (see src-tauri/src/scripts/interpreter.rs)
+ */
+
+import { createHash } from 'crypto';
+import { createReadStream, createWriteStream, existsSync, mkdirSync, readFileSync, renameSync, rmSync, writeFileSync } from 'fs';
+import { join, dirname } from 'path';
+import { fileURLToPath } from 'url';
+import { pipeline } from 'stream/promises';
+import { createGunzip } from 'zlib';
+import { extract } from 'tar';
+
+const __dirname = dirname(fileURLToPath(import.meta.url));
+const rootDir = join(__dirname, '..');
+const resourcesDir = join(rootDir, 'src-tauri', 'resources');
+const pythonDir = join(resourcesDir, 'python');
+const stampFile = join(pythonDir, '.bundled');
+
+// python-build-standalone release and the CPython it ships
+const RELEASE = '20241016';
+const PYTHON_VERSION = '3.12.7';
+const RELEASE_URL = `https://github.com/astral-sh/python-build-standalone/releases/download/${RELEASE}`;
+
+// Platform configurations
+const PLATFORMS = {
+  'win-x64': 'x86_64-pc-windows-msvc',
+  'darwin-x64': 'x86_64-apple-darwin',
+  'darwin-arm64': 'aarch64-apple-darwin',
+  'linux-x64': 'x86_64-unknown-linux-gnu',
+};
+
+function archiveName(triple) {
+  return `cpython-${PYTHON_VERSION}+${RELEASE}-${triple}-install_only.tar.gz`;
+}
+
+/**
+ * Download a file from URL to destination
+ */
+async function downloadFile(url, dest) {
+  console.log(`  Downloading from ${url}...`);
+
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Failed to download: ${response.status} ${response.statusText}`);
+  }
+
+  const fileStream = createWriteStream(dest);
+  await pipeline(response.body, fileStream);
+  console.log(`  Downloaded to ${dest}`);
+}
+
+/**
+ * The checksum the release lists for `name`
+ */
+async function expectedSha256(name) {
+  const response = await fetch(`${RELEASE_URL}/SHA256SUMS`);
+  if (!response.ok) {
+    throw new Error(`Failed to download SHA256SUMS: ${response.status} ${response.statusText}`);
+  }
+  for (const line of (await response.text()).split('\n')) {
+    const [hash, file] = line.trim().split(/\s+/);
+    if (file === name) return hash;
+  }
+  throw new Error(`${name} is not listed in the release's SHA256SUMS`);
+}
+
+async function sha256(path) {
+  const hash = createHash('sha256');
+  await pipeline(createReadStream(path), hash);
+  return hash.digest('hex');
+}
+
+/**
+ * Download, verify and extract Python for a specific platform
+ */
+async function bundlePlatform(platformName, triple) {
+  const name = archiveName(triple);
+
+  // Check if already downloaded (idempotent)
+  if (existsSync(stampFile) && readFileSync(stampFile, 'utf8').trim() === name) {
+    console.log(`  ${platformName}: Already bundled (skipping)`);
+    return;
+  }
+
+  console.log(`  ${platformName}: Downloading Python ${PYTHON_VERSION}...`);
+
+  // Create temp directory
+  const tempDir = join(resourcesDir, '.temp-python');
+  if (existsSync(tempDir)) {
+    rmSync(tempDir, { recursive: true });
+  }
+  mkdirSync(tempDir, { recursive: true });
+
+  const archivePath = join(tempDir, name);
+  await downloadFile(`${RELEASE_URL}/${encodeURIComponent(name)}`, archivePath);
+
+  const expected = await expectedSha256(name);
+  const actual = await sha256(archivePath);
+  if (actual !== expected) {
+    throw new Error(`Checksum mismatch for ${name}: expected ${expected}, got ${actual}`);
+  }
+
+  // The archive holds a single python/ directory
+  console.log(`  Extracting ${archivePath}...`);
+  await pipeline(createReadStream(archivePath), createGunzip(), extract({ cwd: tempDir }));
+
+  if (existsSync(pythonDir)) {
+    rmSync(pythonDir, { recursive: true });
+  }
+  renameSync(join(tempDir, 'python'), pythonDir);
+  writeFileSync(stampFile, `${name}\n`);
+
+  // Cleanup temp directory
+  rmSync(tempDir, { recursive: true });
+
+  console.log(`  ${platformName}: Done!`);
+}
+
+/**
+ * Parse command line arguments
+ */
+function parseArgs() {
+  const args = process.argv.slice(2);
+  const options = { platform: null, arch: null };
+
+  for (let i = 0; i < args.length; i++) {
+    if (args[i] === '--platform' && args[i + 1]) {
+      options.platform = args[++i];
+    } else if (args[i] === '--arch' && args[i + 1]) {
+      options.arch = args[++i];
+    }
+  }
+
+  return options;
+}
+
+/**
+ * Get current platform identifier
+ */
+function getCurrentPlatform() {
+  const platform = process.platform;
+  const arch = process.arch;
+
+  if (platform === 'win32') return 'win-x64';
+  if (platform === 'darwin' && arch === 'arm64') return 'darwin-arm64';
+  if (platform === 'darwin') return 'darwin-x64';
+  if (platform === 'linux') return 'linux-x64';
+
+  throw new Error(`Unsupported platform: ${platform} ${arch}`);
+}
+
+/**
+ * Main entry point. Only one platform at a time: the bundle ships a
+ * single resources/python, for the target being built.
+ */
+async function main() {
+  console.log('Bundling Python runtime...');
+  console.log(`Python version: ${PYTHON_VERSION} (python-build-standalone ${RELEASE})`);
+
+  const options = parseArgs();
+
+  let platform;
+  if (options.platform) {
+    platform = options.arch ? `${options.platform}-${options.arch}` : options.platform;
+    if (!PLATFORMS[platform]) {
+      console.error(`Unknown platform: ${platform}`);
+      console.error(`Available: ${Object.keys(PLATFORMS).join(', ')}`);
+      process.exit(1);
+    }
+  } else {
+    platform = getCurrentPlatform();
+  }
+
+  if (!existsSync(resourcesDir)) {
+    mkdirSync(resourcesDir, { recursive: true });
+  }
+
+  await bundlePlatform(platform, PLATFORMS[platform]);
+
+  console.log('\nPython bundling complete!');
+}
+
+main().catch((error) => {
+  console.error('Bundle failed:', error);
+  process.exit(1);
+});
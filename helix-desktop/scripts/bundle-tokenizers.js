@@ -0,0 +1,82 @@
+#!/usr/bin/env node
+
+/**
+ * Bundle the tokenizer vocabularies
+ * Downloads the tiktoken vocabularies helix-shared counts tokens with,
+ * checks them against the hashes tiktoken pins and writes them to
+ * resources/tokenizers/ (see helix-rust/crates/shared/src/tokenizer.rs)
+ */
+
+import { createHash } from 'crypto';
+import { existsSync, mkdirSync, readFileSync, renameSync, writeFileSync } from 'fs';
+import { join, dirname } from 'path';
+import { fileURLToPath } from 'url';
+
+const __dirname = dirname(fileURLToPath(import.meta.url));
+const rootDir = join(__dirname, '..');
+const tokenizersDir = join(rootDir, 'src-tauri', 'resources', 'tokenizers');
+
+const BASE_URL = 'https://openaipublic.blob.core.windows.net/encodings';
+
+// Encodings and their SHA-256, as pinned by tiktoken (tiktoken_ext/openai_public.py)
+const ENCODINGS = {
+  cl100k_base: '223921b76ee99bde995b7ff738513eef100fb51d18c93597a113bcffe865b2a7',
+  o200k_base: '446a9538cb6c348e3516120d7c08b09f57c36495e2acfffe59a5bf8b0cfb1a2d',
+};
+
+function sha256(data) {
+  return createHash('sha256').update(data).digest('hex');
+}
+
+/**
+ * Download and verify one vocabulary
+ */
+async function bundleEncoding(name, expected) {
+  const dest = join(tokenizersDir, `${name}.tiktoken`);
+
+  // Check if already downloaded (idempotent)
+  if (existsSync(dest) && sha256(readFileSync(dest)) === expected) {
+    console.log(`  ${name}: Already bundled (skipping)`);
+    return;
+  }
+
+  const url = `${BASE_URL}/${name}.tiktoken`;
+  console.log(`  Downloading from ${url}...`);
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Failed to download: ${response.status} ${response.statusText}`);
+  }
+
+  const data = Buffer.from(await response.arrayBuffer());
+  const actual = sha256(data);
+  if (actual !== expected) {
+    throw new Error(`Checksum mismatch for ${name}: expected ${expected}, got ${actual}`);
+  }
+
+  // Written beside it first, so an interrupted run leaves no partial file
+  writeFileSync(`${dest}.tmp`, data);
+  renameSync(`${dest}.tmp`, dest);
+  console.log(`  ${name}: Done!`);
+}
+
+/**
+ * Main entry point
+ */
+async function main() {
+  console.log('Bundling tokenizer vocabularies...');
+
+  if (!existsSync(tokenizersDir)) {
+    mkdirSync(tokenizersDir, { recursive: true });
+  }
+
+  for (const [name, hash] of Object.entries(ENCODINGS)) {
+    await bundleEncoding(name, hash);
+  }
+
+  console.log('\nTokenizer bundling complete!');
+}
+
+main().catch((error) => {
+  console.error('Bundle failed:', error);
+  process.exit(1);
+});
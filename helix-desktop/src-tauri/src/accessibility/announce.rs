@@ -0,0 +1,103 @@
+// Screen reader announcements - plain-text summaries of status changes
+//
+// Status changes the backend learns of first (the gateway stopping,
+// recovering or crashing, the nightly restart, do-not-disturb, skill runs
+// awaiting approval) are summed up in one short sentence from the
+// "announcements" catalog, with no ellipses or symbols for a screen reader
+// to spell out. Each is emitted as `accessibility:announcement` for the
+// frontend's live region; gateway summaries also go into the tray tooltip,
+// which is what screen readers read out for the tray icon.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::gateway::monitor::GatewayStatus;
+use crate::gateway::nightly_restart::RestartOutcome;
+use crate::locale::{t, Catalog};
+
+/// What an announcement is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Gateway,
+    Dnd,
+    Approvals,
+}
+
+/// How a live region should deliver it: after the current speech, or
+/// interrupting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Polite,
+    Assertive,
+}
+
+/// Payload for accessibility:announcement event
+#[derive(Debug, Clone, Serialize)]
+pub struct Announcement {
+    pub source: Source,
+    pub priority: Priority,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Emit `text` for the live region
+pub fn announce<R: Runtime>(app: &AppHandle<R>, source: Source, priority: Priority, text: String) {
+    if source == Source::Gateway {
+        crate::tray::set_status_summary(app, &text);
+    }
+    let _ = app.emit(
+        "accessibility:announcement",
+        Announcement {
+            source,
+            priority,
+            text,
+            timestamp: now_ms(),
+        },
+    );
+}
+
+/// The gateway's status changed
+pub fn gateway_status<R: Runtime>(app: &AppHandle<R>, status: GatewayStatus) {
+    let (id, priority) = match status {
+        GatewayStatus::Running => ("gateway-running", Priority::Polite),
+        GatewayStatus::Starting => ("gateway-starting", Priority::Polite),
+        GatewayStatus::Stopped => ("gateway-stopped", Priority::Polite),
+        GatewayStatus::Unhealthy => ("gateway-unhealthy", Priority::Assertive),
+        GatewayStatus::Restarting => ("gateway-restarting", Priority::Polite),
+    };
+    announce(app, Source::Gateway, priority, t("announcements", id));
+}
+
+/// A gateway process exited on its own
+pub fn gateway_crashed<R: Runtime>(app: &AppHandle<R>, name: &str, relaunching: bool) {
+    let id = if relaunching { "gateway-crashed-relaunching" } else { "gateway-crashed" };
+    let text = Catalog::new("announcements").get_args(id, &[("name", name.into())]);
+    announce(app, Source::Gateway, Priority::Assertive, text);
+}
+
+/// How the nightly gateway restart went; nothing when there was no
+/// gateway to restart
+pub fn nightly_restart<R: Runtime>(app: &AppHandle<R>, outcome: RestartOutcome) {
+    let (id, priority) = match outcome {
+        RestartOutcome::Restarted => ("nightly-restart-done", Priority::Polite),
+        RestartOutcome::SkippedActiveSessions => ("nightly-restart-skipped", Priority::Polite),
+        RestartOutcome::Failed => ("nightly-restart-failed", Priority::Assertive),
+        RestartOutcome::SkippedNotRunning => return,
+    };
+    announce(app, Source::Gateway, priority, t("announcements", id));
+}
+
+/// Do-not-disturb turned on or off
+pub fn dnd<R: Runtime>(app: &AppHandle<R>, active: bool) {
+    let id = if active { "dnd-on" } else { "dnd-off" };
+    announce(app, Source::Dnd, Priority::Polite, t("announcements", id));
+}
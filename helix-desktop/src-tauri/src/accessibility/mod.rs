@@ -0,0 +1,4 @@
+// Helix Desktop - Accessibility Module (OS accessibility settings and screen reader announcements)
+
+pub mod announce;
+pub mod state;
@@ -0,0 +1,160 @@
+// OS accessibility settings - screen reader, reduced motion, high contrast
+//
+// Read where each platform keeps them: GNOME's a11y settings on Linux, the
+// universal access defaults on macOS and the accessibility keys of the
+// current user's registry on Windows. A setting the platform doesn't
+// expose, or that can't be read, is reported as unknown and the frontend
+// falls back to its media queries. The settings are polled, since none of
+// the platforms announce changes to a process that isn't a GUI toolkit,
+// and `accessibility:changed` is emitted when they differ.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::CommandError;
+
+/// How often the settings are re-read for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+/// Probes spawn processes; reuse a result this long
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static CACHE: Mutex<Option<(Instant, AccessibilityState)>> = Mutex::new(None);
+
+/// Accessibility settings of the OS; `None` when unknown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AccessibilityState {
+    pub screen_reader: Option<bool>,
+    pub reduced_motion: Option<bool>,
+    pub high_contrast: Option<bool>,
+}
+
+/// Current settings, re-read at most every few seconds
+pub fn current() -> AccessibilityState {
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((at, state)) = *cache {
+            if at.elapsed() < CACHE_TTL {
+                return state;
+            }
+        }
+    }
+
+    let state = probe();
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((Instant::now(), state));
+    }
+    state
+}
+
+/// Trimmed stdout of a command that succeeded
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW, or a console flashes up on every poll
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn probe() -> AccessibilityState {
+    let gsetting = |schema: &str, key: &str| match output("gsettings", &["get", schema, key])?.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    };
+
+    AccessibilityState {
+        screen_reader: gsetting("org.gnome.desktop.a11y.applications", "screen-reader-enabled"),
+        reduced_motion: gsetting("org.gnome.desktop.interface", "enable-animations").map(|enabled| !enabled),
+        // The key exists from GNOME 42; before that the theme was switched
+        high_contrast: gsetting("org.gnome.desktop.a11y.interface", "high-contrast").or_else(|| {
+            output("gsettings", &["get", "org.gnome.desktop.interface", "gtk-theme"])
+                .map(|theme| theme.contains("HighContrast"))
+        }),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn probe() -> AccessibilityState {
+    // Unset keys are off; `defaults` fails on them
+    let flag = |domain: &str, key: &str| Some(output("defaults", &["read", domain, key]).as_deref() == Some("1"));
+
+    AccessibilityState {
+        screen_reader: flag("com.apple.universalaccess", "voiceOverOnOffKey"),
+        reduced_motion: flag("com.apple.universalaccess", "reduceMotion"),
+        high_contrast: flag("com.apple.universalaccess", "increaseContrast"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn probe() -> AccessibilityState {
+    // `reg query` prints "    <name>    <type>    <data>"
+    let value = |key: &str, name: &str| {
+        let out = output("reg", &["query", key, "/v", name])?;
+        out.lines()
+            .find(|line| line.trim_start().starts_with(name))
+            .and_then(|line| line.split_whitespace().last())
+            .map(String::from)
+    };
+    let number = |data: String| match data.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => data.parse().ok(),
+    };
+
+    AccessibilityState {
+        // Narrator; other screen readers don't leave a trace here
+        screen_reader: value(r"HKCU\Software\Microsoft\Narrator\NoRoam", "RunningState")
+            .and_then(number)
+            .map(|running| running == 1),
+        reduced_motion: value(r"HKCU\Control Panel\Desktop\WindowMetrics", "MinAnimate").map(|animate| animate == "0"),
+        // HCF_HIGHCONTRASTON
+        high_contrast: value(r"HKCU\Control Panel\Accessibility\HighContrast", "Flags")
+            .and_then(number)
+            .map(|flags| flags & 1 == 1),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn probe() -> AccessibilityState {
+    AccessibilityState::default()
+}
+
+/// Watch for settings changes (once per process)
+pub fn start(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut last = None;
+        loop {
+            if let Ok(state) = tauri::async_runtime::spawn_blocking(current).await {
+                if last.is_some_and(|last| last != state) {
+                    log::info!("Accessibility settings changed: {:?}", state);
+                    let _ = app.emit("accessibility:changed", state);
+                }
+                last = Some(state);
+            }
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_accessibility_state() -> Result<AccessibilityState, CommandError> {
+    Ok(current())
+}
@@ -0,0 +1,234 @@
+// Entitlements - signed subscription tokens for offline tier checks
+//
+// The `entitlement` edge function signs { sub, tier, iat, exp } with an
+// Ed25519 key. The token is cached per account in entitlement.json and
+// trusted until `exp` (a grace period of a couple of weeks) while Supabase
+// is unreachable. The wall clock is tracked with a high-water mark: a clock
+// set back past it, or before the token was issued, invalidates the token
+// until it is re-verified online.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::tiers::Tier;
+use crate::commands::auth;
+use crate::privacy::residency::{self, DataFeature};
+
+const ENTITLEMENT_FILENAME: &str = "entitlement.json";
+const TOKEN_VERSION: u32 = 1;
+/// Raw Ed25519 public key, base64, matching ENTITLEMENT_SIGNING_KEY
+const PUBLIC_KEY: Option<&str> = option_env!("HELIX_ENTITLEMENT_PUBLIC_KEY");
+/// Clock drift allowed before it counts as tampering
+const CLOCK_TOLERANCE_SECS: u64 = 10 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Serializes reads and writes of the cache file
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Claims {
+    v: u32,
+    sub: String,
+    tier: Tier,
+    /// Seconds since epoch
+    iat: u64,
+    exp: u64,
+}
+
+/// Persisted token and clock high-water mark
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EntitlementFile {
+    token: Option<String>,
+    /// Latest wall-clock time seen, in seconds since epoch
+    #[serde(default)]
+    clock_high_water: u64,
+}
+
+/// Why a cached token can't be used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Invalid {
+    Missing,
+    BadSignature,
+    OtherAccount,
+    Expired { expired_at: u64 },
+    /// The clock moved back past a time already seen
+    ClockTampered,
+}
+
+/// A verified entitlement
+#[derive(Debug, Clone, Serialize)]
+pub struct Entitlement {
+    pub tier: Tier,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Deserialize)]
+struct IssueResponse {
+    token: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn file_path() -> Result<PathBuf, String> {
+    Ok(super::data_dir()?.join(ENTITLEMENT_FILENAME))
+}
+
+fn load() -> EntitlementFile {
+    file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(file: &EntitlementFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    fs::write(file_path()?, json).map_err(|e| format!("Failed to save entitlement: {}", e))
+}
+
+fn verifying_key() -> Result<Option<VerifyingKey>, String> {
+    let Some(encoded) = PUBLIC_KEY else {
+        return Ok(None);
+    };
+    let bytes: [u8; 32] = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid entitlement public key: {}", e))?
+        .try_into()
+        .map_err(|_| "Entitlement public key must be 32 bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes)
+        .map(Some)
+        .map_err(|e| format!("Invalid entitlement public key: {}", e))
+}
+
+/// Check the token's signature and decode its claims
+fn decode(token: &str) -> Result<Claims, Invalid> {
+    let (payload, signature) = token.split_once('.').ok_or(Invalid::BadSignature)?;
+
+    match verifying_key() {
+        Ok(Some(key)) => {
+            let signature: [u8; 64] = URL_SAFE_NO_PAD
+                .decode(signature)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(Invalid::BadSignature)?;
+            key.verify(payload.as_bytes(), &Signature::from_bytes(&signature))
+                .map_err(|_| Invalid::BadSignature)?;
+        }
+        // Without a key nothing can be trusted, development builds included
+        Ok(None) => return Err(Invalid::BadSignature),
+        Err(e) => {
+            log::error!("{}", e);
+            return Err(Invalid::BadSignature);
+        }
+    }
+
+    let claims: Claims = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or(Invalid::BadSignature)?;
+    if claims.v != TOKEN_VERSION {
+        return Err(Invalid::BadSignature);
+    }
+    Ok(claims)
+}
+
+/// The cached entitlement of the active account, if it is still usable.
+/// Advances the clock high-water mark.
+pub fn cached() -> Result<Entitlement, Invalid> {
+    let _guard = FILE_LOCK.lock().map_err(|_| Invalid::Missing)?;
+    let mut file = load();
+    let token = file.token.clone().ok_or(Invalid::Missing)?;
+    let claims = decode(&token)?;
+
+    if super::profiles::active_user_id().as_deref() != Some(claims.sub.as_str()) {
+        return Err(Invalid::OtherAccount);
+    }
+
+    let now = now_secs();
+    if now + CLOCK_TOLERANCE_SECS < file.clock_high_water || now + CLOCK_TOLERANCE_SECS < claims.iat {
+        return Err(Invalid::ClockTampered);
+    }
+    if now > file.clock_high_water {
+        file.clock_high_water = now;
+        if let Err(e) = save(&file) {
+            log::debug!("{}", e);
+        }
+    }
+
+    if now >= claims.exp {
+        return Err(Invalid::Expired { expired_at: claims.exp });
+    }
+    Ok(Entitlement {
+        tier: claims.tier,
+        issued_at: claims.iat,
+        expires_at: claims.exp,
+    })
+}
+
+/// Fetch a fresh token from the entitlement function and cache it. The
+/// function issues it for the user whose session makes the call.
+pub async fn refresh() -> Result<Entitlement, String> {
+    residency::require(DataFeature::Sync).map_err(|e| e.message)?;
+    let supabase_url = auth::get_supabase_url()?;
+    let request = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .post(format!("{}/functions/v1/entitlement", supabase_url));
+    let (request, session) = super::session::authorize(request).await?;
+    let user_id = session.user_id;
+
+    let response = request
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .map_err(|e| format!("Entitlement check failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Entitlement check failed: HTTP {}", response.status()));
+    }
+    let issued: IssueResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid entitlement response: {}", e))?;
+
+    let claims = decode(&issued.token).map_err(|e| format!("Entitlement rejected: {:?}", e))?;
+    if claims.sub != user_id {
+        return Err("Entitlement was issued for another account".to_string());
+    }
+
+    // A verified issue time resets the high-water mark, unless the local
+    // clock disagrees with the server's
+    let now = now_secs();
+    if now.abs_diff(claims.iat) > CLOCK_TOLERANCE_SECS {
+        return Err(format!(
+            "System clock is off by {} minutes; correct it to use paid features",
+            now.abs_diff(claims.iat) / 60
+        ));
+    }
+
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    save(&EntitlementFile {
+        token: Some(issued.token),
+        clock_high_water: now.max(claims.iat),
+    })?;
+
+    Ok(Entitlement {
+        tier: claims.tier,
+        issued_at: claims.iat,
+        expires_at: claims.exp,
+    })
+}
@@ -0,0 +1,8 @@
+// Helix Desktop - Accounts Module
+
+pub mod entitlement;
+pub mod profiles;
+pub mod session;
+pub mod tiers;
+
+pub use profiles::{data_dir, helix_root};
@@ -0,0 +1,353 @@
+// Account profiles - per-user data directories on a shared machine
+//
+// Each account's data lives in ~/.helix/users/<user_id>. The active account
+// is selected at login and remembered in ~/.helix/accounts.json. Until someone
+// logs in, data resolves to ~/.helix itself; the first account to log in
+// adopts that existing data. HELIX_PROJECT_DIR, when set, bypasses account
+// scoping entirely.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::commands::CommandError;
+
+/// Accounts index file within ~/.helix
+const ACCOUNTS_FILENAME: &str = "accounts.json";
+/// Directory holding per-account data within ~/.helix
+const USERS_DIR: &str = "users";
+/// Entries in ~/.helix shared by every account, never migrated
+const MACHINE_LEVEL_ENTRIES: &[&str] = &[
+    USERS_DIR,
+    ACCOUNTS_FILENAME,
+    "startup-state.json",
+    "wipe-audit.jsonl",
+    "telemetry",
+    "gateway-token",
+    "api-token",
+    "skill-sandbox-token",
+    "helix-runtime",
+    "helix-runtime.previous",
+    "runtime-install",
+    "runtime",
+    "update-state.json",
+    "update-snapshots",
+    "update-download",
+    "hooks.json",
+    "hook-runs.jsonl",
+    "workspace-trust.json",
+    "macros",
+    "run",
+];
+/// Prefixes of machine-level entries named per instance, such as
+/// gateway-token-<instance> and gateway-token-handoff-<instance>
+const MACHINE_LEVEL_PREFIXES: &[&str] = &["gateway-token-"];
+
+fn is_machine_level(name: &std::ffi::OsStr) -> bool {
+    let Some(name) = name.to_str() else {
+        return false;
+    };
+    MACHINE_LEVEL_ENTRIES.contains(&name) || MACHINE_LEVEL_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// A local account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub user_id: String,
+    pub email: Option<String>,
+    pub created_at: u64,
+    pub last_login: u64,
+}
+
+/// Persisted accounts index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AccountsFile {
+    #[serde(default)]
+    active_user_id: Option<String>,
+    #[serde(default)]
+    accounts: Vec<Account>,
+    /// Account that adopted the pre-multi-user data in ~/.helix
+    #[serde(default)]
+    legacy_data_owner: Option<String>,
+}
+
+/// Payload for account:switched event
+#[derive(Serialize, Clone)]
+struct AccountSwitchedPayload {
+    user_id: Option<String>,
+    data_dir: String,
+    migrated_legacy_data: bool,
+}
+
+/// Active account for this session
+static ACTIVE_USER: RwLock<Option<String>> = RwLock::new(None);
+
+/// Machine-level Helix directory (~/.helix)
+pub fn helix_root() -> Result<PathBuf, String> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+
+    Ok(home.join(".helix"))
+}
+
+/// Data directory of the active account
+pub fn data_dir() -> Result<PathBuf, String> {
+    if let Ok(dir) = std::env::var("HELIX_PROJECT_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let root = helix_root()?;
+    match active_user_id() {
+        Some(user_id) => Ok(root.join(USERS_DIR).join(user_id)),
+        None => Ok(root),
+    }
+}
+
+/// Scope an OS-level directory (cache, app data) to the active account
+pub fn scoped_os_dir(base: PathBuf) -> PathBuf {
+    match active_user_id() {
+        Some(user_id) => base.join(USERS_DIR).join(user_id),
+        None => base,
+    }
+}
+
+/// Id of the active account, if anyone is logged in
+pub fn active_user_id() -> Option<String> {
+    ACTIVE_USER.read().ok().and_then(|u| u.clone())
+}
+
+/// Whether `path` belongs to another account's data directory
+pub fn is_other_account_path(path: &Path) -> bool {
+    let Ok(users_dir) = helix_root().map(|r| r.join(USERS_DIR)) else {
+        return false;
+    };
+    let users_dir = users_dir.canonicalize().unwrap_or(users_dir);
+
+    let Ok(relative) = path.strip_prefix(&users_dir) else {
+        return false;
+    };
+
+    match relative.components().next() {
+        Some(owner) => active_user_id().as_deref() != owner.as_os_str().to_str(),
+        None => false,
+    }
+}
+
+/// User ids become directory names, so only allow id-like strings
+fn validate_user_id(user_id: &str) -> Result<(), String> {
+    let valid = !user_id.is_empty()
+        && user_id.len() <= 64
+        && user_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid user id: {}", user_id))
+    }
+}
+
+fn accounts_path() -> Result<PathBuf, String> {
+    Ok(helix_root()?.join(ACCOUNTS_FILENAME))
+}
+
+fn load_accounts() -> AccountsFile {
+    accounts_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_accounts(accounts: &AccountsFile) -> Result<(), String> {
+    let path = accounts_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(accounts)
+        .map_err(|e| format!("Failed to serialize accounts: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write accounts: {}", e))
+}
+
+/// Restore the last active account (called at startup, before config init)
+pub fn init() {
+    let accounts = load_accounts();
+    if let Ok(mut active) = ACTIVE_USER.write() {
+        *active = accounts.active_user_id.clone();
+    }
+
+    if let Some(user_id) = accounts.active_user_id {
+        log::info!("Restored active account {}", user_id);
+    }
+}
+
+/// Move pre-multi-user data from ~/.helix into the account's directory
+fn migrate_legacy_data(root: &Path, target: &Path) -> Result<bool, String> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(false),
+    };
+
+    let mut moved = false;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read .helix directory: {}", e))?;
+        let name = entry.file_name();
+        if is_machine_level(&name) {
+            continue;
+        }
+
+        let destination = target.join(&name);
+        fs::rename(entry.path(), &destination)
+            .map_err(|e| format!("Failed to migrate {:?}: {}", name, e))?;
+        moved = true;
+    }
+
+    Ok(moved)
+}
+
+/// Make `user_id` the active account, creating it on first login
+pub async fn activate(app: &AppHandle, user_id: &str, email: Option<&str>) -> Result<Account, String> {
+    validate_user_id(user_id)?;
+
+    let root = helix_root()?;
+    let user_dir = root.join(USERS_DIR).join(user_id);
+    fs::create_dir_all(&user_dir)
+        .map_err(|e| format!("Failed to create account directory: {}", e))?;
+
+    let mut accounts = load_accounts();
+    let now = current_timestamp();
+
+    // The first account adopts whatever a single-user install left behind
+    let mut migrated_legacy_data = false;
+    if accounts.legacy_data_owner.is_none() && accounts.accounts.is_empty() {
+        migrated_legacy_data = migrate_legacy_data(&root, &user_dir)?;
+        accounts.legacy_data_owner = Some(user_id.to_string());
+        if migrated_legacy_data {
+            log::info!("Migrated existing Helix data into account {}", user_id);
+        }
+    }
+
+    let account = match accounts.accounts.iter_mut().find(|a| a.user_id == user_id) {
+        Some(existing) => {
+            existing.last_login = now;
+            if let Some(email) = email {
+                existing.email = Some(email.to_string());
+            }
+            existing.clone()
+        }
+        None => {
+            let account = Account {
+                user_id: user_id.to_string(),
+                email: email.map(String::from),
+                created_at: now,
+                last_login: now,
+            };
+            accounts.accounts.push(account.clone());
+            account
+        }
+    };
+
+    let switched = accounts.active_user_id.as_deref() != Some(user_id);
+    accounts.active_user_id = Some(user_id.to_string());
+    save_accounts(&accounts)?;
+
+    {
+        let mut active = ACTIVE_USER.write().map_err(|e| e.to_string())?;
+        *active = Some(user_id.to_string());
+    }
+
+    if switched || migrated_legacy_data {
+        rebind_account_state(app, migrated_legacy_data).await?;
+    }
+
+    Ok(account)
+}
+
+/// Deactivate the active account and rebind state to ~/.helix
+pub async fn sign_out(app: &AppHandle) -> Result<(), String> {
+    super::session::clear();
+    let mut accounts = load_accounts();
+    accounts.active_user_id = None;
+    save_accounts(&accounts)?;
+
+    {
+        let mut active = ACTIVE_USER.write().map_err(|e| e.to_string())?;
+        *active = None;
+    }
+
+    rebind_account_state(app, false).await
+}
+
+/// Point config, watchers and in-memory state at the active account's data
+async fn rebind_account_state(app: &AppHandle, migrated_legacy_data: bool) -> Result<(), String> {
+    crate::commands::config::init(app).map_err(|e| e.to_string())?;
+    crate::commands::scheduler::reload_job_registry().await;
+    crate::vault::lock();
+
+    let state = app.state::<crate::AppState>();
+    {
+        let mut watcher = state.config_watcher.write().await;
+        if watcher.is_watching() {
+            watcher.stop()?;
+            watcher.start(app.clone())?;
+        }
+    }
+
+    let _ = app.emit("account:switched", AccountSwitchedPayload {
+        user_id: active_user_id(),
+        data_dir: data_dir()?.to_string_lossy().to_string(),
+        migrated_legacy_data,
+    });
+
+    Ok(())
+}
+
+/// Get current timestamp in milliseconds
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Tauri commands for account management
+
+/// List accounts known on this machine
+#[tauri::command]
+pub fn list_accounts() -> Result<Vec<Account>, CommandError> {
+    Ok(load_accounts().accounts)
+}
+
+/// Get the active account, if any
+#[tauri::command]
+pub fn get_active_account() -> Result<Option<Account>, CommandError> {
+    let Some(user_id) = active_user_id() else {
+        return Ok(None);
+    };
+
+    Ok(load_accounts()
+        .accounts
+        .into_iter()
+        .find(|a| a.user_id == user_id))
+}
+
+/// Switch to another account that has logged in on this machine before
+#[tauri::command]
+pub async fn switch_account(app: AppHandle, user_id: String) -> Result<Account, CommandError> {
+    let known = load_accounts().accounts.iter().any(|a| a.user_id == user_id);
+    if !known {
+        return Err(CommandError::not_found(format!("Unknown account: {}", user_id))
+            .with_details(serde_json::json!({ "user_id": user_id })));
+    }
+
+    activate(&app, &user_id, None).await.map_err(Into::into)
+}
+
+/// Sign out of the active account; data falls back to ~/.helix
+#[tauri::command]
+pub async fn sign_out_account(app: AppHandle) -> Result<(), CommandError> {
+    sign_out(&app).await.map_err(Into::into)
+}
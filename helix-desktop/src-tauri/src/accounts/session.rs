@@ -0,0 +1,191 @@
+// Supabase session - the signed-in user's tokens for calls made for them
+//
+// Desktop calls to Supabase authenticate as the user: the anon key as
+// `apikey` and the user's access token as the bearer, so row-level security
+// decides what they can read and write. The service role key never leaves
+// the server. The session is saved per account in session.json, readable by
+// the owner only, when they log in, and refreshed with its refresh token
+// shortly before the access token expires.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::commands::auth;
+
+const SESSION_FILENAME: &str = "session.json";
+/// Refresh when the access token has less than this left
+const REFRESH_MARGIN_SECS: u64 = 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One refresh at a time; a refresh token can only be used once
+static REFRESH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionFile {
+    user_id: String,
+    access_token: String,
+    refresh_token: String,
+    /// Seconds since epoch
+    expires_at: u64,
+}
+
+/// The signed-in user, for a call made on their behalf
+#[derive(Debug, Clone)]
+pub struct UserSession {
+    pub user_id: String,
+    pub access_token: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn file_path() -> Result<PathBuf, String> {
+    Ok(super::data_dir()?.join(SESSION_FILENAME))
+}
+
+fn load() -> Option<SessionFile> {
+    let content = fs::read_to_string(file_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save(session: &SessionFile) -> Result<(), String> {
+    let path = file_path()?;
+    let json = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // The refresh token signs the user in; owner-only from the start
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to save session: {}", e))?;
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to save session: {}", e))
+}
+
+/// Read a GoTrue token response (login or refresh)
+fn from_response(response: &Value) -> Result<SessionFile, String> {
+    let text = |key: &str| {
+        response
+            .get(key)
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| format!("Missing {} in auth response", key))
+    };
+    let expires_at = response
+        .get("expires_at")
+        .and_then(Value::as_u64)
+        .or_else(|| response.get("expires_in").and_then(Value::as_u64).map(|secs| now_secs() + secs))
+        .ok_or("Missing expiry in auth response")?;
+    let user_id = response
+        .get("user")
+        .and_then(|user| user.get("id"))
+        .and_then(Value::as_str)
+        .ok_or("Missing user ID in auth response")?
+        .to_string();
+
+    Ok(SessionFile {
+        user_id,
+        access_token: text("access_token")?,
+        refresh_token: text("refresh_token")?,
+        expires_at,
+    })
+}
+
+/// Save the session from a login response. Call once the account it
+/// belongs to is active, so it lands in that account's directory.
+pub fn store(response: &Value) -> Result<(), String> {
+    let session = from_response(response)?;
+    if super::profiles::active_user_id().as_deref() != Some(session.user_id.as_str()) {
+        return Err("Session belongs to another account".to_string());
+    }
+    save(&session)
+}
+
+/// Forget the active account's session, as at sign-out
+pub fn clear() {
+    if let Ok(path) = file_path() {
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove session: {}", e);
+            }
+        }
+    }
+}
+
+async fn refresh(session: &SessionFile) -> Result<SessionFile, String> {
+    let anon_key = auth::get_supabase_anon_key()?;
+    let supabase_url = auth::get_supabase_url()?;
+
+    let response = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .post(format!("{}/auth/v1/token?grant_type=refresh_token", supabase_url))
+        .header("apikey", &anon_key)
+        .json(&serde_json::json!({ "refresh_token": session.refresh_token }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to refresh session: {}", e))?;
+
+    let status = response.status();
+    if status.is_client_error() {
+        // Revoked or already used; only logging in again helps
+        clear();
+        return Err("Session expired; log in again".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("Failed to refresh session: HTTP {}", status));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid session response: {}", e))?;
+    let refreshed = from_response(&body)?;
+    if refreshed.user_id != session.user_id {
+        return Err("Refreshed session belongs to another account".to_string());
+    }
+    save(&refreshed)?;
+    Ok(refreshed)
+}
+
+/// The active account's session, refreshed first if it is about to expire
+pub async fn current() -> Result<UserSession, String> {
+    let user_id = super::profiles::active_user_id().ok_or("No active Helix account")?;
+    let _guard = REFRESH_LOCK.lock().await;
+
+    let mut session = load()
+        .filter(|session| session.user_id == user_id)
+        .ok_or("Not signed in to Helix cloud; log in again")?;
+    if session.expires_at <= now_secs() + REFRESH_MARGIN_SECS {
+        session = refresh(&session).await?;
+    }
+
+    Ok(UserSession {
+        user_id: session.user_id,
+        access_token: session.access_token,
+    })
+}
+
+/// Authenticate `request` as the active account: the anon key as apikey
+/// and the user's access token as the bearer
+pub async fn authorize(request: reqwest::RequestBuilder) -> Result<(reqwest::RequestBuilder, UserSession), String> {
+    let anon_key = auth::get_supabase_anon_key()?;
+    let session = current().await?;
+    let request = request
+        .header("apikey", anon_key)
+        .header("Authorization", format!("Bearer {}", session.access_token));
+    Ok((request, session))
+}
@@ -0,0 +1,229 @@
+// Subscription tiers - gates features on the active account's plan
+//
+// The tier comes from a signed entitlement (see entitlement.rs), fetched at
+// login, when the Realtime bridge sees the subscription change, and again
+// once the cached one is older than REVALIDATE_AFTER. While Supabase can't
+// be reached the cached entitlement stands until it expires; after that, or
+// if the clock was tampered with, only free features are available until it
+// can be re-verified. Gated commands call `require`, which fails with a
+// `tier_required` error the UI can upsell on.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::entitlement::{self, Entitlement, Invalid};
+use crate::commands::{CommandError, ErrorCode};
+
+const REVALIDATE_AFTER: Duration = Duration::from_secs(3600);
+const REVALIDATE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Retry interval while Supabase is unreachable
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Subscription plans, lowest first (matches web/src/lib/types.ts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Core,
+    Phantom,
+    Overseer,
+    Architect,
+}
+
+impl Tier {
+    fn name(self) -> &'static str {
+        match self {
+            Tier::Core => "core",
+            Tier::Phantom => "phantom",
+            Tier::Overseer => "overseer",
+            Tier::Architect => "architect",
+        }
+    }
+}
+
+/// A feature that needs more than the free plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Multi-instance sync through the sync coordinator
+    SyncCoordinator,
+    /// Installing skills from the ClawHub marketplace
+    SkillsMarketplace,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 2] = [Feature::SyncCoordinator, Feature::SkillsMarketplace];
+
+    fn label(self) -> &'static str {
+        match self {
+            Feature::SyncCoordinator => "Sync coordinator",
+            Feature::SkillsMarketplace => "Skills marketplace",
+        }
+    }
+
+    pub fn required_tier(self) -> Tier {
+        match self {
+            Feature::SyncCoordinator => Tier::Phantom,
+            Feature::SkillsMarketplace => Tier::Overseer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureAccess {
+    pub feature: Feature,
+    pub required_tier: Tier,
+    pub allowed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionStatus {
+    pub tier: Tier,
+    /// When the entitlement in use was issued, in seconds since epoch
+    pub verified_at: Option<u64>,
+    /// End of the offline grace period
+    pub expires_at: Option<u64>,
+    /// Whether the last re-verification failed to reach Supabase
+    pub stale: bool,
+    /// Why no entitlement could be used, when the tier fell back to core
+    pub unverified: Option<Invalid>,
+    pub features: Vec<FeatureAccess>,
+}
+
+/// The entitlement in effect and whether it was just confirmed online
+struct TierState {
+    entitlement: Option<Entitlement>,
+    verified: bool,
+    unverified: Option<Invalid>,
+}
+
+impl TierState {
+    fn tier(&self) -> Tier {
+        self.entitlement.as_ref().map(|e| e.tier).unwrap_or(Tier::Core)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// The active account's entitlement, re-verified online when the cached
+/// one is old, unusable or `force` is set
+async fn current(force: bool) -> TierState {
+    let cached = entitlement::cached();
+    let fresh = cached
+        .as_ref()
+        .is_ok_and(|e| now_secs().saturating_sub(e.issued_at) < REVALIDATE_AFTER.as_secs());
+    if fresh && !force {
+        return TierState {
+            entitlement: cached.ok(),
+            verified: true,
+            unverified: None,
+        };
+    }
+
+    // Offline, the cached entitlement stands without waiting on a request
+    let refreshed = if crate::remote::connectivity::is_online() {
+        entitlement::refresh().await
+    } else {
+        Err("Offline".to_string())
+    };
+    match refreshed {
+        Ok(fetched) => TierState {
+            entitlement: Some(fetched),
+            verified: true,
+            unverified: None,
+        },
+        Err(e) => {
+            log::debug!("Entitlement not re-verified: {}", e);
+            match cached {
+                Ok(cached) => TierState {
+                    entitlement: Some(cached),
+                    verified: false,
+                    unverified: None,
+                },
+                Err(invalid) => TierState {
+                    entitlement: None,
+                    verified: false,
+                    unverified: Some(invalid),
+                },
+            }
+        }
+    }
+}
+
+/// Fetch a new entitlement now, e.g. after the subscription changed
+pub fn reverify() {
+    tauri::async_runtime::spawn(async {
+        if let Err(e) = entitlement::refresh().await {
+            log::debug!("Entitlement not re-verified: {}", e);
+        }
+    });
+}
+
+/// Fail with `tier_required` unless the active account's plan includes `feature`
+pub async fn require(feature: Feature) -> Result<(), CommandError> {
+    let state = current(false).await;
+    let tier = state.tier();
+    let required = feature.required_tier();
+    if tier >= required {
+        return Ok(());
+    }
+
+    Err(CommandError::new(
+        ErrorCode::TierRequired,
+        format!("{} requires the {} plan", feature.label(), required.name()),
+    )
+    .with_details(json!({
+        "feature": feature,
+        "required_tier": required,
+        "current_tier": tier,
+        "unverified": state.unverified,
+    })))
+}
+
+/// Keep the entitlement current while someone is signed in, retrying
+/// quickly after a failure so it is re-verified as soon as Supabase is
+/// reachable again
+pub fn start() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let verified = super::profiles::active_user_id().is_none() || current(false).await.verified;
+            tokio::time::sleep(if verified { REVALIDATE_INTERVAL } else { RETRY_INTERVAL }).await;
+        }
+    });
+}
+
+// Tauri commands
+
+/// The active account's plan and which gated features it includes
+#[tauri::command]
+pub async fn get_subscription_status(refresh: Option<bool>) -> Result<SubscriptionStatus, CommandError> {
+    let state = current(refresh.unwrap_or(false)).await;
+    let tier = state.tier();
+
+    Ok(SubscriptionStatus {
+        tier,
+        verified_at: state.entitlement.as_ref().map(|e| e.issued_at),
+        expires_at: state.entitlement.as_ref().map(|e| e.expires_at),
+        stale: !state.verified,
+        unverified: state.unverified,
+        features: Feature::ALL
+            .iter()
+            .map(|&feature| FeatureAccess {
+                feature,
+                required_tier: feature.required_tier(),
+                allowed: tier >= feature.required_tier(),
+            })
+            .collect(),
+    })
+}
+
+/// Check a gated feature before the UI offers it; errors with `tier_required`
+#[tauri::command]
+pub async fn check_feature_access(feature: Feature) -> Result<(), CommandError> {
+    require(feature).await
+}
@@ -0,0 +1,104 @@
+// Service activity log
+//
+// Gateway and sync events are only emitted to the frontend as they happen.
+// This keeps a copy of them in `activity/events.jsonl` so the activity
+// timeline can show them later.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const ACTIVITY_DIR: &str = "activity";
+const EVENTS_FILE: &str = "events.jsonl";
+/// Once the log grows past this, it is cut back to the newest KEEP_EVENTS
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+const KEEP_EVENTS: usize = 2000;
+
+/// Serializes appends and trims of the log
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Service an event came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceKind {
+    Gateway,
+    Sync,
+}
+
+/// One recorded service event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEvent {
+    /// Milliseconds since epoch
+    pub timestamp: u64,
+    pub service: ServiceKind,
+    /// Short machine-readable name, e.g. "started" or "unhealthy"
+    pub action: String,
+    pub summary: String,
+    pub details: Option<serde_json::Value>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn log_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(ACTIVITY_DIR).join(EVENTS_FILE))
+}
+
+fn append(event: &ServiceEvent) -> Result<(), String> {
+    let path = log_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create activity directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(event).map_err(|e| format!("Failed to serialize activity event: {}", e))?;
+    let _guard = LOG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open activity log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write activity log: {}", e))?;
+    drop(file);
+
+    if fs::metadata(&path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read activity log: {}", e))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let kept = lines[lines.len().saturating_sub(KEEP_EVENTS)..].join("\n");
+        fs::write(&path, kept + "\n").map_err(|e| format!("Failed to trim activity log: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Record a service event. Failures are logged, never returned, so callers
+/// on the gateway and sync paths aren't affected by the log.
+pub fn record(service: ServiceKind, action: &str, summary: impl Into<String>, details: Option<serde_json::Value>) {
+    let event = ServiceEvent {
+        timestamp: now_ms(),
+        service,
+        action: action.to_string(),
+        summary: summary.into(),
+        details,
+    };
+    if let Err(e) = append(&event) {
+        log::debug!("Failed to record {} activity: {}", action, e);
+    }
+}
+
+/// Recorded events at or after `since_ms`, oldest first
+pub fn read_since(since_ms: u64) -> Vec<ServiceEvent> {
+    let Ok(content) = log_path().and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ServiceEvent>(line).ok())
+        .filter(|event| event.timestamp >= since_ms)
+        .collect()
+}
@@ -0,0 +1,4 @@
+// Helix Desktop - Activity Module (service event log and the activity timeline)
+
+pub mod events;
+pub mod timeline;
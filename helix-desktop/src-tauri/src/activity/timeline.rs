@@ -0,0 +1,316 @@
+// Activity timeline
+//
+// Merges what Helix has been doing into one feed, newest first:
+//   - audit: secure wipes, guardrail decisions and schema migrations
+//   - scheduler: job runs, including deferred ones
+//   - gateway and sync: service events from the activity log
+//   - memory: memories created in Supabase
+// Pages are cursor based so events arriving while the user scrolls don't
+// shift or repeat entries.
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::events::{self, ServiceKind};
+use crate::commands::{scheduler, CommandError};
+use crate::policy::guardrails;
+use crate::privacy::wipe;
+use crate::psychology::{memories, migrations};
+
+const DEFAULT_RANGE: &str = "7d";
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+/// Guardrail decisions are read newest first up to this many
+const DECISION_LIMIT: usize = 1000;
+const MEMORY_COLUMNS: &str = "id,content,created_at";
+const MEMORY_PREVIEW_CHARS: usize = 140;
+
+/// Where a timeline event came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineSource {
+    Audit,
+    Scheduler,
+    Sync,
+    Gateway,
+    Memory,
+}
+
+/// One entry in the feed
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Stable within a source, used in cursors
+    pub id: String,
+    /// Milliseconds since epoch
+    pub timestamp: u64,
+    pub source: TimelineSource,
+    pub action: String,
+    pub title: String,
+    pub details: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TimelineFilters {
+    /// Only these sources; all when empty
+    #[serde(default)]
+    pub sources: Vec<TimelineSource>,
+    /// Case-insensitive match on the title
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelinePage {
+    pub events: Vec<TimelineEvent>,
+    /// Pass back as `cursor` for the next page; None on the last page
+    pub next_cursor: Option<String>,
+    /// Sources that couldn't be read, with the reason
+    pub unavailable: Vec<String>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// "24h", "7d", "4w" or "all" as a window length in milliseconds
+fn range_ms(range: &str) -> Result<Option<u64>, CommandError> {
+    if range == "all" {
+        return Ok(None);
+    }
+    let invalid = || {
+        CommandError::invalid_input(format!("Invalid range: {} (expected e.g. 24h, 7d, 4w or all)", range))
+            .with_details(json!({ "range": range }))
+    };
+    let unit_ms = match range.chars().last() {
+        Some('h') => 3_600_000,
+        Some('d') => 86_400_000,
+        Some('w') => 7 * 86_400_000,
+        _ => return Err(invalid()),
+    };
+    range[..range.len() - 1]
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .map(|n| Some(n * unit_ms))
+        .ok_or_else(invalid)
+}
+
+/// Cursor format: "<timestamp>:<id>" of the last event on the previous page
+fn parse_cursor(cursor: &str) -> Result<(u64, String), CommandError> {
+    cursor
+        .split_once(':')
+        .and_then(|(ts, id)| Some((ts.parse().ok()?, id.to_string())))
+        .ok_or_else(|| CommandError::invalid_input(format!("Invalid cursor: {}", cursor)))
+}
+
+fn rfc3339(ms: u64) -> Option<String> {
+    Utc.timestamp_millis_opt(ms as i64).single().map(|t| t.to_rfc3339())
+}
+
+/// Id for a record that has none of its own, derived from its content so
+/// it stays the same however the records around it change
+fn content_id<T: Serialize>(prefix: &str, timestamp: u64, record: &T) -> String {
+    let digest = Sha256::digest(serde_json::to_vec(record).unwrap_or_default());
+    format!("{}-{}-{}", prefix, timestamp, hex::encode(&digest[..8]))
+}
+
+/// snake_case serde name of an enum value, with spaces
+fn label<T: Serialize + std::fmt::Debug>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.replace('_', " ")))
+        .unwrap_or_else(|| format!("{:?}", value))
+}
+
+fn audit_events() -> Result<Vec<TimelineEvent>, String> {
+    let mut out = Vec::new();
+
+    for report in wipe::get_wipe_history().map_err(|e| e.message)? {
+        out.push(TimelineEvent {
+            id: format!("wipe-{}", report.timestamp),
+            timestamp: report.timestamp,
+            source: TimelineSource::Audit,
+            action: "secure_wipe".to_string(),
+            title: format!(
+                "Secure wipe of {} ({} files)",
+                report.scope.names().join(", "),
+                report.files_wiped
+            ),
+            details: serde_json::to_value(&report).ok(),
+        });
+    }
+
+    for decision in guardrails::get_guardrail_decisions(Some(DECISION_LIMIT)).map_err(|e| e.message)? {
+        out.push(TimelineEvent {
+            id: content_id("guardrail", decision.timestamp, &decision),
+            timestamp: decision.timestamp,
+            source: TimelineSource::Audit,
+            action: format!("guardrail_{}", label(&decision.decision).replace(' ', "_")),
+            title: format!(
+                "Guardrails {} {} write to {}",
+                label(&decision.decision),
+                label(&decision.source),
+                decision.path
+            ),
+            details: serde_json::to_value(&decision).ok(),
+        });
+    }
+
+    for migration in migrations::get_migration_report().map_err(|e| e.message)?.applied {
+        out.push(TimelineEvent {
+            id: format!("migration-{}-{}", migration.timestamp, migration.file),
+            timestamp: migration.timestamp,
+            source: TimelineSource::Audit,
+            action: "migration".to_string(),
+            title: format!("Migrated {} from {} to {}", migration.file, migration.from, migration.to),
+            details: serde_json::to_value(&migration).ok(),
+        });
+    }
+
+    Ok(out)
+}
+
+fn scheduler_events() -> Result<Vec<TimelineEvent>, String> {
+    let jobs = scheduler::get_scheduled_jobs().map_err(|e| e.message)?;
+    Ok(jobs
+        .iter()
+        .flat_map(|job| {
+            job.runs.iter().map(move |run| {
+                let unmet: Vec<_> = run.preconditions.iter().filter(|c| !c.met).collect();
+                TimelineEvent {
+                    id: format!("job-{}-{}", job.id, run.started_at),
+                    timestamp: run.started_at * 1000,
+                    source: TimelineSource::Scheduler,
+                    action: format!("job_{}", label(&run.status).replace(' ', "_")),
+                    title: format!("{} job {}", label(&job.job_type), label(&run.status)),
+                    details: Some(json!({
+                        "job_id": job.id,
+                        "finished_at": run.finished_at.map(|t| t * 1000),
+                        "error": run.error,
+                        "unmet_preconditions": unmet,
+                    })),
+                }
+            })
+        })
+        .collect())
+}
+
+fn service_events(since_ms: u64) -> Vec<TimelineEvent> {
+    events::read_since(since_ms)
+        .into_iter()
+        .map(|event| {
+            let source = match event.service {
+                ServiceKind::Gateway => TimelineSource::Gateway,
+                ServiceKind::Sync => TimelineSource::Sync,
+            };
+            TimelineEvent {
+                id: content_id(&label(&event.service), event.timestamp, &event),
+                timestamp: event.timestamp,
+                source,
+                action: event.action,
+                title: event.summary,
+                details: event.details,
+            }
+        })
+        .collect()
+}
+
+/// Memories in the window, newest first, no newer than the cursor
+async fn memory_events(since_ms: u64, until_ms: Option<u64>, limit: usize) -> Result<Vec<TimelineEvent>, String> {
+    let since = rfc3339(since_ms);
+    let until = until_ms.and_then(rfc3339);
+    let records = memories::fetch_latest(MEMORY_COLUMNS, since.as_deref(), until.as_deref(), limit).await?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| {
+            let timestamp = chrono::DateTime::parse_from_rfc3339(&record.created_at)
+                .ok()?
+                .timestamp_millis() as u64;
+            let preview: String = record.content.chars().take(MEMORY_PREVIEW_CHARS).collect();
+            Some(TimelineEvent {
+                id: format!("memory-{}", record.id),
+                timestamp,
+                source: TimelineSource::Memory,
+                action: "memory_created".to_string(),
+                title: "Memory created".to_string(),
+                details: Some(json!({ "memory_id": record.id, "preview": preview })),
+            })
+        })
+        .collect())
+}
+
+// Tauri commands
+
+/// A page of the activity feed, newest first. `range` is how far back to
+/// look (default 7d); `cursor` is the previous page's next_cursor.
+#[tauri::command]
+pub async fn get_activity_timeline(
+    range: Option<String>,
+    filters: Option<TimelineFilters>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> Result<TimelinePage, CommandError> {
+    let window = range_ms(range.as_deref().unwrap_or(DEFAULT_RANGE))?;
+    let filters = filters.unwrap_or_default();
+    let cursor = cursor.as_deref().map(parse_cursor).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let since_ms = window.map(|w| now_ms().saturating_sub(w)).unwrap_or(0);
+    let wanted = |source| filters.sources.is_empty() || filters.sources.contains(&source);
+
+    let mut events = Vec::new();
+    let mut unavailable = Vec::new();
+
+    if wanted(TimelineSource::Audit) {
+        match audit_events() {
+            Ok(found) => events.extend(found),
+            Err(e) => unavailable.push(format!("audit: {}", e)),
+        }
+    }
+    if wanted(TimelineSource::Scheduler) {
+        match scheduler_events() {
+            Ok(found) => events.extend(found),
+            Err(e) => unavailable.push(format!("scheduler: {}", e)),
+        }
+    }
+    if wanted(TimelineSource::Gateway) || wanted(TimelineSource::Sync) {
+        events.extend(service_events(since_ms).into_iter().filter(|e| wanted(e.source)));
+    }
+    if wanted(TimelineSource::Memory) {
+        // One extra so a full page of memories still reports a next page
+        match memory_events(since_ms, cursor.as_ref().map(|(ts, _)| *ts), limit + 1).await {
+            Ok(found) => events.extend(found),
+            Err(e) => unavailable.push(format!("memory: {}", e)),
+        }
+    }
+
+    let search = filters.search.as_deref().map(str::to_lowercase).filter(|s| !s.is_empty());
+    events.retain(|event| {
+        event.timestamp >= since_ms
+            && cursor
+                .as_ref()
+                .is_none_or(|(ts, id)| (event.timestamp, &event.id) < (*ts, id))
+            && search
+                .as_ref()
+                .is_none_or(|s| event.title.to_lowercase().contains(s))
+    });
+    events.sort_by(|a, b| (b.timestamp, &b.id).cmp(&(a.timestamp, &a.id)));
+
+    let has_more = events.len() > limit;
+    events.truncate(limit);
+    let next_cursor = has_more
+        .then(|| events.last().map(|e| format!("{}:{}", e.timestamp, e.id)))
+        .flatten();
+
+    Ok(TimelinePage {
+        events,
+        next_cursor,
+        unavailable,
+    })
+}
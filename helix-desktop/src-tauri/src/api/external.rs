@@ -0,0 +1,157 @@
+// External read API - layer summaries and wellness trends for dashboards
+//
+// `/api/external/v1` is the one part of the local API that reader tokens
+// (readers.rs) open, besides the full API token. Its responses are a
+// versioned schema of their own rather than the commands' JSON, so
+// dashboards don't break when the app's internals change: fields are only
+// ever added within v1. Layer contents aren't exposed, only how complete
+// and how fresh each layer is; the wellness series leaves out the inferred
+// sleep times. A reader token only sees the layers it was granted, and the
+// wellness trends if it was granted them.
+
+use axum::extract::{Path, Query};
+use axum::response::Response;
+use axum::routing::get;
+use axum::{Extension, Router};
+use serde::{Deserialize, Serialize};
+
+use super::readers::ReaderScope;
+use super::routes::reply;
+use super::server::ApiContext;
+use crate::commands::{psychology, CommandError, ErrorCode};
+use crate::psychology::wellness;
+
+const SCHEMA_VERSION: u32 = 1;
+
+pub fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/external/v1/layers", get(layers))
+        .route("/api/external/v1/layers/:layer", get(layer))
+        .route("/api/external/v1/wellness", get(wellness_trends))
+}
+
+/// Set by the server for requests made with a reader token; `None` is the
+/// full API token
+type Scope = Option<Extension<ReaderScope>>;
+
+#[derive(Debug, Serialize)]
+struct LayerSummary {
+    id: String,
+    name: String,
+    /// healthy, warning, error or inactive
+    status: String,
+    files_present: usize,
+    files_expected: usize,
+    /// Unix seconds of the newest file
+    updated_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct LayersResponse {
+    schema_version: u32,
+    layers: Vec<LayerSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct LayerResponse {
+    schema_version: u32,
+    layer: LayerSummary,
+}
+
+#[derive(Debug, Serialize)]
+struct WellnessDay {
+    date: String,
+    score: Option<f64>,
+    valence: Option<f64>,
+    memories: usize,
+    activity: usize,
+    sleep_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct WellnessResponse {
+    schema_version: u32,
+    range: String,
+    average_score: Option<f64>,
+    /// Score change per day
+    trend: Option<f64>,
+    average_sleep_hours: Option<f64>,
+    updated_at: Option<String>,
+    /// Oldest first
+    days: Vec<WellnessDay>,
+}
+
+fn denied(what: &str) -> CommandError {
+    CommandError::new(ErrorCode::PermissionDenied, format!("This token can't read {}", what))
+}
+
+fn layer_summaries(scope: &Scope) -> Result<Vec<LayerSummary>, CommandError> {
+    Ok(psychology::get_layer_status()?
+        .into_iter()
+        .filter(|layer| scope.as_ref().is_none_or(|scope| scope.allows_layer(&layer.id)))
+        .map(|layer| LayerSummary {
+            id: layer.id,
+            name: layer.name,
+            status: layer.status,
+            files_present: layer.file_count,
+            files_expected: layer.total_files,
+            updated_at: layer.last_modified,
+        })
+        .collect())
+}
+
+async fn layers(scope: Scope) -> Response {
+    reply(layer_summaries(&scope).map(|layers| LayersResponse {
+        schema_version: SCHEMA_VERSION,
+        layers,
+    }))
+}
+
+async fn layer(scope: Scope, Path(layer): Path<String>) -> Response {
+    let result = layer_summaries(&None).and_then(|layers| {
+        let summary = layers
+            .into_iter()
+            .find(|summary| summary.id == layer)
+            .ok_or_else(|| CommandError::not_found(format!("Unknown layer: {}", layer)))?;
+        if scope.as_ref().is_some_and(|scope| !scope.allows_layer(&layer)) {
+            return Err(denied(&format!("the {} layer", layer)));
+        }
+        Ok(LayerResponse {
+            schema_version: SCHEMA_VERSION,
+            layer: summary,
+        })
+    });
+    reply(result)
+}
+
+#[derive(Deserialize)]
+struct WellnessQuery {
+    range: Option<String>,
+}
+
+async fn wellness_trends(scope: Scope, Query(query): Query<WellnessQuery>) -> Response {
+    if scope.as_ref().is_some_and(|scope| !scope.wellness) {
+        return reply::<(), _>(Err(denied("wellness trends")));
+    }
+    let result = wellness::get_wellness_trends(query.range).map(|trends| WellnessResponse {
+        schema_version: SCHEMA_VERSION,
+        range: trends.range,
+        average_score: trends.average_score,
+        trend: trends.trend,
+        average_sleep_hours: trends.average_sleep_hours,
+        updated_at: trends.updated_at,
+        days: trends
+            .entries
+            .into_iter()
+            .map(|entry| WellnessDay {
+                date: entry.date,
+                score: entry.score,
+                valence: entry.valence,
+                memories: entry.memories,
+                activity: entry.activity,
+                sleep_hours: entry.sleep_hours,
+            })
+            .collect(),
+    });
+    reply(result)
+}
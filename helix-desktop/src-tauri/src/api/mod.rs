@@ -0,0 +1,7 @@
+// Helix Desktop - Local API Module (token-protected localhost REST facade)
+
+pub mod external;
+pub mod readers;
+pub mod routes;
+pub mod server;
+pub mod token;
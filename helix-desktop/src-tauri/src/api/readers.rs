@@ -0,0 +1,198 @@
+// Reader tokens - read-only local API access for external dashboards
+//
+// Dashboards such as Grafana or Home Assistant get a token of their own
+// instead of the full API token: it only opens the `/api/external/v1`
+// routes (see external.rs), and only for the psychology layers it names
+// and, if granted, the wellness trends. Tokens are shown once when created;
+// `api-readers.json` in the account's data directory keeps their SHA-256
+// hashes, so a leaked file doesn't leak access. Tokens belong to the account
+// that created them: after a switch, only the active account's tokens open
+// anything. Revoking a token takes effect on the next request.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::token;
+use crate::commands::{psychology, CommandError};
+
+const READERS_FILENAME: &str = "api-readers.json";
+/// Reader tokens start with this, so they are told apart from the API token
+const TOKEN_PREFIX: &str = "hxr_";
+
+/// Serializes reads and writes of the readers file
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// What a reader token may read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderScope {
+    /// Layer ids, as in get_layer_status
+    pub layers: Vec<String>,
+    pub wellness: bool,
+}
+
+impl ReaderScope {
+    pub fn allows_layer(&self, layer: &str) -> bool {
+        self.layers.iter().any(|allowed| allowed == layer)
+    }
+}
+
+/// A reader token, without the token itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderToken {
+    pub id: String,
+    /// Who uses it, e.g. "Grafana"
+    pub name: String,
+    #[serde(flatten)]
+    pub scope: ReaderScope,
+    pub created_at: u64,
+    #[serde(skip_serializing)]
+    token_hash: String,
+}
+
+/// A newly created reader token; `token` is not stored and can't be shown
+/// again
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedReaderToken {
+    #[serde(flatten)]
+    pub reader: ReaderToken,
+    pub token: String,
+}
+
+fn readers_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(READERS_FILENAME))
+}
+
+fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn read_readers() -> Result<Vec<ReaderToken>, String> {
+    let path = readers_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read reader tokens: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse reader tokens: {}", e))
+}
+
+/// Hashes are written too, unlike when the tokens are listed
+#[derive(Serialize)]
+struct StoredReader<'a> {
+    #[serde(flatten)]
+    reader: &'a ReaderToken,
+    token_hash: &'a str,
+}
+
+fn write_readers(readers: &[ReaderToken]) -> Result<(), String> {
+    let stored: Vec<StoredReader> = readers
+        .iter()
+        .map(|reader| StoredReader {
+            reader,
+            token_hash: &reader.token_hash,
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())?;
+
+    let path = readers_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create reader tokens file: {}", e))?;
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write reader tokens file: {}", e))
+}
+
+/// The scope of `given`, if it is a reader token of the active account
+pub fn authorize(given: &str) -> Option<ReaderScope> {
+    if !given.starts_with(TOKEN_PREFIX) {
+        return None;
+    }
+    let given_hash = hash(given);
+    let _guard = FILE_LOCK.lock().ok()?;
+    read_readers()
+        .ok()?
+        .into_iter()
+        .find(|reader| token::matches(&reader.token_hash, &given_hash))
+        .map(|reader| reader.scope)
+}
+
+// Tauri commands
+
+/// Reader tokens, newest first
+#[tauri::command]
+pub fn list_api_reader_tokens() -> Result<Vec<ReaderToken>, CommandError> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut readers = read_readers()?;
+    readers.sort_by_key(|reader| std::cmp::Reverse(reader.created_at));
+    Ok(readers)
+}
+
+/// Create a read-only token for `layers` and, with `wellness`, the
+/// wellness trends
+#[tauri::command]
+pub fn create_api_reader_token(
+    name: String,
+    layers: Vec<String>,
+    wellness: bool,
+) -> Result<CreatedReaderToken, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::invalid_input("A reader token needs a name"));
+    }
+    let known: Vec<String> = psychology::get_layer_status()?.into_iter().map(|layer| layer.id).collect();
+    if let Some(unknown) = layers.iter().find(|layer| !known.contains(layer)) {
+        return Err(CommandError::invalid_input(format!("Unknown layer: {}", unknown))
+            .with_details(serde_json::json!({ "layer": unknown, "layers": known })));
+    }
+    if layers.is_empty() && !wellness {
+        return Err(CommandError::invalid_input("A reader token must grant a layer or the wellness trends"));
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill(&mut bytes);
+    let token = format!("{}{}", TOKEN_PREFIX, hex::encode(bytes));
+    let reader = ReaderToken {
+        id: format!("reader-{}", hex::encode(rand::random::<[u8; 6]>())),
+        name,
+        scope: ReaderScope { layers, wellness },
+        created_at: chrono::Utc::now().timestamp().max(0) as u64,
+        token_hash: hash(&token),
+    };
+
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut readers = read_readers()?;
+    readers.push(reader.clone());
+    write_readers(&readers)?;
+    log::info!("Created local API reader token {} ({})", reader.id, reader.name);
+
+    Ok(CreatedReaderToken { reader, token })
+}
+
+/// Revoke a reader token
+#[tauri::command]
+pub fn revoke_api_reader_token(id: String) -> Result<(), CommandError> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut readers = read_readers()?;
+    let count = readers.len();
+    readers.retain(|reader| reader.id != id);
+    if readers.len() == count {
+        return Err(CommandError::not_found(format!("Unknown reader token: {}", id)));
+    }
+    write_readers(&readers)?;
+    log::info!("Revoked local API reader token {}", id);
+    Ok(())
+}
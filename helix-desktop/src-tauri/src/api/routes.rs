@@ -0,0 +1,333 @@
+// Local API routes - thin wrappers over the Tauri commands
+//
+// Responses are the commands' own JSON. Command errors become
+// `{"error": "...", "code": "..."}` with the HTTP status picked from the
+// error code (see commands/error.rs); a `busy` error is a 503 with
+// Retry-After. Psychology data is read-only here.
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use super::external;
+use super::server::ApiContext;
+use crate::automation::hooks;
+use crate::commands::preconditions::Precondition;
+use crate::commands::{gateway, psychology, rust_executables, scheduler, CommandError, ErrorCode};
+use crate::gateway::actions::{self as gateway_actions, ActionDeclaration};
+use crate::policy::skill_approvals;
+use crate::startup::safe_mode;
+
+pub fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/health", get(health))
+        // Memories (background tasks)
+        .route("/api/memories/synthesis", post(memory_synthesis))
+        .route("/api/memories/import", post(memory_import))
+        .route("/api/tasks", get(list_tasks))
+        .route("/api/tasks/:task_id", delete(cancel_task))
+        // Scheduler
+        .route("/api/scheduler/health", get(scheduler_health))
+        .route("/api/scheduler/config", get(scheduler_config))
+        .route("/api/scheduler/jobs", get(list_jobs).post(create_job))
+        .route("/api/scheduler/jobs/:job_id", get(get_job).delete(delete_job))
+        .route("/api/scheduler/jobs/:job_id/pause", post(pause_job))
+        .route("/api/scheduler/jobs/:job_id/resume", post(resume_job))
+        .route("/api/scheduler/jobs/:job_id/trigger", post(trigger_job))
+        .route("/api/scheduler/jobs/:job_id/dependencies", put(set_job_dependencies))
+        .route("/api/scheduler/jobs/:job_id/history", get(job_history))
+        // Psychology (read-only)
+        .route("/api/psychology/soul", get(soul))
+        .route("/api/psychology/layers", get(layers))
+        .route("/api/psychology/layers/:layer", get(layer))
+        .route("/api/psychology/status", get(layer_status))
+        .route("/api/context", get(context))
+        // Prospective layer
+        .route("/api/calendar/commitments", get(commitments))
+        // Agent skill runs, queued for the user's approval
+        .route("/api/skills/runs", post(request_skill_run))
+        .route("/api/skills/runs/:run_id", get(skill_run))
+        // Actions agents and skills declare for the palette
+        .route("/api/actions", get(list_actions))
+        .route("/api/actions/:source/:owner", put(declare_actions).delete(remove_actions))
+        // Sync clients report conflicts for the sync_conflict hooks
+        .route("/api/sync/conflicts", post(sync_conflict))
+        // Read-only, also open to reader tokens
+        .merge(external::router())
+}
+
+/// Retry-After for `busy` errors: a decay or synthesis pass holds the
+/// psychology lock for seconds, not minutes
+const BUSY_RETRY_AFTER_SECS: u64 = 5;
+
+pub fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::InvalidInput | ErrorCode::Config | ErrorCode::ConfirmationRequired => StatusCode::BAD_REQUEST,
+        ErrorCode::Unauthenticated => StatusCode::UNAUTHORIZED,
+        ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorCode::TierRequired => StatusCode::PAYMENT_REQUIRED,
+        ErrorCode::AlreadyExists | ErrorCode::Conflict => StatusCode::CONFLICT,
+        ErrorCode::VaultLocked => StatusCode::LOCKED,
+        ErrorCode::ServiceUnavailable | ErrorCode::Disabled | ErrorCode::Offline | ErrorCode::Busy => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        ErrorCode::Unsupported => StatusCode::NOT_IMPLEMENTED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(super) fn reply<T: Serialize, E: Into<CommandError>>(result: Result<T, E>) -> Response {
+    match result.map_err(Into::into) {
+        Ok(value) => Json(value).into_response(),
+        Err(e) => {
+            let body = serde_json::json!({ "error": e.message, "code": e.code, "details": e.details });
+            if e.code == ErrorCode::Busy {
+                let retry_after = [(header::RETRY_AFTER, BUSY_RETRY_AFTER_SECS.to_string())];
+                return (status_for(e.code), retry_after, Json(body)).into_response();
+            }
+            (status_for(e.code), Json(body)).into_response()
+        }
+    }
+}
+
+fn task_reply<E: Into<CommandError>>(result: Result<String, E>) -> Response {
+    reply(result.map(|task_id| serde_json::json!({ "task_id": task_id })))
+}
+
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    version: String,
+    safe_mode: bool,
+    gateway: Option<gateway::GatewayStatus>,
+    scheduler: Option<scheduler::SchedulerHealth>,
+}
+
+async fn health(State(ctx): State<ApiContext>) -> Response {
+    Json(Health {
+        status: "ok",
+        version: ctx.app.package_info().version.to_string(),
+        safe_mode: safe_mode::get_safe_mode_status().map(|s| s.active).unwrap_or(false),
+        gateway: gateway::gateway_status(None).ok(),
+        scheduler: scheduler::get_scheduler_health().ok(),
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct SynthesisRequest {
+    user_id: String,
+}
+
+async fn memory_synthesis(State(ctx): State<ApiContext>, Json(req): Json<SynthesisRequest>) -> Response {
+    let app = ctx.app.clone();
+    task_reply(rust_executables::start_memory_synthesis(ctx.app, app.state(), req.user_id).await)
+}
+
+#[derive(Deserialize)]
+struct ImportRequest {
+    user_id: String,
+    archive_path: String,
+    dry_run: Option<bool>,
+}
+
+async fn memory_import(State(ctx): State<ApiContext>, Json(req): Json<ImportRequest>) -> Response {
+    let app = ctx.app.clone();
+    task_reply(
+        rust_executables::import_chat_export(ctx.app, app.state(), req.user_id, req.archive_path, req.dry_run)
+            .await,
+    )
+}
+
+async fn list_tasks(State(ctx): State<ApiContext>) -> Response {
+    let state = ctx.app.state::<crate::AppState>();
+    Json(state.task_manager.list()).into_response()
+}
+
+async fn cancel_task(State(ctx): State<ApiContext>, Path(task_id): Path<String>) -> Response {
+    let state = ctx.app.state::<crate::AppState>();
+    reply(state.task_manager.cancel(&task_id))
+}
+
+async fn scheduler_health() -> Response {
+    reply(scheduler::get_scheduler_health())
+}
+
+async fn scheduler_config() -> Response {
+    reply(scheduler::get_scheduler_config())
+}
+
+async fn list_jobs() -> Response {
+    reply(scheduler::get_scheduled_jobs())
+}
+
+/// A recurring job with `cron_expression`, or a one-shot job with `run_at`
+#[derive(Deserialize)]
+struct CreateJobRequest {
+    job_type: scheduler::JobType,
+    #[serde(default)]
+    cron_expression: String,
+    #[serde(default)]
+    run_at: Option<u64>,
+    #[serde(default)]
+    after_run: Option<scheduler::AfterRun>,
+    #[serde(default)]
+    preconditions: Option<Vec<Precondition>>,
+    #[serde(default)]
+    macro_name: Option<String>,
+    #[serde(default)]
+    max_retries: Option<u32>,
+    #[serde(default)]
+    retry_backoff_seconds: Option<u64>,
+    #[serde(default)]
+    depends_on: Option<Vec<String>>,
+}
+
+async fn create_job(Json(req): Json<CreateJobRequest>) -> Response {
+    if let Some(run_at) = req.run_at {
+        return reply(scheduler::schedule_job_once(
+            req.job_type,
+            run_at,
+            req.after_run,
+            req.macro_name,
+            req.max_retries,
+            req.retry_backoff_seconds,
+            req.depends_on,
+        ));
+    }
+    reply(scheduler::create_job(
+        req.job_type,
+        req.cron_expression,
+        req.preconditions,
+        req.macro_name,
+        req.max_retries,
+        req.retry_backoff_seconds,
+        req.depends_on,
+    ))
+}
+
+async fn get_job(Path(job_id): Path<String>) -> Response {
+    reply(scheduler::get_job(job_id))
+}
+
+async fn delete_job(Path(job_id): Path<String>) -> Response {
+    reply(scheduler::delete_job(job_id))
+}
+
+async fn pause_job(Path(job_id): Path<String>) -> Response {
+    reply(scheduler::pause_job(job_id))
+}
+
+async fn resume_job(Path(job_id): Path<String>) -> Response {
+    reply(scheduler::resume_job(job_id))
+}
+
+async fn trigger_job(Path(job_id): Path<String>) -> Response {
+    reply(scheduler::trigger_job(job_id).await)
+}
+
+#[derive(Deserialize)]
+struct DependenciesRequest {
+    depends_on: Vec<String>,
+}
+
+async fn set_job_dependencies(Path(job_id): Path<String>, Json(req): Json<DependenciesRequest>) -> Response {
+    reply(scheduler::set_job_dependencies(job_id, req.depends_on))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+async fn job_history(Path(job_id): Path<String>, Query(query): Query<HistoryQuery>) -> Response {
+    reply(scheduler::get_job_history(job_id, query.limit).await)
+}
+
+async fn soul() -> Response {
+    reply(psychology::get_soul())
+}
+
+async fn layers() -> Response {
+    reply(psychology::get_all_layers())
+}
+
+async fn layer(Path(layer): Path<String>) -> Response {
+    reply(psychology::get_layer(layer))
+}
+
+async fn layer_status() -> Response {
+    reply(psychology::get_layer_status())
+}
+
+#[derive(Deserialize)]
+struct ContextQuery {
+    persona: String,
+    budget_tokens: Option<usize>,
+    query: Option<String>,
+    refresh: Option<bool>,
+}
+
+async fn context(Query(query): Query<ContextQuery>) -> Response {
+    reply(crate::psychology::context::build_context(query.persona, query.budget_tokens, query.query, query.refresh).await)
+}
+
+#[derive(Deserialize)]
+struct CommitmentsQuery {
+    days: Option<u32>,
+}
+
+async fn commitments(Query(query): Query<CommitmentsQuery>) -> Response {
+    reply(crate::calendar::commitments::get_upcoming_commitments(query.days).await)
+}
+
+#[derive(Deserialize)]
+struct SkillRunRequest {
+    skill_id: String,
+    #[serde(default)]
+    input: serde_json::Value,
+    agent: Option<String>,
+}
+
+async fn request_skill_run(Json(req): Json<SkillRunRequest>) -> Response {
+    let input = if req.input.is_null() { serde_json::json!({}) } else { req.input };
+    reply(skill_approvals::request(&req.skill_id, input, req.agent, "api").await)
+}
+
+async fn skill_run(Path(run_id): Path<String>) -> Response {
+    reply(skill_approvals::load(&run_id))
+}
+
+#[derive(Deserialize)]
+struct ActionsDeclaration {
+    actions: Vec<ActionDeclaration>,
+}
+
+async fn list_actions() -> Response {
+    reply(gateway_actions::list())
+}
+
+async fn declare_actions(
+    Path((source, owner)): Path<(String, String)>,
+    Json(req): Json<ActionsDeclaration>,
+) -> Response {
+    reply(gateway_actions::declare(&source, &owner, req.actions))
+}
+
+async fn remove_actions(Path((source, owner)): Path<(String, String)>) -> Response {
+    reply(gateway_actions::declare(&source, &owner, Vec::new()))
+}
+
+async fn sync_conflict(Json(conflict): Json<serde_json::Value>) -> Response {
+    reply(hooks::report_sync_conflict(conflict))
+}
@@ -0,0 +1,214 @@
+// Local API server - axum bound to 127.0.0.1, behind a bearer token
+//
+// Started at launch when `api.enabled` is set in config, or on demand from
+// settings. Every request must carry `Authorization: Bearer <token>` and a
+// localhost Host header; the latter keeps web pages from reaching the API
+// through DNS rebinding. Besides the API token, reader tokens (readers.rs)
+// are accepted on the external read routes, with their scope attached to
+// the request.
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+use super::{readers, routes, token};
+use crate::commands::{config, CommandError};
+
+/// Shared with every request handler
+#[derive(Clone)]
+pub struct ApiContext {
+    pub app: AppHandle,
+    token: Arc<RwLock<String>>,
+    port: u16,
+}
+
+struct RunningServer {
+    port: u16,
+    token: Arc<RwLock<String>>,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// Routes reader tokens may use
+const EXTERNAL_PREFIX: &str = "/api/external/";
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// Local API status reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub url: Option<String>,
+    /// File scripts read the token from
+    pub token_path: String,
+}
+
+fn status() -> Result<ApiStatus, String> {
+    let server = SERVER.lock().map_err(|e| e.to_string())?;
+    let port = server.as_ref().map(|s| s.port);
+    Ok(ApiStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|p| format!("http://127.0.0.1:{}/api", p)),
+        token_path: token::token_path()?.display().to_string(),
+    })
+}
+
+fn is_local_host(host: &str, port: u16) -> bool {
+    ["127.0.0.1", "localhost", "[::1]"]
+        .iter()
+        .any(|name| host == format!("{}:{}", name, port))
+}
+
+async fn authorize(State(ctx): State<ApiContext>, request: Request, next: Next) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if !is_local_host(host, ctx.port) {
+        return routes::error_response(StatusCode::FORBIDDEN, "Invalid Host header");
+    }
+
+    let given = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .unwrap_or("");
+    let authorized = ctx
+        .token
+        .read()
+        .map(|expected| token::matches(&expected, given))
+        .unwrap_or(false);
+    if authorized {
+        return next.run(request).await;
+    }
+
+    match readers::authorize(given) {
+        Some(scope) if request.uri().path().starts_with(EXTERNAL_PREFIX) => {
+            let mut request = request;
+            request.extensions_mut().insert(scope);
+            next.run(request).await
+        }
+        Some(_) => routes::error_response(StatusCode::FORBIDDEN, "Reader tokens only open /api/external"),
+        None => routes::error_response(StatusCode::UNAUTHORIZED, "Missing or invalid API token"),
+    }
+}
+
+async fn start(app: AppHandle, port: u16) -> Result<ApiStatus, String> {
+    if SERVER.lock().map_err(|e| e.to_string())?.is_some() {
+        return Err("Local API is already running".to_string());
+    }
+
+    let token = Arc::new(RwLock::new(token::get_or_create()?));
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+
+    let ctx = ApiContext {
+        app,
+        token: token.clone(),
+        port,
+    };
+    let router = routes::router()
+        .layer(middleware::from_fn_with_state(ctx.clone(), authorize))
+        .with_state(ctx);
+
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = result {
+            log::warn!("Local API server stopped: {}", e);
+        }
+    });
+
+    *SERVER.lock().map_err(|e| e.to_string())? = Some(RunningServer {
+        port,
+        token,
+        shutdown,
+    });
+    log::info!("Local API listening on 127.0.0.1:{}", port);
+
+    status()
+}
+
+/// Base URL of the running API, or of the one that starts at launch
+pub fn local_url() -> Option<String> {
+    let running = SERVER.lock().ok().and_then(|server| server.as_ref().map(|s| s.port));
+    let port = running.or_else(|| {
+        config::get_config()
+            .ok()
+            .map(|c| c.api)
+            .filter(|api| api.enabled)
+            .map(|api| api.port)
+    })?;
+    Some(format!("http://127.0.0.1:{}/api", port))
+}
+
+/// Start the API at launch if enabled in config
+pub fn auto_start(app: &AppHandle) {
+    let api = config::get_config().map(|c| c.api).unwrap_or_default();
+    if !api.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start(app, api.port).await {
+            log::warn!("Failed to start local API: {}", e);
+        }
+    });
+}
+
+// Tauri commands for the local API
+
+/// Start the local API (port defaults to `api.port` from config)
+#[tauri::command]
+pub async fn start_api_server(app: AppHandle, port: Option<u16>) -> Result<ApiStatus, CommandError> {
+    let port = match port {
+        Some(port) => port,
+        None => config::get_config()?.api.port,
+    };
+    start(app, port).await.map_err(Into::into)
+}
+
+#[tauri::command]
+pub fn stop_api_server() -> Result<(), CommandError> {
+    let server = SERVER.lock().map_err(|e| e.to_string())?.take();
+    if let Some(server) = server {
+        let _ = server.shutdown.send(());
+        log::info!("Local API stopped");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_api_status() -> Result<ApiStatus, CommandError> {
+    status().map_err(Into::into)
+}
+
+/// The current API token, for copying into scripts
+#[tauri::command]
+pub fn get_api_token() -> Result<String, CommandError> {
+    token::get_or_create().map_err(Into::into)
+}
+
+/// Replace the API token; takes effect immediately if the server is running
+#[tauri::command]
+pub fn rotate_api_token() -> Result<String, CommandError> {
+    let new_token = token::rotate()?;
+    if let Some(server) = SERVER.lock().map_err(|e| e.to_string())?.as_ref() {
+        *server.token.write().map_err(|e| e.to_string())? = new_token.clone();
+    }
+    Ok(new_token)
+}
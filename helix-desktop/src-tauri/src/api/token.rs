@@ -0,0 +1,92 @@
+// Local API token - stored in ~/.helix/api-token so scripts and the CLI can read it
+//
+// A file rather than the keyring: anything that automates Helix runs as the
+// same OS user and needs the token without a keyring prompt.
+
+use rand::Rng;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const TOKEN_FILENAME: &str = "api-token";
+
+pub fn token_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::helix_root()?.join(TOKEN_FILENAME))
+}
+
+fn is_valid(token: &str) -> bool {
+    token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn write_token(path: &Path, token: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Owner-only from the moment it exists, not after a chmod
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(|e| format!("Failed to create token file: {}", e))?;
+    // The mode only applies to a new file; tighten one left by an older version
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to set token file permissions: {}", e))?;
+    }
+    file.write_all(token.as_bytes())
+        .map_err(|e| format!("Failed to write token file: {}", e))
+}
+
+/// The token in the owner-only file at `path`, generated on first use.
+/// Also keeps the tokens of helpers the app starts, such as the skill
+/// sandbox. The value is never logged.
+pub fn load_or_create(path: &Path) -> Result<String, String> {
+    if let Ok(contents) = fs::read_to_string(path) {
+        let token = contents.trim();
+        if is_valid(token) {
+            return Ok(token.to_string());
+        }
+        log::warn!("Token file {} is invalid, regenerating", path.display());
+    }
+    let token = generate_token();
+    write_token(path, &token)?;
+    log::info!("Generated new token in {}", path.display());
+    Ok(token)
+}
+
+/// The API token, generated on first use
+pub fn get_or_create() -> Result<String, String> {
+    load_or_create(&token_path()?)
+}
+
+/// Replace the token; clients holding the old one are locked out
+pub fn rotate() -> Result<String, String> {
+    let token = generate_token();
+    write_token(&token_path()?, &token)?;
+    log::info!("Generated new local API token");
+    Ok(token)
+}
+
+/// Compare in constant time so response timing doesn't leak the token
+pub fn matches(expected: &str, given: &str) -> bool {
+    if expected.len() != given.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(given.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
@@ -0,0 +1,529 @@
+// Lifecycle hooks - user commands and skills run on app events
+//
+// Like git hooks for Helix: a hook names an event (gateway started, sync
+// conflict, job failed, update installed) and either a shell command or a
+// skill to run when it happens. Hooks live in ~/.helix/hooks.json and run in
+// the order they were added, one after the other, each within its own
+// timeout; a command still running then is killed. Commands get the event
+// in their environment: HELIX_HOOK_EVENT, HELIX_HOOK_ID, the whole payload
+// as JSON in HELIX_HOOK_PAYLOAD, and each top-level scalar field of the
+// payload as HELIX_EVENT_<FIELD>, e.g. HELIX_EVENT_JOB_ID. Skills get the
+// hook's input with `event` and `payload` added, and go through skill run
+// approvals like any other requested run (policy/skill_approvals.rs).
+// Every run is appended to ~/.helix/hook-runs.jsonl and announced with
+// hooks:run. Not to be confused with the post-update data migrations in
+// updater/migrations.rs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::CommandError;
+use crate::policy::isolation::{self, ProcessKind};
+use crate::policy::skill_approvals::{self, RunStatus};
+
+const HOOKS_FILE: &str = "hooks.json";
+const RUNS_FILE: &str = "hook-runs.jsonl";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 600;
+/// Characters of a command's output kept in its run
+const OUTPUT_TAIL_CHARS: usize = 4000;
+/// Once the run log grows past this, it is cut back to the newest KEEP_RUNS
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+const KEEP_RUNS: usize = 500;
+const DEFAULT_RUNS_LIMIT: usize = 100;
+
+/// Serializes reads and writes of the hooks file and the run log
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+/// For hooks:run, which is raised outside of commands
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// What a hook runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// The gateway started or was attached; payload: instance, port, url
+    GatewayStarted,
+    /// A sync client reported a conflict; payload: the client's report
+    SyncConflict,
+    /// A scheduler job failed for good, retries used up; payload: job_id,
+    /// job_type, error, retries
+    JobFailed,
+    /// First launch of a new version; payload: from_version, to_version
+    UpdateInstalled,
+}
+
+impl HookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::GatewayStarted => "gateway_started",
+            HookEvent::SyncConflict => "sync_conflict",
+            HookEvent::JobFailed => "job_failed",
+            HookEvent::UpdateInstalled => "update_installed",
+        }
+    }
+}
+
+/// What a hook does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HookAction {
+    /// Run through `sh -c` (`cmd /C` on Windows)
+    Command { command: String },
+    Skill {
+        skill_id: String,
+        #[serde(default)]
+        input: Value,
+    },
+}
+
+/// A registered hook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hook {
+    /// Assigned when the hook is first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub event: HookEvent,
+    #[serde(flatten)]
+    pub action: HookAction,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookRunStatus {
+    Succeeded,
+    Failed,
+    TimedOut,
+    /// A skill run still waiting for approval when the timeout passed
+    Queued,
+}
+
+/// One execution of a hook, also the payload of hooks:run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    pub hook_id: String,
+    pub hook_name: String,
+    pub event: HookEvent,
+    /// Milliseconds since epoch
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub status: HookRunStatus,
+    pub exit_code: Option<i32>,
+    /// Tail of stdout and stderr, or the skill's output
+    pub output: Option<String>,
+    pub error: Option<String>,
+    /// Skill run id, for skill hooks
+    #[serde(default)]
+    pub skill_run_id: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn hooks_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::helix_root()?.join(HOOKS_FILE))
+}
+
+fn runs_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::helix_root()?.join(RUNS_FILE))
+}
+
+fn read_hooks() -> Result<Vec<Hook>, String> {
+    let path = hooks_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read hooks: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse hooks: {}", e))
+}
+
+fn write_hooks(hooks: &[Hook]) -> Result<(), String> {
+    let path = hooks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(hooks).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write hooks: {}", e))
+}
+
+fn append_run(run: &HookRun) -> Result<(), String> {
+    let path = runs_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+    let line = serde_json::to_string(run).map_err(|e| format!("Failed to serialize hook run: {}", e))?;
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open hook run log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write hook run log: {}", e))?;
+    drop(file);
+
+    if fs::metadata(&path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read hook run log: {}", e))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let kept = lines[lines.len().saturating_sub(KEEP_RUNS)..].join("\n");
+        fs::write(&path, kept + "\n").map_err(|e| format!("Failed to trim hook run log: {}", e))?;
+    }
+    Ok(())
+}
+
+fn record(run: &HookRun) {
+    if let Err(e) = append_run(run) {
+        log::warn!("Failed to log run of hook {}: {}", run.hook_id, e);
+    }
+    if let Some(app) = APP.get() {
+        let _ = app.emit("hooks:run", run);
+    }
+}
+
+/// The last `OUTPUT_TAIL_CHARS` characters of `text`
+fn tail(text: &str) -> String {
+    let count = text.chars().count();
+    text.chars().skip(count.saturating_sub(OUTPUT_TAIL_CHARS)).collect()
+}
+
+/// Environment a command hook gets for `event`
+fn hook_env(hook: &Hook, payload: &Value) -> Vec<(String, String)> {
+    let mut env = vec![
+        ("HELIX_HOOK_EVENT".to_string(), hook.event.as_str().to_string()),
+        ("HELIX_HOOK_ID".to_string(), hook.id.clone()),
+        ("HELIX_HOOK_PAYLOAD".to_string(), payload.to_string()),
+    ];
+    if let Some(fields) = payload.as_object() {
+        for (key, value) in fields {
+            let value = match value {
+                Value::String(text) => text.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => continue,
+            };
+            let name: String = key
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+                .collect();
+            env.push((format!("HELIX_EVENT_{}", name), value));
+        }
+    }
+    env
+}
+
+async fn run_command(hook: &Hook, command: &str, payload: &Value, run: &mut HookRun) {
+    #[cfg(windows)]
+    let shell = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let shell = ("sh", "-c");
+    let mut cmd = match isolation::command(ProcessKind::Hooks, shell.0, &[]) {
+        Ok(cmd) => tokio::process::Command::from(cmd),
+        Err(e) => {
+            run.status = HookRunStatus::Failed;
+            run.error = Some(e.message);
+            return;
+        }
+    };
+    cmd.arg(shell.1).arg(command);
+    cmd.envs(hook_env(hook, payload))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            run.status = HookRunStatus::Failed;
+            run.error = Some(format!("Failed to start command: {}", e));
+            return;
+        }
+    };
+    if let Err(e) = isolation::attach(ProcessKind::Hooks, child.id()) {
+        // Dropping the child kills it
+        run.status = HookRunStatus::Failed;
+        run.error = Some(e.message);
+        return;
+    }
+    // Dropping the wait on timeout kills the command
+    match tokio::time::timeout(Duration::from_secs(hook.timeout_secs), child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            run.exit_code = output.status.code();
+            run.output = Some(tail(text.trim_end())).filter(|text| !text.is_empty());
+            if output.status.success() {
+                run.status = HookRunStatus::Succeeded;
+            } else {
+                run.status = HookRunStatus::Failed;
+                run.error = Some(match output.status.code() {
+                    Some(code) => format!("Exited with code {}", code),
+                    None => "Terminated by a signal".to_string(),
+                });
+            }
+        }
+        Ok(Err(e)) => {
+            run.status = HookRunStatus::Failed;
+            run.error = Some(format!("Failed to run command: {}", e));
+        }
+        Err(_) => {
+            run.status = HookRunStatus::TimedOut;
+            run.error = Some(format!("Timed out after {}s", hook.timeout_secs));
+        }
+    }
+}
+
+async fn run_skill(hook: &Hook, skill_id: &str, input: &Value, payload: &Value, run: &mut HookRun) {
+    let mut input = match input {
+        Value::Object(fields) => fields.clone(),
+        Value::Null => Map::new(),
+        other => Map::from_iter([("input".to_string(), other.clone())]),
+    };
+    input.insert("event".to_string(), json!(hook.event.as_str()));
+    input.insert("payload".to_string(), payload.clone());
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    let request = skill_approvals::request(skill_id, Value::Object(input), None, "hook");
+    let requested = match tokio::time::timeout(timeout, request).await {
+        Ok(Ok(requested)) => requested,
+        Ok(Err(e)) => {
+            run.status = HookRunStatus::Failed;
+            run.error = Some(e.message);
+            return;
+        }
+        Err(_) => {
+            run.status = HookRunStatus::TimedOut;
+            run.error = Some(format!("Timed out after {}s", hook.timeout_secs));
+            return;
+        }
+    };
+    run.skill_run_id = Some(requested.id.clone());
+    let remaining = timeout.saturating_sub(Duration::from_millis(now_ms().saturating_sub(run.started_at)));
+    let skill_run = match skill_approvals::wait(&requested.id, remaining).await {
+        Ok(skill_run) => skill_run,
+        Err(e) => {
+            run.status = HookRunStatus::Failed;
+            run.error = Some(e.message);
+            return;
+        }
+    };
+
+    run.output = skill_run.output.map(|output| tail(&output.to_string()));
+    run.error = skill_run.error;
+    run.status = match skill_run.status {
+        RunStatus::Completed => HookRunStatus::Succeeded,
+        RunStatus::Pending => HookRunStatus::Queued,
+        RunStatus::Running => HookRunStatus::TimedOut,
+        RunStatus::Failed => HookRunStatus::Failed,
+        RunStatus::Rejected => {
+            run.error = Some("Skill run was rejected".to_string());
+            HookRunStatus::Failed
+        }
+    };
+}
+
+/// Run one hook for `payload` and log the run
+async fn execute(hook: &Hook, payload: &Value) -> HookRun {
+    let started = Instant::now();
+    let mut run = HookRun {
+        hook_id: hook.id.clone(),
+        hook_name: hook.name.clone(),
+        event: hook.event,
+        started_at: now_ms(),
+        duration_ms: 0,
+        status: HookRunStatus::Failed,
+        exit_code: None,
+        output: None,
+        error: None,
+        skill_run_id: None,
+    };
+    match &hook.action {
+        HookAction::Command { command } => run_command(hook, command, payload, &mut run).await,
+        HookAction::Skill { skill_id, input } => run_skill(hook, skill_id, input, payload, &mut run).await,
+    }
+    run.duration_ms = started.elapsed().as_millis() as u64;
+
+    if run.status != HookRunStatus::Succeeded {
+        log::warn!(
+            "Hook {} ({}) on {}: {:?} {}",
+            hook.name,
+            hook.id,
+            hook.event.as_str(),
+            run.status,
+            run.error.as_deref().unwrap_or_default()
+        );
+    }
+    record(&run);
+    run
+}
+
+/// Keep the app handle for hooks:run
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Run the enabled hooks for `event` in the background, in order
+pub fn fire(event: HookEvent, payload: Value) {
+    let hooks = match FILE_LOCK.lock().map_err(|e| e.to_string()).and_then(|_guard| read_hooks()) {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            log::warn!("Hooks for {} skipped: {}", event.as_str(), e);
+            return;
+        }
+    };
+    let hooks: Vec<Hook> = hooks.into_iter().filter(|hook| hook.enabled && hook.event == event).collect();
+    if hooks.is_empty() {
+        return;
+    }
+
+    log::info!("Running {} hook(s) for {}", hooks.len(), event.as_str());
+    tauri::async_runtime::spawn(async move {
+        for hook in hooks {
+            execute(&hook, &payload).await;
+        }
+    });
+}
+
+/// A sync client ran into conflicting edits; logged as sync activity and
+/// passed to the sync_conflict hooks
+pub fn report_sync_conflict(conflict: Value) -> Result<(), CommandError> {
+    if !conflict.is_object() {
+        return Err(CommandError::invalid_input("A sync conflict report must be a JSON object"));
+    }
+    let entity = conflict
+        .get("entity")
+        .or_else(|| conflict.get("entity_id"))
+        .and_then(Value::as_str)
+        .unwrap_or("an item");
+    events::record(
+        ServiceKind::Sync,
+        "conflict",
+        format!("Sync conflict on {}", entity),
+        Some(conflict.clone()),
+    );
+    fire(HookEvent::SyncConflict, conflict);
+    Ok(())
+}
+
+fn validate(hook: &Hook) -> Result<(), CommandError> {
+    if hook.name.trim().is_empty() {
+        return Err(CommandError::invalid_input("A hook needs a name"));
+    }
+    match &hook.action {
+        HookAction::Command { command } if command.trim().is_empty() => {
+            return Err(CommandError::invalid_input("A command hook needs a command"));
+        }
+        HookAction::Skill { skill_id, .. } if skill_id.trim().is_empty() => {
+            return Err(CommandError::invalid_input("A skill hook needs a skill_id"));
+        }
+        _ => {}
+    }
+    if hook.timeout_secs == 0 || hook.timeout_secs > MAX_TIMEOUT_SECS {
+        return Err(CommandError::invalid_input(format!(
+            "Hook timeout must be between 1 and {} seconds",
+            MAX_TIMEOUT_SECS
+        ))
+        .with_details(json!({ "timeout_secs": hook.timeout_secs })));
+    }
+    Ok(())
+}
+
+// Tauri commands
+
+/// Registered hooks, in the order they run
+#[tauri::command]
+pub fn list_hooks() -> Result<Vec<Hook>, CommandError> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    Ok(read_hooks()?)
+}
+
+/// Add a hook, or replace the one with the same id
+#[tauri::command]
+pub fn save_hook(hook: Hook) -> Result<Hook, CommandError> {
+    let mut hook = hook;
+    hook.name = hook.name.trim().to_string();
+    validate(&hook)?;
+
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut hooks = read_hooks()?;
+    if hook.id.is_empty() {
+        hook.id = format!("hook-{}", hex::encode(rand::random::<[u8; 6]>()));
+        hook.created_at = now_ms();
+        hooks.push(hook.clone());
+    } else {
+        let existing = hooks
+            .iter_mut()
+            .find(|existing| existing.id == hook.id)
+            .ok_or_else(|| CommandError::not_found(format!("Unknown hook: {}", hook.id)))?;
+        hook.created_at = existing.created_at;
+        *existing = hook.clone();
+    }
+    write_hooks(&hooks)?;
+
+    log::info!("Saved hook {} ({}) on {}", hook.name, hook.id, hook.event.as_str());
+    Ok(hook)
+}
+
+/// Remove a hook; its runs stay in the log
+#[tauri::command]
+pub fn delete_hook(id: String) -> Result<(), CommandError> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut hooks = read_hooks()?;
+    let count = hooks.len();
+    hooks.retain(|hook| hook.id != id);
+    if hooks.len() == count {
+        return Err(CommandError::not_found(format!("Unknown hook: {}", id)));
+    }
+    write_hooks(&hooks)?;
+    Ok(())
+}
+
+/// Run a hook now with `payload` (an empty object by default), even if it
+/// is disabled, and wait for the run
+#[tauri::command]
+pub async fn test_hook(id: String, payload: Option<Value>) -> Result<HookRun, CommandError> {
+    let hook = list_hooks()?
+        .into_iter()
+        .find(|hook| hook.id == id)
+        .ok_or_else(|| CommandError::not_found(format!("Unknown hook: {}", id)))?;
+    Ok(execute(&hook, &payload.unwrap_or_else(|| json!({}))).await)
+}
+
+/// Logged hook runs, newest first, of one hook or all of them
+#[tauri::command]
+pub fn get_hook_runs(hook_id: Option<String>, limit: Option<usize>) -> Result<Vec<HookRun>, CommandError> {
+    let limit = limit.unwrap_or(DEFAULT_RUNS_LIMIT).min(KEEP_RUNS);
+    let path = runs_path()?;
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<HookRun>(line).ok())
+        .filter(|run| hook_id.as_ref().is_none_or(|id| &run.hook_id == id))
+        .take(limit)
+        .collect())
+}
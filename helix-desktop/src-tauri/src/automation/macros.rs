@@ -0,0 +1,599 @@
+// Command macros - recorded sequences of backend commands
+//
+// While a recording is running, every replayable command the frontend
+// invokes is captured with its arguments (camelCase, as the frontend sends
+// them). Stopping saves the steps to ~/.helix/macros/<name>.json. String
+// arguments may then be edited to contain `{{param}}` placeholders: a
+// placeholder that is the whole string is replaced by the parameter's JSON
+// value, one inside a longer string by its text. Parameters come from
+// run_macro, else the macro's `params` defaults. Every step is rendered and
+// checked before the first one runs; a dry run stops there and returns the
+// resolved steps. A scheduler Custom job replays a macro by name.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::commands::discord::{self, WebhookPayload};
+use crate::commands::scheduler::{self, JobStatus, JobType};
+use crate::commands::scheduler_runner;
+use crate::commands::{config, gateway, psychology, rust_executables, CommandError, ErrorCode};
+use crate::config::patch::PatchOp;
+
+/// Macro directory within ~/.helix
+const MACROS_DIR: &str = "macros";
+const MAX_NAME_LEN: usize = 64;
+/// How often Custom scheduler jobs are checked for being due
+const JOB_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Cron expressions aren't evaluated by the scheduler yet; like a new
+/// job's first run, a Custom job runs again an hour after the last one
+const CUSTOM_JOB_PERIOD_SECS: u64 = 3600;
+
+/// One recorded command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroStep {
+    /// Tauri command name, e.g. "start_gateway"
+    pub command: String,
+    /// Arguments as the frontend passes them; may contain placeholders
+    #[serde(default)]
+    pub args: Value,
+    /// Keep going with the next step when this one fails
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// A named, replayable command sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Default values of the placeholders the steps use
+    #[serde(default)]
+    pub params: BTreeMap<String, Value>,
+    pub steps: Vec<MacroStep>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// The commands a macro can replay, parsed from a step
+#[derive(Deserialize)]
+#[serde(tag = "command", content = "args", rename_all = "snake_case")]
+enum MacroCommand {
+    StartGateway { instance: Option<String> },
+    StopGateway { instance: Option<String> },
+    SetDnd { until: Option<u64> },
+    #[serde(rename_all = "camelCase")]
+    PauseScheduler { duration_secs: u64 },
+    ResumeScheduler {},
+    #[serde(rename_all = "camelCase")]
+    TriggerJob { job_id: String },
+    #[serde(rename_all = "camelCase")]
+    RunSynthesis { dry_run: bool },
+    #[serde(rename_all = "camelCase")]
+    RunDecay { dry_run: bool },
+    PatchConfig { ops: Vec<PatchOp> },
+    ExecuteAction { id: String, params: Option<Value> },
+    SendWebhook { url: String, payload: WebhookPayload },
+    StartVoicePipeline { port: Option<u16> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// Checked by a dry run; not executed
+    Planned,
+    Succeeded,
+    Failed,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+/// What happened to one step of a run
+#[derive(Debug, Clone, Serialize)]
+pub struct StepOutcome {
+    pub index: usize,
+    pub command: String,
+    /// Arguments with placeholders filled in
+    pub args: Value,
+    pub status: StepStatus,
+    /// The command's return value
+    pub output: Option<Value>,
+    pub error: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Result of run_macro
+#[derive(Debug, Clone, Serialize)]
+pub struct MacroRun {
+    pub name: String,
+    pub dry_run: bool,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub success: bool,
+    pub steps: Vec<StepOutcome>,
+}
+
+/// The recording in progress; also the payload of macros:recording
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingStatus {
+    pub name: String,
+    pub started_at: i64,
+    pub steps: usize,
+}
+
+struct Recording {
+    name: String,
+    started_at: i64,
+    steps: Vec<MacroStep>,
+}
+
+impl Recording {
+    fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            name: self.name.clone(),
+            started_at: self.started_at,
+            steps: self.steps.len(),
+        }
+    }
+}
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+// Storage
+
+fn macros_dir() -> Result<PathBuf, String> {
+    Ok(crate::accounts::profiles::helix_root()?.join(MACROS_DIR))
+}
+
+fn validate_name(name: &str) -> Result<(), CommandError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(CommandError::invalid_input(format!(
+            "Invalid macro name '{}': use up to {} letters, digits, '-' or '_'",
+            name, MAX_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn macro_path(name: &str) -> Result<PathBuf, CommandError> {
+    validate_name(name)?;
+    Ok(macros_dir()?.join(format!("{}.json", name)))
+}
+
+pub fn load(name: &str) -> Result<Macro, CommandError> {
+    let path = macro_path(name)?;
+    let content = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => CommandError::not_found(format!("No macro named '{}'", name))
+            .with_details(json!({ "name": name })),
+        _ => CommandError::from_io("Failed to read macro", e),
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::new(ErrorCode::Config, format!("Invalid macro '{}': {}", name, e)))
+}
+
+fn save(definition: &Macro) -> Result<(), CommandError> {
+    let path = macro_path(&definition.name)?;
+    fs::create_dir_all(macros_dir()?).map_err(|e| CommandError::from_io("Failed to create macros directory", e))?;
+    let content =
+        serde_json::to_string_pretty(definition).map_err(|e| format!("Failed to serialize macro: {}", e))?;
+    fs::write(&path, content).map_err(|e| CommandError::from_io("Failed to write macro", e))
+}
+
+// Recording
+
+/// Wrap the app's command handler so invocations are captured while a
+/// recording is running
+pub fn recording_handler<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        observe(&invoke);
+        handler(invoke)
+    }
+}
+
+fn observe<R: Runtime>(invoke: &Invoke<R>) {
+    let status = {
+        let Ok(mut recording) = RECORDING.lock() else {
+            return;
+        };
+        let Some(recording) = recording.as_mut() else {
+            return;
+        };
+        let InvokeBody::Json(args) = invoke.message.payload() else {
+            return;
+        };
+        let step = MacroStep {
+            command: invoke.message.command().to_string(),
+            args: args.clone(),
+            continue_on_error: false,
+        };
+        // Reads (status, lists) and the recorder's own commands aren't replayable
+        if parse(&step.command, &step.args).is_err() {
+            return;
+        }
+        recording.steps.push(step);
+        recording.status()
+    };
+    let _ = invoke.message.webview_ref().emit("macros:recording", Some(status));
+}
+
+// Replay
+
+fn parse(command: &str, args: &Value) -> Result<MacroCommand, String> {
+    let args = if args.is_null() { json!({}) } else { args.clone() };
+    serde_json::from_value(json!({ "command": command, "args": args })).map_err(|e| e.to_string())
+}
+
+/// `{{name}}` when that is all of `text`
+fn whole_placeholder(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix("{{")?.strip_suffix("}}")?;
+    (!inner.contains("{{") && !inner.contains("}}")).then(|| inner.trim())
+}
+
+fn render_str(text: &str, params: &Map<String, Value>, missing: &mut BTreeSet<String>) -> Value {
+    if let Some(name) = whole_placeholder(text) {
+        return params.get(name).cloned().unwrap_or_else(|| {
+            missing.insert(name.to_string());
+            Value::Null
+        });
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let name = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match params.get(name) {
+            Some(Value::String(value)) => out.push_str(value),
+            Some(value) => out.push_str(&value.to_string()),
+            None => {
+                missing.insert(name.to_string());
+                out.push_str(&rest[start..end]);
+            }
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    Value::String(out)
+}
+
+/// `value` with every placeholder in its strings filled in
+fn render(value: &Value, params: &Map<String, Value>, missing: &mut BTreeSet<String>) -> Value {
+    match value {
+        Value::String(text) => render_str(text, params, missing),
+        Value::Array(items) => Value::Array(items.iter().map(|item| render(item, params, missing)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, item)| (key.clone(), render(item, params, missing)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn output(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+async fn execute(app: &AppHandle, command: MacroCommand) -> Result<Value, CommandError> {
+    Ok(match command {
+        MacroCommand::StartGateway { instance } => output(gateway::start_gateway(app.clone(), instance).await?),
+        MacroCommand::StopGateway { instance } => output(gateway::stop_gateway(app.clone(), instance)?),
+        MacroCommand::SetDnd { until } => output(crate::policy::dnd::set_dnd(app.clone(), until)?),
+        MacroCommand::PauseScheduler { duration_secs } => {
+            output(scheduler::pause_scheduler(app.clone(), duration_secs)?)
+        }
+        MacroCommand::ResumeScheduler {} => output(scheduler::resume_scheduler(app.clone())?),
+        MacroCommand::TriggerJob { job_id } => output(scheduler::trigger_job(job_id).await?),
+        MacroCommand::RunSynthesis { dry_run } => {
+            output(psychology::run_synthesis(app.clone(), app.state(), dry_run).await?)
+        }
+        MacroCommand::RunDecay { dry_run } => output(psychology::run_decay(app.clone(), app.state(), dry_run).await?),
+        MacroCommand::PatchConfig { ops } => output(config::patch_config(app.clone(), ops)?),
+        MacroCommand::ExecuteAction { id, params } => {
+            output(crate::palette::actions::execute_action(app.clone(), app.state(), id, params).await?)
+        }
+        MacroCommand::SendWebhook { url, payload } => output(discord::send_webhook(url, payload).await?),
+        MacroCommand::StartVoicePipeline { port } => output(rust_executables::start_voice_pipeline(port).await?),
+    })
+}
+
+/// Replay `name` with `params` over its defaults. Fails before running
+/// anything if a placeholder has no value or a step can't be replayed.
+pub async fn run(
+    app: &AppHandle,
+    name: &str,
+    params: Map<String, Value>,
+    dry_run: bool,
+) -> Result<MacroRun, CommandError> {
+    let definition = load(name)?;
+    let started_at = now_ms();
+
+    let mut values: Map<String, Value> = definition.params.clone().into_iter().collect();
+    values.extend(params);
+
+    let mut missing = BTreeSet::new();
+    let rendered: Vec<Value> = definition
+        .steps
+        .iter()
+        .map(|step| render(&step.args, &values, &mut missing))
+        .collect();
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.into_iter().collect();
+        return Err(CommandError::invalid_input(format!(
+            "Macro '{}' needs values for: {}",
+            name,
+            missing.join(", ")
+        ))
+        .with_details(json!({ "name": name, "missing": missing })));
+    }
+
+    let mut commands = Vec::with_capacity(definition.steps.len());
+    for (index, (step, args)) in definition.steps.iter().zip(&rendered).enumerate() {
+        let command = parse(&step.command, args).map_err(|e| {
+            CommandError::invalid_input(format!("Step {} ({}) can't be replayed: {}", index + 1, step.command, e))
+                .with_details(json!({ "name": name, "step": index, "command": step.command }))
+        })?;
+        commands.push(command);
+    }
+
+    let mut steps: Vec<StepOutcome> = definition
+        .steps
+        .iter()
+        .zip(rendered)
+        .enumerate()
+        .map(|(index, (step, args))| StepOutcome {
+            index,
+            command: step.command.clone(),
+            args,
+            status: if dry_run { StepStatus::Planned } else { StepStatus::Skipped },
+            output: None,
+            error: None,
+            duration_ms: None,
+        })
+        .collect();
+
+    let mut success = true;
+    if !dry_run {
+        for ((outcome, command), step) in steps.iter_mut().zip(commands).zip(&definition.steps) {
+            let started = Instant::now();
+            let result = execute(app, command).await;
+            outcome.duration_ms = Some(started.elapsed().as_millis() as u64);
+            match result {
+                Ok(value) => {
+                    outcome.status = StepStatus::Succeeded;
+                    outcome.output = Some(value);
+                }
+                Err(e) => {
+                    log::warn!("Macro '{}' step {} ({}) failed: {}", name, outcome.index + 1, outcome.command, e);
+                    outcome.status = StepStatus::Failed;
+                    outcome.error = Some(e.message);
+                    success = false;
+                    if !step.continue_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(MacroRun {
+        name: definition.name,
+        dry_run,
+        started_at,
+        finished_at: now_ms(),
+        success,
+        steps,
+    })
+}
+
+/// Run the Custom scheduler jobs that are due
+async fn run_due_jobs(app: &AppHandle) -> Result<(), CommandError> {
+    let now = Utc::now().timestamp().max(0) as u64;
+    let due: Vec<_> = scheduler::get_scheduled_jobs()?
+        .into_iter()
+        .filter(|job| {
+            job.job_type == JobType::Custom
+                && !matches!(job.status, JobStatus::Paused | JobStatus::Running)
+                && !job.is_spent()
+                && job.next_run <= now
+        })
+        .collect();
+
+    for job in due {
+        let job = scheduler::trigger_job(job.id).await?;
+        if job.status == JobStatus::Deferred {
+            continue;
+        }
+
+        let result = match job.macro_name.as_deref() {
+            Some(name) => scheduler_runner::run_owned(&job, run(app, name, Map::new(), false)).await,
+            None => Err(CommandError::invalid_input("Custom job has no macro")),
+        };
+        match result {
+            Ok(run) if run.success => scheduler::complete_job(job.id.clone(), Some(output(&run)))?,
+            Ok(run) => {
+                let error = run
+                    .steps
+                    .iter()
+                    .find_map(|step| step.error.clone())
+                    .unwrap_or_else(|| "Macro step failed".to_string());
+                scheduler::fail_job(job.id.clone(), error)?
+            }
+            Err(e) => scheduler::fail_job(job.id.clone(), e.message)?,
+        }
+        // A one-shot job is gone or archived once completed, and otherwise
+        // keeps its retry time
+        if !job.is_one_shot() {
+            scheduler::reschedule_job(&job.id, now + CUSTOM_JOB_PERIOD_SECS)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check Custom scheduler jobs every minute
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_due_jobs(&app).await {
+                log::debug!("Custom job check skipped: {}", e);
+            }
+            tokio::time::sleep(JOB_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// Tauri commands
+
+/// Start capturing replayable commands into the macro `name`
+#[tauri::command]
+pub fn start_macro_recording(app: AppHandle, name: String) -> Result<RecordingStatus, CommandError> {
+    validate_name(&name)?;
+    let mut recording = RECORDING.lock().map_err(|e| e.to_string())?;
+    if let Some(current) = recording.as_ref() {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            format!("Already recording macro '{}'", current.name),
+        ));
+    }
+
+    let started = Recording {
+        name,
+        started_at: now_ms(),
+        steps: Vec::new(),
+    };
+    let status = started.status();
+    *recording = Some(started);
+    let _ = app.emit("macros:recording", Some(&status));
+    Ok(status)
+}
+
+/// Stop recording and save the macro, replacing one of the same name.
+/// With `discard` nothing is saved.
+#[tauri::command]
+pub fn stop_macro_recording(app: AppHandle, discard: Option<bool>) -> Result<Option<Macro>, CommandError> {
+    let recording = RECORDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| CommandError::new(ErrorCode::Conflict, "No macro is being recorded"))?;
+    let _ = app.emit("macros:recording", None::<RecordingStatus>);
+
+    if discard.unwrap_or(false) {
+        return Ok(None);
+    }
+    if recording.steps.is_empty() {
+        return Err(CommandError::invalid_input(format!(
+            "Nothing was recorded for macro '{}'",
+            recording.name
+        )));
+    }
+
+    let now = now_ms();
+    let created_at = load(&recording.name).map(|existing| existing.created_at).unwrap_or(now);
+    let definition = Macro {
+        name: recording.name,
+        description: None,
+        params: BTreeMap::new(),
+        steps: recording.steps,
+        created_at,
+        updated_at: now,
+    };
+    save(&definition)?;
+    Ok(Some(definition))
+}
+
+/// The recording in progress, if any
+#[tauri::command]
+pub fn get_macro_recording() -> Result<Option<RecordingStatus>, CommandError> {
+    let recording = RECORDING.lock().map_err(|e| e.to_string())?;
+    Ok(recording.as_ref().map(Recording::status))
+}
+
+/// Saved macros, by name
+#[tauri::command]
+pub fn list_macros() -> Result<Vec<Macro>, CommandError> {
+    let dir = macros_dir()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut macros: Vec<Macro> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            load(path.file_stem()?.to_str()?).ok()
+        })
+        .collect();
+    macros.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(macros)
+}
+
+/// Create or replace a macro, e.g. after adding placeholders to its steps
+#[tauri::command]
+pub fn save_macro(definition: Macro) -> Result<Macro, CommandError> {
+    validate_name(&definition.name)?;
+    if definition.steps.is_empty() {
+        return Err(CommandError::invalid_input("A macro needs at least one step"));
+    }
+
+    let now = now_ms();
+    let created_at = load(&definition.name).map(|existing| existing.created_at).unwrap_or(now);
+    let definition = Macro {
+        created_at,
+        updated_at: now,
+        ..definition
+    };
+    save(&definition)?;
+    Ok(definition)
+}
+
+#[tauri::command]
+pub fn delete_macro(name: String) -> Result<(), CommandError> {
+    let path = macro_path(&name)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(CommandError::not_found(format!("No macro named '{}'", name)))
+        }
+        Err(e) => Err(CommandError::from_io("Failed to delete macro", e)),
+    }
+}
+
+/// Replay a macro. `params` fill its placeholders; `dry_run` only checks
+/// the steps and returns them resolved.
+#[tauri::command]
+pub async fn run_macro(
+    app: AppHandle,
+    name: String,
+    params: Option<Map<String, Value>>,
+    dry_run: Option<bool>,
+) -> Result<MacroRun, CommandError> {
+    run(&app, &name, params.unwrap_or_default(), dry_run.unwrap_or(false)).await
+}
@@ -0,0 +1,4 @@
+// Helix Desktop - Automation Module (recorded command macros and lifecycle hooks)
+
+pub mod hooks;
+pub mod macros;
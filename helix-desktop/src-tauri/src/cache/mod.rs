@@ -0,0 +1,3 @@
+// Helix Desktop - Cache Module (responses of deterministic external calls)
+
+pub mod responses;
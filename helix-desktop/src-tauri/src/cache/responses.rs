@@ -0,0 +1,321 @@
+// Response cache - content-addressed results of deterministic external calls
+//
+// Calls whose answer depends only on their input (embedding a text,
+// summarizing the same text again, speaking the same phrase) look here
+// before going out. Entries are keyed by a SHA-256 of the namespace, model
+// and input, so inputs aren't stored in the clear, and expire after the TTL
+// their caller gives. The SQLite file lives in the account's cache
+// directory, which a "caches" wipe removes; past
+// `response_cache.max_size_mb` the least recently used entries go first.
+// The cache is best effort: a failure is logged and counts as a miss.
+// Hits and misses since launch are reported with the agent metrics.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::commands::config::{self, ResponseCacheConfig};
+use crate::commands::CommandError;
+
+const DB_FILENAME: &str = "response-cache.sqlite";
+/// Least recently used entries removed per eviction round
+const EVICTION_BATCH: i64 = 64;
+
+/// The open database and the path it was opened at; reopened when the
+/// active account, and with it the cache directory, changes
+static POOL: LazyLock<tokio::sync::Mutex<Option<(PathBuf, SqlitePool)>>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(None));
+/// Hits and misses by namespace since launch
+static COUNTERS: LazyLock<Mutex<BTreeMap<String, (u64, u64)>>> = LazyLock::new(|| Mutex::new(BTreeMap::new()));
+/// Entries and bytes stored, as of the last write
+static TOTALS: Mutex<(u64, u64)> = Mutex::new((0, 0));
+
+/// Hits and misses of one namespace
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NamespaceStats {
+    pub namespace: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: Option<f64>,
+}
+
+/// Cache usage, part of the agent metrics snapshot
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits over lookups since launch; none before the first lookup
+    pub hit_rate: Option<f64>,
+    pub entries: u64,
+    pub size_bytes: u64,
+    pub max_size_bytes: u64,
+    pub namespaces: Vec<NamespaceStats>,
+}
+
+fn settings() -> ResponseCacheConfig {
+    config::get_config().map(|c| c.response_cache).unwrap_or_default()
+}
+
+fn hit_rate(hits: u64, misses: u64) -> Option<f64> {
+    (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64)
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Key of an input: the namespace and each part (model, options, text),
+/// hashed together
+pub fn key(namespace: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(namespace.as_bytes());
+    for part in parts {
+        hasher.update([0]);
+        hasher.update(part.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn db_path() -> Result<PathBuf, String> {
+    let cache_dir = dirs::cache_dir().ok_or("Failed to determine cache directory")?;
+    let dir = crate::accounts::profiles::scoped_os_dir(cache_dir.join("helix"));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    Ok(dir.join(DB_FILENAME))
+}
+
+async fn pool() -> Result<SqlitePool, String> {
+    let path = db_path()?;
+    let mut current = POOL.lock().await;
+    if let Some((open_path, pool)) = current.as_ref() {
+        if *open_path == path {
+            return Ok(pool.clone());
+        }
+    }
+    if let Some((_, old)) = current.take() {
+        old.close().await;
+    }
+
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open response cache: {}", e))?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS responses (
+            key TEXT PRIMARY KEY,
+            namespace TEXT NOT NULL,
+            value BLOB NOT NULL,
+            size INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            last_used INTEGER NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to create response cache table: {}", e))?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS responses_last_used ON responses (last_used)")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to index response cache: {}", e))?;
+
+    refresh_totals(&pool).await;
+    *current = Some((path, pool.clone()));
+    Ok(pool)
+}
+
+async fn refresh_totals(pool: &SqlitePool) {
+    let totals = sqlx::query_as::<_, (i64, i64)>("SELECT COUNT(*), COALESCE(SUM(size), 0) FROM responses")
+        .fetch_one(pool)
+        .await;
+    match totals {
+        Ok((entries, bytes)) => {
+            if let Ok(mut totals) = TOTALS.lock() {
+                *totals = (entries.max(0) as u64, bytes.max(0) as u64);
+            }
+        }
+        Err(e) => log::debug!("Failed to total the response cache: {}", e),
+    }
+}
+
+fn count(namespace: &str, hit: bool) {
+    if let Ok(mut counters) = COUNTERS.lock() {
+        let (hits, misses) = counters.entry(namespace.to_string()).or_default();
+        if hit {
+            *hits += 1;
+        } else {
+            *misses += 1;
+        }
+    }
+}
+
+/// The unexpired value stored under `key`, counting the hit or miss
+pub async fn get(namespace: &str, key: &str) -> Option<Vec<u8>> {
+    if !settings().enabled {
+        return None;
+    }
+    let value = match lookup(key).await {
+        Ok(value) => value,
+        Err(e) => {
+            log::debug!("Response cache lookup failed: {}", e);
+            None
+        }
+    };
+    count(namespace, value.is_some());
+    value
+}
+
+async fn lookup(key: &str) -> Result<Option<Vec<u8>>, String> {
+    let pool = pool().await?;
+    let now = now_secs();
+    let value = sqlx::query_as::<_, (Vec<u8>,)>("SELECT value FROM responses WHERE key = ? AND expires_at > ?")
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if value.is_some() {
+        sqlx::query("UPDATE responses SET last_used = ? WHERE key = ?")
+            .bind(now)
+            .bind(key)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(value.map(|(value,)| value))
+}
+
+/// Store `value` under `key` for `ttl`, then evict down to the size limit
+pub async fn put(namespace: &str, key: &str, value: &[u8], ttl: Duration) {
+    let settings = settings();
+    if !settings.enabled {
+        return;
+    }
+    if let Err(e) = store(namespace, key, value, ttl, settings.max_size_mb * 1024 * 1024).await {
+        log::debug!("Response cache write failed: {}", e);
+    }
+}
+
+async fn store(namespace: &str, key: &str, value: &[u8], ttl: Duration, max_bytes: u64) -> Result<(), String> {
+    if value.len() as u64 > max_bytes {
+        return Ok(());
+    }
+    let pool = pool().await?;
+    let now = now_secs();
+    sqlx::query(
+        "INSERT OR REPLACE INTO responses (key, namespace, value, size, created_at, expires_at, last_used)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(key)
+    .bind(namespace)
+    .bind(value)
+    .bind(value.len() as i64)
+    .bind(now)
+    .bind(now.saturating_add(ttl.as_secs() as i64))
+    .bind(now)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    refresh_totals(&pool).await;
+
+    if size_bytes() <= max_bytes {
+        return Ok(());
+    }
+    sqlx::query("DELETE FROM responses WHERE expires_at <= ?")
+        .bind(now)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh_totals(&pool).await;
+    while size_bytes() > max_bytes {
+        let evicted = sqlx::query(
+            "DELETE FROM responses WHERE key IN (SELECT key FROM responses ORDER BY last_used ASC LIMIT ?)",
+        )
+        .bind(EVICTION_BATCH)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        refresh_totals(&pool).await;
+        if evicted.rows_affected() == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn size_bytes() -> u64 {
+    TOTALS.lock().map(|totals| totals.1).unwrap_or(0)
+}
+
+/// Close the database before its file is removed (see privacy/wipe.rs)
+pub async fn close() {
+    if let Some((_, pool)) = POOL.lock().await.take() {
+        pool.close().await;
+    }
+    if let Ok(mut totals) = TOTALS.lock() {
+        *totals = (0, 0);
+    }
+}
+
+pub fn stats() -> CacheStats {
+    let settings = settings();
+    let (entries, size_bytes) = TOTALS.lock().map(|totals| *totals).unwrap_or_default();
+    let namespaces: Vec<NamespaceStats> = COUNTERS
+        .lock()
+        .map(|counters| {
+            counters
+                .iter()
+                .map(|(namespace, &(hits, misses))| NamespaceStats {
+                    namespace: namespace.clone(),
+                    hits,
+                    misses,
+                    hit_rate: hit_rate(hits, misses),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let hits = namespaces.iter().map(|n| n.hits).sum();
+    let misses = namespaces.iter().map(|n| n.misses).sum();
+
+    CacheStats {
+        enabled: settings.enabled,
+        hits,
+        misses,
+        hit_rate: hit_rate(hits, misses),
+        entries,
+        size_bytes,
+        max_size_bytes: settings.max_size_mb * 1024 * 1024,
+        namespaces,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_response_cache_stats() -> Result<CacheStats, CommandError> {
+    Ok(stats())
+}
+
+/// Remove cached responses, of one namespace or all; returns how many
+#[tauri::command]
+pub async fn clear_response_cache(namespace: Option<String>) -> Result<u64, CommandError> {
+    let pool = pool().await.map_err(CommandError::internal)?;
+    let result = match &namespace {
+        Some(namespace) => sqlx::query("DELETE FROM responses WHERE namespace = ?").bind(namespace).execute(&pool).await,
+        None => sqlx::query("DELETE FROM responses").execute(&pool).await,
+    }
+    .map_err(|e| CommandError::internal(format!("Failed to clear response cache: {}", e)))?;
+    refresh_totals(&pool).await;
+
+    log::info!("Cleared {} cached responses", result.rows_affected());
+    Ok(result.rows_affected())
+}
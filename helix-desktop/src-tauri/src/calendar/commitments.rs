@@ -0,0 +1,258 @@
+// Upcoming commitments - calendar events correlated with prospective-layer goals
+//
+// Events are linked to goals by keyword overlap between the event text and the
+// goal's id and description. The result (and a snapshot for the synthesis
+// script at identity/upcoming_commitments.json) shows which goals actually
+// have time on the calendar.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::connector;
+use super::ics::CalendarEvent;
+use crate::commands::config;
+use crate::vault;
+use crate::commands::CommandError;
+
+const GOALS_FILE: &str = "identity/goals.json";
+pub const SNAPSHOT_FILE: &str = "identity/upcoming_commitments.json";
+
+/// Goals at or above this priority (1 = highest) are flagged when unscheduled
+const HIGH_PRIORITY: u32 = 1;
+/// Keyword stems are compared on this many leading characters
+const STEM_CHARS: usize = 6;
+
+pub(crate) const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "also", "being", "beyond", "could", "every", "from", "have",
+    "into", "just", "like", "make", "more", "only", "other", "over", "some", "than", "that",
+    "their", "them", "then", "there", "these", "they", "this", "through", "what", "when",
+    "where", "which", "while", "with", "would", "your",
+];
+
+/// A goal from identity/goals.json
+#[derive(Debug, Clone)]
+struct Goal {
+    id: String,
+    description: String,
+    priority: Option<u32>,
+    keywords: HashSet<String>,
+}
+
+/// A calendar event with the goals it appears to serve
+#[derive(Debug, Clone, Serialize)]
+pub struct Commitment {
+    #[serde(flatten)]
+    pub event: CalendarEvent,
+    pub goal_ids: Vec<String>,
+}
+
+/// How much calendar time a goal has in the lookahead window
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalAlignment {
+    pub goal_id: String,
+    pub description: String,
+    pub priority: Option<u32>,
+    pub scheduled_events: usize,
+    pub scheduled_hours: f64,
+    pub next_event: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingCommitments {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub lookahead_days: u32,
+    pub commitments: Vec<Commitment>,
+    pub alignment: Vec<GoalAlignment>,
+    /// Schedule-goal alignment patterns, consumed by the synthesis script
+    pub patterns: Vec<String>,
+    /// Sources that could not be fetched
+    pub errors: Vec<String>,
+}
+
+/// Lowercased keyword stems, without stopwords and short words
+pub(crate) fn keywords(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() >= 4 && !STOPWORDS.contains(&w.as_str()))
+        .map(|w| w.chars().take(STEM_CHARS).collect())
+        .collect()
+}
+
+fn load_goals() -> Vec<Goal> {
+    let Ok(dir) = crate::accounts::data_dir() else {
+        return Vec::new();
+    };
+    let Ok(content) = vault::read_to_string(&dir.join(GOALS_FILE)) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+
+    let entries = json
+        .get("core_goals")
+        .or_else(|| json.get("goals"))
+        .and_then(|g| g.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    entries
+        .iter()
+        .filter_map(|goal| {
+            let id = goal.get("id")?.as_str()?.to_string();
+            let description = goal
+                .get("description")
+                .or_else(|| goal.get("title"))
+                .and_then(|d| d.as_str())
+                .unwrap_or(&id)
+                .to_string();
+            let keywords = keywords(&format!("{} {}", id.replace('_', " "), description));
+            Some(Goal {
+                priority: goal.get("priority").and_then(|p| p.as_u64()).map(|p| p as u32),
+                id,
+                description,
+                keywords,
+            })
+        })
+        .collect()
+}
+
+fn correlate(events: Vec<CalendarEvent>, goals: &[Goal]) -> Vec<Commitment> {
+    events
+        .into_iter()
+        .map(|event| {
+            let text = format!(
+                "{} {} {}",
+                event.summary,
+                event.description.as_deref().unwrap_or(""),
+                event.location.as_deref().unwrap_or("")
+            );
+            let event_keywords = keywords(&text);
+            let goal_ids = goals
+                .iter()
+                .filter(|goal| !goal.keywords.is_disjoint(&event_keywords))
+                .map(|goal| goal.id.clone())
+                .collect();
+            Commitment { event, goal_ids }
+        })
+        .collect()
+}
+
+fn hours(event: &CalendarEvent) -> f64 {
+    (event.end - event.start).num_minutes().max(0) as f64 / 60.0
+}
+
+fn align(commitments: &[Commitment], goals: &[Goal]) -> Vec<GoalAlignment> {
+    goals
+        .iter()
+        .map(|goal| {
+            let linked: Vec<&Commitment> = commitments
+                .iter()
+                .filter(|c| c.goal_ids.contains(&goal.id))
+                .collect();
+            GoalAlignment {
+                goal_id: goal.id.clone(),
+                description: goal.description.clone(),
+                priority: goal.priority,
+                scheduled_events: linked.len(),
+                scheduled_hours: linked.iter().map(|c| hours(&c.event)).sum(),
+                next_event: linked.iter().map(|c| c.event.start).min(),
+            }
+        })
+        .collect()
+}
+
+fn patterns(commitments: &[Commitment], alignment: &[GoalAlignment], days: u32) -> Vec<String> {
+    let mut patterns = Vec::new();
+
+    for goal in alignment {
+        let high_priority = goal.priority.is_some_and(|p| p <= HIGH_PRIORITY);
+        if goal.scheduled_events == 0 {
+            if high_priority {
+                patterns.push(format!(
+                    "Goal '{}' (priority {}) has no scheduled time in the next {} days",
+                    goal.description,
+                    goal.priority.unwrap_or(HIGH_PRIORITY),
+                    days
+                ));
+            }
+        } else {
+            patterns.push(format!(
+                "Goal '{}' has {} scheduled events ({:.1}h) in the next {} days",
+                goal.description, goal.scheduled_events, goal.scheduled_hours, days
+            ));
+        }
+    }
+
+    // Timed events only; all-day events would swamp the totals
+    let timed: Vec<&Commitment> = commitments.iter().filter(|c| !c.event.all_day).collect();
+    let total: f64 = timed.iter().map(|c| hours(&c.event)).sum();
+    let unlinked: f64 = timed
+        .iter()
+        .filter(|c| c.goal_ids.is_empty())
+        .map(|c| hours(&c.event))
+        .sum();
+    if total > 0.0 && unlinked / total >= 0.75 {
+        patterns.push(format!(
+            "{:.0}% of scheduled time in the next {} days is not linked to any goal",
+            unlinked / total * 100.0,
+            days
+        ));
+    }
+
+    patterns
+}
+
+fn save_snapshot(upcoming: &UpcomingCommitments) -> Result<(), String> {
+    let path = crate::accounts::data_dir()?.join(SNAPSHOT_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(upcoming).map_err(|e| e.to_string())?;
+    vault::write(&path, &json)
+}
+
+/// Fetch upcoming events from every configured calendar and align them with goals
+#[tauri::command]
+pub async fn get_upcoming_commitments(days: Option<u32>) -> Result<UpcomingCommitments, CommandError> {
+    let calendar = config::get_config()?.calendar;
+    let days = days.unwrap_or(calendar.lookahead_days).clamp(1, 90);
+    let from = Utc::now();
+    let to = from + Duration::days(days as i64);
+
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    for source in &calendar.sources {
+        match connector::fetch_events(source, from, to).await {
+            Ok(mut fetched) => events.append(&mut fetched),
+            Err(e) => {
+                log::warn!("Calendar '{}' failed: {}", source.name, e);
+                errors.push(format!("{}: {}", source.name, e));
+            }
+        }
+    }
+    events.sort_by_key(|e| e.start);
+
+    let goals = load_goals();
+    let commitments = correlate(events, &goals);
+    let alignment = align(&commitments, &goals);
+    let patterns = patterns(&commitments, &alignment, days);
+
+    let upcoming = UpcomingCommitments {
+        from,
+        to,
+        lookahead_days: days,
+        commitments,
+        alignment,
+        patterns,
+        errors,
+    };
+
+    if let Err(e) = save_snapshot(&upcoming) {
+        log::warn!("Failed to save commitments snapshot: {}", e);
+    }
+
+    Ok(upcoming)
+}
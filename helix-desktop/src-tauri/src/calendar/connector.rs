@@ -0,0 +1,141 @@
+// Calendar fetching - ICS feeds over HTTP and CalDAV calendar-query
+//
+// Read-only: only GET and REPORT requests are ever sent.
+
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use std::time::Duration;
+
+use super::ics::{self, CalendarEvent};
+use crate::commands::config::CalendarSource;
+use crate::commands::keyring;
+
+const FETCH_TIMEOUT_SECS: u64 = 20;
+
+/// Keyring key holding a CalDAV source's password
+pub fn password_key(source: &CalendarSource) -> String {
+    format!("calendar_{}", source.name)
+}
+
+/// Upcoming events from one source within [from, to)
+pub async fn fetch_events(
+    source: &CalendarSource,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<CalendarEvent>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let body = match source.kind.as_str() {
+        "ics" => fetch_ics(&client, source).await?,
+        "caldav" => fetch_caldav(&client, source, from, to).await?,
+        other => return Err(format!("Unknown calendar kind '{}'", other)),
+    };
+
+    Ok(extract_calendars(&body)
+        .iter()
+        .flat_map(|calendar| ics::parse_events(calendar, &source.name, from, to))
+        .collect())
+}
+
+async fn fetch_ics(client: &reqwest::Client, source: &CalendarSource) -> Result<String, String> {
+    // webcal:// is just a hint for calendar apps
+    let url = match source.url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => source.url.clone(),
+    };
+
+    let mut request = client.get(&url);
+    if let Some(username) = &source.username {
+        request = request.basic_auth(username, keyring::get_secret(password_key(source))?);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", source.name, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned HTTP {}", source.name, response.status()));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", source.name, e))
+}
+
+async fn fetch_caldav(
+    client: &reqwest::Client,
+    source: &CalendarSource,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<String, String> {
+    let username = source
+        .username
+        .as_ref()
+        .ok_or_else(|| format!("CalDAV source '{}' has no username", source.name))?;
+    let password = keyring::get_secret(password_key(source))?
+        .ok_or_else(|| format!("No password stored for '{}'", source.name))?;
+
+    let query = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><C:calendar-data/></D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="{}" end="{}"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#,
+        from.format("%Y%m%dT%H%M%SZ"),
+        to.format("%Y%m%dT%H%M%SZ")
+    );
+
+    let method = Method::from_bytes(b"REPORT").map_err(|e| e.to_string())?;
+    let response = client
+        .request(method, &source.url)
+        .basic_auth(username, Some(password))
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(query)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", source.name, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned HTTP {}", source.name, response.status()));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", source.name, e))
+}
+
+/// VCALENDAR blocks in a response: the whole body for ICS, one per
+/// calendar-data element for a CalDAV multistatus
+fn extract_calendars(body: &str) -> Vec<String> {
+    let unescaped = body
+        .replace("&#13;", "\r")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+
+    let mut calendars = Vec::new();
+    let mut rest = unescaped.as_str();
+    while let Some(start) = rest.find("BEGIN:VCALENDAR") {
+        let block = &rest[start..];
+        let end = block
+            .find("END:VCALENDAR")
+            .map(|i| i + "END:VCALENDAR".len())
+            .unwrap_or(block.len());
+        calendars.push(block[..end].to_string());
+        rest = &block[end..];
+    }
+    calendars
+}
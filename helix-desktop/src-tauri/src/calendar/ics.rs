@@ -0,0 +1,221 @@
+// ICS (RFC 5545) event parsing - just enough for upcoming commitments
+//
+// Handles line folding, text escapes, all-day and UTC/floating times, and
+// daily/weekly RRULEs with INTERVAL, COUNT and UNTIL. Times with a TZID are
+// read as local time. Other recurrence rules yield their first instance only.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+
+/// Cap on expanded occurrences of a single recurring event within the window
+const MAX_OCCURRENCES: usize = 500;
+
+/// A calendar event (or one occurrence of a recurring event)
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub all_day: bool,
+    /// Name of the calendar source
+    pub source: String,
+}
+
+/// Raw properties of one VEVENT
+#[derive(Default)]
+struct RawEvent {
+    props: Vec<(String, String, String)>, // name, params, value
+}
+
+impl RawEvent {
+    fn get(&self, name: &str) -> Option<(&str, &str)> {
+        self.props
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, p, v)| (p.as_str(), v.as_str()))
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .map(|(_, v)| unescape(v))
+            .filter(|v| !v.trim().is_empty())
+    }
+}
+
+/// Join folded lines (continuations start with a space or tab)
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(rest);
+                }
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Parse a DTSTART/DTEND value; returns the instant and whether it is all-day
+fn parse_datetime(params: &str, value: &str) -> Option<(DateTime<Utc>, bool)> {
+    let value = value.trim();
+
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let local = Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+        return Some((local.with_timezone(&Utc), true));
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive), false));
+    }
+
+    // Floating or TZID time: read as local
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some((local.with_timezone(&Utc), false))
+}
+
+/// Parsed RRULE subset
+struct Recurrence {
+    step: Duration,
+    count: Option<usize>,
+    until: Option<DateTime<Utc>>,
+}
+
+fn parse_rrule(value: &str) -> Option<Recurrence> {
+    let mut freq = None;
+    let mut interval = 1i64;
+    let mut count = None;
+    let mut until = None;
+
+    for part in value.split(';') {
+        let (key, val) = part.split_once('=')?;
+        match key {
+            "FREQ" => freq = Some(val),
+            "INTERVAL" => interval = val.parse().unwrap_or(1).max(1),
+            "COUNT" => count = val.parse().ok(),
+            "UNTIL" => until = parse_datetime("", val).map(|(t, _)| t),
+            _ => {}
+        }
+    }
+
+    let step = match freq? {
+        "DAILY" => Duration::days(interval),
+        "WEEKLY" => Duration::weeks(interval),
+        _ => return None,
+    };
+    Some(Recurrence { step, count, until })
+}
+
+/// Events overlapping [from, to), with recurring events expanded
+pub fn parse_events(ics: &str, source: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<CalendarEvent> {
+    let mut raw_events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+
+    for line in unfold(ics) {
+        match line.trim_end() {
+            "BEGIN:VEVENT" => current = Some(RawEvent::default()),
+            "END:VEVENT" => raw_events.extend(current.take()),
+            _ => {
+                let Some(event) = current.as_mut() else {
+                    continue;
+                };
+                let Some((head, value)) = line.split_once(':') else {
+                    continue;
+                };
+                let (name, params) = head.split_once(';').unwrap_or((head, ""));
+                event
+                    .props
+                    .push((name.to_uppercase(), params.to_uppercase(), value.to_string()));
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for raw in raw_events {
+        if raw.get("STATUS").is_some_and(|(_, v)| v.eq_ignore_ascii_case("CANCELLED")) {
+            continue;
+        }
+        let Some((start, all_day)) = raw.get("DTSTART").and_then(|(p, v)| parse_datetime(p, v)) else {
+            continue;
+        };
+        let end = raw
+            .get("DTEND")
+            .and_then(|(p, v)| parse_datetime(p, v))
+            .map(|(t, _)| t)
+            .unwrap_or(if all_day { start + Duration::days(1) } else { start });
+        let duration = end - start;
+
+        let template = CalendarEvent {
+            uid: raw.text("UID").unwrap_or_default(),
+            summary: raw.text("SUMMARY").unwrap_or_else(|| "(No title)".to_string()),
+            description: raw.text("DESCRIPTION"),
+            location: raw.text("LOCATION"),
+            start,
+            end,
+            all_day,
+            source: source.to_string(),
+        };
+
+        let recurrence = raw.get("RRULE").and_then(|(_, v)| parse_rrule(v));
+        let Some(rule) = recurrence else {
+            if end > from && start < to {
+                events.push(template);
+            }
+            continue;
+        };
+
+        // Start at the first occurrence that ends inside the window, so the
+        // cap limits occurrences shown rather than ones since DTSTART
+        let first = if end <= from {
+            ((from - end).num_seconds() / rule.step.num_seconds()) as usize + 1
+        } else {
+            0
+        };
+        let mut occurrence_start = start + rule.step * first as i32;
+        for n in first..first + MAX_OCCURRENCES {
+            if rule.count.is_some_and(|c| n >= c)
+                || rule.until.is_some_and(|u| occurrence_start > u)
+                || occurrence_start >= to
+            {
+                break;
+            }
+            let occurrence_end = occurrence_start + duration;
+            if occurrence_end > from {
+                events.push(CalendarEvent {
+                    start: occurrence_start,
+                    end: occurrence_end,
+                    ..template.clone()
+                });
+            }
+            occurrence_start += rule.step;
+        }
+    }
+
+    events.sort_by_key(|e| e.start);
+    events
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Calendar Module (read-only ICS/CalDAV connector)
+
+pub mod commitments;
+pub mod connector;
+pub mod ics;
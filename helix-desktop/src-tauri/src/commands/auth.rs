@@ -4,6 +4,11 @@ use std::fs;
 use std::process::Command;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
+use std::time::Duration;
+
+use crate::tasks::run_process;
+use crate::commands::CommandError;
+use crate::remote::cloud_queue::{self, CallError, CloudCall};
 
 /// Claude Code credentials structure (from ~/.claude/.credentials.json)
 #[derive(Deserialize)]
@@ -106,7 +111,7 @@ fn find_in_path(finder: &str, cmd: &str) -> Option<String> {
 /// Detect if Claude Code is installed and authenticated
 /// This checks both the CLI availability and credential status
 #[tauri::command]
-pub fn detect_claude_code() -> Result<ClaudeCodeInfo, String> {
+pub fn detect_claude_code() -> Result<ClaudeCodeInfo, CommandError> {
     let home = dirs::home_dir()
         .ok_or_else(|| "Could not find home directory".to_string())?;
 
@@ -197,34 +202,65 @@ pub fn detect_claude_code() -> Result<ClaudeCodeInfo, String> {
     }
 }
 
+/// Default time limit for a Claude Code run
+const CLAUDE_CODE_TIMEOUT_SECS: u64 = 600;
+
 /// Run a command via Claude Code CLI (uses the user's authenticated session)
 /// This is the proper way to use Claude Code - via subprocess, not token extraction
+///
+/// Runs as a background task and returns its id; the output arrives in the
+/// `task:finished` event and `cancel_task` kills the CLI process.
 #[tauri::command]
-pub async fn run_claude_code(prompt: String, working_dir: Option<String>) -> Result<String, String> {
+pub async fn run_claude_code(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    prompt: String,
+    working_dir: Option<String>,
+    timeout_secs: Option<u64>,
+    context_persona: Option<String>,
+) -> Result<String, CommandError> {
     let cli_path = check_claude_cli()
-        .ok_or_else(|| "Claude Code CLI not found. Install it with: npm install -g @anthropic-ai/claude-code".to_string())?;
+        .ok_or_else(|| CommandError::process("Claude Code CLI not found. Install it with: npm install -g @anthropic-ai/claude-code"))?;
 
-    let mut cmd = Command::new(&cli_path);
+    let mut cmd = tokio::process::Command::new(&cli_path);
 
     // Use print mode for non-interactive output
     cmd.arg("--print");
     cmd.arg(&prompt);
 
+    // Helix context for the persona, appended to the system prompt
+    if let Some(persona) = context_persona {
+        let bundle = crate::psychology::context::build_bundle(
+            &persona,
+            crate::psychology::context::DEFAULT_BUDGET_TOKENS,
+            Some(&prompt),
+            false,
+        )
+        .await?;
+        cmd.arg("--append-system-prompt").arg(bundle.text);
+    }
+
     // Set working directory if provided
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
 
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to run Claude Code: {}", e))?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(CLAUDE_CODE_TIMEOUT_SECS));
 
-    if output.status.success() {
-        String::from_utf8(output.stdout)
-            .map_err(|e| format!("Invalid UTF-8 in output: {}", e))
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Claude Code error: {}", stderr))
-    }
+    state.task_manager.spawn(app, "claude-code", Some(timeout), |_task| async move {
+        let output = run_process(cmd)
+            .await
+            .map_err(|e| format!("Failed to run Claude Code: {}", e))?;
+
+        if output.status.success() {
+            String::from_utf8(output.stdout)
+                .map_err(|e| format!("Invalid UTF-8 in output: {}", e))
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(format!("Claude Code error: {}", stderr))
+        }
+    })
+    .map_err(Into::into)
 }
 
 // ============================================================================
@@ -284,11 +320,11 @@ fn get_auth_profiles_path() -> Result<String, String> {
 ///
 /// **BYOK Pattern**: Credentials stored locally, never transmitted to Helix servers.
 #[tauri::command]
-pub async fn run_openclaw_oauth(provider: String, flow: String) -> Result<OAuthFlowResult, String> {
+pub async fn run_openclaw_oauth(provider: String, flow: String) -> Result<OAuthFlowResult, CommandError> {
     // Validate inputs
     let valid_providers = vec!["anthropic", "openai-codex"];
     if !valid_providers.contains(&provider.as_str()) {
-        return Err(format!("Unsupported provider: {}", provider));
+        return Err(CommandError::invalid_input(format!("Unsupported provider: {}", provider)));
     }
 
     // Build OpenClaw CLI command
@@ -309,13 +345,13 @@ pub async fn run_openclaw_oauth(provider: String, flow: String) -> Result<OAuthF
                 .arg(&provider);
         }
         _ => {
-            return Err(format!("Unsupported flow: {}", flow));
+            return Err(CommandError::invalid_input(format!("Unsupported flow: {}", flow)));
         }
     }
 
     // Execute OpenClaw subprocess
     let output = cmd.output()
-        .map_err(|e| format!("Failed to execute openclaw: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to execute openclaw: {}", e)))?;
 
     let auth_profiles_path = get_auth_profiles_path()?;
 
@@ -329,7 +365,7 @@ pub async fn run_openclaw_oauth(provider: String, flow: String) -> Result<OAuthF
         })
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        Err(stderr)
+        Err(CommandError::process(stderr))
     }
 }
 
@@ -338,7 +374,7 @@ pub async fn run_openclaw_oauth(provider: String, flow: String) -> Result<OAuthF
 /// Reads auth-profiles.json and checks if the provider has credentials.
 /// Returns early if file doesn't exist (graceful fallback).
 #[tauri::command]
-pub fn check_oauth_credentials(provider: String) -> Result<CheckCredentialsResult, String> {
+pub fn check_oauth_credentials(provider: String) -> Result<CheckCredentialsResult, CommandError> {
     let auth_profiles_path = get_auth_profiles_path()?;
 
     // Check if file exists (may not if user hasn't authenticated yet)
@@ -420,11 +456,16 @@ struct SupabaseSubscription {
     tier: String,
 }
 
+/// Get the Supabase anon key from environment. Calls made for the user
+/// pair it with their access token (accounts::session).
+pub fn get_supabase_anon_key() -> Result<String, String> {
+    std::env::var("SUPABASE_ANON_KEY")
+        .map_err(|_| "SUPABASE_ANON_KEY environment variable not set".to_string())
+}
+
 /// Get Supabase credentials from environment
-fn get_supabase_credentials() -> Result<(String, String), String> {
-    let anon_key = std::env::var("SUPABASE_ANON_KEY")
-        .or_else(|_| std::env::var("SUPABASE_ANON_KEY"))
-        .map_err(|_| "SUPABASE_ANON_KEY environment variable not set".to_string())?;
+pub fn get_supabase_credentials() -> Result<(String, String), String> {
+    let anon_key = get_supabase_anon_key()?;
 
     let service_role_key = std::env::var("SUPABASE_SERVICE_ROLE_KEY")
         .or_else(|_| std::env::var("SUPABASE_SERVICE_ROLE_KEY"))
@@ -434,7 +475,7 @@ fn get_supabase_credentials() -> Result<(String, String), String> {
 }
 
 /// Get Supabase URL from environment or use default
-fn get_supabase_url() -> Result<String, String> {
+pub fn get_supabase_url() -> Result<String, String> {
     Ok(std::env::var("SUPABASE_URL")
         .unwrap_or_else(|_| "https://helix-backend.supabase.co".to_string()))
 }
@@ -445,9 +486,10 @@ fn get_supabase_url() -> Result<String, String> {
 /// Returns user_id, email, and tier (core, phantom, overseer, architect).
 #[tauri::command]
 pub async fn supabase_login(
+    app: tauri::AppHandle,
     email: String,
     password: String,
-) -> Result<SupabaseLoginResponse, String> {
+) -> Result<SupabaseLoginResponse, CommandError> {
     let (anon_key, _) = get_supabase_credentials()?;
     let supabase_url = get_supabase_url()?;
 
@@ -514,6 +556,12 @@ pub async fn supabase_login(
         Err(_) => "core".to_string(),
     };
 
+    // Step 3: Switch local data to this account's directory, and keep the
+    // session there for calls made on the user's behalf
+    crate::accounts::profiles::activate(&app, &user_id, Some(&email)).await?;
+    crate::accounts::session::store(&auth_data)?;
+    crate::accounts::tiers::reverify();
+
     Ok(SupabaseLoginResponse {
         success: true,
         user_id: Some(user_id),
@@ -531,7 +579,7 @@ pub async fn supabase_login(
 pub async fn supabase_signup(
     email: String,
     password: String,
-) -> Result<SupabaseSignupResponse, String> {
+) -> Result<SupabaseSignupResponse, CommandError> {
     // Validate password strength
     if password.len() < 8 {
         return Ok(SupabaseSignupResponse {
@@ -593,21 +641,20 @@ pub async fn supabase_signup(
     })
 }
 
-/// Register this device with Supabase
-///
-/// Inserts into user_instances table so web dashboard knows about this device.
-/// Handles conflicts by updating if instance_id already exists.
-/// Sends both instance_id and device_id (same value) for backwards compatibility.
-#[tauri::command]
-pub async fn register_device(
-    user_id: String,
-    device_id: String,
-    device_name: String,
-    device_type: String,
-    platform: String,
-) -> Result<DeviceRegistrationResponse, String> {
-    let (anon_key, _) = get_supabase_credentials()?;
-    let supabase_url = get_supabase_url()?;
+/// A device as registered in user_instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceRegistration {
+    pub user_id: String,
+    pub device_id: String,
+    pub device_name: String,
+    pub device_type: String,
+    pub platform: String,
+}
+
+/// Upsert the device into user_instances and remember it for heartbeats
+pub(crate) async fn post_device_registration(registration: &DeviceRegistration) -> Result<(), CallError> {
+    let (anon_key, _) = get_supabase_credentials().map_err(CallError::Rejected)?;
+    let supabase_url = get_supabase_url().map_err(CallError::Rejected)?;
 
     let client = reqwest::Client::new();
 
@@ -619,44 +666,36 @@ pub async fn register_device(
         .header("Content-Type", "application/json")
         .header("Prefer", "resolution=merge-duplicates")
         .json(&serde_json::json!({
-            "user_id": user_id,
-            "instance_id": device_id,
-            "device_id": device_id,
-            "device_name": device_name,
-            "device_type": device_type,
-            "platform": platform,
+            "user_id": registration.user_id,
+            "instance_id": registration.device_id,
+            "device_id": registration.device_id,
+            "device_name": registration.device_name,
+            "device_type": registration.device_type,
+            "platform": registration.platform,
             "last_heartbeat": Utc::now().to_rfc3339(),
             "is_online": true
         }))
         .send()
         .await
-        .map_err(|e| format!("Failed to register device: {}", e))?;
+        .map_err(|e| CallError::Transient(format!("Failed to register device: {}", e)))?;
 
     if response.status().is_success() {
-        Ok(DeviceRegistrationResponse {
-            success: true,
-            error: None,
-        })
+        crate::remote::heartbeat::remember_device(&registration.device_id);
+        Ok(())
     } else {
+        let status = response.status();
         let error_text = response
             .text()
             .await
             .unwrap_or_else(|_| "Failed to register device".to_string());
-        Ok(DeviceRegistrationResponse {
-            success: false,
-            error: Some(error_text),
-        })
+        Err(CallError::from_status(status, error_text))
     }
 }
 
-/// Send heartbeat to keep device online status fresh
-///
-/// Call every 60 seconds to keep is_online=true and last_heartbeat updated.
-/// This is called periodically by the frontend and doesn't require user context.
-#[tauri::command]
-pub async fn send_heartbeat(device_id: String) -> Result<HeartbeatResponse, String> {
-    let (anon_key, _) = get_supabase_credentials()?;
-    let supabase_url = get_supabase_url()?;
+/// Mark the device online with a fresh last_heartbeat
+pub(crate) async fn patch_heartbeat(device_id: &str) -> Result<(), CallError> {
+    let (anon_key, _) = get_supabase_credentials().map_err(CallError::Rejected)?;
+    let supabase_url = get_supabase_url().map_err(CallError::Rejected)?;
 
     let client = reqwest::Client::new();
 
@@ -674,22 +713,191 @@ pub async fn send_heartbeat(device_id: String) -> Result<HeartbeatResponse, Stri
         }))
         .send()
         .await
-        .map_err(|e| format!("Failed to send heartbeat: {}", e))?;
+        .map_err(|e| CallError::Transient(format!("Failed to send heartbeat: {}", e)))?;
 
     if response.status().is_success() {
-        Ok(HeartbeatResponse {
-            success: true,
-            error: None,
-        })
+        Ok(())
     } else {
+        let status = response.status();
         let error_text = response
             .text()
             .await
             .unwrap_or_else(|_| "Failed to send heartbeat".to_string());
-        Ok(HeartbeatResponse {
+        Err(CallError::from_status(status, error_text))
+    }
+}
+
+/// Mark the device retired and offline in user_instances, as the signed-in
+/// user so row-level security applies. Matching no row is a failure: the
+/// device isn't the user's, or is already gone.
+pub(crate) async fn patch_retirement(device_id: &str) -> Result<(), CallError> {
+    let supabase_url = get_supabase_url().map_err(CallError::Rejected)?;
+
+    let request = reqwest::Client::new()
+        .patch(format!(
+            "{}/rest/v1/user_instances?instance_id=eq.{}",
+            supabase_url, device_id
+        ))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&serde_json::json!({
+            "retired_at": Utc::now().to_rfc3339(),
+            "is_online": false
+        }));
+    // Signing in again brings the session back, so keep the call queued
+    let (request, _) = crate::accounts::session::authorize(request)
+        .await
+        .map_err(CallError::Transient)?;
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CallError::Transient(format!("Failed to retire device: {}", e)))?;
+
+    if response.status().is_success() {
+        let rows: Vec<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| CallError::Transient(format!("Invalid retirement response: {}", e)))?;
+        if rows.is_empty() {
+            return Err(CallError::Rejected(format!("No device {} to retire for this account", device_id)));
+        }
+        Ok(())
+    } else {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to retire device".to_string());
+        Err(CallError::from_status(status, error_text))
+    }
+}
+
+/// The error a queued call is reported with
+fn queued_error(error: CommandError) -> CommandError {
+    error.with_details(serde_json::json!({ "queued": true }))
+}
+
+/// Register this device with Supabase
+///
+/// Inserts into user_instances table so web dashboard knows about this device.
+/// Handles conflicts by updating if instance_id already exists.
+/// Sends both instance_id and device_id (same value) for backwards compatibility.
+/// Offline or during a Supabase outage the registration is queued and
+/// retried (remote/cloud_queue.rs); the error then says `queued`.
+#[tauri::command]
+pub async fn register_device(
+    user_id: String,
+    device_id: String,
+    device_name: String,
+    device_type: String,
+    platform: String,
+) -> Result<DeviceRegistrationResponse, CommandError> {
+    get_supabase_credentials()?;
+    let registration = DeviceRegistration {
+        user_id,
+        device_id,
+        device_name,
+        device_type,
+        platform,
+    };
+    let call = CloudCall::RegisterDevice(registration.clone());
+
+    if let Err(e) = crate::remote::connectivity::require_online("Device registration") {
+        cloud_queue::enqueue(call, &e.message);
+        return Err(queued_error(e));
+    }
+
+    match post_device_registration(&registration).await {
+        Ok(()) => {
+            cloud_queue::settled(&call);
+            Ok(DeviceRegistrationResponse {
+                success: true,
+                error: None,
+            })
+        }
+        Err(CallError::Rejected(error)) => Ok(DeviceRegistrationResponse {
             success: false,
-            error: Some(error_text),
-        })
+            error: Some(error),
+        }),
+        Err(CallError::Transient(error)) => {
+            cloud_queue::enqueue(call, &error);
+            Err(queued_error(CommandError::network(error)))
+        }
+    }
+}
+
+/// Send heartbeat to keep device online status fresh
+///
+/// Keeps is_online=true and last_heartbeat updated. remote/heartbeat.rs
+/// calls this on the `discord.heartbeat_interval` cadence for the device
+/// registered last; it doesn't require user context. A heartbeat that
+/// can't get through is queued like a registration.
+#[tauri::command]
+pub async fn send_heartbeat(device_id: String) -> Result<HeartbeatResponse, CommandError> {
+    crate::privacy::residency::require(crate::privacy::residency::DataFeature::Sync)?;
+    get_supabase_credentials()?;
+    let call = CloudCall::Heartbeat {
+        device_id: device_id.clone(),
+    };
+
+    if let Err(e) = crate::remote::connectivity::require_online("Device heartbeat") {
+        cloud_queue::enqueue(call, &e.message);
+        return Err(queued_error(e));
+    }
+
+    match patch_heartbeat(&device_id).await {
+        Ok(()) => {
+            cloud_queue::settled(&call);
+            Ok(HeartbeatResponse {
+                success: true,
+                error: None,
+            })
+        }
+        Err(CallError::Rejected(error)) => Ok(HeartbeatResponse {
+            success: false,
+            error: Some(error),
+        }),
+        Err(CallError::Transient(error)) => {
+            cloud_queue::enqueue(call, &error);
+            Err(queued_error(CommandError::network(error)))
+        }
+    }
+}
+
+/// Retire this device
+///
+/// Stops heartbeats for the registered device at once and marks its
+/// user_instances row retired and offline, so the web dashboard drops it
+/// from the device list. Offline or during an outage the retirement is
+/// queued like a registration. Register again to bring the device back.
+#[tauri::command]
+pub async fn deregister_instance() -> Result<DeviceRegistrationResponse, CommandError> {
+    let device_id = crate::remote::heartbeat::device_id()
+        .ok_or_else(|| CommandError::not_found("This device is not registered"))?;
+    crate::remote::heartbeat::forget_device();
+    cloud_queue::discard_device(&device_id);
+    let call = CloudCall::RetireDevice {
+        device_id: device_id.clone(),
+    };
+
+    if let Err(e) = crate::remote::connectivity::require_online("Device deregistration") {
+        cloud_queue::enqueue(call, &e.message);
+        return Err(queued_error(e));
+    }
+
+    match patch_retirement(&device_id).await {
+        Ok(()) => Ok(DeviceRegistrationResponse {
+            success: true,
+            error: None,
+        }),
+        Err(CallError::Rejected(error)) => Ok(DeviceRegistrationResponse {
+            success: false,
+            error: Some(error),
+        }),
+        Err(CallError::Transient(error)) => {
+            cloud_queue::enqueue(call, &error);
+            Err(queued_error(CommandError::network(error)))
+        }
     }
 }
 
@@ -697,8 +905,8 @@ pub async fn send_heartbeat(device_id: String) -> Result<HeartbeatResponse, Stri
 ///
 /// Returns machine hostname (e.g., "MacBook-Pro", "DESKTOP-ABC123")
 #[tauri::command]
-pub fn get_hostname() -> Result<String, String> {
+pub fn get_hostname() -> Result<String, CommandError> {
     hostname::get()
-        .map_err(|e| format!("Failed to get hostname: {}", e))
+        .map_err(|e| CommandError::internal(format!("Failed to get hostname: {}", e)))
         .map(|h| h.into_string().unwrap_or_else(|_| "Desktop".to_string()))
 }
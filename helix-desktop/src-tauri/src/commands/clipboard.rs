@@ -1,8 +1,10 @@
 /// Clipboard Command Module
 /// Provides cross-platform clipboard operations
 
+use crate::commands::CommandError;
+
 #[tauri::command]
-pub async fn copy_to_clipboard(text: String) -> Result<(), String> {
+pub async fn copy_to_clipboard(text: String) -> Result<(), CommandError> {
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
@@ -52,12 +54,12 @@ pub async fn copy_to_clipboard(text: String) -> Result<(), String> {
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        Err("Clipboard not supported on this platform".to_string())
+        Err(CommandError::new(crate::commands::ErrorCode::Unsupported, "Clipboard not supported on this platform"))
     }
 }
 
 #[tauri::command]
-pub async fn paste_from_clipboard() -> Result<String, String> {
+pub async fn paste_from_clipboard() -> Result<String, CommandError> {
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
@@ -68,7 +70,7 @@ pub async fn paste_from_clipboard() -> Result<String, String> {
 
         String::from_utf8(output.stdout)
             .map(|s| s.trim().to_string())
-            .map_err(|e| format!("Failed to decode clipboard: {}", e))
+            .map_err(|e| CommandError::internal(format!("Failed to decode clipboard: {}", e)))
     }
 
     #[cfg(target_os = "macos")]
@@ -80,7 +82,7 @@ pub async fn paste_from_clipboard() -> Result<String, String> {
 
         String::from_utf8(output.stdout)
             .map(|s| s.trim().to_string())
-            .map_err(|e| format!("Failed to decode clipboard: {}", e))
+            .map_err(|e| CommandError::internal(format!("Failed to decode clipboard: {}", e)))
     }
 
     #[cfg(target_os = "linux")]
@@ -95,11 +97,11 @@ pub async fn paste_from_clipboard() -> Result<String, String> {
 
         String::from_utf8(output.stdout)
             .map(|s| s.trim().to_string())
-            .map_err(|e| format!("Failed to decode clipboard: {}", e))
+            .map_err(|e| CommandError::internal(format!("Failed to decode clipboard: {}", e)))
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        Err("Clipboard not supported on this platform".to_string())
+        Err(CommandError::new(crate::commands::ErrorCode::Unsupported, "Clipboard not supported on this platform"))
     }
 }
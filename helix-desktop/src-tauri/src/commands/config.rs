@@ -1,11 +1,14 @@
 // Configuration management commands
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::AppHandle;
+use crate::commands::{CommandError, ErrorCode};
+use crate::config::patch::{self, ConfigChange, PatchErrorKind, PatchOp};
 
 static CONFIG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
@@ -23,6 +26,38 @@ pub struct HelixConfig {
     pub hash_chain: HashChainConfig,
     #[serde(default)]
     pub branding: BrandingConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    #[serde(default)]
+    pub email: EmailConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub scripts: ScriptsConfig,
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    #[serde(default)]
+    pub guardrails: GuardrailsConfig,
+    #[serde(default)]
+    pub goals: GoalsConfig,
+    #[serde(default)]
+    pub gateway: GatewayConfig,
+    #[serde(default)]
+    pub updates: UpdatesConfig,
+    #[serde(default)]
+    pub data_residency: DataResidencyConfig,
+    #[serde(default)]
+    pub skill_approvals: SkillApprovalsConfig,
+    #[serde(default)]
+    pub response_cache: ResponseCacheConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub integrations: IntegrationsConfig,
+    #[serde(default)]
+    pub isolation: IsolationConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +66,8 @@ pub struct DiscordConfig {
     pub enabled: bool,
     #[serde(default)]
     pub webhooks: DiscordWebhooks,
+    /// How often (ms) the device heartbeat is published (see
+    /// remote/heartbeat.rs)
     #[serde(default = "default_heartbeat_interval")]
     pub heartbeat_interval: u64,
 }
@@ -113,8 +150,558 @@ impl Default for BrandingConfig {
     }
 }
 
+/// Anonymous usage analytics; off unless the user opts in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Collector URL; nothing is sent until one is set
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default = "default_telemetry_flush_interval")]
+    pub flush_interval_secs: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            flush_interval_secs: default_telemetry_flush_interval(),
+        }
+    }
+}
+
+/// Read-only calendar sources for the prospective layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    #[serde(default)]
+    pub sources: Vec<CalendarSource>,
+    #[serde(default = "default_calendar_lookahead")]
+    pub lookahead_days: u32,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            lookahead_days: default_calendar_lookahead(),
+        }
+    }
+}
+
+/// An ICS feed or CalDAV calendar. CalDAV passwords live in the keyring
+/// under `calendar_<name>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarSource {
+    pub name: String,
+    /// "ics" or "caldav"
+    #[serde(default = "default_calendar_kind")]
+    pub kind: String,
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+/// IMAP ingestion of a dedicated folder into memories.
+/// The password lives in the keyring under `email_imap_password`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    /// Folder or label to ingest from
+    #[serde(default = "default_email_folder")]
+    pub folder: String,
+    #[serde(default = "default_email_poll_interval")]
+    pub poll_interval_secs: u64,
+    /// Extra redaction rules, applied on top of the built-in ones
+    #[serde(default)]
+    pub redaction_rules: Vec<RedactionRule>,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: default_imap_port(),
+            username: String::new(),
+            folder: default_email_folder(),
+            poll_interval_secs: default_email_poll_interval(),
+            redaction_rules: Vec::new(),
+        }
+    }
+}
+
+/// A named regex whose matches are replaced before text leaves the machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// Token-protected localhost REST API (see api/server.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Start the API at launch
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+        }
+    }
+}
+
+/// Python script runner (see scripts/runner.rs)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ScriptsConfig {
+    /// Interpreter to use instead of the bundled or system Python
+    #[serde(default)]
+    pub python_path: Option<String>,
+    /// Extra environment for every script, e.g. HELIX_DECAY_RATE; the
+    /// built-in decay reads its settings from here too
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Run decay and synthesis with decay.py and synthesis.py instead of
+    /// the built-in implementations (psychology/decay.rs, synthesis.rs)
+    #[serde(default)]
+    pub python_psychology: bool,
+}
+
+/// Do-not-disturb schedule (see policy/dnd.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Local time, HH:MM
+    #[serde(default = "default_quiet_start")]
+    pub start: String,
+    /// Local time, HH:MM; before `start` means the next morning
+    #[serde(default = "default_quiet_end")]
+    pub end: String,
+    /// Days the window starts on, 0 = Sunday; empty means every day
+    #[serde(default)]
+    pub days: Vec<u8>,
+    #[serde(default = "default_true")]
+    pub suppress_notifications: bool,
+    /// Hold back scheduler jobs that surface results to the user
+    #[serde(default = "default_true")]
+    pub defer_jobs: bool,
+    /// Discord heartbeat interval (ms) while do-not-disturb is active
+    #[serde(default = "default_quiet_heartbeat_interval")]
+    pub heartbeat_interval: u64,
+    /// Also treat the OS focus / do-not-disturb mode as quiet time
+    #[serde(default = "default_true")]
+    pub detect_os_focus: bool,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_start(),
+            end: default_quiet_end(),
+            days: Vec::new(),
+            suppress_notifications: true,
+            defer_jobs: true,
+            heartbeat_interval: default_quiet_heartbeat_interval(),
+            detect_os_focus: true,
+        }
+    }
+}
+
+/// Soul guardrails for automated psychology writes (see policy/guardrails.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Soul headings whose sections automated writes may not change
+    #[serde(default = "default_protected_sections")]
+    pub protected_sections: Vec<String>,
+    /// "<file>#<JSON pointer>" values in layer files, e.g.
+    /// "psychology/trust_map.json#/trust_thresholds"
+    #[serde(default = "default_protected_paths")]
+    pub protected_paths: Vec<String>,
+    /// "queue" (hold for manual approval) or "block"
+    #[serde(default = "default_on_violation")]
+    pub on_violation: String,
+}
+
+impl Default for GuardrailsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            protected_sections: default_protected_sections(),
+            protected_paths: default_protected_paths(),
+            on_violation: default_on_violation(),
+        }
+    }
+}
+
+/// Goal check-in reminders (see psychology/goals.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalsConfig {
+    #[serde(default = "default_true")]
+    pub reminders: bool,
+    /// Remind about a goal after this many days without a check-in;
+    /// a goal's own `reminder_days` overrides it
+    #[serde(default = "default_goal_reminder_days")]
+    pub reminder_after_days: u32,
+}
+
+impl Default for GoalsConfig {
+    fn default() -> Self {
+        Self {
+            reminders: true,
+            reminder_after_days: default_goal_reminder_days(),
+        }
+    }
+}
+
+/// Gateway instances and their supervision (see commands/gateway.rs and
+/// gateway/supervisor.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayConfig {
+    /// When to relaunch a gateway that exits on its own: "never",
+    /// "on_failure" (non-zero exit or killed by a signal) or "always"
+    #[serde(default = "default_restart_policy")]
+    pub restart_policy: String,
+    /// Relaunches allowed within `restart_window_secs` before giving up
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    #[serde(default = "default_restart_window")]
+    pub restart_window_secs: u64,
+    /// How long start_gateway waits for a new process to answer /health
+    #[serde(default = "default_gateway_startup_timeout")]
+    pub startup_timeout_secs: u64,
+    /// How spawned gateways get their token: "stdin", "file" (a file only
+    /// the user can read, removed once the gateway is up) or "argv", which
+    /// shows it in process listings
+    #[serde(default = "default_token_handoff")]
+    pub token_handoff: String,
+    /// Local "HH:MM" to restart the default gateway each night; off when
+    /// unset (see gateway/nightly_restart.rs)
+    #[serde(default)]
+    pub nightly_restart_at: Option<String>,
+    /// How long after `nightly_restart_at` the restart may still happen,
+    /// waiting for active sessions to finish
+    #[serde(default = "default_nightly_restart_window")]
+    pub nightly_restart_window_mins: u64,
+    /// helix-runtime directory to start the gateway from, instead of
+    /// detecting it (see gateway/runtime.rs)
+    #[serde(default)]
+    pub runtime_path: Option<String>,
+    /// Install helix-runtime at startup when none is found, and keep the
+    /// one Helix installed at the pinned version (see gateway/installer.rs)
+    #[serde(default = "default_true")]
+    pub runtime_auto_update: bool,
+    /// wss:// URL of a gateway running on another host; the default
+    /// instance connects to it instead of spawning one
+    #[serde(default)]
+    pub remote_url: Option<String>,
+    /// Port pinned for the default gateway with set_gateway_port
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Port the default gateway last started on; preferred over the
+    /// default port so saved frontend URLs keep working
+    #[serde(default)]
+    pub last_port: Option<u16>,
+    /// Environment variables for the default gateway process (API keys,
+    /// proxy settings); a `keyring:<name>` value is read from the keyring
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Working directories and variables of the default gateway's agents,
+    /// by agent id
+    #[serde(default)]
+    pub agents: BTreeMap<String, GatewayAgentConfig>,
+    /// Named gateways run alongside the default one, e.g. one per agent profile
+    #[serde(default)]
+    pub instances: BTreeMap<String, GatewayInstanceConfig>,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            restart_policy: default_restart_policy(),
+            max_restarts: default_max_restarts(),
+            restart_window_secs: default_restart_window(),
+            startup_timeout_secs: default_gateway_startup_timeout(),
+            token_handoff: default_token_handoff(),
+            nightly_restart_at: None,
+            nightly_restart_window_mins: default_nightly_restart_window(),
+            runtime_path: None,
+            runtime_auto_update: true,
+            remote_url: None,
+            port: None,
+            last_port: None,
+            env: BTreeMap::new(),
+            agents: BTreeMap::new(),
+            instances: BTreeMap::new(),
+        }
+    }
+}
+
+/// A named gateway instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GatewayInstanceConfig {
+    /// Fixed port; any free port when unset
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Gateway state directory (config, agents, sessions); ~/.openclaw-<name>
+    /// when unset, as with `openclaw --profile <name>`
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Start with the app, after the default gateway
+    #[serde(default)]
+    pub auto_start: bool,
+    /// Environment variables for this gateway, as `gateway.env`
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Its agents' settings, as `gateway.agents`
+    #[serde(default)]
+    pub agents: BTreeMap<String, GatewayAgentConfig>,
+}
+
+/// What a gateway is given for one of its agents when it is spawned
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GatewayAgentConfig {
+    /// The agent's workspace, overriding the gateway config's; passed as
+    /// HELIX_AGENT_<ID>_WORKSPACE and created if missing
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Passed as HELIX_AGENT_<ID>_<NAME>, for the gateway config to use as
+    /// `${HELIX_AGENT_<ID>_<NAME>}`; a `keyring:<name>` value is read from
+    /// the keyring at spawn time
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// App update downloads (see updater/download.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatesConfig {
+    /// Download speed cap in KiB/s; unlimited when unset. Read when a
+    /// download starts or resumes.
+    #[serde(default)]
+    pub bandwidth_limit_kbps: Option<u32>,
+}
+
+/// Where each feature may process data (see privacy/residency.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataResidencyConfig {
+    /// "cloud" (the voice pipeline sends audio to Deepgram) or "local"
+    #[serde(default = "default_processing")]
+    pub voice_transcription: String,
+    /// "cloud" (memories are embedded with Gemini for the psychology
+    /// analyses) or "local"
+    #[serde(default = "default_processing")]
+    pub summarization: String,
+    /// Supabase sync: the Realtime feed, session handoff, device heartbeat
+    /// and the sync coordinator
+    #[serde(default = "default_true")]
+    pub sync: bool,
+}
+
+impl Default for DataResidencyConfig {
+    fn default() -> Self {
+        Self {
+            voice_transcription: default_processing(),
+            summarization: default_processing(),
+            sync: true,
+        }
+    }
+}
+
+/// Skill runs requested by agents (see policy/skill_approvals.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillApprovalsConfig {
+    /// Runs that skip the approval queue
+    #[serde(default)]
+    pub auto_approve: Vec<AutoApproveRule>,
+}
+
+/// Run `skill_id` without asking, for any agent or only `agent`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoApproveRule {
+    pub skill_id: String,
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+/// Cached results of deterministic external calls (see cache/responses.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseCacheConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Least recently used entries are evicted past this size
+    #[serde(default = "default_response_cache_size")]
+    pub max_size_mb: u64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: default_response_cache_size(),
+        }
+    }
+}
+
+/// Backend UI surfaces: tray, notifications (see locale/strings.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiConfig {
+    /// Locale of backend strings, e.g. "es"; the OS locale when unset
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+/// Home automation and other outside systems (see integrations/)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrationsConfig {
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+}
+
+/// Confinement of spawned processes, one section per kind (see
+/// policy/isolation.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IsolationConfig {
+    #[serde(default)]
+    pub gateway: ProcessLimits,
+    /// Psychology scripts
+    #[serde(default)]
+    pub scripts: ProcessLimits,
+    /// helix-rust binaries run by scheduler jobs
+    #[serde(default)]
+    pub maintenance: ProcessLimits,
+    /// Hook commands
+    #[serde(default)]
+    pub hooks: ProcessLimits,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessLimits {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Memory ceiling (Linux, Windows)
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+    /// CPU ceiling, 100 being one core (Linux, Windows)
+    #[serde(default)]
+    pub cpu_percent: Option<u32>,
+    /// Writable besides the data and temp directories; all other writes
+    /// are denied (macOS)
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+}
+
+/// Status publishing to an MQTT broker, with Home Assistant discovery.
+/// The broker password lives in the keyring under `mqtt_password`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_mqtt_host")]
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    /// Off only for a broker without TLS; the password is then sent to
+    /// loopback brokers only
+    #[serde(default = "default_true")]
+    pub tls: bool,
+    /// PEM CA file for a broker with a private certificate
+    #[serde(default)]
+    pub ca_file: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Topics are `<topic_prefix>/<node_id>/...`
+    #[serde(default = "default_mqtt_topic_prefix")]
+    pub topic_prefix: String,
+    /// Tells several Helix installs on one broker apart
+    #[serde(default = "default_mqtt_node_id")]
+    pub node_id: String,
+    /// Publish Home Assistant discovery configs
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+    /// State is also published on every change
+    #[serde(default = "default_mqtt_publish_interval")]
+    pub publish_interval_secs: u64,
+    /// Accept actions on the command topic, from anyone who can publish
+    /// on the broker
+    #[serde(default)]
+    pub commands: bool,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_mqtt_host(),
+            port: default_mqtt_port(),
+            tls: true,
+            ca_file: None,
+            username: None,
+            topic_prefix: default_mqtt_topic_prefix(),
+            node_id: default_mqtt_node_id(),
+            discovery: true,
+            discovery_prefix: default_mqtt_discovery_prefix(),
+            publish_interval_secs: default_mqtt_publish_interval(),
+            commands: false,
+        }
+    }
+}
+
 fn default_true() -> bool { true }
+fn default_telemetry_flush_interval() -> u64 { 3600 }
+fn default_calendar_lookahead() -> u32 { 14 }
+fn default_calendar_kind() -> String { "ics".to_string() }
+fn default_imap_port() -> u16 { 993 }
+fn default_email_folder() -> String { "Helix".to_string() }
+fn default_email_poll_interval() -> u64 { 300 }
+fn default_api_port() -> u16 { 18793 }
 fn default_heartbeat_interval() -> u64 { 60000 }
+fn default_quiet_start() -> String { "22:00".to_string() }
+fn default_quiet_end() -> String { "07:00".to_string() }
+fn default_quiet_heartbeat_interval() -> u64 { 900000 }
+fn default_goal_reminder_days() -> u32 { 7 }
+fn default_on_violation() -> String { "queue".to_string() }
+fn default_restart_policy() -> String { "on_failure".to_string() }
+fn default_max_restarts() -> u32 { 5 }
+fn default_processing() -> String { "cloud".to_string() }
+fn default_restart_window() -> u64 { 600 }
+fn default_gateway_startup_timeout() -> u64 { 30 }
+fn default_token_handoff() -> String { "stdin".to_string() }
+fn default_nightly_restart_window() -> u64 { 60 }
+fn default_response_cache_size() -> u64 { 256 }
+fn default_mqtt_host() -> String { "127.0.0.1".to_string() }
+fn default_mqtt_port() -> u16 { 8883 }
+fn default_mqtt_topic_prefix() -> String { "helix".to_string() }
+fn default_mqtt_node_id() -> String { "desktop".to_string() }
+fn default_mqtt_discovery_prefix() -> String { "homeassistant".to_string() }
+fn default_mqtt_publish_interval() -> u64 { 60 }
+fn default_protected_sections() -> Vec<String> {
+    vec!["CORE VALUES", "WHAT I DON'T DO", "THE TRANSPARENCY AGREEMENT", "Trust Configuration"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+fn default_protected_paths() -> Vec<String> {
+    vec!["psychology/trust_map.json#/trust_thresholds".to_string()]
+}
 fn default_layers() -> Vec<String> {
     vec!["soul", "emotional", "relational", "prospective", "purpose"]
         .into_iter()
@@ -142,7 +729,7 @@ pub fn init(_app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
 }
 
 #[tauri::command]
-pub fn get_config() -> Result<HelixConfig, String> {
+pub fn get_config() -> Result<HelixConfig, CommandError> {
     let path = CONFIG_PATH.lock().map_err(|e| e.to_string())?;
     let config_path = path.as_ref().ok_or("Config not initialized")?;
 
@@ -156,7 +743,7 @@ pub fn get_config() -> Result<HelixConfig, String> {
 }
 
 #[tauri::command]
-pub fn set_config(config: HelixConfig) -> Result<(), String> {
+pub fn set_config(config: HelixConfig) -> Result<(), CommandError> {
     let path = CONFIG_PATH.lock().map_err(|e| e.to_string())?;
     let config_path = path.as_ref().ok_or("Config not initialized")?;
 
@@ -169,8 +756,84 @@ pub fn set_config(config: HelixConfig) -> Result<(), String> {
     Ok(())
 }
 
+/// Result of patch_config
+#[derive(Debug, Serialize)]
+pub struct ConfigPatchResult {
+    pub config: HelixConfig,
+    pub changes: Vec<ConfigChange>,
+}
+
+/// Apply JSON Patch operations (RFC 6902) to the config. The read, patch,
+/// validation and write happen under one lock and either all apply or
+/// none do, so settings pages editing different sections don't clobber
+/// each other the way whole-object set_config calls do.
+#[tauri::command]
+pub fn patch_config(app: AppHandle, ops: Vec<PatchOp>) -> Result<ConfigPatchResult, CommandError> {
+    let path = CONFIG_PATH.lock().map_err(|e| e.to_string())?;
+    let config_path = path.as_ref().ok_or("Config not initialized")?;
+
+    let content = fs::read_to_string(config_path)
+        .map_err(|e| CommandError::from_io("Failed to read config", e))?;
+    let current: HelixConfig = serde_json::from_str(&content)
+        .map_err(|e| CommandError::new(ErrorCode::Config, format!("Failed to parse config: {}", e)))?;
+
+    // Patch the config as get_config returns it, defaults filled in
+    let before = serde_json::to_value(&current)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let mut after = before.clone();
+
+    patch::apply(&mut after, &ops).map_err(|e| {
+        let code = match e.kind {
+            PatchErrorKind::Invalid => ErrorCode::InvalidInput,
+            PatchErrorKind::NotFound => ErrorCode::NotFound,
+            PatchErrorKind::TestFailed => ErrorCode::Conflict,
+        };
+        CommandError::new(code, format!("Patch operation {} failed: {}", e.index, e.message))
+            .with_details(serde_json::json!({ "op_index": e.index, "path": e.path }))
+    })?;
+
+    let config: HelixConfig = serde_json::from_value(after.clone()).map_err(|e| {
+        CommandError::invalid_input(format!("Patched config is invalid: {}", e))
+    })?;
+
+    // Keys HelixConfig doesn't know would be dropped silently on write
+    let normalized = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let rejected: Vec<String> = patch::diff(&normalized, &after)
+        .into_iter()
+        .map(|change| change.path)
+        .collect();
+    if !rejected.is_empty() {
+        return Err(CommandError::invalid_input(format!(
+            "Patch sets unknown config keys: {}",
+            rejected.join(", ")
+        ))
+        .with_details(serde_json::json!({ "paths": rejected })));
+    }
+
+    let changes = patch::diff(&before, &normalized);
+    if changes.is_empty() {
+        return Ok(ConfigPatchResult { config, changes });
+    }
+
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let tmp_path = config_path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .map_err(|e| CommandError::from_io("Failed to write config", e))?;
+    fs::rename(&tmp_path, config_path)
+        .map_err(|e| CommandError::from_io("Failed to replace config", e))?;
+
+    // Announce the diff here; the file watcher sees no change against the
+    // snapshot and stays quiet
+    crate::config::watcher::record_snapshot(Some(normalized));
+    crate::config::watcher::emit_config_changed(&app, config_path, "patch", Some(changes.clone()));
+
+    Ok(ConfigPatchResult { config, changes })
+}
+
 #[tauri::command]
-pub fn get_config_path() -> Result<String, String> {
+pub fn get_config_path() -> Result<String, CommandError> {
     let path = CONFIG_PATH.lock().map_err(|e| e.to_string())?;
     let config_path = path.as_ref().ok_or("Config not initialized")?;
 
@@ -178,10 +841,7 @@ pub fn get_config_path() -> Result<String, String> {
 }
 
 fn get_helix_directory() -> Result<PathBuf, String> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
-
-    let helix_dir = home.join(".helix");
+    let helix_dir = crate::accounts::data_dir()?;
     fs::create_dir_all(&helix_dir)
         .map_err(|e| format!("Could not create .helix directory: {}", e))?;
 
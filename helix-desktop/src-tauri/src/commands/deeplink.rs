@@ -8,6 +8,7 @@
 
 use tauri::{AppHandle, Emitter};
 use serde::Serialize;
+use crate::commands::CommandError;
 
 /// Supported deep link action types derived from the URL path.
 #[derive(Debug, Clone, Serialize)]
@@ -29,7 +30,7 @@ pub struct DeepLinkInfo {
 ///
 /// Returns a [`DeepLinkInfo`] indicating whether the URL was accepted.
 #[tauri::command]
-pub async fn handle_deep_link(url: String, app: AppHandle) -> Result<DeepLinkInfo, String> {
+pub async fn handle_deep_link(url: String, app: AppHandle) -> Result<DeepLinkInfo, CommandError> {
     // Validate the URL starts with helix://
     if !url.starts_with("helix://") {
         return Ok(DeepLinkInfo {
@@ -82,7 +83,7 @@ pub async fn handle_deep_link(url: String, app: AppHandle) -> Result<DeepLinkInf
 /// wrapper that returns `None` when the plugin is not active or when the
 /// app was started without a deep link.
 #[tauri::command]
-pub async fn get_launch_deep_link() -> Result<Option<String>, String> {
+pub async fn get_launch_deep_link() -> Result<Option<String>, CommandError> {
     // Check environment for launch URL (set by OS when app is launched via deep link)
     // On Windows this comes from the command-line args, on macOS from the NSAppleEventManager.
     // Tauri's deep-link plugin populates this when configured.
@@ -1,6 +1,7 @@
 // Discord webhook logging commands
 
 use serde::{Deserialize, Serialize};
+use crate::commands::CommandError;
 
 #[derive(Serialize, Deserialize)]
 pub struct WebhookPayload {
@@ -32,7 +33,7 @@ pub struct WebhookTestResult {
 }
 
 #[tauri::command]
-pub async fn send_webhook(url: String, payload: WebhookPayload) -> Result<(), String> {
+pub async fn send_webhook(url: String, payload: WebhookPayload) -> Result<(), CommandError> {
     let client = reqwest::Client::new();
 
     let response = client
@@ -40,20 +41,20 @@ pub async fn send_webhook(url: String, payload: WebhookPayload) -> Result<(), St
         .json(&payload)
         .send()
         .await
-        .map_err(|e| format!("Failed to send webhook: {}", e))?;
+        .map_err(|e| CommandError::network(format!("Failed to send webhook: {}", e)))?;
 
     if response.status().is_success() {
         Ok(())
     } else {
-        Err(format!(
+        Err(CommandError::network(format!(
             "Webhook failed with status: {}",
             response.status()
-        ))
+        )))
     }
 }
 
 #[tauri::command]
-pub async fn test_webhook(url: String) -> Result<WebhookTestResult, String> {
+pub async fn test_webhook(url: String) -> Result<WebhookTestResult, CommandError> {
     let client = reqwest::Client::new();
 
     let test_payload = WebhookPayload {
@@ -0,0 +1,171 @@
+// Command errors - stable codes the frontend can branch on and translate
+//
+// Every Tauri command returns `Result<_, CommandError>`. Over IPC an error
+// serializes as `{ code, message, details }`: `code` is from the catalog
+// below and never changes meaning, `message` is an English fallback for
+// logs and untranslated UI, and `details` carries the values a translated
+// message needs (paths, names, limits).
+//
+// Internal helpers keep returning `Result<_, String>`; `?` turns those into
+// `internal` errors. Commands pick a specific code where the failure is one
+// the UI can act on.
+
+use helix_shared::AlreadyRunning;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// The error-code catalog. Add new codes at the end; never rename or reuse one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Unexpected failure with no more specific code
+    Internal,
+    /// An argument was missing, malformed or out of range
+    InvalidInput,
+    /// The requested file, layer, task, account or record does not exist
+    NotFound,
+    /// The thing being created already exists
+    AlreadyExists,
+    /// Not signed in, or the session has expired
+    Unauthenticated,
+    /// Signed in, but not allowed (tier, scope or OS permission)
+    PermissionDenied,
+    /// A confirmation phrase or token did not match
+    ConfirmationRequired,
+    /// Reading or writing a local file failed
+    Io,
+    /// A network request failed or returned an error status
+    Network,
+    /// A request or task ran past its time limit
+    Timeout,
+    /// The config file is unreadable or a value is invalid
+    Config,
+    /// The OS keyring could not be read or written
+    Keyring,
+    /// Psychology data is encrypted and the vault is locked
+    VaultLocked,
+    /// A required service (gateway, scheduler, sandbox) is not running
+    ServiceUnavailable,
+    /// The component is disabled by safe mode or by config
+    Disabled,
+    /// An external program (Python, a helix-rust binary) is missing or failed
+    ProcessFailed,
+    /// The operation conflicts with one already in progress
+    Conflict,
+    /// The feature is not available on this platform
+    Unsupported,
+    /// The feature needs a higher subscription tier; details name the tiers
+    TierRequired,
+    /// The feature needs the network and the app is offline
+    Offline,
+    /// Another writer holds a lock; safe to retry shortly
+    Busy,
+}
+
+/// Error returned by every Tauri command
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Attach values for a translated message, e.g. `json!({ "path": p })`
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    /// File errors; missing files and denied access keep their own codes
+    pub fn from_io(context: &str, e: std::io::Error) -> Self {
+        let code = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, format!("{}: {}", context, e))
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Network, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ServiceUnavailable, message)
+    }
+
+    pub fn unauthenticated(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unauthenticated, message)
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::PermissionDenied, message)
+    }
+
+    pub fn process(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ProcessFailed, message)
+    }
+
+    pub fn offline(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Offline, message)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+/// A gateway or helper whose pid file a live copy holds
+impl From<AlreadyRunning> for CommandError {
+    fn from(AlreadyRunning(holder): AlreadyRunning) -> Self {
+        Self::new(ErrorCode::Conflict, format!("{} is already running (pid {})", holder.name, holder.pid))
+            .with_details(serde_json::json!({ "name": holder.name, "pid": holder.pid, "port": holder.port }))
+    }
+}
+
+/// Commands are also called from Rust (local API, MCP, other commands)
+/// where errors are plain strings
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.message
+    }
+}
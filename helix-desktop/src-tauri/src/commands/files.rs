@@ -3,6 +3,7 @@
 use std::fs;
 use std::path::PathBuf;
 use serde::Serialize;
+use crate::commands::CommandError;
 
 #[derive(Serialize)]
 pub struct DirectoryEntry {
@@ -14,40 +15,40 @@ pub struct DirectoryEntry {
 }
 
 #[tauri::command]
-pub fn read_file(path: String) -> Result<String, String> {
+pub fn read_file(path: String) -> Result<String, CommandError> {
     // Validate path is within allowed directories
     validate_path(&path)?;
 
     fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+        .map_err(|e| CommandError::from_io("Failed to read file", e))
 }
 
 #[tauri::command]
-pub fn write_file(path: String, content: String) -> Result<(), String> {
+pub fn write_file(path: String, content: String) -> Result<(), CommandError> {
     // Validate path is within allowed directories
     validate_path(&path)?;
 
     // Ensure parent directory exists
     if let Some(parent) = PathBuf::from(&path).parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| CommandError::from_io("Failed to create directory", e))?;
     }
 
     fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+        .map_err(|e| CommandError::from_io("Failed to write file", e))
 }
 
 #[tauri::command]
-pub fn list_directory(path: String) -> Result<Vec<DirectoryEntry>, String> {
+pub fn list_directory(path: String) -> Result<Vec<DirectoryEntry>, CommandError> {
     validate_path(&path)?;
 
     let entries = fs::read_dir(&path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+        .map_err(|e| CommandError::from_io("Failed to read directory", e))?;
 
     let mut result = Vec::new();
 
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let entry = entry.map_err(|e| CommandError::from_io("Failed to read entry", e))?;
         let metadata = entry.metadata().ok();
 
         result.push(DirectoryEntry {
@@ -67,20 +68,20 @@ pub fn list_directory(path: String) -> Result<Vec<DirectoryEntry>, String> {
 }
 
 #[tauri::command]
-pub fn file_exists(path: String) -> Result<bool, String> {
+pub fn file_exists(path: String) -> Result<bool, CommandError> {
     validate_path(&path)?;
     Ok(PathBuf::from(&path).exists())
 }
 
 #[tauri::command]
-pub fn ensure_directory(path: String) -> Result<(), String> {
+pub fn ensure_directory(path: String) -> Result<(), CommandError> {
     validate_path(&path)?;
 
     fs::create_dir_all(&path)
-        .map_err(|e| format!("Failed to create directory: {}", e))
+        .map_err(|e| CommandError::from_io("Failed to create directory", e))
 }
 
-fn validate_path(path: &str) -> Result<(), String> {
+fn validate_path(path: &str) -> Result<(), CommandError> {
     let path_buf = PathBuf::from(path);
 
     // Get home directory
@@ -97,6 +98,13 @@ fn validate_path(path: &str) -> Result<(), String> {
         path_buf.parent().and_then(|p| p.canonicalize().ok())
     };
 
+    // Accounts can't reach into each other's data
+    if let Some(p) = &canonical_path {
+        if crate::accounts::profiles::is_other_account_path(p) {
+            return Err(CommandError::permission_denied("Access denied: path belongs to another account"));
+        }
+    }
+
     let canonical_helix = helix_dir.canonicalize().ok();
 
     // Allow access only to .helix directory
@@ -109,7 +117,7 @@ fn validate_path(path: &str) -> Result<(), String> {
             if path.contains(".helix") {
                 Ok(())
             } else {
-                Err("Access denied: path outside .helix directory".to_string())
+                Err(CommandError::permission_denied("Access denied: path outside .helix directory"))
             }
         }
     }
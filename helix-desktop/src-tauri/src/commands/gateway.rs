@@ -1,27 +1,98 @@
-// Gateway management commands - spawns helix-runtime gateway
-
+// Gateway management commands - spawns helix-runtime gateways
+//
+// Besides the default gateway, named instances (e.g. one per agent profile)
+// can run side by side. Each has its own port, token, state directory and
+// environment variables; commands take an optional instance name and act
+// on the default one without it. start_gateway only reports success once
+// the new process answers /health; one that exits or stays silent for
+// `gateway.startup_timeout_secs` is stopped and its stderr returned. It
+// then has to pass the RPC handshake (gateway::handshake): accept this
+// device's token and run a supported helix-runtime, or it is stopped too
+// and the error says whether to update helix-runtime or Helix. The
+// token goes over stdin or a private file (`gateway.token_handoff`), so it
+// stays out of process listings. At launch, a gateway that is attached to
+// instead (remote, adopted, or already listening on the port) passes the
+// same handshake before gateway:started; when the default gateway can't
+// be used, gateway:start_failed carries the error.
+//
+// Per-agent settings (`gateway.agents`, or an instance's `agents`) reach
+// the gateway through its environment: an agent's working directory as
+// HELIX_AGENT_<ID>_WORKSPACE, which helix-runtime uses as its workspace,
+// and its variables as HELIX_AGENT_<ID>_<NAME>, for the gateway config to
+// refer to. The gateway holds every agent's variables, but a process an
+// agent starts only gets that agent's; ids that would make them ambiguous
+// ("my-agent" and "my_agent") are refused. Like `env`, `keyring:<name>`
+// values are read from the keyring when the gateway is spawned; they are
+// only ever in the process's environment, and only variable names are
+// logged.
+//
+// With `gateway.remote_url` set, the default instance is a gateway running
+// on another host: start_gateway connects to that wss:// URL instead of
+// spawning a process, and the monitor checks its /health over HTTPS. The
+// remote gateway must accept this device's token (get_gateway_token).
+//
+// Each spawned gateway holds the pid file gateway-<instance>, so a second
+// copy isn't started while one runs. A gateway left running by a crashed
+// run is adopted at startup when it still serves its port
+// (startup::orphans): it has no Child to wait on, so it is tracked through
+// its pid file.
+
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
-use std::process::{Child, Command, Stdio};
+use std::path::PathBuf;
+use std::process::{Child, ExitStatus, Stdio};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 use serde::Serialize;
 use rand::Rng;
 use keyring::Entry;
+use helix_shared::pidfile::{self, AlreadyRunning, PidFile, PidRecord};
+use crate::commands::config::{self, GatewayAgentConfig, GatewayInstanceConfig};
+use crate::commands::{CommandError, ErrorCode};
+use crate::activity::events::{self, ServiceKind};
+use crate::automation::hooks::{self, HookEvent};
+use crate::gateway::handshake;
+use crate::policy::isolation::{self, ProcessKind};
+use crate::startup::orphans;
 
 /// Default OpenClaw gateway port
 const DEFAULT_GATEWAY_PORT: u16 = 18789;
+/// Instance used when a command names none. The UI connection, the health
+/// monitor and agent metrics follow this one.
+pub const DEFAULT_INSTANCE: &str = "default";
+const MAX_INSTANCE_NAME_LEN: usize = 32;
 /// Keyring service name (matches keyring.rs)
 const KEYRING_SERVICE: &str = "helix-desktop";
 /// Keyring key for the gateway token
 const GATEWAY_TOKEN_KEY: &str = "gateway-token";
 /// Fallback file name for token storage when keyring is unavailable
 const GATEWAY_TOKEN_FILENAME: &str = "gateway-token";
+/// Prefix of gateway env values that name a keyring secret
+const KEYRING_ENV_PREFIX: &str = "keyring:";
+/// Lists the agents with settings in the environment, for helix-runtime to
+/// tell their variables apart
+const AGENTS_ENV: &str = "HELIX_AGENTS";
+/// Pause between /health probes while a new gateway starts
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Time given to the log readers to drain a failed gateway's output
+const STDERR_DRAIN_DELAY: Duration = Duration::from_millis(200);
+/// Stderr lines returned with a startup failure
+const STARTUP_STDERR_LINES: usize = 40;
+/// Prefix of gateway pid files; the instance name follows
+const PIDFILE_PREFIX: &str = "gateway-";
 
 pub struct GatewayProcess {
     child: Option<Child>,
     port: u16,
     url: String,
+    /// Connected to the remote gateway rather than running a process
+    remote: bool,
+    /// Pid file of the spawned child, removed when it is dropped
+    pidfile: Option<PidFile>,
+    /// Pid file of a gateway left by a previous run and adopted at startup
+    adopted: Option<PidFile>,
 }
 
 impl GatewayProcess {
@@ -30,18 +101,258 @@ impl GatewayProcess {
             child: None,
             port: DEFAULT_GATEWAY_PORT,
             url: format!("ws://127.0.0.1:{}", DEFAULT_GATEWAY_PORT),
+            remote: false,
+            pidfile: None,
+            adopted: None,
         }
     }
 }
 
-static GATEWAY: Mutex<Option<GatewayProcess>> = Mutex::new(None);
+/// Gateway processes by instance name. The default instance is always
+/// present; named ones only while running.
+static GATEWAYS: Mutex<BTreeMap<String, GatewayProcess>> = Mutex::new(BTreeMap::new());
 
 pub fn init(_app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let mut gateway = GATEWAY.lock().map_err(|e| e.to_string())?;
-    *gateway = Some(GatewayProcess::new());
+    let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+    gateways.insert(DEFAULT_INSTANCE.to_string(), GatewayProcess::new());
+    Ok(())
+}
+
+/// The instance a command refers to, validated; the default one when unnamed
+fn instance_name(instance: Option<String>) -> Result<String, CommandError> {
+    let name = instance
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_INSTANCE.to_string());
+
+    let valid = name.len() <= MAX_INSTANCE_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(CommandError::invalid_input(format!(
+            "Invalid gateway instance name '{}': use up to {} letters, digits, '-' or '_'",
+            name, MAX_INSTANCE_NAME_LEN
+        )));
+    }
+    Ok(name)
+}
+
+/// "Gateway" or "Gateway 'name'", for logs and activity
+pub(crate) fn display_name(instance: &str) -> String {
+    if instance == DEFAULT_INSTANCE {
+        "Gateway".to_string()
+    } else {
+        format!("Gateway '{}'", instance)
+    }
+}
+
+/// Settings of a named instance from the `gateway.instances` config
+fn instance_config(instance: &str) -> GatewayInstanceConfig {
+    config::get_config()
+        .ok()
+        .and_then(|config| config.gateway.instances.get(instance).cloned())
+        .unwrap_or_default()
+}
+
+/// State directory of a named instance, created if needed. The default
+/// instance uses the gateway's own (see config_sync::gateway_state_dir).
+fn instance_state_dir(instance: &str, settings: &GatewayInstanceConfig) -> Result<PathBuf, String> {
+    let dir = match settings.working_dir.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()
+            .ok_or_else(|| "Could not determine home directory".to_string())?
+            .join(format!(".openclaw-{}", instance)),
+    };
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create gateway state directory: {}", e))?;
+    Ok(dir)
+}
+
+/// A gateway's configured environment with `keyring:<name>` values looked
+/// up. Fails rather than starting the gateway without a secret it expects.
+fn resolve_env(instance: &str, vars: &BTreeMap<String, String>) -> Result<Vec<(String, String)>, CommandError> {
+    let mut resolved = Vec::with_capacity(vars.len());
+    for (name, value) in vars {
+        if name.is_empty() || name.contains(['=', '\0']) {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid environment variable name '{}' for {}",
+                name,
+                display_name(instance)
+            )));
+        }
+
+        let value = match value.strip_prefix(KEYRING_ENV_PREFIX) {
+            Some(key) => crate::commands::keyring::get_secret(key.trim().to_string())?.ok_or_else(|| {
+                CommandError::not_found(format!(
+                    "Keyring secret '{}' for {} of {} is not set",
+                    key.trim(),
+                    name,
+                    display_name(instance)
+                ))
+                .with_details(serde_json::json!({ "instance": instance, "variable": name, "secret": key.trim() }))
+            })?,
+            None => value.clone(),
+        };
+        resolved.push((name.clone(), value));
+    }
+    Ok(resolved)
+}
+
+/// HELIX_AGENT_<ID>_ prefix of an agent's variables, for an id helix-runtime
+/// keeps as is (letters, digits, `_` and `-`, starting with a letter or digit)
+fn agent_env_prefix(instance: &str, agent: &str) -> Result<String, CommandError> {
+    let valid = agent.len() <= 64
+        && agent.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && agent.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(CommandError::invalid_input(format!(
+            "Invalid agent id '{}' in the agent settings of {}",
+            agent,
+            display_name(instance)
+        )));
+    }
+    Ok(format!("HELIX_AGENT_{}_", agent.to_ascii_uppercase().replace('-', "_")))
+}
+
+/// A gateway's agent settings, resolved for its spawn
+struct AgentEnv {
+    env: Vec<(String, String)>,
+    /// Working directories, which the gateway may write to
+    dirs: Vec<PathBuf>,
+}
+
+/// Environment carrying a gateway's agent settings; working directories
+/// are created if missing. helix-runtime hands each agent's processes only
+/// the variables of that agent, telling them apart by prefix, so ids whose
+/// variables could be mistaken for another agent's are refused.
+fn resolve_agent_env(instance: &str, agents: &BTreeMap<String, GatewayAgentConfig>) -> Result<AgentEnv, CommandError> {
+    let mut prefixes: Vec<(&str, String)> = Vec::new();
+    for agent in agents.keys() {
+        let prefix = agent_env_prefix(instance, agent)?;
+        if let Some((other, _)) = prefixes.iter().find(|(_, p)| *p == prefix) {
+            return Err(CommandError::invalid_input(format!(
+                "Agents '{}' and '{}' of {} both pass their settings as {}*; rename one of them",
+                other,
+                agent,
+                display_name(instance),
+                prefix
+            )));
+        }
+        prefixes.push((agent, prefix));
+    }
+
+    let mut env = Vec::new();
+    let mut dirs = Vec::new();
+    for (agent, settings) in agents {
+        let prefix = agent_env_prefix(instance, agent)?;
+        if let Some(dir) = settings.working_dir.as_deref().map(str::trim).filter(|dir| !dir.is_empty()) {
+            let dir = PathBuf::from(dir);
+            if !dir.is_absolute() {
+                return Err(CommandError::invalid_input(format!(
+                    "Working directory of agent '{}' must be an absolute path, got {}",
+                    agent,
+                    dir.display()
+                )));
+            }
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create the working directory of agent '{}': {}", agent, e))?;
+            env.push((format!("{}WORKSPACE", prefix), dir.to_string_lossy().to_string()));
+            dirs.push(dir);
+        }
+
+        // Names the gateway config can refer to as ${NAME}
+        if let Some(name) = settings.env.keys().find(|name| {
+            *name == "WORKSPACE"
+                || !name.starts_with(|c: char| c.is_ascii_uppercase() || c == '_')
+                || !name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }) {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid variable name '{}' for agent '{}': use A-Z, 0-9 and _, other than WORKSPACE",
+                name, agent
+            )));
+        }
+        for (name, value) in resolve_env(instance, &settings.env)? {
+            let key = format!("{}{}", prefix, name);
+            // HELIX_AGENT_A_B_X is agent "a_b"'s, not variable B_X of agent "a"
+            if let Some((other, _)) = prefixes
+                .iter()
+                .find(|(_, p)| p.len() > prefix.len() && key.starts_with(p.as_str()))
+            {
+                return Err(CommandError::invalid_input(format!(
+                    "Variable '{}' of agent '{}' would be read as agent '{}''s; rename it",
+                    name, agent, other
+                )));
+            }
+            env.push((key, value));
+        }
+    }
+    if !agents.is_empty() {
+        let ids: Vec<&str> = agents.keys().map(String::as_str).collect();
+        env.push((AGENTS_ENV.to_string(), ids.join(",")));
+    }
+    Ok(AgentEnv { env, dirs })
+}
+
+/// Forget a process that exited or was stopped
+fn forget(gateways: &mut BTreeMap<String, GatewayProcess>, instance: &str) -> Option<Child> {
+    if instance != DEFAULT_INSTANCE {
+        return gateways.remove(instance)?.child;
+    }
+    let gateway = gateways.get_mut(instance)?;
+    gateway.port = 0;
+    gateway.url = String::new();
+    gateway.remote = false;
+    gateway.pidfile = None;
+    gateway.adopted = None;
+    gateway.child.take()
+}
+
+/// Pid file name of a gateway instance
+fn pidfile_name(instance: &str) -> String {
+    format!("{}{}", PIDFILE_PREFIX, instance)
+}
+
+/// The gateway instance a pid file belongs to, if it is a gateway's
+pub(crate) fn pidfile_instance(name: &str) -> Option<&str> {
+    name.strip_prefix(PIDFILE_PREFIX)
+}
+
+/// Take over a gateway left running by a previous run, found listening on
+/// its recorded port at startup
+pub(crate) fn adopt(instance: &str, record: PidRecord) -> Result<(), String> {
+    let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+    let gateway = gateways
+        .entry(instance.to_string())
+        .or_insert_with(GatewayProcess::new);
+    if gateway.child.is_some() || gateway.remote || gateway.adopted.is_some() {
+        return Err(format!("{} is already running", display_name(instance)));
+    }
+    let port = record.port.ok_or_else(|| "its pid file names no port".to_string())?;
+    gateway.adopted = Some(PidFile::adopt(record).map_err(|e| e.to_string())?);
+    gateway.port = port;
+    gateway.url = format!("ws://127.0.0.1:{}", port);
     Ok(())
 }
 
+/// The `gateway.remote_url` the default instance connects to, if set
+pub(crate) fn remote_url() -> Result<Option<reqwest::Url>, CommandError> {
+    let url = config::get_config()
+        .ok()
+        .and_then(|config| config.gateway.remote_url)
+        .filter(|url| !url.trim().is_empty());
+    url.map(|url| parse_remote_url(&url)).transpose()
+}
+
+fn parse_remote_url(url: &str) -> Result<reqwest::Url, CommandError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| CommandError::invalid_input(format!("Invalid remote gateway URL '{}': {}", url.trim(), e)))?;
+    if parsed.scheme() != "wss" || parsed.host_str().is_none() {
+        return Err(CommandError::invalid_input(format!(
+            "Remote gateway URL must look like wss://host[:port], got '{}'",
+            url.trim()
+        )));
+    }
+    Ok(parsed)
+}
+
 /// Generate a cryptographically secure 256-bit token as a 64-character hex string
 fn generate_token() -> String {
     let mut rng = rand::thread_rng();
@@ -50,16 +361,26 @@ fn generate_token() -> String {
     hex::encode(bytes)
 }
 
-/// Get the fallback token file path: ~/.helix/gateway-token
-fn get_token_file_path() -> Result<std::path::PathBuf, String> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not determine home directory".to_string())?;
-    Ok(home.join(".helix").join(GATEWAY_TOKEN_FILENAME))
+/// Keyring key and fallback file name of an instance's token
+fn token_names(instance: &str) -> (String, String) {
+    if instance == DEFAULT_INSTANCE {
+        (GATEWAY_TOKEN_KEY.to_string(), GATEWAY_TOKEN_FILENAME.to_string())
+    } else {
+        (
+            format!("{}-{}", GATEWAY_TOKEN_KEY, instance),
+            format!("{}-{}", GATEWAY_TOKEN_FILENAME, instance),
+        )
+    }
+}
+
+/// Get the fallback token file path: ~/.helix/gateway-token[-<instance>]
+fn get_token_file_path(filename: &str) -> Result<std::path::PathBuf, String> {
+    Ok(crate::accounts::profiles::helix_root()?.join(filename))
 }
 
 /// Try to read a token from the fallback file
-fn read_token_from_file() -> Result<Option<String>, String> {
-    let path = get_token_file_path()?;
+fn read_token_from_file(filename: &str) -> Result<Option<String>, String> {
+    let path = get_token_file_path(filename)?;
     match fs::read_to_string(&path) {
         Ok(contents) => {
             let token = contents.trim().to_string();
@@ -76,8 +397,8 @@ fn read_token_from_file() -> Result<Option<String>, String> {
 }
 
 /// Write a token to the fallback file with restrictive permissions
-fn write_token_to_file(token: &str) -> Result<(), String> {
-    let path = get_token_file_path()?;
+fn write_token_to_file(filename: &str, token: &str) -> Result<(), String> {
+    let path = get_token_file_path(filename)?;
 
     // Ensure ~/.helix directory exists
     if let Some(parent) = path.parent() {
@@ -104,18 +425,96 @@ fn write_token_to_file(token: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Get or create a cryptographically secure gateway token.
+/// How a spawned gateway receives its token (`gateway.token_handoff`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenHandoff {
+    /// `--token-stdin`, written to the piped stdin and closed
+    Stdin,
+    /// `--token-file` naming a 0600 file, removed once the gateway is up
+    File,
+    /// `--token`, visible in process listings; for runtimes without the others
+    Argv,
+}
+
+fn token_handoff() -> Result<TokenHandoff, CommandError> {
+    let mode = config::get_config()
+        .map(|c| c.gateway.token_handoff)
+        .unwrap_or_else(|_| config::GatewayConfig::default().token_handoff);
+    match mode.trim() {
+        "stdin" => Ok(TokenHandoff::Stdin),
+        "file" => Ok(TokenHandoff::File),
+        "argv" => Ok(TokenHandoff::Argv),
+        other => Err(CommandError::new(
+            ErrorCode::Config,
+            format!("Unknown gateway.token_handoff '{}': use \"stdin\", \"file\" or \"argv\"", other),
+        )),
+    }
+}
+
+/// ~/.helix/gateway-token-handoff[-<instance>]
+fn handoff_file_path(instance: &str) -> Result<PathBuf, String> {
+    let (_, filename) = token_names(instance);
+    get_token_file_path(&filename.replacen(GATEWAY_TOKEN_FILENAME, "gateway-token-handoff", 1))
+}
+
+/// Write the token for `--token-file`, readable only by the user
+fn write_handoff_file(instance: &str, token: &str) -> Result<PathBuf, String> {
+    let path = handoff_file_path(instance)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+
+    // Created fresh so the mode applies even if an old file was left behind
+    let _ = fs::remove_file(&path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create gateway token file: {}", e))?;
+    writeln!(file, "{}", token).map_err(|e| format!("Failed to write gateway token file: {}", e))?;
+    Ok(path)
+}
+
+fn remove_handoff_file(instance: &str) {
+    if let Ok(path) = handoff_file_path(instance) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Write the token to a gateway started with `--token-stdin` and close its stdin
+fn send_token(child: &mut Child, token: &str) -> std::io::Result<()> {
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| std::io::Error::other("stdin is not piped"))?;
+    writeln!(stdin, "{}", token)
+}
+
+/// Get or create the default gateway's token
+pub fn get_or_create_gateway_token() -> Result<String, String> {
+    get_or_create_instance_token(DEFAULT_INSTANCE)
+}
+
+/// Get or create a cryptographically secure token for a gateway instance.
 ///
 /// Token resolution order:
-/// 1. OS keyring (service: "helix-desktop", key: "gateway-token")
-/// 2. Fallback file at ~/.helix/gateway-token
+/// 1. OS keyring (service: "helix-desktop", key: "gateway-token", suffixed
+///    with "-<instance>" for named instances)
+/// 2. Fallback file at ~/.helix/gateway-token[-<instance>]
 /// 3. Session-only generated token (last resort, not persisted)
 ///
 /// On first launch, generates a 256-bit random token (64 hex chars),
 /// stores it in the keyring, and returns it. The token value is NEVER logged.
-fn get_or_create_gateway_token() -> Result<String, String> {
+pub fn get_or_create_instance_token(instance: &str) -> Result<String, String> {
+    let (key, filename) = token_names(instance);
+
     // 1. Try to read from OS keyring
-    match Entry::new(KEYRING_SERVICE, GATEWAY_TOKEN_KEY) {
+    match Entry::new(KEYRING_SERVICE, &key) {
         Ok(entry) => {
             match entry.get_password() {
                 Ok(token) => {
@@ -132,7 +531,7 @@ fn get_or_create_gateway_token() -> Result<String, String> {
                 Err(e) => {
                     log::warn!("Keyring read failed: {}, trying fallback file", e);
                     // Fall through to file-based fallback
-                    return get_or_create_token_from_file();
+                    return get_or_create_token_from_file(&filename);
                 }
             }
 
@@ -144,7 +543,7 @@ fn get_or_create_gateway_token() -> Result<String, String> {
                 Ok(()) => {
                     log::info!("Gateway token stored in OS keyring");
                     // Also write to file as backup
-                    if let Err(e) = write_token_to_file(&token) {
+                    if let Err(e) = write_token_to_file(&filename, &token) {
                         log::warn!("Failed to write backup token file: {}", e);
                     }
                     Ok(token)
@@ -152,22 +551,22 @@ fn get_or_create_gateway_token() -> Result<String, String> {
                 Err(e) => {
                     log::warn!("Failed to store token in keyring: {}, using fallback file", e);
                     // Store in file instead
-                    write_token_to_file(&token)?;
+                    write_token_to_file(&filename, &token)?;
                     Ok(token)
                 }
             }
         }
         Err(e) => {
             log::warn!("Failed to create keyring entry: {}, using fallback", e);
-            get_or_create_token_from_file()
+            get_or_create_token_from_file(&filename)
         }
     }
 }
 
 /// Fallback: get or create token from file system
-fn get_or_create_token_from_file() -> Result<String, String> {
+fn get_or_create_token_from_file(filename: &str) -> Result<String, String> {
     // Try to read existing token from file
-    match read_token_from_file() {
+    match read_token_from_file(filename) {
         Ok(Some(token)) => {
             log::info!("Gateway token retrieved from fallback file");
             return Ok(token);
@@ -184,7 +583,7 @@ fn get_or_create_token_from_file() -> Result<String, String> {
     let token = generate_token();
     log::info!("Generated new gateway token (256-bit) for file storage");
 
-    match write_token_to_file(&token) {
+    match write_token_to_file(filename, &token) {
         Ok(()) => Ok(token),
         Err(e) => {
             // Last resort: session-only token (not persisted)
@@ -195,55 +594,372 @@ fn get_or_create_token_from_file() -> Result<String, String> {
     }
 }
 
-/// Tauri command: Get the current gateway token for frontend use
+/// Tauri command: Get a gateway instance's token for frontend use
 #[tauri::command]
-pub fn get_gateway_token() -> Result<String, String> {
-    get_or_create_gateway_token()
+pub fn get_gateway_token(instance: Option<String>) -> Result<String, CommandError> {
+    let instance = instance_name(instance)?;
+    get_or_create_instance_token(&instance).map_err(Into::into)
 }
 
 #[derive(Serialize, Clone)]
 pub struct GatewayStatus {
+    pub instance: String,
     pub running: bool,
     pub port: Option<u16>,
     pub pid: Option<u32>,
     pub url: Option<String>,
+    /// Connected to a gateway on another host rather than running one
+    pub remote: bool,
 }
 
 #[derive(Serialize, Clone)]
 pub struct GatewayStarted {
+    pub instance: String,
     pub port: u16,
     pub url: String,
 }
 
+/// Payload for gateway:stopped event
+#[derive(Serialize, Clone)]
+struct GatewayStopped {
+    instance: String,
+}
+
+/// Payload for gateway:start_failed event
+#[derive(Serialize, Clone)]
+struct GatewayStartFailed {
+    instance: String,
+    error: CommandError,
+}
+
+/// Why a spawned gateway never became ready
+enum StartupFailure {
+    /// The process ended; its exit status when it was still ours to reap
+    Exited(Option<ExitStatus>),
+    /// No answer in time; the last health check error
+    TimedOut(Option<String>),
+}
+
+/// Start a gateway and wait until it answers /health. In remote mode the
+/// default instance connects to the remote gateway instead.
 #[tauri::command]
-pub fn start_gateway(app: AppHandle) -> Result<GatewayStarted, String> {
-    let mut gateway_lock = GATEWAY.lock().map_err(|e| e.to_string())?;
-    let gateway = gateway_lock.as_mut().ok_or("Gateway not initialized")?;
+pub async fn start_gateway(app: AppHandle, instance: Option<String>) -> Result<GatewayStarted, CommandError> {
+    let instance = instance_name(instance)?;
+    if instance == DEFAULT_INSTANCE {
+        if let Some(url) = remote_url()? {
+            return connect_remote(&app, &url).await;
+        }
+    }
+
+    let first_log = crate::gateway::logs::next_seq();
+    let result = spawn_gateway(&app, &instance).inspect_err(|_| remove_handoff_file(&instance))?;
+
+    let timeout = startup_timeout();
+    let ready = wait_until_ready(&instance, result.port, timeout).await;
+    // The gateway has read its token by now, or won't start
+    remove_handoff_file(&instance);
+    if let Err(failure) = ready {
+        return Err(startup_failed(&instance, result.port, timeout, failure, first_log).await);
+    }
+    let handshake = match handshake::verify(&app, &instance, &result.url, false).await {
+        Ok(handshake) => handshake,
+        Err(e) => {
+            discard(&instance);
+            return Err(handshake_failed(&instance, &result.url, e));
+        }
+    };
+
+    if instance == DEFAULT_INSTANCE {
+        remember_port(result.port);
+    }
+    events::record(
+        ServiceKind::Gateway,
+        "started",
+        format!("{} started on port {}", display_name(&instance), result.port),
+        Some(serde_json::json!({ "instance": instance, "version": handshake.version })),
+    );
+
+    // Emit event to frontend
+    let _ = app.emit("gateway:started", result.clone());
+    hooks::fire(HookEvent::GatewayStarted, serde_json::to_value(&result).unwrap_or_default());
+
+    Ok(result)
+}
+
+fn startup_timeout() -> Duration {
+    let timeout_secs = config::get_config()
+        .map(|c| c.gateway.startup_timeout_secs)
+        .unwrap_or_else(|_| config::GatewayConfig::default().startup_timeout_secs);
+    Duration::from_secs(timeout_secs.max(1))
+}
+
+/// Point the default instance at the remote gateway, without checking it
+fn attach_remote(url: &reqwest::Url) -> Result<GatewayStarted, CommandError> {
+    let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+    let gateway = gateways
+        .entry(DEFAULT_INSTANCE.to_string())
+        .or_insert_with(GatewayProcess::new);
+    if gateway.child.is_some() || gateway.adopted.is_some() {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            "Gateway is running locally; stop it before connecting to the remote one",
+        ));
+    }
+    if gateway.remote {
+        return Err(CommandError::new(ErrorCode::Conflict, "Gateway already connected"));
+    }
 
-    if gateway.child.is_some() {
-        return Err("Gateway already running".to_string());
+    gateway.remote = true;
+    gateway.port = url.port_or_known_default().unwrap_or(443);
+    gateway.url = url.to_string();
+    Ok(GatewayStarted {
+        instance: DEFAULT_INSTANCE.to_string(),
+        port: gateway.port,
+        url: gateway.url.clone(),
+    })
+}
+
+fn announce_remote(app: &AppHandle, started: &GatewayStarted) {
+    events::record(
+        ServiceKind::Gateway,
+        "attached",
+        format!("Connected to remote gateway at {}", started.url),
+        Some(serde_json::json!({ "instance": started.instance, "url": started.url })),
+    );
+    crate::gateway::config_sync::set_restart_pending(app, false);
+    let _ = app.emit("gateway:started", started.clone());
+    hooks::fire(HookEvent::GatewayStarted, serde_json::to_value(started).unwrap_or_default());
+}
+
+/// Connect to the remote gateway and wait until it answers /health
+async fn connect_remote(app: &AppHandle, url: &reqwest::Url) -> Result<GatewayStarted, CommandError> {
+    let started = attach_remote(url)?;
+    let timeout = startup_timeout();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let attached = GATEWAYS
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(DEFAULT_INSTANCE)
+            .is_some_and(|g| g.remote);
+        if !attached {
+            return Err(CommandError::unavailable("Gateway was stopped while connecting"));
+        }
+
+        let health = crate::gateway::monitor::check_remote_gateway_health(&started.url).await;
+        if health.healthy {
+            break;
+        }
+        if Instant::now() >= deadline {
+            if let Ok(mut gateways) = GATEWAYS.lock() {
+                forget(&mut gateways, DEFAULT_INSTANCE);
+            }
+            let message = match health.error {
+                Some(error) => format!(
+                    "Remote gateway at {} did not answer within {}s: {}",
+                    started.url,
+                    timeout.as_secs(),
+                    error
+                ),
+                None => format!("Remote gateway at {} did not answer within {}s", started.url, timeout.as_secs()),
+            };
+            log::warn!("{}", message);
+            events::record(
+                ServiceKind::Gateway,
+                "start_failed",
+                message.clone(),
+                Some(serde_json::json!({ "instance": DEFAULT_INSTANCE, "url": started.url })),
+            );
+            return Err(CommandError::new(ErrorCode::Timeout, message).with_details(serde_json::json!({
+                "instance": DEFAULT_INSTANCE,
+                "url": started.url,
+                "timeout_secs": timeout.as_secs(),
+            })));
+        }
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+    if let Err(e) = handshake::verify(app, DEFAULT_INSTANCE, &started.url, true).await {
+        if let Ok(mut gateways) = GATEWAYS.lock() {
+            forget(&mut gateways, DEFAULT_INSTANCE);
+        }
+        return Err(handshake_failed(DEFAULT_INSTANCE, &started.url, e));
+    }
+
+    announce_remote(app, &started);
+    Ok(started)
+}
+
+/// Record a gateway that answered /health but failed the handshake
+fn handshake_failed(instance: &str, url: &str, error: CommandError) -> CommandError {
+    log::warn!("{}", error.message);
+    events::record(
+        ServiceKind::Gateway,
+        "handshake_failed",
+        error.message.clone(),
+        Some(serde_json::json!({ "instance": instance, "url": url, "details": error.details })),
+    );
+    error
+}
+
+/// Stop and forget a gateway that started but can't be used
+fn discard(instance: &str) {
+    if let Ok(mut gateways) = GATEWAYS.lock() {
+        if let Some(mut child) = forget(&mut gateways, instance) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Poll /health until the gateway answers, it exits or `timeout` passes
+async fn wait_until_ready(instance: &str, port: u16, timeout: Duration) -> Result<(), StartupFailure> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let exited = {
+            let mut gateways = GATEWAYS.lock().map_err(|_| StartupFailure::Exited(None))?;
+            match gateways.get_mut(instance).and_then(|g| g.child.as_mut()) {
+                Some(child) => child.try_wait().ok().flatten().map(Some),
+                // Stopped, or reaped by the supervisor, while starting
+                None => Some(None),
+            }
+        };
+        if let Some(status) = exited {
+            return Err(StartupFailure::Exited(status));
+        }
+
+        let health = crate::gateway::monitor::check_gateway_health(port).await;
+        if health.healthy {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(StartupFailure::TimedOut(health.error));
+        }
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+}
+
+/// Stop a gateway that never became ready and describe why, with what it
+/// wrote to stderr since `first_log`
+async fn startup_failed(
+    instance: &str,
+    port: u16,
+    timeout: Duration,
+    failure: StartupFailure,
+    first_log: u64,
+) -> CommandError {
+    discard(instance);
+    tokio::time::sleep(STDERR_DRAIN_DELAY).await;
+    let stderr = crate::gateway::logs::stderr_since(first_log, STARTUP_STDERR_LINES);
+
+    let (code, mut message, exit_code) = match failure {
+        StartupFailure::Exited(status) => (
+            ErrorCode::ProcessFailed,
+            match status {
+                Some(status) => format!("{} exited during startup ({})", display_name(instance), status),
+                None => format!("{} exited during startup", display_name(instance)),
+            },
+            status.and_then(|status| status.code()),
+        ),
+        StartupFailure::TimedOut(error) => (
+            ErrorCode::Timeout,
+            match error {
+                Some(error) => format!(
+                    "{} did not become ready within {}s: {}",
+                    display_name(instance),
+                    timeout.as_secs(),
+                    error
+                ),
+                None => format!("{} did not become ready within {}s", display_name(instance), timeout.as_secs()),
+            },
+            None,
+        ),
+    };
+    log::warn!("{}", message);
+    if let Some(last) = stderr.last() {
+        message = format!("{}. Last output: {}", message, last);
+    }
+
+    events::record(
+        ServiceKind::Gateway,
+        "start_failed",
+        message.clone(),
+        Some(serde_json::json!({ "instance": instance, "port": port })),
+    );
+    CommandError::new(code, message).with_details(serde_json::json!({
+        "instance": instance,
+        "port": port,
+        "timeout_secs": timeout.as_secs(),
+        "exit_code": exit_code,
+        "stderr": stderr,
+    }))
+}
+
+/// Spawn the gateway process and register it; readiness is start_gateway's
+fn spawn_gateway(app: &AppHandle, instance: &str) -> Result<GatewayStarted, CommandError> {
+    let instance = instance.to_string();
+    let is_default = instance == DEFAULT_INSTANCE;
+    let settings = if is_default { GatewayInstanceConfig::default() } else { instance_config(&instance) };
+
+    let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+    if gateways.get(&instance).is_some_and(|g| g.child.is_some() || g.adopted.is_some()) {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            format!("{} already running", display_name(&instance)),
+        ));
+    }
+    // Started by another copy of the app, or left by one that couldn't be
+    // stopped at startup
+    if let Some(holder) = pidfile::holder(&pidfile_name(&instance)) {
+        return Err(AlreadyRunning(holder).into());
     }
 
-    // Use default OpenClaw port or find available if taken
-    let port = if is_port_available(DEFAULT_GATEWAY_PORT) {
-        DEFAULT_GATEWAY_PORT
+    // Default instance: its pinned port, else the one it used last, the
+    // default OpenClaw port or any free one. Named instances: their
+    // configured port, or any free one.
+    let port = match settings.port {
+        _ if is_default => default_instance_port()?,
+        Some(port) => {
+            if !is_port_available(port) {
+                return Err(CommandError::new(
+                    ErrorCode::Conflict,
+                    format!("Port {} for {} is already in use", port, display_name(&instance)),
+                ));
+            }
+            port
+        }
+        None => find_available_port().map_err(|e| e.to_string())?,
+    };
+
+    let (env_vars, agents) = if is_default {
+        config::get_config()
+            .map(|c| (c.gateway.env, c.gateway.agents))
+            .unwrap_or_default()
     } else {
-        find_available_port().map_err(|e| e.to_string())?
+        (settings.env.clone(), settings.agents.clone())
     };
+    let mut env = resolve_env(&instance, &env_vars)?;
+    let agent_env = resolve_agent_env(&instance, &agents)?;
+    env.extend(agent_env.env);
 
     // Get openclaw path
-    let openclaw_path = get_openclaw_path(&app)?;
-    let openclaw_dir = get_openclaw_directory()?;
+    let openclaw_path = get_openclaw_path(app)?;
+    let openclaw_dir = crate::gateway::runtime::runtime_dir()?;
+    let state_dir = if is_default { None } else { Some(instance_state_dir(&instance, &settings)?) };
 
-    log::info!("Starting OpenClaw gateway from: {:?}", openclaw_path);
+    log::info!("Starting OpenClaw gateway '{}' from: {:?}", instance, openclaw_path);
     log::info!("Working directory: {:?}", openclaw_dir);
+    if let Some(dir) = &state_dir {
+        log::info!("State directory: {:?}", dir);
+    }
 
-    // Get or generate a per-device gateway token (never logged)
-    let gateway_token = get_or_create_gateway_token()?;
+    // Get or generate a per-device, per-instance gateway token (never logged)
+    let gateway_token = get_or_create_instance_token(&instance)?;
+    let handoff = token_handoff()?;
 
     // Build arguments based on executable type
     let openclaw_mjs = openclaw_dir.join("openclaw.mjs");
-    let args: Vec<String> = if openclaw_path.to_string_lossy() == "node" && openclaw_mjs.exists() {
+    let via_node = openclaw_path.file_stem().is_some_and(|stem| stem == "node");
+    let mut args: Vec<String> = if via_node && openclaw_mjs.exists() {
         // Running via node + openclaw.mjs
         vec![
             openclaw_mjs.to_string_lossy().to_string(),
@@ -252,8 +968,6 @@ pub fn start_gateway(app: AppHandle) -> Result<GatewayStarted, String> {
             port.to_string(),
             "--bind".to_string(),
             "loopback".to_string(),
-            "--token".to_string(),
-            gateway_token.clone(),
         ]
     } else if openclaw_path.to_string_lossy() == "npx" {
         // Running via npx (global fallback)
@@ -264,8 +978,6 @@ pub fn start_gateway(app: AppHandle) -> Result<GatewayStarted, String> {
             port.to_string(),
             "--bind".to_string(),
             "loopback".to_string(),
-            "--token".to_string(),
-            gateway_token.clone(),
         ]
     } else {
         // Direct executable (bundled or bin symlink)
@@ -275,10 +987,16 @@ pub fn start_gateway(app: AppHandle) -> Result<GatewayStarted, String> {
             port.to_string(),
             "--bind".to_string(),
             "loopback".to_string(),
-            "--token".to_string(),
-            gateway_token,
         ]
     };
+    match handoff {
+        TokenHandoff::Stdin => args.push("--token-stdin".to_string()),
+        TokenHandoff::File => {
+            let path = write_handoff_file(&instance, &gateway_token)?;
+            args.extend(["--token-file".to_string(), path.to_string_lossy().to_string()]);
+        }
+        TokenHandoff::Argv => args.extend(["--token".to_string(), gateway_token.clone()]),
+    }
 
     // Log command without exposing the token value
     let sanitized_args: Vec<String> = args.iter().enumerate().map(|(i, a)| {
@@ -291,74 +1009,381 @@ pub fn start_gateway(app: AppHandle) -> Result<GatewayStarted, String> {
     }).collect();
     log::info!("Gateway command: {:?} {:?}", openclaw_path, sanitized_args);
 
-    // Spawn gateway process
-    let child = Command::new(&openclaw_path)
+    // Spawn gateway process, confined if isolation.gateway says so
+    let writable: Vec<PathBuf> = std::iter::once(openclaw_dir.clone())
+        .chain(state_dir.clone().or_else(crate::gateway::config_sync::gateway_state_dir))
+        .chain(agent_env.dirs)
+        .collect();
+    let mut command = isolation::command(ProcessKind::Gateway, &openclaw_path, &writable)?;
+    command
         .args(&args)
         .current_dir(&openclaw_dir)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stderr(Stdio::piped());
+    if handoff == TokenHandoff::Stdin {
+        command.stdin(Stdio::piped());
+    }
+    // The gateway and the tools it starts look node up on PATH, which a
+    // Tauri app launched from the desktop may not have
+    if let Some(path) = crate::gateway::node::search_path() {
+        command.env("PATH", path);
+    }
+    if !env.is_empty() {
+        // Names only; values may be secrets
+        let names: Vec<&str> = env.iter().map(|(name, _)| name.as_str()).collect();
+        log::info!("Gateway environment: {:?}", names);
+        command.envs(env);
+    }
+    if let Some(dir) = &state_dir {
+        command.env("OPENCLAW_STATE_DIR", dir);
+    }
+    // Where the gateway declares its skills' actions (gateway/actions.rs)
+    if let Some(url) = crate::api::server::local_url() {
+        command.env("HELIX_API_URL", url);
+        if let Ok(path) = crate::api::token::token_path() {
+            command.env("HELIX_API_TOKEN_FILE", path);
+        }
+    }
+    let mut child = command
         .spawn()
         .map_err(|e| format!("Failed to start gateway: {}. Make sure helix-runtime is built.", e))?;
+    if let Err(e) = isolation::attach(ProcessKind::Gateway, Some(child.id())) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(e);
+    }
+    let acquired = PidFile::spawned(&pidfile_name(&instance), child.id(), Some(port));
+    let pidfile = match acquired.map_err(|e| e.downcast::<AlreadyRunning>()) {
+        Ok(pidfile) => Some(pidfile),
+        Err(Ok(running)) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(running.into());
+        }
+        Err(Err(e)) => {
+            log::warn!("{} runs without a pid file: {:#}", display_name(&instance), e);
+            None
+        }
+    };
+    if handoff == TokenHandoff::Stdin {
+        if let Err(e) = send_token(&mut child, &gateway_token) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CommandError::process(format!("Failed to pass the token to {}: {}", display_name(&instance), e)));
+        }
+    }
+    crate::gateway::logs::capture(app, &mut child);
 
     let url = format!("ws://127.0.0.1:{}", port);
 
-    gateway.child = Some(child);
-    gateway.port = port;
-    gateway.url = url.clone();
+    gateways.insert(
+        instance.clone(),
+        GatewayProcess {
+            child: Some(child),
+            port,
+            url: url.clone(),
+            remote: false,
+            pidfile,
+            adopted: None,
+        },
+    );
+
+    // A fresh process loads the current config
+    if is_default {
+        crate::gateway::config_sync::set_restart_pending(app, false);
+    }
 
-    let result = GatewayStarted { port, url: url.clone() };
+    Ok(GatewayStarted { instance, port, url })
+}
 
-    // Emit event to frontend
-    let _ = app.emit("gateway:started", result.clone());
+#[tauri::command]
+pub fn stop_gateway(app: AppHandle, instance: Option<String>) -> Result<(), CommandError> {
+    stop(app, instance, None)
+}
 
-    Ok(result)
+/// Stop a gateway, giving it `grace` to exit on SIGTERM (closing its
+/// channels and sessions) before it is killed
+pub(crate) fn stop_gracefully(app: AppHandle, instance: Option<String>, grace: Duration) -> Result<(), CommandError> {
+    stop(app, instance, Some(grace))
 }
 
-#[tauri::command]
-pub fn stop_gateway(app: AppHandle) -> Result<(), String> {
-    let mut gateway_lock = GATEWAY.lock().map_err(|e| e.to_string())?;
-    let gateway = gateway_lock.as_mut().ok_or("Gateway not initialized")?;
+fn stop(app: AppHandle, instance: Option<String>, grace: Option<Duration>) -> Result<(), CommandError> {
+    let instance = instance_name(instance)?;
+    let (remote, adopted, child) = {
+        let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+        let Some(gateway) = gateways.get(&instance) else {
+            return Err(if instance == DEFAULT_INSTANCE {
+                CommandError::unavailable("Gateway not initialized")
+            } else {
+                CommandError::not_found(format!("{} is not running", display_name(&instance)))
+            });
+        };
+        let remote = gateway.remote;
+        let adopted = gateway.adopted.as_ref().map(|pidfile| pidfile.record().pid);
+        // An adopted gateway's pid file is what identifies its process, so
+        // it is kept until the process is gone
+        if gateway.adopted.as_ref().is_some_and(|pidfile| !orphans::terminate(pidfile.record())) {
+            return Err(CommandError::process(format!(
+                "{} left by the previous run could not be stopped",
+                display_name(&instance)
+            )));
+        }
+        (remote, adopted, forget(&mut gateways, &instance))
+    };
+    if let Some(pid) = adopted {
+        log::info!("Stopped {} adopted from the previous run ({})", display_name(&instance), pid);
+    }
 
-    if let Some(mut child) = gateway.child.take() {
+    if let Some(mut child) = child {
+        if let Some(grace) = grace {
+            terminate(&mut child, grace);
+        }
         let _ = child.kill();
         let _ = child.wait();
     }
 
-    gateway.port = 0;
-    gateway.url = String::new();
-
-    let _ = app.emit("gateway:stopped", ());
+    let message = if remote {
+        "Disconnected from remote gateway".to_string()
+    } else {
+        format!("{} stopped", display_name(&instance))
+    };
+    events::record(
+        ServiceKind::Gateway,
+        "stopped",
+        message,
+        Some(serde_json::json!({ "instance": instance })),
+    );
+    let _ = app.emit("gateway:stopped", GatewayStopped { instance });
 
     Ok(())
 }
 
-#[tauri::command]
-pub fn gateway_status() -> Result<GatewayStatus, String> {
-    let gateway_lock = GATEWAY.lock().map_err(|e| e.to_string())?;
+/// Ask `child` to exit and wait up to `grace` for it; the caller kills it
+/// if it is still running. Windows has no equivalent of SIGTERM for a
+/// console process, so there it is killed straight away.
+fn terminate(child: &mut Child, grace: Duration) {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill(2) on the pid of a child we have not reaped yet
+        if unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGTERM) } != 0 {
+            return;
+        }
+        let deadline = Instant::now() + grace;
+        while Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        log::warn!("Gateway did not exit within {:?} of SIGTERM, killing it", grace);
+    }
+    #[cfg(not(unix))]
+    let _ = (child, grace);
+}
+
+/// Forget gateway processes that exited on their own and return how each
+/// ended. A stopped gateway was already taken by stop_gateway. Adopted
+/// gateways aren't ours to wait on; one that is gone is only forgotten,
+/// and the monitor reports the gateway down.
+pub(crate) fn reap_exited() -> Vec<(String, ExitStatus)> {
+    let Ok(mut gateways) = GATEWAYS.lock() else {
+        return Vec::new();
+    };
+    let vanished: Vec<String> = gateways
+        .iter()
+        .filter(|(_, g)| g.adopted.as_ref().is_some_and(|pidfile| !pidfile.record().is_alive()))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in &vanished {
+        log::warn!("{} adopted from the previous run has exited", display_name(name));
+        forget(&mut gateways, name);
+    }
+
+    let exited: Vec<(String, ExitStatus)> = gateways
+        .iter_mut()
+        .filter_map(|(name, g)| Some((name.clone(), g.child.as_mut()?.try_wait().ok()??)))
+        .collect();
 
-    match gateway_lock.as_ref() {
-        Some(g) if g.child.is_some() => Ok(GatewayStatus {
+    for (name, _) in &exited {
+        forget(&mut gateways, name);
+    }
+    exited
+}
+
+fn process_status(instance: &str, gateway: Option<&GatewayProcess>) -> GatewayStatus {
+    match gateway {
+        Some(g) if g.child.is_some() || g.remote || g.adopted.is_some() => GatewayStatus {
+            instance: instance.to_string(),
             running: true,
             port: Some(g.port),
-            pid: g.child.as_ref().map(|c| c.id()),
+            pid: g
+                .child
+                .as_ref()
+                .map(|c| c.id())
+                .or(g.adopted.as_ref().map(|pidfile| pidfile.record().pid)),
             url: Some(g.url.clone()),
-        }),
-        _ => Ok(GatewayStatus {
+            remote: g.remote,
+        },
+        _ => GatewayStatus {
+            instance: instance.to_string(),
             running: false,
             port: None,
             pid: None,
             url: None,
-        }),
+            remote: false,
+        },
     }
 }
 
 #[tauri::command]
-pub fn get_gateway_url() -> Result<String, String> {
-    let gateway_lock = GATEWAY.lock().map_err(|e| e.to_string())?;
+pub fn gateway_status(instance: Option<String>) -> Result<GatewayStatus, CommandError> {
+    let instance = instance_name(instance)?;
+    let gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+    Ok(process_status(&instance, gateways.get(&instance)))
+}
 
-    match gateway_lock.as_ref() {
+/// The default gateway, configured instances and any other running ones
+#[tauri::command]
+pub fn list_gateway_instances() -> Result<Vec<GatewayStatus>, CommandError> {
+    let configured = config::get_config()
+        .map(|config| config.gateway.instances.into_keys().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+
+    let mut names: Vec<String> = std::iter::once(DEFAULT_INSTANCE.to_string())
+        .chain(gateways.keys().cloned())
+        .chain(configured)
+        .collect();
+    names.sort();
+    names.dedup();
+
+    Ok(names
+        .iter()
+        .map(|name| process_status(name, gateways.get(name)))
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_gateway_url(instance: Option<String>) -> Result<String, CommandError> {
+    let instance = instance_name(instance)?;
+    if instance == DEFAULT_INSTANCE {
+        if let Some(url) = remote_url()? {
+            return Ok(url.to_string());
+        }
+    }
+    let gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+
+    match gateways.get(&instance) {
         Some(g) if !g.url.is_empty() => Ok(g.url.clone()),
-        _ => Ok(format!("ws://127.0.0.1:{}", DEFAULT_GATEWAY_PORT)),
+        _ if instance == DEFAULT_INSTANCE => Ok(format!("ws://127.0.0.1:{}", expected_default_port())),
+        _ => Err(CommandError::unavailable(format!("{} is not running", display_name(&instance)))),
+    }
+}
+
+/// Pin the default gateway to `port`, or unpin it with `None`. A running
+/// gateway keeps its port until restarted; the restart is flagged as pending.
+#[tauri::command]
+pub fn set_gateway_port(app: AppHandle, port: Option<u16>) -> Result<(), CommandError> {
+    if port.is_some_and(|port| port < 1024) {
+        return Err(CommandError::invalid_input("Gateway port must be between 1024 and 65535"));
+    }
+
+    let mut config = config::get_config()?;
+    config.gateway.port = port;
+    config::set_config(config)?;
+    match port {
+        Some(port) => log::info!("Gateway port pinned to {}", port),
+        None => log::info!("Gateway port unpinned"),
+    }
+
+    let running_port = GATEWAYS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(DEFAULT_INSTANCE)
+        .filter(|g| g.child.is_some() || g.adopted.is_some())
+        .map(|g| g.port);
+    if let (Some(running), Some(pinned)) = (running_port, port) {
+        if running != pinned {
+            crate::gateway::config_sync::set_restart_pending(&app, true);
+        }
+    }
+    Ok(())
+}
+
+/// Connect the default gateway to one running on another host (wss://), or
+/// spawn it locally again with `None`. A connected or running gateway
+/// switches over when restarted; the restart is flagged as pending.
+#[tauri::command]
+pub fn set_gateway_remote_url(app: AppHandle, url: Option<String>) -> Result<(), CommandError> {
+    let url = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &url {
+        parse_remote_url(url)?;
+    }
+
+    let mut config = config::get_config()?;
+    if config.gateway.remote_url == url {
+        return Ok(());
+    }
+    config.gateway.remote_url = url.clone();
+    config::set_config(config)?;
+    match &url {
+        Some(url) => log::info!("Gateway set to remote {}", url),
+        None => log::info!("Gateway set to run locally"),
+    }
+
+    let active = GATEWAYS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(DEFAULT_INSTANCE)
+        .is_some_and(|g| g.child.is_some() || g.remote || g.adopted.is_some());
+    if active {
+        crate::gateway::config_sync::set_restart_pending(&app, true);
+    }
+    Ok(())
+}
+
+/// Port for the default gateway; a pinned port that is taken is an error
+/// rather than silently replaced
+fn default_instance_port() -> Result<u16, CommandError> {
+    let settings = config::get_config().map(|c| c.gateway).unwrap_or_default();
+    if let Some(port) = settings.port {
+        if !is_port_available(port) {
+            return Err(CommandError::new(
+                ErrorCode::Conflict,
+                format!("Pinned gateway port {} is already in use", port),
+            )
+            .with_details(serde_json::json!({ "port": port })));
+        }
+        return Ok(port);
+    }
+
+    let mut preferred = settings.last_port.into_iter().chain([DEFAULT_GATEWAY_PORT]);
+    match preferred.find(|port| is_port_available(*port)) {
+        Some(port) => Ok(port),
+        None => find_available_port().map_err(|e| e.to_string().into()),
+    }
+}
+
+/// Port a running default gateway is expected on: pinned, last used, or
+/// the default OpenClaw port
+fn expected_default_port() -> u16 {
+    config::get_config()
+        .ok()
+        .and_then(|c| c.gateway.port.or(c.gateway.last_port))
+        .unwrap_or(DEFAULT_GATEWAY_PORT)
+}
+
+/// Persist the default gateway's port for the next launch
+fn remember_port(port: u16) {
+    let Ok(mut config) = config::get_config() else {
+        return;
+    };
+    if config.gateway.last_port == Some(port) {
+        return;
+    }
+    config.gateway.last_port = Some(port);
+    if let Err(e) = config::set_config(config) {
+        log::warn!("Failed to save gateway port: {}", e);
     }
 }
 
@@ -371,48 +1396,32 @@ fn find_available_port() -> std::io::Result<u16> {
     Ok(listener.local_addr()?.port())
 }
 
-fn get_openclaw_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    // Try bundled openclaw first (for production)
-    if let Ok(resource_dir) = app.path().resource_dir() {
-        #[cfg(target_os = "windows")]
-        let openclaw_binary = "openclaw.cmd";
-        #[cfg(not(target_os = "windows"))]
-        let openclaw_binary = "openclaw";
+/// The openclaw launcher bundled with the app (for production), used
+/// unless a runtime path is configured
+pub(crate) fn bundled_openclaw(app: &AppHandle) -> Option<PathBuf> {
+    let resource_dir = app.path().resource_dir().ok().filter(|_| !crate::gateway::runtime::is_configured())?;
+    #[cfg(target_os = "windows")]
+    let openclaw_binary = "openclaw.cmd";
+    #[cfg(not(target_os = "windows"))]
+    let openclaw_binary = "openclaw";
 
-        let bundled_path = resource_dir.join("openclaw").join(openclaw_binary);
-        if bundled_path.exists() {
-            return Ok(bundled_path);
-        }
+    let bundled_path = resource_dir.join("openclaw").join(openclaw_binary);
+    bundled_path.exists().then_some(bundled_path)
+}
+
+fn get_openclaw_path(app: &AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    if let Some(bundled_path) = bundled_openclaw(app) {
+        return Ok(bundled_path);
     }
 
     // Development: helix-runtime is the source, use node to run openclaw.mjs directly
-    let openclaw_dir = get_openclaw_directory()?;
+    let openclaw_dir = crate::gateway::runtime::runtime_dir()?;
     let openclaw_mjs = openclaw_dir.join("openclaw.mjs");
 
     if openclaw_mjs.exists() {
-        // Return full path to node.exe - PATH may not be available in Tauri context
+        // Full path to node - PATH may not be available in Tauri context
         log::info!("Found openclaw.mjs at: {:?}", openclaw_mjs);
-
-        #[cfg(target_os = "windows")]
-        {
-            // Try common node installation paths on Windows
-            let node_paths = [
-                "C:\\Program Files\\nodejs\\node.exe",
-                "C:\\Program Files (x86)\\nodejs\\node.exe",
-            ];
-            for path in node_paths {
-                let node_path = std::path::PathBuf::from(path);
-                if node_path.exists() {
-                    log::info!("Using node at: {:?}", node_path);
-                    return Ok(node_path);
-                }
-            }
-            // Fallback to node in PATH
-            return Ok(std::path::PathBuf::from("node"));
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        return Ok(std::path::PathBuf::from("node"));
+        return crate::gateway::node::require();
     }
 
     #[cfg(target_os = "windows")]
@@ -438,110 +1447,126 @@ fn get_openclaw_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     }
 }
 
-fn get_openclaw_directory() -> Result<std::path::PathBuf, String> {
-    // Try to find helix-runtime relative to the executable
-    // Release binary is at: helix-desktop/src-tauri/target/release/helix-desktop.exe
-    // We need to go up to find: Helix/helix-runtime
-    if let Ok(exe_path) = std::env::current_exe() {
-        // From exe: target/release/helix-desktop.exe
-        // Go up 4 levels: release -> target -> src-tauri -> helix-desktop -> Helix
-        // Then look for helix-runtime sibling
-        if let Some(exe_dir) = exe_path.parent() {
-            // Try going up 4 levels (for release build structure)
-            let helix_root = exe_dir
-                .join("..")     // target
-                .join("..")     // src-tauri
-                .join("..")     // helix-desktop
-                .join("..");    // Helix (root)
-
-            let dev_path = helix_root.join("helix-runtime");
-            if dev_path.exists() {
-                log::info!("Found helix-runtime at (exe relative): {:?}", dev_path);
-                return Ok(dev_path.canonicalize().map_err(|e| e.to_string())?);
-            }
-
-            // Also try from exe_dir directly (in case structure is different)
-            let alt_path = exe_dir.join("..").join("..").join("helix-runtime");
-            if alt_path.exists() {
-                log::info!("Found helix-runtime at (alt): {:?}", alt_path);
-                return Ok(alt_path.canonicalize().map_err(|e| e.to_string())?);
+/// Auto-start gateway on app launch (called from setup)
+pub fn auto_start_gateway(app: &AppHandle) -> Result<(), String> {
+    if let Some(url) = remote_url()? {
+        // Connect without holding up app startup on the handshake
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match connect_remote(&app_handle, &url).await {
+                Ok(started) => log::info!("Gateway connected to remote {}", started.url),
+                Err(e) => auto_start_failed(&app_handle, e),
             }
-        }
+        });
+        auto_start_instances(app);
+        return Ok(());
     }
 
-    // Try CARGO_MANIFEST_DIR for cargo run scenarios
-    if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
-        let dev_path = std::path::PathBuf::from(&manifest_dir)
-            .join("..")
-            .join("..")
-            .join("helix-runtime");
-
-        if dev_path.exists() {
-            log::info!("Found helix-runtime at (manifest): {:?}", dev_path);
-            return Ok(dev_path.canonicalize().map_err(|e| e.to_string())?);
+    // A gateway adopted from the previous run, or one already running on
+    // the expected port, is attached to rather than started again
+    let adopted_port = GATEWAYS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(DEFAULT_INSTANCE)
+        .filter(|g| g.adopted.is_some())
+        .map(|g| g.port);
+    let port = adopted_port.unwrap_or_else(expected_default_port);
+    if adopted_port.is_some() || !is_port_available(port) {
+        log::info!("Gateway already running on port {}", port);
+
+        let mut gateways = GATEWAYS.lock().map_err(|e| e.to_string())?;
+        if let Some(gateway) = gateways.get_mut(DEFAULT_INSTANCE) {
+            gateway.port = port;
+            gateway.url = format!("ws://127.0.0.1:{}", port);
         }
-    }
-
-    // Production: try home directory paths
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
-
-    // Try ~/.helix/helix-runtime
-    let helix_openclaw = home.join(".helix").join("helix-runtime");
-    if helix_openclaw.exists() {
-        log::info!("Found helix-runtime at (home): {:?}", helix_openclaw);
-        return Ok(helix_openclaw);
-    }
-
-    // Try ~/.openclaw
-    let openclaw_home = home.join(".openclaw");
-    if openclaw_home.exists() {
-        log::info!("Found openclaw at: {:?}", openclaw_home);
-        return Ok(openclaw_home);
-    }
+        drop(gateways);
 
-    // Hardcoded fallback for known development path
-    let known_dev_path = std::path::PathBuf::from("C:\\Users\\Specter\\Desktop\\Helix\\helix-runtime");
-    if known_dev_path.exists() {
-        log::info!("Found helix-runtime at (hardcoded): {:?}", known_dev_path);
-        return Ok(known_dev_path);
+        let adopted = adopted_port.is_some();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = attach_running(&app, port, adopted).await {
+                auto_start_failed(&app, e);
+            }
+        });
+    } else {
+        // Start gateway without holding up app startup on its readiness
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match start_gateway(app.clone(), None).await {
+                Ok(result) => {
+                    log::info!("Gateway started successfully on port {}", result.port);
+                }
+                // Don't fail app startup if gateway fails; the UI offers
+                // the way out and the user can start it manually
+                Err(e) => auto_start_failed(&app, e),
+            }
+        });
     }
 
-    log::warn!("Could not find helix-runtime directory");
-    Err("Could not find helix-runtime directory".to_string())
+    auto_start_instances(app);
+    Ok(())
 }
 
-/// Auto-start gateway on app launch (called from setup)
-pub fn auto_start_gateway(app: &AppHandle) -> Result<(), String> {
-    // Check if gateway is already running by probing the port
-    if !is_port_available(DEFAULT_GATEWAY_PORT) {
-        log::info!("Gateway already running on port {}", DEFAULT_GATEWAY_PORT);
-
-        let mut gateway_lock = GATEWAY.lock().map_err(|e| e.to_string())?;
-        if let Some(gateway) = gateway_lock.as_mut() {
-            gateway.port = DEFAULT_GATEWAY_PORT;
-            gateway.url = format!("ws://127.0.0.1:{}", DEFAULT_GATEWAY_PORT);
+/// Use the gateway found on `port` at launch once it passes the handshake.
+/// One adopted from the previous run is ours and stopped when it fails;
+/// anything else listening there is left alone.
+async fn attach_running(app: &AppHandle, port: u16, adopted: bool) -> Result<(), CommandError> {
+    let url = format!("ws://127.0.0.1:{}", port);
+    if let Err(e) = handshake::verify(app, DEFAULT_INSTANCE, &url, false).await {
+        if adopted {
+            if let Err(stop_error) = stop_gateway(app.clone(), None) {
+                log::warn!("Failed to stop unusable gateway: {}", stop_error);
+            }
+        } else if let Ok(mut gateways) = GATEWAYS.lock() {
+            forget(&mut gateways, DEFAULT_INSTANCE);
         }
+        return Err(handshake_failed(DEFAULT_INSTANCE, &url, e));
+    }
 
-        let _ = app.emit("gateway:started", GatewayStarted {
-            port: DEFAULT_GATEWAY_PORT,
-            url: format!("ws://127.0.0.1:{}", DEFAULT_GATEWAY_PORT),
-        });
+    events::record(
+        ServiceKind::Gateway,
+        "attached",
+        format!("Connected to gateway already running on port {}", port),
+        None,
+    );
+    let started = GatewayStarted {
+        instance: DEFAULT_INSTANCE.to_string(),
+        port,
+        url,
+    };
+    let _ = app.emit("gateway:started", started.clone());
+    hooks::fire(HookEvent::GatewayStarted, serde_json::to_value(&started).unwrap_or_default());
+    Ok(())
+}
 
-        return Ok(());
-    }
+/// Tell the UI the default gateway didn't come up at launch, and why
+fn auto_start_failed(app: &AppHandle, error: CommandError) {
+    log::warn!("Failed to auto-start gateway: {}", error);
+    let _ = app.emit("gateway:start_failed", GatewayStartFailed {
+        instance: DEFAULT_INSTANCE.to_string(),
+        error,
+    });
+}
 
-    // Start gateway
-    match start_gateway(app.clone()) {
-        Ok(result) => {
-            log::info!("Gateway started successfully on port {}", result.port);
-            Ok(())
+/// Start the named instances configured with `auto_start`
+fn auto_start_instances(app: &AppHandle) {
+    let Ok(config) = config::get_config() else {
+        return;
+    };
+    for (name, settings) in config.gateway.instances {
+        if !settings.auto_start || name == DEFAULT_INSTANCE {
+            continue;
         }
-        Err(e) => {
-            log::warn!("Failed to auto-start gateway: {}", e);
-            // Don't fail app startup if gateway fails
-            // User can start it manually
-            Ok(())
+        if GATEWAYS.lock().is_ok_and(|gateways| gateways.get(&name).is_some_and(|g| g.adopted.is_some())) {
+            log::info!("Gateway '{}' adopted from the previous run is already running", name);
+            continue;
         }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match start_gateway(app, Some(name.clone())).await {
+                Ok(result) => log::info!("Gateway '{}' started on port {}", name, result.port),
+                Err(e) => log::warn!("Failed to auto-start gateway '{}': {}", name, e),
+            }
+        });
     }
 }
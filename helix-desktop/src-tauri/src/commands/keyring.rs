@@ -1,52 +1,71 @@
 // Secure credential storage commands using system keyring
 
 use keyring::Entry;
+use crate::commands::{CommandError, ErrorCode};
 
 const SERVICE_NAME: &str = "helix-desktop";
 
+/// User credential keys stored by the frontend (KEYRING_KEYS in useKeyring.ts)
+pub const USER_SECRET_KEYS: &[&str] = &[
+    "anthropic_api_key",
+    "openai_api_key",
+    "google_api_key",
+    "discord_webhook_commands",
+    "discord_webhook_api",
+    "discord_webhook_heartbeat",
+    "discord_webhook_alerts",
+    "supabase_key",
+    "email_imap_password",
+    "mqtt_password",
+];
+
+fn keyring_error(context: &str, e: keyring::Error) -> CommandError {
+    CommandError::new(ErrorCode::Keyring, format!("{}: {}", context, e))
+}
+
 #[tauri::command]
-pub fn store_secret(key: String, value: String) -> Result<(), String> {
+pub fn store_secret(key: String, value: String) -> Result<(), CommandError> {
     let entry = Entry::new(SERVICE_NAME, &key)
-        .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
+        .map_err(|e| keyring_error("Failed to create keyring entry", e))?;
 
     entry.set_password(&value)
-        .map_err(|e| format!("Failed to store secret: {}", e))?;
+        .map_err(|e| keyring_error("Failed to store secret", e))?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_secret(key: String) -> Result<Option<String>, String> {
+pub fn get_secret(key: String) -> Result<Option<String>, CommandError> {
     let entry = Entry::new(SERVICE_NAME, &key)
-        .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
+        .map_err(|e| keyring_error("Failed to create keyring entry", e))?;
 
     match entry.get_password() {
         Ok(password) => Ok(Some(password)),
         Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(format!("Failed to retrieve secret: {}", e)),
+        Err(e) => Err(keyring_error("Failed to retrieve secret", e)),
     }
 }
 
 #[tauri::command]
-pub fn delete_secret(key: String) -> Result<(), String> {
+pub fn delete_secret(key: String) -> Result<(), CommandError> {
     let entry = Entry::new(SERVICE_NAME, &key)
-        .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
+        .map_err(|e| keyring_error("Failed to create keyring entry", e))?;
 
     match entry.delete_password() {
         Ok(()) => Ok(()),
         Err(keyring::Error::NoEntry) => Ok(()), // Already deleted
-        Err(e) => Err(format!("Failed to delete secret: {}", e)),
+        Err(e) => Err(keyring_error("Failed to delete secret", e)),
     }
 }
 
 #[tauri::command]
-pub fn has_secret(key: String) -> Result<bool, String> {
+pub fn has_secret(key: String) -> Result<bool, CommandError> {
     let entry = Entry::new(SERVICE_NAME, &key)
-        .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
+        .map_err(|e| keyring_error("Failed to create keyring entry", e))?;
 
     match entry.get_password() {
         Ok(_) => Ok(true),
         Err(keyring::Error::NoEntry) => Ok(false),
-        Err(e) => Err(format!("Failed to check secret: {}", e)),
+        Err(e) => Err(keyring_error("Failed to check secret", e)),
     }
 }
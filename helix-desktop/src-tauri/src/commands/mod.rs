@@ -1,5 +1,8 @@
 // Helix Desktop Commands
 
+pub mod error;
+pub use error::{CommandError, ErrorCode};
+
 pub mod auth;
 pub mod gateway;
 pub mod config;
@@ -9,7 +12,13 @@ pub mod system;
 pub mod discord;
 pub mod psychology;
 pub mod scheduler;
+pub mod scheduler_alerts;
+pub mod scheduler_runner;
+pub mod scheduler_store;
+pub mod scheduler_transfer;
+pub mod preconditions;
 pub mod rust_executables;
+pub mod syntheses;
 
 // Phase C: Desktop Features
 pub mod clipboard;
@@ -0,0 +1,166 @@
+// Service preconditions for scheduler jobs
+//
+// Jobs that talk to the gateway, Supabase or the voice pipeline check
+// those services before they run. Results are cached for a short while so
+// several jobs coming due together don't probe the same service each.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::commands::{auth, gateway, rust_executables, CommandError};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const CACHE_TTL: Duration = Duration::from_secs(30);
+const VOICE_PIPELINE: &str = "voice-pipeline";
+const VOICE_PIPELINE_PORT: u16 = 18791;
+
+/// A service a job needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Precondition {
+    /// OpenClaw gateway answering its health endpoint
+    GatewayHealthy,
+    /// Supabase project reachable with the configured credentials
+    SupabaseReachable,
+    /// voice-pipeline process started and listening
+    VoicePipelineUp,
+}
+
+impl Precondition {
+    pub const ALL: [Precondition; 3] = [
+        Precondition::GatewayHealthy,
+        Precondition::SupabaseReachable,
+        Precondition::VoicePipelineUp,
+    ];
+}
+
+/// Outcome of one precondition check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreconditionCheck {
+    pub precondition: Precondition,
+    pub met: bool,
+    /// Why it is unmet
+    pub detail: Option<String>,
+    /// Seconds since epoch
+    pub checked_at: u64,
+}
+
+static CACHE: LazyLock<Mutex<HashMap<Precondition, (Instant, PreconditionCheck)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn gateway_healthy() -> Result<(), String> {
+    // ws://host:port -> http://host:port/health
+    let url = gateway::get_gateway_url(None).map_err(|e| e.message)?;
+    let base = url
+        .replacen("wss://", "https://", 1)
+        .replacen("ws://", "http://", 1);
+
+    let response = client()?
+        .get(format!("{}/health", base.trim_end_matches('/')))
+        .send()
+        .await
+        .map_err(|e| format!("Gateway not responding: {}", e))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Gateway health check returned HTTP {}", response.status()))
+    }
+}
+
+async fn supabase_reachable() -> Result<(), String> {
+    if !crate::remote::connectivity::is_online() {
+        return Err("Offline".to_string());
+    }
+    let anon_key = auth::get_supabase_anon_key()?;
+    let supabase_url = auth::get_supabase_url()?;
+
+    let response = client()?
+        .get(format!("{}/auth/v1/health", supabase_url))
+        .header("apikey", &anon_key)
+        .send()
+        .await
+        .map_err(|e| format!("Supabase unreachable: {}", e))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase health check returned HTTP {}", response.status()))
+    }
+}
+
+async fn voice_pipeline_up() -> Result<(), String> {
+    if !rust_executables::is_running(VOICE_PIPELINE) {
+        return Err("voice-pipeline is not running".to_string());
+    }
+    tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::net::TcpStream::connect(("127.0.0.1", VOICE_PIPELINE_PORT)),
+    )
+    .await
+    .map_err(|_| "voice-pipeline did not accept a connection in time".to_string())?
+    .map(|_| ())
+    .map_err(|e| format!("voice-pipeline not listening: {}", e))
+}
+
+async fn probe(precondition: Precondition) -> PreconditionCheck {
+    let result = match precondition {
+        Precondition::GatewayHealthy => gateway_healthy().await,
+        Precondition::SupabaseReachable => supabase_reachable().await,
+        Precondition::VoicePipelineUp => voice_pipeline_up().await,
+    };
+    PreconditionCheck {
+        precondition,
+        met: result.is_ok(),
+        detail: result.err(),
+        checked_at: now_secs(),
+    }
+}
+
+/// Check each precondition, reusing results younger than CACHE_TTL
+pub async fn check(preconditions: &[Precondition]) -> Vec<PreconditionCheck> {
+    let mut checks = Vec::with_capacity(preconditions.len());
+    for precondition in preconditions {
+        let cached = CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(precondition).cloned())
+            .filter(|(at, _)| at.elapsed() < CACHE_TTL)
+            .map(|(_, check)| check);
+
+        let check = match cached {
+            Some(check) => check,
+            None => {
+                let check = probe(*precondition).await;
+                if let Ok(mut cache) = CACHE.lock() {
+                    cache.insert(*precondition, (Instant::now(), check.clone()));
+                }
+                check
+            }
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+// Tauri commands
+
+/// Current availability of every service jobs can depend on
+#[tauri::command]
+pub async fn get_service_availability() -> Result<Vec<PreconditionCheck>, CommandError> {
+    Ok(check(&Precondition::ALL).await)
+}
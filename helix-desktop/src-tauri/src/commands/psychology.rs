@@ -1,10 +1,26 @@
 // Psychology layer commands for Helix seven-layer architecture
+//
+// Decay and synthesis run in-process (psychology/decay.rs, synthesis.rs)
+// unless `scripts.python_psychology` sends them to decay.py and
+// synthesis.py; either way a task's output is a ScriptOutput.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::UNIX_EPOCH;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::policy::guardrails::{self, WriteOutcome, WriteSource};
+use crate::policy::workspace_trust;
+use crate::commands::config;
+use crate::psychology::pass::PassLog;
+use crate::psychology::{decay, goals, graph, ikigai, lock, migrations, schema, soul_history, synthesis};
+use crate::scripts::{PythonScript, ScriptOutput};
+use crate::vault;
+use crate::commands::{CommandError, ErrorCode};
+
+/// Time limit for decay/synthesis runs
+const PSYCHOLOGY_SCRIPT_TIMEOUT_SECS: u64 = 300;
 
 /// Response for soul content
 #[derive(Serialize)]
@@ -39,7 +55,7 @@ pub struct MemoryDecayConfig {
 }
 
 /// Layer file mappings
-const LAYER_FILES: &[(&str, &[&str])] = &[
+pub(crate) const LAYER_FILES: &[(&str, &[&str])] = &[
     ("narrative", &["psychology/psyeval.json"]),
     ("emotional", &["psychology/emotional_tags.json"]),
     ("relational", &["psychology/attachments.json", "psychology/trust_map.json"]),
@@ -50,16 +66,34 @@ const LAYER_FILES: &[(&str, &[&str])] = &[
 ];
 
 fn get_helix_dir() -> Result<PathBuf, String> {
-    // Check for HELIX_PROJECT_DIR env var first
-    if let Ok(dir) = std::env::var("HELIX_PROJECT_DIR") {
-        return Ok(PathBuf::from(dir));
+    // HELIX_PROJECT_DIR, else the active account's data directory
+    crate::accounts::data_dir()
+}
+
+/// The Python scripts read psychology files directly and can't decrypt them
+fn ensure_scripts_can_read() -> Result<(), CommandError> {
+    if vault::is_enabled() {
+        return Err(CommandError::new(
+            ErrorCode::VaultLocked,
+            "Psychology scripts are unavailable while psychology data is encrypted",
+        ));
     }
+    Ok(())
+}
 
-    // Fall back to current directory or ~/.helix
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
+/// Vault read/write failures; a locked vault gets its own code
+pub(crate) fn vault_error(context: &str, e: String) -> CommandError {
+    let code = if e == vault::store::LOCKED_MESSAGE {
+        ErrorCode::VaultLocked
+    } else {
+        ErrorCode::Io
+    };
+    CommandError::new(code, format!("{}: {}", context, e))
+}
 
-    Ok(home.join(".helix"))
+fn unknown_layer(layer: &str) -> CommandError {
+    CommandError::not_found(format!("Unknown layer: {}", layer))
+        .with_details(serde_json::json!({ "layer": layer }))
 }
 
 fn get_file_modified_time(path: &PathBuf) -> u64 {
@@ -72,12 +106,12 @@ fn get_file_modified_time(path: &PathBuf) -> u64 {
 }
 
 #[tauri::command]
-pub fn get_soul() -> Result<SoulResponse, String> {
+pub fn get_soul() -> Result<SoulResponse, CommandError> {
     let helix_dir = get_helix_dir()?;
     let soul_path = helix_dir.join("soul").join("HELIX_SOUL.md");
 
-    let content = fs::read_to_string(&soul_path)
-        .map_err(|e| format!("Failed to read soul file: {}", e))?;
+    let content = vault::read_to_string(&soul_path)
+        .map_err(|e| vault_error("Failed to read soul file", e))?;
 
     let last_modified = get_file_modified_time(&soul_path);
 
@@ -87,23 +121,74 @@ pub fn get_soul() -> Result<SoulResponse, String> {
     })
 }
 
+/// Replace the soul document. `source` marks an automated write, which is
+/// checked against the soul guardrails (see policy/guardrails.rs). Written
+/// content is kept in the soul history (psychology/soul_history.rs).
 #[tauri::command]
-pub fn update_soul(content: String) -> Result<(), String> {
+pub fn update_soul(
+    app: tauri::AppHandle,
+    content: String,
+    source: Option<WriteSource>,
+) -> Result<WriteOutcome, CommandError> {
     let helix_dir = get_helix_dir()?;
-    let soul_path = helix_dir.join("soul").join("HELIX_SOUL.md");
+    let previous = soul_history::read_current(&helix_dir)
+        .map_err(|e| vault_error("Failed to read soul file", e))?;
+    let writes = [(guardrails::SOUL_FILE, content)];
+
+    let outcome = match source {
+        Some(source) => guardrails::apply(&app, source, &helix_dir, &writes)?,
+        None => write_files(&helix_dir, &writes)?,
+    };
+    let outcome = outcome
+        .into_iter()
+        .next()
+        .ok_or_else(|| CommandError::internal("Soul write produced no outcome"))?;
+
+    // Queued writes are recorded when approved
+    if let WriteOutcome::Written { .. } = outcome {
+        if let Err(e) = soul_history::record(&helix_dir, previous.as_deref(), &writes[0].1) {
+            log::warn!("Failed to record soul version: {}", e);
+        }
+    }
+    Ok(outcome)
+}
 
-    // Ensure directory exists
-    if let Some(parent) = soul_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create soul directory: {}", e))?;
+/// Unguarded writes, for edits made by the user. Fails with `busy` while
+/// a decay or synthesis pass holds the psychology lock.
+pub(crate) fn write_files(helix_dir: &Path, writes: &[(&str, String)]) -> Result<Vec<WriteOutcome>, CommandError> {
+    let _lock = lock::acquire(helix_dir, lock::WRITE_WAIT)?;
+    let mut outcomes = Vec::new();
+    for (file_rel, content) in writes {
+        let file_path = helix_dir.join(file_rel);
+
+        // Ensure directory exists
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        vault::write(&file_path, content)
+            .map_err(|e| vault_error(&format!("Failed to write {}", file_rel), e))?;
+        outcomes.push(WriteOutcome::Written { path: file_rel.to_string() });
     }
+    Ok(outcomes)
+}
+
+/// Read and parse one layer file, upgrading it to the current schema
+pub(crate) fn read_layer_file(helix_dir: &Path, file_rel: &str) -> Result<serde_json::Value, CommandError> {
+    let content = vault::read_to_string(&helix_dir.join(file_rel))
+        .map_err(|e| vault_error(&format!("Failed to read {}", file_rel), e))?;
+
+    let data: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", file_rel, e))?;
 
-    fs::write(&soul_path, content)
-        .map_err(|e| format!("Failed to write soul file: {}", e))
+    // Older files are upgraded to the current schema on read
+    migrations::upgrade(helix_dir, file_rel, data)
+        .map_err(|e| vault_error(&format!("Failed to migrate {}", file_rel), e))
 }
 
 #[tauri::command]
-pub fn get_layer(layer: String) -> Result<LayerResponse, String> {
+pub fn get_layer(layer: String) -> Result<LayerResponse, CommandError> {
     let helix_dir = get_helix_dir()?;
 
     // Find the layer files
@@ -111,7 +196,7 @@ pub fn get_layer(layer: String) -> Result<LayerResponse, String> {
         .iter()
         .find(|(name, _)| *name == layer)
         .map(|(_, files)| files.to_vec())
-        .ok_or_else(|| format!("Unknown layer: {}", layer))?;
+        .ok_or_else(|| unknown_layer(&layer))?;
 
     if files.is_empty() {
         return Ok(LayerResponse {
@@ -129,11 +214,7 @@ pub fn get_layer(layer: String) -> Result<LayerResponse, String> {
         let file_path = helix_dir.join(file_rel);
 
         if file_path.exists() {
-            let content = fs::read_to_string(&file_path)
-                .map_err(|e| format!("Failed to read {}: {}", file_rel, e))?;
-
-            let data: serde_json::Value = serde_json::from_str(&content)
-                .map_err(|e| format!("Failed to parse {}: {}", file_rel, e))?;
+            let data = read_layer_file(&helix_dir, file_rel)?;
 
             // Get file name without extension as key
             let key = PathBuf::from(file_rel)
@@ -159,7 +240,7 @@ pub fn get_layer(layer: String) -> Result<LayerResponse, String> {
 }
 
 #[tauri::command]
-pub fn get_all_layers() -> Result<HashMap<String, LayerResponse>, String> {
+pub fn get_all_layers() -> Result<HashMap<String, LayerResponse>, CommandError> {
     let mut result = HashMap::new();
 
     for (layer_name, _) in LAYER_FILES {
@@ -176,144 +257,333 @@ pub fn get_all_layers() -> Result<HashMap<String, LayerResponse>, String> {
     Ok(result)
 }
 
-#[tauri::command]
-pub fn update_layer(layer: String, data: serde_json::Value) -> Result<(), String> {
-    let helix_dir = get_helix_dir()?;
-
-    // Find the layer files
-    let files: Vec<&str> = LAYER_FILES
+/// Split `data` into the layer's file writes. A single-file layer's data is
+/// the file content; a multi-file layer's data is keyed by file stem, and
+/// files it leaves out aren't written.
+fn layer_writes<'a>(layer: &str, data: &'a serde_json::Value) -> Result<Vec<(&'static str, &'a serde_json::Value)>, CommandError> {
+    let files: Vec<&'static str> = LAYER_FILES
         .iter()
         .find(|(name, _)| *name == layer)
         .map(|(_, files)| files.to_vec())
-        .ok_or_else(|| format!("Unknown layer: {}", layer))?;
+        .ok_or_else(|| unknown_layer(layer))?;
 
     if files.is_empty() {
-        return Err("Cannot update integration layer directly".to_string());
+        return Err(CommandError::invalid_input("Cannot update integration layer directly"));
     }
 
-    // For single-file layers, write directly
-    // For multi-file layers, expect data to be keyed by file name
     if files.len() == 1 {
-        let file_path = helix_dir.join(files[0]);
-
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        }
-
-        let content = serde_json::to_string_pretty(&data)
-            .map_err(|e| format!("Failed to serialize data: {}", e))?;
-
-        fs::write(&file_path, content)
-            .map_err(|e| format!("Failed to write file: {}", e))
-    } else {
-        // Multi-file layer: data should be an object with keys matching file stems
-        let data_obj = data.as_object()
-            .ok_or_else(|| "Data must be an object for multi-file layers".to_string())?;
-
-        for file_rel in files {
-            let file_path = helix_dir.join(file_rel);
-
-            let key = PathBuf::from(file_rel)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            if let Some(file_data) = data_obj.get(&key) {
-                if let Some(parent) = file_path.parent() {
-                    fs::create_dir_all(parent)
-                        .map_err(|e| format!("Failed to create directory: {}", e))?;
-                }
+        return Ok(vec![(files[0], data)]);
+    }
 
-                let content = serde_json::to_string_pretty(file_data)
-                    .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    // Multi-file layer: data should be an object with keys matching file stems
+    let data_obj = data.as_object()
+        .ok_or_else(|| CommandError::invalid_input("Data must be an object for multi-file layers"))?;
 
-                fs::write(&file_path, content)
-                    .map_err(|e| format!("Failed to write {}: {}", file_rel, e))?;
-            }
+    let mut writes = Vec::new();
+    for file_rel in files {
+        let key = PathBuf::from(file_rel)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        if let Some(file_data) = data_obj.get(&key) {
+            writes.push((file_rel, file_data));
         }
-
-        Ok(())
     }
+    Ok(writes)
+}
+
+fn schema_violations(writes: &[(&str, &serde_json::Value)]) -> Vec<schema::SchemaViolation> {
+    writes
+        .iter()
+        .flat_map(|(file_rel, data)| schema::validate(file_rel, data))
+        .collect()
 }
 
+/// Replace a layer's files. `source` marks an automated write, which is
+/// checked against the soul guardrails (see policy/guardrails.rs). Nothing
+/// is written unless every file matches its schema (psychology/schema.rs).
 #[tauri::command]
-pub fn run_decay(dry_run: bool) -> Result<String, String> {
+pub fn update_layer(
+    app: tauri::AppHandle,
+    layer: String,
+    data: serde_json::Value,
+    source: Option<WriteSource>,
+) -> Result<Vec<WriteOutcome>, CommandError> {
     let helix_dir = get_helix_dir()?;
-    let script_path = helix_dir.join("scripts").join("decay.py");
+    let files = layer_writes(&layer, &data)?;
 
-    if !script_path.exists() {
-        return Err("decay.py script not found".to_string());
+    let violations = schema_violations(&files);
+    if !violations.is_empty() {
+        return Err(CommandError::invalid_input(format!(
+            "{} layer data doesn't match its schema ({} problem(s))",
+            layer,
+            violations.len()
+        ))
+        .with_details(serde_json::json!({ "layer": layer, "violations": violations })));
     }
 
-    let mut cmd = std::process::Command::new("python3");
-    cmd.arg(&script_path);
-
-    if dry_run {
-        cmd.env("HELIX_DRY_RUN", "true");
+    let mut writes = Vec::with_capacity(files.len());
+    for (file_rel, file_data) in files {
+        let content = serde_json::to_string_pretty(file_data)
+            .map_err(|e| format!("Failed to serialize data: {}", e))?;
+        writes.push((file_rel, content));
     }
 
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to run decay script: {}", e))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    match source {
+        Some(source) => guardrails::apply(&app, source, &helix_dir, &writes),
+        None => write_files(&helix_dir, &writes),
     }
 }
 
+/// Result of checking layer data against its schemas
+#[derive(Serialize)]
+pub struct LayerValidation {
+    pub layer: String,
+    pub valid: bool,
+    pub violations: Vec<schema::SchemaViolation>,
+}
+
+/// Check data for update_layer without writing it
 #[tauri::command]
-pub fn run_synthesis(dry_run: bool) -> Result<String, String> {
+pub fn validate_layer(layer: String, data: serde_json::Value) -> Result<LayerValidation, CommandError> {
+    let violations = schema_violations(&layer_writes(&layer, &data)?);
+    Ok(LayerValidation {
+        layer,
+        valid: violations.is_empty(),
+        violations,
+    })
+}
+
+/// Path of a psychology script; errors if missing, if the vault is on or
+/// if the workspace's scripts aren't trusted as they are
+pub(crate) fn psychology_script(name: &str) -> Result<PathBuf, CommandError> {
     let helix_dir = get_helix_dir()?;
-    let script_path = helix_dir.join("scripts").join("synthesis.py");
+    let script_path = helix_dir.join("scripts").join(name);
 
     if !script_path.exists() {
-        return Err("synthesis.py script not found".to_string());
+        return Err(CommandError::not_found(format!("{} script not found", name)));
     }
+    ensure_scripts_can_read()?;
+    workspace_trust::ensure_trusted(&helix_dir)?;
 
-    let mut cmd = std::process::Command::new("python3");
-    cmd.arg(&script_path);
+    Ok(script_path)
+}
+
+/// Task output for a finished script: the structured ScriptOutput as JSON
+fn script_task_output(output: ScriptOutput) -> Result<String, CommandError> {
+    let output = output.into_result()?;
+    serde_json::to_string(&output)
+        .map_err(|e| CommandError::internal(format!("Failed to serialize script output: {}", e)))
+}
 
-    if dry_run {
-        cmd.env("HELIX_DRY_RUN", "true");
+/// Add a section to a script's JSON result (the integration report)
+fn add_report_section<T: Serialize>(output: &mut ScriptOutput, key: &str, value: &T) {
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    match output.result.as_mut().and_then(serde_json::Value::as_object_mut) {
+        Some(report) => {
+            report.insert(key.to_string(), value);
+        }
+        None => {
+            let mut report = serde_json::Map::new();
+            if let Some(previous) = output.result.take() {
+                report.insert("report".to_string(), previous);
+            }
+            report.insert(key.to_string(), value);
+            output.result = Some(serde_json::Value::Object(report));
+        }
     }
+}
 
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to run synthesis script: {}", e))?;
+/// Whether decay and synthesis go to the Python scripts
+pub(crate) fn python_psychology() -> bool {
+    config::get_config().is_ok_and(|config| config.scripts.python_psychology)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Run a built-in pass under the psychology lock, off the async runtime,
+/// handing each line of its log to `on_line` as the pass writes it. Reports
+/// as `script` would.
+async fn native_pass<T, F>(
+    script: &'static str,
+    helix_dir: PathBuf,
+    pass: F,
+    mut on_line: impl FnMut(&str),
+) -> Result<ScriptOutput, CommandError>
+where
+    T: Serialize,
+    F: FnOnce(&Path, &mut PassLog) -> Result<T, String> + Send + 'static,
+{
+    let (line_tx, mut line_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let pass = tokio::task::spawn_blocking(move || {
+        let _lock = lock::acquire(&helix_dir, lock::PASS_WAIT)?;
+        let started = Instant::now();
+        let mut log = PassLog::new(|line: &str| {
+            let _ = line_tx.send(line.to_string());
+        });
+        let report = pass(&helix_dir, &mut log);
+        Ok(ScriptOutput::native(script, started, log.into_lines(), report))
+    });
+    // Ends once the pass drops its log
+    while let Some(line) = line_rx.recv().await {
+        on_line(&line);
     }
+    pass.await.map_err(|e| CommandError::internal(e.to_string()))?
 }
 
+/// The built-in decay pass, as a script would report it
+pub(crate) async fn native_decay(
+    helix_dir: PathBuf,
+    dry_run: bool,
+    on_line: impl FnMut(&str),
+) -> Result<ScriptOutput, CommandError> {
+    let settings = decay::DecaySettings::load(dry_run)?;
+    native_pass("decay", helix_dir, move |helix_dir, log| decay::run(helix_dir, &settings, log), on_line).await
+}
+
+/// The built-in synthesis pass in one of synthesis.py's modes, as a script
+/// would report it
+pub(crate) async fn native_synthesis(
+    helix_dir: PathBuf,
+    mode: &str,
+    dry_run: bool,
+    on_line: impl FnMut(&str),
+) -> Result<ScriptOutput, CommandError> {
+    let mode = mode.to_string();
+    native_pass(
+        "synthesis",
+        helix_dir,
+        move |helix_dir, log| synthesis::run(helix_dir, &mode, dry_run, log),
+        on_line,
+    )
+    .await
+}
+
+/// Run a decay pass as a background task; returns the task id
 #[tauri::command]
-pub fn restore_from_decay() -> Result<String, String> {
+pub async fn run_decay(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    dry_run: bool,
+) -> Result<String, CommandError> {
+    let script = if python_psychology() {
+        let mut script = PythonScript::new(psychology_script("decay.py")?);
+        if dry_run {
+            script = script.env("HELIX_DRY_RUN", "true");
+        }
+        Some(script)
+    } else {
+        None
+    };
+
+    let timeout = Duration::from_secs(PSYCHOLOGY_SCRIPT_TIMEOUT_SECS);
     let helix_dir = get_helix_dir()?;
-    let script_path = helix_dir.join("scripts").join("decay.py");
+    let guard_app = app.clone();
+
+    state
+        .task_manager
+        .spawn(app, "decay", Some(timeout), |_task| async move {
+            // Changes to protected content are rolled back once the run ends
+            let _snapshot = guardrails::Snapshot::capture(&guard_app, WriteSource::Decay, &helix_dir);
+            let output = match script {
+                Some(script) => script.run().await?,
+                None => native_decay(helix_dir.clone(), dry_run, |_| {}).await?,
+            };
+            script_task_output(output)
+        })
+        .map_err(Into::into)
+}
 
-    if !script_path.exists() {
-        return Err("decay.py script not found".to_string());
-    }
+/// Run a synthesis pass as a background task; returns the task id
+#[tauri::command]
+pub async fn run_synthesis(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    dry_run: bool,
+) -> Result<String, CommandError> {
+    let script = if python_psychology() {
+        let mut script = PythonScript::new(psychology_script("synthesis.py")?);
+        if dry_run {
+            script = script.env("HELIX_DRY_RUN", "true");
+        }
+        Some(script)
+    } else {
+        None
+    };
+
+    let timeout = Duration::from_secs(PSYCHOLOGY_SCRIPT_TIMEOUT_SECS);
+    let helix_dir = get_helix_dir()?;
+    let guard_app = app.clone();
+
+    state
+        .task_manager
+        .spawn(app, "synthesis", Some(timeout), |task| async move {
+            // Changes to protected content are rolled back once the run ends
+            let _snapshot = guardrails::Snapshot::capture(&guard_app, WriteSource::Synthesis, &helix_dir);
+            let mut output = match script {
+                Some(script) => {
+                    task.progress("synthesizing", None, Some("Running synthesis.py".to_string()));
+                    script.run().await?
+                }
+                None => {
+                    task.progress("synthesizing", None, Some("Synthesizing across layers".to_string()));
+                    native_synthesis(helix_dir.clone(), "full", dry_run, |_| {}).await?
+                }
+            }
+            .into_result()?;
+            match goals::goal_stats() {
+                Ok(stats) => add_report_section(&mut output, "goal_stats", &stats),
+                Err(e) => log::warn!("Goal stats left out of synthesis report: {}", e),
+            }
+            task.progress("purpose", None, Some("Scoring purpose alignment".to_string()));
+            match ikigai::analyze(dry_run).await {
+                Ok(alignment) => add_report_section(&mut output, "purpose_alignment", &alignment),
+                Err(e) => log::warn!("Purpose alignment left out of synthesis report: {}", e),
+            }
+            let output = script_task_output(output)?;
 
-    let output = std::process::Command::new("python3")
-        .arg(&script_path)
-        .arg("--restore")
-        .output()
-        .map_err(|e| format!("Failed to run restore: {}", e))?;
+            if !dry_run {
+                task.progress("relationships", None, Some("Updating relationship graph".to_string()));
+                match graph::update_from_memories().await {
+                    Ok(count) => log::info!("Relationship graph updated from {} memories", count),
+                    Err(e) => log::warn!("Relationship graph update skipped: {}", e),
+                }
+            }
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+            task.progress("complete", Some(100.0), None);
+            Ok::<_, CommandError>(output)
+        })
+        .map_err(Into::into)
+}
+
+/// Put back what soft decay faded
+#[tauri::command]
+pub async fn restore_from_decay() -> Result<ScriptOutput, CommandError> {
+    if python_psychology() {
+        return PythonScript::new(psychology_script("decay.py")?)
+            .arg("--restore")
+            .run()
+            .await
+            .map_err(CommandError::process)?
+            .into_result();
     }
+
+    let helix_dir = get_helix_dir()?;
+    tokio::task::spawn_blocking(move || {
+        let _lock = lock::acquire(&helix_dir, lock::WRITE_WAIT)?;
+        let started = Instant::now();
+        let mut log = PassLog::quiet();
+        let report = decay::restore(&helix_dir, &mut log);
+        ScriptOutput::native("decay", started, log.into_lines(), report).into_result()
+    })
+    .await
+    .map_err(|e| CommandError::internal(e.to_string()))?
 }
 
 #[tauri::command]
-pub fn get_layer_status() -> Result<Vec<LayerStatus>, String> {
+pub fn get_layer_status() -> Result<Vec<LayerStatus>, CommandError> {
     let helix_dir = get_helix_dir()?;
     let mut status = Vec::new();
 
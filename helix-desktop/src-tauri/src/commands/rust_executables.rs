@@ -1,17 +1,70 @@
 // Rust Executables Integration
 // Manages spawning and monitoring of CPU-intensive Rust binaries
+// Long-running ones hold a pid file, so one isn't started twice and a
+// crashed run's are stopped at startup
 
 use std::collections::HashMap;
 use std::process::{Child, Command};
 use std::sync::Mutex;
+use std::time::Duration;
+use helix_shared::pidfile::{self, AlreadyRunning, PidFile};
+use helix_shared::tokenizer;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 
+use crate::accounts::tiers::{self, Feature};
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{CommandError, ErrorCode};
+use crate::privacy::residency::{self, DataFeature};
+use crate::tasks::run_process;
+
 lazy_static::lazy_static! {
-    static ref RUNNING_PROCESSES: Mutex<HashMap<String, Child>> =
+    static ref RUNNING_PROCESSES: Mutex<HashMap<String, HelperProcess>> =
         Mutex::new(HashMap::new());
 }
 
+/// A long-running executable the app started
+struct HelperProcess {
+    child: Child,
+    /// Removed with the entry
+    _pidfile: Option<PidFile>,
+}
+
+/// Refuse to start `name` while a copy of it holds its pid file
+fn ensure_not_running(name: &str) -> Result<(), CommandError> {
+    match pidfile::holder(name) {
+        Some(holder) => Err(AlreadyRunning(holder).into()),
+        None => Ok(()),
+    }
+}
+
+/// Track a spawned executable under `name` and write its pid file. Should a
+/// copy have taken the name since ensure_not_running, this one is killed.
+fn track(
+    processes: &mut HashMap<String, HelperProcess>,
+    name: &str,
+    mut child: Child,
+    port: Option<u16>,
+) -> Result<(), CommandError> {
+    let pidfile = match PidFile::spawned(name, child.id(), port).map_err(|e| e.downcast::<AlreadyRunning>()) {
+        Ok(pidfile) => Some(pidfile),
+        Err(Ok(running)) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(running.into());
+        }
+        Err(Err(e)) => {
+            log::warn!("{} runs without a pid file: {:#}", name, e);
+            None
+        }
+    };
+    processes.insert(name.to_string(), HelperProcess { child, _pidfile: pidfile });
+    Ok(())
+}
+
+/// Executables whose start and stop go in the activity log
+const SYNC_EXES: &[&str] = &["sync-coordinator", "obsidian-sync"];
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RustExeStatus {
     pub name: String,
@@ -20,44 +73,170 @@ pub struct RustExeStatus {
     pub pid: Option<u32>,
 }
 
+/// Time limit for a memory synthesis run
+const MEMORY_SYNTHESIS_TIMEOUT_SECS: u64 = 900;
+
 /// Start Memory Synthesis engine
 /// Performs CPU-intensive pattern recognition on memories from Supabase
+/// Runs as a cancellable background task; returns the task id
+#[command]
+pub async fn start_memory_synthesis(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    user_id: String,
+) -> Result<String, CommandError> {
+    let binary_path = find_binary("memory-synthesis")?;
+
+    let mut cmd = tokio::process::Command::new(&binary_path);
+    cmd.arg("--user-id").arg(&user_id);
+    // Pattern texts are capped in tokens, counted with the bundled vocabulary
+    if let Some(dir) = tokenizer::bundled_dir() {
+        cmd.env(tokenizer::TOKENIZER_DIR_ENV, dir);
+    }
+
+    let timeout = Duration::from_secs(MEMORY_SYNTHESIS_TIMEOUT_SECS);
+
+    state.task_manager.spawn(app, "memory-synthesis", Some(timeout), |task| async move {
+        task.progress(
+            "synthesizing",
+            None,
+            Some(format!("Detecting memory patterns for user {}", user_id)),
+        );
+
+        let output = run_process(cmd)
+            .await
+            .map_err(|e| format!("Failed to spawn memory-synthesis: {}", e))?;
+
+        if output.status.success() {
+            task.progress("complete", Some(100.0), None);
+            Ok(format!("Memory synthesis completed for user {}", user_id))
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    })
+    .map_err(Into::into)
+}
+
+/// Tracking name of `memory-synthesis --serve`, which answers synthesis reads
+pub(crate) const SYNTHESIS_SERVER: &str = "memory-synthesis-server";
+pub(crate) const SYNTHESIS_SERVER_PORT: u16 = 18788;
+/// Environment variable the synthesis server reads its token from
+const SYNTHESIS_TOKEN_ENV: &str = "HELIX_SYNTHESIS_TOKEN";
+
+/// Token of the synthesis server this launch started. Kept in memory only;
+/// a server left over from an earlier launch can't be called.
+static SYNTHESIS_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+/// Bearer token for the synthesis server, if this launch started it
+pub(crate) fn synthesis_token() -> Option<String> {
+    SYNTHESIS_TOKEN.lock().ok().and_then(|token| token.clone())
+}
+
+/// Start the Memory Synthesis server
+/// Serves pages of stored syntheses to list_syntheses, to callers with the
+/// token generated here
 #[command]
-pub async fn start_memory_synthesis(user_id: String) -> Result<String, String> {
+pub async fn start_memory_synthesis_server(port: Option<u16>) -> Result<String, CommandError> {
     let binary_path = find_binary("memory-synthesis")?;
+    ensure_not_running(SYNTHESIS_SERVER)?;
+    let port_num = port.unwrap_or(SYNTHESIS_SERVER_PORT);
+    let token = hex::encode(rand::random::<[u8; 32]>());
 
     let child = Command::new(&binary_path)
-        .arg("--user-id")
-        .arg(&user_id)
+        .arg("--serve")
+        .arg("--port")
+        .arg(port_num.to_string())
+        .env(SYNTHESIS_TOKEN_ENV, &token)
         .spawn()
-        .map_err(|e| format!("Failed to spawn memory-synthesis: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to spawn memory-synthesis server: {}", e)))?;
 
     let pid = child.id();
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
-    processes.insert("memory-synthesis".to_string(), child);
+    track(&mut processes, SYNTHESIS_SERVER, child, Some(port_num))?;
+    *SYNTHESIS_TOKEN.lock().map_err(|e| e.to_string())? = Some(token);
 
     Ok(format!(
-        "Memory synthesis started with PID {} for user {}",
-        pid, user_id
+        "Memory synthesis server started on port {} with PID {}",
+        port_num, pid
     ))
 }
 
+/// Time limit for importing a chat export archive
+const MEMORY_IMPORT_TIMEOUT_SECS: u64 = 1800;
+
+/// Import a ChatGPT or Claude data export into memories
+/// Parses the archive, converts conversations into deduplicated memories
+/// and writes them to Supabase (unless dry_run). Runs as a background task;
+/// the task output is the JSON import summary. Returns the task id.
+#[command]
+pub async fn import_chat_export(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    user_id: String,
+    archive_path: String,
+    dry_run: Option<bool>,
+) -> Result<String, CommandError> {
+    if !std::path::Path::new(&archive_path).is_file() {
+        return Err(CommandError::not_found(format!("Archive not found: {}", archive_path)));
+    }
+
+    let binary_path = find_binary("memory-import")?;
+
+    let mut cmd = tokio::process::Command::new(&binary_path);
+    cmd.arg("--user-id")
+        .arg(&user_id)
+        .arg("--archive")
+        .arg(&archive_path);
+
+    if dry_run.unwrap_or(false) {
+        cmd.arg("--dry-run");
+    }
+
+    let timeout = Duration::from_secs(MEMORY_IMPORT_TIMEOUT_SECS);
+
+    state.task_manager.spawn(app, "memory-import", Some(timeout), |task| async move {
+        task.progress("importing", None, Some("Importing conversations".to_string()));
+
+        let output = run_process(cmd)
+            .await
+            .map_err(|e| format!("Failed to spawn memory-import: {}", e))?;
+
+        if output.status.success() {
+            task.progress("complete", Some(100.0), None);
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    })
+    .map_err(Into::into)
+}
+
 /// Start Skill Execution Sandbox
-/// WASM-based secure sandbox for skill execution
+/// WASM-based secure sandbox for skill execution, on loopback and only
+/// answering requests that carry the install's sandbox token. It runs a
+/// skill only with an approval signed by this app launch.
 #[command]
-pub async fn start_skill_sandbox(port: Option<u16>) -> Result<String, String> {
+pub async fn start_skill_sandbox(port: Option<u16>) -> Result<String, CommandError> {
     let binary_path = find_binary("skill-sandbox")?;
+    ensure_not_running("skill-sandbox")?;
     let port_num = port.unwrap_or(18790);
 
+    let token = crate::policy::skill_approvals::sandbox_token()?;
+
     let child = Command::new(&binary_path)
         .arg("--port")
         .arg(port_num.to_string())
+        .env(crate::policy::skill_approvals::SANDBOX_TOKEN_ENV, token)
+        .env(
+            crate::policy::skill_approvals::APPROVAL_KEY_ENV,
+            crate::policy::skill_approvals::approval_key(),
+        )
         .spawn()
-        .map_err(|e| format!("Failed to spawn skill-sandbox: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to spawn skill-sandbox: {}", e)))?;
 
     let pid = child.id();
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
-    processes.insert("skill-sandbox".to_string(), child);
+    track(&mut processes, "skill-sandbox", child, Some(port_num))?;
 
     Ok(format!(
         "Skill sandbox started on port {} with PID {}",
@@ -66,56 +245,178 @@ pub async fn start_skill_sandbox(port: Option<u16>) -> Result<String, String> {
 }
 
 /// Start Voice Processing Pipeline
-/// Handles audio processing and voice integration
+/// Handles audio processing and voice integration; transcribes with the
+/// local whisper.cpp engine when residency keeps voice on the device
 #[command]
-pub async fn start_voice_pipeline(port: Option<u16>) -> Result<String, String> {
+pub async fn start_voice_pipeline(port: Option<u16>) -> Result<String, CommandError> {
+    let engine = if residency::allows(DataFeature::VoiceTranscription) { "cloud" } else { "local" };
     let binary_path = find_binary("voice-pipeline")?;
+    ensure_not_running("voice-pipeline")?;
     let port_num = port.unwrap_or(18791);
 
     let child = Command::new(&binary_path)
         .arg("--port")
         .arg(port_num.to_string())
+        .arg("--engine")
+        .arg(engine)
         .spawn()
-        .map_err(|e| format!("Failed to spawn voice-pipeline: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to spawn voice-pipeline: {}", e)))?;
 
     let pid = child.id();
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
-    processes.insert("voice-pipeline".to_string(), child);
+    track(&mut processes, "voice-pipeline", child, Some(port_num))?;
 
     Ok(format!(
-        "Voice pipeline started on port {} with PID {}",
-        port_num, pid
+        "Voice pipeline started on port {} with PID {} ({} transcription)",
+        port_num, pid, engine
     ))
 }
 
 /// Start Sync Coordinator
 /// Manages synchronization across multiple Helix instances
 #[command]
-pub async fn start_sync_coordinator(port: Option<u16>) -> Result<String, String> {
+pub async fn start_sync_coordinator(port: Option<u16>) -> Result<String, CommandError> {
+    tiers::require(Feature::SyncCoordinator).await?;
+    residency::require(DataFeature::Sync)?;
+    crate::remote::connectivity::require_online("Sync coordinator")?;
+
     let binary_path = find_binary("sync-coordinator")?;
+    ensure_not_running("sync-coordinator")?;
     let port_num = port.unwrap_or(18792);
 
     let child = Command::new(&binary_path)
         .arg("--port")
         .arg(port_num.to_string())
         .spawn()
-        .map_err(|e| format!("Failed to spawn sync-coordinator: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to spawn sync-coordinator: {}", e)))?;
 
     let pid = child.id();
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
-    processes.insert("sync-coordinator".to_string(), child);
-
+    track(&mut processes, "sync-coordinator", child, Some(port_num))?;
+
+    events::record(
+        ServiceKind::Sync,
+        "started",
+        format!("Sync coordinator started on port {}", port_num),
+        None,
+    );
     Ok(format!(
         "Sync coordinator started on port {} with PID {}",
         port_num, pid
     ))
 }
 
+/// Start Obsidian vault sync
+/// Exports memories, syntheses and the integration report as Markdown notes
+/// into the chosen vault and ingests new or edited notes back as memories,
+/// re-syncing whenever notes change
+#[command]
+pub async fn start_obsidian_sync(user_id: String, vault_path: String) -> Result<String, CommandError> {
+    if !std::path::Path::new(&vault_path).is_dir() {
+        return Err(CommandError::not_found(format!("Vault folder not found: {}", vault_path)));
+    }
+
+    let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
+    if processes.contains_key("obsidian-sync") {
+        return Err(CommandError::new(ErrorCode::Conflict, "Obsidian sync is already running"));
+    }
+
+    let binary_path = find_binary("obsidian-sync")?;
+    ensure_not_running("obsidian-sync")?;
+    let helix_dir = crate::accounts::data_dir()?;
+
+    let child = Command::new(&binary_path)
+        .arg("--user-id")
+        .arg(&user_id)
+        .arg("--vault")
+        .arg(&vault_path)
+        .arg("--helix-dir")
+        .arg(&helix_dir)
+        .spawn()
+        .map_err(|e| CommandError::process(format!("Failed to spawn obsidian-sync: {}", e)))?;
+
+    let pid = child.id();
+    track(&mut processes, "obsidian-sync", child, None)?;
+
+    events::record(
+        ServiceKind::Sync,
+        "started",
+        format!("Obsidian sync started for {}", vault_path),
+        None,
+    );
+    Ok(format!(
+        "Obsidian sync started for {} with PID {}",
+        vault_path, pid
+    ))
+}
+
+/// Keyring key holding the IMAP password for email ingestion
+const EMAIL_PASSWORD_KEY: &str = "email_imap_password";
+
+/// Start Email Ingestion
+/// Polls the configured IMAP folder and turns new emails into memories.
+/// Redaction runs in the poller, before anything is sent to Supabase.
+#[command]
+pub async fn start_email_ingest(user_id: String) -> Result<String, CommandError> {
+    let email = super::config::get_config()?.email;
+    if !email.enabled {
+        return Err(CommandError::new(ErrorCode::Disabled, "Email ingestion is disabled in settings"));
+    }
+    if email.host.is_empty() || email.username.is_empty() {
+        return Err(CommandError::new(ErrorCode::Config, "Email ingestion needs an IMAP host and username"));
+    }
+    let password = super::keyring::get_secret(EMAIL_PASSWORD_KEY.to_string())?
+        .ok_or_else(|| CommandError::new(ErrorCode::Keyring, "No IMAP password stored in the keyring"))?;
+
+    let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
+    if processes.contains_key("email-ingest") {
+        return Err(CommandError::new(ErrorCode::Conflict, "Email ingestion is already running"));
+    }
+
+    let binary_path = find_binary("email-ingest")?;
+    ensure_not_running("email-ingest")?;
+    let state_file = crate::accounts::data_dir()?.join("email_ingest_state.json");
+
+    let mut cmd = Command::new(&binary_path);
+    cmd.arg("--user-id")
+        .arg(&user_id)
+        .arg("--host")
+        .arg(&email.host)
+        .arg("--port")
+        .arg(email.port.to_string())
+        .arg("--username")
+        .arg(&email.username)
+        .arg("--folder")
+        .arg(&email.folder)
+        .arg("--interval")
+        .arg(email.poll_interval_secs.to_string())
+        .arg("--state-file")
+        .arg(&state_file)
+        // Passed via the environment so it never appears in the process list
+        .env("HELIX_IMAP_PASSWORD", password);
+
+    for rule in &email.redaction_rules {
+        cmd.arg("--redact").arg(format!("{}={}", rule.name, rule.pattern));
+    }
+
+    let child = cmd
+        .spawn()
+        .map_err(|e| CommandError::process(format!("Failed to spawn email-ingest: {}", e)))?;
+
+    let pid = child.id();
+    track(&mut processes, "email-ingest", child, None)?;
+
+    Ok(format!(
+        "Email ingestion started for folder '{}' with PID {}",
+        email.folder, pid
+    ))
+}
+
 /// Start Psychology Decay Calculator
 /// Computes memory decay using psychological models
 /// Can run once or on schedule (handled by scheduler)
 #[command]
-pub async fn start_psychology_decay(once: Option<bool>) -> Result<String, String> {
+pub async fn start_psychology_decay(once: Option<bool>) -> Result<String, CommandError> {
     let binary_path = find_binary("psychology-decay")?;
 
     let mut cmd = Command::new(&binary_path);
@@ -126,25 +427,47 @@ pub async fn start_psychology_decay(once: Option<bool>) -> Result<String, String
 
     let output = cmd
         .output()
-        .map_err(|e| format!("Failed to run psychology-decay: {}", e))?;
+        .map_err(|e| CommandError::process(format!("Failed to run psychology-decay: {}", e)))?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        Err(CommandError::process(String::from_utf8_lossy(&output.stderr)))
     }
 }
 
+/// Whether a spawned executable (by binary name) is being tracked as running
+pub(crate) fn is_running(name: &str) -> bool {
+    RUNNING_PROCESSES
+        .lock()
+        .map(|processes| processes.contains_key(name))
+        .unwrap_or(false)
+}
+
 /// Get status of all Rust executables
 /// Returns running status, port, and PID for each binary
 #[command]
-pub async fn get_rust_exe_status() -> Result<Vec<RustExeStatus>, String> {
+pub async fn get_rust_exe_status(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<RustExeStatus>, CommandError> {
     let processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
 
     let statuses = vec![
         RustExeStatus {
             name: "memory-synthesis".to_string(),
-            running: processes.contains_key("memory-synthesis"),
+            running: state.task_manager.is_running("memory-synthesis"),
+            port: None,
+            pid: None,
+        },
+        RustExeStatus {
+            name: SYNTHESIS_SERVER.to_string(),
+            running: processes.contains_key(SYNTHESIS_SERVER),
+            port: Some(SYNTHESIS_SERVER_PORT),
+            pid: None,
+        },
+        RustExeStatus {
+            name: "memory-import".to_string(),
+            running: state.task_manager.is_running("memory-import"),
             port: None,
             pid: None,
         },
@@ -166,6 +489,18 @@ pub async fn get_rust_exe_status() -> Result<Vec<RustExeStatus>, String> {
             port: Some(18792),
             pid: None,
         },
+        RustExeStatus {
+            name: "obsidian-sync".to_string(),
+            running: processes.contains_key("obsidian-sync"),
+            port: None,
+            pid: None,
+        },
+        RustExeStatus {
+            name: "email-ingest".to_string(),
+            running: processes.contains_key("email-ingest"),
+            port: None,
+            pid: None,
+        },
         RustExeStatus {
             name: "psychology-decay".to_string(),
             running: false, // One-shot tool, never stays running
@@ -180,28 +515,32 @@ pub async fn get_rust_exe_status() -> Result<Vec<RustExeStatus>, String> {
 /// Stop a running Rust executable
 /// Kills the process and removes it from tracking
 #[command]
-pub async fn stop_rust_exe(name: String) -> Result<String, String> {
+pub async fn stop_rust_exe(name: String) -> Result<String, CommandError> {
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
 
-    if let Some(mut child) = processes.remove(&name) {
-        child
+    if let Some(mut process) = processes.remove(&name) {
+        process
+            .child
             .kill()
             .map_err(|e| format!("Failed to kill {}: {}", name, e))?;
+        if SYNC_EXES.contains(&name.as_str()) {
+            events::record(ServiceKind::Sync, "stopped", format!("Stopped {}", name), None);
+        }
         Ok(format!("Stopped {}", name))
     } else {
-        Err(format!("{} is not running", name))
+        Err(CommandError::not_found(format!("{} is not running", name)))
     }
 }
 
 /// Stop all running Rust executables
 /// Called on shutdown
 #[command]
-pub async fn stop_all_rust_exes() -> Result<String, String> {
+pub async fn stop_all_rust_exes() -> Result<String, CommandError> {
     let mut processes = RUNNING_PROCESSES.lock().map_err(|e| e.to_string())?;
 
     let mut killed = Vec::new();
-    for (name, mut child) in processes.drain() {
-        if let Ok(()) = child.kill() {
+    for (name, mut process) in processes.drain() {
+        if let Ok(()) = process.child.kill() {
             killed.push(name);
         }
     }
@@ -216,7 +555,7 @@ pub async fn stop_all_rust_exes() -> Result<String, String> {
 /// Find binary path - checks multiple locations
 /// 1. Relative path in app bundle (./helix-rust/target/release/)
 /// 2. System PATH
-fn find_binary(name: &str) -> Result<String, String> {
+pub(crate) fn find_binary(name: &str) -> Result<String, CommandError> {
     let exe_name = if cfg!(target_os = "windows") {
         format!("{}.exe", name)
     } else {
@@ -249,8 +588,9 @@ fn find_binary(name: &str) -> Result<String, String> {
         return Ok(exe_name);
     }
 
-    Err(format!(
+    Err(CommandError::process(format!(
         "Binary {} not found. Tried: ./{}, system PATH",
         name, relative_path
     ))
+    .with_details(serde_json::json!({ "binary": name })))
 }
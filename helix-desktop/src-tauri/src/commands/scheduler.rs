@@ -1,13 +1,49 @@
 // Scheduler commands for managing Layer 5 integration jobs
 // Provides Tauri command handlers for memory consolidation, synthesis, and scheduled tasks
 
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::automation::hooks::{self, HookEvent};
+use crate::startup::Component;
+use crate::commands::preconditions::{self, Precondition, PreconditionCheck};
+use crate::commands::scheduler_alerts::{self, FailureAlerts};
+use crate::commands::{scheduler_runner, scheduler_store};
+use crate::commands::{CommandError, ErrorCode};
+
+/// First retry delay for a job deferred on unmet preconditions; doubles
+/// with each consecutive deferral up to MAX_PRECONDITION_RETRY_SECS
+const PRECONDITION_RETRY_SECS: u64 = 60;
+const MAX_PRECONDITION_RETRY_SECS: u64 = 3600;
+/// Delay before a run that found the psychology files locked is tried again
+const BUSY_RETRY_SECS: u64 = 60;
+/// Run records kept per job in the registry; the store keeps all of them
+/// until purge_job_history
+pub(crate) const MAX_JOB_RUNS: usize = 20;
+/// Runs get_job_history returns by default, and at most
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+const MAX_HISTORY_LIMIT: usize = 1000;
+/// Longest scheduler-wide pause
+const MAX_PAUSE_SECS: u64 = 7 * 24 * 3600;
+/// Retry policy of jobs created without one: retries after a failure, and
+/// the first retry's delay, doubling with each further retry
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BACKOFF_SECS: u64 = 300;
+const MAX_RETRIES: u32 = 10;
+const MAX_RETRY_BACKOFF_SECS: u64 = 24 * 3600;
+/// A dependency counts for a run when it completed this recently, and
+/// after the dependent job last completed
+const DEPENDENCY_WINDOW_SECS: u64 = 24 * 3600;
+/// Largest `jitter_minutes`
+pub const MAX_JITTER_MINUTES: u32 = 120;
 
 /// Scheduler job status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,18 +58,134 @@ pub enum JobStatus {
     Failed,
     #[serde(rename = "paused")]
     Paused,
+    /// Held back until `next_run`: by do-not-disturb, quiet hours, unmet
+    /// preconditions or another writer holding the psychology lock
+    #[serde(rename = "deferred")]
+    Deferred,
+    /// Fires once the jobs in `depends_on` have completed
+    #[serde(rename = "waiting")]
+    Waiting,
+    /// A one-shot job that has run, kept for its history; it doesn't run
+    /// again
+    #[serde(rename = "archived")]
+    Archived,
+}
+
+/// What becomes of a one-shot job once it has completed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AfterRun {
+    /// Remove it, with its run history
+    #[default]
+    Delete,
+    /// Keep it as Archived
+    Archive,
+}
+
+/// What happens to runs a job missed while the machine slept or the app
+/// was closed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Drop the missed runs and wait for the next scheduled time
+    Skip,
+    /// Run once on wake, however many runs were missed
+    #[default]
+    RunOnceImmediately,
+    /// Run once on wake for every missed run, one after the other
+    RunAllMissed,
 }
 
 /// Scheduler job type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum JobType {
+    /// memory-synthesis for the active account
     Consolidation,
+    /// synthesis.py, full mode
     Synthesis,
     FullIntegration,
+    /// psychology-decay --once
     MemoryFadeout,
+    /// synthesis.py, emotional mode
     PatternAnalysis,
+    /// synthesis.py, wellness mode
     RecommendationGeneration,
+    /// Daily check for goals without recent check-ins (psychology/goals.rs)
+    GoalReminder,
+    /// Replays the recorded macro named by the job's `macro_name`
+    /// (automation/macros.rs)
+    Custom,
+}
+
+impl JobType {
+    /// Jobs whose results reach the user (insights, recommendations); held
+    /// back during do-not-disturb. Maintenance jobs run regardless.
+    pub fn is_noisy(&self) -> bool {
+        matches!(
+            self,
+            JobType::Synthesis
+                | JobType::PatternAnalysis
+                | JobType::RecommendationGeneration
+                | JobType::GoalReminder
+        )
+    }
+
+    /// Services the job needs; unmet ones defer the run
+    pub fn preconditions(&self) -> Vec<Precondition> {
+        match self {
+            JobType::Consolidation
+            | JobType::Synthesis
+            | JobType::FullIntegration
+            | JobType::MemoryFadeout
+            | JobType::PatternAnalysis => vec![Precondition::SupabaseReachable],
+            JobType::RecommendationGeneration => {
+                vec![Precondition::GatewayHealthy, Precondition::SupabaseReachable]
+            }
+            JobType::GoalReminder | JobType::Custom => Vec::new(),
+        }
+    }
+}
+
+/// One attempt to run a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    /// Counts up from 1 over the job's runs
+    #[serde(default)]
+    pub number: u64,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub status: JobStatus,
+    /// Precondition checks made before the attempt
+    pub preconditions: Vec<PreconditionCheck>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Exit status of the job's process, for jobs that run one
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Tails of the process output (see scheduler_runner.rs)
+    #[serde(default)]
+    pub stdout: Option<String>,
+    #[serde(default)]
+    pub stderr: Option<String>,
+}
+
+impl JobRun {
+    fn new(started_at: u64, status: JobStatus, preconditions: Vec<PreconditionCheck>) -> Self {
+        Self {
+            number: 0,
+            started_at,
+            finished_at: None,
+            status,
+            preconditions,
+            error: None,
+            duration_ms: None,
+            exit_code: None,
+            stdout: None,
+            stderr: None,
+        }
+    }
 }
 
 /// Scheduler job details
@@ -51,6 +203,75 @@ pub struct SchedulerJob {
     pub duration_ms: Option<u64>,
     pub error: Option<String>,
     pub result: Option<serde_json::Value>,
+    /// Services checked before each run (the job type's unless overridden)
+    #[serde(default)]
+    pub preconditions: Vec<Precondition>,
+    /// Consecutive runs deferred on unmet preconditions
+    #[serde(default)]
+    pub precondition_deferrals: u32,
+    /// Recent run attempts, oldest first
+    #[serde(default)]
+    pub runs: Vec<JobRun>,
+    /// Macro a Custom job replays
+    #[serde(default)]
+    pub macro_name: Option<String>,
+    /// Times a failed run is retried before the job is marked failed
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles with each further one
+    #[serde(default = "default_retry_backoff_seconds")]
+    pub retry_backoff_seconds: u64,
+    /// Retries made since the job last completed or was started afresh
+    #[serde(default)]
+    pub retry_count: u32,
+    /// Jobs that must complete, within DEPENDENCY_WINDOW_SECS and since this
+    /// job last completed, before it runs
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Maintenance jobs only; see scheduler_runner.rs
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+    /// Missed runs still to replay under RunAllMissed
+    #[serde(default)]
+    pub missed_runs: u32,
+    /// Set for a one-shot job: the time it runs once (seconds since epoch)
+    /// instead of recurring; its `cron_expression` is empty
+    #[serde(default)]
+    pub run_at: Option<u64>,
+    /// One-shot jobs only
+    #[serde(default)]
+    pub after_run: AfterRun,
+    /// Starts during the scheduler's quiet hours instead of waiting them out
+    #[serde(default)]
+    pub run_in_quiet_hours: bool,
+}
+
+impl SchedulerJob {
+    pub fn is_one_shot(&self) -> bool {
+        self.run_at.is_some()
+    }
+
+    /// A one-shot job that is done running on its own: archived, or failed
+    /// with no retries left. It still runs when triggered.
+    pub fn is_spent(&self) -> bool {
+        self.is_one_shot() && matches!(self.status, JobStatus::Failed | JobStatus::Archived)
+    }
+}
+
+fn default_max_retries() -> u32 { DEFAULT_MAX_RETRIES }
+fn default_retry_backoff_seconds() -> u64 { DEFAULT_RETRY_BACKOFF_SECS }
+
+/// Payload for scheduler:job-retry event
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRetry {
+    pub job_id: String,
+    pub job_type: JobType,
+    /// 1 for the first retry
+    pub attempt: u32,
+    pub max_retries: u32,
+    /// When the retry runs (seconds since epoch)
+    pub next_run: u64,
+    pub error: String,
 }
 
 /// Scheduler configuration
@@ -68,6 +289,89 @@ pub struct SchedulerConfig {
     pub synthesis_day: u32, // Day of month (default: 1)
     pub max_concurrent_jobs: u32,
     pub timeout_seconds: u32,
+    /// All jobs are held back until this time (seconds since epoch), without
+    /// changing their own status
+    #[serde(default)]
+    pub paused_until: Option<u64>,
+    /// When failing jobs raise a desktop notification (see scheduler_alerts.rs)
+    #[serde(default)]
+    pub failure_alerts: FailureAlerts,
+    /// Suspends all job firing, job statuses untouched
+    #[serde(default)]
+    pub maintenance_mode: MaintenanceMode,
+    /// Recurring runs move by a random amount of up to this many minutes
+    /// either way, drawn for each run; 0 keeps them on time
+    #[serde(default)]
+    pub jitter_minutes: u32,
+    /// Daily window in which only jobs with `run_in_quiet_hours` start;
+    /// the others are deferred to its end
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A daily local time window, "HH:MM" to "HH:MM"; one whose end comes
+/// before its start runs past midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn times(&self) -> Result<(NaiveTime, NaiveTime), String> {
+        let parse = |field: &str, time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .map_err(|_| format!("quiet_hours.{} must be HH:MM, got '{}'", field, time))
+        };
+        Ok((parse("start", &self.start)?, parse("end", &self.end)?))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let (start, end) = self.times()?;
+        if start == end {
+            return Err("quiet_hours must not start and end at the same time".to_string());
+        }
+        Ok(())
+    }
+
+    /// When the window `now` falls in ends (seconds since epoch), or None
+    /// outside it
+    pub fn end_after(&self, now: DateTime<Local>) -> Option<u64> {
+        let (start, end) = self.times().ok()?;
+        let time = now.time();
+        let inside = if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+        if !inside {
+            return None;
+        }
+        let date = if time >= end { now.date_naive() + chrono::Duration::days(1) } else { now.date_naive() };
+        Local
+            .from_local_datetime(&date.and_time(end))
+            .earliest()
+            .map(|at| at.timestamp().max(0) as u64)
+    }
+}
+
+/// Scheduler-wide maintenance mode, also the payload of the
+/// scheduler:maintenance-mode event. Unlike a pause, which defers each job
+/// as it comes due, nothing about the jobs changes: triggers are ignored
+/// and due jobs simply stay due until it ends.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceMode {
+    pub enabled: bool,
+    /// Ends by itself at this time (seconds since epoch); `None` lasts
+    /// until it is turned off
+    #[serde(default)]
+    pub until: Option<u64>,
+}
+
+impl MaintenanceMode {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.enabled && self.until.is_none_or(|until| until > now)
+    }
 }
 
 impl Default for SchedulerConfig {
@@ -85,24 +389,24 @@ impl Default for SchedulerConfig {
             synthesis_day: 1,
             max_concurrent_jobs: 2,
             timeout_seconds: 1800, // 30 minutes
+            paused_until: None,
+            failure_alerts: FailureAlerts::default(),
+            maintenance_mode: MaintenanceMode::default(),
+            jitter_minutes: 0,
+            quiet_hours: None,
         }
     }
 }
 
-/// In-memory job registry (in production, this would be backed by SQLite)
+/// Jobs of the active account, written through to the scheduler store
 static JOB_REGISTRY: LazyLock<Mutex<HashMap<String, SchedulerJob>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 static JOB_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// For events raised outside of commands (job retries)
+static APP: OnceLock<AppHandle> = OnceLock::new();
 
 fn get_helix_dir() -> Result<PathBuf, String> {
-    if let Ok(dir) = std::env::var("HELIX_PROJECT_DIR") {
-        return Ok(PathBuf::from(dir));
-    }
-
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
-
-    Ok(home.join(".helix"))
+    crate::accounts::data_dir()
 }
 
 fn get_config_path() -> Result<PathBuf, String> {
@@ -110,21 +414,69 @@ fn get_config_path() -> Result<PathBuf, String> {
     Ok(helix_dir.join("config").join("scheduler.json"))
 }
 
-fn lock_registry() -> std::sync::MutexGuard<'static, HashMap<String, SchedulerJob>> {
+pub(crate) fn lock_registry() -> std::sync::MutexGuard<'static, HashMap<String, SchedulerJob>> {
     JOB_REGISTRY.lock().expect("Job registry mutex poisoned")
 }
 
+/// Fill the registry from the active account's scheduler database. A run
+/// still marked running was cut short when the app quit, and is failed.
+pub async fn reload_job_registry() {
+    let jobs = match scheduler_store::load().await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            log::warn!("Failed to load scheduler jobs: {}", e);
+            Vec::new()
+        }
+    };
+
+    let now = now_secs();
+    let mut registry = lock_registry();
+    registry.clear();
+    for mut job in jobs {
+        if job.status == JobStatus::Running {
+            let error = "Interrupted when the app quit".to_string();
+            job.status = JobStatus::Failed;
+            job.completed_at = Some(now);
+            finish_run(&mut job, now, JobStatus::Failed, Some(error.clone()));
+            job.error = Some(error);
+            scheduler_store::save(&job);
+        }
+        registry.insert(job.id.clone(), job);
+    }
+    log::debug!("Loaded {} scheduler jobs", registry.len());
+}
+
+/// Load the stored jobs at startup, before anything schedules new ones
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    tauri::async_runtime::block_on(reload_job_registry());
+}
+
+fn job_not_found(job_id: &str) -> CommandError {
+    CommandError::not_found(format!("Job not found: {}", job_id))
+        .with_details(serde_json::json!({ "job_id": job_id }))
+}
+
+/// Refuse to schedule or run jobs while safe mode holds the scheduler back
+pub(crate) fn ensure_scheduler_enabled() -> Result<(), CommandError> {
+    if crate::startup::safe_mode::is_enabled(Component::Scheduler) {
+        Ok(())
+    } else {
+        Err(CommandError::new(ErrorCode::Disabled, "Scheduler is disabled in safe mode"))
+    }
+}
+
 /// Get current scheduler configuration
 #[tauri::command]
-pub fn get_scheduler_config() -> Result<SchedulerConfig, String> {
+pub fn get_scheduler_config() -> Result<SchedulerConfig, CommandError> {
     let config_path = get_config_path()?;
 
     if config_path.exists() {
         let content = fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read scheduler config: {}", e))?;
+            .map_err(|e| CommandError::from_io("Failed to read scheduler config", e))?;
 
         serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse scheduler config: {}", e))
+            .map_err(|e| CommandError::new(ErrorCode::Config, format!("Failed to parse scheduler config: {}", e)))
     } else {
         Ok(SchedulerConfig::default())
     }
@@ -132,7 +484,16 @@ pub fn get_scheduler_config() -> Result<SchedulerConfig, String> {
 
 /// Update scheduler configuration
 #[tauri::command]
-pub fn set_scheduler_config(config: SchedulerConfig) -> Result<(), String> {
+pub fn set_scheduler_config(config: SchedulerConfig) -> Result<(), CommandError> {
+    if config.jitter_minutes > MAX_JITTER_MINUTES {
+        return Err(CommandError::invalid_input(format!(
+            "jitter_minutes must be at most {}",
+            MAX_JITTER_MINUTES
+        )));
+    }
+    if let Some(quiet_hours) = &config.quiet_hours {
+        quiet_hours.validate().map_err(CommandError::invalid_input)?;
+    }
     let config_path = get_config_path()?;
 
     if let Some(parent) = config_path.parent() {
@@ -144,12 +505,111 @@ pub fn set_scheduler_config(config: SchedulerConfig) -> Result<(), String> {
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
     fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write scheduler config: {}", e))
+        .map_err(|e| CommandError::from_io("Failed to write scheduler config", e))
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// When the scheduler-wide pause ends, if one is in effect
+pub fn paused_until() -> Option<u64> {
+    get_scheduler_config()
+        .ok()?
+        .paused_until
+        .filter(|until| *until > now_secs())
+}
+
+/// Pause all job firing until `until` (seconds since epoch), or lift the
+/// pause with `None`
+pub fn set_paused_until(until: Option<u64>) -> Result<(), CommandError> {
+    let mut config = get_scheduler_config()?;
+    config.paused_until = until;
+    set_scheduler_config(config)
+}
+
+/// Maintenance mode, if it is in effect
+pub fn maintenance_mode() -> Option<MaintenanceMode> {
+    get_scheduler_config()
+        .ok()
+        .map(|config| config.maintenance_mode)
+        .filter(|mode| mode.is_active(now_secs()))
+}
+
+/// When the quiet hours in progress end, if they are in progress
+pub fn quiet_until() -> Option<u64> {
+    get_scheduler_config().ok()?.quiet_hours?.end_after(Local::now())
+}
+
+/// `next_run` moved by up to `jitter_minutes` either way, never into the
+/// past; a run due within the jitter is left alone
+fn jittered(next_run: u64, jitter_minutes: u32) -> u64 {
+    let jitter = jitter_minutes as i64 * 60;
+    let now = now_secs();
+    if jitter == 0 || next_run < now + jitter as u64 {
+        return next_run;
+    }
+    let offset = rand::thread_rng().gen_range(-jitter..=jitter);
+    next_run.saturating_add_signed(offset).max(now)
+}
+
+/// Payload for scheduler:paused event
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerPaused {
+    pub paused_until: Option<u64>,
+}
+
+/// When a maintenance job runs next
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingRun {
+    pub job_type: JobType,
+    /// Seconds since epoch; `None` when the job is off or paused
+    pub next_run: Option<u64>,
+}
+
+/// Next occurrence of a daily "HH:MM" local time, in seconds since epoch
+pub(crate) fn next_daily(time: &str) -> Option<u64> {
+    let time = NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()?;
+    let now = Local::now();
+    (0..=1)
+        .filter_map(|offset| {
+            let date = now.date_naive() + chrono::Duration::days(offset);
+            Local.from_local_datetime(&date.and_time(time)).earliest()
+        })
+        .find(|slot| *slot > now)
+        .map(|slot| slot.timestamp().max(0) as u64)
+}
+
+/// Next consolidation and synthesis: the registered job's next run, else
+/// the daily time from the config
+pub fn upcoming_maintenance() -> Vec<UpcomingRun> {
+    let config = get_scheduler_config().unwrap_or_default();
+    let jobs = get_scheduled_jobs().unwrap_or_default();
+
+    [
+        (JobType::Consolidation, config.daily_consolidation, &config.consolidation_time),
+        (JobType::Synthesis, config.daily_synthesis, &config.synthesis_time),
+    ]
+    .into_iter()
+    .map(|(job_type, daily, time)| {
+        let job = jobs.iter().find(|job| job.job_type == job_type && !job.is_one_shot());
+        let next_run = match job {
+            Some(job) if job.status == JobStatus::Paused => None,
+            Some(job) => Some(job.next_run),
+            None if config.enabled && daily => next_daily(time),
+            None => None,
+        };
+        UpcomingRun { job_type, next_run }
+    })
+    .collect()
 }
 
 /// Get all scheduled jobs
 #[tauri::command]
-pub fn get_scheduled_jobs() -> Result<Vec<SchedulerJob>, String> {
+pub fn get_scheduled_jobs() -> Result<Vec<SchedulerJob>, CommandError> {
     let registry = lock_registry();
     let mut jobs: Vec<_> = registry.values().cloned().collect();
     jobs.sort_by_key(|j| j.next_run);
@@ -158,29 +618,142 @@ pub fn get_scheduled_jobs() -> Result<Vec<SchedulerJob>, String> {
 
 /// Get a specific job by ID
 #[tauri::command]
-pub fn get_job(job_id: String) -> Result<SchedulerJob, String> {
+pub fn get_job(job_id: String) -> Result<SchedulerJob, CommandError> {
     let registry = lock_registry();
     registry
         .get(&job_id)
         .cloned()
-        .ok_or_else(|| format!("Job not found: {}", job_id))
+        .ok_or_else(|| job_not_found(&job_id))
+}
+
+pub(crate) fn validate_retry_policy(max_retries: Option<u32>, retry_backoff_seconds: Option<u64>) -> Result<(), CommandError> {
+    if max_retries.is_some_and(|retries| retries > MAX_RETRIES) {
+        return Err(CommandError::invalid_input(format!("max_retries must be at most {}", MAX_RETRIES)));
+    }
+    if retry_backoff_seconds.is_some_and(|secs| secs == 0 || secs > MAX_RETRY_BACKOFF_SECS) {
+        return Err(CommandError::invalid_input(format!(
+            "retry_backoff_seconds must be between 1 and {}",
+            MAX_RETRY_BACKOFF_SECS
+        )));
+    }
+    Ok(())
+}
+
+/// Jobs in the registry that `from` reaches through depends_on, as the
+/// path to `to` if it is one of them
+fn dependency_path(registry: &HashMap<String, SchedulerJob>, from: &str, to: &str) -> Option<Vec<String>> {
+    let mut stack = vec![vec![from.to_string()]];
+    let mut seen = std::collections::HashSet::new();
+    while let Some(path) = stack.pop() {
+        let last = path.last()?;
+        if last == to {
+            return Some(path);
+        }
+        if !seen.insert(last.clone()) {
+            continue;
+        }
+        for next in registry.get(last).map(|job| job.depends_on.as_slice()).unwrap_or_default() {
+            let mut longer = path.clone();
+            longer.push(next.clone());
+            stack.push(longer);
+        }
+    }
+    None
+}
+
+/// Check that `job_id` may depend on `depends_on`: the jobs exist and none
+/// of them already depends on `job_id`, directly or through others
+pub(crate) fn check_dependencies(
+    registry: &HashMap<String, SchedulerJob>,
+    job_id: &str,
+    depends_on: &[String],
+) -> Result<(), CommandError> {
+    for dependency in depends_on {
+        if dependency == job_id {
+            return Err(CommandError::invalid_input("A job can't depend on itself")
+                .with_details(serde_json::json!({ "job_id": job_id })));
+        }
+        if !registry.contains_key(dependency) {
+            return Err(job_not_found(dependency));
+        }
+        if let Some(path) = dependency_path(registry, dependency, job_id) {
+            let cycle: Vec<&str> = std::iter::once(job_id).chain(path.iter().map(String::as_str)).collect();
+            return Err(CommandError::invalid_input(format!("Dependency cycle: {}", cycle.join(" -> ")))
+                .with_details(serde_json::json!({ "cycle": cycle })));
+        }
+    }
+    Ok(())
+}
+
+/// Each id once, in the order given
+fn dedup_ids(ids: Vec<String>) -> Vec<String> {
+    let mut unique = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+/// Dependencies of `job` without a completion in its current window
+fn unmet_dependencies(registry: &HashMap<String, SchedulerJob>, job: &SchedulerJob, now: u64) -> Vec<String> {
+    let since = now
+        .saturating_sub(DEPENDENCY_WINDOW_SECS)
+        .max(job.last_run.unwrap_or(0));
+    job.depends_on
+        .iter()
+        .filter(|id| {
+            !registry.get(*id).is_some_and(|dependency| {
+                dependency.status != JobStatus::Running && dependency.last_run.is_some_and(|at| at >= since)
+            })
+        })
+        .cloned()
+        .collect()
 }
 
-/// Create a new scheduled job
+/// Create a new scheduled job; `preconditions` replaces the job type's
+/// default service checks. Custom jobs name the macro they replay. The
+/// retry policy defaults to 3 retries, the first after 5 minutes. With
+/// `depends_on`, the job only runs after those jobs complete.
 #[tauri::command]
 pub fn create_job(
     job_type: JobType,
     cron_expression: String,
-) -> Result<SchedulerJob, String> {
+    preconditions: Option<Vec<Precondition>>,
+    macro_name: Option<String>,
+    max_retries: Option<u32>,
+    retry_backoff_seconds: Option<u64>,
+    depends_on: Option<Vec<String>>,
+) -> Result<SchedulerJob, CommandError> {
+    ensure_scheduler_enabled()?;
+    validate_retry_policy(max_retries, retry_backoff_seconds)?;
+
+    let macro_name = match (&job_type, macro_name) {
+        (JobType::Custom, Some(name)) => Some(crate::automation::macros::load(&name)?.name),
+        (JobType::Custom, None) => {
+            return Err(CommandError::invalid_input("Custom jobs need the name of the macro to run"));
+        }
+        (_, Some(_)) => {
+            return Err(CommandError::invalid_input("Only Custom jobs run a macro"));
+        }
+        (_, None) => None,
+    };
+
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|e| format!("Failed to get current time: {}", e))?
         .as_secs();
 
     let counter = JOB_COUNTER.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+    let id = format!("job_{}_{}", now, counter);
+    let depends_on = dedup_ids(depends_on.unwrap_or_default());
 
+    let mut registry = lock_registry();
+    check_dependencies(&registry, &id, &depends_on)?;
     let job = SchedulerJob {
-        id: format!("job_{}_{}", now, counter),
+        id,
+        preconditions: preconditions.unwrap_or_else(|| job_type.preconditions()),
         job_type,
         status: JobStatus::Pending,
         scheduled_at: now,
@@ -192,73 +765,460 @@ pub fn create_job(
         duration_ms: None,
         error: None,
         result: None,
+        precondition_deferrals: 0,
+        runs: Vec::new(),
+        macro_name,
+        max_retries: max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+        retry_backoff_seconds: retry_backoff_seconds.unwrap_or(DEFAULT_RETRY_BACKOFF_SECS),
+        retry_count: 0,
+        depends_on,
+        misfire_policy: MisfirePolicy::default(),
+        missed_runs: 0,
+        run_at: None,
+        after_run: AfterRun::default(),
+        run_in_quiet_hours: false,
     };
 
     let job_id = job.id.clone();
-    lock_registry().insert(job_id, job.clone());
+    scheduler_store::save(&job);
+    registry.insert(job_id, job.clone());
 
     Ok(job)
 }
 
+/// Queue a job that runs once at `run_at` (seconds since epoch) and is then
+/// deleted, or archived with `after_run`. It doesn't take the place of the
+/// recurring job of its type. Goal reminders only recur.
+#[tauri::command]
+pub fn schedule_job_once(
+    job_type: JobType,
+    run_at: u64,
+    after_run: Option<AfterRun>,
+    macro_name: Option<String>,
+    max_retries: Option<u32>,
+    retry_backoff_seconds: Option<u64>,
+    depends_on: Option<Vec<String>>,
+) -> Result<SchedulerJob, CommandError> {
+    if job_type == JobType::GoalReminder {
+        return Err(CommandError::invalid_input("Goal reminders can't be scheduled once"));
+    }
+    if run_at <= now_secs() {
+        return Err(CommandError::invalid_input("run_at must be in the future"));
+    }
+    let job = create_job(
+        job_type,
+        String::new(),
+        None,
+        macro_name,
+        max_retries,
+        retry_backoff_seconds,
+        depends_on,
+    )?;
+
+    let mut registry = lock_registry();
+    let job = registry.get_mut(&job.id).ok_or_else(|| job_not_found(&job.id))?;
+    job.run_at = Some(run_at);
+    job.next_run = run_at;
+    job.after_run = after_run.unwrap_or_default();
+    scheduler_store::save(job);
+    log::info!("Scheduler job {} ({:?}) runs once at {}", job.id, job.job_type, run_at);
+    Ok(job.clone())
+}
+
+/// Job types a backend-driven job of `job_type` waits on: synthesis runs
+/// after consolidation, and the weekly full integration after both, so the
+/// three make one pipeline
+fn pipeline_dependencies(job_type: &JobType) -> &'static [JobType] {
+    match job_type {
+        JobType::Synthesis => &[JobType::Consolidation],
+        JobType::FullIntegration => &[JobType::Consolidation, JobType::Synthesis],
+        _ => &[],
+    }
+}
+
+/// Link a newly created backend-driven job into the pipeline: to the jobs
+/// it waits on, and into the jobs that wait on it
+fn join_pipeline(job_id: &str) {
+    let mut registry = lock_registry();
+    let Some(job_type) = registry.get(job_id).map(|job| job.job_type.clone()) else {
+        return;
+    };
+
+    let mut edges: Vec<(String, String)> = registry
+        .values()
+        .filter(|other| !other.is_one_shot() && pipeline_dependencies(&job_type).contains(&other.job_type))
+        .map(|dependency| (job_id.to_string(), dependency.id.clone()))
+        .collect();
+    edges.extend(
+        registry
+            .values()
+            .filter(|other| !other.is_one_shot() && pipeline_dependencies(&other.job_type).contains(&job_type))
+            .map(|dependent| (dependent.id.clone(), job_id.to_string())),
+    );
+
+    for (dependent, dependency) in edges {
+        // Dependencies the user set may already run the other way
+        if check_dependencies(&registry, &dependent, std::slice::from_ref(&dependency)).is_err() {
+            continue;
+        }
+        if let Some(job) = registry.get_mut(&dependent) {
+            if !job.depends_on.contains(&dependency) {
+                job.depends_on.push(dependency);
+                scheduler_store::save(job);
+            }
+        }
+    }
+}
+
+/// The recurring job of `job_type`, created with `cron_expression` if there
+/// is none. For backend-driven jobs that must exist exactly once; a new one
+/// joins the consolidation, synthesis and full integration pipeline.
+pub fn ensure_job(job_type: JobType, cron_expression: &str) -> Result<SchedulerJob, CommandError> {
+    let existing = lock_registry()
+        .values()
+        .find(|job| job.job_type == job_type && !job.is_one_shot())
+        .cloned();
+
+    match existing {
+        Some(job) => Ok(job),
+        None => {
+            let job = create_job(job_type, cron_expression.to_string(), None, None, None, None, None)?;
+            join_pipeline(&job.id);
+            get_job(job.id)
+        }
+    }
+}
+
+/// Replace the jobs `job_id` waits on; refused if that would make a cycle
+#[tauri::command]
+pub fn set_job_dependencies(job_id: String, depends_on: Vec<String>) -> Result<SchedulerJob, CommandError> {
+    let depends_on = dedup_ids(depends_on);
+    let mut registry = lock_registry();
+    if !registry.contains_key(&job_id) {
+        return Err(job_not_found(&job_id));
+    }
+    check_dependencies(&registry, &job_id, &depends_on)?;
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    job.depends_on = depends_on;
+    scheduler_store::save(job);
+    Ok(job.clone())
+}
+
+/// Set when a job runs next (seconds since epoch). A pending retry is kept
+/// when it comes sooner.
+pub fn reschedule_job(job_id: &str, next_run: u64) -> Result<(), CommandError> {
+    let jitter_minutes = get_scheduler_config().map(|config| config.jitter_minutes).unwrap_or(0);
+    let next_run = jittered(next_run, jitter_minutes);
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    job.next_run = if job.retry_count > 0 && job.status == JobStatus::Pending {
+        next_run.min(job.next_run)
+    } else {
+        next_run
+    };
+    scheduler_store::save(job);
+    Ok(())
+}
+
+/// Change how often and how soon a job's failed runs are retried
+#[tauri::command]
+pub fn set_job_retry_policy(
+    job_id: String,
+    max_retries: Option<u32>,
+    retry_backoff_seconds: Option<u64>,
+) -> Result<SchedulerJob, CommandError> {
+    validate_retry_policy(max_retries, retry_backoff_seconds)?;
+    let mut registry = lock_registry();
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    if let Some(retries) = max_retries {
+        job.max_retries = retries;
+    }
+    if let Some(secs) = retry_backoff_seconds {
+        job.retry_backoff_seconds = secs;
+    }
+    scheduler_store::save(job);
+    Ok(job.clone())
+}
+
+/// Set what happens to a maintenance job's runs missed while the machine
+/// slept. Other job types are run by the modules that own them, on their
+/// own schedules, so the policy doesn't apply to them.
+#[tauri::command]
+pub fn set_job_misfire_policy(job_id: String, policy: MisfirePolicy) -> Result<SchedulerJob, CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    if !scheduler_runner::dispatches(&job.job_type) {
+        return Err(CommandError::new(
+            ErrorCode::Unsupported,
+            format!("{:?} jobs keep their own schedule; misfire policies apply to maintenance jobs", job.job_type),
+        ));
+    }
+    job.misfire_policy = policy;
+    if policy != MisfirePolicy::RunAllMissed {
+        job.missed_runs = 0;
+    }
+    scheduler_store::save(job);
+    Ok(job.clone())
+}
+
+/// Let a job start during quiet hours, or make it wait them out
+#[tauri::command]
+pub fn set_job_run_in_quiet_hours(job_id: String, allowed: bool) -> Result<SchedulerJob, CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    job.run_in_quiet_hours = allowed;
+    scheduler_store::save(job);
+    Ok(job.clone())
+}
+
+/// Record the runs still to replay after the one now due
+pub(crate) fn set_missed_runs(job_id: &str, missed_runs: u32) -> Result<(), CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    job.missed_runs = missed_runs;
+    scheduler_store::save(job);
+    Ok(())
+}
+
+/// Whether another missed run is owed after a run that `succeeded`, taking
+/// it if so. A failed run drops the rest; its retries take over.
+pub(crate) fn take_missed_run(job_id: &str, succeeded: bool) -> Result<bool, CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    if job.missed_runs == 0 {
+        return Ok(false);
+    }
+    if succeeded {
+        job.missed_runs -= 1;
+    } else {
+        log::info!("Scheduler job {} failed; {} missed runs dropped", job.id, job.missed_runs);
+        job.missed_runs = 0;
+    }
+    scheduler_store::save(job);
+    Ok(succeeded)
+}
+
 /// Pause a scheduled job
 #[tauri::command]
-pub fn pause_job(job_id: String) -> Result<(), String> {
+pub fn pause_job(job_id: String) -> Result<(), CommandError> {
     let mut registry = lock_registry();
     if let Some(job) = registry.get_mut(&job_id) {
         job.status = JobStatus::Paused;
+        scheduler_store::save(job);
         Ok(())
     } else {
-        Err(format!("Job not found: {}", job_id))
+        Err(job_not_found(&job_id))
     }
 }
 
 /// Resume a paused job
 #[tauri::command]
-pub fn resume_job(job_id: String) -> Result<(), String> {
+pub fn resume_job(job_id: String) -> Result<(), CommandError> {
     let mut registry = lock_registry();
     if let Some(job) = registry.get_mut(&job_id) {
+        if job.status == JobStatus::Archived {
+            return Err(archived(&job_id));
+        }
         job.status = JobStatus::Pending;
+        scheduler_store::save(job);
         Ok(())
     } else {
-        Err(format!("Job not found: {}", job_id))
+        Err(job_not_found(&job_id))
     }
 }
 
-/// Delete a scheduled job
+/// Delete a scheduled job; jobs that waited on it no longer do
 #[tauri::command]
-pub fn delete_job(job_id: String) -> Result<(), String> {
-    lock_registry().remove(&job_id);
+pub fn delete_job(job_id: String) -> Result<(), CommandError> {
+    remove_job(&mut lock_registry(), &job_id);
     Ok(())
 }
 
-/// Manually trigger a job execution (for testing)
+fn remove_job(registry: &mut HashMap<String, SchedulerJob>, job_id: &str) {
+    if registry.remove(job_id).is_some() {
+        scheduler_store::delete(job_id);
+        for job in registry.values_mut().filter(|job| job.depends_on.iter().any(|id| id == job_id)) {
+            job.depends_on.retain(|id| id != job_id);
+            scheduler_store::save(job);
+        }
+    }
+}
+
+fn archived(job_id: &str) -> CommandError {
+    CommandError::new(ErrorCode::Conflict, format!("Job {} ran once and is archived", job_id))
+}
+
+fn record_run(job: &mut SchedulerJob, mut run: JobRun) {
+    run.number = job.runs.last().map_or(0, |last| last.number) + 1;
+    job.runs.push(run);
+    let excess = job.runs.len().saturating_sub(MAX_JOB_RUNS);
+    job.runs.drain(..excess);
+}
+
+/// Close the run record of the attempt in progress
+fn finish_run(job: &mut SchedulerJob, now: u64, status: JobStatus, error: Option<String>) {
+    if let Some(run) = job.runs.last_mut().filter(|run| run.status == JobStatus::Running) {
+        run.finished_at = Some(now);
+        run.status = status;
+        run.error = error;
+        run.duration_ms.get_or_insert(now.saturating_sub(run.started_at) * 1000);
+    }
+}
+
+/// Attach what the job's process did to the run in progress, ahead of
+/// completing or failing the job
+pub(crate) fn set_run_output(
+    job_id: &str,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    stdout: String,
+    stderr: String,
+) -> Result<(), CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    if let Some(run) = job.runs.last_mut().filter(|run| run.status == JobStatus::Running) {
+        run.exit_code = exit_code;
+        run.duration_ms = Some(duration_ms);
+        run.stdout = Some(stdout).filter(|text| !text.is_empty());
+        run.stderr = Some(stderr).filter(|text| !text.is_empty());
+        scheduler_store::save(job);
+    }
+    Ok(())
+}
+
+/// Start a job run. Every job is deferred while the scheduler is paused,
+/// those without `run_in_quiet_hours` until quiet hours end, noisy jobs
+/// during do-not-disturb, and any job with exponential backoff
+/// while its preconditions are unmet; a job whose dependencies haven't
+/// completed waits for them, and those that are idle are brought forward.
+/// Check the returned status before doing the work. Maintenance jobs do
+/// theirs in the background (see scheduler_runner.rs).
 #[tauri::command]
-pub fn trigger_job(job_id: String) -> Result<SchedulerJob, String> {
+pub async fn trigger_job(job_id: String) -> Result<SchedulerJob, CommandError> {
+    ensure_scheduler_enabled()?;
+    if maintenance_mode().is_some() {
+        log::debug!("Scheduler job {} not started: maintenance mode", job_id);
+        return get_job(job_id);
+    }
+    let paused_until = paused_until();
+    let quiet_until = quiet_until();
+
+    let required = {
+        let mut registry = lock_registry();
+        if registry.get(&job_id).is_some_and(|job| job.status == JobStatus::Archived) {
+            return Err(archived(&job_id));
+        }
+        let now = now_secs();
+        let waiting_on = registry
+            .get(&job_id)
+            .map(|job| unmet_dependencies(&registry, job, now))
+            .ok_or_else(|| job_not_found(&job_id))?;
+        if paused_until.is_none() && !waiting_on.is_empty() {
+            for dependency in registry.values_mut().filter(|job| waiting_on.contains(&job.id)) {
+                // Failed runs and retries keep their own schedule
+                let idle = match dependency.status {
+                    JobStatus::Completed | JobStatus::Waiting => true,
+                    JobStatus::Pending => dependency.retry_count == 0,
+                    _ => false,
+                };
+                if idle && dependency.next_run > now {
+                    dependency.next_run = now;
+                    scheduler_store::save(dependency);
+                }
+            }
+            let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+            if job.status != JobStatus::Waiting {
+                log::info!("Scheduler job {} is waiting for {}", job.id, waiting_on.join(", "));
+            }
+            job.status = JobStatus::Waiting;
+            job.error = Some(format!("Waiting for {}", waiting_on.join(", ")));
+            scheduler_store::save(job);
+            return Ok(job.clone());
+        }
+        let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+        if let Some(until) = paused_until {
+            job.status = JobStatus::Deferred;
+            job.next_run = until;
+            scheduler_store::save(job);
+            return Ok(job.clone());
+        }
+        if let Some(until) = quiet_until.filter(|_| !job.run_in_quiet_hours) {
+            log::debug!("Scheduler job {} deferred to the end of quiet hours", job.id);
+            job.status = JobStatus::Deferred;
+            job.next_run = until;
+            scheduler_store::save(job);
+            return Ok(job.clone());
+        }
+        if job.job_type.is_noisy() {
+            if let Some(until_ms) = crate::policy::dnd::defer_until() {
+                job.status = JobStatus::Deferred;
+                job.next_run = until_ms / 1000;
+                scheduler_store::save(job);
+                return Ok(job.clone());
+            }
+        }
+        job.preconditions.clone()
+    };
+
+    // Probed without holding the registry lock
+    let checks = preconditions::check(&required).await;
+    let unmet: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.met)
+        .map(|check| check.detail.clone().unwrap_or_else(|| format!("{:?} unmet", check.precondition)))
+        .collect();
+
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|e| format!("Failed to get current time: {}", e))?
         .as_secs();
 
     let mut registry = lock_registry();
-    if let Some(job) = registry.get_mut(&job_id) {
-        job.status = JobStatus::Running;
-        job.started_at = Some(now);
-        Ok(job.clone())
-    } else {
-        Err(format!("Job not found: {}", job_id))
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    if !unmet.is_empty() {
+        let backoff = PRECONDITION_RETRY_SECS
+            .saturating_mul(1 << job.precondition_deferrals.min(16))
+            .min(MAX_PRECONDITION_RETRY_SECS);
+        job.precondition_deferrals += 1;
+        job.status = JobStatus::Deferred;
+        job.next_run = now + backoff;
+        record_run(job, JobRun {
+            finished_at: Some(now),
+            error: Some(unmet.join("; ")),
+            duration_ms: Some(0),
+            ..JobRun::new(now, JobStatus::Deferred, checks)
+        });
+        scheduler_store::save(job);
+        return Ok(job.clone());
     }
+
+    job.precondition_deferrals = 0;
+    // A run after a final failure starts with a fresh retry budget
+    if job.status == JobStatus::Failed {
+        job.retry_count = 0;
+    }
+    job.status = JobStatus::Running;
+    job.started_at = Some(now);
+    record_run(job, JobRun::new(now, JobStatus::Running, checks));
+    scheduler_store::save(job);
+    if scheduler_runner::dispatches(&job.job_type) {
+        scheduler_runner::spawn(job.clone());
+    }
+    Ok(job.clone())
 }
 
 /// Mark a job as completed
 #[tauri::command]
-pub fn complete_job(job_id: String, result: Option<serde_json::Value>) -> Result<(), String> {
+pub fn complete_job(job_id: String, result: Option<serde_json::Value>) -> Result<(), CommandError> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|e| format!("Failed to get current time: {}", e))?
         .as_secs();
 
+    let alerts = get_scheduler_config().map(|config| config.failure_alerts).unwrap_or_default();
     let mut registry = lock_registry();
     if let Some(job) = registry.get_mut(&job_id) {
+        let was_alerting = alerts.is_alerting(job);
         job.status = JobStatus::Completed;
         job.completed_at = Some(now);
         job.last_run = Some(now);
@@ -266,34 +1226,229 @@ pub fn complete_job(job_id: String, result: Option<serde_json::Value>) -> Result
             job.duration_ms = Some((now - started) * 1000);
         }
         job.result = result;
-        Ok(())
+        job.retry_count = 0;
+        finish_run(job, now, JobStatus::Completed, None);
+        scheduler_store::save(job);
+        if let Some(app) = APP.get().filter(|_| was_alerting) {
+            scheduler_alerts::job_recovered(app, job);
+        }
     } else {
-        Err(format!("Job not found: {}", job_id))
+        return Err(job_not_found(&job_id));
     }
+
+    // Jobs the runner carries out start as soon as their last dependency
+    // completes; the others when their owner next checks them
+    let ready: Vec<String> = registry
+        .values()
+        .filter(|job| job.status == JobStatus::Waiting && job.depends_on.contains(&job_id))
+        .filter(|job| scheduler_runner::dispatches(&job.job_type) && unmet_dependencies(&registry, job, now).is_empty())
+        .map(|job| job.id.clone())
+        .collect();
+
+    // A one-shot job is done; those waiting on it have been let go above
+    let after_run = registry.get(&job_id).filter(|job| job.is_one_shot()).map(|job| job.after_run);
+    match after_run {
+        Some(AfterRun::Delete) => {
+            log::info!("One-shot scheduler job {} completed; deleting it", job_id);
+            remove_job(&mut registry, &job_id);
+        }
+        Some(AfterRun::Archive) => {
+            if let Some(job) = registry.get_mut(&job_id) {
+                job.status = JobStatus::Archived;
+                scheduler_store::save(job);
+            }
+        }
+        None => {}
+    }
+    drop(registry);
+    for job_id in ready {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = trigger_job(job_id.clone()).await {
+                log::warn!("Failed to start scheduler job {} after its dependencies: {}", job_id, e.message);
+            }
+        });
+    }
+    Ok(())
 }
 
-/// Mark a job as failed
+/// Store what a run produced, ahead of failing the job
+pub(crate) fn set_job_result(job_id: &str, result: Option<serde_json::Value>) -> Result<(), CommandError> {
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    job.result = result;
+    scheduler_store::save(job);
+    Ok(())
+}
+
+/// End a run that found another writer holding the lock it needs: the job
+/// is deferred for BUSY_RETRY_SECS, without using up a retry or counting
+/// as a failure
+pub(crate) fn defer_busy_job(job_id: &str, error: String) -> Result<(), CommandError> {
+    let now = now_secs();
+    let mut registry = lock_registry();
+    let job = registry.get_mut(job_id).ok_or_else(|| job_not_found(job_id))?;
+    finish_run(job, now, JobStatus::Deferred, Some(error));
+    job.status = JobStatus::Deferred;
+    job.next_run = now + BUSY_RETRY_SECS;
+    log::info!("Scheduler job {} deferred {}s: psychology files are locked", job.id, BUSY_RETRY_SECS);
+    scheduler_store::save(job);
+    Ok(())
+}
+
+/// Mark a job's run as failed. While the job has retries left it is
+/// rescheduled after its backoff, doubled for each retry already made,
+/// and scheduler:job-retry is emitted; otherwise the job is failed.
 #[tauri::command]
-pub fn fail_job(job_id: String, error: String) -> Result<(), String> {
+pub fn fail_job(job_id: String, error: String) -> Result<(), CommandError> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|e| format!("Failed to get current time: {}", e))?
         .as_secs();
 
+    let config = get_scheduler_config().unwrap_or_default();
     let mut registry = lock_registry();
-    if let Some(job) = registry.get_mut(&job_id) {
-        job.status = JobStatus::Failed;
-        job.completed_at = Some(now);
-        job.error = Some(error);
-        Ok(())
+    let job = registry.get_mut(&job_id).ok_or_else(|| job_not_found(&job_id))?;
+    job.completed_at = Some(now);
+    finish_run(job, now, JobStatus::Failed, Some(error.clone()));
+    job.error = Some(error.clone());
+
+    if job.retry_count < job.max_retries {
+        let backoff = job
+            .retry_backoff_seconds
+            .saturating_mul(1 << job.retry_count.min(16))
+            .min(MAX_RETRY_BACKOFF_SECS);
+        job.retry_count += 1;
+        job.status = JobStatus::Pending;
+        job.next_run = now + backoff;
+        log::info!(
+            "Retrying scheduler job {} in {}s ({}/{}): {}",
+            job.id,
+            backoff,
+            job.retry_count,
+            job.max_retries,
+            error
+        );
+        if let Some(app) = APP.get() {
+            let _ = app.emit(
+                "scheduler:job-retry",
+                JobRetry {
+                    job_id: job.id.clone(),
+                    job_type: job.job_type.clone(),
+                    attempt: job.retry_count,
+                    max_retries: job.max_retries,
+                    next_run: job.next_run,
+                    error: error.clone(),
+                },
+            );
+        }
     } else {
-        Err(format!("Job not found: {}", job_id))
+        job.status = JobStatus::Failed;
+        hooks::fire(
+            HookEvent::JobFailed,
+            serde_json::json!({
+                "job_id": job.id,
+                "job_type": job.job_type,
+                "error": error,
+                "retries": job.retry_count,
+            }),
+        );
+    }
+    if let Some(app) = APP.get() {
+        scheduler_alerts::job_failed(app, &config, job, &error);
+    }
+    scheduler_store::save(job);
+    Ok(())
+}
+
+/// Jobs whose failure streak reached their alert threshold
+pub fn failing_job_count() -> u32 {
+    let alerts = get_scheduler_config().map(|config| config.failure_alerts).unwrap_or_default();
+    lock_registry().values().filter(|job| alerts.is_alerting(job)).count() as u32
+}
+
+/// Runs of a job, newest first, including those older than the job record
+/// keeps
+#[tauri::command]
+pub async fn get_job_history(job_id: String, limit: Option<usize>) -> Result<Vec<JobRun>, CommandError> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+    Ok(scheduler_store::history(&job_id, limit).await?)
+}
+
+/// Delete the records of runs that started before `before` (unix seconds);
+/// runs still in progress are kept. Returns how many were deleted.
+#[tauri::command]
+pub async fn purge_job_history(before: u64) -> Result<u64, CommandError> {
+    // The registry's copies go first, so a later save doesn't write them back
+    for job in lock_registry().values_mut() {
+        job.runs.retain(|run| run.finished_at.is_none() || run.started_at >= before);
     }
+    let purged = scheduler_store::purge_history(before).await?;
+    log::info!("Purged {} scheduler job runs from before {}", purged, before);
+    Ok(purged)
+}
+
+/// Hold back all jobs for `duration_secs` without changing their status
+#[tauri::command]
+pub fn pause_scheduler(app: AppHandle, duration_secs: u64) -> Result<SchedulerPaused, CommandError> {
+    if duration_secs == 0 || duration_secs > MAX_PAUSE_SECS {
+        return Err(CommandError::invalid_input(format!(
+            "duration_secs must be between 1 and {}",
+            MAX_PAUSE_SECS
+        )));
+    }
+    let paused = SchedulerPaused {
+        paused_until: Some(now_secs() + duration_secs),
+    };
+    set_paused_until(paused.paused_until)?;
+    let _ = app.emit("scheduler:paused", &paused);
+    Ok(paused)
+}
+
+/// Lift a scheduler-wide pause
+#[tauri::command]
+pub fn resume_scheduler(app: AppHandle) -> Result<(), CommandError> {
+    set_paused_until(None)?;
+    let _ = app.emit("scheduler:paused", SchedulerPaused { paused_until: None });
+    Ok(())
+}
+
+/// Turn maintenance mode on, optionally until `until` (seconds since
+/// epoch), or off. Jobs already running finish; no others start, whether
+/// due, retried or triggered by hand.
+#[tauri::command]
+pub fn scheduler_maintenance_mode(
+    app: AppHandle,
+    enabled: bool,
+    until: Option<u64>,
+) -> Result<MaintenanceMode, CommandError> {
+    if enabled && until.is_some_and(|until| until <= now_secs()) {
+        return Err(CommandError::invalid_input("Maintenance mode must end in the future"));
+    }
+    let mode = MaintenanceMode {
+        enabled,
+        until: until.filter(|_| enabled),
+    };
+    let mut config = get_scheduler_config()?;
+    config.maintenance_mode = mode.clone();
+    set_scheduler_config(config)?;
+    match (mode.enabled, mode.until) {
+        (true, Some(until)) => log::info!("Scheduler in maintenance mode until {}", until),
+        (true, None) => log::info!("Scheduler in maintenance mode"),
+        (false, _) => log::info!("Scheduler maintenance mode ended"),
+    }
+    let _ = app.emit("scheduler:maintenance-mode", &mode);
+    Ok(mode)
+}
+
+/// Next consolidation and synthesis runs
+#[tauri::command]
+pub fn get_upcoming_maintenance() -> Result<Vec<UpcomingRun>, CommandError> {
+    Ok(upcoming_maintenance())
 }
 
 /// Get scheduler health status (for monitoring)
 #[tauri::command]
-pub fn get_scheduler_health() -> Result<SchedulerHealth, String> {
+pub fn get_scheduler_health() -> Result<SchedulerHealth, CommandError> {
     let jobs = get_scheduled_jobs()?;
 
     let running_count = jobs.iter().filter(|j| j.status == JobStatus::Running).count();
@@ -0,0 +1,151 @@
+// Scheduler failure alerts - desktop notifications for failing jobs
+//
+// A job that fails `after_failures` runs in a row (1 by default, so the
+// first failure) raises a desktop notification, once per streak: retries
+// that fail too don't notify again, and a completed run ends the streak.
+// Each job type can have its own rule under `failure_alerts.job_types` in
+// the scheduler config, or be left quiet with `enabled: false`; the
+// others use `failure_alerts.default`. Notifications follow do-not-disturb
+// like every other; the `scheduler:job-failure-alert` event is raised
+// regardless. The tray's Maintenance menu counts the jobs whose streak
+// reached its threshold, so a missed notification still shows.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::commands::scheduler::{JobStatus, JobType, SchedulerConfig, SchedulerJob};
+
+/// When a job type's failures raise a notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureAlertRule {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Consecutive failed runs, retries included, before notifying
+    #[serde(default = "default_after_failures")]
+    pub after_failures: u32,
+}
+
+impl Default for FailureAlertRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_failures: default_after_failures(),
+        }
+    }
+}
+
+fn default_true() -> bool { true }
+fn default_after_failures() -> u32 { 1 }
+
+/// Failure alert rules of the scheduler config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailureAlerts {
+    /// For job types without a rule of their own
+    #[serde(default)]
+    pub default: FailureAlertRule,
+    #[serde(default)]
+    pub job_types: HashMap<JobType, FailureAlertRule>,
+}
+
+impl FailureAlerts {
+    pub fn rule(&self, job_type: &JobType) -> &FailureAlertRule {
+        self.job_types.get(job_type).unwrap_or(&self.default)
+    }
+
+    /// Whether `job`'s current failure streak reached its threshold
+    pub fn is_alerting(&self, job: &SchedulerJob) -> bool {
+        let rule = self.rule(&job.job_type);
+        rule.enabled && consecutive_failures(job) >= rule.after_failures.max(1)
+    }
+}
+
+/// Payload for scheduler:job-failure-alert event
+#[derive(Debug, Clone, Serialize)]
+pub struct JobFailureAlert {
+    pub job_id: String,
+    pub job_type: JobType,
+    pub consecutive_failures: u32,
+    pub error: String,
+    /// Retries are used up; the job waits for its next scheduled run
+    pub retries_exhausted: bool,
+}
+
+/// Failed runs since the job last completed; deferrals don't count either way
+pub fn consecutive_failures(job: &SchedulerJob) -> u32 {
+    job.runs
+        .iter()
+        .rev()
+        .filter(|run| run.status != JobStatus::Deferred)
+        .take_while(|run| run.status == JobStatus::Failed)
+        .count() as u32
+}
+
+fn job_name(strings: &crate::locale::Catalog, job_type: &JobType) -> String {
+    let id = match job_type {
+        JobType::Consolidation => "job-consolidation",
+        JobType::Synthesis => "job-synthesis",
+        JobType::FullIntegration => "job-full-integration",
+        JobType::MemoryFadeout => "job-memory-fadeout",
+        JobType::PatternAnalysis => "job-pattern-analysis",
+        JobType::RecommendationGeneration => "job-recommendation-generation",
+        JobType::GoalReminder => "job-goal-reminder",
+        JobType::Custom => "job-custom",
+    };
+    strings.get(id)
+}
+
+/// Alert on a failed run of `job`, if it is the one that reaches the job
+/// type's threshold
+pub fn job_failed(app: &AppHandle, config: &SchedulerConfig, job: &SchedulerJob, error: &str) {
+    let rule = config.failure_alerts.rule(&job.job_type);
+    let failures = consecutive_failures(job);
+    if !rule.enabled || failures != rule.after_failures.max(1) {
+        return;
+    }
+
+    log::info!("Scheduler job {} failed {} time(s) in a row; notifying", job.id, failures);
+    let _ = app.emit(
+        "scheduler:job-failure-alert",
+        JobFailureAlert {
+            job_id: job.id.clone(),
+            job_type: job.job_type.clone(),
+            consecutive_failures: failures,
+            error: error.to_string(),
+            retries_exhausted: job.status == JobStatus::Failed,
+        },
+    );
+    if crate::policy::dnd::status().suppress_notifications {
+        return;
+    }
+
+    let strings = crate::locale::Catalog::new("notifications");
+    let job_name = job_name(&strings, &job.job_type);
+    let body = if failures > 1 {
+        strings.get_args(
+            "job-failed-repeatedly-body",
+            &[("job", job_name.into()), ("count", failures.into()), ("error", error.into())],
+        )
+    } else {
+        strings.get_args("job-failed-body", &[("job", job_name.into()), ("error", error.into())])
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(strings.get("job-failed-title"))
+        .body(body)
+        .show()
+    {
+        log::debug!("Failed to show scheduler failure notification: {}", e);
+    }
+}
+
+/// A completed run ended `job`'s alerting failure streak; lets the tray
+/// drop it from the count
+pub fn job_recovered(app: &AppHandle, job: &SchedulerJob) {
+    let _ = app.emit(
+        "scheduler:job-recovered",
+        serde_json::json!({ "job_id": job.id, "job_type": job.job_type }),
+    );
+}
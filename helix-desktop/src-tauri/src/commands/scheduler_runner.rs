@@ -0,0 +1,556 @@
+// Scheduler job runner - carries out maintenance jobs
+//
+// Each maintenance job type runs one executable:
+//   - Consolidation: `memory-synthesis --user-id <account>`
+//   - MemoryFadeout: `psychology-decay --once`
+//   - Synthesis, PatternAnalysis, RecommendationGeneration: synthesis in
+//     its full, emotional and wellness modes, in-process (or synthesis.py
+//     with `scripts.python_psychology`)
+//
+// A job runs when triggered (from the UI, the palette, the local API or a
+// macro) and when it comes due.
+//
+// At most `max_concurrent_jobs` run at once: a triggered job past the limit
+// stays marked running while it waits for a slot, and due jobs are only
+// triggered while slots are free. A run that outlives `timeout_seconds` is
+// stopped (its process killed), and the job fails with a timeout error and
+// `scheduler:job-timeout`. FullIntegration, GoalReminder and Custom jobs
+// are run by the modules that own them (transformation.rs, goals.rs,
+// macros.rs), through run_owned, so the same slots and timeout apply.
+//
+// While a job runs its stdout is sent as it comes in `scheduler:job-output`
+// events, numbered per run, for a live log. The exit status, the tail of
+// stdout and stderr and any JSON result the script wrote go into the job
+// record; all but the result also go into the run's entry in the job
+// history. Daily consolidation and synthesis jobs are created at their
+// configured times. A job with dependencies waits until they have
+// completed; when the last one does, the waiting job is triggered straight
+// away.
+//
+// While the machine sleeps the minute timer stops but the clock doesn't,
+// so a check that comes much later than the last one means the machine
+// woke up (the first check after launch counts too). Jobs that came due in
+// between are misfires, handled by each job's misfire policy: skipped to
+// their next time, run once (the default), or run once for every missed
+// day, up to MAX_MISSED_RUNS, one after another. Each misfire is announced
+// with `scheduler:misfire`.
+//
+// A run that finds the psychology files locked by another writer (a busy
+// error, or a script exiting with EXIT_LOCKED) is deferred and tried again
+// shortly; it uses up no retry and the schedule is left as it was.
+//
+// One-shot jobs (`run_at`) run when due like any other, but aren't
+// rescheduled or replayed: once completed they are gone or archived, and
+// one that failed for good stays failed until triggered again.
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::commands::psychology::{self, psychology_script};
+use crate::commands::rust_executables::find_binary;
+use crate::commands::scheduler::{self, JobStatus, JobType, MisfirePolicy, SchedulerConfig, SchedulerJob};
+use crate::commands::{CommandError, ErrorCode};
+use crate::policy::guardrails::{self, WriteSource};
+use crate::psychology::lock;
+use crate::scripts::PythonScript;
+use crate::policy::isolation::{self, ProcessKind};
+use crate::tasks::run_isolated_process;
+
+/// How often due jobs are looked for
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Period of job types without a configured time
+const DEFAULT_PERIOD_SECS: u64 = 24 * 3600;
+/// Characters of stdout and stderr kept in the job record
+const OUTPUT_TAIL_CHARS: usize = 4000;
+/// A check this long after the previous one follows a sleep
+const WAKE_GAP_SECS: u64 = 180;
+/// Jobs overdue by less than this on wake ran late, not missed
+const MISFIRE_GRACE_SECS: u64 = 300;
+/// Most missed runs replayed under RunAllMissed, counting the first
+const MAX_MISSED_RUNS: u64 = 7;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Execution slots, as many as `max_concurrent_jobs`
+static SLOTS: Semaphore = Semaphore::const_new(0);
+/// Slots SLOTS stands for, taken or not
+static SLOT_COUNT: Mutex<usize> = Mutex::new(0);
+/// Taken slots to drop when they come back, after the limit was lowered
+static SLOTS_OWED: AtomicUsize = AtomicUsize::new(0);
+
+/// What a job type runs
+enum Action {
+    /// A helix-rust binary
+    Binary { name: &'static str, args: Vec<String> },
+    /// Synthesis in one of synthesis.py's HELIX_SYNTHESIS_MODEs
+    Synthesis { mode: &'static str },
+}
+
+/// Outcome of a run, stored as the job's result
+#[derive(Debug, Clone, Serialize)]
+pub struct JobOutput {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+    /// JSON the script wrote to HELIX_RESULT_FILE
+    pub result: Option<serde_json::Value>,
+}
+
+/// Payload for scheduler:job-timeout event
+#[derive(Debug, Clone, Serialize)]
+pub struct JobTimeout {
+    pub job_id: String,
+    pub job_type: JobType,
+    pub timeout_seconds: u64,
+}
+
+/// Payload for scheduler:job-output event
+#[derive(Debug, Clone, Serialize)]
+pub struct JobOutputChunk {
+    pub job_id: String,
+    pub job_type: JobType,
+    /// 0 for the first chunk of a run
+    pub seq: u64,
+    /// Whole lines of stdout, unless a line is very long
+    pub chunk: String,
+}
+
+/// Payload for scheduler:misfire event
+#[derive(Debug, Clone, Serialize)]
+pub struct Misfire {
+    pub job_id: String,
+    pub job_type: JobType,
+    pub policy: MisfirePolicy,
+    /// Runs missed, at most MAX_MISSED_RUNS
+    pub missed: u32,
+    /// When the first missed run was due, seconds since epoch
+    pub due_at: u64,
+}
+
+/// Resize SLOTS to `limit`
+fn set_concurrency(limit: u32) {
+    let limit = limit.max(1) as usize;
+    let Ok(mut count) = SLOT_COUNT.lock() else {
+        return;
+    };
+    if limit > *count {
+        // Slots still owed are cancelled rather than added
+        let added = limit - *count;
+        let owed = SLOTS_OWED.swap(0, Ordering::SeqCst);
+        SLOTS_OWED.store(owed.saturating_sub(added), Ordering::SeqCst);
+        SLOTS.add_permits(added.saturating_sub(owed));
+    } else if limit < *count {
+        let removed = *count - limit;
+        let forgotten = SLOTS.forget_permits(removed);
+        SLOTS_OWED.fetch_add(removed - forgotten, Ordering::SeqCst);
+    }
+    *count = limit;
+}
+
+/// Give a slot back, unless the limit was lowered while it was taken
+fn release(slot: SemaphorePermit<'static>) {
+    let owed = SLOTS_OWED
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |owed| owed.checked_sub(1))
+        .is_ok();
+    if owed {
+        slot.forget();
+    }
+}
+
+/// Whether jobs of this type are carried out here
+pub fn dispatches(job_type: &JobType) -> bool {
+    matches!(
+        job_type,
+        JobType::Consolidation
+            | JobType::Synthesis
+            | JobType::MemoryFadeout
+            | JobType::PatternAnalysis
+            | JobType::RecommendationGeneration
+    )
+}
+
+fn action(job_type: &JobType) -> Result<Action, CommandError> {
+    match job_type {
+        JobType::Consolidation => {
+            let user_id = crate::accounts::profiles::active_user_id()
+                .ok_or_else(|| CommandError::unauthenticated("Memory consolidation needs a signed-in account"))?;
+            Ok(Action::Binary {
+                name: "memory-synthesis",
+                args: vec!["--user-id".to_string(), user_id],
+            })
+        }
+        JobType::MemoryFadeout => Ok(Action::Binary {
+            name: "psychology-decay",
+            args: vec!["--once".to_string()],
+        }),
+        JobType::Synthesis => Ok(Action::Synthesis { mode: "full" }),
+        JobType::PatternAnalysis => Ok(Action::Synthesis { mode: "emotional" }),
+        JobType::RecommendationGeneration => Ok(Action::Synthesis { mode: "wellness" }),
+        other => Err(CommandError::invalid_input(format!("{:?} jobs are not run by the scheduler", other))),
+    }
+}
+
+/// The last `OUTPUT_TAIL_CHARS` characters of `text`
+fn tail(text: &str) -> String {
+    let count = text.chars().count();
+    text.chars().skip(count.saturating_sub(OUTPUT_TAIL_CHARS)).collect()
+}
+
+/// Run what `job_type` stands for, handing its stdout to `on_stdout` as it
+/// comes
+async fn run(job_type: &JobType, mut on_stdout: impl FnMut(&str)) -> Result<JobOutput, CommandError> {
+    match action(job_type)? {
+        Action::Binary { name, args } => {
+            let binary = find_binary(name)?;
+            let mut cmd = tokio::process::Command::from(isolation::command(ProcessKind::Maintenance, &binary, &[])?);
+            cmd.args(&args);
+
+            let started = Instant::now();
+            let output = run_isolated_process(cmd, ProcessKind::Maintenance, on_stdout)
+                .await
+                .map_err(|e| CommandError::process(format!("Failed to run {}: {}", name, e)))?;
+            Ok(JobOutput {
+                command: std::iter::once(name.to_string()).chain(args).collect::<Vec<_>>().join(" "),
+                exit_code: output.status.code(),
+                success: output.status.success(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                stdout: tail(&String::from_utf8_lossy(&output.stdout)),
+                stderr: tail(&String::from_utf8_lossy(&output.stderr)),
+                result: None,
+            })
+        }
+        Action::Synthesis { mode } => {
+            let python = psychology::python_psychology();
+            let script = if python {
+                Some(
+                    PythonScript::new(psychology_script("synthesis.py")?).env("HELIX_SYNTHESIS_MODE", mode),
+                )
+            } else {
+                None
+            };
+            let helix_dir = crate::accounts::data_dir()?;
+            // Changes to protected content are rolled back once the run ends
+            let _snapshot = APP
+                .get()
+                .map(|app| guardrails::Snapshot::capture(app, WriteSource::Synthesis, &helix_dir));
+
+            let output = match script {
+                Some(script) => script.run_streaming(on_stdout).await?,
+                None => {
+                    psychology::native_synthesis(helix_dir.clone(), mode, false, |line| {
+                        on_stdout(&format!("{}\n", line))
+                    })
+                    .await?
+                }
+            };
+            if output.exit_code == Some(lock::EXIT_LOCKED) {
+                return Err(lock::busy());
+            }
+            Ok(JobOutput {
+                command: if python {
+                    format!("synthesis.py ({})", mode)
+                } else {
+                    format!("synthesis ({})", mode)
+                },
+                exit_code: output.exit_code,
+                success: output.success,
+                duration_ms: output.duration_ms,
+                stdout: tail(&output.stdout),
+                stderr: tail(&output.stderr),
+                result: output.result,
+            })
+        }
+    }
+}
+
+/// When a job of `job_type` runs next after a run now
+fn next_run(job_type: &JobType, config: &SchedulerConfig) -> u64 {
+    let configured = match job_type {
+        JobType::Consolidation => scheduler::next_daily(&config.consolidation_time),
+        JobType::Synthesis => scheduler::next_daily(&config.synthesis_time),
+        _ => None,
+    };
+    configured.unwrap_or_else(|| scheduler::now_secs() + DEFAULT_PERIOD_SECS)
+}
+
+fn job_timeout(config: &SchedulerConfig) -> Duration {
+    Duration::from_secs(config.timeout_seconds.max(1) as u64)
+}
+
+/// Log and announce a run that outlived `timeout`; returns the job's error
+fn timed_out(job: &SchedulerJob, timeout: Duration) -> CommandError {
+    log::warn!("Scheduler job {} timed out after {}s", job.id, timeout.as_secs());
+    if let Some(app) = APP.get() {
+        let _ = app.emit(
+            "scheduler:job-timeout",
+            JobTimeout {
+                job_id: job.id.clone(),
+                job_type: job.job_type.clone(),
+                timeout_seconds: timeout.as_secs(),
+            },
+        );
+    }
+    CommandError::new(ErrorCode::Timeout, format!("Timed out after {}s", timeout.as_secs()))
+}
+
+/// Carry out a job that trigger_job just marked running
+async fn execute(job: SchedulerJob, config: &SchedulerConfig) -> Result<(), CommandError> {
+    let timeout = job_timeout(config);
+    let mut seq = 0;
+    let on_stdout = |chunk: &str| {
+        if let Some(app) = APP.get() {
+            let _ = app.emit(
+                "scheduler:job-output",
+                JobOutputChunk {
+                    job_id: job.id.clone(),
+                    job_type: job.job_type.clone(),
+                    seq,
+                    chunk: chunk.to_string(),
+                },
+            );
+        }
+        seq += 1;
+    };
+
+    // Dropping the run on timeout kills its process (see run_isolated_process)
+    let outcome = match tokio::time::timeout(timeout, run(&job.job_type, on_stdout)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(timed_out(&job, timeout)),
+    };
+    if let Ok(output) = &outcome {
+        scheduler::set_run_output(
+            &job.id,
+            output.exit_code,
+            output.duration_ms,
+            output.stdout.clone(),
+            output.stderr.clone(),
+        )?;
+    }
+    let succeeded = matches!(&outcome, Ok(output) if output.success);
+    match outcome {
+        Ok(output) if output.success => {
+            log::info!("Scheduler job {} ({}) completed", job.id, output.command);
+            scheduler::complete_job(job.id.clone(), serde_json::to_value(&output).ok())?;
+        }
+        Ok(output) => {
+            let error = match output.stderr.trim() {
+                "" => format!("{} exited with code {:?}", output.command, output.exit_code),
+                stderr => stderr.lines().last().unwrap_or(stderr).to_string(),
+            };
+            log::warn!("Scheduler job {} failed: {}", job.id, error);
+            scheduler::set_job_result(&job.id, serde_json::to_value(&output).ok())?;
+            scheduler::fail_job(job.id.clone(), error)?;
+        }
+        Err(error) if error.code == ErrorCode::Busy => {
+            // Tried again shortly, for the same scheduled run
+            scheduler::defer_busy_job(&job.id, error.message)?;
+            return Ok(());
+        }
+        Err(error) => {
+            log::warn!("Scheduler job {} failed: {}", job.id, error.message);
+            scheduler::fail_job(job.id.clone(), error.message)?;
+        }
+    }
+
+    // A run that came due moves on to the next slot, or to the next missed
+    // run still owed; an early manual run leaves the schedule alone
+    if !job.is_one_shot() && job.next_run <= job.started_at.unwrap_or_else(scheduler::now_secs) {
+        let next = if scheduler::take_missed_run(&job.id, succeeded)? {
+            scheduler::now_secs()
+        } else {
+            next_run(&job.job_type, config)
+        };
+        scheduler::reschedule_job(&job.id, next)?;
+    }
+    Ok(())
+}
+
+/// Run `job` in the background once a slot is free; for jobs that
+/// trigger_job started
+pub fn spawn(job: SchedulerJob) {
+    tauri::async_runtime::spawn(async move {
+        let config = scheduler::get_scheduler_config().unwrap_or_default();
+        set_concurrency(config.max_concurrent_jobs);
+        if SLOTS.available_permits() == 0 {
+            log::info!("Scheduler job {} is waiting for a free slot", job.id);
+        }
+        let Ok(slot) = SLOTS.acquire().await else {
+            return;
+        };
+
+        let job_id = job.id.clone();
+        if let Err(e) = execute(job, &config).await {
+            log::warn!("Failed to record the run of scheduler job {}: {}", job_id, e.message);
+        }
+        release(slot);
+    });
+}
+
+/// Carry out `work` for a job its own module runs (FullIntegration,
+/// GoalReminder, Custom) under the same limits as the jobs run here: it
+/// waits for a free slot, and is dropped with a timeout error once it
+/// outlives `timeout_seconds`
+pub async fn run_owned<T>(
+    job: &SchedulerJob,
+    work: impl Future<Output = Result<T, CommandError>>,
+) -> Result<T, CommandError> {
+    let config = scheduler::get_scheduler_config().unwrap_or_default();
+    set_concurrency(config.max_concurrent_jobs);
+    if SLOTS.available_permits() == 0 {
+        log::info!("Scheduler job {} is waiting for a free slot", job.id);
+    }
+    let slot = SLOTS
+        .acquire()
+        .await
+        .map_err(|_| CommandError::internal("Scheduler slots are closed"))?;
+
+    let timeout = job_timeout(&config);
+    let outcome = tokio::time::timeout(timeout, work).await;
+    release(slot);
+    outcome.unwrap_or_else(|_| Err(timed_out(job, timeout)))
+}
+
+/// The job of `job_type`, created to run next at the daily `time`
+fn ensure_daily(job_type: JobType, time: &str) -> Result<(), CommandError> {
+    let exists = scheduler::get_scheduled_jobs()?
+        .iter()
+        .any(|job| job.job_type == job_type && !job.is_one_shot());
+    if exists {
+        return Ok(());
+    }
+
+    let (hour, minute) = time.trim().split_once(':').unwrap_or(("0", "0"));
+    let number = |s: &str| s.trim().parse::<u32>().unwrap_or(0);
+    let cron = format!("{} {} * * *", number(minute), number(hour));
+    let job = scheduler::ensure_job(job_type, &cron)?;
+    if let Some(next) = scheduler::next_daily(time) {
+        scheduler::reschedule_job(&job.id, next)?;
+    }
+    Ok(())
+}
+
+/// Start the dispatchable jobs that are due, up to the concurrency limit
+async fn run_due_jobs() -> Result<(), CommandError> {
+    let config = scheduler::get_scheduler_config()?;
+    if !config.enabled || config.maintenance_mode.is_active(scheduler::now_secs()) {
+        return Ok(());
+    }
+    if config.daily_consolidation {
+        ensure_daily(JobType::Consolidation, &config.consolidation_time)?;
+    }
+    if config.daily_synthesis {
+        ensure_daily(JobType::Synthesis, &config.synthesis_time)?;
+    }
+
+    set_concurrency(config.max_concurrent_jobs);
+    let jobs = scheduler::get_scheduled_jobs()?;
+    let mut slots = SLOTS.available_permits();
+    let now = scheduler::now_secs();
+
+    // Sorted by next_run, so the longest overdue go first
+    for job in jobs {
+        if slots == 0 {
+            break;
+        }
+        if !dispatches(&job.job_type)
+            || matches!(job.status, JobStatus::Paused | JobStatus::Running)
+            || job.is_spent()
+            || job.next_run > now
+        {
+            continue;
+        }
+        // trigger_job defers the job or hands it back to spawn
+        if scheduler::trigger_job(job.id).await?.status == JobStatus::Running {
+            slots -= 1;
+        }
+    }
+    Ok(())
+}
+
+/// Apply the misfire policy of each maintenance job that came due while
+/// the machine slept
+fn handle_misfires() -> Result<(), CommandError> {
+    let config = scheduler::get_scheduler_config()?;
+    let now = scheduler::now_secs();
+    // Runs due during maintenance mode start once it ends, as any due run
+    if !config.enabled || config.maintenance_mode.is_active(now) {
+        return Ok(());
+    }
+
+    for job in scheduler::get_scheduled_jobs()? {
+        // Deferred and retrying jobs are due at a time of their own making
+        let retrying = job.status == JobStatus::Pending && job.retry_count > 0;
+        // A one-shot job has no later run to skip to or replay; it runs late
+        if !dispatches(&job.job_type)
+            || job.is_one_shot()
+            || retrying
+            || matches!(
+                job.status,
+                JobStatus::Paused | JobStatus::Running | JobStatus::Waiting | JobStatus::Deferred
+            )
+            || job.next_run + MISFIRE_GRACE_SECS > now
+        {
+            continue;
+        }
+
+        let missed = (1 + (now - job.next_run) / DEFAULT_PERIOD_SECS).min(MAX_MISSED_RUNS) as u32;
+        log::info!(
+            "Scheduler job {} missed {} run(s) since {}; policy {:?}",
+            job.id,
+            missed,
+            job.next_run,
+            job.misfire_policy
+        );
+        match job.misfire_policy {
+            MisfirePolicy::Skip => scheduler::reschedule_job(&job.id, next_run(&job.job_type, &config))?,
+            MisfirePolicy::RunOnceImmediately => {}
+            MisfirePolicy::RunAllMissed => scheduler::set_missed_runs(&job.id, missed - 1)?,
+        }
+        if let Some(app) = APP.get() {
+            let _ = app.emit(
+                "scheduler:misfire",
+                Misfire {
+                    job_id: job.id.clone(),
+                    job_type: job.job_type.clone(),
+                    policy: job.misfire_policy,
+                    missed,
+                    due_at: job.next_run,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Look for due jobs every minute, and for missed ones after a sleep
+pub fn start(app: AppHandle) {
+    if APP.set(app).is_err() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_check: Option<u64> = None;
+        loop {
+            let now = scheduler::now_secs();
+            let woke = last_check.is_none_or(|last| now.saturating_sub(last) > WAKE_GAP_SECS);
+            last_check = Some(now);
+            if woke {
+                if let Err(e) = handle_misfires() {
+                    log::debug!("Scheduler misfire check skipped: {}", e);
+                }
+            }
+            if let Err(e) = run_due_jobs().await {
+                log::debug!("Scheduler job check skipped: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
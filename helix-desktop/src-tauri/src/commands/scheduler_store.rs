@@ -0,0 +1,499 @@
+// Scheduler store - SQLite persistence for scheduler jobs and their runs
+//
+// The scheduler keeps its registry in memory for the synchronous commands
+// and writes every change through to `scheduler.db` in the account's data
+// directory, so jobs, statuses and run history survive a restart. Writes
+// are queued to one task that applies them in order, each to the database
+// of the account that was active when it was made; loading goes through
+// the same queue, so it sees every write made before it. The schema is
+// versioned with `PRAGMA user_version` and migrated on open.
+//
+// `job_runs` is the jobs' audit log: runs are upserted by their number and
+// never dropped on save, so it holds every run (with its duration, exit
+// status and output tails) while a job record only carries the latest
+// MAX_JOB_RUNS. Old runs go with purge_job_history or with their job.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::Row;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::sync::{mpsc, oneshot};
+
+use super::scheduler::{JobRun, SchedulerJob, MAX_JOB_RUNS};
+
+const DB_FILENAME: &str = "scheduler.db";
+
+/// Schema changes, in order; a database at user_version N has had the
+/// first N applied
+const MIGRATIONS: &[&[&str]] = &[
+    // Jobs and their runs
+    &[
+        "CREATE TABLE jobs (
+            id TEXT PRIMARY KEY,
+            job_type TEXT NOT NULL,
+            status TEXT NOT NULL,
+            scheduled_at INTEGER NOT NULL,
+            started_at INTEGER,
+            completed_at INTEGER,
+            cron_expression TEXT NOT NULL,
+            next_run INTEGER NOT NULL,
+            last_run INTEGER,
+            duration_ms INTEGER,
+            error TEXT,
+            result TEXT,
+            preconditions TEXT NOT NULL,
+            precondition_deferrals INTEGER NOT NULL DEFAULT 0,
+            macro_name TEXT
+        )",
+        "CREATE TABLE job_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL REFERENCES jobs (id) ON DELETE CASCADE,
+            started_at INTEGER NOT NULL,
+            finished_at INTEGER,
+            status TEXT NOT NULL,
+            preconditions TEXT NOT NULL,
+            error TEXT
+        )",
+        "CREATE INDEX job_runs_job_id ON job_runs (job_id, id)",
+    ],
+    // Retry policy
+    &[
+        "ALTER TABLE jobs ADD COLUMN max_retries INTEGER NOT NULL DEFAULT 3",
+        "ALTER TABLE jobs ADD COLUMN retry_backoff_seconds INTEGER NOT NULL DEFAULT 300",
+        "ALTER TABLE jobs ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0",
+    ],
+    // Run history: numbered runs with their process outcome
+    &[
+        "ALTER TABLE job_runs ADD COLUMN run_number INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE job_runs ADD COLUMN duration_ms INTEGER",
+        "ALTER TABLE job_runs ADD COLUMN exit_code INTEGER",
+        "ALTER TABLE job_runs ADD COLUMN stdout TEXT",
+        "ALTER TABLE job_runs ADD COLUMN stderr TEXT",
+        // Saves inserted each job's runs in order, so ids number them
+        "UPDATE job_runs SET run_number = id",
+        "UPDATE job_runs SET duration_ms = (finished_at - started_at) * 1000 WHERE finished_at IS NOT NULL",
+        "CREATE UNIQUE INDEX job_runs_number ON job_runs (job_id, run_number)",
+        "CREATE INDEX job_runs_started_at ON job_runs (started_at)",
+    ],
+    // Job dependencies, a JSON array of job ids
+    &["ALTER TABLE jobs ADD COLUMN depends_on TEXT NOT NULL DEFAULT '[]'"],
+    // Misfire policy
+    &[
+        "ALTER TABLE jobs ADD COLUMN misfire_policy TEXT NOT NULL DEFAULT 'run_once_immediately'",
+        "ALTER TABLE jobs ADD COLUMN missed_runs INTEGER NOT NULL DEFAULT 0",
+    ],
+    // One-shot jobs
+    &[
+        "ALTER TABLE jobs ADD COLUMN run_at INTEGER",
+        "ALTER TABLE jobs ADD COLUMN after_run TEXT NOT NULL DEFAULT 'delete'",
+    ],
+    // Jobs allowed to start during quiet hours
+    &["ALTER TABLE jobs ADD COLUMN run_in_quiet_hours INTEGER NOT NULL DEFAULT 0"],
+];
+
+enum Op {
+    Save(Box<SchedulerJob>),
+    Delete(String),
+    Load(oneshot::Sender<Result<Vec<SchedulerJob>, String>>),
+    History {
+        job_id: String,
+        limit: usize,
+        reply: oneshot::Sender<Result<Vec<JobRun>, String>>,
+    },
+    Purge {
+        before: u64,
+        reply: oneshot::Sender<Result<u64, String>>,
+    },
+}
+
+/// Queue of the store task
+static QUEUE: LazyLock<mpsc::UnboundedSender<(PathBuf, Op)>> = LazyLock::new(|| {
+    let (queue, receiver) = mpsc::unbounded_channel();
+    tauri::async_runtime::spawn(run(receiver));
+    queue
+});
+
+fn db_path() -> Result<PathBuf, String> {
+    let dir = crate::accounts::data_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    Ok(dir.join(DB_FILENAME))
+}
+
+fn enqueue(op: Op) -> Result<(), String> {
+    let path = db_path()?;
+    QUEUE
+        .send((path, op))
+        .map_err(|_| "Scheduler store is not running".to_string())
+}
+
+/// Write `job` and its runs through to the database
+pub fn save(job: &SchedulerJob) {
+    if let Err(e) = enqueue(Op::Save(Box::new(job.clone()))) {
+        log::warn!("Failed to save scheduler job {}: {}", job.id, e);
+    }
+}
+
+/// Remove a job and its runs from the database
+pub fn delete(job_id: &str) {
+    if let Err(e) = enqueue(Op::Delete(job_id.to_string())) {
+        log::warn!("Failed to delete scheduler job {}: {}", job_id, e);
+    }
+}
+
+/// Every job stored for the active account
+pub async fn load() -> Result<Vec<SchedulerJob>, String> {
+    let (reply, response) = oneshot::channel();
+    enqueue(Op::Load(reply))?;
+    response
+        .await
+        .map_err(|_| "Scheduler store is not running".to_string())?
+}
+
+/// Runs of `job_id`, newest first
+pub async fn history(job_id: &str, limit: usize) -> Result<Vec<JobRun>, String> {
+    let (reply, response) = oneshot::channel();
+    enqueue(Op::History {
+        job_id: job_id.to_string(),
+        limit,
+        reply,
+    })?;
+    response
+        .await
+        .map_err(|_| "Scheduler store is not running".to_string())?
+}
+
+/// Delete finished runs that started before `before`; returns how many
+pub async fn purge_history(before: u64) -> Result<u64, String> {
+    let (reply, response) = oneshot::channel();
+    enqueue(Op::Purge { before, reply })?;
+    response
+        .await
+        .map_err(|_| "Scheduler store is not running".to_string())?
+}
+
+/// Apply queued operations in order, keeping the last database open
+async fn run(mut queue: mpsc::UnboundedReceiver<(PathBuf, Op)>) {
+    let mut open: Option<(PathBuf, SqlitePool)> = None;
+    while let Some((path, op)) = queue.recv().await {
+        let pool = match &open {
+            Some((open_path, pool)) if *open_path == path => Ok(pool.clone()),
+            _ => {
+                if let Some((_, old)) = open.take() {
+                    old.close().await;
+                }
+                connect(&path).await.inspect(|pool| open = Some((path, pool.clone())))
+            }
+        };
+
+        match (pool, op) {
+            (Ok(pool), Op::Save(job)) => {
+                if let Err(e) = write_job(&pool, &job).await {
+                    log::warn!("Failed to save scheduler job {}: {}", job.id, e);
+                }
+            }
+            (Ok(pool), Op::Delete(job_id)) => {
+                if let Err(e) = delete_job(&pool, &job_id).await {
+                    log::warn!("Failed to delete scheduler job {}: {}", job_id, e);
+                }
+            }
+            (Ok(pool), Op::Load(reply)) => {
+                let _ = reply.send(read_jobs(&pool).await);
+            }
+            (Ok(pool), Op::History { job_id, limit, reply }) => {
+                let _ = reply.send(read_history(&pool, &job_id, limit).await);
+            }
+            (Ok(pool), Op::Purge { before, reply }) => {
+                let _ = reply.send(purge_runs(&pool, before).await);
+            }
+            (Err(e), Op::Load(reply)) => {
+                let _ = reply.send(Err(e));
+            }
+            (Err(e), Op::History { reply, .. }) => {
+                let _ = reply.send(Err(e));
+            }
+            (Err(e), Op::Purge { reply, .. }) => {
+                let _ = reply.send(Err(e));
+            }
+            (Err(e), _) => log::warn!("Scheduler job not written: {}", e),
+        }
+    }
+}
+
+async fn connect(path: &Path) -> Result<SqlitePool, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .foreign_keys(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open scheduler database: {}", e))?;
+    migrate(&pool).await?;
+    Ok(pool)
+}
+
+async fn migrate(pool: &SqlitePool) -> Result<(), String> {
+    let (version,) = sqlx::query_as::<_, (i64,)>("PRAGMA user_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read scheduler schema version: {}", e))?;
+
+    for (index, statements) in MIGRATIONS.iter().enumerate().skip(version.max(0) as usize) {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for statement in *statements {
+            sqlx::query(statement)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Scheduler migration {} failed: {}", index + 1, e))?;
+        }
+        sqlx::query(&format!("PRAGMA user_version = {}", index + 1))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        log::info!("Scheduler database migrated to version {}", index + 1);
+    }
+    Ok(())
+}
+
+/// The serde name of a unit enum variant
+fn variant_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn from_variant_name<T: DeserializeOwned>(name: String) -> Result<T, String> {
+    serde_json::from_value(Value::String(name)).map_err(|e| e.to_string())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+async fn write_job(pool: &SqlitePool, job: &SchedulerJob) -> Result<(), String> {
+    let result = job.result.as_ref().map(to_json).transpose()?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // An upsert: REPLACE would delete the row and cascade to its runs
+    sqlx::query(
+        "INSERT INTO jobs (id, job_type, status, scheduled_at, started_at, completed_at,
+            cron_expression, next_run, last_run, duration_ms, error, result, preconditions,
+            precondition_deferrals, macro_name, max_retries, retry_backoff_seconds, retry_count,
+            depends_on, misfire_policy, missed_runs, run_at, after_run, run_in_quiet_hours)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT (id) DO UPDATE SET
+            job_type = excluded.job_type, status = excluded.status,
+            scheduled_at = excluded.scheduled_at, started_at = excluded.started_at,
+            completed_at = excluded.completed_at, cron_expression = excluded.cron_expression,
+            next_run = excluded.next_run, last_run = excluded.last_run,
+            duration_ms = excluded.duration_ms, error = excluded.error, result = excluded.result,
+            preconditions = excluded.preconditions,
+            precondition_deferrals = excluded.precondition_deferrals,
+            macro_name = excluded.macro_name, max_retries = excluded.max_retries,
+            retry_backoff_seconds = excluded.retry_backoff_seconds,
+            retry_count = excluded.retry_count, depends_on = excluded.depends_on,
+            misfire_policy = excluded.misfire_policy, missed_runs = excluded.missed_runs,
+            run_at = excluded.run_at, after_run = excluded.after_run,
+            run_in_quiet_hours = excluded.run_in_quiet_hours",
+    )
+    .bind(&job.id)
+    .bind(variant_name(&job.job_type))
+    .bind(variant_name(&job.status))
+    .bind(job.scheduled_at as i64)
+    .bind(job.started_at.map(|at| at as i64))
+    .bind(job.completed_at.map(|at| at as i64))
+    .bind(&job.cron_expression)
+    .bind(job.next_run as i64)
+    .bind(job.last_run.map(|at| at as i64))
+    .bind(job.duration_ms.map(|ms| ms as i64))
+    .bind(&job.error)
+    .bind(result)
+    .bind(to_json(&job.preconditions)?)
+    .bind(job.precondition_deferrals as i64)
+    .bind(&job.macro_name)
+    .bind(job.max_retries as i64)
+    .bind(job.retry_backoff_seconds as i64)
+    .bind(job.retry_count as i64)
+    .bind(to_json(&job.depends_on)?)
+    .bind(variant_name(&job.misfire_policy))
+    .bind(job.missed_runs as i64)
+    .bind(job.run_at.map(|at| at as i64))
+    .bind(variant_name(&job.after_run))
+    .bind(job.run_in_quiet_hours)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // The registry only holds the latest runs; older ones stay as they are
+    for run in &job.runs {
+        sqlx::query(
+            "INSERT INTO job_runs (job_id, run_number, started_at, finished_at, status, preconditions,
+                error, duration_ms, exit_code, stdout, stderr)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (job_id, run_number) DO UPDATE SET
+                started_at = excluded.started_at, finished_at = excluded.finished_at,
+                status = excluded.status, preconditions = excluded.preconditions,
+                error = excluded.error, duration_ms = excluded.duration_ms,
+                exit_code = excluded.exit_code, stdout = excluded.stdout, stderr = excluded.stderr",
+        )
+        .bind(&job.id)
+        .bind(run.number as i64)
+        .bind(run.started_at as i64)
+        .bind(run.finished_at.map(|at| at as i64))
+        .bind(variant_name(&run.status))
+        .bind(to_json(&run.preconditions)?)
+        .bind(&run.error)
+        .bind(run.duration_ms.map(|ms| ms as i64))
+        .bind(run.exit_code)
+        .bind(&run.stdout)
+        .bind(&run.stderr)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn delete_job(pool: &SqlitePool, job_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn optional_secs(row: &SqliteRow, column: &str) -> Result<Option<u64>, String> {
+    row.try_get::<Option<i64>, _>(column)
+        .map(|value| value.map(|v| v.max(0) as u64))
+        .map_err(|e| e.to_string())
+}
+
+fn secs(row: &SqliteRow, column: &str) -> Result<u64, String> {
+    row.try_get::<i64, _>(column)
+        .map(|value| value.max(0) as u64)
+        .map_err(|e| e.to_string())
+}
+
+fn text(row: &SqliteRow, column: &str) -> Result<String, String> {
+    row.try_get(column).map_err(|e| e.to_string())
+}
+
+fn optional_text(row: &SqliteRow, column: &str) -> Result<Option<String>, String> {
+    row.try_get(column).map_err(|e| e.to_string())
+}
+
+fn run_from_row(row: &SqliteRow) -> Result<JobRun, String> {
+    Ok(JobRun {
+        number: secs(row, "run_number")?,
+        started_at: secs(row, "started_at")?,
+        finished_at: optional_secs(row, "finished_at")?,
+        status: from_variant_name(text(row, "status")?)?,
+        preconditions: from_json(&text(row, "preconditions")?)?,
+        error: optional_text(row, "error")?,
+        duration_ms: optional_secs(row, "duration_ms")?,
+        exit_code: row.try_get("exit_code").map_err(|e| e.to_string())?,
+        stdout: optional_text(row, "stdout")?,
+        stderr: optional_text(row, "stderr")?,
+    })
+}
+
+fn job_from_row(row: &SqliteRow) -> Result<SchedulerJob, String> {
+    Ok(SchedulerJob {
+        id: text(row, "id")?,
+        job_type: from_variant_name(text(row, "job_type")?)?,
+        status: from_variant_name(text(row, "status")?)?,
+        scheduled_at: secs(row, "scheduled_at")?,
+        started_at: optional_secs(row, "started_at")?,
+        completed_at: optional_secs(row, "completed_at")?,
+        cron_expression: text(row, "cron_expression")?,
+        next_run: secs(row, "next_run")?,
+        last_run: optional_secs(row, "last_run")?,
+        duration_ms: optional_secs(row, "duration_ms")?,
+        error: optional_text(row, "error")?,
+        result: optional_text(row, "result")?.as_deref().map(from_json).transpose()?,
+        preconditions: from_json(&text(row, "preconditions")?)?,
+        precondition_deferrals: secs(row, "precondition_deferrals")? as u32,
+        runs: Vec::new(),
+        macro_name: optional_text(row, "macro_name")?,
+        max_retries: secs(row, "max_retries")? as u32,
+        retry_backoff_seconds: secs(row, "retry_backoff_seconds")?,
+        retry_count: secs(row, "retry_count")? as u32,
+        depends_on: from_json(&text(row, "depends_on")?)?,
+        misfire_policy: from_variant_name(text(row, "misfire_policy")?)?,
+        missed_runs: secs(row, "missed_runs")? as u32,
+        run_at: optional_secs(row, "run_at")?,
+        after_run: from_variant_name(text(row, "after_run")?)?,
+        run_in_quiet_hours: secs(row, "run_in_quiet_hours")? != 0,
+    })
+}
+
+async fn read_jobs(pool: &SqlitePool) -> Result<Vec<SchedulerJob>, String> {
+    let rows = sqlx::query("SELECT * FROM jobs")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let run_rows = sqlx::query(
+        "SELECT * FROM (
+            SELECT *, ROW_NUMBER() OVER (PARTITION BY job_id ORDER BY run_number DESC) AS recent
+            FROM job_runs
+         ) WHERE recent <= ? ORDER BY job_id, run_number",
+    )
+    .bind(MAX_JOB_RUNS as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut jobs = Vec::with_capacity(rows.len());
+    for row in &rows {
+        // A row this version can't read (a job type from a newer build) is
+        // skipped rather than failing the whole load
+        match job_from_row(row) {
+            Ok(job) => jobs.push(job),
+            Err(e) => log::warn!("Skipping unreadable scheduler job: {}", e),
+        }
+    }
+    for row in &run_rows {
+        let Ok(job_id) = text(row, "job_id") else {
+            continue;
+        };
+        if let Some(job) = jobs.iter_mut().find(|job| job.id == job_id) {
+            match run_from_row(row) {
+                Ok(run) => job.runs.push(run),
+                Err(e) => log::debug!("Skipping unreadable run of job {}: {}", job_id, e),
+            }
+        }
+    }
+    Ok(jobs)
+}
+
+async fn read_history(pool: &SqlitePool, job_id: &str, limit: usize) -> Result<Vec<JobRun>, String> {
+    let rows = sqlx::query("SELECT * FROM job_runs WHERE job_id = ? ORDER BY run_number DESC LIMIT ?")
+        .bind(job_id)
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    rows.iter().map(run_from_row).collect()
+}
+
+async fn purge_runs(pool: &SqlitePool, before: u64) -> Result<u64, String> {
+    sqlx::query("DELETE FROM job_runs WHERE started_at < ? AND finished_at IS NOT NULL")
+        .bind(before as i64)
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,285 @@
+// Scheduler export and import - moving Layer 5 schedules between machines
+//
+// `export_scheduler_state` bundles the scheduler config and job
+// definitions as JSON; run history stays behind. `import_scheduler_state`
+// checks a bundle as a whole before touching anything: the config's times
+// and days, each job's retry policy and macro, and the dependencies of the
+// jobs it would leave (all present, no cycles). `replace` drops the jobs
+// this machine has; `merge` keeps them, but a bundled job replaces the one
+// with its id and, except for Custom jobs, the ones of its type, since the
+// backend keeps one recurring job per maintenance type. Imported jobs
+// start afresh: no runs, retries or errors, and a run time in the past
+// moves to an hour from now; archived one-shot jobs stay archived. The
+// local pause (`paused_until`) and maintenance mode are kept either way,
+// and an import that would replace a running job is refused.
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::commands::scheduler::{self, JobStatus, JobType, SchedulerConfig, SchedulerJob};
+use crate::commands::scheduler_store;
+use crate::commands::{CommandError, ErrorCode};
+
+/// Bumped when the bundle layout changes incompatibly
+const BUNDLE_VERSION: u32 = 1;
+/// Delay before the first run of an imported job whose time has passed
+const IMPORTED_RUN_DELAY_SECS: u64 = 3600;
+
+/// Scheduler config and jobs, as exported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerBundle {
+    pub version: u32,
+    /// Seconds since epoch
+    pub exported_at: u64,
+    pub config: SchedulerConfig,
+    pub jobs: Vec<SchedulerJob>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Keep this machine's jobs alongside the bundled ones
+    Merge,
+    /// Drop this machine's jobs first
+    Replace,
+}
+
+/// What an import changed
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerImport {
+    pub mode: ImportMode,
+    /// Bundled jobs now in the scheduler
+    pub imported: usize,
+    /// Jobs of this machine that were deleted or replaced
+    pub removed: Vec<String>,
+}
+
+fn validate_time(field: &str, time: &str, problems: &mut Vec<String>) {
+    if NaiveTime::parse_from_str(time.trim(), "%H:%M").is_err() {
+        problems.push(format!("{} must be HH:MM, got '{}'", field, time));
+    }
+}
+
+fn validate_config(config: &SchedulerConfig, problems: &mut Vec<String>) {
+    validate_time("consolidation_time", &config.consolidation_time, problems);
+    validate_time("synthesis_time", &config.synthesis_time, problems);
+    validate_time("integration_time", &config.integration_time, problems);
+    if !config.integration_day.trim().parse::<u32>().is_ok_and(|day| day <= 6) {
+        problems.push(format!("integration_day must be 0-6, got '{}'", config.integration_day));
+    }
+    if !(1..=31).contains(&config.synthesis_day) {
+        problems.push(format!("synthesis_day must be 1-31, got {}", config.synthesis_day));
+    }
+    if config.max_concurrent_jobs == 0 {
+        problems.push("max_concurrent_jobs must be at least 1".to_string());
+    }
+    if config.timeout_seconds == 0 {
+        problems.push("timeout_seconds must be at least 1".to_string());
+    }
+    if config.jitter_minutes > scheduler::MAX_JITTER_MINUTES {
+        problems.push(format!("jitter_minutes must be at most {}", scheduler::MAX_JITTER_MINUTES));
+    }
+    if let Some(Err(e)) = config.quiet_hours.as_ref().map(|quiet_hours| quiet_hours.validate()) {
+        problems.push(e);
+    }
+}
+
+fn validate_job(job: &SchedulerJob, problems: &mut Vec<String>) {
+    if job.id.trim().is_empty() {
+        problems.push("A job has no id".to_string());
+        return;
+    }
+    if let Err(e) = scheduler::validate_retry_policy(Some(job.max_retries), Some(job.retry_backoff_seconds)) {
+        problems.push(format!("Job {}: {}", job.id, e.message));
+    }
+    match (&job.job_type, &job.macro_name) {
+        (JobType::Custom, Some(name)) => {
+            if crate::automation::macros::load(name).is_err() {
+                problems.push(format!("Job {} replays macro '{}', which this machine doesn't have", job.id, name));
+            }
+        }
+        (JobType::Custom, None) => problems.push(format!("Custom job {} names no macro", job.id)),
+        (_, Some(_)) => problems.push(format!("Job {}: only Custom jobs run a macro", job.id)),
+        (_, None) => {}
+    }
+}
+
+/// A bundled job as it enters this machine's scheduler
+fn fresh(mut job: SchedulerJob, now: u64) -> SchedulerJob {
+    job.status = match job.status {
+        JobStatus::Paused | JobStatus::Archived => job.status,
+        _ => JobStatus::Pending,
+    };
+    if job.next_run <= now {
+        job.next_run = now + IMPORTED_RUN_DELAY_SECS;
+    }
+    if job.is_one_shot() {
+        job.run_at = Some(job.next_run);
+    }
+    job.started_at = None;
+    job.completed_at = None;
+    job.last_run = None;
+    job.duration_ms = None;
+    job.error = None;
+    job.result = None;
+    job.precondition_deferrals = 0;
+    job.runs.clear();
+    job.retry_count = 0;
+    job.missed_runs = 0;
+    job
+}
+
+/// The scheduler config and job definitions as a JSON bundle
+#[tauri::command]
+pub fn export_scheduler_state() -> Result<String, CommandError> {
+    let mut config = scheduler::get_scheduler_config()?;
+    config.paused_until = None;
+    config.maintenance_mode = Default::default();
+    let mut jobs: Vec<SchedulerJob> = scheduler::lock_registry()
+        .values()
+        .cloned()
+        .map(|mut job| {
+            job.runs.clear();
+            job
+        })
+        .collect();
+    jobs.sort_by(|a, b| a.scheduled_at.cmp(&b.scheduled_at).then_with(|| a.id.cmp(&b.id)));
+
+    let bundle = SchedulerBundle {
+        version: BUNDLE_VERSION,
+        exported_at: scheduler::now_secs(),
+        config,
+        jobs,
+    };
+    serde_json::to_string_pretty(&bundle).map_err(|e| CommandError::internal(e.to_string()))
+}
+
+/// Load a bundle from export_scheduler_state; nothing changes unless all
+/// of it is valid
+#[tauri::command]
+pub fn import_scheduler_state(json: String, mode: ImportMode) -> Result<SchedulerImport, CommandError> {
+    scheduler::ensure_scheduler_enabled()?;
+    let bundle: SchedulerBundle = serde_json::from_str(&json)
+        .map_err(|e| CommandError::invalid_input(format!("Not a scheduler export: {}", e)))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(CommandError::invalid_input(format!(
+            "This scheduler export is from a newer Helix (format {}, this one reads up to {})",
+            bundle.version, BUNDLE_VERSION
+        )));
+    }
+
+    let mut problems = Vec::new();
+    validate_config(&bundle.config, &mut problems);
+    let mut ids = HashSet::new();
+    for job in &bundle.jobs {
+        validate_job(job, &mut problems);
+        if !ids.insert(job.id.as_str()) {
+            problems.push(format!("Job {} appears more than once", job.id));
+        }
+    }
+    if !problems.is_empty() {
+        return Err(CommandError::invalid_input(format!("Invalid scheduler export: {}", problems[0]))
+            .with_details(serde_json::json!({ "problems": problems })));
+    }
+
+    let now = scheduler::now_secs();
+    let mut registry = scheduler::lock_registry();
+
+    // This machine's jobs that the bundle displaces, and the bundled job
+    // that takes over each one's place in others' dependencies
+    let mut replaced_by: HashMap<String, String> = HashMap::new();
+    for existing in registry.values() {
+        let replacement = match mode {
+            ImportMode::Replace => Some(String::new()),
+            ImportMode::Merge => bundle
+                .jobs
+                .iter()
+                .find(|job| {
+                    job.id == existing.id
+                        || (job.job_type != JobType::Custom
+                            && job.job_type == existing.job_type
+                            && !job.is_one_shot()
+                            && !existing.is_one_shot())
+                })
+                .map(|job| job.id.clone()),
+        };
+        if let Some(replacement) = replacement {
+            replaced_by.insert(existing.id.clone(), replacement);
+        }
+    }
+
+    let running: Vec<&str> = registry
+        .values()
+        .filter(|job| job.status == JobStatus::Running && replaced_by.contains_key(&job.id))
+        .map(|job| job.id.as_str())
+        .collect();
+    if !running.is_empty() {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            "Jobs the import would replace are running; try again once they finish",
+        )
+        .with_details(serde_json::json!({ "running": running })));
+    }
+
+    let mut next: HashMap<String, SchedulerJob> = registry
+        .values()
+        .filter(|job| !replaced_by.contains_key(&job.id))
+        .cloned()
+        .map(|mut job| {
+            let depends_on = std::mem::take(&mut job.depends_on);
+            for id in depends_on {
+                let id = replaced_by.get(&id).cloned().unwrap_or(id);
+                if !id.is_empty() && !job.depends_on.contains(&id) {
+                    job.depends_on.push(id);
+                }
+            }
+            (job.id.clone(), job)
+        })
+        .collect();
+    for job in &bundle.jobs {
+        next.insert(job.id.clone(), fresh(job.clone(), now));
+    }
+
+    for job in next.values() {
+        if let Err(e) = scheduler::check_dependencies(&next, &job.id, &job.depends_on) {
+            problems.push(format!("Job {}: {}", job.id, e.message));
+        }
+    }
+    if !problems.is_empty() {
+        return Err(CommandError::invalid_input(format!("Invalid scheduler export: {}", problems[0]))
+            .with_details(serde_json::json!({ "problems": problems })));
+    }
+
+    let mut config = bundle.config;
+    config.paused_until = scheduler::paused_until();
+    config.maintenance_mode = scheduler::maintenance_mode().unwrap_or_default();
+    scheduler::set_scheduler_config(config)?;
+
+    let mut removed: Vec<String> = replaced_by.into_keys().collect();
+    removed.sort();
+    for id in &removed {
+        scheduler_store::delete(id);
+    }
+    for job in next.values() {
+        let changed = registry
+            .get(&job.id)
+            .is_none_or(|current| current.depends_on != job.depends_on || ids.contains(job.id.as_str()));
+        if changed {
+            scheduler_store::save(job);
+        }
+    }
+    *registry = next;
+
+    log::info!(
+        "Imported {} scheduler jobs ({:?}), replacing {}",
+        bundle.jobs.len(),
+        mode,
+        removed.len()
+    );
+    Ok(SchedulerImport {
+        mode,
+        imported: bundle.jobs.len(),
+        removed,
+    })
+}
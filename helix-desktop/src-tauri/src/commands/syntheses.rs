@@ -0,0 +1,150 @@
+// Memory synthesis retrieval
+//
+// Detected patterns live in Supabase's memory_synthesis table, which the
+// app doesn't query directly. list_syntheses asks the memory-synthesis
+// server (`memory-synthesis --serve`, on loopback) for one filtered page of
+// the active account's patterns at a time, so the insights view never loads
+// every pattern ever created. The server is started on first use with a
+// per-launch token and left running like the other helpers.
+
+use helix_shared::pidfile;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::command;
+
+use crate::accounts::profiles;
+use crate::commands::rust_executables::{self, SYNTHESIS_SERVER, SYNTHESIS_SERVER_PORT};
+use crate::commands::{CommandError, ErrorCode};
+use crate::privacy::residency::{self, DataFeature};
+
+/// Time limit for one page request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a freshly started server gets to start listening
+const STARTUP_WAIT: Duration = Duration::from_secs(10);
+const STARTUP_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct Synthesis {
+    pub id: String,
+    pub user_id: String,
+    pub pattern_type: String,
+    pub memory_ids: Vec<String>,
+    pub synthesis_content: String,
+    pub confidence_score: f32,
+    pub created_at: String,
+}
+
+/// A page of syntheses, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct SynthesisPage {
+    pub items: Vec<Synthesis>,
+    pub page: u32,
+    pub page_size: u32,
+    /// Matches across all pages
+    pub total: i64,
+    pub has_more: bool,
+}
+
+#[derive(Serialize)]
+struct ListQuery<'a> {
+    user_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_confidence: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_size: Option<u32>,
+}
+
+async fn listening(port: u16) -> bool {
+    tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok()
+}
+
+/// Port and token of the running server, starting it first if needed
+async fn server() -> Result<(u16, String), CommandError> {
+    if let Some(holder) = pidfile::holder(SYNTHESIS_SERVER) {
+        let token = rust_executables::synthesis_token().ok_or_else(|| {
+            CommandError::unavailable("Memory synthesis server was started by another launch; stop it to use it here")
+                .with_details(serde_json::json!({ "pid": holder.pid }))
+        })?;
+        if let Some(port) = holder.port {
+            return Ok((port, token));
+        }
+    }
+
+    match rust_executables::start_memory_synthesis_server(None).await {
+        Ok(_) => {}
+        // Started by a concurrent call
+        Err(e) if e.code == ErrorCode::Conflict => {}
+        Err(e) => return Err(e),
+    }
+
+    let deadline = Instant::now() + STARTUP_WAIT;
+    while !listening(SYNTHESIS_SERVER_PORT).await {
+        if Instant::now() >= deadline {
+            return Err(CommandError::unavailable("Memory synthesis server did not start listening in time"));
+        }
+        tokio::time::sleep(STARTUP_POLL).await;
+    }
+    let token = rust_executables::synthesis_token()
+        .ok_or_else(|| CommandError::unavailable("Memory synthesis server token is missing"))?;
+    Ok((SYNTHESIS_SERVER_PORT, token))
+}
+
+/// One page of the active account's syntheses. `pattern_type` matches
+/// exactly; `page` is 1-based. The server caps page_size.
+#[command]
+pub async fn list_syntheses(
+    pattern_type: Option<String>,
+    min_confidence: Option<f32>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<SynthesisPage, CommandError> {
+    if min_confidence.is_some_and(|confidence| !(0.0..=1.0).contains(&confidence)) {
+        return Err(CommandError::invalid_input("min_confidence must be between 0 and 1")
+            .with_details(serde_json::json!({ "min_confidence": min_confidence })));
+    }
+
+    residency::require(DataFeature::Sync)?;
+    let user_id = profiles::active_user_id().ok_or_else(|| CommandError::unauthenticated("No active Helix account"))?;
+    let (port, token) = server().await?;
+    let query = ListQuery {
+        user_id: &user_id,
+        pattern_type: pattern_type.as_deref(),
+        min_confidence,
+        page,
+        page_size,
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::internal(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client
+        .get(format!("http://127.0.0.1:{}/syntheses", port))
+        .query(&query)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| CommandError::unavailable(format!("Memory synthesis server unreachable: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = format!("Memory synthesis server returned HTTP {}: {}", status, body);
+        return Err(if status.is_client_error() {
+            CommandError::invalid_input(message)
+        } else {
+            CommandError::unavailable(message)
+        });
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| CommandError::internal(format!("Invalid synthesis page: {}", e)))
+}
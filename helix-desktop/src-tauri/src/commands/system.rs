@@ -2,6 +2,7 @@
 
 use std::fs;
 use serde::Serialize;
+use crate::commands::CommandError;
 
 #[derive(Serialize)]
 pub struct SystemInfo {
@@ -23,22 +24,27 @@ pub struct HelixPaths {
 }
 
 #[tauri::command]
-pub fn get_system_info() -> Result<SystemInfo, String> {
+pub async fn get_system_info() -> Result<SystemInfo, CommandError> {
+    // Detecting node runs `node --version` on a cold cache
+    let node = tauri::async_runtime::spawn_blocking(|| crate::gateway::node::environment(false))
+        .await
+        .map_err(|e| CommandError::internal(format!("Node detection failed: {}", e)))?;
     Ok(SystemInfo {
         os: std::env::consts::OS.to_string(),
         arch: std::env::consts::ARCH.to_string(),
         platform: get_platform(),
-        node_version: get_node_version(),
+        // "v22.12.0", as `node --version` prints it
+        node_version: node.version.map(|version| format!("v{}", version)),
         helix_version: env!("CARGO_PKG_VERSION").to_string(),
     })
 }
 
 #[tauri::command]
-pub fn get_helix_paths() -> Result<HelixPaths, String> {
+pub fn get_helix_paths() -> Result<HelixPaths, CommandError> {
     let home = dirs::home_dir()
         .ok_or_else(|| "Could not find home directory".to_string())?;
 
-    let helix_dir = home.join(".helix");
+    let helix_dir = crate::accounts::data_dir()?;
 
     Ok(HelixPaths {
         home: home.to_string_lossy().to_string(),
@@ -51,21 +57,15 @@ pub fn get_helix_paths() -> Result<HelixPaths, String> {
 }
 
 #[tauri::command]
-pub fn is_first_run() -> Result<bool, String> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
-
-    let onboarded_marker = home.join(".helix").join(".onboarded");
+pub fn is_first_run() -> Result<bool, CommandError> {
+    let onboarded_marker = crate::accounts::data_dir()?.join(".onboarded");
 
     Ok(!onboarded_marker.exists())
 }
 
 #[tauri::command]
-pub fn mark_onboarded() -> Result<(), String> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
-
-    let helix_dir = home.join(".helix");
+pub fn mark_onboarded() -> Result<(), CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
     fs::create_dir_all(&helix_dir)
         .map_err(|e| format!("Failed to create .helix directory: {}", e))?;
 
@@ -90,7 +90,7 @@ fn get_platform() -> String {
 /// Get the node capabilities this desktop platform supports.
 /// Used by the frontend to determine what caps to declare in the gateway connect frame.
 #[tauri::command]
-pub fn get_node_capabilities() -> Result<Vec<String>, String> {
+pub fn get_node_capabilities() -> Result<Vec<String>, CommandError> {
     let mut caps = vec!["system".to_string(), "clipboard".to_string()];
 
     // Platform-specific capabilities
@@ -115,18 +115,3 @@ pub fn get_node_capabilities() -> Result<Vec<String>, String> {
     Ok(caps)
 }
 
-fn get_node_version() -> Option<String> {
-    use std::process::Command;
-
-    #[cfg(target_os = "windows")]
-    let node = "node.exe";
-    #[cfg(not(target_os = "windows"))]
-    let node = "node";
-
-    Command::new(node)
-        .arg("--version")
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
-        .map(|s| s.trim().to_string())
-}
@@ -1,5 +1,6 @@
 // Config module - file watching and configuration management
 
+pub mod patch;
 pub mod watcher;
 
 pub use watcher::ConfigWatcher;
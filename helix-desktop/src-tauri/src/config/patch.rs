@@ -0,0 +1,275 @@
+// Config patching - RFC 6902 JSON Patch over RFC 6901 JSON Pointer paths,
+// plus a leaf diff used for config:changed payloads
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A single JSON Patch operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    /// Fails the whole patch unless the value at `path` equals `value`
+    Test { path: String, value: Value },
+}
+
+impl PatchOp {
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. }
+            | PatchOp::Move { path, .. }
+            | PatchOp::Copy { path, .. }
+            | PatchOp::Test { path, .. } => path,
+        }
+    }
+}
+
+/// Why an operation could not be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchErrorKind {
+    /// Malformed pointer or an operation that can never apply
+    Invalid,
+    /// The pointer does not resolve in the current document
+    NotFound,
+    /// A `test` operation did not match
+    TestFailed,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatchError {
+    /// Index of the failing operation
+    pub index: usize,
+    pub path: String,
+    pub kind: PatchErrorKind,
+    pub message: String,
+}
+
+/// One changed leaf, addressed by JSON Pointer. `None` means absent.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Apply all operations, or none: `doc` is only replaced when every
+/// operation succeeds
+pub fn apply(doc: &mut Value, ops: &[PatchOp]) -> Result<(), PatchError> {
+    let mut working = doc.clone();
+
+    for (index, op) in ops.iter().enumerate() {
+        apply_one(&mut working, op).map_err(|(kind, message)| PatchError {
+            index,
+            path: op.path().to_string(),
+            kind,
+            message,
+        })?;
+    }
+
+    *doc = working;
+    Ok(())
+}
+
+type OpResult<T> = Result<T, (PatchErrorKind, String)>;
+
+fn apply_one(doc: &mut Value, op: &PatchOp) -> OpResult<()> {
+    match op {
+        PatchOp::Add { path, value } => add(doc, &parse_pointer(path)?, value.clone()),
+        PatchOp::Remove { path } => remove(doc, &parse_pointer(path)?).map(|_| ()),
+        PatchOp::Replace { path, value } => {
+            let target = get_mut(doc, &parse_pointer(path)?)
+                .ok_or_else(|| not_found(path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            let from_tokens = parse_pointer(from)?;
+            let to_tokens = parse_pointer(path)?;
+            if to_tokens.len() > from_tokens.len() && to_tokens.starts_with(&from_tokens) {
+                return Err((
+                    PatchErrorKind::Invalid,
+                    format!("Cannot move {} into its own child {}", from, path),
+                ));
+            }
+            let value = remove(doc, &from_tokens)?;
+            add(doc, &to_tokens, value)
+        }
+        PatchOp::Copy { from, path } => {
+            let value = get(doc, &parse_pointer(from)?)
+                .cloned()
+                .ok_or_else(|| not_found(from))?;
+            add(doc, &parse_pointer(path)?, value)
+        }
+        PatchOp::Test { path, value } => {
+            let current = get(doc, &parse_pointer(path)?).ok_or_else(|| not_found(path))?;
+            if current == value {
+                Ok(())
+            } else {
+                Err((
+                    PatchErrorKind::TestFailed,
+                    format!("Value at {} does not match the expected value", path),
+                ))
+            }
+        }
+    }
+}
+
+fn not_found(path: &str) -> (PatchErrorKind, String) {
+    (PatchErrorKind::NotFound, format!("No value at {}", path))
+}
+
+/// Split a JSON Pointer into unescaped reference tokens ("" is the root)
+pub fn parse_pointer(pointer: &str) -> OpResult<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/').ok_or_else(|| {
+        (PatchErrorKind::Invalid, format!("Pointer must start with '/': {}", pointer))
+    })?;
+
+    rest.split('/')
+        .map(|token| {
+            if token.contains('~') && !valid_escapes(token) {
+                return Err((
+                    PatchErrorKind::Invalid,
+                    format!("Invalid escape in pointer: {}", pointer),
+                ));
+            }
+            Ok(token.replace("~1", "/").replace("~0", "~"))
+        })
+        .collect()
+}
+
+fn valid_escapes(token: &str) -> bool {
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c == '~' && !matches!(chars.next(), Some('0') | Some('1')) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Escape a key for use as a pointer token
+pub fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Array index for an existing element; "-" and leading zeros are rejected
+fn array_index(token: &str, len: usize) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse::<usize>().ok().filter(|i| *i < len)
+}
+
+fn get<'a>(doc: &'a Value, tokens: &[String]) -> Option<&'a Value> {
+    tokens.iter().try_fold(doc, |node, token| match node {
+        Value::Object(map) => map.get(token),
+        Value::Array(items) => array_index(token, items.len()).map(|i| &items[i]),
+        _ => None,
+    })
+}
+
+fn get_mut<'a>(doc: &'a mut Value, tokens: &[String]) -> Option<&'a mut Value> {
+    tokens.iter().try_fold(doc, |node, token| match node {
+        Value::Object(map) => map.get_mut(token),
+        Value::Array(items) => {
+            let len = items.len();
+            array_index(token, len).map(move |i| &mut items[i])
+        }
+        _ => None,
+    })
+}
+
+fn add(doc: &mut Value, tokens: &[String], value: Value) -> OpResult<()> {
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+
+    let parent = get_mut(doc, parent_tokens).ok_or_else(|| {
+        (PatchErrorKind::NotFound, format!("Parent of {} does not exist", pointer_of(tokens)))
+    })?;
+
+    match parent {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            // Inserting may target one past the end, or "-" to append
+            let index = if last == "-" {
+                items.len()
+            } else {
+                array_index(last, items.len() + 1).ok_or_else(|| {
+                    (PatchErrorKind::Invalid, format!("Invalid array index at {}", pointer_of(tokens)))
+                })?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err((
+            PatchErrorKind::Invalid,
+            format!("Cannot add a child to a scalar at {}", pointer_of(parent_tokens)),
+        )),
+    }
+}
+
+fn remove(doc: &mut Value, tokens: &[String]) -> OpResult<Value> {
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        return Err((PatchErrorKind::Invalid, "Cannot remove the whole config".to_string()));
+    };
+
+    let missing = || not_found(&pointer_of(tokens));
+    match get_mut(doc, parent_tokens).ok_or_else(missing)? {
+        Value::Object(map) => map.remove(last).ok_or_else(missing),
+        Value::Array(items) => {
+            let index = array_index(last, items.len()).ok_or_else(missing)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(missing()),
+    }
+}
+
+fn pointer_of(tokens: &[String]) -> String {
+    tokens.iter().map(|t| format!("/{}", escape_token(t))).collect()
+}
+
+/// Leaf-level differences between two documents. Arrays are compared
+/// whole, so reordering a list shows up as one change.
+pub fn diff(old: &Value, new: &Value) -> Vec<ConfigChange> {
+    let mut out = Vec::new();
+    diff_into(Some(old), Some(new), &mut String::new(), &mut out);
+    out
+}
+
+fn diff_into(old: Option<&Value>, new: Option<&Value>, prefix: &mut String, out: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let len = prefix.len();
+                prefix.push('/');
+                prefix.push_str(&escape_token(key));
+                diff_into(a.get(key), b.get(key), prefix, out);
+                prefix.truncate(len);
+            }
+        }
+        _ if old != new => out.push(ConfigChange {
+            path: prefix.clone(),
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
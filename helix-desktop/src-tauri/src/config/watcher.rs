@@ -1,16 +1,24 @@
 // Config file watcher - monitors ~/.helix/config.json for changes
 
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
+use crate::gateway::GatewayConfigSync;
+use crate::commands::CommandError;
+use super::patch::{self, ConfigChange};
+
 /// Debounce duration for rapid file changes
 const DEBOUNCE_MS: u64 = 100;
 
+/// Config contents as of the last config:changed event, for diffing
+static SNAPSHOT: Mutex<Option<Value>> = Mutex::new(None);
+
 /// Config file watcher that emits events to the frontend
 pub struct ConfigWatcher {
     watcher: Option<RecommendedWatcher>,
@@ -28,9 +36,9 @@ impl ConfigWatcher {
         }
     }
 
-    /// Get the config file path
+    /// Get the config file path of the active account
     pub fn config_path() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| home.join(".helix").join("config.json"))
+        crate::accounts::data_dir().ok().map(|dir| dir.join("config.json"))
     }
 
     /// Start watching the config file
@@ -86,6 +94,8 @@ impl ConfigWatcher {
             *watching = true;
         }
 
+        record_snapshot(read_config_value(&config_path));
+
         // Spawn debounce thread
         let watching_flag = Arc::clone(&self.watching);
         let config_path_clone = config_path.clone();
@@ -142,6 +152,7 @@ impl ConfigWatcher {
     ) {
         let mut last_event: Option<Instant> = None;
         let debounce_duration = Duration::from_millis(DEBOUNCE_MS);
+        let mut gateway_sync = GatewayConfigSync::load(&config_path);
 
         loop {
             // Check for stop signal
@@ -176,15 +187,15 @@ impl ConfigWatcher {
                         if should_emit {
                             last_event = Some(now);
 
-                            // Emit event to frontend
-                            if let Err(e) = app_handle.emit("config:changed", ConfigChangedPayload {
-                                path: config_path.to_string_lossy().to_string(),
-                                timestamp: chrono_timestamp(),
-                            }) {
-                                log::error!("Failed to emit config:changed event: {}", e);
-                            } else {
-                                log::debug!("Emitted config:changed event");
+                            // Writes already announced by patch_config, and
+                            // touches that don't change anything, diff empty
+                            let changes = take_changes(read_config_value(&config_path));
+                            if !changes.as_ref().is_some_and(Vec::is_empty) {
+                                emit_config_changed(&app_handle, &config_path, "file", changes);
                             }
+
+                            // Push agent/model changes to the gateway
+                            gateway_sync.on_config_changed(&app_handle, &config_path);
                         }
                     }
                 }
@@ -216,6 +227,55 @@ impl Drop for ConfigWatcher {
 struct ConfigChangedPayload {
     path: String,
     timestamp: u64,
+    /// "file" for edits seen on disk, "patch" for patch_config
+    source: &'static str,
+    /// Changed leaves; absent when the file couldn't be read or parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changes: Option<Vec<ConfigChange>>,
+}
+
+/// Emit config:changed to the frontend
+pub fn emit_config_changed(
+    app_handle: &AppHandle,
+    config_path: &Path,
+    source: &'static str,
+    changes: Option<Vec<ConfigChange>>,
+) {
+    let payload = ConfigChangedPayload {
+        path: config_path.to_string_lossy().to_string(),
+        timestamp: chrono_timestamp(),
+        source,
+        changes,
+    };
+
+    if let Err(e) = app_handle.emit("config:changed", payload) {
+        log::error!("Failed to emit config:changed event: {}", e);
+    } else {
+        log::debug!("Emitted config:changed event");
+    }
+}
+
+/// Remember the config contents the frontend was last told about
+pub fn record_snapshot(value: Option<Value>) {
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        *snapshot = value;
+    }
+}
+
+/// Diff the config against the last snapshot and store it as the new one
+fn take_changes(current: Option<Value>) -> Option<Vec<ConfigChange>> {
+    let mut snapshot = SNAPSHOT.lock().ok()?;
+    let changes = match (snapshot.as_ref(), current.as_ref()) {
+        (Some(old), Some(new)) => Some(patch::diff(old, new)),
+        _ => None,
+    };
+    *snapshot = current;
+    changes
+}
+
+fn read_config_value(config_path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(config_path).ok()?;
+    serde_json::from_str(&content).ok()
 }
 
 /// Get current timestamp in milliseconds
@@ -233,25 +293,25 @@ fn chrono_timestamp() -> u64 {
 pub async fn start_config_watcher(
     app_handle: AppHandle,
     state: tauri::State<'_, crate::AppState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut watcher = state.config_watcher.write().await;
-    watcher.start(app_handle)
+    watcher.start(app_handle).map_err(Into::into)
 }
 
 /// Stop watching the config file
 #[tauri::command]
 pub async fn stop_config_watcher(
     state: tauri::State<'_, crate::AppState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut watcher = state.config_watcher.write().await;
-    watcher.stop()
+    watcher.stop().map_err(Into::into)
 }
 
 /// Check if config watcher is active
 #[tauri::command]
 pub async fn is_config_watcher_active(
     state: tauri::State<'_, crate::AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let watcher = state.config_watcher.read().await;
     Ok(watcher.is_watching())
 }
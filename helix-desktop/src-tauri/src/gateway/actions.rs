@@ -0,0 +1,269 @@
+// Gateway actions - actions gateway agents and skills make invokable
+//
+// An agent or skill declares what it can be asked to do: a name, a title
+// for people, the gateway method that carries it out and a schema for the
+// method's params (see params_schema.rs). Declarations arrive through the
+// local API (`PUT /api/actions/:source/:owner`, where the gateway declares
+// its skills' actions; see HELIX_API_URL in commands/gateway.rs) or the
+// `declare_gateway_actions` command and replace whatever the same owner
+// declared before; they are kept in `gateway-actions.json` in the account's
+// data directory, so they are searchable while the gateway is down. The
+// command palette lists them next to its own actions, and talk mode
+// transcripts resolve to them (palette/intent.rs). Params are
+// checked against the declared schema before the method is called over
+// the shared gateway connection (client.rs), so a malformed call from a
+// voice transcript never reaches the agent.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::{client, params_schema};
+use crate::commands::CommandError;
+
+const ACTIONS_FILE: &str = "gateway-actions.json";
+/// Actions one owner may declare
+const MAX_ACTIONS_PER_OWNER: usize = 50;
+
+/// Serializes reads and writes of the actions file
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// What declared an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionSource {
+    Agent,
+    Skill,
+}
+
+impl ActionSource {
+    fn parse(source: &str) -> Result<Self, CommandError> {
+        match source {
+            "agent" => Ok(ActionSource::Agent),
+            "skill" => Ok(ActionSource::Skill),
+            other => Err(CommandError::invalid_input(format!(
+                "Unknown action source: {} (expected agent or skill)",
+                other
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionSource::Agent => "agent",
+            ActionSource::Skill => "skill",
+        }
+    }
+}
+
+/// An action as an agent or skill declares it
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionDeclaration {
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Gateway method that carries the action out
+    pub method: String,
+    /// JSON Schema of the method's params; any object when omitted
+    #[serde(default = "default_params")]
+    pub params: Value,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+fn default_params() -> Value {
+    json!({ "type": "object" })
+}
+
+/// A declared action in the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayAction {
+    /// "<source>:<owner>/<name>"
+    pub id: String,
+    pub source: ActionSource,
+    /// Agent or skill id
+    pub owner: String,
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub method: String,
+    pub params: Value,
+    pub keywords: Vec<String>,
+    pub declared_at: u64,
+}
+
+impl GatewayAction {
+    /// Whether it can run without asking for params
+    pub fn needs_params(&self) -> bool {
+        !params_schema::validate(&self.params, &json!({})).is_empty()
+    }
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// Agent, skill and action names: letters, digits, '-', '_' and '.'
+fn check_name(kind: &str, name: &str) -> Result<(), CommandError> {
+    let valid = !name.is_empty()
+        && name.len() <= 100
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CommandError::invalid_input(format!("Invalid {} name: {:?}", kind, name)))
+    }
+}
+
+fn actions_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(ACTIONS_FILE))
+}
+
+fn read_actions() -> Result<Vec<GatewayAction>, String> {
+    let path = actions_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read gateway actions: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse gateway actions: {}", e))
+}
+
+fn write_actions(actions: &[GatewayAction]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(actions).map_err(|e| e.to_string())?;
+    fs::write(actions_path()?, json).map_err(|e| format!("Failed to write gateway actions: {}", e))
+}
+
+/// Every declared action, ordered by id
+pub fn list() -> Result<Vec<GatewayAction>, String> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    read_actions()
+}
+
+/// The declared action `id`
+pub fn get(id: &str) -> Result<GatewayAction, CommandError> {
+    list()?
+        .into_iter()
+        .find(|action| action.id == id)
+        .ok_or_else(|| CommandError::not_found(format!("Unknown gateway action: {}", id)))
+}
+
+fn build(
+    source: ActionSource,
+    owner: &str,
+    declaration: ActionDeclaration,
+    declared_at: u64,
+) -> Result<GatewayAction, CommandError> {
+    check_name("action", &declaration.name)?;
+    let method = declaration.method.trim();
+    if method.is_empty() || method == "connect" {
+        return Err(CommandError::invalid_input(format!(
+            "Action {} has no callable gateway method",
+            declaration.name
+        )));
+    }
+    params_schema::check(&declaration.params)
+        .map_err(|e| CommandError::invalid_input(format!("Action {}: {}", declaration.name, e)))?;
+    // Params are sent as the method's params object
+    if declaration.params.get("type").is_some_and(|t| t != "object") {
+        return Err(CommandError::invalid_input(format!(
+            "Action {}: params must be an object schema",
+            declaration.name
+        )));
+    }
+
+    Ok(GatewayAction {
+        id: format!("{}:{}/{}", source.as_str(), owner, declaration.name),
+        source,
+        owner: owner.to_string(),
+        title: declaration
+            .title
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| declaration.name.replace(['_', '-', '.'], " ")),
+        name: declaration.name,
+        description: declaration.description,
+        method: method.to_string(),
+        params: declaration.params,
+        keywords: declaration.keywords,
+        declared_at,
+    })
+}
+
+/// Replace the actions `owner` declared; an empty list removes them
+pub fn declare(
+    source: &str,
+    owner: &str,
+    declarations: Vec<ActionDeclaration>,
+) -> Result<Vec<GatewayAction>, CommandError> {
+    let source = ActionSource::parse(source)?;
+    check_name(source.as_str(), owner)?;
+    if declarations.len() > MAX_ACTIONS_PER_OWNER {
+        return Err(CommandError::invalid_input(format!(
+            "{} {} declares {} actions; at most {} are allowed",
+            source.as_str(),
+            owner,
+            declarations.len(),
+            MAX_ACTIONS_PER_OWNER
+        )));
+    }
+
+    let now = now_ms();
+    let mut declared = Vec::with_capacity(declarations.len());
+    for declaration in declarations {
+        let action = build(source, owner, declaration, now)?;
+        if declared.iter().any(|d: &GatewayAction| d.id == action.id) {
+            return Err(CommandError::invalid_input(format!("Action {} is declared twice", action.name)));
+        }
+        declared.push(action);
+    }
+
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut actions = read_actions()?;
+    actions.retain(|action| !(action.source == source && action.owner == owner));
+    actions.extend(declared.iter().cloned());
+    actions.sort_by(|a, b| a.id.cmp(&b.id));
+    write_actions(&actions)?;
+
+    log::info!("{} {} declared {} gateway actions", source.as_str(), owner, declared.len());
+    Ok(declared)
+}
+
+/// Check `params` against the action's schema and call its gateway method
+pub async fn invoke(id: &str, params: Option<Value>) -> Result<Value, CommandError> {
+    let action = get(id)?;
+    let params = params.unwrap_or_else(|| json!({}));
+    let errors = params_schema::validate(&action.params, &params);
+    if !errors.is_empty() {
+        return Err(CommandError::invalid_input(format!("Invalid params for {}: {}", action.title, errors.join("; ")))
+            .with_details(json!({ "id": action.id, "errors": errors, "schema": action.params })));
+    }
+
+    client::call(&action.method, params)
+        .await
+        .map_err(CommandError::network)
+}
+
+// Tauri commands
+
+/// Actions declared by gateway agents and skills
+#[tauri::command]
+pub fn list_gateway_actions() -> Result<Vec<GatewayAction>, CommandError> {
+    Ok(list()?)
+}
+
+/// Replace the actions an agent or skill declared
+#[tauri::command]
+pub fn declare_gateway_actions(
+    source: String,
+    owner: String,
+    actions: Vec<ActionDeclaration>,
+) -> Result<Vec<GatewayAction>, CommandError> {
+    declare(&source, &owner, actions)
+}
+
+/// Run a declared action with `params`, after validating them
+#[tauri::command]
+pub async fn invoke_gateway_action(id: String, params: Option<Value>) -> Result<Value, CommandError> {
+    invoke(&id, params).await
+}
@@ -0,0 +1,446 @@
+// Agent metrics - per-agent statistics forwarded from the gateway
+//
+// While the gateway runs, a read-only RPC connection (see rpc.rs) is held
+// open. Agent lifecycle events give live run counts, completed runs and
+// errors; `agents.list` and `sessions.list` are polled on an interval for
+// names, token totals and model usage. Counters cover the current
+// connection and reset when the gateway restarts.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use super::rpc::{Frame, GatewayRpc};
+use crate::commands::{gateway, CommandError};
+
+/// How often names and session usage are pulled
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Wait between connection attempts, and between gateway-running checks
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+/// Agent id used by the gateway for sessions without an agent prefix
+const DEFAULT_AGENT_ID: &str = "main";
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static SNAPSHOT: LazyLock<Mutex<AgentMetricsSnapshot>> =
+    LazyLock::new(|| Mutex::new(AgentMetricsSnapshot::default()));
+
+/// What an agent is doing right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentState {
+    Idle,
+    /// At least one run in progress
+    Busy,
+    /// Its most recent run ended in an error
+    Failing,
+}
+
+/// Tokens used with one model, across an agent's sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUsage {
+    pub provider: Option<String>,
+    pub model: String,
+    pub sessions: usize,
+    pub total_tokens: u64,
+}
+
+/// Statistics for one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentMetrics {
+    pub agent_id: String,
+    pub name: Option<String>,
+    pub state: AgentState,
+    pub active_runs: usize,
+    /// Runs completed since the connection was made
+    pub messages_handled: u64,
+    /// Runs that ended in an error since the connection was made
+    pub errors: u64,
+    pub last_error: Option<String>,
+    /// Last lifecycle event or session update, in milliseconds
+    pub last_active: Option<u64>,
+    pub sessions: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub models: Vec<ModelUsage>,
+}
+
+/// Everything known about the gateway's agents
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentMetricsSnapshot {
+    /// Whether the metrics connection to the gateway is up
+    pub connected: bool,
+    pub updated_at: Option<u64>,
+    pub agents: Vec<AgentMetrics>,
+    /// Why the last connection attempt failed or ended
+    pub error: Option<String>,
+    /// Hit rate and size of the response cache when the snapshot was taken
+    pub response_cache: crate::cache::responses::CacheStats,
+}
+
+/// Payload for gateway:agent-state event
+#[derive(Serialize, Clone)]
+struct AgentStatePayload {
+    agent_id: String,
+    state: AgentState,
+    previous: Option<AgentState>,
+}
+
+/// Live counters from lifecycle events
+#[derive(Debug, Default)]
+struct Activity {
+    active_runs: HashSet<String>,
+    messages_handled: u64,
+    errors: u64,
+    last_error: Option<String>,
+    last_run_failed: bool,
+    last_active: Option<u64>,
+}
+
+/// Totals from sessions.list
+#[derive(Debug, Default)]
+struct SessionUsage {
+    sessions: usize,
+    input_tokens: u64,
+    output_tokens: u64,
+    total_tokens: u64,
+    last_updated: Option<u64>,
+    models: BTreeMap<(Option<String>, String), (usize, u64)>,
+}
+
+/// Per-connection state; dropped when the connection ends
+#[derive(Debug, Default)]
+struct Tracker {
+    default_agent: Option<String>,
+    names: HashMap<String, Option<String>>,
+    usage: HashMap<String, SessionUsage>,
+    activity: HashMap<String, Activity>,
+}
+
+impl Tracker {
+    /// "agent:<id>:<rest>" -> id; other keys belong to the default agent
+    fn agent_for_session(&self, session_key: &str) -> String {
+        let mut parts = session_key.splitn(3, ':');
+        match (parts.next(), parts.next()) {
+            (Some("agent"), Some(id)) if !id.is_empty() => id.to_lowercase(),
+            _ => self
+                .default_agent
+                .clone()
+                .unwrap_or_else(|| DEFAULT_AGENT_ID.to_string()),
+        }
+    }
+
+    /// Apply an `agent` event; returns whether anything changed
+    fn on_agent_event(&mut self, payload: &Value) -> bool {
+        if payload.get("stream").and_then(Value::as_str) != Some("lifecycle") {
+            return false;
+        }
+        let (Some(run_id), Some(session_key)) = (
+            payload.get("runId").and_then(Value::as_str),
+            payload.get("sessionKey").and_then(Value::as_str),
+        ) else {
+            return false;
+        };
+
+        let agent_id = self.agent_for_session(session_key);
+        let activity = self.activity.entry(agent_id).or_default();
+        activity.last_active = payload.get("ts").and_then(Value::as_u64).or(Some(now_ms()));
+
+        match payload.pointer("/data/phase").and_then(Value::as_str) {
+            Some("start") => {
+                activity.active_runs.insert(run_id.to_string());
+            }
+            Some("end") => {
+                activity.active_runs.remove(run_id);
+                activity.messages_handled += 1;
+                activity.last_run_failed = false;
+            }
+            Some("error") => {
+                activity.active_runs.remove(run_id);
+                activity.errors += 1;
+                activity.last_run_failed = true;
+                activity.last_error = payload
+                    .pointer("/data/error")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn on_agents_list(&mut self, payload: &Value) {
+        self.default_agent = payload
+            .get("defaultId")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        self.names = payload
+            .get("agents")
+            .and_then(Value::as_array)
+            .map(|agents| {
+                agents
+                    .iter()
+                    .filter_map(|agent| {
+                        let id = agent.get("id")?.as_str()?.to_string();
+                        let name = agent
+                            .get("name")
+                            .or_else(|| agent.pointer("/identity/name"))
+                            .and_then(Value::as_str)
+                            .map(str::to_string);
+                        Some((id, name))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    fn on_sessions_list(&mut self, payload: &Value) {
+        let mut usage: HashMap<String, SessionUsage> = HashMap::new();
+        let rows = payload.get("sessions").and_then(Value::as_array);
+
+        for row in rows.into_iter().flatten() {
+            let Some(key) = row.get("key").and_then(Value::as_str) else {
+                continue;
+            };
+            let count = |field: &str| row.get(field).and_then(Value::as_u64).unwrap_or(0);
+            let input = count("inputTokens");
+            let output = count("outputTokens");
+            let total = row
+                .get("totalTokens")
+                .and_then(Value::as_u64)
+                .unwrap_or(input + output);
+
+            let entry = usage.entry(self.agent_for_session(key)).or_default();
+            entry.sessions += 1;
+            entry.input_tokens += input;
+            entry.output_tokens += output;
+            entry.total_tokens += total;
+            if let Some(updated) = row.get("updatedAt").and_then(Value::as_u64) {
+                entry.last_updated = entry.last_updated.max(Some(updated));
+            }
+            if let Some(model) = row.get("model").and_then(Value::as_str) {
+                let provider = row
+                    .get("modelProvider")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                let model_entry = entry.models.entry((provider, model.to_string())).or_default();
+                model_entry.0 += 1;
+                model_entry.1 += total;
+            }
+        }
+
+        self.usage = usage;
+    }
+
+    fn agents(&self) -> Vec<AgentMetrics> {
+        let ids: BTreeMap<&String, ()> = self
+            .names
+            .keys()
+            .chain(self.usage.keys())
+            .chain(self.activity.keys())
+            .map(|id| (id, ()))
+            .collect();
+
+        ids.into_keys()
+            .map(|id| {
+                let usage = self.usage.get(id);
+                let activity = self.activity.get(id);
+
+                let active_runs = activity.map(|a| a.active_runs.len()).unwrap_or(0);
+                let state = if active_runs > 0 {
+                    AgentState::Busy
+                } else if activity.is_some_and(|a| a.last_run_failed) {
+                    AgentState::Failing
+                } else {
+                    AgentState::Idle
+                };
+
+                AgentMetrics {
+                    agent_id: id.clone(),
+                    name: self.names.get(id).cloned().flatten(),
+                    state,
+                    active_runs,
+                    messages_handled: activity.map(|a| a.messages_handled).unwrap_or(0),
+                    errors: activity.map(|a| a.errors).unwrap_or(0),
+                    last_error: activity.and_then(|a| a.last_error.clone()),
+                    last_active: activity
+                        .and_then(|a| a.last_active)
+                        .max(usage.and_then(|u| u.last_updated)),
+                    sessions: usage.map(|u| u.sessions).unwrap_or(0),
+                    input_tokens: usage.map(|u| u.input_tokens).unwrap_or(0),
+                    output_tokens: usage.map(|u| u.output_tokens).unwrap_or(0),
+                    total_tokens: usage.map(|u| u.total_tokens).unwrap_or(0),
+                    models: usage
+                        .map(|u| {
+                            u.models
+                                .iter()
+                                .map(|((provider, model), (sessions, tokens))| ModelUsage {
+                                    provider: provider.clone(),
+                                    model: model.clone(),
+                                    sessions: *sessions,
+                                    total_tokens: *tokens,
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Which poll a pending request id belongs to
+enum Pending {
+    Agents,
+    Sessions,
+}
+
+/// Start forwarding agent metrics (once per process)
+pub fn start(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let running = gateway::gateway_status(None).map(|s| s.running).unwrap_or(false);
+            if running {
+                let error = match forward_metrics(&app).await {
+                    Ok(()) => None,
+                    Err(e) => {
+                        log::debug!("Agent metrics connection ended: {}", e);
+                        Some(e)
+                    }
+                };
+                publish(&app, |snapshot| {
+                    snapshot.connected = false;
+                    snapshot.error = error;
+                });
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+/// Hold one connection open, publishing metrics until it closes
+async fn forward_metrics(app: &AppHandle) -> Result<(), String> {
+    let url = gateway::get_gateway_url(None).map_err(|e| e.message)?;
+    let token = gateway::get_or_create_gateway_token().ok();
+    let mut rpc = GatewayRpc::connect(&url, token).await?;
+
+    let mut tracker = Tracker::default();
+    let mut pending: HashMap<String, Pending> = HashMap::new();
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    publish(app, |snapshot| {
+        snapshot.connected = true;
+        snapshot.error = None;
+        snapshot.agents.clear();
+    });
+
+    loop {
+        let frame = tokio::select! {
+            _ = poll.tick() => None,
+            frame = rpc.next_frame() => match frame? {
+                Some(frame) => Some(frame),
+                None => return Ok(()),
+            },
+        };
+
+        let changed = match frame {
+            None => {
+                pending.insert(rpc.send("agents.list", json!({})).await?, Pending::Agents);
+                pending.insert(rpc.send("sessions.list", json!({})).await?, Pending::Sessions);
+                false
+            }
+            Some(Frame::Event { event, payload }) if event == "agent" => {
+                tracker.on_agent_event(&payload)
+            }
+            Some(Frame::Response { id, ok, payload, error }) => match pending.remove(&id) {
+                Some(kind) if !ok => {
+                    let method = match kind {
+                        Pending::Agents => "agents.list",
+                        Pending::Sessions => "sessions.list",
+                    };
+                    log::debug!("Gateway {} failed: {}", method, error.unwrap_or_default());
+                    false
+                }
+                Some(Pending::Agents) => {
+                    tracker.on_agents_list(&payload);
+                    false
+                }
+                // Agents are listed first, so publish once sessions are in
+                Some(Pending::Sessions) => {
+                    tracker.on_sessions_list(&payload);
+                    true
+                }
+                None => false,
+            },
+            Some(Frame::Event { .. }) => false,
+        };
+
+        if changed {
+            let agents = tracker.agents();
+            publish(app, |snapshot| snapshot.agents = agents);
+        }
+    }
+}
+
+/// Update the cached snapshot, then emit it and any agent state changes
+fn publish(app: &AppHandle, update: impl FnOnce(&mut AgentMetricsSnapshot)) {
+    let (snapshot, transitions) = {
+        let Ok(mut current) = SNAPSHOT.lock() else {
+            return;
+        };
+        let previous: HashMap<String, AgentState> = current
+            .agents
+            .iter()
+            .map(|a| (a.agent_id.clone(), a.state))
+            .collect();
+
+        update(&mut current);
+        current.updated_at = Some(now_ms());
+        current.response_cache = crate::cache::responses::stats();
+
+        let transitions: Vec<AgentStatePayload> = current
+            .agents
+            .iter()
+            .filter(|a| previous.get(&a.agent_id) != Some(&a.state))
+            .map(|a| AgentStatePayload {
+                agent_id: a.agent_id.clone(),
+                state: a.state,
+                previous: previous.get(&a.agent_id).copied(),
+            })
+            .collect();
+
+        (current.clone(), transitions)
+    };
+
+    let _ = app.emit("gateway:agent-metrics", &snapshot);
+    for transition in transitions {
+        let _ = app.emit("gateway:agent-state", transition);
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Latest per-agent metrics forwarded from the gateway
+#[tauri::command]
+pub fn get_agent_metrics() -> Result<AgentMetricsSnapshot, CommandError> {
+    let mut snapshot = SNAPSHOT
+        .lock()
+        .map(|snapshot| snapshot.clone())
+        .map_err(|e| CommandError::internal(e.to_string()))?;
+    snapshot.response_cache = crate::cache::responses::stats();
+    Ok(snapshot)
+}
@@ -0,0 +1,156 @@
+// Gateway client - one shared backend connection to the default gateway
+//
+// Backend features that call gateway methods (session handoff, the
+// context builder, and jobs or tray actions to come) go through here
+// instead of opening a connection each, so they work with no window open;
+// the frontend can use it too through gateway_rpc. The connection is opened on first use with the stored
+// token and operator read and write scopes, then kept: a task owns the
+// socket and matches responses to waiting requests by id, so callers don't
+// queue behind each other. When the gateway goes away, waiting requests
+// fail and the next one reconnects, also following a changed gateway URL.
+// Gateway events on this connection are dropped; the frontend client and
+// agent_metrics subscribe to the ones they need.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use super::rpc::{Frame, GatewayRpc};
+use crate::commands::{gateway as gateway_commands, CommandError};
+
+const SCOPES: &[&str] = &["operator.read", "operator.write"];
+/// How long a request may wait for its response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The open connection, if any
+static CONNECTION: LazyLock<Mutex<Option<Connection>>> = LazyLock::new(|| Mutex::new(None));
+
+type Reply = oneshot::Sender<Result<Value, String>>;
+
+/// A request on its way to the connection task
+struct Call {
+    method: String,
+    params: Value,
+    reply: Reply,
+}
+
+struct Connection {
+    url: String,
+    calls: mpsc::UnboundedSender<Call>,
+}
+
+/// Run the connection until the gateway closes it or every handle is gone
+async fn run(mut rpc: GatewayRpc, mut calls: mpsc::UnboundedReceiver<Call>, url: String) {
+    let mut waiting: HashMap<String, (String, Reply)> = HashMap::new();
+    let error = loop {
+        tokio::select! {
+            call = calls.recv() => {
+                let Some(call) = call else {
+                    break None;
+                };
+                match rpc.send(&call.method, call.params).await {
+                    Ok(id) => {
+                        waiting.insert(id, (call.method, call.reply));
+                    }
+                    Err(e) => {
+                        let _ = call.reply.send(Err(e.clone()));
+                        break Some(e);
+                    }
+                }
+            }
+            frame = rpc.next_frame() => match frame {
+                Ok(Some(Frame::Response { id, ok, payload, error })) => {
+                    if let Some((method, reply)) = waiting.remove(&id) {
+                        let _ = reply.send(if ok {
+                            Ok(payload)
+                        } else {
+                            Err(format!(
+                                "Gateway rejected {}: {}",
+                                method,
+                                error.unwrap_or_else(|| "unknown error".to_string())
+                            ))
+                        });
+                    }
+                }
+                Ok(Some(Frame::Event { .. })) => {}
+                Ok(None) => break Some("Gateway closed the connection".to_string()),
+                Err(e) => break Some(e),
+            },
+        }
+    };
+
+    if let Some(error) = &error {
+        log::info!("Gateway client connection to {} ended: {}", url, error);
+    }
+    let error = error.unwrap_or_else(|| "Gateway client shut down".to_string());
+    for (_, (_, reply)) in waiting {
+        let _ = reply.send(Err(error.clone()));
+    }
+}
+
+/// Handle to the open connection, connecting first when there is none or
+/// the gateway moved
+async fn connection() -> Result<mpsc::UnboundedSender<Call>, String> {
+    let url = gateway_commands::get_gateway_url(None).map_err(|e| e.message)?;
+    let mut current = CONNECTION.lock().await;
+    if let Some(connection) = current.as_ref() {
+        if connection.url == url && !connection.calls.is_closed() {
+            return Ok(connection.calls.clone());
+        }
+    }
+
+    let token = gateway_commands::get_or_create_gateway_token().ok();
+    let rpc = GatewayRpc::connect_with_scopes(&url, token, SCOPES).await?;
+    let (calls, receiver) = mpsc::unbounded_channel();
+    tauri::async_runtime::spawn(run(rpc, receiver, url.clone()));
+    log::debug!("Gateway client connected to {}", url);
+
+    *current = Some(Connection {
+        url,
+        calls: calls.clone(),
+    });
+    Ok(calls)
+}
+
+/// Send `method` over an open connection and wait for its payload
+async fn send(calls: mpsc::UnboundedSender<Call>, method: &str, params: Value) -> Result<Value, String> {
+    let (reply, response) = oneshot::channel();
+    calls
+        .send(Call {
+            method: method.to_string(),
+            params,
+            reply,
+        })
+        .map_err(|_| "Gateway connection closed".to_string())?;
+
+    match tokio::time::timeout(REQUEST_TIMEOUT, response).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Gateway connection closed".to_string()),
+        Err(_) => Err(format!("Gateway did not answer {} in time", method)),
+    }
+}
+
+/// Call `method` on the default gateway and return its payload
+pub async fn call(method: &str, params: Value) -> Result<Value, String> {
+    send(connection().await?, method, params).await
+}
+
+// Tauri commands
+
+/// Call a gateway method over the backend's connection, for windows
+/// without a gateway socket of their own
+#[tauri::command]
+pub async fn gateway_rpc(method: String, params: Option<Value>) -> Result<Value, CommandError> {
+    let method = method.trim();
+    if method.is_empty() || method == "connect" {
+        return Err(CommandError::invalid_input(format!("Not a callable gateway method: {:?}", method)));
+    }
+    let calls = connection()
+        .await
+        .map_err(|e| CommandError::unavailable(format!("Gateway is not reachable: {}", e)))?;
+    send(calls, method, params.unwrap_or_else(|| json!({})))
+        .await
+        .map_err(CommandError::network)
+}
@@ -0,0 +1,341 @@
+// Gateway config sync - translates ~/.helix/config.json agent/model changes
+// into gateway (OpenClaw) configuration updates
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::config::HelixConfig;
+
+/// Helix config sections that are mirrored into the gateway config
+const SYNCED_SECTIONS: [&str; 2] = ["agents", "models"];
+
+/// How the gateway applies a change, mirroring helix-runtime's
+/// `gateway/config-reload.ts` rules. First matching prefix wins.
+const RELOAD_RULES: &[(&str, ReloadAction)] = &[
+    ("agents.defaults.heartbeat", ReloadAction::Hot),
+    ("agent.heartbeat", ReloadAction::Hot),
+    ("agents", ReloadAction::None),
+    ("agent", ReloadAction::None),
+    ("models", ReloadAction::None),
+    ("gateway", ReloadAction::Restart),
+    ("plugins", ReloadAction::Restart),
+    ("discovery", ReloadAction::Restart),
+    ("canvasHost", ReloadAction::Restart),
+];
+
+/// Set when a synced change only takes effect after a gateway restart
+static RESTART_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// How a config change reaches the running gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReloadAction {
+    /// Read on demand by the gateway, nothing to do
+    None,
+    /// Applied in place by the gateway's config watcher
+    Hot,
+    /// Requires restarting the gateway process
+    Restart,
+}
+
+/// Payload for gateway:config-reload event
+#[derive(Serialize, Clone)]
+struct ConfigReloadPayload {
+    changed_paths: Vec<String>,
+    action: ReloadAction,
+    restart_pending: bool,
+    error: Option<String>,
+    timestamp: u64,
+}
+
+/// Payload for gateway:restart-pending event
+#[derive(Serialize, Clone)]
+struct RestartPendingPayload {
+    pending: bool,
+}
+
+/// Tracks the last applied agent/model settings so only the diff is pushed
+pub struct GatewayConfigSync {
+    snapshot: Map<String, Value>,
+}
+
+impl GatewayConfigSync {
+    /// Snapshot the current helix config as the baseline
+    pub fn load(config_path: &Path) -> Self {
+        let snapshot = read_synced_sections(config_path).unwrap_or_default();
+        Self { snapshot }
+    }
+
+    /// Handle a change to the helix config file
+    pub fn on_config_changed(&mut self, app_handle: &AppHandle, config_path: &Path) {
+        let current = match read_synced_sections(config_path) {
+            Ok(sections) => sections,
+            Err(e) => {
+                // Usually a partially written file; the next change event picks it up
+                log::debug!("Skipping gateway config sync: {}", e);
+                return;
+            }
+        };
+
+        let mut changed = Vec::new();
+        diff_paths(
+            &Value::Object(self.snapshot.clone()),
+            &Value::Object(current.clone()),
+            &mut Vec::new(),
+            &mut changed,
+        );
+
+        if changed.is_empty() {
+            return;
+        }
+
+        let changed_paths: Vec<String> = changed.iter().map(|p| p.join(".")).collect();
+        log::info!("Helix config changed gateway settings: {:?}", changed_paths);
+
+        let result = apply_to_gateway_config(&current, &changed);
+        let (action, error) = match result {
+            Ok(action) => {
+                self.snapshot = current;
+                (action, None)
+            }
+            Err(e) => {
+                log::warn!("Failed to sync config to gateway: {}", e);
+                (ReloadAction::None, Some(e))
+            }
+        };
+
+        if action == ReloadAction::Restart {
+            set_restart_pending(app_handle, true);
+        }
+
+        let summary = match &error {
+            Some(e) => format!("Failed to sync config to gateway: {}", e),
+            None if action == ReloadAction::Restart => {
+                format!("Synced {} gateway settings (restart needed)", changed_paths.len())
+            }
+            None => format!("Synced {} gateway settings", changed_paths.len()),
+        };
+        events::record(
+            ServiceKind::Sync,
+            "config_synced",
+            summary,
+            Some(serde_json::json!({ "changed_paths": changed_paths, "action": action })),
+        );
+
+        let _ = app_handle.emit("gateway:config-reload", ConfigReloadPayload {
+            changed_paths,
+            action,
+            restart_pending: is_restart_pending(),
+            error,
+            timestamp: chrono_timestamp(),
+        });
+    }
+}
+
+/// Whether a synced change is waiting on a gateway restart
+pub fn is_restart_pending() -> bool {
+    RESTART_PENDING.load(Ordering::SeqCst)
+}
+
+/// Update the pending restart flag and the tray indicator
+pub fn set_restart_pending(app_handle: &AppHandle, pending: bool) {
+    if RESTART_PENDING.swap(pending, Ordering::SeqCst) == pending {
+        return;
+    }
+
+    #[cfg(desktop)]
+    crate::tray::set_restart_pending_indicator(app_handle, pending);
+
+    let _ = app_handle.emit("gateway:restart-pending", RestartPendingPayload { pending });
+}
+
+/// Classify a changed path using the gateway's reload rules
+pub fn classify_path(path: &str) -> ReloadAction {
+    RELOAD_RULES
+        .iter()
+        .find(|(prefix, _)| path == *prefix || path.starts_with(&format!("{}.", prefix)))
+        .map(|(_, action)| *action)
+        .unwrap_or(ReloadAction::Restart)
+}
+
+/// Gateway config file: $OPENCLAW_CONFIG_PATH, $OPENCLAW_STATE_DIR/openclaw.json,
+/// or ~/.openclaw/openclaw.json (same resolution as helix-runtime)
+fn gateway_config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("OPENCLAW_CONFIG_PATH") {
+        if !path.trim().is_empty() {
+            return Some(PathBuf::from(path.trim()));
+        }
+    }
+
+    gateway_state_dir().map(|dir| dir.join("openclaw.json"))
+}
+
+/// Gateway state directory: $OPENCLAW_STATE_DIR or ~/.openclaw
+pub fn gateway_state_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("OPENCLAW_STATE_DIR") {
+        if !dir.trim().is_empty() {
+            return Some(PathBuf::from(dir.trim()));
+        }
+    }
+
+    dirs::home_dir().map(|home| home.join(".openclaw"))
+}
+
+/// Read the synced sections of the helix config
+fn read_synced_sections(config_path: &Path) -> Result<Map<String, Value>, String> {
+    let content = fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+    let config: HelixConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+
+    let mut sections = Map::new();
+    sections.insert(SYNCED_SECTIONS[0].to_string(), config.agents);
+    sections.insert(SYNCED_SECTIONS[1].to_string(), config.models);
+    Ok(sections)
+}
+
+/// Collect the leaf paths that differ between two JSON values
+fn diff_paths(old: &Value, new: &Value, prefix: &mut Vec<String>, out: &mut Vec<Vec<String>>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                prefix.push(key.clone());
+                diff_paths(
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    prefix,
+                    out,
+                );
+                prefix.pop();
+            }
+        }
+        _ if old != new => out.push(prefix.clone()),
+        _ => {}
+    }
+}
+
+/// Write the changed paths into the gateway config and decide how the
+/// running gateway will pick them up
+fn apply_to_gateway_config(
+    helix: &Map<String, Value>,
+    changed: &[Vec<String>],
+) -> Result<ReloadAction, String> {
+    let path = gateway_config_path()
+        .ok_or_else(|| "Could not determine gateway config path".to_string())?;
+
+    let mut gateway = if path.exists() {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read gateway config: {}", e))?;
+        // JSON5 features (comments, trailing commas) can't be rewritten safely
+        serde_json::from_str::<Value>(&content)
+            .map_err(|e| format!("Gateway config is not plain JSON, edit it manually: {}", e))?
+    } else {
+        Value::Object(Map::new())
+    };
+
+    let mut action = ReloadAction::None;
+    for segments in changed {
+        let new_value = lookup(helix, segments);
+        set_path(&mut gateway, segments, new_value);
+        action = action.max(classify_path(&segments.join(".")));
+    }
+
+    // With reloads off the gateway never re-reads its config
+    let reload_mode = gateway
+        .pointer("/gateway/reload/mode")
+        .and_then(Value::as_str)
+        .unwrap_or("hybrid");
+    if reload_mode == "off" {
+        action = ReloadAction::Restart;
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create gateway config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&gateway)
+        .map_err(|e| format!("Failed to serialize gateway config: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write gateway config: {}", e))?;
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to replace gateway config: {}", e))?;
+
+    Ok(action)
+}
+
+/// Look up a path in the helix sections; null means removed
+fn lookup(root: &Map<String, Value>, segments: &[String]) -> Option<Value> {
+    let (first, rest) = segments.split_first()?;
+    let mut current = root.get(first)?;
+    for segment in rest {
+        current = current.get(segment)?;
+    }
+    if current.is_null() {
+        None
+    } else {
+        Some(current.clone())
+    }
+}
+
+/// Set (or remove, for `None`) a value at a path, creating objects as needed
+fn set_path(root: &mut Value, segments: &[String], value: Option<Value>) {
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+
+    let mut current = root;
+    for segment in parents {
+        if value.is_none() {
+            // Nothing to remove below a missing parent
+            current = match current.get_mut(segment) {
+                Some(next) => next,
+                None => return,
+            };
+            continue;
+        }
+
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        current = match current {
+            Value::Object(obj) => obj
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new())),
+            _ => unreachable!(),
+        };
+    }
+
+    match (current.as_object_mut(), value) {
+        (Some(obj), Some(v)) => {
+            obj.insert(last.clone(), v);
+        }
+        (Some(obj), None) => {
+            obj.remove(last);
+        }
+        (None, Some(v)) => {
+            let mut obj = Map::new();
+            obj.insert(last.clone(), v);
+            *current = Value::Object(obj);
+        }
+        (None, None) => {}
+    }
+}
+
+/// Get current timestamp in milliseconds
+fn chrono_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
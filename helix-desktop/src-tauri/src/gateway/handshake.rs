@@ -0,0 +1,159 @@
+// Gateway handshake - checking that a gateway is one this build can use
+//
+// A gateway answering /health is listening, not necessarily usable: it may
+// reject this device's token, or run a helix-runtime too old or too new for
+// this build. start_gateway therefore ends with `verify`, as does attaching
+// to a gateway already running at launch. It connects over the RPC socket
+// with the instance's token and reads the version the gateway reports in
+// its hello. A rejected token, a protocol mismatch or a
+// version outside SUPPORTED_VERSIONS fails the start with an error whose
+// `details.action` says what to do about it:
+//
+//   update_runtime          update the helix-runtime checkout or managed
+//                           install (update_runtime)
+//   update_helix            install a newer Helix: the runtime ships with it,
+//                           or the gateway is newer than this build supports
+//   update_remote_gateway   update helix-runtime on the remote gateway's host
+//   check_token             give the gateway this device's token
+//
+// A version that isn't a release number, like a development build's "dev",
+// is let through with a warning.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::rpc::GatewayRpc;
+use crate::commands::gateway as gateway_commands;
+use crate::commands::{CommandError, ErrorCode};
+
+type Version = (u64, u64, u64);
+
+/// helix-runtime versions this build works with
+pub const SUPPORTED_VERSIONS: VersionRange = VersionRange {
+    min: (2026, 1, 0),
+    below: (2027, 0, 0),
+};
+
+/// From `min` up to, not including, `below`
+#[derive(Debug, Clone, Copy)]
+pub struct VersionRange {
+    pub min: Version,
+    pub below: Version,
+}
+
+impl VersionRange {
+    fn contains(&self, version: Version) -> bool {
+        version >= self.min && version < self.below
+    }
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((a, b, c), (x, y, z)) = (self.min, self.below);
+        write!(f, ">={}.{}.{} <{}.{}.{}", a, b, c, x, y, z)
+    }
+}
+
+/// What a gateway reported when the handshake succeeded
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayHandshake {
+    pub protocol: Option<u64>,
+    /// helix-runtime version as reported
+    pub version: Option<String>,
+}
+
+/// "2026.2.3" or "1.4.0-beta.1" (pre-release and build suffixes ignored);
+/// None for anything else, such as "dev"
+fn parse_version(version: &str) -> Option<Version> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+/// The way out of a version or protocol mismatch
+fn update_action(app: &AppHandle, remote: bool, too_new: bool) -> &'static str {
+    if too_new {
+        "update_helix"
+    } else if remote {
+        "update_remote_gateway"
+    } else if gateway_commands::bundled_openclaw(app).is_some() {
+        "update_helix"
+    } else {
+        "update_runtime"
+    }
+}
+
+/// Connect to the gateway of `instance` at `url` and check that it accepts
+/// this device's token and runs a supported helix-runtime
+pub async fn verify(app: &AppHandle, instance: &str, url: &str, remote: bool) -> Result<GatewayHandshake, CommandError> {
+    let name = gateway_commands::display_name(instance);
+    let token = gateway_commands::get_or_create_instance_token(instance).ok();
+    let rpc = GatewayRpc::connect(url, token)
+        .await
+        .map_err(|e| rejected(app, instance, &name, remote, e))?;
+    let handshake = GatewayHandshake {
+        protocol: rpc.hello().get("protocol").and_then(Value::as_u64),
+        version: rpc.hello().pointer("/server/version").and_then(Value::as_str).map(str::to_string),
+    };
+    drop(rpc);
+
+    let reported = handshake.version.as_deref().unwrap_or("none");
+    let Some(version) = handshake.version.as_deref().and_then(parse_version) else {
+        log::warn!("{} reports no release version ({}); not checking it", name, reported);
+        return Ok(handshake);
+    };
+    if SUPPORTED_VERSIONS.contains(version) {
+        log::info!("{} runs helix-runtime {}", name, reported);
+        return Ok(handshake);
+    }
+
+    let too_new = version >= SUPPORTED_VERSIONS.below;
+    let message = format!(
+        "{} runs helix-runtime {}, but this Helix supports {}; {}",
+        name,
+        reported,
+        SUPPORTED_VERSIONS,
+        if too_new { "update Helix" } else { "update helix-runtime" }
+    );
+    Err(CommandError::new(ErrorCode::Unsupported, message).with_details(serde_json::json!({
+        "instance": instance,
+        "reason": "version",
+        "version": reported,
+        "supported": SUPPORTED_VERSIONS.to_string(),
+        "action": update_action(app, remote, too_new),
+    })))
+}
+
+/// Describe a failed connect by what the gateway objected to
+fn rejected(app: &AppHandle, instance: &str, name: &str, remote: bool, error: String) -> CommandError {
+    if error.contains("unauthorized") {
+        let message = if remote {
+            format!("{} rejected this device's token; set it on the remote gateway ({})", name, error)
+        } else {
+            format!("{} rejected this device's token ({})", name, error)
+        };
+        CommandError::new(ErrorCode::Unauthenticated, message).with_details(serde_json::json!({
+            "instance": instance,
+            "reason": "token",
+            "action": "check_token",
+        }))
+    } else if error.contains("protocol mismatch") {
+        CommandError::new(
+            ErrorCode::Unsupported,
+            format!(
+                "{} speaks another gateway protocol than this Helix; update helix-runtime or Helix so they match",
+                name
+            ),
+        )
+        .with_details(serde_json::json!({
+            "instance": instance,
+            "reason": "protocol",
+            "supported": SUPPORTED_VERSIONS.to_string(),
+            "action": update_action(app, remote, false),
+        }))
+    } else {
+        CommandError::unavailable(format!("{} answers /health but the handshake failed: {}", name, error))
+            .with_details(serde_json::json!({ "instance": instance, "reason": "connect" }))
+    }
+}
@@ -0,0 +1,431 @@
+// helix-runtime installer - a Helix-managed runtime in ~/.helix/helix-runtime
+//
+// Installs without a checkout next to the app or a bundled runtime have no
+// gateway to start. The installer fetches the release manifest of the
+// runtime version this build is pinned to (PINNED_VERSION) and downloads the
+// archive it names. The manifest comes from the same server as the archive,
+// so its SHA-256 only catches a broken download; the archive is refused
+// unless it also matches the manifest's minisign signature under the
+// updater public key in tauri.conf.json, which only the release pipeline
+// can sign with. The archive is unpacked into a staging directory,
+// its production dependencies installed with npm when it ships without
+// node_modules, and only then swapped in for the previous install, which is
+// put back if the swap fails. A marker file (MARKER_FILE) records what was
+// installed; a directory at the same path without it is the user's own and
+// is never replaced.
+//
+// Each phase is reported as task:progress of a "runtime-install" task. At
+// startup `start` installs the runtime when none is found and brings a
+// managed install to the pinned version, unless `gateway.runtime_auto_update`
+// is off; a configured runtime path or a bundled runtime turns both off.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+
+use super::runtime;
+use crate::commands::config;
+use crate::updater::download;
+use crate::commands::{CommandError, ErrorCode};
+use crate::tasks::TaskHandle;
+
+/// helix-runtime release this build installs; within handshake::SUPPORTED_VERSIONS
+pub const PINNED_VERSION: &str = "2026.2.3";
+/// Release manifests live at <RELEASES_URL>/<version>/manifest.json
+const RELEASES_URL: &str = "https://releases.project-helix.org/helix-runtime";
+/// Task kind of installs and updates
+pub const TASK_KIND: &str = "runtime-install";
+/// Limit for a whole install, npm install included
+pub const INSTALL_TIMEOUT: Duration = Duration::from_secs(1800);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const INSTALL_DIR: &str = "helix-runtime";
+/// Download and unpack area, next to the install so the swap is a rename
+const STAGING_DIR: &str = "runtime-install";
+/// Where the previous install waits until the new one is in place
+const PREVIOUS_DIR: &str = "helix-runtime.previous";
+/// Written into a managed install
+const MARKER_FILE: &str = ".helix-install.json";
+#[cfg(windows)]
+const NPM: &str = "npm.cmd";
+#[cfg(not(windows))]
+const NPM: &str = "npm";
+
+/// Set while an install runs
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+/// manifest.json of a runtime release
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseManifest {
+    version: String,
+    /// The .tar.gz archive
+    url: String,
+    /// Hex SHA-256 of the archive
+    sha256: String,
+    /// Base64 minisign signature of the archive, made with the updater key
+    signature: String,
+}
+
+/// Contents of MARKER_FILE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstallRecord {
+    version: String,
+    sha256: String,
+    /// Seconds since epoch
+    installed_at: u64,
+}
+
+/// State of the managed runtime, as returned by get_runtime_install
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeInstall {
+    pub path: String,
+    pub pinned_version: String,
+    /// Version of the managed install; None when there is none
+    pub installed_version: Option<String>,
+    /// The gateway is started from the managed install
+    pub in_use: bool,
+    pub update_available: bool,
+    pub installing: bool,
+    /// The path holds a runtime Helix didn't install, which it leaves alone
+    pub unmanaged: bool,
+}
+
+/// Clears INSTALLING when an install ends, however it ends
+struct InstallGuard;
+
+impl InstallGuard {
+    fn acquire() -> Result<Self, String> {
+        INSTALLING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map_err(|_| "helix-runtime is already being installed".to_string())?;
+        Ok(Self)
+    }
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        INSTALLING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn helix_root() -> Result<PathBuf, String> {
+    crate::accounts::profiles::helix_root()
+}
+
+/// ~/.helix/helix-runtime
+pub fn install_dir() -> Result<PathBuf, String> {
+    Ok(helix_root()?.join(INSTALL_DIR))
+}
+
+fn record(dir: &Path) -> Option<InstallRecord> {
+    let content = std::fs::read_to_string(dir.join(MARKER_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Whether `dir` was installed by the installer
+pub fn is_managed(dir: &Path) -> bool {
+    dir.join(MARKER_FILE).is_file()
+}
+
+fn is_empty_dir(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+fn status() -> Result<RuntimeInstall, String> {
+    let dir = install_dir()?;
+    let installed_version = record(&dir).map(|record| record.version);
+    let selected = runtime::runtime_dir().ok();
+    Ok(RuntimeInstall {
+        path: dir.to_string_lossy().to_string(),
+        pinned_version: PINNED_VERSION.to_string(),
+        update_available: installed_version.as_deref().is_some_and(|version| version != PINNED_VERSION),
+        installed_version,
+        in_use: selected.is_some_and(|selected| selected == dir.canonicalize().unwrap_or(dir.clone())),
+        installing: INSTALLING.load(Ordering::SeqCst),
+        unmanaged: dir.exists() && !is_managed(&dir) && !is_empty_dir(&dir),
+    })
+}
+
+async fn fetch_manifest(client: &reqwest::Client) -> Result<ReleaseManifest, String> {
+    let url = format!("{}/{}/manifest.json", RELEASES_URL, PINNED_VERSION);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch the helix-runtime release manifest: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Fetching {} failed with status: {}", url, response.status()));
+    }
+    let manifest: ReleaseManifest = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid helix-runtime release manifest: {}", e))?;
+    if manifest.version != PINNED_VERSION {
+        return Err(format!(
+            "Release manifest is for helix-runtime {}, expected {}",
+            manifest.version, PINNED_VERSION
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Stream the archive to `path`; Err when its SHA-256 isn't the manifest's
+/// or it isn't signed with the updater key
+async fn download(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    task: &TaskHandle,
+    manifest: &ReleaseManifest,
+    path: &Path,
+) -> Result<(), String> {
+    let public_key = minisign_verify::PublicKey::decode(&download::decode_base64(&download::updater_pubkey(app)?)?)
+        .map_err(|e| format!("Invalid updater public key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&download::decode_base64(manifest.signature.trim())?)
+        .map_err(|e| format!("Invalid helix-runtime signature: {}", e))?;
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|e| format!("helix-runtime signature doesn't fit the updater key: {}", e))?;
+
+    let mut response = client
+        .get(&manifest.url)
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .send()
+        .await
+        .map_err(|e| format!("Download request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download request failed with status: {}", response.status()));
+    }
+    let total = response.content_length().filter(|total| *total > 0);
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| format!("Failed to create download file: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut received: u64 = 0;
+
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download failed: {}", e))? {
+        hasher.update(&chunk);
+        verifier.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write download file: {}", e))?;
+        received += chunk.len() as u64;
+        task.progress(
+            "downloading",
+            total.map(|total| received as f32 / total as f32 * 100.0),
+            Some(format!("{} KiB", received / 1024)),
+        );
+    }
+    file.flush().await.map_err(|e| e.to_string())?;
+
+    let digest = hex::encode(hasher.finalize());
+    if !digest.eq_ignore_ascii_case(manifest.sha256.trim()) {
+        return Err(format!(
+            "helix-runtime {} archive failed verification: SHA-256 {} does not match the release's {}",
+            manifest.version, digest, manifest.sha256
+        ));
+    }
+    verifier.finalize().map_err(|e| {
+        format!(
+            "helix-runtime {} archive failed verification: bad signature ({})",
+            manifest.version, e
+        )
+    })
+}
+
+/// Unpack the .tar.gz at `archive` into `into` and return the runtime
+/// root: `into` itself, or the archive's single top-level directory
+fn extract(archive: &Path, into: &Path) -> Result<PathBuf, String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open the archive: {}", e))?;
+    std::fs::create_dir_all(into).map_err(|e| format!("Failed to create {}: {}", into.display(), e))?;
+    // unpack refuses entries that would land outside `into`
+    tar::Archive::new(flate2::read::GzDecoder::new(file))
+        .unpack(into)
+        .map_err(|e| format!("Failed to unpack the helix-runtime archive: {}", e))?;
+
+    if runtime::is_valid(into) {
+        return Ok(into.to_path_buf());
+    }
+    let entries: Vec<PathBuf> = std::fs::read_dir(into)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    match entries.as_slice() {
+        [root] if runtime::is_valid(root) => Ok(root.clone()),
+        _ => Err("The helix-runtime archive has no openclaw.mjs".to_string()),
+    }
+}
+
+/// Put `staged` at `dir`, restoring the previous install if that fails
+fn swap(staged: &Path, dir: &Path, previous: &Path) -> Result<(), String> {
+    if previous.exists() {
+        std::fs::remove_dir_all(previous).map_err(|e| format!("Failed to remove {}: {}", previous.display(), e))?;
+    }
+    let had_previous = dir.exists();
+    if had_previous {
+        std::fs::rename(dir, previous).map_err(|e| {
+            format!(
+                "Failed to move the current helix-runtime aside ({}); stop the gateway and try again",
+                e
+            )
+        })?;
+    }
+    if let Err(e) = std::fs::rename(staged, dir) {
+        if had_previous {
+            let _ = std::fs::rename(previous, dir);
+        }
+        return Err(format!("Failed to move the new helix-runtime into place: {}", e));
+    }
+    if had_previous {
+        let _ = std::fs::remove_dir_all(previous);
+    }
+    Ok(())
+}
+
+/// Install the pinned runtime into ~/.helix/helix-runtime, replacing an
+/// older managed install; `reinstall` also replaces one at the pinned
+/// version. Body of the runtime-install task.
+pub async fn install(app: &AppHandle, task: &TaskHandle, reinstall: bool) -> Result<String, String> {
+    let _guard = InstallGuard::acquire()?;
+    let dir = install_dir()?;
+    if dir.exists() && !is_managed(&dir) && !is_empty_dir(&dir) {
+        return Err(format!(
+            "{} holds a helix-runtime Helix didn't install; update it yourself, or remove it to let Helix manage it",
+            dir.display()
+        ));
+    }
+    if !reinstall && record(&dir).is_some_and(|record| record.version == PINNED_VERSION) {
+        return Ok(format!("helix-runtime {} is already installed", PINNED_VERSION));
+    }
+
+    let staging = helix_root()?.join(STAGING_DIR);
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| format!("Failed to clear {}: {}", staging.display(), e))?;
+    }
+    std::fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+    let result = install_staged(app, task, &staging, &dir).await;
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+async fn install_staged(app: &AppHandle, task: &TaskHandle, staging: &Path, dir: &Path) -> Result<String, String> {
+    task.progress("manifest", None, Some(format!("Looking up helix-runtime {}", PINNED_VERSION)));
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let manifest = fetch_manifest(&client).await?;
+
+    let archive = staging.join("helix-runtime.tar.gz");
+    download(app, &client, task, &manifest, &archive).await?;
+
+    task.progress("extracting", None, None);
+    let (archive_path, unpack_dir) = (archive.clone(), staging.join("unpacked"));
+    let root = tokio::task::spawn_blocking(move || extract(&archive_path, &unpack_dir))
+        .await
+        .map_err(|e| e.to_string())??;
+    let _ = std::fs::remove_file(&archive);
+
+    if !root.join("node_modules").is_dir() {
+        task.progress("dependencies", None, Some(format!("{} install --omit=dev", NPM)));
+        runtime::run_update_step(&root, NPM, &["install", "--omit=dev"])
+            .await
+            .map_err(|e| e.message)?;
+    }
+
+    let record = InstallRecord {
+        version: manifest.version.clone(),
+        sha256: manifest.sha256.trim().to_lowercase(),
+        installed_at: chrono::Utc::now().timestamp() as u64,
+    };
+    let json = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())?;
+    std::fs::write(root.join(MARKER_FILE), json).map_err(|e| format!("Failed to write {}: {}", MARKER_FILE, e))?;
+
+    task.progress("installing", None, Some(dir.to_string_lossy().to_string()));
+    let previous = helix_root()?.join(PREVIOUS_DIR);
+    let (staged, target) = (root.clone(), dir.to_path_buf());
+    tokio::task::spawn_blocking(move || swap(&staged, &target, &previous))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    log::info!("helix-runtime {} installed at {:?}", manifest.version, dir);
+    let running = crate::commands::gateway::gateway_status(None).is_ok_and(|status| status.running && !status.remote);
+    if running {
+        super::config_sync::set_restart_pending(app, true);
+    }
+    task.progress("complete", Some(100.0), None);
+    Ok(format!("helix-runtime {} installed at {}", manifest.version, dir.display()))
+}
+
+/// Startup check: install the runtime when there is none to start the
+/// gateway from, or update a managed install that isn't at PINNED_VERSION
+pub fn start(app: AppHandle) {
+    let enabled = config::get_config().is_ok_and(|config| config.gateway.runtime_auto_update);
+    if !enabled || runtime::is_configured() || crate::commands::gateway::bundled_openclaw(&app).is_some() {
+        return;
+    }
+    let Ok(dir) = install_dir() else {
+        return;
+    };
+    let missing = runtime::runtime_dir().map_or(true, |selected| !runtime::is_usable(&selected));
+    let outdated = record(&dir).is_some_and(|record| record.version != PINNED_VERSION);
+    if !missing && !outdated {
+        return;
+    }
+    if missing {
+        log::info!("No helix-runtime found; installing {} into {:?}", PINNED_VERSION, dir);
+    } else {
+        log::info!("Updating the managed helix-runtime to {}", PINNED_VERSION);
+    }
+
+    let state = app.state::<crate::AppState>();
+    let body_app = app.clone();
+    let spawned = state.task_manager.spawn(app.clone(), TASK_KIND, Some(INSTALL_TIMEOUT), |task| async move {
+        let result = install(&body_app, &task, false).await;
+        // A fresh install is what the gateway auto-start was missing
+        if result.is_ok() && missing {
+            let running = crate::commands::gateway::gateway_status(None).is_ok_and(|status| status.running);
+            if !running {
+                if let Err(e) = crate::commands::gateway::start_gateway(body_app.clone(), None).await {
+                    log::warn!("Failed to start the gateway after installing helix-runtime: {}", e.message);
+                }
+            }
+        }
+        result
+    });
+    if let Err(e) = spawned {
+        log::warn!("Failed to start the helix-runtime install: {}", e);
+    }
+}
+
+// Tauri commands
+
+/// The managed runtime: where it goes, what is installed and what this
+/// build pins
+#[tauri::command]
+pub fn get_runtime_install() -> Result<RuntimeInstall, CommandError> {
+    status().map_err(CommandError::internal)
+}
+
+/// Install or update the managed runtime as a background task; returns the
+/// task id. `reinstall` replaces an install already at the pinned version.
+#[tauri::command]
+pub async fn install_runtime(
+    app: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    reinstall: Option<bool>,
+) -> Result<String, CommandError> {
+    if INSTALLING.load(Ordering::SeqCst) {
+        return Err(CommandError::new(ErrorCode::Conflict, "helix-runtime is already being installed"));
+    }
+    let body_app = app.clone();
+    let reinstall = reinstall.unwrap_or(false);
+    state
+        .task_manager
+        .spawn(app, TASK_KIND, Some(INSTALL_TIMEOUT), |task| async move {
+            install(&body_app, &task, reinstall).await
+        })
+        .map_err(Into::into)
+}
@@ -0,0 +1,250 @@
+// Gateway log capture
+//
+// start_gateway pipes the process's stdout and stderr. A reader thread per
+// stream drains them (so the pipes never fill and block the gateway), keeps
+// the last MAX_LINES lines in a ring buffer and emits each as `gateway:log`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::CommandError;
+
+const MAX_LINES: usize = 2000;
+/// Lines returned when the caller doesn't set a limit
+const DEFAULT_LIMIT: usize = 500;
+/// Longer lines are cut, so one runaway line can't hold megabytes
+const MAX_LINE_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" | "silly" => Some(Self::Trace),
+            "debug" | "verbose" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "fatal" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload for gateway:log event
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayLogLine {
+    /// Increases by one per line, across restarts
+    pub seq: u64,
+    /// Milliseconds since epoch
+    pub timestamp: u64,
+    pub stream: LogStream,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+struct LogBuffer {
+    lines: VecDeque<GatewayLogLine>,
+    next_seq: u64,
+}
+
+static BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer {
+    lines: VecDeque::new(),
+    next_seq: 0,
+});
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Drop ANSI color and cursor sequences
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                // Parameters run until the final byte in @..~
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Level from a JSON log record or a level word near the start of the line;
+/// otherwise stdout is info and stderr is warn
+fn detect_level(message: &str, stream: LogStream) -> LogLevel {
+    if message.starts_with('{') {
+        if let Ok(record) = serde_json::from_str::<Value>(message) {
+            let name = record
+                .get("level")
+                .or_else(|| record.pointer("/_meta/logLevelName"))
+                .and_then(Value::as_str);
+            if let Some(level) = name.and_then(LogLevel::parse) {
+                return level;
+            }
+        }
+    }
+
+    let head: String = message.chars().take(64).collect();
+    head.split(|c: char| !c.is_ascii_alphabetic())
+        .find_map(LogLevel::parse)
+        .unwrap_or(match stream {
+            LogStream::Stdout => LogLevel::Info,
+            LogStream::Stderr => LogLevel::Warn,
+        })
+}
+
+fn push(app: &AppHandle, stream: LogStream, raw: &str) {
+    let mut message = strip_ansi(raw.trim_end());
+    if message.trim().is_empty() {
+        return;
+    }
+    if message.chars().count() > MAX_LINE_CHARS {
+        message = message.chars().take(MAX_LINE_CHARS).collect::<String>() + "…";
+    }
+
+    let line = {
+        let Ok(mut buffer) = BUFFER.lock() else {
+            return;
+        };
+        let line = GatewayLogLine {
+            seq: buffer.next_seq,
+            timestamp: now_ms(),
+            stream,
+            level: detect_level(&message, stream),
+            message,
+        };
+        buffer.next_seq += 1;
+        if buffer.lines.len() == MAX_LINES {
+            buffer.lines.pop_front();
+        }
+        buffer.lines.push_back(line.clone());
+        line
+    };
+
+    let _ = app.emit("gateway:log", &line);
+}
+
+fn spawn_reader(app: AppHandle, stream: LogStream, pipe: impl Read + Send + 'static) {
+    let name = match stream {
+        LogStream::Stdout => "gateway-stdout",
+        LogStream::Stderr => "gateway-stderr",
+    };
+    let spawned = std::thread::Builder::new().name(name.to_string()).spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        // Read bytes, not String lines, so invalid UTF-8 doesn't end capture
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(_) => push(&app, stream, &String::from_utf8_lossy(&buf)),
+                Err(e) => {
+                    log::debug!("Stopped reading gateway {}: {}", name, e);
+                    break;
+                }
+            }
+        }
+    });
+    if let Err(e) = spawned {
+        log::warn!("Failed to start {} reader: {}", name, e);
+    }
+}
+
+/// Take the child's piped output and start capturing it. Readers end when
+/// the process exits and closes its pipes.
+pub fn capture(app: &AppHandle, child: &mut std::process::Child) {
+    if let Some(stdout) = child.stdout.take() {
+        spawn_reader(app.clone(), LogStream::Stdout, stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_reader(app.clone(), LogStream::Stderr, stderr);
+    }
+}
+
+/// Sequence number the next captured line will get
+pub fn next_seq() -> u64 {
+    BUFFER.lock().map(|buffer| buffer.next_seq).unwrap_or(0)
+}
+
+/// The last `limit` stderr lines captured from `seq` on, oldest first
+pub fn stderr_since(seq: u64, limit: usize) -> Vec<String> {
+    let Ok(buffer) = BUFFER.lock() else {
+        return Vec::new();
+    };
+    let mut lines: Vec<String> = buffer
+        .lines
+        .iter()
+        .rev()
+        .filter(|line| line.seq >= seq && line.stream == LogStream::Stderr)
+        .take(limit)
+        .map(|line| line.message.clone())
+        .collect();
+    lines.reverse();
+    lines
+}
+
+// Tauri commands
+
+/// Recent gateway output, oldest first. `level` is the minimum level to
+/// include; `after_seq` returns only lines newer than one already seen.
+#[tauri::command]
+pub fn get_gateway_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+    after_seq: Option<u64>,
+) -> Result<Vec<GatewayLogLine>, CommandError> {
+    let min_level = match level.as_deref() {
+        Some(name) => LogLevel::parse(name).ok_or_else(|| {
+            CommandError::invalid_input(format!(
+                "Invalid log level: {} (expected trace, debug, info, warn or error)",
+                name
+            ))
+        })?,
+        None => LogLevel::Trace,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let buffer = BUFFER.lock().map_err(|e| e.to_string())?;
+    let mut lines: Vec<GatewayLogLine> = buffer
+        .lines
+        .iter()
+        .rev()
+        .filter(|line| line.level >= min_level && after_seq.is_none_or(|seq| line.seq > seq))
+        .take(limit)
+        .cloned()
+        .collect();
+    lines.reverse();
+    Ok(lines)
+}
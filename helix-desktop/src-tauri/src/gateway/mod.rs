@@ -1,5 +1,20 @@
 // Helix Desktop - Gateway Module
 
+pub mod actions;
+pub mod agent_metrics;
+pub mod client;
+pub mod config_sync;
+pub mod handshake;
+pub mod installer;
+pub mod logs;
 pub mod monitor;
+pub mod node;
+pub mod nightly_restart;
+pub mod params_schema;
+pub mod rpc;
+pub mod runtime;
+pub mod speakers;
+pub mod supervisor;
 
+pub use config_sync::GatewayConfigSync;
 pub use monitor::GatewayMonitor;
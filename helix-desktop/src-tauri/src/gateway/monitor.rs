@@ -1,13 +1,29 @@
 // Helix Desktop - Gateway Health Monitor
+//
+// Each check's details (latency, and the uptime, version and connected
+// channels from the /health body when the gateway reports them) are kept
+// for get_gateway_health_details and emitted as `gateway:health`. A remote
+// gateway (`gateway.remote_url`) is checked over HTTPS and never restarted
+// from here.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Runtime};
 use tokio::sync::RwLock;
 use tokio::time::interval;
 
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{gateway as gateway_commands, CommandError};
+
+/// Wait before the first auto-restart; doubles with each further attempt
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(2);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(60);
+/// A session updated this recently counts as active
+const ACTIVE_SESSION_AGE_MS: f64 = 10.0 * 60.0 * 1000.0;
+
 /// Gateway connection status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -38,6 +54,28 @@ pub struct GatewayStatusEvent {
     pub timestamp: u64,
 }
 
+/// Outcome of the latest health check, also the payload of gateway:health
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GatewayHealthDetails {
+    pub healthy: bool,
+    pub port: u16,
+    /// Round trip of the /health request
+    pub latency_ms: Option<u64>,
+    pub last_checked: Option<u64>,
+    /// Last check that found the gateway healthy
+    pub last_success: Option<u64>,
+    pub uptime_secs: Option<u64>,
+    pub version: Option<String>,
+    /// Channels the gateway reports as connected
+    pub connected_channels: Vec<String>,
+    /// Sessions updated in the last ten minutes, when the gateway lists them
+    pub active_sessions: Option<u32>,
+    pub error: Option<String>,
+}
+
+static LAST_HEALTH: LazyLock<Mutex<GatewayHealthDetails>> =
+    LazyLock::new(|| Mutex::new(GatewayHealthDetails::default()));
+
 /// Gateway health monitor
 pub struct GatewayMonitor {
     status: Arc<RwLock<GatewayStatus>>,
@@ -99,7 +137,7 @@ impl GatewayMonitor {
                     .as_millis() as u64,
             };
 
-            let _ = app.emit("gateway:status", &event);
+            emit_status(app, &event);
         }
     }
 
@@ -108,8 +146,10 @@ impl GatewayMonitor {
         self.auto_restart.store(enabled, Ordering::SeqCst);
     }
 
-    /// Start the health monitoring loop
-    pub fn start<R: Runtime + 'static>(&self, app: AppHandle<R>) {
+    /// Start the health monitoring loop. When the gateway process started
+    /// by the desktop stops answering, it is restarted here with exponential
+    /// backoff, so recovery doesn't depend on a window being open.
+    pub fn start(&self, app: AppHandle) {
         if self.running.swap(true, Ordering::SeqCst) {
             return; // Already running
         }
@@ -130,68 +170,212 @@ impl GatewayMonitor {
             while running.load(Ordering::SeqCst) {
                 interval.tick().await;
 
+                // Follow the default instance started by start_gateway; a
+                // gateway that was stopped on purpose is not monitored
+                let process = gateway_commands::gateway_status(None).ok();
+                let managed = process.as_ref().is_some_and(|p| p.running);
+                let remote = process.as_ref().filter(|p| p.remote).and_then(|p| p.url.clone());
+                if let Some(process_port) = process.as_ref().and_then(|p| p.port) {
+                    *port.write().await = process_port;
+                }
+                {
+                    let mut s = status.write().await;
+                    let next = match (*s, managed) {
+                        (GatewayStatus::Stopped, true) => Some((GatewayStatus::Starting, "Gateway starting...")),
+                        (GatewayStatus::Stopped, false) => None,
+                        (_, false) => Some((GatewayStatus::Stopped, "Gateway stopped")),
+                        _ => None,
+                    };
+                    if let Some((next, message)) = next {
+                        *s = next;
+                        consecutive_failures = 0;
+                        restart_attempts = 0;
+                        emit_status(
+                            &app,
+                            &GatewayStatusEvent {
+                                status: next,
+                                message: Some(message.to_string()),
+                                timestamp: current_timestamp(),
+                            },
+                        );
+                    }
+                }
+
                 let current_status = *status.read().await;
 
-                // Skip health checks if gateway is stopped or starting
-                if current_status == GatewayStatus::Stopped
-                    || current_status == GatewayStatus::Starting
-                    || current_status == GatewayStatus::Restarting
-                {
+                // Skip health checks if gateway is stopped or restarting
+                if current_status == GatewayStatus::Stopped || current_status == GatewayStatus::Restarting {
                     consecutive_failures = 0;
                     continue;
                 }
 
                 let current_port = *port.read().await;
-                let is_healthy = check_gateway_health(current_port).await;
+                let health = record_health(match &remote {
+                    Some(url) => check_remote_gateway_health(url).await,
+                    None => check_gateway_health(current_port).await,
+                });
+                let _ = app.emit("gateway:health", &health);
 
-                if is_healthy {
+                if health.healthy {
                     consecutive_failures = 0;
                     restart_attempts = 0;
 
                     let mut s = status.write().await;
-                    if *s == GatewayStatus::Unhealthy {
+                    if *s == GatewayStatus::Unhealthy || *s == GatewayStatus::Starting {
+                        let message = if *s == GatewayStatus::Unhealthy {
+                            events::record(ServiceKind::Gateway, "recovered", "Gateway recovered", None);
+                            "Gateway recovered"
+                        } else {
+                            "Gateway started"
+                        };
                         *s = GatewayStatus::Running;
-                        let _ = app.emit(
-                            "gateway:status",
-                            GatewayStatusEvent {
+                        emit_status(
+                            &app,
+                            &GatewayStatusEvent {
                                 status: GatewayStatus::Running,
-                                message: Some("Gateway recovered".to_string()),
+                                message: Some(message.to_string()),
+                                timestamp: current_timestamp(),
+                            },
+                        );
+                    }
+                    continue;
+                }
+
+                consecutive_failures += 1;
+                if consecutive_failures < unhealthy_threshold {
+                    continue;
+                }
+
+                {
+                    let mut s = status.write().await;
+                    if *s != GatewayStatus::Unhealthy {
+                        *s = GatewayStatus::Unhealthy;
+                        events::record(
+                            ServiceKind::Gateway,
+                            "unhealthy",
+                            format!("Gateway not responding after {} checks", consecutive_failures),
+                            None,
+                        );
+                        emit_status(
+                            &app,
+                            &GatewayStatusEvent {
+                                status: GatewayStatus::Unhealthy,
+                                message: Some(format!(
+                                    "Gateway not responding after {} checks",
+                                    consecutive_failures
+                                )),
                                 timestamp: current_timestamp(),
                             },
                         );
                     }
-                } else {
-                    consecutive_failures += 1;
-
-                    if consecutive_failures >= unhealthy_threshold {
-                        let mut s = status.write().await;
-                        if *s != GatewayStatus::Unhealthy {
-                            *s = GatewayStatus::Unhealthy;
-                            let _ = app.emit(
-                                "gateway:status",
-                                GatewayStatusEvent {
-                                    status: GatewayStatus::Unhealthy,
-                                    message: Some(format!(
-                                        "Gateway not responding after {} checks",
-                                        consecutive_failures
-                                    )),
-                                    timestamp: current_timestamp(),
-                                },
-                            );
-                        }
-                        drop(s);
-
-                        // Attempt auto-restart if enabled
-                        if auto_restart.load(Ordering::SeqCst) && restart_attempts < max_retries {
-                            restart_attempts += 1;
-                            let _ = app.emit(
-                                "gateway:restart-requested",
-                                serde_json::json!({
-                                    "attempt": restart_attempts,
-                                    "max_retries": max_retries
-                                }),
-                            );
-                        }
+                }
+
+                // A remote gateway is restarted where it runs
+                if remote.is_some() || !auto_restart.load(Ordering::SeqCst) || restart_attempts > max_retries {
+                    continue;
+                }
+                if restart_attempts == max_retries {
+                    // Report giving up once; a recovery or manual restart resets the count
+                    restart_attempts += 1;
+                    events::record(
+                        ServiceKind::Gateway,
+                        "restart_gave_up",
+                        format!("Gateway auto-restart gave up after {} attempts", max_retries),
+                        None,
+                    );
+                    emit_status(
+                        &app,
+                        &GatewayStatusEvent {
+                            status: GatewayStatus::Unhealthy,
+                            message: Some(format!(
+                                "Gateway still not responding after {} restarts",
+                                max_retries
+                            )),
+                            timestamp: current_timestamp(),
+                        },
+                    );
+                    continue;
+                }
+
+                restart_attempts += 1;
+                let delay = restart_backoff(restart_attempts);
+                events::record(
+                    ServiceKind::Gateway,
+                    "restart_requested",
+                    format!("Gateway restart requested (attempt {} of {})", restart_attempts, max_retries),
+                    None,
+                );
+                let _ = app.emit(
+                    "gateway:restart-requested",
+                    serde_json::json!({
+                        "attempt": restart_attempts,
+                        "max_retries": max_retries,
+                        "delay_ms": delay.as_millis() as u64,
+                    }),
+                );
+                *status.write().await = GatewayStatus::Restarting;
+                emit_status(
+                    &app,
+                    &GatewayStatusEvent {
+                        status: GatewayStatus::Restarting,
+                        message: Some("Gateway restarting...".to_string()),
+                        timestamp: current_timestamp(),
+                    },
+                );
+
+                tokio::time::sleep(delay).await;
+                let stop_app = app.clone();
+                let stopped = tauri::async_runtime::spawn_blocking(move || {
+                    gateway_commands::stop_gateway(stop_app, None)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map_err(|e| e.message));
+                let result = match stopped {
+                    Ok(()) => gateway_commands::start_gateway(app.clone(), None)
+                        .await
+                        .map_err(|e| e.message),
+                    Err(e) => Err(e),
+                };
+
+                consecutive_failures = 0;
+                match result {
+                    Ok(started) => {
+                        *port.write().await = started.port;
+                        *status.write().await = GatewayStatus::Starting;
+                        events::record(
+                            ServiceKind::Gateway,
+                            "auto_restarted",
+                            format!("Gateway restarted automatically (attempt {} of {})", restart_attempts, max_retries),
+                            None,
+                        );
+                        let _ = app.emit(
+                            "gateway:auto-restarted",
+                            serde_json::json!({
+                                "attempt": restart_attempts,
+                                "max_retries": max_retries,
+                                "port": started.port,
+                                "url": started.url,
+                            }),
+                        );
+                    }
+                    Err(e) => {
+                        log::warn!("Gateway auto-restart failed: {}", e);
+                        *status.write().await = GatewayStatus::Unhealthy;
+                        events::record(
+                            ServiceKind::Gateway,
+                            "restart_failed",
+                            format!("Gateway auto-restart failed: {}", e),
+                            None,
+                        );
+                        emit_status(
+                            &app,
+                            &GatewayStatusEvent {
+                                status: GatewayStatus::Unhealthy,
+                                message: Some(format!("Gateway restart failed: {}", e)),
+                                timestamp: current_timestamp(),
+                            },
+                        );
                     }
                 }
             }
@@ -232,27 +416,148 @@ impl GatewayMonitor {
     }
 }
 
-/// Check if the gateway is healthy by attempting a WebSocket connection
-async fn check_gateway_health(port: u16) -> bool {
-    let url = format!("http://127.0.0.1:{}/health", port);
+/// Check the gateway's /health endpoint, reading uptime, version and
+/// channels from the body when it is JSON. A gateway without the endpoint
+/// counts as healthy if it accepts connections.
+pub(crate) async fn check_gateway_health(port: u16) -> GatewayHealthDetails {
+    probe(format!("http://127.0.0.1:{}/health", port), format!("127.0.0.1:{}", port), port).await
+}
+
+/// Check a remote gateway's /health over HTTPS: wss://host:port/path is
+/// probed at https://host:port/path/health
+pub(crate) async fn check_remote_gateway_health(url: &str) -> GatewayHealthDetails {
+    let parsed = reqwest::Url::parse(url).ok();
+    let host = parsed.as_ref().and_then(|u| u.host_str()).unwrap_or_default();
+    let port = parsed.as_ref().and_then(|u| u.port_or_known_default()).unwrap_or(443);
+    let base = url.replacen("wss://", "https://", 1).replacen("ws://", "http://", 1);
+    probe(format!("{}/health", base.trim_end_matches('/')), format!("{}:{}", host, port), port).await
+}
+
+/// GET `url`, falling back to a TCP connect to `addr`
+async fn probe(url: String, addr: String, port: u16) -> GatewayHealthDetails {
+    let mut health = GatewayHealthDetails {
+        port,
+        last_checked: Some(current_timestamp()),
+        ..Default::default()
+    };
 
     let client = match reqwest::Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
     {
         Ok(c) => c,
-        Err(_) => return false,
+        Err(e) => {
+            health.error = Some(e.to_string());
+            return health;
+        }
     };
 
+    let started = Instant::now();
     match client.get(&url).send().await {
-        Ok(response) => response.status().is_success(),
-        Err(_) => {
+        Ok(response) => {
+            health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            health.healthy = response.status().is_success();
+            if !health.healthy {
+                health.error = Some(format!("Health check returned HTTP {}", response.status()));
+            }
+            if let Ok(body) = response.json::<Value>().await {
+                parse_health_body(&body, &mut health);
+            }
+        }
+        Err(e) => {
             // Try TCP connection as fallback
-            tokio::net::TcpStream::connect(format!("127.0.0.1:{}", port))
+            let started = Instant::now();
+            health.healthy = tokio::net::TcpStream::connect(addr)
                 .await
-                .is_ok()
+                .is_ok();
+            if health.healthy {
+                health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            } else {
+                health.error = Some(format!("Gateway not responding: {}", e));
+            }
+        }
+    }
+    health
+}
+
+/// Pick the fields the desktop shows out of a /health body. Uptime may be
+/// `uptimeMs` or seconds in `uptime`; a channel counts as connected when
+/// it or one of its accounts is `linked` or `connected`; a session is
+/// active when its `age` in `sessions.recent` is under ten minutes.
+fn parse_health_body(body: &Value, health: &mut GatewayHealthDetails) {
+    health.uptime_secs = body
+        .get("uptimeMs")
+        .and_then(Value::as_f64)
+        .map(|ms| ms / 1000.0)
+        .or_else(|| body.get("uptime").and_then(Value::as_f64))
+        .map(|secs| secs as u64);
+    health.version = body.get("version").and_then(Value::as_str).map(String::from);
+    health.active_sessions = body
+        .pointer("/sessions/recent")
+        .and_then(Value::as_array)
+        .map(|recent| {
+            recent
+                .iter()
+                .filter(|session| {
+                    session
+                        .get("age")
+                        .and_then(Value::as_f64)
+                        .is_some_and(|age| age < ACTIVE_SESSION_AGE_MS)
+                })
+                .count() as u32
+        });
+
+    let is_connected = |channel: &Value| {
+        ["linked", "connected"]
+            .iter()
+            .any(|key| channel.get(key).and_then(Value::as_bool) == Some(true))
+    };
+    health.connected_channels = match body.get("channels") {
+        Some(Value::Object(channels)) => channels
+            .iter()
+            .filter(|(_, channel)| {
+                is_connected(channel)
+                    || channel
+                        .get("accounts")
+                        .and_then(Value::as_object)
+                        .is_some_and(|accounts| accounts.values().any(is_connected))
+            })
+            .map(|(name, _)| name.clone())
+            .collect(),
+        Some(Value::Array(channels)) => channels
+            .iter()
+            .filter_map(|channel| match channel {
+                Value::String(name) => Some(name.clone()),
+                _ if is_connected(channel) => channel.get("name").and_then(Value::as_str).map(String::from),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+}
+
+/// Keep a check as the latest, carrying over the last success
+fn record_health(mut health: GatewayHealthDetails) -> GatewayHealthDetails {
+    if let Ok(mut last) = LAST_HEALTH.lock() {
+        health.last_success = if health.healthy { health.last_checked } else { last.last_success };
+        if last.healthy != health.healthy {
+            crate::integrations::presence::notify_changed();
         }
+        *last = health.clone();
     }
+    health
+}
+
+/// Outcome of the latest health check
+pub fn last_health() -> GatewayHealthDetails {
+    LAST_HEALTH.lock().map(|last| last.clone()).unwrap_or_default()
+}
+
+/// Delay before restart attempt `attempt` (1-based)
+fn restart_backoff(attempt: u32) -> Duration {
+    RESTART_BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(RESTART_MAX_DELAY)
 }
 
 /// Get current timestamp in milliseconds
@@ -263,8 +568,39 @@ fn current_timestamp() -> u64 {
         .as_millis() as u64
 }
 
+/// Emit gateway:status and announce the change to screen readers
+fn emit_status<R: Runtime>(app: &AppHandle<R>, event: &GatewayStatusEvent) {
+    let _ = app.emit("gateway:status", event);
+    crate::accessibility::announce::gateway_status(app, event.status);
+}
+
 /// Create a global gateway monitor instance
 #[allow(dead_code)]
 pub fn create_monitor() -> GatewayMonitor {
     GatewayMonitor::new()
 }
+
+// Tauri commands
+
+/// Latency, uptime, version and connected channels of the default gateway,
+/// checked now when it is running; otherwise the last check, marked unhealthy
+#[tauri::command]
+pub async fn get_gateway_health_details() -> Result<GatewayHealthDetails, CommandError> {
+    let process = gateway_commands::gateway_status(None)?;
+    match process.port.filter(|_| process.running) {
+        Some(_) if process.remote => {
+            let url = process.url.unwrap_or_default();
+            Ok(record_health(check_remote_gateway_health(&url).await))
+        }
+        Some(port) => Ok(record_health(check_gateway_health(port).await)),
+        None => {
+            let last = LAST_HEALTH.lock().map_err(|e| e.to_string())?.clone();
+            Ok(GatewayHealthDetails {
+                healthy: false,
+                latency_ms: None,
+                error: Some("Gateway is not running".to_string()),
+                ..last
+            })
+        }
+    }
+}
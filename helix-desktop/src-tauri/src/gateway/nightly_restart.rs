@@ -0,0 +1,292 @@
+// Nightly gateway restart
+//
+// With `gateway.nightly_restart_at` set ("03:30"), the default gateway is
+// restarted once a night, any time from that local time until
+// `gateway.nightly_restart_window_mins` later. While /health lists
+// sessions updated in the last ten minutes the restart waits, checking
+// again every few minutes; if the window closes first, that night is
+// skipped. The gateway gets SIGTERM and a grace period to wind down before
+// it is killed, then starts again as start_gateway does. A remote gateway
+// or one that isn't running is left alone.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::monitor::check_gateway_health;
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{config, gateway as gateway_commands, CommandError};
+
+/// How often the window is checked for
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait before checking again while sessions are active
+const BUSY_RETRY: Duration = Duration::from_secs(5 * 60);
+/// Time the gateway gets to exit on SIGTERM before it is killed
+const STOP_GRACE: Duration = Duration::from_secs(15);
+/// Longest window accepted, so consecutive nights never overlap
+const MAX_WINDOW_MINS: u64 = 12 * 60;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static STATE: Mutex<RestartState> = Mutex::new(RestartState::new());
+
+/// How a night's restart went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartOutcome {
+    Restarted,
+    /// Sessions were still active when the window closed
+    SkippedActiveSessions,
+    /// The gateway wasn't running, or runs on another host
+    SkippedNotRunning,
+    Failed,
+}
+
+struct RestartState {
+    /// Night (the date its window opened) already dealt with
+    handled: Option<NaiveDate>,
+    /// No new check before this while sessions are active
+    retry_at: Option<Instant>,
+    last_run: Option<u64>,
+    last_outcome: Option<RestartOutcome>,
+    last_message: Option<String>,
+}
+
+impl RestartState {
+    const fn new() -> Self {
+        Self {
+            handled: None,
+            retry_at: None,
+            last_run: None,
+            last_outcome: None,
+            last_message: None,
+        }
+    }
+}
+
+/// Schedule and last result of the nightly restart
+#[derive(Debug, Clone, Serialize)]
+pub struct NightlyRestartStatus {
+    pub enabled: bool,
+    /// Local "HH:MM" the window opens at
+    pub at: Option<String>,
+    pub window_mins: u64,
+    /// When the next restart may happen (ms)
+    pub next_run: Option<u64>,
+    /// When the last night's restart happened or was skipped (ms)
+    pub last_run: Option<u64>,
+    pub last_outcome: Option<RestartOutcome>,
+    pub last_message: Option<String>,
+}
+
+/// Payload for gateway:scheduled-restart event
+#[derive(Debug, Clone, Serialize)]
+struct ScheduledRestartPayload {
+    outcome: RestartOutcome,
+    message: String,
+    timestamp: u64,
+}
+
+fn now_ms() -> u64 {
+    Local::now().timestamp_millis().max(0) as u64
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Window opening time and length, when the restart is configured
+fn settings() -> Option<(NaiveTime, ChronoDuration)> {
+    let gateway = config::get_config().ok()?.gateway;
+    let at = parse_time(gateway.nightly_restart_at.as_deref()?)?;
+    let mins = gateway.nightly_restart_window_mins.clamp(1, MAX_WINDOW_MINS);
+    Some((at, ChronoDuration::minutes(mins as i64)))
+}
+
+fn window_start(night: NaiveDate, at: NaiveTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&night.and_time(at)).earliest()
+}
+
+/// The night whose window contains `now`, and when that window closes. A
+/// window running past midnight belongs to the day it opened.
+fn open_window(now: DateTime<Local>, at: NaiveTime, window: ChronoDuration) -> Option<(NaiveDate, DateTime<Local>)> {
+    let today = now.date_naive();
+    [today.pred_opt()?, today].into_iter().find_map(|night| {
+        let start = window_start(night, at)?;
+        let end = start + window;
+        (start <= now && now < end).then_some((night, end))
+    })
+}
+
+/// Now while a window not yet dealt with is open, else the next opening
+fn next_run(now: DateTime<Local>, at: NaiveTime, window: ChronoDuration, handled: Option<NaiveDate>) -> Option<DateTime<Local>> {
+    if let Some((night, _)) = open_window(now, at, window) {
+        if handled != Some(night) {
+            return Some(now);
+        }
+    }
+    (0..=2)
+        .filter_map(|offset| window_start(now.date_naive() + ChronoDuration::days(offset), at))
+        .find(|start| *start > now)
+}
+
+/// Record how the night went; skips for a stopped gateway are only logged
+fn finish(app: &AppHandle, night: NaiveDate, outcome: RestartOutcome, message: String) {
+    if let Ok(mut state) = STATE.lock() {
+        state.handled = Some(night);
+        state.retry_at = None;
+        state.last_run = Some(now_ms());
+        state.last_outcome = Some(outcome);
+        state.last_message = Some(message.clone());
+    }
+
+    let action = match outcome {
+        RestartOutcome::SkippedNotRunning => {
+            log::debug!("{}", message);
+            return;
+        }
+        RestartOutcome::Restarted => "scheduled_restart",
+        RestartOutcome::SkippedActiveSessions => "scheduled_restart_skipped",
+        RestartOutcome::Failed => "scheduled_restart_failed",
+    };
+    log::info!("{}", message);
+    events::record(ServiceKind::Gateway, action, message.clone(), None);
+    let _ = app.emit(
+        "gateway:scheduled-restart",
+        ScheduledRestartPayload {
+            outcome,
+            message,
+            timestamp: now_ms(),
+        },
+    );
+    crate::accessibility::announce::nightly_restart(app, outcome);
+}
+
+async fn restart(app: &AppHandle) -> Result<u16, String> {
+    let stop_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        gateway_commands::stop_gracefully(stop_app, None, STOP_GRACE)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.message)?;
+    gateway_commands::start_gateway(app.clone(), None)
+        .await
+        .map(|started| started.port)
+        .map_err(|e| e.message)
+}
+
+async fn tick(app: &AppHandle) {
+    let Some((at, window)) = settings() else {
+        return;
+    };
+    let Some((night, closes)) = open_window(Local::now(), at, window) else {
+        return;
+    };
+    match STATE.lock() {
+        Ok(state) if state.handled == Some(night) => return,
+        Ok(state) if state.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) => return,
+        Ok(_) => {}
+        Err(_) => return,
+    }
+
+    let status = gateway_commands::gateway_status(None).ok();
+    let Some(port) = status.filter(|s| s.running && !s.remote).and_then(|s| s.port) else {
+        finish(app, night, RestartOutcome::SkippedNotRunning, "Nightly restart skipped: gateway not running".to_string());
+        return;
+    };
+
+    let active = check_gateway_health(port).await.active_sessions.unwrap_or(0);
+    if active > 0 {
+        let retry = Local::now() + ChronoDuration::from_std(BUSY_RETRY).unwrap_or_default();
+        if retry < closes {
+            log::info!("Nightly gateway restart waiting on {} active sessions", active);
+            if let Ok(mut state) = STATE.lock() {
+                state.retry_at = Some(Instant::now() + BUSY_RETRY);
+            }
+        } else {
+            finish(
+                app,
+                night,
+                RestartOutcome::SkippedActiveSessions,
+                format!("Nightly gateway restart skipped: {} sessions active until the window closed", active),
+            );
+        }
+        return;
+    }
+
+    match restart(app).await {
+        Ok(port) => finish(app, night, RestartOutcome::Restarted, format!("Gateway restarted for the night on port {}", port)),
+        Err(e) => finish(app, night, RestartOutcome::Failed, format!("Nightly gateway restart failed: {}", e)),
+    }
+}
+
+/// Check for the restart window every minute (once per process)
+pub fn start(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tick(&app).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn status() -> NightlyRestartStatus {
+    let gateway = config::get_config().map(|c| c.gateway).unwrap_or_default();
+    let state = STATE.lock().ok();
+    let handled = state.as_ref().and_then(|s| s.handled);
+    let next_run = settings()
+        .and_then(|(at, window)| next_run(Local::now(), at, window, handled))
+        .map(|next| next.timestamp_millis().max(0) as u64);
+
+    NightlyRestartStatus {
+        enabled: next_run.is_some(),
+        at: gateway.nightly_restart_at,
+        window_mins: gateway.nightly_restart_window_mins,
+        next_run,
+        last_run: state.as_ref().and_then(|s| s.last_run),
+        last_outcome: state.as_ref().and_then(|s| s.last_outcome),
+        last_message: state.as_ref().and_then(|s| s.last_message.clone()),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_nightly_restart() -> Result<NightlyRestartStatus, CommandError> {
+    Ok(status())
+}
+
+/// Restart the default gateway nightly at `at` ("HH:MM", local), or stop
+/// doing so with `None`; `window_mins` keeps its value when not given
+#[tauri::command]
+pub fn set_nightly_restart(at: Option<String>, window_mins: Option<u64>) -> Result<NightlyRestartStatus, CommandError> {
+    let at = at.map(|at| at.trim().to_string()).filter(|at| !at.is_empty());
+    if let Some(at) = &at {
+        if parse_time(at).is_none() {
+            return Err(CommandError::invalid_input(format!("Invalid restart time (expected HH:MM): {}", at)));
+        }
+    }
+    if let Some(mins) = window_mins {
+        if !(1..=MAX_WINDOW_MINS).contains(&mins) {
+            return Err(CommandError::invalid_input(format!(
+                "Restart window must be between 1 and {} minutes",
+                MAX_WINDOW_MINS
+            )));
+        }
+    }
+
+    let mut config = config::get_config()?;
+    config.gateway.nightly_restart_at = at;
+    if let Some(mins) = window_mins {
+        config.gateway.nightly_restart_window_mins = mins;
+    }
+    config::set_config(config)?;
+    Ok(status())
+}
@@ -0,0 +1,405 @@
+// Node.js doctor - finding the node the gateway runs on
+//
+// An app started from the desktop doesn't get the login shell's PATH, so a
+// node installed through a version manager is often not where a bare
+// `node` lookup lands. Candidates are tried in order:
+// 1. node on PATH
+// 2. Volta's shim (~/.volta/bin, or $VOLTA_HOME)
+// 3. fnm's default alias, then its installed versions, newest first
+// 4. nvm's installed versions, newest first ($NVM_HOME with nvm-windows)
+// 5. Common install locations: Homebrew, /usr/local, Program Files and
+//    nvm-windows' $NVM_SYMLINK
+//
+// PATH entries, shims and install locations are asked for `--version`;
+// version manager installs are versioned by their directory name. The first
+// candidate at the version helix-runtime requires (its engines.node, or
+// MIN_VERSION) is used. Without one the environment reports the newest node
+// it found, or none, with suggestions for fixing it. The result is cached
+// until `get_node_environment` is asked to refresh or the node disappears.
+
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::commands::{CommandError, ErrorCode};
+
+type Version = (u64, u64, u64);
+
+/// helix-runtime's engines.node, for a runtime that doesn't say
+const MIN_VERSION: Version = (22, 12, 0);
+#[cfg(windows)]
+const NODE: &str = "node.exe";
+#[cfg(not(windows))]
+const NODE: &str = "node";
+#[cfg(windows)]
+const NPM: &str = "npm.cmd";
+#[cfg(not(windows))]
+const NPM: &str = "npm";
+
+/// Result of the last detection
+static DETECTED: Mutex<Option<NodeEnvironment>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSource {
+    Path,
+    Volta,
+    Fnm,
+    Nvm,
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    Ok,
+    /// Only nodes older than `required` were found
+    TooOld,
+    Missing,
+}
+
+/// A node binary that was found
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeCandidate {
+    pub path: String,
+    pub source: NodeSource,
+    /// None when `--version` failed
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeEnvironment {
+    pub status: NodeStatus,
+    /// The node the gateway runs on; with TooOld, the newest one found
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub source: Option<NodeSource>,
+    /// npm next to that node
+    pub npm_path: Option<String>,
+    /// Minimum version, as ">=22.12.0"
+    pub required: String,
+    pub candidates: Vec<NodeCandidate>,
+    /// What to do about a TooOld or Missing status
+    pub suggestions: Vec<String>,
+}
+
+fn format_version((major, minor, patch): Version) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// "v22.12.0", "22.12.0" or "22.12"
+fn parse_version(text: &str) -> Option<Version> {
+    let mut parts = text.trim().trim_start_matches('v').split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+/// engines.node of the runtime the gateway starts from, if it's ">=x.y.z"
+fn required_version() -> Version {
+    super::runtime::runtime_dir()
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(dir.join("package.json")).ok())
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|package| package.pointer("/engines/node")?.as_str()?.trim().strip_prefix(">=").and_then(parse_version))
+        .unwrap_or(MIN_VERSION)
+}
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_default()
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+/// Path of node inside an installed version directory
+fn versioned_binary(dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        dir.join(NODE)
+    } else {
+        dir.join("bin").join(NODE)
+    }
+}
+
+/// Subdirectories of `dir` named as versions, newest first
+fn installed_versions(dir: &Path) -> Vec<(Version, PathBuf)> {
+    let mut versions: Vec<(Version, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((parse_version(&entry.file_name().to_string_lossy())?, entry.path())))
+        .collect();
+    versions.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    versions
+}
+
+fn path_candidates() -> Vec<PathBuf> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    std::env::split_paths(&path).map(|dir| dir.join(NODE)).collect()
+}
+
+fn volta_candidates() -> Vec<PathBuf> {
+    let root = env_dir("VOLTA_HOME").unwrap_or_else(|| {
+        if cfg!(windows) {
+            dirs::data_local_dir().unwrap_or_default().join("Volta")
+        } else {
+            home().join(".volta")
+        }
+    });
+    vec![root.join("bin").join(NODE)]
+}
+
+/// fnm's default alias, then (version, path) of its installs
+fn fnm_candidates() -> (Vec<PathBuf>, Vec<(Version, PathBuf)>) {
+    let roots: Vec<PathBuf> = match env_dir("FNM_DIR") {
+        Some(dir) => vec![dir],
+        None => vec![
+            dirs::data_dir().unwrap_or_default().join("fnm"),
+            home().join(".local").join("share").join("fnm"),
+            home().join(".fnm"),
+        ],
+    };
+    let mut aliases = Vec::new();
+    let mut versions = Vec::new();
+    for root in roots {
+        aliases.push(versioned_binary(&root.join("aliases").join("default")));
+        for (version, dir) in installed_versions(&root.join("node-versions")) {
+            versions.push((version, versioned_binary(&dir.join("installation"))));
+        }
+    }
+    (aliases, versions)
+}
+
+/// (version, path) of nvm's installs; nvm-windows keeps them in $NVM_HOME
+fn nvm_candidates() -> Vec<(Version, PathBuf)> {
+    let dir = if cfg!(windows) {
+        env_dir("NVM_HOME").unwrap_or_else(|| dirs::data_dir().unwrap_or_default().join("nvm"))
+    } else {
+        env_dir("NVM_DIR").unwrap_or_else(|| home().join(".nvm")).join("versions").join("node")
+    };
+    installed_versions(&dir)
+        .into_iter()
+        .map(|(version, dir)| (version, versioned_binary(&dir)))
+        .collect()
+}
+
+fn system_candidates() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        let mut dirs: Vec<PathBuf> = env_dir("NVM_SYMLINK").into_iter().collect();
+        dirs.extend(["ProgramFiles", "ProgramFiles(x86)"].iter().filter_map(|name| env_dir(name)).map(|dir| dir.join("nodejs")));
+        dirs.push(PathBuf::from("C:\\Program Files\\nodejs"));
+        dirs.into_iter().map(|dir| dir.join(NODE)).collect()
+    } else {
+        ["/opt/homebrew/bin", "/usr/local/bin", "/usr/bin", "/opt/local/bin", "/snap/bin"]
+            .iter()
+            .map(|dir| Path::new(dir).join(NODE))
+            .collect()
+    }
+}
+
+/// Every node binary that exists, in lookup order, with its version when
+/// known without running it
+fn candidates() -> Vec<(PathBuf, NodeSource, Option<Version>)> {
+    let (fnm_aliases, fnm_versions) = fnm_candidates();
+    let mut found: Vec<(PathBuf, NodeSource, Option<Version>)> = Vec::new();
+    found.extend(path_candidates().into_iter().map(|path| (path, NodeSource::Path, None)));
+    found.extend(volta_candidates().into_iter().map(|path| (path, NodeSource::Volta, None)));
+    found.extend(fnm_aliases.into_iter().map(|path| (path, NodeSource::Fnm, None)));
+    found.extend(nvm_candidates().into_iter().map(|(version, path)| (path, NodeSource::Nvm, Some(version))));
+    found.extend(fnm_versions.into_iter().map(|(version, path)| (path, NodeSource::Fnm, Some(version))));
+    found.extend(system_candidates().into_iter().map(|path| (path, NodeSource::System, None)));
+
+    let mut seen = Vec::new();
+    found
+        .into_iter()
+        .filter(|(path, _, _)| path.is_file())
+        .filter(|(path, _, _)| {
+            let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+            let new = !seen.contains(&key);
+            seen.push(key);
+            new
+        })
+        .collect()
+}
+
+/// Run `--version`; None if the binary is broken
+fn probe(path: &Path) -> Option<Version> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Which version managers the machine has, for the suggestions
+fn managers() -> Vec<NodeSource> {
+    let mut managers = Vec::new();
+    if volta_candidates().iter().any(|path| path.is_file()) {
+        managers.push(NodeSource::Volta);
+    }
+    let (aliases, versions) = fnm_candidates();
+    if !versions.is_empty() || aliases.iter().any(|path| path.is_file()) {
+        managers.push(NodeSource::Fnm);
+    }
+    if !nvm_candidates().is_empty() || env_dir("NVM_DIR").is_some() || env_dir("NVM_HOME").is_some() {
+        managers.push(NodeSource::Nvm);
+    }
+    managers
+}
+
+fn suggestions(status: NodeStatus, found: Option<&NodeCandidate>, required: Version) -> Vec<String> {
+    let required_text = format_version(required);
+    let major = required.0;
+    let mut suggestions = Vec::new();
+    match (status, found) {
+        (NodeStatus::Ok, _) => return suggestions,
+        (NodeStatus::TooOld, Some(found)) => suggestions.push(format!(
+            "Node.js {} at {} is older than the {} helix-runtime requires; upgrade it",
+            found.version.as_deref().unwrap_or("?"),
+            found.path,
+            required_text
+        )),
+        _ => suggestions.push(format!("Install Node.js {} or newer from https://nodejs.org", required_text)),
+    }
+    for manager in managers() {
+        suggestions.push(match manager {
+            NodeSource::Volta => format!("With Volta: volta install node@{}", major),
+            NodeSource::Fnm => format!("With fnm: fnm install {} && fnm default {}", major, major),
+            NodeSource::Nvm if cfg!(windows) => format!("With nvm-windows: nvm install {} && nvm use {}", major, major),
+            NodeSource::Nvm => format!("With nvm: nvm install {} && nvm alias default {}", major, major),
+            NodeSource::Path | NodeSource::System => continue,
+        });
+    }
+    suggestions.push("Restart Helix after installing so it finds the new node".to_string());
+    suggestions
+}
+
+fn detect() -> NodeEnvironment {
+    let required = required_version();
+    let mut probed = Vec::new();
+    let mut chosen: Option<(usize, Version)> = None;
+    let mut newest: Option<(usize, Version)> = None;
+    for (path, source, known) in candidates() {
+        let version = known.or_else(|| probe(&path));
+        probed.push(NodeCandidate {
+            path: path.to_string_lossy().to_string(),
+            source,
+            version: version.map(format_version),
+        });
+        let Some(version) = version else {
+            continue;
+        };
+        let index = probed.len() - 1;
+        if version >= required {
+            chosen = Some((index, version));
+            break;
+        }
+        if newest.is_none_or(|(_, best)| version > best) {
+            newest = Some((index, version));
+        }
+    }
+
+    let status = match (chosen, newest) {
+        (Some(_), _) => NodeStatus::Ok,
+        (None, Some(_)) => NodeStatus::TooOld,
+        (None, None) => NodeStatus::Missing,
+    };
+    let found = chosen.or(newest).map(|(index, _)| probed[index].clone());
+    let npm_path = found
+        .as_ref()
+        .and_then(|found| Path::new(&found.path).parent().map(|dir| dir.join(NPM)))
+        .filter(|npm| npm.is_file())
+        .map(|npm| npm.to_string_lossy().to_string());
+    NodeEnvironment {
+        status,
+        suggestions: suggestions(status, found.as_ref(), required),
+        path: found.as_ref().map(|found| found.path.clone()),
+        version: found.as_ref().and_then(|found| found.version.clone()),
+        source: found.as_ref().map(|found| found.source),
+        npm_path,
+        required: format!(">={}", format_version(required)),
+        candidates: probed,
+    }
+}
+
+/// The node environment. Cached; `refresh` detects it again (after
+/// installing or upgrading node).
+pub fn environment(refresh: bool) -> NodeEnvironment {
+    if !refresh {
+        if let Some(cached) = DETECTED.lock().ok().and_then(|detected| detected.clone()) {
+            if cached.path.as_deref().is_some_and(|path| Path::new(path).is_file()) {
+                return cached;
+            }
+        }
+    }
+
+    let environment = detect();
+    match (&environment.status, &environment.path) {
+        (NodeStatus::Ok, Some(path)) => {
+            log::info!("Using Node.js {:?} at {} ({:?})", environment.version, path, environment.source)
+        }
+        (status, path) => log::warn!("No usable Node.js ({:?}, found {:?})", status, path),
+    }
+    if let Ok(mut detected) = DETECTED.lock() {
+        *detected = Some(environment.clone());
+    }
+    environment
+}
+
+/// Node to start the gateway with; Err explains what's wrong and how to
+/// fix it
+pub fn require() -> Result<PathBuf, CommandError> {
+    let environment = environment(false);
+    if environment.status == NodeStatus::Ok {
+        if let Some(path) = &environment.path {
+            return Ok(PathBuf::from(path));
+        }
+    }
+
+    let (code, message) = match environment.status {
+        NodeStatus::TooOld => (
+            ErrorCode::Unsupported,
+            format!(
+                "The gateway needs Node.js {}, but the newest found is {}",
+                environment.required,
+                environment.version.as_deref().unwrap_or("unknown")
+            ),
+        ),
+        _ => (
+            ErrorCode::NotFound,
+            format!("The gateway needs Node.js {}, and none was found", environment.required),
+        ),
+    };
+    Err(CommandError::new(code, message).with_details(serde_json::json!({
+        "reason": "node",
+        "action": "install_node",
+        "node": environment,
+    })))
+}
+
+/// PATH with the detected node's directory first, for child processes
+/// (the gateway, npm, pnpm) that look node up themselves
+pub fn search_path() -> Option<OsString> {
+    let environment = environment(false);
+    let dir = PathBuf::from(environment.path?).parent()?.to_path_buf();
+    let mut dirs = vec![dir];
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+    std::env::join_paths(dirs).ok()
+}
+
+// Tauri commands
+
+/// Where node was found, whether it's new enough, and what to do if not.
+/// `refresh` skips the cached result.
+#[tauri::command]
+pub async fn get_node_environment(refresh: Option<bool>) -> Result<NodeEnvironment, CommandError> {
+    // Probes every candidate with `node --version`
+    tauri::async_runtime::spawn_blocking(move || environment(refresh.unwrap_or(false)))
+        .await
+        .map_err(|e| CommandError::internal(format!("Node detection failed: {}", e)))
+}
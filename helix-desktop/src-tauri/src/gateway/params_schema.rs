@@ -0,0 +1,202 @@
+// Params schema - the JSON Schema subset gateway actions declare params in
+//
+// Schemas come from agents and skills, so only keywords that both a
+// parameter form and this validator understand are accepted: `type` (a
+// name or a list of names), `enum`, `const`, object `properties`,
+// `required` and `additionalProperties`, array `items`, `minItems` and
+// `maxItems`, string `minLength` and `maxLength`, number `minimum` and
+// `maximum`, plus the annotations `title`, `description`, `default` and
+// `examples`. A schema using anything else is refused when it is declared,
+// rather than its constraints being skipped at dispatch.
+
+use serde_json::Value;
+
+const KEYWORDS: &[&str] = &[
+    "$schema",
+    "type",
+    "enum",
+    "const",
+    "properties",
+    "required",
+    "additionalProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "title",
+    "description",
+    "default",
+    "examples",
+];
+
+const TYPES: &[&str] = &["object", "array", "string", "number", "integer", "boolean", "null"];
+
+/// Check that `schema` only uses the supported subset
+pub fn check(schema: &Value) -> Result<(), String> {
+    check_at(schema, "params")
+}
+
+fn check_at(schema: &Value, path: &str) -> Result<(), String> {
+    let map = schema
+        .as_object()
+        .ok_or_else(|| format!("{}: a schema must be an object", path))?;
+
+    for (keyword, value) in map {
+        let invalid = || format!("{}: invalid {}", path, keyword);
+        match keyword.as_str() {
+            "type" => {
+                let names = match value {
+                    Value::Array(names) => names.iter().collect(),
+                    name => vec![name],
+                };
+                if names.is_empty() || !names.iter().all(|n| n.as_str().is_some_and(|n| TYPES.contains(&n))) {
+                    return Err(invalid());
+                }
+            }
+            "enum" | "examples" if !value.is_array() => return Err(invalid()),
+            "properties" => {
+                let properties = value.as_object().ok_or_else(invalid)?;
+                for (name, property) in properties {
+                    check_at(property, &format!("{}.{}", path, name))?;
+                }
+            }
+            "required" => {
+                let names = value.as_array().ok_or_else(invalid)?;
+                if !names.iter().all(Value::is_string) {
+                    return Err(invalid());
+                }
+            }
+            "additionalProperties" => match value {
+                Value::Bool(_) => {}
+                schema => check_at(schema, &format!("{}.*", path))?,
+            },
+            "items" => check_at(value, &format!("{}[]", path))?,
+            "minItems" | "maxItems" | "minLength" | "maxLength" if value.as_u64().is_none() => return Err(invalid()),
+            "minimum" | "maximum" if !value.is_number() => return Err(invalid()),
+            other if !KEYWORDS.contains(&other) => {
+                return Err(format!("{}: unsupported schema keyword {}", path, other));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// What is wrong with `value` under `schema`; empty when it is valid
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "params", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(map) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(types) = map.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            name => name.as_str().into_iter().collect(),
+        };
+        if !allowed.iter().any(|name| has_type(value, name)) {
+            errors.push(format!("{}: expected {}, got {}", path, allowed.join(" or "), type_name(value)));
+            return;
+        }
+    }
+    if let Some(Value::Array(options)) = map.get("enum") {
+        if !options.contains(value) {
+            errors.push(format!("{}: must be one of {}", path, Value::Array(options.clone())));
+        }
+    }
+    if let Some(expected) = map.get("const") {
+        if expected != value {
+            errors.push(format!("{}: must be {}", path, expected));
+        }
+    }
+
+    let bound = |keyword: &str| map.get(keyword).and_then(Value::as_f64);
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = map.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        errors.push(format!("{}.{}: required", path, name));
+                    }
+                }
+            }
+            let properties = map.get("properties").and_then(Value::as_object);
+            for (name, item) in object {
+                let item_path = format!("{}.{}", path, name);
+                match (properties.and_then(|p| p.get(name)), map.get("additionalProperties")) {
+                    (Some(property), _) => validate_at(property, item, &item_path, errors),
+                    (None, Some(Value::Bool(false))) => {
+                        errors.push(format!("{}: not an accepted parameter", item_path))
+                    }
+                    (None, Some(additional)) => validate_at(additional, item, &item_path, errors),
+                    (None, None) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            let count = items.len() as f64;
+            if bound("minItems").is_some_and(|min| count < min) {
+                errors.push(format!("{}: needs at least {} items", path, map["minItems"]));
+            }
+            if bound("maxItems").is_some_and(|max| count > max) {
+                errors.push(format!("{}: takes at most {} items", path, map["maxItems"]));
+            }
+            if let Some(item_schema) = map.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}[{}]", path, index), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as f64;
+            if bound("minLength").is_some_and(|min| length < min) {
+                errors.push(format!("{}: needs at least {} characters", path, map["minLength"]));
+            }
+            if bound("maxLength").is_some_and(|max| length > max) {
+                errors.push(format!("{}: takes at most {} characters", path, map["maxLength"]));
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if bound("minimum").is_some_and(|min| number < min) {
+                errors.push(format!("{}: must be at least {}", path, map["minimum"]));
+            }
+            if bound("maximum").is_some_and(|max| number > max) {
+                errors.push(format!("{}: must be at most {}", path, map["maximum"]));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
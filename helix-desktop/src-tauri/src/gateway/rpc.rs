@@ -0,0 +1,185 @@
+// Gateway RPC client - minimal WebSocket client for the OpenClaw gateway
+// protocol (req/res/event frames), used by backend pollers and, shared,
+// by gateway/client.rs. The frontend keeps its own client in
+// src/lib/gateway-client.ts.
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// Protocol version spoken by this client (matches gateway-client.ts)
+const PROTOCOL_VERSION: u32 = 3;
+/// How long to wait for the connect.challenge nonce before connecting anyway
+const CHALLENGE_WAIT: Duration = Duration::from_millis(750);
+/// Limit for the handshake as a whole
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A frame received from the gateway
+#[derive(Debug)]
+pub enum Frame {
+    Response {
+        id: String,
+        ok: bool,
+        payload: Value,
+        error: Option<String>,
+    },
+    Event {
+        event: String,
+        payload: Value,
+    },
+}
+
+/// An authenticated gateway connection, with read-only operator scope
+/// unless connected with others
+pub struct GatewayRpc {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    /// Payload of the connect response (hello-ok)
+    hello: Value,
+}
+
+impl GatewayRpc {
+    /// Connect and complete the handshake
+    pub async fn connect(url: &str, token: Option<String>) -> Result<Self, String> {
+        Self::connect_with_scopes(url, token, &["operator.read"]).await
+    }
+
+    /// Connect with other operator scopes than read-only
+    pub async fn connect_with_scopes(url: &str, token: Option<String>, scopes: &[&str]) -> Result<Self, String> {
+        tokio::time::timeout(CONNECT_TIMEOUT, Self::handshake(url, token, scopes))
+            .await
+            .map_err(|_| "Timed out connecting to gateway".to_string())?
+    }
+
+    async fn handshake(url: &str, token: Option<String>, scopes: &[&str]) -> Result<Self, String> {
+        let (ws, _) = connect_async(url)
+            .await
+            .map_err(|e| format!("Failed to connect to gateway: {}", e))?;
+        let mut rpc = Self {
+            ws,
+            next_id: 0,
+            hello: Value::Null,
+        };
+
+        // The gateway may send a nonce to bind the connect request to
+        let nonce = match tokio::time::timeout(CHALLENGE_WAIT, rpc.next_frame()).await {
+            Ok(Ok(Some(Frame::Event { event, payload }))) if event == "connect.challenge" => {
+                payload.get("nonce").and_then(Value::as_str).map(str::to_string)
+            }
+            _ => None,
+        };
+
+        let mut params = json!({
+            "minProtocol": PROTOCOL_VERSION,
+            "maxProtocol": PROTOCOL_VERSION,
+            "client": {
+                "id": "gateway-client",
+                "displayName": "Helix Desktop",
+                "version": env!("CARGO_PKG_VERSION"),
+                "platform": std::env::consts::OS,
+                "mode": "backend",
+            },
+            "role": "operator",
+            "scopes": scopes,
+        });
+        if let Some(token) = token {
+            params["auth"] = json!({ "token": token });
+        }
+        if let Some(nonce) = nonce {
+            params["nonce"] = Value::String(nonce);
+        }
+
+        rpc.hello = rpc.request("connect", params).await?;
+        Ok(rpc)
+    }
+
+    /// The gateway's answer to the handshake: protocol, server version,
+    /// features and policy
+    pub fn hello(&self) -> &Value {
+        &self.hello
+    }
+
+    /// Send a request without waiting; returns its id
+    pub async fn send(&mut self, method: &str, params: Value) -> Result<String, String> {
+        self.next_id += 1;
+        let id = format!("helix-desktop-{}", self.next_id);
+        let frame = json!({ "type": "req", "id": id, "method": method, "params": params });
+
+        self.ws
+            .send(Message::Text(frame.to_string()))
+            .await
+            .map_err(|e| format!("Failed to send {} request: {}", method, e))?;
+        Ok(id)
+    }
+
+    /// Send a request and wait for its response. Events arriving in the
+    /// meantime are dropped.
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let request_id = self.send(method, params).await?;
+
+        loop {
+            match self.next_frame().await? {
+                Some(Frame::Response { id, ok, payload, error }) if id == request_id => {
+                    return if ok {
+                        Ok(payload)
+                    } else {
+                        Err(format!(
+                            "Gateway rejected {}: {}",
+                            method,
+                            error.unwrap_or_else(|| "unknown error".to_string())
+                        ))
+                    };
+                }
+                Some(_) => {}
+                None => return Err("Gateway closed the connection".to_string()),
+            }
+        }
+    }
+
+    /// Next request response or event; `None` once the connection closes.
+    /// Cancel-safe, so it can be raced against timers.
+    pub async fn next_frame(&mut self) -> Result<Option<Frame>, String> {
+        loop {
+            let message = match self.ws.next().await {
+                Some(Ok(message)) => message,
+                Some(Err(e)) => return Err(format!("Gateway connection error: {}", e)),
+                None => return Ok(None),
+            };
+
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => return Ok(None),
+                // Pings are answered by tungstenite; nothing else is expected
+                _ => continue,
+            };
+
+            if let Some(frame) = parse_frame(&text) {
+                return Ok(Some(frame));
+            }
+        }
+    }
+}
+
+fn parse_frame(text: &str) -> Option<Frame> {
+    let value: Value = serde_json::from_str(text).ok()?;
+
+    match value.get("type")?.as_str()? {
+        "res" => Some(Frame::Response {
+            id: value.get("id")?.as_str()?.to_string(),
+            ok: value.get("ok").and_then(Value::as_bool).unwrap_or(false),
+            payload: value.get("payload").cloned().unwrap_or(Value::Null),
+            error: value
+                .pointer("/error/message")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }),
+        "event" => Some(Frame::Event {
+            event: value.get("event")?.as_str()?.to_string(),
+            payload: value.get("payload").cloned().unwrap_or(Value::Null),
+        }),
+        _ => None,
+    }
+}
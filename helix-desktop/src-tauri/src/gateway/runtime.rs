@@ -0,0 +1,399 @@
+// Helix Desktop - helix-runtime resolution
+//
+// The gateway runs from a helix-runtime checkout (or an OpenClaw install).
+// `gateway.runtime_path` overrides both the lookup and the bundled runtime;
+// without it each strategy in STRATEGIES proposes directories in order and
+// the first containing openclaw.mjs wins. A directory without it is used
+// only when no candidate has one, so bundled and npx installs keep working.
+// A configured path missing openclaw.mjs is an error, not silently replaced.
+//
+// `update_runtime` is the way out of a gateway handshake that finds the
+// runtime too old (gateway::handshake): a git checkout is pulled
+// (fast-forward only), then its dependencies installed and rebuilt with
+// pnpm. A runtime Helix installed itself (gateway::installer) is updated
+// by installing the pinned release again. The runtime bundled with the app
+// is only updated with the app.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::config;
+use crate::commands::{CommandError, ErrorCode};
+
+/// Entry point of a usable runtime
+const ENTRY_FILE: &str = "openclaw.mjs";
+#[cfg(windows)]
+const PNPM: &str = "pnpm.cmd";
+#[cfg(not(windows))]
+const PNPM: &str = "pnpm";
+/// Commands of update_runtime, run in the runtime directory in order
+const UPDATE_STEPS: &[(&str, &[&str])] = &[
+    ("git", &["pull", "--ff-only"]),
+    (PNPM, &["install"]),
+    (PNPM, &["build"]),
+];
+/// Limit for each update step; a cold install and build take minutes
+const UPDATE_STEP_TIMEOUT: Duration = Duration::from_secs(600);
+/// Lines of output kept in the error of a failed step
+const UPDATE_OUTPUT_LINES: usize = 20;
+
+/// Set while update_runtime runs
+static UPDATING: AtomicBool = AtomicBool::new(false);
+
+/// Where a runtime candidate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeSource {
+    /// `gateway.runtime_path`
+    Configured,
+    /// Sibling of the desktop checkout the executable was built in
+    ExeRelative,
+    /// Sibling of the crate, for `cargo run`
+    Manifest,
+    /// ~/.helix/helix-runtime
+    HelixHome,
+    /// ~/.openclaw
+    OpenclawHome,
+}
+
+/// Result of update_runtime
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeUpdate {
+    pub path: String,
+    /// package.json version before and after
+    pub previous_version: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Payload of runtime:update-progress, sent as each step starts
+#[derive(Debug, Clone, Serialize)]
+struct UpdateProgress {
+    step: usize,
+    total: usize,
+    command: String,
+}
+
+/// A way of proposing runtime directories
+struct Strategy {
+    source: RuntimeSource,
+    candidates: fn() -> Vec<PathBuf>,
+}
+
+/// Tried in order when no runtime path is configured
+const STRATEGIES: &[Strategy] = &[
+    Strategy { source: RuntimeSource::ExeRelative, candidates: exe_relative },
+    Strategy { source: RuntimeSource::Manifest, candidates: manifest_relative },
+    Strategy { source: RuntimeSource::HelixHome, candidates: helix_home },
+    Strategy { source: RuntimeSource::OpenclawHome, candidates: openclaw_home },
+];
+
+/// A directory considered for the runtime, as returned by detect_runtime_paths
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeCandidate {
+    pub path: String,
+    pub source: RuntimeSource,
+    pub exists: bool,
+    /// Contains openclaw.mjs
+    pub valid: bool,
+    /// The one the gateway is started from
+    pub selected: bool,
+}
+
+/// Release binary at helix-desktop/src-tauri/target/release/: four levels
+/// up is the Helix root; some layouts put helix-runtime two levels up
+fn exe_relative() -> Vec<PathBuf> {
+    let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) else {
+        return Vec::new();
+    };
+    vec![
+        exe_dir.join("..").join("..").join("..").join("..").join("helix-runtime"),
+        exe_dir.join("..").join("..").join("helix-runtime"),
+    ]
+}
+
+fn manifest_relative() -> Vec<PathBuf> {
+    std::env::var("CARGO_MANIFEST_DIR")
+        .map(|dir| vec![PathBuf::from(dir).join("..").join("..").join("helix-runtime")])
+        .unwrap_or_default()
+}
+
+fn helix_home() -> Vec<PathBuf> {
+    super::installer::install_dir().map(|dir| vec![dir]).unwrap_or_default()
+}
+
+fn openclaw_home() -> Vec<PathBuf> {
+    dirs::home_dir().map(|home| vec![home.join(".openclaw")]).unwrap_or_default()
+}
+
+pub(super) fn is_valid(dir: &Path) -> bool {
+    dir.join(ENTRY_FILE).is_file()
+}
+
+/// Whether the gateway can be started from `dir`: it has openclaw.mjs, or
+/// an npm-installed openclaw
+pub(super) fn is_usable(dir: &Path) -> bool {
+    #[cfg(windows)]
+    let binary = "openclaw.cmd";
+    #[cfg(not(windows))]
+    let binary = "openclaw";
+    is_valid(dir) || dir.join("node_modules").join(".bin").join(binary).exists()
+}
+
+/// Absolute form of an existing directory, as given otherwise
+fn normalize(dir: PathBuf) -> PathBuf {
+    dir.canonicalize().unwrap_or(dir)
+}
+
+fn configured_path() -> Option<PathBuf> {
+    config::get_config()
+        .ok()
+        .and_then(|config| config.gateway.runtime_path)
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Whether `gateway.runtime_path` overrides detection, and the bundled runtime
+pub fn is_configured() -> bool {
+    configured_path().is_some()
+}
+
+/// Candidates of every strategy, deduplicated, in lookup order
+fn detected() -> Vec<(RuntimeSource, PathBuf)> {
+    let mut seen = Vec::new();
+    let mut found = Vec::new();
+    for strategy in STRATEGIES {
+        for dir in (strategy.candidates)() {
+            let dir = normalize(dir);
+            if !seen.contains(&dir) {
+                seen.push(dir.clone());
+                found.push((strategy.source, dir));
+            }
+        }
+    }
+    found
+}
+
+/// Directory the gateway is started from
+pub fn runtime_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = configured_path() {
+        if !is_valid(&dir) {
+            return Err(format!(
+                "Configured runtime path {} has no {}; fix or clear it in the gateway settings",
+                dir.display(),
+                ENTRY_FILE
+            ));
+        }
+        return Ok(normalize(dir));
+    }
+
+    let candidates = detected();
+    if let Some((source, dir)) = candidates.iter().find(|(_, dir)| is_valid(dir)) {
+        log::info!("Found helix-runtime at ({:?}): {:?}", source, dir);
+        return Ok(dir.clone());
+    }
+    if let Some((source, dir)) = candidates.iter().find(|(_, dir)| dir.is_dir()) {
+        log::info!("Using runtime directory without {} ({:?}): {:?}", ENTRY_FILE, source, dir);
+        return Ok(dir.clone());
+    }
+
+    log::warn!("Could not find helix-runtime directory");
+    Err("Could not find helix-runtime directory; set its path in the gateway settings".to_string())
+}
+
+/// Version in the runtime's package.json
+pub fn runtime_version(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+    package.get("version")?.as_str().map(str::to_string)
+}
+
+/// Run one update step in `dir`; Err carries the tail of its output
+pub(super) async fn run_update_step(dir: &Path, program: &str, args: &[&str]) -> Result<(), CommandError> {
+    let command = format!("{} {}", program, args.join(" "));
+    let child = tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .envs(super::node::search_path().map(|path| ("PATH", path)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| CommandError::process(format!("Failed to run {}: {}", command, e)))?;
+    let output = tokio::time::timeout(UPDATE_STEP_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            CommandError::new(
+                ErrorCode::Timeout,
+                format!("{} did not finish within {}s", command, UPDATE_STEP_TIMEOUT.as_secs()),
+            )
+        })?
+        .map_err(|e| CommandError::process(format!("Failed to run {}: {}", command, e)))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+    let tail = lines[lines.len().saturating_sub(UPDATE_OUTPUT_LINES)..].to_vec();
+    Err(CommandError::process(format!("{} failed ({})", command, output.status)).with_details(serde_json::json!({
+        "command": command,
+        "exit_code": output.status.code(),
+        "output": tail,
+    })))
+}
+
+// Tauri commands
+
+/// The configured runtime path and every detected candidate, marking the
+/// one the gateway is started from
+#[tauri::command]
+pub fn detect_runtime_paths() -> Result<Vec<RuntimeCandidate>, CommandError> {
+    let selected = runtime_dir().ok();
+    let mut candidates: Vec<(RuntimeSource, PathBuf)> = configured_path()
+        .map(|dir| (RuntimeSource::Configured, normalize(dir)))
+        .into_iter()
+        .collect();
+    for (source, dir) in detected() {
+        if !candidates.iter().any(|(_, known)| *known == dir) {
+            candidates.push((source, dir));
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .map(|(source, dir)| RuntimeCandidate {
+            path: dir.to_string_lossy().to_string(),
+            source,
+            exists: dir.is_dir(),
+            valid: is_valid(&dir),
+            selected: selected.as_ref() == Some(&dir),
+        })
+        .collect())
+}
+
+/// Use `path` as the runtime directory, or go back to detection with
+/// `None`. A running gateway switches when restarted; the restart is
+/// flagged as pending.
+#[tauri::command]
+pub fn set_runtime_path(app: AppHandle, path: Option<String>) -> Result<Option<String>, CommandError> {
+    let path = match path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty()) {
+        Some(path) => {
+            let dir = PathBuf::from(&path);
+            if !dir.is_dir() {
+                return Err(CommandError::not_found(format!("Runtime directory {} does not exist", path)));
+            }
+            if !is_valid(&dir) {
+                return Err(CommandError::invalid_input(format!(
+                    "{} is not a helix-runtime directory: it has no {}",
+                    path, ENTRY_FILE
+                )));
+            }
+            Some(normalize(dir).to_string_lossy().to_string())
+        }
+        None => None,
+    };
+
+    let mut config = config::get_config()?;
+    if config.gateway.runtime_path == path {
+        return Ok(path);
+    }
+    config.gateway.runtime_path = path.clone();
+    config::set_config(config)?;
+    match &path {
+        Some(path) => log::info!("Gateway runtime path set to {}", path),
+        None => log::info!("Gateway runtime path cleared, detecting it again"),
+    }
+
+    let running = crate::commands::gateway::gateway_status(None).is_ok_and(|status| status.running && !status.remote);
+    if running {
+        super::config_sync::set_restart_pending(&app, true);
+    }
+    Ok(path)
+}
+
+/// Update the helix-runtime checkout the gateway is started from: pull,
+/// install and build, reporting each step as runtime:update-progress. A
+/// managed install gets the pinned release instead, as a runtime-install
+/// task. A running gateway picks the update up when restarted; the restart
+/// is flagged as pending.
+#[tauri::command]
+pub async fn update_runtime(app: AppHandle) -> Result<RuntimeUpdate, CommandError> {
+    if crate::commands::gateway::bundled_openclaw(&app).is_some() {
+        return Err(CommandError::new(
+            ErrorCode::Unsupported,
+            "The bundled helix-runtime is updated with Helix; install the latest Helix update",
+        )
+        .with_details(serde_json::json!({ "action": "update_helix" })));
+    }
+    let dir = runtime_dir()?;
+    if super::installer::is_managed(&dir) {
+        let previous_version = runtime_version(&dir);
+        let state = app.state::<crate::AppState>();
+        let body_app = app.clone();
+        state
+            .task_manager
+            .run(app.clone(), super::installer::TASK_KIND, Some(super::installer::INSTALL_TIMEOUT), |task| async move {
+                super::installer::install(&body_app, &task, false).await
+            })
+            .await?;
+        return Ok(RuntimeUpdate {
+            path: dir.to_string_lossy().to_string(),
+            previous_version,
+            version: runtime_version(&dir),
+        });
+    }
+    if !dir.ancestors().any(|ancestor| ancestor.join(".git").exists()) {
+        return Err(CommandError::new(
+            ErrorCode::Unsupported,
+            format!("{} is not a git checkout; update it the way it was installed", dir.display()),
+        ));
+    }
+    if UPDATING.swap(true, Ordering::SeqCst) {
+        return Err(CommandError::new(ErrorCode::Conflict, "helix-runtime is already being updated"));
+    }
+
+    let previous_version = runtime_version(&dir);
+    log::info!("Updating helix-runtime at {:?} (version {:?})", dir, previous_version);
+    let mut result = Ok(());
+    for (index, (program, args)) in UPDATE_STEPS.iter().enumerate() {
+        let _ = app.emit(
+            "runtime:update-progress",
+            UpdateProgress {
+                step: index + 1,
+                total: UPDATE_STEPS.len(),
+                command: format!("{} {}", program, args.join(" ")),
+            },
+        );
+        result = run_update_step(&dir, program, args).await;
+        if result.is_err() {
+            break;
+        }
+    }
+    UPDATING.store(false, Ordering::SeqCst);
+    if let Err(e) = result {
+        log::warn!("helix-runtime update failed: {}", e.message);
+        return Err(e);
+    }
+
+    let version = runtime_version(&dir);
+    log::info!("helix-runtime updated to {:?}", version);
+    let running = crate::commands::gateway::gateway_status(None).is_ok_and(|status| status.running && !status.remote);
+    if running {
+        super::config_sync::set_restart_pending(&app, true);
+    }
+    Ok(RuntimeUpdate {
+        path: dir.to_string_lossy().to_string(),
+        previous_version,
+        version,
+    })
+}
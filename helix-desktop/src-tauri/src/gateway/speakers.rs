@@ -0,0 +1,102 @@
+// Voice speakers - enrolling household members for speaker verification
+//
+// The gateway keeps the voice profiles and checks sensitive voice commands
+// against them (helix/voice/speaker-verification.ts in helix-runtime); the
+// desktop records the samples and sends them with `voice.speakers.enroll`
+// over the shared gateway connection (client.rs). Samples are base64 16-bit
+// mono PCM at 16 kHz, the rate the voice pipeline records at. A request
+// must fit in one gateway frame, so the samples are capped here before they
+// are sent. Voiceprints never come back: the gateway lists names and sample
+// counts only.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::client;
+use crate::commands::CommandError;
+
+/// Rate the samples are recorded at
+const SAMPLE_RATE: u32 = 16_000;
+/// Samples one enrollment may send
+const MAX_SAMPLES: usize = 5;
+/// Base64 bytes of all samples together; the gateway takes frames of 512 KiB
+const MAX_ENROLLMENT_BYTES: usize = 448 * 1024;
+
+/// An enrolled voice profile, as the gateway lists it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceSpeaker {
+    pub id: String,
+    pub name: String,
+    /// Samples the profile was enrolled from
+    pub samples: u32,
+    pub enrolled_at: String,
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(value: Value, field: &str) -> Result<T, CommandError> {
+    serde_json::from_value(value.get(field).cloned().unwrap_or(Value::Null))
+        .map_err(|e| CommandError::internal(format!("Unexpected voice.speakers reply: {}", e)))
+}
+
+fn check_samples(samples: &[String]) -> Result<(), CommandError> {
+    if samples.is_empty() || samples.len() > MAX_SAMPLES {
+        return Err(CommandError::invalid_input(format!(
+            "Enrollment takes 1 to {} samples, got {}",
+            MAX_SAMPLES,
+            samples.len()
+        )));
+    }
+    let total: usize = samples.iter().map(String::len).sum();
+    if total > MAX_ENROLLMENT_BYTES {
+        return Err(CommandError::invalid_input("Enrollment samples are too long; record shorter phrases"));
+    }
+    for (index, sample) in samples.iter().enumerate() {
+        let pcm = STANDARD
+            .decode(sample)
+            .map_err(|e| CommandError::invalid_input(format!("Sample {} is not base64: {}", index + 1, e)))?;
+        if pcm.is_empty() || pcm.len() % 2 != 0 {
+            return Err(CommandError::invalid_input(format!("Sample {} is not 16-bit audio", index + 1)));
+        }
+    }
+    Ok(())
+}
+
+// Tauri commands
+
+/// Voice profiles enrolled on the gateway
+#[tauri::command]
+pub async fn list_voice_speakers() -> Result<Vec<VoiceSpeaker>, CommandError> {
+    let reply = client::call("voice.speakers.list", json!({})).await.map_err(CommandError::network)?;
+    parse(reply, "profiles")
+}
+
+/// Enroll `name` from recorded samples (base64 16-bit mono PCM at 16 kHz),
+/// replacing an earlier profile of the same name
+#[tauri::command]
+pub async fn enroll_voice_speaker(name: String, samples: Vec<String>) -> Result<VoiceSpeaker, CommandError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(CommandError::invalid_input("A name is required to enroll a voice"));
+    }
+    check_samples(&samples)?;
+
+    let count = samples.len();
+    let samples: Vec<Value> = samples
+        .into_iter()
+        .map(|pcm| json!({ "pcm": pcm, "sampleRate": SAMPLE_RATE }))
+        .collect();
+    let reply = client::call("voice.speakers.enroll", json!({ "name": name, "samples": samples }))
+        .await
+        .map_err(CommandError::network)?;
+    log::info!("Enrolled a voice profile from {} samples", count);
+    parse(reply, "profile")
+}
+
+/// Remove an enrolled voice profile; false when there was none
+#[tauri::command]
+pub async fn remove_voice_speaker(id: String) -> Result<bool, CommandError> {
+    let reply = client::call("voice.speakers.remove", json!({ "id": id })).await.map_err(CommandError::network)?;
+    parse(reply, "removed")
+}
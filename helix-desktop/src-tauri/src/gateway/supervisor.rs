@@ -0,0 +1,161 @@
+// Gateway supervisor
+//
+// Nothing waits on the spawned gateways, so without this a crashed process
+// would still be reported as running. Every few seconds each instance's
+// child is polled with try_wait; when one has exited on its own,
+// `gateway:crashed` is emitted and it is relaunched according to the
+// `gateway` config section, with restarts counted per instance. Hangs (a
+// live process that stops answering) are the monitor's job.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{config, gateway as gateway_commands};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RELAUNCH_BASE_DELAY: Duration = Duration::from_secs(1);
+const RELAUNCH_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Payload for gateway:crashed event
+#[derive(Debug, Clone, Serialize)]
+struct CrashedPayload {
+    instance: String,
+    exit_code: Option<i32>,
+    /// Terminating signal on Unix
+    signal: Option<i32>,
+    /// Whether a relaunch follows
+    relaunching: bool,
+    /// Relaunches in the current window, including this one
+    restarts: u32,
+    max_restarts: u32,
+    timestamp: u64,
+}
+
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+fn should_relaunch(policy: &str, status: &ExitStatus) -> bool {
+    match policy {
+        "always" => true,
+        "never" => false,
+        _ => !status.success(),
+    }
+}
+
+/// Poll the gateway processes for the lifetime of the app
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut relaunches_by_instance: HashMap<String, VecDeque<Instant>> = HashMap::new();
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            for (instance, status) in gateway_commands::reap_exited() {
+                let relaunches = relaunches_by_instance.entry(instance.clone()).or_default();
+                supervise_exit(&app, instance, status, relaunches).await;
+            }
+        }
+    });
+}
+
+/// Report an exited gateway and relaunch it if the policy allows
+async fn supervise_exit(
+    app: &AppHandle,
+    instance: String,
+    status: ExitStatus,
+    relaunches: &mut VecDeque<Instant>,
+) {
+    let name = gateway_commands::display_name(&instance);
+    let settings = config::get_config().map(|c| c.gateway).unwrap_or_default();
+    let window = Duration::from_secs(settings.restart_window_secs);
+    while relaunches.front().is_some_and(|at| at.elapsed() > window) {
+        relaunches.pop_front();
+    }
+
+    let wanted = should_relaunch(&settings.restart_policy, &status);
+    let relaunching = wanted && (relaunches.len() as u32) < settings.max_restarts;
+    let restarts = relaunches.len() as u32 + u32::from(relaunching);
+
+    log::warn!("{} exited unexpectedly: {}", name, status);
+    events::record(
+        ServiceKind::Gateway,
+        "crashed",
+        format!("{} exited unexpectedly ({})", name, status),
+        Some(serde_json::json!({
+            "instance": instance,
+            "exit_code": status.code(),
+            "signal": exit_signal(&status),
+        })),
+    );
+    let _ = app.emit(
+        "gateway:crashed",
+        CrashedPayload {
+            instance: instance.clone(),
+            exit_code: status.code(),
+            signal: exit_signal(&status),
+            relaunching,
+            restarts,
+            max_restarts: settings.max_restarts,
+            timestamp: current_timestamp(),
+        },
+    );
+    crate::accessibility::announce::gateway_crashed(app, &name, relaunching);
+
+    if !relaunching {
+        if wanted {
+            events::record(
+                ServiceKind::Gateway,
+                "restart_gave_up",
+                format!(
+                    "{} crashed {} times in {} minutes; not relaunching",
+                    name,
+                    relaunches.len() + 1,
+                    settings.restart_window_secs / 60
+                ),
+                None,
+            );
+        }
+        return;
+    }
+
+    // 1s, 2s, 4s... for crashes in quick succession
+    let delay = RELAUNCH_BASE_DELAY
+        .saturating_mul(1 << relaunches.len().min(16))
+        .min(RELAUNCH_MAX_DELAY);
+    relaunches.push_back(Instant::now());
+    tokio::time::sleep(delay).await;
+
+    let result = gateway_commands::start_gateway(app.clone(), Some(instance))
+        .await
+        .map_err(|e| e.message);
+    match result {
+        Ok(started) => log::info!("{} relaunched on port {}", name, started.port),
+        Err(e) => {
+            log::warn!("Failed to relaunch {}: {}", name, e);
+            events::record(
+                ServiceKind::Gateway,
+                "restart_failed",
+                format!("{} relaunch failed: {}", name, e),
+                None,
+            );
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Integrations Module (MQTT status and commands for Home Assistant)
+
+pub mod mqtt;
+pub mod mqtt_client;
+pub mod presence;
@@ -0,0 +1,462 @@
+// MQTT bridge - Helix status and commands for Home Assistant
+//
+// With `integrations.mqtt.enabled`, Helix keeps a connection to the broker
+// and publishes its presence (presence.rs) as JSON on
+// `<topic_prefix>/<node_id>/state`: on connect, whenever it changes and
+// every `publish_interval_secs`. `.../availability` says "online" while
+// connected and, through the broker's last will, "offline" otherwise. With
+// `discovery`, Home Assistant discovery configs under `discovery_prefix`
+// turn that into a Helix device: a gateway sensor, a talk mode switch, a
+// do-not-disturb binary sensor and a button per scheduler job; they are
+// published again when Home Assistant comes back online.
+//
+// With `commands` (off by default: anyone who can publish on the broker
+// can then act on this machine), `.../command` accepts `{"action": "toggle_talk_mode"}`
+// and `{"action": "run_job", "job_id": ...}` (or `"job_type"`), and the
+// talk mode switch sends "ON" or "OFF" to `.../talk_mode/set`. Talk mode
+// is toggled the way the tray does it, through the frontend; jobs run as
+// if triggered from the scheduler view. The broker password is read from
+// the keyring (`mqtt_password`) and only sent over TLS, or in the clear to
+// a broker on this machine. Config changes reconnect within CONFIG_POLL.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+use super::mqtt_client::{self, ConnectOptions, Message, MqttWriter};
+use super::presence::{self, Presence};
+use crate::commands::config::{self, MqttConfig};
+use crate::commands::{keyring, scheduler, CommandError};
+
+const KEEP_ALIVE_SECS: u16 = 60;
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// How often config changes are picked up
+const CONFIG_POLL: Duration = Duration::from_secs(15);
+const MIN_PUBLISH_INTERVAL_SECS: u64 = 5;
+const RECONNECT_MIN: Duration = Duration::from_secs(5);
+const RECONNECT_MAX: Duration = Duration::from_secs(300);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static STATUS: LazyLock<Mutex<MqttStatus>> = LazyLock::new(|| Mutex::new(MqttStatus::default()));
+
+/// State of the bridge, also the payload of integrations:mqtt-status
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MqttStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    /// "host:port"
+    pub broker: Option<String>,
+    pub connected_at: Option<u64>,
+    pub last_published: Option<u64>,
+    pub last_command: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// An action received on the command topic
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Command {
+    ToggleTalkMode,
+    RunJob {
+        job_id: Option<String>,
+        job_type: Option<String>,
+    },
+}
+
+/// Topics of one connection
+struct Topics {
+    base: String,
+    availability: String,
+    state: String,
+    command: String,
+    talk_mode_set: String,
+    ha_status: String,
+}
+
+impl Topics {
+    fn new(config: &MqttConfig) -> Self {
+        let base = format!("{}/{}", config.topic_prefix.trim_end_matches('/'), config.node_id);
+        Self {
+            availability: format!("{}/availability", base),
+            state: format!("{}/state", base),
+            command: format!("{}/command", base),
+            talk_mode_set: format!("{}/talk_mode/set", base),
+            ha_status: format!("{}/status", config.discovery_prefix.trim_end_matches('/')),
+            base,
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn update_status(app: &AppHandle, update: impl FnOnce(&mut MqttStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        let before = (status.connected, status.last_error.clone());
+        update(&mut status);
+        if before != (status.connected, status.last_error.clone()) {
+            let _ = app.emit("integrations:mqtt-status", status.clone());
+        }
+    }
+}
+
+fn current_config() -> MqttConfig {
+    config::get_config().map(|c| c.integrations.mqtt).unwrap_or_default()
+}
+
+/// Keep the bridge connected while it is enabled (once per process)
+pub fn start(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = RECONNECT_MIN;
+        loop {
+            let config = current_config();
+            if !config.enabled {
+                update_status(&app, |status| *status = MqttStatus::default());
+                tokio::time::sleep(CONFIG_POLL).await;
+                continue;
+            }
+
+            match run_session(&app, &config).await {
+                // Config changed; reconnect with the new one
+                Ok(()) => backoff = RECONNECT_MIN,
+                Err(e) => {
+                    log::warn!("MQTT bridge disconnected: {}", e);
+                    update_status(&app, |status| {
+                        status.connected = false;
+                        status.last_error = Some(e);
+                    });
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RECONNECT_MAX);
+                }
+            }
+        }
+    });
+}
+
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_matches(['[', ']'])
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// One connection, until it fails or the config changes
+async fn run_session(app: &AppHandle, config: &MqttConfig) -> Result<(), String> {
+    let topics = Topics::new(config);
+    let client_id = format!("helix-{}", config.node_id);
+    let password = keyring::get_secret("mqtt_password".to_string()).ok().flatten();
+    let broker = format!("{}:{}", config.host, config.port);
+    update_status(app, |status| {
+        status.enabled = true;
+        status.broker = Some(broker.clone());
+    });
+    if password.is_some() && !config.tls && !is_loopback(&config.host) {
+        return Err(format!("Refusing to send the broker password to {} without TLS", broker));
+    }
+
+    let (mut writer, mut reader) = mqtt_client::connect(&ConnectOptions {
+        host: &config.host,
+        port: config.port,
+        tls: config.tls,
+        ca_file: config.ca_file.as_deref(),
+        client_id: &client_id,
+        username: config.username.as_deref(),
+        password: password.as_deref(),
+        keep_alive_secs: KEEP_ALIVE_SECS,
+        will: Some((&topics.availability, "offline")),
+    })
+    .await?;
+    log::info!("MQTT bridge connected to {}", broker);
+    update_status(app, |status| {
+        status.connected = true;
+        status.connected_at = Some(now_ms());
+        status.last_error = None;
+    });
+
+    // Reads aren't cancel safe, so they get a task of their own
+    let (messages_tx, mut messages) = mpsc::channel::<Message>(32);
+    let read_task = tokio::spawn(async move {
+        while let Ok(message) = reader.next_message().await {
+            if messages_tx.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let result = serve(app, config, &topics, &mut writer, &mut messages).await;
+    read_task.abort();
+    if result.is_ok() {
+        let _ = writer.publish(&topics.availability, b"offline", true).await;
+        writer.disconnect().await;
+        log::info!("MQTT bridge config changed; reconnecting");
+    }
+    result
+}
+
+async fn serve(
+    app: &AppHandle,
+    config: &MqttConfig,
+    topics: &Topics,
+    writer: &mut MqttWriter,
+    messages: &mut mpsc::Receiver<Message>,
+) -> Result<(), String> {
+    writer.publish(&topics.availability, b"online", true).await?;
+    if config.commands {
+        writer.subscribe(&topics.command).await?;
+        writer.subscribe(&topics.talk_mode_set).await?;
+    }
+    let mut buttons = Vec::new();
+    if config.discovery {
+        writer.subscribe(&topics.ha_status).await?;
+        buttons = publish_discovery(writer, config, topics, &[]).await?;
+    }
+    let mut last = publish_state(writer, topics).await?;
+
+    let interval = Duration::from_secs(config.publish_interval_secs.max(MIN_PUBLISH_INTERVAL_SECS));
+    let mut publish_tick = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    let mut ping_tick = tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut config_tick = tokio::time::interval_at(tokio::time::Instant::now() + CONFIG_POLL, CONFIG_POLL);
+
+    loop {
+        tokio::select! {
+            message = messages.recv() => {
+                let message = message.ok_or("Connection to broker lost")?;
+                if message.topic == topics.ha_status {
+                    // Home Assistant restarted and forgot the device
+                    if message.payload == b"online" {
+                        buttons = publish_discovery(writer, config, topics, &buttons).await?;
+                        last = publish_state(writer, topics).await?;
+                    }
+                } else {
+                    handle_message(app, topics, message).await;
+                }
+            }
+            _ = presence::changed() => {
+                let current = presence::snapshot().await?;
+                if current != last {
+                    last = publish_state(writer, topics).await?;
+                }
+            }
+            _ = publish_tick.tick() => {
+                last = publish_state(writer, topics).await?;
+                if config.discovery {
+                    buttons = publish_discovery(writer, config, topics, &buttons).await?;
+                }
+            }
+            _ = ping_tick.tick() => writer.ping().await?,
+            _ = config_tick.tick() => {
+                if current_config() != *config {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+async fn publish_state(writer: &mut MqttWriter, topics: &Topics) -> Result<Presence, String> {
+    let presence = presence::snapshot().await?;
+    let payload = serde_json::to_vec(&presence).map_err(|e| e.to_string())?;
+    writer.publish(&topics.state, &payload, true).await?;
+    if let Ok(mut status) = STATUS.lock() {
+        status.last_published = Some(now_ms());
+    }
+    Ok(presence)
+}
+
+/// Job ids and names for the run buttons
+fn job_buttons() -> Vec<(String, String)> {
+    scheduler::get_scheduled_jobs()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|job| {
+            let kind = serde_json::to_value(&job.job_type)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.replace('_', " ")))
+                .unwrap_or_else(|| "job".to_string());
+            (job.id, format!("Run {}", kind))
+        })
+        .collect()
+}
+
+/// Publish the device's discovery configs; buttons of jobs that are gone
+/// since `previous` are removed. Returns the job ids now published.
+async fn publish_discovery(
+    writer: &mut MqttWriter,
+    config: &MqttConfig,
+    topics: &Topics,
+    previous: &[String],
+) -> Result<Vec<String>, String> {
+    let prefix = config.discovery_prefix.trim_end_matches('/');
+    let node = &config.node_id;
+    let unique = |object: &str| format!("helix_{}_{}", node, object).replace(['-', '.', ' '], "_");
+    let device = json!({
+        "identifiers": [format!("helix_{}", node)],
+        "name": format!("Helix ({})", node),
+        "manufacturer": "Helix",
+        "model": "Helix Desktop",
+        "sw_version": env!("CARGO_PKG_VERSION"),
+    });
+    let common = |object: &str, name: &str| {
+        json!({
+            "name": name,
+            "object_id": unique(object),
+            "unique_id": unique(object),
+            "availability_topic": topics.availability,
+            "device": device,
+        })
+    };
+    let with = |mut base: Value, extra: Value| {
+        if let (Some(base), Value::Object(extra)) = (base.as_object_mut(), extra) {
+            base.extend(extra);
+        }
+        base
+    };
+    let bool_template = |field: &str| format!("{{{{ 'ON' if value_json.{} else 'OFF' }}}}", field);
+
+    let mut configs = vec![
+        (
+            format!("{}/sensor/{}/gateway/config", prefix, node),
+            with(
+                common("gateway", "Gateway"),
+                json!({
+                    "state_topic": topics.state,
+                    "value_template": "{{ value_json.gateway }}",
+                    "icon": "mdi:server-network",
+                }),
+            ),
+        ),
+        (
+            format!("{}/binary_sensor/{}/dnd/config", prefix, node),
+            with(
+                common("dnd", "Do not disturb"),
+                json!({
+                    "state_topic": topics.state,
+                    "value_template": bool_template("dnd"),
+                    "icon": "mdi:minus-circle",
+                }),
+            ),
+        ),
+    ];
+    let talk_mode = json!({
+        "state_topic": topics.state,
+        "value_template": bool_template("talk_mode"),
+        "icon": "mdi:microphone",
+    });
+    if config.commands {
+        configs.push((
+            format!("{}/switch/{}/talk_mode/config", prefix, node),
+            with(
+                with(common("talk_mode", "Talk mode"), talk_mode),
+                json!({ "command_topic": topics.talk_mode_set }),
+            ),
+        ));
+    } else {
+        configs.push((
+            format!("{}/binary_sensor/{}/talk_mode/config", prefix, node),
+            with(common("talk_mode", "Talk mode"), talk_mode),
+        ));
+    }
+
+    let jobs = if config.commands { job_buttons() } else { Vec::new() };
+    for (job_id, name) in &jobs {
+        let object = format!("job_{}", job_id);
+        configs.push((
+            format!("{}/button/{}/{}/config", prefix, node, unique(&object)),
+            with(
+                common(&object, name),
+                json!({
+                    "command_topic": topics.command,
+                    "payload_press": json!({ "action": "run_job", "job_id": job_id }).to_string(),
+                    "icon": "mdi:play",
+                }),
+            ),
+        ));
+    }
+
+    for (topic, payload) in configs {
+        let payload = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+        writer.publish(&topic, &payload, true).await?;
+    }
+    // An empty retained config removes the entity
+    for job_id in previous.iter().filter(|id| !jobs.iter().any(|(job, _)| job == *id)) {
+        let object = unique(&format!("job_{}", job_id));
+        writer.publish(&format!("{}/button/{}/{}/config", prefix, node, object), b"", true).await?;
+    }
+
+    log::debug!("Published Home Assistant discovery under {}", topics.base);
+    Ok(jobs.into_iter().map(|(job_id, _)| job_id).collect())
+}
+
+fn set_talk_mode(app: &AppHandle, active: bool) {
+    if presence::talk_mode_active() != active {
+        let _ = app.emit("tray:toggle-talk-mode", json!({ "active": active }));
+    }
+}
+
+async fn run_job(job_id: Option<String>, job_type: Option<String>) -> Result<(), CommandError> {
+    let job_id = match (job_id, job_type) {
+        (Some(job_id), _) => job_id,
+        (None, Some(job_type)) => scheduler::get_scheduled_jobs()?
+            .into_iter()
+            .find(|job| !job.is_one_shot() && serde_json::to_value(&job.job_type).is_ok_and(|v| v == job_type.as_str()))
+            .map(|job| job.id)
+            .ok_or_else(|| CommandError::not_found(format!("No scheduler job of type {}", job_type)))?,
+        (None, None) => return Err(CommandError::invalid_input("run_job needs a job_id or job_type")),
+    };
+    scheduler::trigger_job(job_id).await.map(|_| ())
+}
+
+async fn handle_message(app: &AppHandle, topics: &Topics, message: Message) {
+    let payload = String::from_utf8_lossy(&message.payload).trim().to_string();
+    let description = if message.topic == topics.talk_mode_set {
+        match payload.as_str() {
+            "ON" => set_talk_mode(app, true),
+            "OFF" => set_talk_mode(app, false),
+            other => log::warn!("MQTT talk mode command not understood: {:?}", other),
+        }
+        format!("talk_mode {}", payload)
+    } else if message.topic == topics.command {
+        match serde_json::from_str::<Command>(&payload) {
+            Ok(Command::ToggleTalkMode) => {
+                set_talk_mode(app, !presence::talk_mode_active());
+                "toggle_talk_mode".to_string()
+            }
+            Ok(Command::RunJob { job_id, job_type }) => {
+                let target = job_id.clone().or_else(|| job_type.clone()).unwrap_or_default();
+                if let Err(e) = run_job(job_id, job_type).await {
+                    log::warn!("MQTT run_job {} failed: {}", target, e.message);
+                }
+                format!("run_job {}", target)
+            }
+            Err(e) => {
+                log::warn!("MQTT command not understood: {}", e);
+                return;
+            }
+        }
+    } else {
+        return;
+    };
+
+    log::info!("MQTT command: {}", description);
+    if let Ok(mut status) = STATUS.lock() {
+        status.last_command = Some(description);
+    }
+}
+
+// Tauri commands
+
+/// Whether the MQTT bridge is enabled and connected
+#[tauri::command]
+pub fn get_mqtt_status() -> Result<MqttStatus, CommandError> {
+    Ok(STATUS.lock().map_err(|e| e.to_string())?.clone())
+}
@@ -0,0 +1,278 @@
+// MQTT client - the small part of MQTT 3.1.1 the Home Assistant bridge uses
+//
+// QoS 0 only: connect with a last will, publish (optionally retained),
+// subscribe, keepalive pings and incoming publishes. Brokers deliver QoS 0
+// to a QoS 0 subscription, so nothing here is acknowledged. Connections use
+// TLS (rustls, checked against the public roots or a given CA file) unless
+// the config turns it off; QoS 1/2 and persistent sessions aren't needed.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const SUBSCRIBE: u8 = 0x82;
+const PINGREQ: u8 = 0xC0;
+const DISCONNECT: u8 = 0xE0;
+/// Longest packet accepted from the broker
+const MAX_PACKET_BYTES: usize = 256 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How to connect
+pub struct ConnectOptions<'a> {
+    pub host: &'a str,
+    pub port: u16,
+    pub tls: bool,
+    /// PEM file of the CA that signed the broker's certificate; the public
+    /// roots when None
+    pub ca_file: Option<&'a str>,
+    pub client_id: &'a str,
+    pub username: Option<&'a str>,
+    pub password: Option<&'a str>,
+    pub keep_alive_secs: u16,
+    /// Published, retained, by the broker when the connection drops
+    pub will: Option<(&'a str, &'a str)>,
+}
+
+/// A publish received on a subscription
+#[derive(Debug)]
+pub struct Message {
+    pub topic: String,
+    pub payload: Vec<u8>,
+}
+
+/// A broker connection, plain or TLS
+trait Stream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Stream for T {}
+
+type BoxStream = Box<dyn Stream>;
+
+/// Sending half of a connection
+pub struct MqttWriter {
+    stream: WriteHalf<BoxStream>,
+    next_packet_id: u16,
+}
+
+/// Receiving half of a connection
+pub struct MqttReader {
+    stream: ReadHalf<BoxStream>,
+}
+
+fn put_string(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+/// Fixed header with its variable-length remaining length, then `body`
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![kind];
+    let mut length = body.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+fn tls_config(ca_file: Option<&str>) -> Result<ClientConfig, String> {
+    let mut roots = RootCertStore::empty();
+    match ca_file {
+        Some(path) => {
+            let certs = CertificateDer::pem_file_iter(path)
+                .map_err(|e| format!("Failed to read CA file {}: {}", path, e))?;
+            for cert in certs {
+                let cert = cert.map_err(|e| format!("Invalid certificate in {}: {}", path, e))?;
+                roots
+                    .add(cert)
+                    .map_err(|e| format!("Invalid certificate in {}: {}", path, e))?;
+            }
+        }
+        None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    Ok(ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth())
+}
+
+/// TCP connection to the broker, wrapped in TLS when asked to
+async fn open(options: &ConnectOptions<'_>) -> Result<BoxStream, String> {
+    let address = format!("{}:{}", options.host, options.port);
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address))
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", address))?
+        .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+    let _ = stream.set_nodelay(true);
+    if !options.tls {
+        return Ok(Box::new(stream));
+    }
+
+    let connector = TlsConnector::from(Arc::new(tls_config(options.ca_file)?));
+    let server_name = ServerName::try_from(options.host.to_string())
+        .map_err(|e| format!("Invalid broker host {}: {}", options.host, e))?;
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, connector.connect(server_name, stream))
+        .await
+        .map_err(|_| format!("Timed out negotiating TLS with {}", address))?
+        .map_err(|e| format!("TLS with {} failed: {}", address, e))?;
+    Ok(Box::new(stream))
+}
+
+/// Connect and wait for the broker to accept
+pub async fn connect(options: &ConnectOptions<'_>) -> Result<(MqttWriter, MqttReader), String> {
+    let (read, write) = tokio::io::split(open(options).await?);
+    let mut writer = MqttWriter {
+        stream: write,
+        next_packet_id: 1,
+    };
+    let mut reader = MqttReader { stream: read };
+
+    let mut flags = 0x02; // clean session
+    if options.will.is_some() {
+        flags |= 0x04 | 0x20; // will, retained
+    }
+    if options.username.is_some() {
+        flags |= 0x80;
+    }
+    if options.password.is_some() {
+        flags |= 0x40;
+    }
+    let mut body = Vec::new();
+    put_string(&mut body, b"MQTT");
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&options.keep_alive_secs.to_be_bytes());
+    put_string(&mut body, options.client_id.as_bytes());
+    if let Some((topic, payload)) = options.will {
+        put_string(&mut body, topic.as_bytes());
+        put_string(&mut body, payload.as_bytes());
+    }
+    if let Some(username) = options.username {
+        put_string(&mut body, username.as_bytes());
+    }
+    if let Some(password) = options.password {
+        put_string(&mut body, password.as_bytes());
+    }
+    writer.send(&packet(CONNECT, &body)).await?;
+
+    let (kind, body) = tokio::time::timeout(CONNECT_TIMEOUT, reader.read_packet())
+        .await
+        .map_err(|_| "Broker did not answer the connect".to_string())??;
+    if kind & 0xF0 != CONNACK || body.len() < 2 {
+        return Err("Broker sent an invalid connect answer".to_string());
+    }
+    match body[1] {
+        0 => Ok((writer, reader)),
+        1 => Err("Broker refused the connection: unsupported protocol version".to_string()),
+        2 => Err("Broker refused the connection: client id rejected".to_string()),
+        3 => Err("Broker refused the connection: server unavailable".to_string()),
+        4 => Err("Broker refused the connection: bad username or password".to_string()),
+        5 => Err("Broker refused the connection: not authorized".to_string()),
+        code => Err(format!("Broker refused the connection (code {})", code)),
+    }
+}
+
+impl MqttWriter {
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.stream
+            .write_all(bytes)
+            .await
+            .map_err(|e| format!("Failed to write to broker: {}", e))
+    }
+
+    pub async fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<(), String> {
+        let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+        put_string(&mut body, topic.as_bytes());
+        body.extend_from_slice(payload);
+        let kind = if retain { PUBLISH | 0x01 } else { PUBLISH };
+        self.send(&packet(kind, &body)).await
+    }
+
+    pub async fn subscribe(&mut self, filter: &str) -> Result<(), String> {
+        let id = self.next_packet_id;
+        self.next_packet_id = self.next_packet_id.checked_add(1).unwrap_or(1);
+        let mut body = Vec::new();
+        body.extend_from_slice(&id.to_be_bytes());
+        put_string(&mut body, filter.as_bytes());
+        body.push(0); // QoS 0
+        self.send(&packet(SUBSCRIBE, &body)).await
+    }
+
+    pub async fn ping(&mut self) -> Result<(), String> {
+        self.send(&[PINGREQ, 0]).await
+    }
+
+    pub async fn disconnect(mut self) {
+        let _ = self.send(&[DISCONNECT, 0]).await;
+        let _ = self.stream.shutdown().await;
+    }
+}
+
+impl MqttReader {
+    async fn read_byte(&mut self) -> Result<u8, String> {
+        self.stream
+            .read_u8()
+            .await
+            .map_err(|e| format!("Connection to broker lost: {}", e))
+    }
+
+    /// Packet type byte and body of the next packet
+    async fn read_packet(&mut self) -> Result<(u8, Vec<u8>), String> {
+        let kind = self.read_byte().await?;
+        let mut length = 0usize;
+        for shift in 0..4 {
+            let byte = self.read_byte().await?;
+            length |= ((byte & 0x7F) as usize) << (7 * shift);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        if length > MAX_PACKET_BYTES {
+            return Err(format!("Broker sent a {} byte packet", length));
+        }
+        let mut body = vec![0; length];
+        self.stream
+            .read_exact(&mut body)
+            .await
+            .map_err(|e| format!("Connection to broker lost: {}", e))?;
+        Ok((kind, body))
+    }
+
+    /// The next publish on a subscription; other packets (acks, ping
+    /// answers) are skipped
+    pub async fn next_message(&mut self) -> Result<Message, String> {
+        loop {
+            let (kind, body) = self.read_packet().await?;
+            if kind & 0xF0 != PUBLISH || body.len() < 2 {
+                continue;
+            }
+            let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+            let Some(topic) = body.get(2..2 + topic_len) else {
+                continue;
+            };
+            // QoS 1 and 2 publishes carry a packet id before the payload
+            let qos = (kind >> 1) & 0x03;
+            let payload_start = 2 + topic_len + if qos > 0 { 2 } else { 0 };
+            return Ok(Message {
+                topic: String::from_utf8_lossy(topic).to_string(),
+                payload: body.get(payload_start..).unwrap_or_default().to_vec(),
+            });
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Presence - the status Helix shows to home automation
+//
+// Gateway health, whether talk mode is on and do-not-disturb, gathered in
+// one snapshot for the MQTT bridge (mqtt.rs). Talk mode runs in the
+// frontend, which reports it through `set_talk_mode_active`; the backend
+// keeps the last report so the tray and the bridge can show it. Anything
+// that changes presence calls `notify_changed`, so the bridge publishes
+// right away instead of waiting for its next interval.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::commands::CommandError;
+
+static TALK_MODE: AtomicBool = AtomicBool::new(false);
+static CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Helix status as home automation sees it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Presence {
+    /// "healthy", "unhealthy" or "stopped"
+    pub gateway: String,
+    pub talk_mode: bool,
+    pub dnd: bool,
+}
+
+/// Whether the frontend last reported talk mode as running
+pub fn talk_mode_active() -> bool {
+    TALK_MODE.load(Ordering::SeqCst)
+}
+
+/// Wake whoever waits in `changed`
+pub fn notify_changed() {
+    CHANGED.notify_waiters();
+}
+
+/// Resolves on the next `notify_changed`
+pub async fn changed() {
+    CHANGED.notified().await;
+}
+
+/// Current presence; reads the gateway's last health check, not a new one.
+/// The do-not-disturb state is read from disk, off the async runtime.
+pub async fn snapshot() -> Result<Presence, String> {
+    let running = crate::commands::gateway::gateway_status(None).is_ok_and(|status| status.running);
+    let gateway = if !running {
+        "stopped"
+    } else if crate::gateway::monitor::last_health().healthy {
+        "healthy"
+    } else {
+        "unhealthy"
+    };
+    let dnd = tauri::async_runtime::spawn_blocking(crate::policy::dnd::status)
+        .await
+        .map_err(|e| format!("Failed to read do-not-disturb status: {}", e))?;
+    Ok(Presence {
+        gateway: gateway.to_string(),
+        talk_mode: talk_mode_active(),
+        dnd: dnd.active,
+    })
+}
+
+// Tauri commands
+
+/// Report whether talk mode is running
+#[tauri::command]
+pub fn set_talk_mode_active(app: AppHandle, active: bool) -> Result<(), CommandError> {
+    if TALK_MODE.swap(active, Ordering::SeqCst) != active {
+        let _ = app.emit("talk-mode:changed", serde_json::json!({ "active": active }));
+        notify_changed();
+    }
+    Ok(())
+}
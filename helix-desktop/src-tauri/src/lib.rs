@@ -1,9 +1,29 @@
 // Helix Desktop - Tauri Backend
 
+mod accessibility;
+mod accounts;
+mod activity;
+mod api;
+mod automation;
+mod cache;
+mod calendar;
 mod commands;
 mod config;
 mod gateway;
+mod integrations;
+mod locale;
+mod mcp;
+mod palette;
+mod policy;
+mod privacy;
+mod psychology;
+mod remote;
+mod scripts;
+mod startup;
+mod tasks;
+mod telemetry;
 mod tray;
+mod vault;
 #[allow(dead_code)]
 mod updater;
 
@@ -13,11 +33,29 @@ use tokio::sync::RwLock;
 
 use crate::config::ConfigWatcher;
 use crate::gateway::GatewayMonitor;
+use crate::startup::{safe_mode, Component};
+use crate::tasks::TaskManager;
 
 /// Application state shared across the app
 pub struct AppState {
     pub gateway_monitor: Arc<RwLock<GatewayMonitor>>,
     pub config_watcher: Arc<RwLock<ConfigWatcher>>,
+    pub task_manager: Arc<TaskManager>,
+}
+
+/// Whether the process was launched as an MCP server (`--mcp`)
+pub fn is_mcp_mode() -> bool {
+    std::env::args().any(|arg| arg == mcp::server::MCP_FLAG)
+}
+
+/// Serve MCP over stdio for the last active account, without opening a window
+pub fn run_mcp() {
+    accounts::profiles::init();
+
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start async runtime");
+    if let Err(e) = runtime.block_on(mcp::server::serve_stdio()) {
+        eprintln!("MCP server stopped: {}", e);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -34,14 +72,52 @@ pub fn run() {
         .manage(AppState {
             gateway_monitor: Arc::new(RwLock::new(GatewayMonitor::new())),
             config_watcher: Arc::new(RwLock::new(ConfigWatcher::new())),
+            task_manager: Arc::new(TaskManager::new()),
         })
         .setup(|app| {
+            // Crash-loop detection: decides whether this launch runs in safe mode
+            let safe_mode_status = safe_mode::begin_launch();
+
+            // Select the last active account before anything resolves data paths
+            accounts::profiles::init();
+
+            // User hooks announce their runs through the app
+            automation::hooks::init(app.handle());
+            // So do scripts refused for want of workspace trust
+            policy::workspace_trust::init(app.handle());
+
+            // First launch after an update: migrate data, rolling back to
+            // the pre-update snapshot if a hook fails
+            updater::migrations::run_pending(app.handle());
+
             // Initialize configuration
             commands::config::init(app.handle())?;
 
+            // Scheduler jobs and their history, from the account's database
+            commands::scheduler::init(app.handle());
+
+            // Bundled portable Python, if shipped, lives in the resource dir
+            scripts::interpreter::init(app.path().resource_dir().ok());
+            // So do the tokenizer vocabularies. Bundled resources keep their
+            // path under src-tauri/, resources/ included
+            if let Ok(dir) = app.path().resource_dir() {
+                let tokenizers = dir.join("resources").join("tokenizers");
+                if !tokenizers.is_dir() {
+                    log::warn!(
+                        "Tokenizer vocabularies missing at {:?}; token counts fall back to estimates",
+                        tokenizers
+                    );
+                }
+                helix_shared::tokenizer::set_bundled_dir(tokenizers);
+            }
+
             // Start gateway monitor
             commands::gateway::init(app.handle())?;
 
+            // Gateways and helpers a crashed run left behind: adopt the
+            // gateways still serving, stop the rest
+            startup::orphans::reconcile();
+
             // Initialize system tray (desktop only)
             #[cfg(desktop)]
             {
@@ -50,11 +126,16 @@ pub fn run() {
 
             // Start gateway health monitoring
             let state = app.state::<AppState>();
-            let monitor = state.gateway_monitor.blocking_read();
-            monitor.start(app.handle().clone());
+            if safe_mode::is_enabled(Component::GatewayMonitor) {
+                safe_mode::enter(Component::GatewayMonitor);
+                let monitor = state.gateway_monitor.blocking_read();
+                monitor.start(app.handle().clone());
+                gateway::agent_metrics::start(app.handle().clone());
+            }
 
             // Start config file watcher
-            {
+            if safe_mode::is_enabled(Component::ConfigWatcher) {
+                safe_mode::enter(Component::ConfigWatcher);
                 let mut watcher = state.config_watcher.blocking_write();
                 if let Err(e) = watcher.start(app.handle().clone()) {
                     log::warn!("Failed to start config watcher: {}", e);
@@ -62,26 +143,126 @@ pub fn run() {
             }
 
             // Auto-start OpenClaw gateway
-            if let Err(e) = commands::gateway::auto_start_gateway(app.handle()) {
-                log::warn!("Failed to auto-start gateway: {}", e);
+            if safe_mode::is_enabled(Component::Gateway) {
+                safe_mode::enter(Component::Gateway);
+                if let Err(e) = commands::gateway::auto_start_gateway(app.handle()) {
+                    log::warn!("Failed to auto-start gateway: {}", e);
+                }
+                // Install helix-runtime when there is none, or update the
+                // one Helix manages to the pinned release
+                gateway::installer::start(app.handle().clone());
+            }
+
+            // Notice a gateway process that exits on its own and relaunch it
+            gateway::supervisor::start(app.handle().clone());
+
+            // Optional nightly restart of the default gateway
+            gateway::nightly_restart::start(app.handle().clone());
+
+            // Goal check-in reminders; the scheduler job is skipped while
+            // safe mode holds the scheduler back
+            psychology::goals::start(app.handle().clone());
+
+            // Scheduler Custom jobs replay recorded macros
+            automation::macros::start(app.handle().clone());
+
+            // Maintenance jobs run their memory and synthesis executables
+            commands::scheduler_runner::start(app.handle().clone());
+
+            // Daily wellness scores for the purpose layer
+            psychology::wellness::start();
+
+            // Weekly full integration: cross-layer analyses and milestone detection
+            psychology::transformation::start(app.handle().clone());
+
+            // Online/offline verdict for the cloud features below
+            remote::connectivity::start(app.handle().clone());
+
+            // Keep the cached subscription tier current for feature gates
+            accounts::tiers::start();
+
+            // Dashboard edits to memories, subscriptions and devices
+            remote::realtime::start(app.handle().clone());
+
+            // Device heartbeats to Supabase and the Discord heartbeat webhook
+            remote::heartbeat::start();
+
+            // Retry device registrations and heartbeats that hit an outage
+            remote::cloud_queue::start();
+
+            // Do-not-disturb: announce quiet-hours and OS focus changes
+            policy::dnd::start(app.handle().clone());
+            policy::skill_approvals::start(app.handle().clone());
+
+            // Helix status on MQTT for Home Assistant (only if enabled in config)
+            integrations::mqtt::start(app.handle().clone());
+
+            // Screen reader, reduced motion and high contrast changes
+            accessibility::state::start(app.handle().clone());
+
+            // Token-protected localhost REST API (only if enabled in config)
+            if safe_mode::is_enabled(Component::LocalApi) {
+                safe_mode::enter(Component::LocalApi);
+                api::server::auto_start(app.handle());
             }
 
             // Initialize auto-updater
             updater::init(app.handle());
 
+            // Opt-in usage analytics (no-op unless enabled in config)
+            telemetry::analytics::init(app.handle(), &safe_mode_status);
+
+            safe_mode::notify_frontend(app.handle(), &safe_mode_status);
+            safe_mode::spawn_stability_timer();
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        // Commands pass through the macro recorder on their way in
+        .invoke_handler(automation::macros::recording_handler(tauri::generate_handler![
             // Gateway commands
             commands::gateway::start_gateway,
             commands::gateway::stop_gateway,
             commands::gateway::gateway_status,
             commands::gateway::get_gateway_url,
+            commands::gateway::set_gateway_port,
+            commands::gateway::set_gateway_remote_url,
+            commands::gateway::list_gateway_instances,
+            gateway::logs::get_gateway_logs,
             commands::gateway::get_gateway_token,
+            gateway::agent_metrics::get_agent_metrics,
+            gateway::monitor::get_gateway_health_details,
+            gateway::runtime::detect_runtime_paths,
+            gateway::runtime::set_runtime_path,
+            gateway::runtime::update_runtime,
+            gateway::installer::get_runtime_install,
+            gateway::installer::install_runtime,
+            gateway::node::get_node_environment,
+            gateway::nightly_restart::get_nightly_restart,
+            gateway::nightly_restart::set_nightly_restart,
+            gateway::client::gateway_rpc,
+            gateway::actions::list_gateway_actions,
+            gateway::actions::declare_gateway_actions,
+            gateway::actions::invoke_gateway_action,
+            gateway::speakers::list_voice_speakers,
+            gateway::speakers::enroll_voice_speaker,
+            gateway::speakers::remove_voice_speaker,
+
+            // Locale (translated backend strings)
+            locale::strings::get_locale,
+            locale::strings::get_locale_strings,
+            locale::strings::set_locale,
+
+            // Accessibility (OS settings; announcements are events)
+            accessibility::state::get_accessibility_state,
+
+            // Response cache (deterministic external calls)
+            cache::responses::get_response_cache_stats,
+            cache::responses::clear_response_cache,
 
             // Config commands
             commands::config::get_config,
             commands::config::set_config,
+            commands::config::patch_config,
             commands::config::get_config_path,
 
             // Keyring commands
@@ -117,8 +298,17 @@ pub fn run() {
             commands::auth::supabase_signup,
             commands::auth::register_device,
             commands::auth::send_heartbeat,
+            commands::auth::deregister_instance,
             commands::auth::get_hostname,
 
+            // Account profiles (multi-user)
+            accounts::profiles::list_accounts,
+            accounts::profiles::get_active_account,
+            accounts::profiles::switch_account,
+            accounts::profiles::sign_out_account,
+            accounts::tiers::get_subscription_status,
+            accounts::tiers::check_feature_access,
+
             // Discord logging
             commands::discord::send_webhook,
             commands::discord::test_webhook,
@@ -129,29 +319,165 @@ pub fn run() {
             commands::psychology::get_layer,
             commands::psychology::get_all_layers,
             commands::psychology::update_layer,
+            commands::psychology::validate_layer,
             commands::psychology::run_decay,
             commands::psychology::run_synthesis,
             commands::psychology::restore_from_decay,
             commands::psychology::get_layer_status,
+            psychology::migrations::get_migration_report,
+            psychology::soul_history::get_soul_history,
+            psychology::soul_history::diff_soul,
+            psychology::soul_history::restore_soul,
+            psychology::graph::get_relationship_graph,
+            psychology::goals::get_goal_stats,
+            psychology::goals::log_goal_progress,
+            psychology::context::build_context,
+            psychology::context::count_tokens,
+            psychology::wellness::get_wellness_trends,
+            psychology::ikigai::get_purpose_alignment,
+            psychology::transformation::get_milestone_candidates,
+            psychology::transformation::approve_milestone,
+            psychology::transformation::reject_milestone,
+            scripts::runner::get_python_runtime,
+
+            // Do-not-disturb
+            policy::dnd::get_dnd_status,
+            policy::dnd::set_dnd,
+
+            // Home automation integrations
+            integrations::presence::set_talk_mode_active,
+            integrations::mqtt::get_mqtt_status,
+
+            // Soul guardrails (automated psychology writes)
+            policy::guardrails::list_pending_writes,
+            policy::guardrails::approve_pending_write,
+            policy::guardrails::reject_pending_write,
+            policy::guardrails::get_guardrail_decisions,
+            // Skill run approvals (agent-requested skill executions)
+            policy::skill_approvals::list_skill_runs,
+            policy::skill_approvals::approve_skill_run,
+            policy::skill_approvals::reject_skill_run,
+            policy::skill_approvals::set_skill_auto_approve,
+            // Process isolation (limits for spawned processes)
+            policy::isolation::get_isolation_support,
+            // Workspace trust (approval of project-local scripts)
+            policy::workspace_trust::get_workspace_trust,
+            policy::workspace_trust::trust_workspace,
+            policy::workspace_trust::revoke_workspace_trust,
+
+            // Command palette
+            palette::actions::query_actions,
+            palette::actions::execute_action,
+            palette::intent::route_voice_intent,
+
+            // Command macros (recorded command sequences)
+            automation::macros::start_macro_recording,
+            automation::macros::stop_macro_recording,
+            automation::macros::get_macro_recording,
+            automation::macros::list_macros,
+            automation::macros::save_macro,
+            automation::macros::delete_macro,
+            automation::macros::run_macro,
+
+            // Lifecycle hooks (user commands and skills run on app events)
+            automation::hooks::list_hooks,
+            automation::hooks::save_hook,
+            automation::hooks::delete_hook,
+            automation::hooks::test_hook,
+            automation::hooks::get_hook_runs,
+
+            // Psychology vault (at-rest encryption)
+            vault::store::get_vault_status,
+            vault::store::encrypt_psychology_data,
+            vault::store::unlock_vault,
+            vault::store::lock_vault,
+
+            // Secure wipe
+            privacy::wipe::secure_wipe,
+            privacy::wipe::get_wipe_history,
+            privacy::residency::explain_data_flow,
+
+            // Activity timeline
+            activity::timeline::get_activity_timeline,
+
+            // Supabase Realtime bridge
+            remote::realtime::get_realtime_status,
+
+            // Connectivity
+            remote::connectivity::get_connectivity_status,
+            remote::connectivity::report_network_change,
+            remote::cloud_queue::get_cloud_queue,
+
+            // Session handoff
+            remote::handoff::create_session_handoff,
+            remote::handoff::list_session_handoffs,
+            remote::handoff::accept_session_handoff,
+            remote::handoff::decline_session_handoff,
+            remote::handoff::cancel_session_handoff,
+
+            // Local REST API
+            api::server::start_api_server,
+            api::server::stop_api_server,
+            api::server::get_api_status,
+            api::server::get_api_token,
+            api::server::rotate_api_token,
+            api::readers::list_api_reader_tokens,
+            api::readers::create_api_reader_token,
+            api::readers::revoke_api_reader_token,
+
+            // MCP server (launched by MCP clients with --mcp)
+            mcp::server::get_mcp_config,
+
+            // Calendar (prospective layer)
+            calendar::commitments::get_upcoming_commitments,
 
             // Config watcher commands
             config::watcher::start_config_watcher,
             config::watcher::stop_config_watcher,
             config::watcher::is_config_watcher_active,
 
+            // Safe mode commands
+            startup::safe_mode::get_safe_mode_status,
+            startup::safe_mode::enable_component,
+            startup::safe_mode::exit_safe_mode,
+
+            // Telemetry commands (opt-in analytics)
+            telemetry::analytics::get_telemetry_status,
+            telemetry::analytics::set_telemetry_settings,
+            telemetry::analytics::preview_telemetry_payload,
+            telemetry::analytics::record_feature_usage,
+
+            // Background task commands
+            tasks::manager::cancel_task,
+            tasks::manager::list_active_tasks,
+
             // Scheduler commands (Layer 5 jobs)
             commands::scheduler::get_scheduler_config,
             commands::scheduler::set_scheduler_config,
             commands::scheduler::get_scheduled_jobs,
             commands::scheduler::get_job,
             commands::scheduler::create_job,
+            commands::scheduler::schedule_job_once,
             commands::scheduler::pause_job,
             commands::scheduler::resume_job,
+            commands::scheduler::set_job_retry_policy,
+            commands::scheduler::set_job_misfire_policy,
+            commands::scheduler::set_job_run_in_quiet_hours,
             commands::scheduler::delete_job,
             commands::scheduler::trigger_job,
+            commands::scheduler::set_job_dependencies,
             commands::scheduler::complete_job,
             commands::scheduler::fail_job,
+            commands::scheduler::get_job_history,
+            commands::scheduler::purge_job_history,
             commands::scheduler::get_scheduler_health,
+            commands::scheduler::pause_scheduler,
+            commands::scheduler::resume_scheduler,
+            commands::scheduler::scheduler_maintenance_mode,
+            commands::scheduler::get_upcoming_maintenance,
+            commands::scheduler_transfer::export_scheduler_state,
+            commands::scheduler_transfer::import_scheduler_state,
+            commands::preconditions::get_service_availability,
 
             // Phase C: Clipboard operations
             commands::clipboard::copy_to_clipboard,
@@ -165,9 +491,14 @@ pub fn run() {
 
             // Rust executables (Task 6)
             commands::rust_executables::start_memory_synthesis,
+            commands::rust_executables::start_memory_synthesis_server,
+            commands::syntheses::list_syntheses,
+            commands::rust_executables::import_chat_export,
             commands::rust_executables::start_skill_sandbox,
             commands::rust_executables::start_voice_pipeline,
             commands::rust_executables::start_sync_coordinator,
+            commands::rust_executables::start_obsidian_sync,
+            commands::rust_executables::start_email_ingest,
             commands::rust_executables::start_psychology_decay,
             commands::rust_executables::get_rust_exe_status,
             commands::rust_executables::stop_rust_exe,
@@ -183,8 +514,11 @@ pub fn run() {
             // Phase J4: Auto-Updater
             updater::check_for_update,
             updater::install_update,
+            updater::pause_update,
+            updater::resume_update,
             updater::get_app_version,
-        ])
+            updater::migrations::get_post_update_status,
+        ]))
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Minimize to tray instead of closing
@@ -192,6 +526,11 @@ pub fn run() {
                 api.prevent_close();
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running Helix");
+        .build(tauri::generate_context!())
+        .expect("error while building Helix")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                safe_mode::mark_clean_exit();
+            }
+        });
 }
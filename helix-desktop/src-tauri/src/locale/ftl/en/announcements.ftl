@@ -0,0 +1,16 @@
+# Screen reader announcements: one plain sentence each, no symbols
+
+gateway-running = Gateway running
+gateway-starting = Gateway starting
+gateway-stopped = Gateway stopped
+gateway-unhealthy = Gateway not responding
+gateway-restarting = Gateway restarting
+gateway-crashed = { $name } stopped unexpectedly
+gateway-crashed-relaunching = { $name } stopped unexpectedly and is being restarted
+
+nightly-restart-done = Gateway restarted for the night
+nightly-restart-skipped = Nightly gateway restart skipped because sessions were active
+nightly-restart-failed = Nightly gateway restart failed
+
+dnd-on = Do not disturb on
+dnd-off = Do not disturb off
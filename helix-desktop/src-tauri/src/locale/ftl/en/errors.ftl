@@ -0,0 +1,24 @@
+# Command errors, one message per code of the catalog in commands/error.rs.
+# The frontend shows these in place of the English `message` of an error.
+
+internal = Something went wrong
+invalid_input = Some of the values entered are not valid
+not_found = It could not be found
+already_exists = It already exists
+unauthenticated = Sign in to continue
+permission_denied = You don't have permission to do this
+confirmation_required = The confirmation did not match
+io = A file could not be read or written
+network = The request failed; check your connection
+timeout = This took too long and was stopped
+config = The configuration is invalid
+keyring = The system keyring could not be accessed
+vault_locked = Unlock the vault to continue
+service_unavailable = A required service is not running
+disabled = This is turned off
+process_failed = An external program failed
+conflict = Something else is already in progress
+unsupported = This isn't available on this platform
+tier_required = This needs a higher plan
+offline = You're offline
+busy = Something else is writing these files; try again in a moment
@@ -0,0 +1,18 @@
+# Desktop notifications
+
+skill-approval-title = Skill run awaiting approval
+skill-approval-body = { $agent } wants to run skill { $skill }
+skill-approval-body-any-agent = An agent wants to run skill { $skill }
+skill-approvals-waiting = { $count } skill runs are waiting for approval
+
+job-failed-title = Scheduled job failed
+job-failed-body = { $job } failed: { $error }
+job-failed-repeatedly-body = { $job } failed { $count } times in a row: { $error }
+job-consolidation = Memory consolidation
+job-synthesis = Synthesis
+job-full-integration = Full integration
+job-memory-fadeout = Memory fadeout
+job-pattern-analysis = Pattern analysis
+job-recommendation-generation = Recommendations
+job-goal-reminder = Goal reminder
+job-custom = Macro job
@@ -0,0 +1,54 @@
+# System tray menu and tooltip
+
+app-name = Helix
+tooltip = Helix
+tooltip-restart-pending = Helix - gateway restart pending
+# Tooltip with the latest status announcement, read out by screen readers
+tooltip-status = { $tooltip }. { $status }
+
+gateway-running = Gateway: Running
+gateway-running-restart-pending = Gateway: Running (restart pending)
+gateway-stopped = Gateway: Stopped
+
+new-chat = New Chat
+talk-mode = Talk Mode
+talk-mode-on = Talk Mode (on)
+
+agents = Agents
+agents-none = No agents configured
+channels = Channels
+channels-none = No channels configured
+status-active = active
+status-connected = connected
+status-idle = idle
+status-disconnected = disconnected
+status-error = error
+
+maintenance = Maintenance
+# Scheduler jobs whose failures raised an alert
+maintenance-failing = Maintenance ({ $count } failing)
+# Scheduler maintenance mode: no jobs start
+maintenance-suspended = Maintenance (suspended)
+job-consolidation = consolidation
+job-synthesis = synthesis
+next-run-off = Next { $job }: off
+next-run = Next { $job }: { $when }
+next-run-after-pause = Next { $job }: { $when } (after pause)
+due-now = due now
+in-duration = in { $duration }
+run-consolidation = Run Consolidation Now
+run-synthesis = Run Synthesis Now
+pause-scheduler-1h = Pause Scheduler for 1 Hour
+pause-scheduler-24h = Pause Scheduler for 24 Hours
+resume-scheduler = Resume Scheduler (paused, { $remaining } left)
+open-scheduler = Open Scheduler
+
+settings = Settings
+approvals = Approvals
+approvals-pending = Approvals ({ $count })
+
+show-window = Show Window
+hide-window = Hide Window
+restart-gateway = Restart Gateway
+restart-gateway-pending = Restart Gateway (pending)
+quit = Quit Helix
@@ -0,0 +1,16 @@
+# Anuncios para lectores de pantalla: una frase sencilla, sin símbolos
+
+gateway-running = Gateway en marcha
+gateway-starting = Gateway iniciándose
+gateway-stopped = Gateway detenido
+gateway-unhealthy = El gateway no responde
+gateway-restarting = Gateway reiniciándose
+gateway-crashed = { $name } se detuvo inesperadamente
+gateway-crashed-relaunching = { $name } se detuvo inesperadamente y se está reiniciando
+
+nightly-restart-done = Gateway reiniciado por la noche
+nightly-restart-skipped = Reinicio nocturno del gateway omitido porque había sesiones activas
+nightly-restart-failed = Falló el reinicio nocturno del gateway
+
+dnd-on = No molestar activado
+dnd-off = No molestar desactivado
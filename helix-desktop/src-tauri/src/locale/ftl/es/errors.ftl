@@ -0,0 +1,24 @@
+# Command errors, one message per code of the catalog in commands/error.rs.
+# The frontend shows these in place of the English `message` of an error.
+
+internal = Algo salió mal
+invalid_input = Algunos de los valores introducidos no son válidos
+not_found = No se encontró
+already_exists = Ya existe
+unauthenticated = Inicia sesión para continuar
+permission_denied = No tienes permiso para hacer esto
+confirmation_required = La confirmación no coincide
+io = No se pudo leer o escribir un archivo
+network = La solicitud falló; comprueba tu conexión
+timeout = Tardó demasiado y se detuvo
+config = La configuración no es válida
+keyring = No se pudo acceder al llavero del sistema
+vault_locked = Desbloquea la bóveda para continuar
+service_unavailable = Un servicio necesario no está en marcha
+disabled = Está desactivado
+process_failed = Falló un programa externo
+conflict = Ya hay otra operación en curso
+unsupported = No está disponible en esta plataforma
+tier_required = Requiere un plan superior
+offline = No tienes conexión
+busy = Otro proceso está escribiendo estos archivos; inténtalo de nuevo en un momento
@@ -0,0 +1,18 @@
+# Desktop notifications
+
+skill-approval-title = Ejecución de skill pendiente de aprobación
+skill-approval-body = { $agent } quiere ejecutar la skill { $skill }
+skill-approval-body-any-agent = Un agente quiere ejecutar la skill { $skill }
+skill-approvals-waiting = { $count } ejecuciones de skills esperan aprobación
+
+job-failed-title = Falló una tarea programada
+job-failed-body = { $job } falló: { $error }
+job-failed-repeatedly-body = { $job } falló { $count } veces seguidas: { $error }
+job-consolidation = Consolidación de memoria
+job-synthesis = Síntesis
+job-full-integration = Integración completa
+job-memory-fadeout = Desvanecimiento de memoria
+job-pattern-analysis = Análisis de patrones
+job-recommendation-generation = Recomendaciones
+job-goal-reminder = Recordatorio de metas
+job-custom = Tarea de macro
@@ -0,0 +1,54 @@
+# System tray menu and tooltip
+
+app-name = Helix
+tooltip = Helix
+tooltip-restart-pending = Helix - reinicio del gateway pendiente
+# Tooltip con el último anuncio de estado, leído por los lectores de pantalla
+tooltip-status = { $tooltip }. { $status }
+
+gateway-running = Gateway: en marcha
+gateway-running-restart-pending = Gateway: en marcha (reinicio pendiente)
+gateway-stopped = Gateway: detenido
+
+new-chat = Nuevo chat
+talk-mode = Modo conversación
+talk-mode-on = Modo conversación (activo)
+
+agents = Agentes
+agents-none = No hay agentes configurados
+channels = Canales
+channels-none = No hay canales configurados
+status-active = activo
+status-connected = conectado
+status-idle = inactivo
+status-disconnected = desconectado
+status-error = error
+
+maintenance = Mantenimiento
+# Scheduler jobs whose failures raised an alert
+maintenance-failing = Mantenimiento ({ $count } con fallos)
+# Scheduler maintenance mode: no jobs start
+maintenance-suspended = Mantenimiento (suspendido)
+job-consolidation = consolidación
+job-synthesis = síntesis
+next-run-off = Próxima { $job }: desactivada
+next-run = Próxima { $job }: { $when }
+next-run-after-pause = Próxima { $job }: { $when } (tras la pausa)
+due-now = ahora
+in-duration = en { $duration }
+run-consolidation = Consolidar ahora
+run-synthesis = Sintetizar ahora
+pause-scheduler-1h = Pausar el planificador 1 hora
+pause-scheduler-24h = Pausar el planificador 24 horas
+resume-scheduler = Reanudar el planificador (en pausa, quedan { $remaining })
+open-scheduler = Abrir el planificador
+
+settings = Ajustes
+approvals = Aprobaciones
+approvals-pending = Aprobaciones ({ $count })
+
+show-window = Mostrar ventana
+hide-window = Ocultar ventana
+restart-gateway = Reiniciar gateway
+restart-gateway-pending = Reiniciar gateway (pendiente)
+quit = Salir de Helix
@@ -0,0 +1,5 @@
+// Helix Desktop - Locale Module (translated strings of backend UI surfaces)
+
+pub mod strings;
+
+pub use strings::{t, Catalog};
@@ -0,0 +1,278 @@
+// Localized strings - Fluent catalogs for the text the backend shows
+//
+// The tray menu, desktop notifications, error messages and screen reader
+// announcements are rendered here rather than in the webview, so their
+// text comes from the Fluent catalogs under ftl/<locale>/<namespace>.ftl,
+// compiled into the binary.
+// The locale is `ui.locale` when set, else the OS locale, negotiated
+// against the catalogs (es-MX gets es) with English as the fallback. A
+// message missing from a translation falls back to English, and one
+// missing from English to its id, so a gap never breaks a menu.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use fluent_syntax::ast;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+use unic_langid::LanguageIdentifier;
+
+use crate::commands::config;
+use crate::commands::CommandError;
+
+const DEFAULT_LOCALE: &str = "en";
+
+/// Namespaces every locale provides
+pub const NAMESPACES: &[&str] = &["tray", "notifications", "errors", "announcements"];
+
+/// Catalog sources by locale and namespace; English must be complete
+const CATALOGS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "en",
+        &[
+            ("tray", include_str!("ftl/en/tray.ftl")),
+            ("notifications", include_str!("ftl/en/notifications.ftl")),
+            ("errors", include_str!("ftl/en/errors.ftl")),
+            ("announcements", include_str!("ftl/en/announcements.ftl")),
+        ],
+    ),
+    (
+        "es",
+        &[
+            ("tray", include_str!("ftl/es/tray.ftl")),
+            ("notifications", include_str!("ftl/es/notifications.ftl")),
+            ("errors", include_str!("ftl/es/errors.ftl")),
+            ("announcements", include_str!("ftl/es/announcements.ftl")),
+        ],
+    ),
+];
+
+type Bundle = FluentBundle<Arc<FluentResource>>;
+/// Parsed bundles by (locale, namespace); `None` when there is no catalog
+type Bundles = HashMap<(String, String), Option<Arc<Bundle>>>;
+
+/// Bundles built so far, on first use
+static BUNDLES: LazyLock<Mutex<Bundles>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The resolved locale and where it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleInfo {
+    /// Locale strings are served in
+    pub locale: String,
+    /// `ui.locale`, when set
+    pub configured: Option<String>,
+    /// Locale reported by the OS
+    pub system: Option<String>,
+    pub available: Vec<String>,
+}
+
+/// Every message of a namespace, formatted for one locale
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleStrings {
+    pub locale: String,
+    pub namespace: String,
+    /// Message id to text. Variables are left as `{$name}` for the
+    /// frontend to fill in.
+    pub strings: BTreeMap<String, String>,
+}
+
+fn available() -> Vec<LanguageIdentifier> {
+    CATALOGS
+        .iter()
+        .filter_map(|(locale, _)| locale.parse().ok())
+        .collect()
+}
+
+fn configured() -> Option<String> {
+    config::get_config()
+        .ok()
+        .and_then(|config| config.ui.locale)
+        .map(|locale| locale.trim().to_string())
+        .filter(|locale| !locale.is_empty())
+}
+
+/// The catalog locale closest to `requested`, English when none is
+fn negotiate(requested: Option<&str>) -> String {
+    let default: LanguageIdentifier = DEFAULT_LOCALE.parse().expect("default locale is valid");
+    let requested: Vec<LanguageIdentifier> = requested
+        // OS locales may come as en_US or en-US.UTF-8
+        .map(|locale| locale.split('.').next().unwrap_or(locale).replace('_', "-"))
+        .and_then(|locale| locale.parse().ok())
+        .into_iter()
+        .collect();
+    let available = available();
+    negotiate_languages(&requested, &available, Some(&default), NegotiationStrategy::Filtering)
+        .first()
+        .map(|locale| locale.to_string())
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Locale the backend renders its strings in
+pub fn current() -> String {
+    negotiate(configured().or_else(sys_locale::get_locale).as_deref())
+}
+
+fn source(locale: &str, namespace: &str) -> Option<&'static str> {
+    CATALOGS
+        .iter()
+        .find(|(l, _)| *l == locale)
+        .and_then(|(_, namespaces)| namespaces.iter().find(|(ns, _)| *ns == namespace))
+        .map(|(_, source)| *source)
+}
+
+fn build(locale: &str, namespace: &str) -> Option<Bundle> {
+    let source = source(locale, namespace)?;
+    let resource = match FluentResource::try_new(source.to_string()) {
+        Ok(resource) => resource,
+        Err((resource, errors)) => {
+            log::warn!("{} errors in the {}/{} catalog: {:?}", errors.len(), locale, namespace, errors);
+            resource
+        }
+    };
+
+    let mut bundle = FluentBundle::new_concurrent(vec![locale.parse().ok()?]);
+    // Isolation marks around variables show up as boxes in native menus
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(Arc::new(resource)) {
+        log::warn!("Duplicate messages in the {}/{} catalog: {:?}", locale, namespace, errors);
+    }
+    Some(bundle)
+}
+
+fn bundle(locale: &str, namespace: &str) -> Option<Arc<Bundle>> {
+    let mut bundles = BUNDLES.lock().ok()?;
+    bundles
+        .entry((locale.to_string(), namespace.to_string()))
+        .or_insert_with(|| build(locale, namespace).map(Arc::new))
+        .clone()
+}
+
+fn format(bundle: &Bundle, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors).into_owned();
+    if args.is_some() && !errors.is_empty() {
+        log::debug!("Formatting {} failed: {:?}", id, errors);
+    }
+    Some(text)
+}
+
+/// One namespace in one locale, for rendering many strings at once
+pub struct Catalog {
+    locale: String,
+    namespace: String,
+}
+
+impl Catalog {
+    /// `namespace` in the current locale
+    pub fn new(namespace: &str) -> Self {
+        Self {
+            locale: current(),
+            namespace: namespace.to_string(),
+        }
+    }
+
+    fn lookup(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        [self.locale.as_str(), DEFAULT_LOCALE]
+            .into_iter()
+            .find_map(|locale| format(&*bundle(locale, &self.namespace)?, id, args))
+            .unwrap_or_else(|| {
+                log::debug!("Missing {}/{} string {}", self.locale, self.namespace, id);
+                id.to_string()
+            })
+    }
+
+    /// Message `id`
+    pub fn get(&self, id: &str) -> String {
+        self.lookup(id, None)
+    }
+
+    /// Message `id` with variables
+    pub fn get_args<'a>(&self, id: &str, args: &[(&'a str, FluentValue<'a>)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        self.lookup(id, Some(&fluent_args))
+    }
+}
+
+/// Message `id` of `namespace` in the current locale
+pub fn t(namespace: &str, id: &str) -> String {
+    Catalog::new(namespace).get(id)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_locale() -> Result<LocaleInfo, CommandError> {
+    Ok(LocaleInfo {
+        locale: current(),
+        configured: configured(),
+        system: sys_locale::get_locale(),
+        available: CATALOGS.iter().map(|(locale, _)| locale.to_string()).collect(),
+    })
+}
+
+/// Every message of `ns` in the current locale, or in `locale` when given,
+/// with English filling the gaps of a partial translation
+#[tauri::command]
+pub fn get_locale_strings(ns: String, locale: Option<String>) -> Result<LocaleStrings, CommandError> {
+    if !NAMESPACES.contains(&ns.as_str()) {
+        return Err(CommandError::not_found(format!("Unknown string namespace: {}", ns)));
+    }
+    let locale = match locale {
+        Some(locale) => negotiate(Some(&locale)),
+        None => current(),
+    };
+
+    let mut strings = BTreeMap::new();
+    for catalog_locale in [DEFAULT_LOCALE, locale.as_str()] {
+        let Some(bundle) = bundle(catalog_locale, &ns) else {
+            continue;
+        };
+        let Some(resource) = source(catalog_locale, &ns).and_then(|s| FluentResource::try_new(s.to_string()).ok())
+        else {
+            continue;
+        };
+        for entry in resource.entries() {
+            if let ast::Entry::Message(message) = entry {
+                // Without arguments, variables render as `{$name}`
+                if let Some(text) = format(&bundle, message.id.name, None) {
+                    strings.insert(message.id.name.to_string(), text);
+                }
+            }
+        }
+    }
+
+    Ok(LocaleStrings {
+        locale,
+        namespace: ns,
+        strings,
+    })
+}
+
+/// Use `locale` for backend strings, or follow the OS again with `None`.
+/// The tray tooltip switches at once; the frontend rebuilds the menu on
+/// `locale:changed`.
+#[tauri::command]
+pub fn set_locale(app: AppHandle, locale: Option<String>) -> Result<String, CommandError> {
+    let locale = locale.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if let Some(requested) = &locale {
+        if requested.replace('_', "-").parse::<LanguageIdentifier>().is_err() {
+            return Err(CommandError::invalid_input(format!("Invalid locale: {}", requested)));
+        }
+    }
+
+    let mut config = config::get_config()?;
+    config.ui.locale = locale;
+    config::set_config(config)?;
+
+    let current = current();
+    log::info!("Backend strings now in {}", current);
+    crate::tray::set_restart_pending_indicator(&app, crate::gateway::config_sync::is_restart_pending());
+    let _ = app.emit("locale:changed", &current);
+    Ok(current)
+}
@@ -2,5 +2,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if helix_desktop_lib::is_mcp_mode() {
+        return helix_desktop_lib::run_mcp();
+    }
     helix_desktop_lib::run()
 }
@@ -0,0 +1,6 @@
+// Helix Desktop - MCP Module (Model Context Protocol server over stdio)
+
+pub mod protocol;
+pub mod resources;
+pub mod server;
+pub mod tools;
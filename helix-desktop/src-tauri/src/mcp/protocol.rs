@@ -0,0 +1,65 @@
+// MCP wire format - JSON-RPC 2.0 messages, one per line
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// MCP revision this server implements
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// An incoming request, or a notification when `id` is absent
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn parse_error(message: impl ToString) -> Self {
+        Self { code: -32700, message: message.to_string() }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self { code: -32601, message: format!("Method not found: {}", method) }
+    }
+
+    pub fn invalid_params(message: impl ToString) -> Self {
+        Self { code: -32602, message: message.to_string() }
+    }
+
+    pub fn internal(message: impl ToString) -> Self {
+        Self { code: -32603, message: message.to_string() }
+    }
+
+    pub fn resource_not_found(uri: &str) -> Self {
+        Self { code: -32002, message: format!("Resource not found: {}", uri) }
+    }
+}
+
+pub fn success(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+pub fn failure(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// A string argument from tool or resource params
+pub fn str_param<'a>(params: &'a Value, key: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::invalid_params(format!("Missing '{}'", key)))
+}
@@ -0,0 +1,57 @@
+// MCP resources - the soul file and each psychology layer, read-only
+
+use serde_json::{json, Value};
+
+use super::protocol::{str_param, RpcError};
+use crate::commands::{psychology, ErrorCode};
+
+const SOUL_URI: &str = "helix://soul";
+const LAYER_URI_PREFIX: &str = "helix://layers/";
+
+pub fn list() -> Result<Value, RpcError> {
+    let mut resources = vec![json!({
+        "uri": SOUL_URI,
+        "name": "Helix Soul",
+        "description": "HELIX_SOUL.md, the narrative core",
+        "mimeType": "text/markdown",
+    })];
+
+    // Layers backed by files; integration is scripts only
+    for layer in psychology::get_layer_status().map_err(RpcError::internal)? {
+        if layer.total_files == 0 {
+            continue;
+        }
+        resources.push(json!({
+            "uri": format!("{}{}", LAYER_URI_PREFIX, layer.id),
+            "name": layer.name,
+            "mimeType": "application/json",
+        }));
+    }
+
+    Ok(json!({ "resources": resources }))
+}
+
+pub fn read(params: &Value) -> Result<Value, RpcError> {
+    let uri = str_param(params, "uri")?;
+
+    let (mime_type, text) = if uri == SOUL_URI {
+        let soul = psychology::get_soul().map_err(RpcError::internal)?;
+        ("text/markdown", soul.content)
+    } else if let Some(layer) = uri.strip_prefix(LAYER_URI_PREFIX) {
+        let layer = psychology::get_layer(layer.to_string()).map_err(|e| {
+            if e.code == ErrorCode::NotFound {
+                RpcError::resource_not_found(uri)
+            } else {
+                RpcError::internal(e)
+            }
+        })?;
+        let text = serde_json::to_string_pretty(&layer.data).map_err(RpcError::internal)?;
+        ("application/json", text)
+    } else {
+        return Err(RpcError::resource_not_found(uri));
+    };
+
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }],
+    }))
+}
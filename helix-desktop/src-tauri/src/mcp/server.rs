@@ -0,0 +1,77 @@
+// MCP server - `helix-desktop --mcp` serves the active account over stdio
+//
+// MCP clients (Claude Desktop and others) launch the app binary with --mcp;
+// no window opens. stdout carries protocol messages only.
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use super::protocol::{self, Request, RpcError, PROTOCOL_VERSION};
+use super::{resources, tools};
+use crate::commands::CommandError;
+
+/// Command line flag that selects MCP mode
+pub const MCP_FLAG: &str = "--mcp";
+
+async fn handle(method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {}, "resources": {} },
+            "serverInfo": { "name": "helix", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools::definitions() })),
+        "tools/call" => tools::call(params).await,
+        "resources/list" => resources::list(),
+        "resources/read" => resources::read(params),
+        other => Err(RpcError::method_not_found(other)),
+    }
+}
+
+/// Serve requests from stdin until it closes
+pub async fn serve_stdio() -> std::io::Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let result = handle(&request.method, &request.params).await;
+                // Notifications get no response
+                request.id.map(|id| match result {
+                    Ok(value) => protocol::success(id, value),
+                    Err(e) => protocol::failure(id, e),
+                })
+            }
+            Err(e) => Some(protocol::failure(Value::Null, RpcError::parse_error(e))),
+        };
+
+        if let Some(response) = response {
+            let mut out = response.to_string();
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Client config snippet (Claude Desktop's `mcpServers` format) for this install
+#[tauri::command]
+pub fn get_mcp_config() -> Result<Value, CommandError> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate executable: {}", e))?;
+    Ok(json!({
+        "mcpServers": {
+            "helix": {
+                "command": exe.display().to_string(),
+                "args": [MCP_FLAG],
+            }
+        }
+    }))
+}
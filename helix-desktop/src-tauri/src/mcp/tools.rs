@@ -0,0 +1,235 @@
+// MCP tools - memory search, layer reads, context bundles and skill execution
+//
+// Skill runs go through the approval queue (policy/skill_approvals.rs):
+// execute_skill waits a while for the user to decide, then hands back the
+// run id for get_skill_run. Failures while running a tool are returned as `isError` results so the
+// client's model can see them; malformed calls are JSON-RPC errors.
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::protocol::{str_param, RpcError};
+use crate::commands::{auth, psychology};
+use crate::policy::skill_approvals::{self, RunStatus, SkillRun};
+use crate::privacy::residency::{self, DataFeature};
+use crate::psychology::context;
+
+const DEFAULT_SEARCH_LIMIT: u64 = 10;
+const MAX_SEARCH_LIMIT: u64 = 50;
+/// Search terms beyond this are ignored
+const MAX_SEARCH_TERMS: usize = 5;
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+/// How long execute_skill waits for the user to approve a run
+const APPROVAL_WAIT: Duration = Duration::from_secs(120);
+
+pub fn definitions() -> Value {
+    json!([
+        {
+            "name": "search_memories",
+            "description": "Search the user's Helix memories by keywords. Every term must appear in the memory. Newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Keywords to search for" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "read_layer",
+            "description": "Read one psychology layer as JSON: narrative, emotional, relational, prospective, transformation or purpose.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "layer": { "type": "string" }
+                },
+                "required": ["layer"]
+            }
+        },
+        {
+            "name": "read_soul",
+            "description": "Read HELIX_SOUL.md, the narrative core of the Helix identity.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "build_context",
+            "description": "Context about the user for a persona (soul sections, relevant memories, active goals, the recent session) packed into a token budget, as markdown.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "persona": { "type": "string", "description": "Who the context is for, e.g. an agent's role" },
+                    "budget_tokens": { "type": "integer", "minimum": 256 },
+                    "query": { "type": "string", "description": "The task at hand, to pick relevant memories" }
+                },
+                "required": ["persona"]
+            }
+        },
+        {
+            "name": "execute_skill",
+            "description": "Ask to run an installed Helix skill in the sandbox. The user approves the run in Helix Desktop unless it is auto-approved; returns the output, or the run id to check with get_skill_run while it waits.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "skill_id": { "type": "string", "format": "uuid" },
+                    "input": { "type": "object" },
+                    "agent": { "type": "string", "description": "Name of the requesting agent, shown to the user" }
+                },
+                "required": ["skill_id"]
+            }
+        },
+        {
+            "name": "get_skill_run",
+            "description": "Status of a skill run requested with execute_skill, with its output once it completed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "run_id": { "type": "string" }
+                },
+                "required": ["run_id"]
+            }
+        }
+    ])
+}
+
+fn tool_result(result: Result<String, String>) -> Value {
+    let (text, is_error) = match result {
+        Ok(text) => (text, false),
+        Err(e) => (e, true),
+    };
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+pub async fn call(params: &Value) -> Result<Value, RpcError> {
+    let name = str_param(params, "name")?;
+    let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+    let result = match name {
+        "search_memories" => {
+            let query = str_param(&args, "query")?;
+            let limit = args
+                .get("limit")
+                .and_then(|l| l.as_u64())
+                .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                .clamp(1, MAX_SEARCH_LIMIT);
+            search_memories(query, limit).await
+        }
+        "read_layer" => {
+            let layer = str_param(&args, "layer")?;
+            psychology::get_layer(layer.to_string())
+                .map_err(String::from)
+                .and_then(|l| pretty(&l.data))
+        }
+        "read_soul" => psychology::get_soul().map(|soul| soul.content).map_err(String::from),
+        "build_context" => {
+            let persona = str_param(&args, "persona")?;
+            let budget = args
+                .get("budget_tokens")
+                .and_then(|b| b.as_u64())
+                .map_or(context::DEFAULT_BUDGET_TOKENS, |b| b as usize);
+            let query = args.get("query").and_then(|q| q.as_str());
+            context::build_bundle(persona, budget, query, false)
+                .await
+                .map(|bundle| bundle.text)
+                .map_err(String::from)
+        }
+        "execute_skill" => {
+            let skill_id = str_param(&args, "skill_id")?;
+            let input = args.get("input").cloned().unwrap_or_else(|| json!({}));
+            let agent = args.get("agent").and_then(|a| a.as_str()).map(String::from);
+            execute_skill(skill_id, input, agent).await
+        }
+        "get_skill_run" => {
+            let run_id = str_param(&args, "run_id")?;
+            skill_approvals::load(run_id).map_err(String::from).and_then(|run| describe_run(&run))
+        }
+        other => return Err(RpcError::invalid_params(format!("Unknown tool: {}", other))),
+    };
+
+    Ok(tool_result(result))
+}
+
+fn pretty(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Memories of the active account whose content contains every term
+async fn search_memories(query: &str, limit: u64) -> Result<String, String> {
+    residency::require(DataFeature::Sync).map_err(|e| e.message)?;
+    let user_id = crate::accounts::profiles::active_user_id()
+        .ok_or("No active Helix account; sign in to Helix Desktop first")?;
+    let supabase_url = auth::get_supabase_url()?;
+
+    let mut filters = vec![
+        ("select", "id,type,content,emotional_valence,created_at".to_string()),
+        ("user_id", format!("eq.{}", user_id)),
+        ("order", "created_at.desc".to_string()),
+        ("limit", limit.to_string()),
+    ];
+    // PostgREST wildcards; strip the user's own so terms stay literal
+    let terms = query
+        .split_whitespace()
+        .map(|t| t.replace(['*', '%', ',', '(', ')'], ""))
+        .filter(|t| !t.is_empty())
+        .take(MAX_SEARCH_TERMS);
+    for term in terms {
+        filters.push(("content", format!("ilike.*{}*", term)));
+    }
+
+    // As the user, so row-level security scopes the search as well
+    let request = client()?.get(format!("{}/rest/v1/memories", supabase_url));
+    let (request, _) = crate::accounts::session::authorize(request).await?;
+    let response = request
+        .query(&filters)
+        .send()
+        .await
+        .map_err(|e| format!("Memory search failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Memory search failed: HTTP {}", response.status()));
+    }
+
+    let memories: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid memory search response: {}", e))?;
+    if memories.as_array().is_some_and(|m| m.is_empty()) {
+        return Ok(format!("No memories match '{}'", query));
+    }
+    pretty(&memories)
+}
+
+/// Queue the run and wait a while for it to be approved and finish
+async fn execute_skill(skill_id: &str, input: Value, agent: Option<String>) -> Result<String, String> {
+    let run = skill_approvals::request(skill_id, input, agent, "mcp")
+        .await
+        .map_err(String::from)?;
+    let run = skill_approvals::wait(&run.id, APPROVAL_WAIT).await.map_err(String::from)?;
+    describe_run(&run)
+}
+
+fn describe_run(run: &SkillRun) -> Result<String, String> {
+    match run.status {
+        RunStatus::Completed => pretty(run.output.as_ref().unwrap_or(&Value::Null)),
+        RunStatus::Failed => Err(run.error.clone().unwrap_or_else(|| "Skill failed".to_string())),
+        RunStatus::Rejected => Err(format!("The user rejected skill run {}", run.id)),
+        RunStatus::Pending if run.auto_approved => Ok(format!(
+            "Skill run {} is auto-approved and runs once Helix Desktop is open; check it later with get_skill_run",
+            run.id
+        )),
+        RunStatus::Pending => Ok(format!(
+            "Skill run {} is waiting for the user's approval in Helix Desktop; check it later with get_skill_run",
+            run.id
+        )),
+        RunStatus::Running => Ok(format!("Skill run {} is running; check it later with get_skill_run", run.id)),
+    }
+}
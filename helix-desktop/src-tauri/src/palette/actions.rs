@@ -0,0 +1,338 @@
+// Command palette actions
+//
+// The catalog is rebuilt on every query from live state: gateway actions
+// depend on whether it is running, and there is one entry per psychology
+// layer, per scheduled job and per action a gateway agent or skill
+// declared (gateway/actions.rs). Ids are stable ("gateway.start",
+// "layer.open:<layer>", "job.trigger:<job id>", "agent.action:<action id>")
+// so the frontend can keep a recent-actions list across sessions. Declared
+// actions carry their params schema; the palette or voice intent asks for
+// the params and passes them to execute_action, which validates them
+// (voice intents are resolved in intent.rs).
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::fuzzy;
+use crate::commands::{gateway, psychology, scheduler, CommandError};
+use crate::gateway::actions::{self as gateway_actions, GatewayAction};
+
+/// Results returned when the query doesn't set a limit
+const DEFAULT_LIMIT: usize = 20;
+
+const LAYER_OPEN_PREFIX: &str = "layer.open:";
+const JOB_TRIGGER_PREFIX: &str = "job.trigger:";
+const AGENT_ACTION_PREFIX: &str = "agent.action:";
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionCategory {
+    Gateway,
+    Psychology,
+    Layer,
+    Scheduler,
+    /// Declared by a gateway agent or skill
+    Agent,
+}
+
+/// An action the palette can run
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteAction {
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub category: ActionCategory,
+    /// Extra words matched by the search but not displayed
+    pub keywords: Vec<String>,
+    /// Whether running it starts a background task
+    pub background: bool,
+    /// JSON Schema of the params it needs, for actions that take any
+    pub params: Option<Value>,
+}
+
+/// A catalog entry with its match score, best first
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedAction {
+    #[serde(flatten)]
+    pub action: PaletteAction,
+    pub score: i64,
+}
+
+/// What running an action did
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionOutcome {
+    pub id: String,
+    pub message: String,
+    /// Frontend route to open, for navigation actions
+    pub route: Option<String>,
+    /// Background task id, see tasks/manager.rs
+    pub task_id: Option<String>,
+}
+
+impl PaletteAction {
+    fn new(id: impl Into<String>, title: impl Into<String>, category: ActionCategory) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            subtitle: None,
+            category,
+            keywords: Vec::new(),
+            background: false,
+            params: None,
+        }
+    }
+
+    fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.keywords = keywords.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    fn background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    fn search_text(&self) -> String {
+        let mut text = self.title.clone();
+        for keyword in &self.keywords {
+            text.push(' ');
+            text.push_str(keyword);
+        }
+        text
+    }
+}
+
+/// All actions available right now
+fn catalog() -> Vec<PaletteAction> {
+    let mut actions = Vec::new();
+
+    let running = gateway::gateway_status(None).map(|s| s.running).unwrap_or(false);
+    if running {
+        actions.push(
+            PaletteAction::new("gateway.stop", "Stop gateway", ActionCategory::Gateway)
+                .keywords(&["shutdown", "kill", "server"]),
+        );
+        actions.push(
+            PaletteAction::new("gateway.restart", "Restart gateway", ActionCategory::Gateway)
+                .keywords(&["reload", "server"]),
+        );
+    } else {
+        actions.push(
+            PaletteAction::new("gateway.start", "Start gateway", ActionCategory::Gateway)
+                .keywords(&["launch", "run", "server"]),
+        );
+    }
+
+    actions.push(
+        PaletteAction::new("psychology.synthesis", "Run synthesis", ActionCategory::Psychology)
+            .subtitle("Synthesize recent memories into the psychology layers")
+            .keywords(&["integration", "memory"])
+            .background(),
+    );
+    actions.push(
+        PaletteAction::new("psychology.decay", "Run memory decay", ActionCategory::Psychology)
+            .subtitle("Fade old, low-salience memories")
+            .keywords(&["fadeout", "forget", "memory"])
+            .background(),
+    );
+    actions.push(
+        PaletteAction::new("psychology.decay_preview", "Preview memory decay", ActionCategory::Psychology)
+            .subtitle("Dry run; nothing is changed")
+            .keywords(&["fadeout", "forget", "memory", "dry run"])
+            .background(),
+    );
+
+    if let Ok(layers) = psychology::get_layer_status() {
+        for layer in layers {
+            actions.push(
+                PaletteAction::new(
+                    format!("{}{}", LAYER_OPEN_PREFIX, layer.id),
+                    format!("Open {}", layer.name),
+                    ActionCategory::Layer,
+                )
+                .subtitle(format!("Layer status: {}", layer.status))
+                .keywords(&["psychology", "layer", &layer.id]),
+            );
+        }
+    }
+
+    if let Ok(jobs) = scheduler::get_scheduled_jobs() {
+        for job in jobs {
+            let label = job_label(&job.job_type);
+            actions.push(
+                PaletteAction::new(
+                    format!("{}{}", JOB_TRIGGER_PREFIX, job.id),
+                    format!("Trigger {} job", label),
+                    ActionCategory::Scheduler,
+                )
+                .subtitle(format!(
+                    "{} ({})",
+                    if job.is_one_shot() { "once" } else { &job.cron_expression },
+                    status_label(&job.status)
+                ))
+                .keywords(&["scheduler", "run", "job"]),
+            );
+        }
+    }
+
+    if let Ok(declared) = gateway_actions::list() {
+        actions.extend(declared.into_iter().map(declared_action));
+    }
+
+    actions
+}
+
+/// The palette entry of an action a gateway agent or skill declared
+pub(super) fn declared_action(declared: GatewayAction) -> PaletteAction {
+    let params = declared.needs_params().then(|| declared.params.clone());
+    let mut keywords = vec![declared.owner.clone(), declared.name.clone()];
+    keywords.extend(declared.keywords.iter().cloned());
+    let subtitle = declared
+        .description
+        .unwrap_or_else(|| format!("{} {}", declared.source.as_str(), declared.owner));
+    PaletteAction {
+        subtitle: Some(subtitle),
+        keywords,
+        params,
+        ..PaletteAction::new(
+            format!("{}{}", AGENT_ACTION_PREFIX, declared.id),
+            declared.title,
+            ActionCategory::Agent,
+        )
+    }
+}
+
+/// Validate `params` and run the declared action `action_id`
+pub(super) async fn run_declared(action_id: &str, params: Option<Value>) -> Result<ActionOutcome, CommandError> {
+    let declared = gateway_actions::get(action_id)?;
+    gateway_actions::invoke(action_id, params).await?;
+    Ok(ActionOutcome {
+        id: format!("{}{}", AGENT_ACTION_PREFIX, declared.id),
+        message: format!("Sent {} to {}", declared.title, declared.owner),
+        route: None,
+        task_id: None,
+    })
+}
+
+/// "full_integration" -> "full integration"
+fn job_label(job_type: &scheduler::JobType) -> String {
+    serde_json::to_value(job_type)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.replace('_', " ")))
+        .unwrap_or_else(|| format!("{:?}", job_type))
+}
+
+fn status_label(status: &scheduler::JobStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Rank `actions` against `search`, dropping non-matches. Ties keep
+/// catalog order, so an empty search lists everything as built.
+fn rank(actions: Vec<PaletteAction>, search: &str, limit: usize) -> Vec<RankedAction> {
+    let mut ranked: Vec<RankedAction> = actions
+        .into_iter()
+        .filter_map(|action| {
+            fuzzy::score(search, &action.search_text()).map(|score| RankedAction { action, score })
+        })
+        .collect();
+
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.score));
+    ranked.truncate(limit);
+    ranked
+}
+
+// Tauri commands
+
+/// Search the actions the palette can run, best match first
+#[tauri::command]
+pub fn query_actions(search: String, limit: Option<usize>) -> Result<Vec<RankedAction>, CommandError> {
+    Ok(rank(catalog(), &search, limit.unwrap_or(DEFAULT_LIMIT)))
+}
+
+/// Run a palette action by id
+#[tauri::command]
+pub async fn execute_action(
+    app: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    id: String,
+    params: Option<Value>,
+) -> Result<ActionOutcome, CommandError> {
+    let outcome = |message: String| ActionOutcome {
+        id: id.clone(),
+        message,
+        route: None,
+        task_id: None,
+    };
+
+    if let Some(layer) = id.strip_prefix(LAYER_OPEN_PREFIX) {
+        let layers = psychology::get_layer_status()?;
+        let layer = layers
+            .iter()
+            .find(|l| l.id == layer)
+            .ok_or_else(|| CommandError::not_found(format!("Unknown layer: {}", layer)))?;
+
+        return Ok(ActionOutcome {
+            route: Some(format!("/psychology/{}", layer.id)),
+            ..outcome(format!("Opening {}", layer.name))
+        });
+    }
+
+    if let Some(action_id) = id.strip_prefix(AGENT_ACTION_PREFIX) {
+        return run_declared(action_id, params).await;
+    }
+
+    if let Some(job_id) = id.strip_prefix(JOB_TRIGGER_PREFIX) {
+        let job = scheduler::trigger_job(job_id.to_string()).await?;
+        if job.status == scheduler::JobStatus::Deferred {
+            let reason = job.runs.last().and_then(|run| run.error.clone());
+            return Ok(outcome(match reason {
+                Some(reason) => format!("Deferred {} job: {}", job_label(&job.job_type), reason),
+                None => format!("Deferred {} job", job_label(&job.job_type)),
+            }));
+        }
+        return Ok(outcome(format!("Triggered {} job", job_label(&job.job_type))));
+    }
+
+    match id.as_str() {
+        "gateway.start" => {
+            let started = gateway::start_gateway(app, None).await?;
+            Ok(outcome(format!("Gateway started on port {}", started.port)))
+        }
+        "gateway.stop" => {
+            gateway::stop_gateway(app, None)?;
+            Ok(outcome("Gateway stopped".to_string()))
+        }
+        "gateway.restart" => {
+            gateway::stop_gateway(app.clone(), None)?;
+            let started = gateway::start_gateway(app, None).await?;
+            Ok(outcome(format!("Gateway restarted on port {}", started.port)))
+        }
+        "psychology.synthesis" => {
+            let task_id = psychology::run_synthesis(app, state, false).await?;
+            Ok(ActionOutcome {
+                task_id: Some(task_id),
+                ..outcome("Synthesis started".to_string())
+            })
+        }
+        "psychology.decay" | "psychology.decay_preview" => {
+            let dry_run = id == "psychology.decay_preview";
+            let task_id = psychology::run_decay(app, state, dry_run).await?;
+            let message = if dry_run { "Decay preview started" } else { "Memory decay started" };
+            Ok(ActionOutcome {
+                task_id: Some(task_id),
+                ..outcome(message.to_string())
+            })
+        }
+        _ => Err(CommandError::not_found(format!("Unknown action: {}", id))
+            .with_details(serde_json::json!({ "id": id }))),
+    }
+}
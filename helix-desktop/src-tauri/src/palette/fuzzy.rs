@@ -0,0 +1,79 @@
+// Fuzzy matching for the command palette
+//
+// Each whitespace-separated query term must match the candidate, either as
+// a substring or as an in-order subsequence of characters. Substrings beat
+// subsequences; matches at the start of a word and runs of consecutive
+// characters score higher, gaps score lower.
+
+const SUBSTRING_BASE: i64 = 100;
+const CHAR_SCORE: i64 = 10;
+const CONSECUTIVE_BONUS: i64 = 15;
+const WORD_START_BONUS: i64 = 20;
+const LEADING_BONUS: i64 = 30;
+const GAP_PENALTY: i64 = 1;
+
+/// Score `candidate` against `query`; `None` if any term doesn't match.
+/// An empty query matches everything with a score of 0.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let haystack: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    query
+        .split_whitespace()
+        .map(|term| {
+            let needle: Vec<char> = term.to_lowercase().chars().collect();
+            substring_score(&needle, &haystack).or_else(|| subsequence_score(&needle, &haystack))
+        })
+        .sum()
+}
+
+fn is_word_start(haystack: &[char], index: usize) -> bool {
+    index == 0 || !haystack[index - 1].is_alphanumeric()
+}
+
+/// Best-placed contiguous occurrence of `needle`
+fn substring_score(needle: &[char], haystack: &[char]) -> Option<i64> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    (0..=haystack.len() - needle.len())
+        .filter(|&start| haystack[start..start + needle.len()] == *needle)
+        .map(|start| {
+            let mut score = SUBSTRING_BASE + CHAR_SCORE * needle.len() as i64;
+            if is_word_start(haystack, start) {
+                score += WORD_START_BONUS;
+            }
+            if start == 0 {
+                score += LEADING_BONUS;
+            }
+            score
+        })
+        .max()
+}
+
+/// Greedy left-to-right subsequence match
+fn subsequence_score(needle: &[char], haystack: &[char]) -> Option<i64> {
+    let mut score = 0;
+    let mut last_match: Option<usize> = None;
+    let mut position = 0;
+
+    for c in needle {
+        let offset = haystack[position..].iter().position(|h| h == c)?;
+        let index = position + offset;
+
+        score += CHAR_SCORE;
+        match last_match {
+            Some(last) if index == last + 1 => score += CONSECUTIVE_BONUS,
+            Some(last) => score -= GAP_PENALTY * (index - last - 1) as i64,
+            None => {}
+        }
+        if is_word_start(haystack, index) {
+            score += WORD_START_BONUS;
+        }
+
+        last_match = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,159 @@
+// Voice intents - a spoken request resolved to a declared gateway action
+//
+// Only the actions gateway agents and skills declared (gateway/actions.rs)
+// are candidates; the palette's own actions stay behind the palette. A
+// transcript names an action when it says every word of the action's title
+// (or of its name), or every word of one of its keywords; anything looser
+// would run actions on chatter meant for the agent. Words match with a
+// suffix ("emails" says "email") and filler words ("please", "the", ...) are
+// ignored; the match saying the most words wins. It runs at once when it
+// needs no params; otherwise it is returned with its params schema so the
+// caller can ask for them and pass them to execute_action. Equally good
+// matches are returned for the caller to choose from, never guessed.
+//
+// The gateway says who spoke with each transcript (talk-mode.transcript):
+// whether it asks for something sensitive (delete, send, pay, ...) and
+// whether an enrolled voice said it. A sensitive request from anyone else
+// is refused before anything runs or is offered, since the palette would
+// run what the caller picks.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::actions::{declared_action, run_declared, ActionOutcome, PaletteAction};
+use crate::commands::CommandError;
+use crate::gateway::actions::{self as gateway_actions, GatewayAction};
+
+/// Shortest word a longer spoken word may extend
+const MIN_STEM_LEN: usize = 4;
+
+/// Words that carry no intent
+const FILLER_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "can", "could", "do", "for", "i", "in", "is", "it", "me", "my", "now", "of", "on",
+    "please", "the", "to", "want", "what", "with", "would", "you",
+];
+
+/// What a transcript resolved to
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VoiceIntent {
+    /// The matching action ran
+    Ran { outcome: ActionOutcome },
+    /// The matching action needs params the transcript doesn't give
+    NeedsParams { action: PaletteAction },
+    /// Several actions match equally well
+    Ambiguous { actions: Vec<PaletteAction> },
+    /// No declared action matches
+    NoMatch,
+    /// A sensitive request from a voice that isn't enrolled; nothing ran
+    Refused { actions: Vec<PaletteAction> },
+}
+
+/// Who said a transcript, as the gateway checked it
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscriptSpeaker {
+    /// Asks for something only an enrolled voice may do
+    pub sensitive: bool,
+    /// An enrolled voice said it
+    pub verified: bool,
+}
+
+impl TranscriptSpeaker {
+    /// A transcript the gateway didn't attribute is treated as sensitive and unverified
+    fn unknown() -> Self {
+        Self {
+            sensitive: true,
+            verified: false,
+        }
+    }
+
+    fn may_run_sensitive(&self) -> bool {
+        !self.sensitive || self.verified
+    }
+}
+
+fn words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Whether the spoken `word` says `term`
+fn says(word: &str, term: &str) -> bool {
+    word == term || (term.len() >= MIN_STEM_LEN && word.starts_with(term))
+}
+
+fn said(spoken: &BTreeSet<String>, term: &str) -> bool {
+    spoken.iter().any(|word| says(word, term))
+}
+
+fn says_all(spoken: &BTreeSet<String>, terms: &BTreeSet<String>) -> bool {
+    !terms.is_empty() && terms.iter().all(|term| said(spoken, term))
+}
+
+/// How well `spoken` names `action`, by the words it matched; None when it doesn't
+fn score(spoken: &BTreeSet<String>, action: &GatewayAction) -> Option<usize> {
+    std::iter::once(&action.title)
+        .chain(std::iter::once(&action.name))
+        .chain(&action.keywords)
+        .map(|phrase| words(phrase))
+        .filter(|terms| says_all(spoken, terms))
+        .map(|terms| terms.len())
+        .max()
+}
+
+/// The declared actions `transcript` names best
+fn best_matches(transcript: &str, actions: Vec<GatewayAction>) -> Vec<GatewayAction> {
+    let spoken = words(transcript);
+    if spoken.is_empty() {
+        return Vec::new();
+    }
+    let scored: Vec<(usize, GatewayAction)> = actions
+        .into_iter()
+        .filter_map(|action| score(&spoken, &action).map(|score| (score, action)))
+        .collect();
+    let Some(best) = scored.iter().map(|(score, _)| *score).max() else {
+        return Vec::new();
+    };
+    scored
+        .into_iter()
+        .filter(|(score, _)| *score == best)
+        .map(|(_, action)| action)
+        .collect()
+}
+
+// Tauri commands
+
+/// Resolve a voice transcript to a declared action and run it if it can
+#[tauri::command]
+pub async fn route_voice_intent(
+    transcript: String,
+    speaker: Option<TranscriptSpeaker>,
+) -> Result<VoiceIntent, CommandError> {
+    let speaker = speaker.unwrap_or_else(TranscriptSpeaker::unknown);
+    let mut matches = best_matches(&transcript, gateway_actions::list()?);
+    if !matches.is_empty() && !speaker.may_run_sensitive() {
+        log::warn!("Voice intent refused: sensitive request from an unverified speaker");
+        return Ok(VoiceIntent::Refused {
+            actions: matches.into_iter().map(declared_action).collect(),
+        });
+    }
+    if matches.len() > 1 {
+        return Ok(VoiceIntent::Ambiguous {
+            actions: matches.into_iter().map(declared_action).collect(),
+        });
+    }
+    let Some(action) = matches.pop() else {
+        return Ok(VoiceIntent::NoMatch);
+    };
+
+    if action.needs_params() {
+        return Ok(VoiceIntent::NeedsParams {
+            action: declared_action(action),
+        });
+    }
+    log::info!("Voice intent resolved to {}", action.id);
+    let outcome = run_declared(&action.id, None).await?;
+    Ok(VoiceIntent::Ran { outcome })
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Command Palette Module (ranked, executable backend actions)
+
+pub mod actions;
+pub mod fuzzy;
+pub mod intent;
@@ -0,0 +1,258 @@
+// Do-not-disturb policy
+//
+// DND is active while a manual override is set (set_dnd), during the
+// configured quiet hours, or while the OS reports a focus mode. While it
+// is active, notifications are suppressed, noisy scheduler jobs are
+// deferred and Discord heartbeats slow down; `quiet_hours` in config
+// switches each of these off individually.
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use super::focus;
+use crate::commands::config::{self, QuietHoursConfig};
+use crate::commands::CommandError;
+
+/// Manual override, in the account's data directory
+const OVERRIDE_FILE: &str = "dnd.json";
+/// How often schedule boundaries and OS focus are re-checked
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+/// Deferral when DND has no known end (OS focus mode)
+const OPEN_ENDED_DEFER_MS: u64 = 30 * 60 * 1000;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// Last status sent to the frontend, to emit only on changes
+static LAST_EMITTED: Mutex<Option<(bool, Option<DndReason>)>> = Mutex::new(None);
+
+/// Why DND is active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DndReason {
+    Manual,
+    QuietHours,
+    OsFocus,
+}
+
+/// Current do-not-disturb state and its effects
+#[derive(Debug, Clone, Serialize)]
+pub struct DndStatus {
+    pub active: bool,
+    pub reason: Option<DndReason>,
+    /// When DND is expected to end (ms); unknown for OS focus mode
+    pub until: Option<u64>,
+    /// Manual override end (ms), if one is set
+    pub manual_until: Option<u64>,
+    pub quiet_hours_enabled: bool,
+    /// OS focus mode; `None` when unsupported or detection is off
+    pub os_focus: Option<bool>,
+    /// Whether notifications should be dropped right now
+    pub suppress_notifications: bool,
+    /// Whether noisy scheduler jobs are deferred right now
+    pub defer_jobs: bool,
+    /// Discord heartbeat interval (ms) to use right now
+    pub heartbeat_interval: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DndOverride {
+    until: Option<u64>,
+}
+
+fn override_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(OVERRIDE_FILE))
+}
+
+fn load_override() -> DndOverride {
+    override_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn now_ms() -> u64 {
+    Local::now().timestamp_millis().max(0) as u64
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// End of the quiet-hours window containing `now`, if any. A window whose
+/// end is not after its start runs past midnight; `days` filters on the
+/// day the window starts.
+fn quiet_window_end(config: &QuietHoursConfig, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let start = parse_time(&config.start)?;
+    let end = parse_time(&config.end)?;
+    if start == end {
+        return None;
+    }
+
+    let today = now.date();
+    [today.pred_opt()?, today].into_iter().find_map(|day| {
+        let weekday = day.weekday().num_days_from_sunday() as u8;
+        if !config.days.is_empty() && !config.days.contains(&weekday) {
+            return None;
+        }
+
+        let window_start = day.and_time(start);
+        let window_end = if end > start {
+            day.and_time(end)
+        } else {
+            (day + ChronoDuration::days(1)).and_time(end)
+        };
+        (window_start <= now && now < window_end).then_some(window_end)
+    })
+}
+
+fn local_ms(datetime: NaiveDateTime) -> Option<u64> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|dt| dt.timestamp_millis().max(0) as u64)
+}
+
+/// Evaluate the policy now
+pub fn status() -> DndStatus {
+    let helix_config = config::get_config().ok();
+    let quiet = helix_config
+        .as_ref()
+        .map(|c| c.quiet_hours.clone())
+        .unwrap_or_default();
+    let normal_heartbeat = helix_config
+        .as_ref()
+        .map(|c| c.discord.heartbeat_interval)
+        .unwrap_or(60000);
+
+    let now = now_ms();
+    let manual_until = load_override().until.filter(|until| *until > now);
+
+    let quiet_until = if quiet.enabled {
+        quiet_window_end(&quiet, Local::now().naive_local()).and_then(local_ms)
+    } else {
+        None
+    };
+
+    let os_focus = if quiet.detect_os_focus {
+        focus::os_focus_active()
+    } else {
+        None
+    };
+
+    let (reason, until) = if manual_until.is_some() {
+        (Some(DndReason::Manual), manual_until)
+    } else if quiet_until.is_some() {
+        (Some(DndReason::QuietHours), quiet_until)
+    } else if os_focus == Some(true) {
+        (Some(DndReason::OsFocus), None)
+    } else {
+        (None, None)
+    };
+    let active = reason.is_some();
+
+    DndStatus {
+        active,
+        reason,
+        until,
+        manual_until,
+        quiet_hours_enabled: quiet.enabled,
+        os_focus,
+        suppress_notifications: active && quiet.suppress_notifications,
+        defer_jobs: active && quiet.defer_jobs,
+        // Never speed heartbeats up
+        heartbeat_interval: if active {
+            quiet.heartbeat_interval.max(normal_heartbeat)
+        } else {
+            normal_heartbeat
+        },
+    }
+}
+
+/// When a noisy job should run instead of now (ms), or `None` to run it
+pub fn defer_until() -> Option<u64> {
+    let status = status();
+    if !status.defer_jobs {
+        return None;
+    }
+    Some(status.until.unwrap_or_else(|| now_ms() + OPEN_ENDED_DEFER_MS))
+}
+
+/// Emit dnd:changed if DND turned on or off or its reason changed, or
+/// unconditionally with `force`; turning on or off is also announced
+fn emit_status(app: &AppHandle, status: &DndStatus, force: bool) {
+    let key = (status.active, status.reason);
+    let previous = match LAST_EMITTED.lock() {
+        Ok(mut last) => last.replace(key),
+        Err(_) => Some(key),
+    };
+
+    if previous != Some(key) || force {
+        let _ = app.emit("dnd:changed", status);
+    }
+    // Not for the first status after launch
+    if previous.is_some_and(|(was_active, _)| was_active != status.active) {
+        crate::accessibility::announce::dnd(app, status.active);
+        crate::integrations::presence::notify_changed();
+    }
+}
+
+/// Watch schedule boundaries and OS focus mode (once per process)
+pub fn start(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let status = tauri::async_runtime::spawn_blocking(status).await;
+            if let Ok(status) = status {
+                emit_status(&app, &status, false);
+            }
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
+
+// Tauri commands
+
+/// Current do-not-disturb state
+#[tauri::command]
+pub async fn get_dnd_status() -> Result<DndStatus, CommandError> {
+    // Reads the OS focus state, which can block
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| CommandError::internal(format!("DND status task failed: {}", e)))
+}
+
+/// Turn on do-not-disturb until `until` (ms since epoch), or clear the
+/// manual override with `None`. Quiet hours and OS focus still apply.
+#[tauri::command]
+pub fn set_dnd(app: AppHandle, until: Option<u64>) -> Result<DndStatus, CommandError> {
+    if let Some(until) = until {
+        if until <= now_ms() {
+            return Err(CommandError::invalid_input("DND end time must be in the future")
+                .with_details(serde_json::json!({ "until": until })));
+        }
+    }
+
+    let path = override_path()?;
+    if until.is_some() {
+        let json = serde_json::to_string_pretty(&DndOverride { until })
+            .map_err(|e| format!("Failed to serialize DND override: {}", e))?;
+        fs::write(&path, json)
+            .map_err(|e| CommandError::from_io("Failed to save DND override", e))?;
+    } else if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| CommandError::from_io("Failed to clear DND override", e))?;
+    }
+
+    let status = status();
+    emit_status(&app, &status, true);
+    Ok(status)
+}
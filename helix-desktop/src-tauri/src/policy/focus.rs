@@ -0,0 +1,67 @@
+// OS focus detection - whether the system's own do-not-disturb is on
+//
+// Read where the platform exposes it: GNOME's notification banner setting
+// on Linux and the Focus assertions file on macOS (readable only with Full
+// Disk Access). Windows has no public API for Focus Assist, so it is
+// reported as unknown there.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Probes spawn processes or read files; reuse a result this long
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+static CACHE: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+
+/// Whether OS focus mode is on; `None` when it can't be determined
+pub fn os_focus_active() -> Option<bool> {
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((at, value)) = *cache {
+            if at.elapsed() < CACHE_TTL {
+                return value;
+            }
+        }
+    }
+
+    let value = probe();
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((Instant::now(), value));
+    }
+    value
+}
+
+#[cfg(target_os = "linux")]
+fn probe() -> Option<bool> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Banners off is GNOME's do-not-disturb
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "false" => Some(true),
+        "true" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn probe() -> Option<bool> {
+    let path = dirs::home_dir()?.join("Library/DoNotDisturb/DB/Assertions.json");
+    let content = std::fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    // Any active assertion record means a Focus is on
+    let records = value
+        .pointer("/data/0/storeAssertionRecords")
+        .and_then(serde_json::Value::as_array);
+    Some(records.is_some_and(|r| !r.is_empty()))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn probe() -> Option<bool> {
+    None
+}
@@ -0,0 +1,610 @@
+// Soul guardrails for automated psychology writes
+//
+// Synthesis, decay and skill outputs can rewrite psychology files with no
+// one reading the result. Their writes are diffed against the protected
+// parts of the soul first: sections of HELIX_SOUL.md whose heading is in
+// `guardrails.protected_sections` (values, boundaries) and JSON pointers
+// into layer files in `guardrails.protected_paths`. A write that changes
+// one of them is queued for manual approval, or refused outright with
+// `on_violation = "block"`. Every decision on an automated write is
+// appended to <data_dir>/guardrails/decisions.jsonl (paths and section
+// names, never contents). Edits the user makes in the app are not guarded.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::config::{self, GuardrailsConfig};
+use crate::commands::psychology::vault_error;
+use crate::commands::{CommandError, ErrorCode};
+use crate::psychology::{lock, soul_history};
+use crate::vault;
+
+/// Soul document, relative to the data directory
+pub const SOUL_FILE: &str = "soul/HELIX_SOUL.md";
+
+/// Pending writes and the decision log, under the data directory
+pub const GUARDRAILS_DIR: &str = "guardrails";
+const PENDING_DIR: &str = "pending";
+const DECISIONS_FILE: &str = "decisions.jsonl";
+const PENDING_ID_PREFIX: &str = "write-";
+/// Decisions returned when the caller doesn't set a limit
+const DEFAULT_DECISION_LIMIT: usize = 100;
+
+/// Automated writer proposing a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteSource {
+    Synthesis,
+    Decay,
+    Skill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// A protected section or value that a write changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    /// Soul section heading as configured, or "<file>#<pointer>"
+    pub target: String,
+    pub change: ChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionKind {
+    Allowed,
+    Blocked,
+    Queued,
+    Approved,
+    Rejected,
+}
+
+/// One entry in the decision log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decision {
+    /// Pending write id, for queued writes and their resolution
+    pub id: Option<String>,
+    pub timestamp: u64,
+    pub source: WriteSource,
+    pub path: String,
+    pub decision: DecisionKind,
+    pub violations: Vec<Violation>,
+}
+
+/// A write held for manual approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingWrite {
+    pub id: String,
+    pub created_at: u64,
+    pub source: WriteSource,
+    pub path: String,
+    pub violations: Vec<Violation>,
+    /// Proposed file content
+    pub content: String,
+    /// SHA-256 of the file when the write was queued; `None` if it didn't exist
+    pub base_hash: Option<String>,
+}
+
+/// What happened to one file of a write
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WriteOutcome {
+    Written { path: String },
+    Queued { path: String, pending_id: String },
+}
+
+impl Decision {
+    fn new(source: WriteSource, path: &str, decision: DecisionKind, violations: Vec<Violation>) -> Self {
+        Self {
+            id: None,
+            timestamp: now_ms(),
+            source,
+            path: path.to_string(),
+            decision,
+            violations,
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn current_config() -> GuardrailsConfig {
+    config::get_config()
+        .map(|c| c.guardrails)
+        .unwrap_or_default()
+}
+
+fn blocks(config: &GuardrailsConfig) -> bool {
+    config.on_violation.eq_ignore_ascii_case("block")
+}
+
+// Diffing
+
+/// Markdown heading level of `line`, if it is a heading
+pub(crate) fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    ((1..=6).contains(&level) && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// "## V. CORE VALUES" -> "core values", "### 1. Test Walls" -> "test walls"
+pub(crate) fn normalize_heading(heading: &str) -> String {
+    let title = heading.trim_start_matches('#').trim();
+    let title = match title.split_once(". ") {
+        Some((prefix, rest)) if is_ordinal(prefix) => rest,
+        _ => title,
+    };
+    title.trim().to_lowercase()
+}
+
+/// Arabic or Roman section number
+fn is_ordinal(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && (prefix.chars().all(|c| c.is_ascii_digit())
+            || prefix.chars().all(|c| "IVXLCDM".contains(c)))
+}
+
+/// Section bodies with trailing whitespace and blank edges ignored, so
+/// reformatting alone is not a change
+fn normalize_body(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Bodies of the protected sections in a markdown document, keyed by
+/// normalized heading. A section runs until the next heading of the same
+/// or a higher level.
+fn protected_sections(doc: &str, protected: &[String]) -> HashMap<String, String> {
+    let wanted: Vec<String> = protected.iter().map(|h| normalize_heading(h)).collect();
+    let mut sections = HashMap::new();
+    let mut open: Option<(String, usize, Vec<&str>)> = None;
+
+    for line in doc.lines() {
+        if let Some(level) = heading_level(line) {
+            if open.as_ref().is_some_and(|(_, open_level, _)| level <= *open_level) {
+                if let Some((name, _, lines)) = open.take() {
+                    sections.insert(name, normalize_body(&lines));
+                }
+            }
+            if open.is_none() {
+                let name = normalize_heading(line);
+                if wanted.contains(&name) {
+                    open = Some((name, level, Vec::new()));
+                    continue;
+                }
+            }
+        }
+
+        if let Some((_, _, lines)) = open.as_mut() {
+            lines.push(line);
+        }
+    }
+
+    if let Some((name, _, lines)) = open {
+        sections.insert(name, normalize_body(&lines));
+    }
+    sections
+}
+
+fn change_kind<T: PartialEq + ?Sized>(old: Option<&T>, new: Option<&T>) -> Option<ChangeKind> {
+    match (old, new) {
+        (None, None) => None,
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (Some(old), Some(new)) => (old != new).then_some(ChangeKind::Modified),
+    }
+}
+
+/// Protected sections or values that replacing `old` with `new` in `path`
+/// would change. `None` means the file is missing before or deleted after.
+pub fn check(path: &str, old: Option<&str>, new: Option<&str>, config: &GuardrailsConfig) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if path == SOUL_FILE {
+        let sections = |doc: Option<&str>| {
+            doc.map(|doc| protected_sections(doc, &config.protected_sections))
+                .unwrap_or_default()
+        };
+        let (old_sections, new_sections) = (sections(old), sections(new));
+
+        for heading in &config.protected_sections {
+            let key = normalize_heading(heading);
+            if let Some(change) = change_kind(old_sections.get(&key), new_sections.get(&key)) {
+                violations.push(Violation { target: heading.clone(), change });
+            }
+        }
+        return violations;
+    }
+
+    // Unparseable JSON counts as removing every protected value
+    let parse = |doc: Option<&str>| doc.and_then(|doc| serde_json::from_str::<Value>(doc).ok());
+    let (old_json, new_json) = (parse(old), parse(new));
+
+    for entry in &config.protected_paths {
+        let Some((file, pointer)) = entry.split_once('#') else {
+            continue;
+        };
+        if file != path {
+            continue;
+        }
+        let old_value = old_json.as_ref().and_then(|doc| doc.pointer(pointer));
+        let new_value = new_json.as_ref().and_then(|doc| doc.pointer(pointer));
+        if let Some(change) = change_kind(old_value, new_value) {
+            violations.push(Violation { target: entry.clone(), change });
+        }
+    }
+    violations
+}
+
+/// Files with anything protected in them
+fn guarded_files(config: &GuardrailsConfig) -> Vec<String> {
+    let mut files = vec![SOUL_FILE.to_string()];
+    for entry in &config.protected_paths {
+        if let Some((file, _)) = entry.split_once('#') {
+            if !files.iter().any(|f| f == file) {
+                files.push(file.to_string());
+            }
+        }
+    }
+    files
+}
+
+// Storage
+
+fn guardrails_dir() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(GUARDRAILS_DIR))
+}
+
+fn pending_path(id: &str) -> Result<PathBuf, CommandError> {
+    // Ids come from the frontend; only accept ones this module could have made
+    let valid = id
+        .strip_prefix(PENDING_ID_PREFIX)
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid {
+        return Err(unknown_pending(id));
+    }
+    Ok(guardrails_dir()?.join(PENDING_DIR).join(format!("{}.json", id)))
+}
+
+fn unknown_pending(id: &str) -> CommandError {
+    CommandError::not_found(format!("No pending write: {}", id))
+        .with_details(serde_json::json!({ "id": id }))
+}
+
+fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Current content of a file, `None` if it doesn't exist
+fn read_existing(path: &Path) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    vault::read_to_string(path).map(Some)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    vault::write(path, content)
+}
+
+/// Append a decision to the log and tell the frontend about anything that
+/// needs attention
+fn decide(app: &AppHandle, decision: &Decision) -> Result<(), String> {
+    let dir = guardrails_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create guardrails directory: {}", e))?;
+
+    let line = serde_json::to_string(decision)
+        .map_err(|e| format!("Failed to serialize guardrail decision: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(DECISIONS_FILE))
+        .map_err(|e| format!("Failed to open guardrail decision log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write guardrail decision log: {}", e))?;
+
+    if decision.decision != DecisionKind::Allowed {
+        let _ = app.emit("guardrails:decision", decision);
+    }
+    Ok(())
+}
+
+/// Hold a write for approval; returns the pending id
+fn queue(
+    app: &AppHandle,
+    source: WriteSource,
+    path: &str,
+    content: &str,
+    base: Option<&str>,
+    violations: Vec<Violation>,
+) -> Result<String, String> {
+    let id = format!("{}{:016x}", PENDING_ID_PREFIX, rand::random::<u64>());
+    let pending = PendingWrite {
+        id: id.clone(),
+        created_at: now_ms(),
+        source,
+        path: path.to_string(),
+        violations: violations.clone(),
+        content: content.to_string(),
+        base_hash: base.map(content_hash),
+    };
+
+    // Pending content is psychology data, so it goes through the vault too
+    let json = serde_json::to_string_pretty(&pending)
+        .map_err(|e| format!("Failed to serialize pending write: {}", e))?;
+    write_file(&guardrails_dir()?.join(PENDING_DIR).join(format!("{}.json", id)), &json)?;
+
+    let decision = Decision {
+        id: Some(id.clone()),
+        ..Decision::new(source, path, DecisionKind::Queued, violations)
+    };
+    decide(app, &decision)?;
+    Ok(id)
+}
+
+fn load_pending(id: &str) -> Result<PendingWrite, CommandError> {
+    let path = pending_path(id)?;
+    if !path.exists() {
+        return Err(unknown_pending(id));
+    }
+    let content = vault::read_to_string(&path).map_err(|e| vault_error("Failed to read pending write", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::internal(format!("Corrupt pending write {}: {}", id, e)))
+}
+
+// Guarded writes
+
+/// Apply automated writes of `(path, content)` pairs, paths relative to
+/// `helix_dir`. Files that change nothing protected are written and the
+/// rest are queued; with `on_violation = "block"` the whole batch is
+/// refused before anything is written.
+pub fn apply(
+    app: &AppHandle,
+    source: WriteSource,
+    helix_dir: &Path,
+    writes: &[(&str, String)],
+) -> Result<Vec<WriteOutcome>, CommandError> {
+    let _lock = lock::acquire(helix_dir, lock::WRITE_WAIT)?;
+    let config = current_config();
+    let mut reviewed = Vec::new();
+
+    for (path, content) in writes {
+        let current = read_existing(&helix_dir.join(path))
+            .map_err(|e| vault_error(&format!("Failed to read {}", path), e))?;
+        let violations = if config.enabled {
+            check(path, current.as_deref(), Some(content), &config)
+        } else {
+            Vec::new()
+        };
+        reviewed.push((*path, content, current, violations));
+    }
+
+    if blocks(&config) && reviewed.iter().any(|(_, _, _, violations)| !violations.is_empty()) {
+        let mut blocked = Vec::new();
+        for (path, _, _, violations) in reviewed {
+            if violations.is_empty() {
+                continue;
+            }
+            decide(app, &Decision::new(source, path, DecisionKind::Blocked, violations.clone()))?;
+            blocked.push(serde_json::json!({ "path": path, "violations": violations }));
+        }
+        return Err(CommandError::permission_denied("Write would change protected soul sections")
+            .with_details(serde_json::json!({ "blocked": blocked })));
+    }
+
+    let mut outcomes = Vec::new();
+    for (path, content, current, violations) in reviewed {
+        let path_string = path.to_string();
+        if violations.is_empty() {
+            write_file(&helix_dir.join(path), content)
+                .map_err(|e| vault_error(&format!("Failed to write {}", path), e))?;
+            if config.enabled {
+                decide(app, &Decision::new(source, path, DecisionKind::Allowed, Vec::new()))?;
+            }
+            outcomes.push(WriteOutcome::Written { path: path_string });
+        } else {
+            let pending_id = queue(app, source, path, content, current.as_deref(), violations)?;
+            outcomes.push(WriteOutcome::Queued { path: path_string, pending_id });
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Guarded files as they were before a script ran. Scripts write files
+/// themselves, so their changes are reviewed afterwards, when the snapshot
+/// is dropped; that also covers scripts cut off by a timeout or cancel.
+pub struct Snapshot {
+    app: AppHandle,
+    source: WriteSource,
+    helix_dir: PathBuf,
+    config: GuardrailsConfig,
+    files: Vec<(String, Option<String>)>,
+}
+
+impl Snapshot {
+    pub fn capture(app: &AppHandle, source: WriteSource, helix_dir: &Path) -> Self {
+        let config = current_config();
+        let files = if config.enabled {
+            guarded_files(&config)
+                .into_iter()
+                .filter_map(|path| {
+                    let content = read_existing(&helix_dir.join(&path)).ok()?;
+                    Some((path, content))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            app: app.clone(),
+            source,
+            helix_dir: helix_dir.to_path_buf(),
+            config,
+            files,
+        }
+    }
+
+    /// Keep allowed changes; restore files whose changes touch anything
+    /// protected and queue (or drop, when blocking) the script's version
+    fn review(&self) {
+        for (path, before) in &self.files {
+            let after = match read_existing(&self.helix_dir.join(path)) {
+                Ok(after) => after,
+                Err(e) => {
+                    log::warn!("Guardrails could not re-read {}: {}", path, e);
+                    continue;
+                }
+            };
+            if after == *before {
+                continue;
+            }
+
+            if let Err(e) = self.review_change(path, before.as_deref(), after.as_deref()) {
+                log::warn!("Guardrail review of {} failed: {}", path, e);
+            }
+        }
+    }
+
+    fn review_change(
+        &self,
+        path: &str,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<(), String> {
+        let (app, source) = (&self.app, self.source);
+        let violations = check(path, before, after, &self.config);
+        if violations.is_empty() {
+            return decide(app, &Decision::new(source, path, DecisionKind::Allowed, Vec::new()));
+        }
+
+        let full_path = self.helix_dir.join(path);
+        match before {
+            Some(before) => write_file(&full_path, before)?,
+            None => fs::remove_file(&full_path).map_err(|e| format!("Failed to remove {}: {}", path, e))?,
+        }
+        log::warn!("Rolled back {:?} write to {}: protected content changed", source, path);
+
+        match after {
+            Some(after) if !blocks(&self.config) => {
+                queue(app, source, path, after, before, violations).map(|_| ())
+            }
+            // Deleting a guarded file is never queued
+            _ => decide(app, &Decision::new(source, path, DecisionKind::Blocked, violations)),
+        }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        self.review();
+    }
+}
+
+// Tauri commands
+
+/// Writes waiting for approval, oldest first
+#[tauri::command]
+pub fn list_pending_writes() -> Result<Vec<PendingWrite>, CommandError> {
+    let dir = guardrails_dir()?.join(PENDING_DIR);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut pending = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let content = vault::read_to_string(&path).map_err(|e| vault_error("Failed to read pending write", e))?;
+        match serde_json::from_str::<PendingWrite>(&content) {
+            Ok(write) => pending.push(write),
+            Err(e) => log::warn!("Skipping corrupt pending write {:?}: {}", path, e),
+        }
+    }
+
+    pending.sort_by_key(|write| write.created_at);
+    Ok(pending)
+}
+
+/// Apply a queued write. Fails if the file changed since it was queued.
+#[tauri::command]
+pub fn approve_pending_write(app: AppHandle, id: String) -> Result<Decision, CommandError> {
+    let pending = load_pending(&id)?;
+    let helix_dir = crate::accounts::data_dir()?;
+    let full_path = helix_dir.join(&pending.path);
+    let _lock = lock::acquire(&helix_dir, lock::WRITE_WAIT)?;
+
+    let current = read_existing(&full_path).map_err(|e| vault_error("Failed to read target file", e))?;
+    if current.as_deref().map(content_hash) != pending.base_hash {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            format!("{} changed after this write was queued; reject it instead", pending.path),
+        )
+        .with_details(serde_json::json!({ "id": id, "path": pending.path })));
+    }
+
+    write_file(&full_path, &pending.content).map_err(|e| vault_error("Failed to apply pending write", e))?;
+    if pending.path == SOUL_FILE {
+        if let Err(e) = soul_history::record(&helix_dir, current.as_deref(), &pending.content) {
+            log::warn!("Failed to record soul version: {}", e);
+        }
+    }
+    resolve(&app, pending, DecisionKind::Approved)
+}
+
+/// Discard a queued write
+#[tauri::command]
+pub fn reject_pending_write(app: AppHandle, id: String) -> Result<Decision, CommandError> {
+    let pending = load_pending(&id)?;
+    resolve(&app, pending, DecisionKind::Rejected)
+}
+
+fn resolve(app: &AppHandle, pending: PendingWrite, kind: DecisionKind) -> Result<Decision, CommandError> {
+    fs::remove_file(pending_path(&pending.id)?)
+        .map_err(|e| CommandError::from_io("Failed to remove pending write", e))?;
+
+    let decision = Decision {
+        id: Some(pending.id),
+        ..Decision::new(pending.source, &pending.path, kind, pending.violations)
+    };
+    decide(app, &decision)?;
+    Ok(decision)
+}
+
+/// Recorded decisions, newest first
+#[tauri::command]
+pub fn get_guardrail_decisions(limit: Option<usize>) -> Result<Vec<Decision>, CommandError> {
+    let path = guardrails_dir()?.join(DECISIONS_FILE);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit.unwrap_or(DEFAULT_DECISION_LIMIT))
+        .collect())
+}
@@ -0,0 +1,604 @@
+// Process isolation - resource and filesystem limits for spawned processes
+//
+// The gateway, psychology scripts, maintenance binaries and hook commands
+// run with the user's full privileges unless `isolation.<process>` in the
+// config confines them. Each platform has its own means:
+//   - Linux: a transient systemd user scope, so cgroup v2 enforces
+//     `memory_mb` (MemoryMax) and `cpu_percent` (CPUQuota)
+//   - macOS: sandbox-exec with a profile that denies writes outside the
+//     data directory, the temp directory, the process's own directories
+//     and `writable_paths`
+//   - Windows: a Job Object with a memory limit and a hard CPU cap, which
+//     the process's children inherit. The process starts suspended and
+//     only runs once it is in its job
+// The launchers (systemd-run, sandbox-exec) exec the real program, so the
+// spawned pid is the process itself and stopping it works as before. A
+// process whose confinement is enabled but unavailable isn't started:
+// running it unconfined would quietly ignore the setting. Limits a
+// platform can't enforce (filesystem on Linux and Windows, resources on
+// macOS) are logged and otherwise ignored.
+
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::commands::config::{self, ProcessLimits};
+use crate::commands::{CommandError, ErrorCode};
+
+/// Smallest memory limit accepted; less and nothing starts
+const MIN_MEMORY_MB: u64 = 64;
+
+/// Spawned processes with a section of their own in `isolation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessKind {
+    Gateway,
+    /// Python psychology scripts
+    Scripts,
+    /// helix-rust binaries run by scheduler jobs
+    Maintenance,
+    /// Hook commands
+    Hooks,
+}
+
+impl ProcessKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProcessKind::Gateway => "gateway",
+            ProcessKind::Scripts => "scripts",
+            ProcessKind::Maintenance => "maintenance",
+            ProcessKind::Hooks => "hooks",
+        }
+    }
+}
+
+/// What this platform can confine
+#[derive(Debug, Clone, Serialize)]
+pub struct IsolationSupport {
+    pub platform: String,
+    /// `memory_mb` and `cpu_percent`
+    pub resource_limits: bool,
+    /// `writable_paths`
+    pub filesystem: bool,
+    /// Why something is unavailable
+    pub detail: Option<String>,
+}
+
+/// Enabled limits for `kind`, validated
+fn limits(kind: ProcessKind) -> Result<Option<ProcessLimits>, CommandError> {
+    let isolation = config::get_config().map(|c| c.isolation).unwrap_or_default();
+    let limits = match kind {
+        ProcessKind::Gateway => isolation.gateway,
+        ProcessKind::Scripts => isolation.scripts,
+        ProcessKind::Maintenance => isolation.maintenance,
+        ProcessKind::Hooks => isolation.hooks,
+    };
+    if !limits.enabled {
+        return Ok(None);
+    }
+    if limits.memory_mb.is_some_and(|mb| mb < MIN_MEMORY_MB) {
+        return Err(CommandError::new(
+            ErrorCode::Config,
+            format!("isolation.{}.memory_mb must be at least {}", kind.as_str(), MIN_MEMORY_MB),
+        ));
+    }
+    if limits.cpu_percent == Some(0) {
+        return Err(CommandError::new(
+            ErrorCode::Config,
+            format!("isolation.{}.cpu_percent must be above 0 (100 is one core)", kind.as_str()),
+        ));
+    }
+    Ok(Some(limits))
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn unavailable(kind: ProcessKind, detail: &str) -> CommandError {
+    CommandError::new(
+        ErrorCode::Unsupported,
+        format!(
+            "Isolation is enabled for {} processes but {}; turn off isolation.{}.enabled to run them unconfined",
+            kind.as_str(),
+            detail,
+            kind.as_str()
+        ),
+    )
+}
+
+/// A command for `program`, confined as `isolation.<kind>` says. Add
+/// arguments, environment and working directory as usual. `writable` are
+/// the directories the process needs to write besides the data and temp
+/// directories. Always pass the spawned process to `attach` as well: on
+/// Windows it starts suspended and attach confines it and lets it run.
+pub fn command(kind: ProcessKind, program: impl AsRef<OsStr>, writable: &[PathBuf]) -> Result<Command, CommandError> {
+    let Some(limits) = limits(kind)? else {
+        return Ok(Command::new(program));
+    };
+    platform::command(kind, &limits, program.as_ref(), writable)
+}
+
+/// Apply limits that can only be set on a spawned process (Windows). On
+/// Err the process has been killed, or is being; don't let it run. The
+/// limits are read again here, so they may have been turned off since
+/// `command` started the process suspended: it is let run all the same.
+pub fn attach(kind: ProcessKind, pid: Option<u32>) -> Result<(), CommandError> {
+    let Some(pid) = pid else {
+        return Ok(());
+    };
+    match limits(kind) {
+        Ok(Some(limits)) => platform::attach(kind, &limits, pid),
+        Ok(None) => platform::release(kind, pid),
+        Err(e) => {
+            platform::kill(pid);
+            Err(e)
+        }
+    }
+}
+
+fn ignored(kind: ProcessKind, what: &str) {
+    log::warn!("isolation.{}: {} can't be enforced on this platform and is ignored", kind.as_str(), what);
+}
+
+/// Directories every confined process may write to
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn base_writable() -> Vec<PathBuf> {
+    let mut dirs = vec![std::env::temp_dir()];
+    if let Ok(root) = crate::accounts::helix_root() {
+        dirs.push(root);
+    }
+    if let Ok(data) = crate::accounts::data_dir() {
+        dirs.push(data);
+    }
+    dirs
+}
+
+/// `program` resolved through PATH when it's a bare name
+#[cfg_attr(windows, allow(dead_code))]
+fn find_program(name: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    pub fn support() -> IsolationSupport {
+        let detail = unavailable_reason();
+        IsolationSupport {
+            platform: "linux".to_string(),
+            resource_limits: detail.is_none(),
+            filesystem: false,
+            detail: detail.or_else(|| Some("writable_paths is only enforced on macOS".to_string())),
+        }
+    }
+
+    fn unavailable_reason() -> Option<String> {
+        if !Path::new("/sys/fs/cgroup/cgroup.controllers").exists() {
+            return Some("cgroup v2 isn't mounted".to_string());
+        }
+        if find_program("systemd-run").is_none() {
+            return Some("systemd-run isn't installed".to_string());
+        }
+        if std::env::var_os("XDG_RUNTIME_DIR").is_none() {
+            return Some("there is no systemd user session".to_string());
+        }
+        None
+    }
+
+    pub fn command(
+        kind: ProcessKind,
+        limits: &ProcessLimits,
+        program: &OsStr,
+        _writable: &[PathBuf],
+    ) -> Result<Command, CommandError> {
+        if !limits.writable_paths.is_empty() {
+            ignored(kind, "writable_paths");
+        }
+        if limits.memory_mb.is_none() && limits.cpu_percent.is_none() {
+            return Ok(Command::new(program));
+        }
+        if let Some(reason) = unavailable_reason() {
+            return Err(unavailable(kind, &reason));
+        }
+
+        let mut cmd = Command::new("systemd-run");
+        cmd.args(["--user", "--scope", "--quiet", "--collect"])
+            .arg(format!("--unit=helix-{}-{}", kind.as_str(), hex::encode(rand::random::<[u8; 4]>())));
+        if let Some(mb) = limits.memory_mb {
+            cmd.args(["-p", &format!("MemoryMax={}M", mb)]);
+        }
+        if let Some(percent) = limits.cpu_percent {
+            cmd.args(["-p", &format!("CPUQuota={}%", percent)]);
+        }
+        cmd.arg("--").arg(program);
+        Ok(cmd)
+    }
+
+    pub fn attach(_kind: ProcessKind, _limits: &ProcessLimits, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is started suspended here
+    pub fn release(_kind: ProcessKind, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is left waiting here; the caller stops the process
+    pub fn kill(_pid: u32) {}
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+
+    pub fn support() -> IsolationSupport {
+        let available = Path::new(SANDBOX_EXEC).exists();
+        IsolationSupport {
+            platform: "macos".to_string(),
+            resource_limits: false,
+            filesystem: available,
+            detail: Some(if available {
+                "memory_mb and cpu_percent are only enforced on Linux and Windows".to_string()
+            } else {
+                "sandbox-exec isn't available".to_string()
+            }),
+        }
+    }
+
+    /// Writes denied except to the WRITABLE_n parameters
+    fn profile(count: usize) -> String {
+        let mut profile = String::from(
+            "(version 1)\n(allow default)\n(deny file-write*)\n(allow file-write*\n  \
+             (literal \"/dev/null\") (literal \"/dev/tty\") (regex #\"^/dev/fd/\")",
+        );
+        for index in 0..count {
+            profile.push_str(&format!("\n  (subpath (param \"WRITABLE_{}\"))", index));
+        }
+        profile.push_str(")\n");
+        profile
+    }
+
+    pub fn command(
+        kind: ProcessKind,
+        limits: &ProcessLimits,
+        program: &OsStr,
+        writable: &[PathBuf],
+    ) -> Result<Command, CommandError> {
+        if limits.memory_mb.is_some() {
+            ignored(kind, "memory_mb");
+        }
+        if limits.cpu_percent.is_some() {
+            ignored(kind, "cpu_percent");
+        }
+        if !Path::new(SANDBOX_EXEC).exists() {
+            return Err(unavailable(kind, "sandbox-exec isn't available"));
+        }
+
+        // The sandbox matches resolved paths: /var is /private/var
+        let mut dirs: Vec<PathBuf> = base_writable()
+            .into_iter()
+            .chain(writable.iter().cloned())
+            .chain(limits.writable_paths.iter().map(PathBuf::from))
+            .map(|dir| dir.canonicalize().unwrap_or(dir))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        let mut cmd = Command::new(SANDBOX_EXEC);
+        cmd.arg("-p").arg(profile(dirs.len()));
+        for (index, dir) in dirs.iter().enumerate() {
+            let mut define = std::ffi::OsString::from(format!("WRITABLE_{}=", index));
+            define.push(dir.as_os_str());
+            cmd.arg("-D").arg(define);
+        }
+        // sandbox-exec runs programs by path, not through PATH
+        let program = match Path::new(program).components().count() {
+            1 => find_program(&program.to_string_lossy()).map(PathBuf::into_os_string),
+            _ => None,
+        }
+        .unwrap_or_else(|| program.to_os_string());
+        cmd.arg(program);
+        Ok(cmd)
+    }
+
+    pub fn attach(_kind: ProcessKind, _limits: &ProcessLimits, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is started suspended here
+    pub fn release(_kind: ProcessKind, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is left waiting here; the caller stops the process
+    pub fn kill(_pid: u32) {}
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use std::ffi::c_void;
+    use std::os::windows::process::CommandExt;
+
+    type Handle = *mut c_void;
+
+    const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION: i32 = 9;
+    const JOB_OBJECT_CPU_RATE_CONTROL_INFORMATION: i32 = 15;
+    const JOB_OBJECT_LIMIT_JOB_MEMORY: u32 = 0x0000_0200;
+    const JOB_OBJECT_CPU_RATE_CONTROL_ENABLE: u32 = 0x1;
+    const JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP: u32 = 0x4;
+    const PROCESS_SET_QUOTA: u32 = 0x0100;
+    const PROCESS_TERMINATE: u32 = 0x0001;
+    const THREAD_SUSPEND_RESUME: u32 = 0x0002;
+    const CREATE_SUSPENDED: u32 = 0x0000_0004;
+    const TH32CS_SNAPTHREAD: u32 = 0x0000_0004;
+    /// Exit code of a process killed because it couldn't be confined
+    const UNCONFINED_EXIT_CODE: u32 = 1;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct BasicLimitInformation {
+        per_process_user_time_limit: i64,
+        per_job_user_time_limit: i64,
+        limit_flags: u32,
+        minimum_working_set_size: usize,
+        maximum_working_set_size: usize,
+        active_process_limit: u32,
+        affinity: usize,
+        priority_class: u32,
+        scheduling_class: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ExtendedLimitInformation {
+        basic: BasicLimitInformation,
+        io_info: [u64; 6],
+        process_memory_limit: usize,
+        job_memory_limit: usize,
+        peak_process_memory_used: usize,
+        peak_job_memory_used: usize,
+    }
+
+    #[repr(C)]
+    struct CpuRateControlInformation {
+        control_flags: u32,
+        cpu_rate: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ThreadEntry32 {
+        size: u32,
+        usage: u32,
+        thread_id: u32,
+        owner_process_id: u32,
+        base_priority: i32,
+        delta_priority: i32,
+        flags: u32,
+    }
+
+    extern "system" {
+        fn CreateJobObjectW(attributes: *mut c_void, name: *const u16) -> Handle;
+        fn SetInformationJobObject(job: Handle, class: i32, info: *mut c_void, length: u32) -> i32;
+        fn AssignProcessToJobObject(job: Handle, process: Handle) -> i32;
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> Handle;
+        fn TerminateProcess(process: Handle, exit_code: u32) -> i32;
+        fn CreateToolhelp32Snapshot(flags: u32, pid: u32) -> Handle;
+        fn Thread32First(snapshot: Handle, entry: *mut ThreadEntry32) -> i32;
+        fn Thread32Next(snapshot: Handle, entry: *mut ThreadEntry32) -> i32;
+        fn OpenThread(access: u32, inherit: i32, thread_id: u32) -> Handle;
+        fn ResumeThread(thread: Handle) -> u32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    pub fn support() -> IsolationSupport {
+        IsolationSupport {
+            platform: "windows".to_string(),
+            resource_limits: true,
+            filesystem: false,
+            detail: Some("writable_paths is only enforced on macOS".to_string()),
+        }
+    }
+
+    fn has_resource_limits(limits: &ProcessLimits) -> bool {
+        limits.memory_mb.is_some() || limits.cpu_percent.is_some()
+    }
+
+    /// Started suspended when there are limits, so it can't run (or start
+    /// children) before attach has put it in its job
+    pub fn command(
+        kind: ProcessKind,
+        limits: &ProcessLimits,
+        program: &OsStr,
+        _writable: &[PathBuf],
+    ) -> Result<Command, CommandError> {
+        if !limits.writable_paths.is_empty() {
+            ignored(kind, "writable_paths");
+        }
+        let mut command = Command::new(program);
+        if has_resource_limits(limits) {
+            command.creation_flags(CREATE_SUSPENDED);
+        }
+        Ok(command)
+    }
+
+    /// Resume the threads of a process started suspended; a no-op for one
+    /// that is running. Err when a thread couldn't be resumed.
+    ///
+    /// SAFETY: plain Win32 calls on handles this function owns
+    unsafe fn resume(pid: u32) -> Result<(), String> {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot.is_null() || snapshot as isize == -1 {
+            return Err("CreateToolhelp32Snapshot failed".to_string());
+        }
+        let mut entry = ThreadEntry32 {
+            size: std::mem::size_of::<ThreadEntry32>() as u32,
+            ..Default::default()
+        };
+        let mut result = Ok(());
+        let mut more = Thread32First(snapshot, &mut entry) != 0;
+        while more {
+            if entry.owner_process_id == pid {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.thread_id);
+                if thread.is_null() || ResumeThread(thread) == u32::MAX {
+                    result = Err(format!("thread {} couldn't be resumed", entry.thread_id));
+                }
+                if !thread.is_null() {
+                    CloseHandle(thread);
+                }
+            }
+            more = Thread32Next(snapshot, &mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+        result
+    }
+
+    /// SAFETY: `job` is a valid job handle and `info` matches `class`
+    unsafe fn set_job_info<T>(job: Handle, class: i32, info: &mut T, what: &str) -> Result<(), String> {
+        let set = SetInformationJobObject(job, class, info as *mut T as *mut c_void, std::mem::size_of::<T>() as u32);
+        if set == 0 {
+            return Err(format!("setting its {} failed", what));
+        }
+        Ok(())
+    }
+
+    /// Put the process, started suspended by `command`, in a new Job
+    /// Object with the limits, then let it run. The job's handle is kept
+    /// open for as long as the app runs, so the limits outlive this call;
+    /// the job ends with its last process.
+    ///
+    /// SAFETY: plain Win32 calls on handles this function owns; the
+    /// structs match their C layouts and outlive the calls
+    unsafe fn confine(limits: &ProcessLimits, process: Handle) -> Result<(), String> {
+        let job = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+        if job.is_null() {
+            return Err("CreateJobObject failed".to_string());
+        }
+        let configured = (|| {
+            if let Some(mb) = limits.memory_mb {
+                let mut info = ExtendedLimitInformation::default();
+                info.basic.limit_flags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+                info.job_memory_limit = (mb as usize).saturating_mul(1024 * 1024);
+                set_job_info(job, JOB_OBJECT_EXTENDED_LIMIT_INFORMATION, &mut info, "memory limit")?;
+            }
+            if let Some(percent) = limits.cpu_percent {
+                // CpuRate is a share of all processors, in hundredths of a percent
+                let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as u32;
+                let mut info = CpuRateControlInformation {
+                    control_flags: JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+                    cpu_rate: (percent.saturating_mul(100) / cpus).clamp(1, 10_000),
+                };
+                set_job_info(job, JOB_OBJECT_CPU_RATE_CONTROL_INFORMATION, &mut info, "CPU cap")?;
+            }
+            if AssignProcessToJobObject(job, process) == 0 {
+                return Err("it couldn't join its job".to_string());
+            }
+            Ok(())
+        })();
+        if configured.is_err() {
+            CloseHandle(job);
+        }
+        configured
+    }
+
+    /// Confine the process and let it run; on any failure it is killed,
+    /// never left running unconfined or suspended
+    pub fn attach(kind: ProcessKind, limits: &ProcessLimits, pid: u32) -> Result<(), CommandError> {
+        if !has_resource_limits(limits) {
+            return release(kind, pid);
+        }
+        // SAFETY: see confine and resume
+        unsafe {
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return Err(unconfined(kind, pid, "it couldn't be opened"));
+            }
+            let result = confine(limits, process).and_then(|()| resume(pid));
+            if result.is_err() {
+                TerminateProcess(process, UNCONFINED_EXIT_CODE);
+            }
+            CloseHandle(process);
+            result.map_err(|e| unconfined(kind, pid, &e))
+        }
+    }
+
+    /// Let a process run unconfined, in case `command` started it suspended
+    /// under limits that are gone now; killed if it can't be resumed
+    pub fn release(kind: ProcessKind, pid: u32) -> Result<(), CommandError> {
+        // SAFETY: see resume
+        unsafe { resume(pid) }.map_err(|e| {
+            kill(pid);
+            unconfined(kind, pid, &e)
+        })
+    }
+
+    /// Kill a process whose limits can no longer be read: it may be
+    /// waiting, suspended, to be confined
+    pub fn kill(pid: u32) {
+        // SAFETY: plain Win32 calls on a handle this function owns
+        unsafe {
+            let process = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if !process.is_null() {
+                TerminateProcess(process, UNCONFINED_EXIT_CODE);
+                CloseHandle(process);
+            }
+        }
+    }
+
+    fn unconfined(kind: ProcessKind, pid: u32, detail: &str) -> CommandError {
+        log::warn!("{} process {} was stopped: {}", kind.as_str(), pid, detail);
+        CommandError::new(
+            ErrorCode::Unsupported,
+            format!("The {} process couldn't be confined as isolation.{} says: {}", kind.as_str(), kind.as_str(), detail),
+        )
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub fn support() -> IsolationSupport {
+        IsolationSupport {
+            platform: std::env::consts::OS.to_string(),
+            resource_limits: false,
+            filesystem: false,
+            detail: Some("Process isolation isn't supported on this platform".to_string()),
+        }
+    }
+
+    pub fn command(
+        kind: ProcessKind,
+        _limits: &ProcessLimits,
+        _program: &OsStr,
+        _writable: &[PathBuf],
+    ) -> Result<Command, CommandError> {
+        Err(unavailable(kind, "this platform can't confine processes"))
+    }
+
+    pub fn attach(_kind: ProcessKind, _limits: &ProcessLimits, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is started suspended here
+    pub fn release(_kind: ProcessKind, _pid: u32) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Nothing is left waiting here; the caller stops the process
+    pub fn kill(_pid: u32) {}
+}
+
+// Tauri commands
+
+/// Which limits this platform can enforce
+#[tauri::command]
+pub fn get_isolation_support() -> Result<IsolationSupport, CommandError> {
+    Ok(platform::support())
+}
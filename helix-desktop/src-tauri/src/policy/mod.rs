@@ -0,0 +1,8 @@
+// Helix Desktop - Policy Module (do-not-disturb, quiet hours, soul guardrails, skill run approvals, workspace trust and process isolation)
+
+pub mod dnd;
+pub mod focus;
+pub mod guardrails;
+pub mod isolation;
+pub mod skill_approvals;
+pub mod workspace_trust;
@@ -0,0 +1,466 @@
+// Skill run approvals
+//
+// Agents don't run skills on their own say-so. A run requested by a gateway
+// agent, through the local API or the MCP execute_skill tool, is saved to
+// <data_dir>/skill-runs/<id>.json and only sent to the skill sandbox once
+// the user approves it, or right away when a rule in
+// `skill_approvals.auto_approve` matches the skill (and agent). The MCP
+// server runs in its own process, so the app polls the directory for new
+// requests and announces each with `skills:approval-requested`, a desktop
+// notification (unless do-not-disturb suppresses them) and the tray's
+// approvals count. Resolved runs are kept with their output, newest
+// KEEP_RESOLVED of them.
+//
+// The sandbox checks that a run was approved: each call carries an HMAC of
+// the run with a key only this app launch holds, and the sandbox runs each
+// approval once. So only the app calls the sandbox: the MCP server saves
+// auto-approved runs as pending too, and the app's poll runs them once it
+// has checked the rule itself.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::accessibility::announce;
+use crate::commands::config::{self, AutoApproveRule, HelixConfig};
+use crate::commands::{CommandError, ErrorCode};
+
+/// Skill sandbox RPC server started by `start_skill_sandbox`
+const SKILL_SANDBOX_URL: &str = "http://127.0.0.1:18790";
+/// Bearer token the sandbox requires, in ~/.helix; one per install
+const SANDBOX_TOKEN_FILENAME: &str = "skill-sandbox-token";
+/// Environment variable the sandbox reads its token from
+pub const SANDBOX_TOKEN_ENV: &str = "HELIX_SANDBOX_TOKEN";
+/// Environment variable the sandbox reads the approval key from
+pub const APPROVAL_KEY_ENV: &str = "HELIX_SANDBOX_APPROVAL_KEY";
+
+/// Signs approved runs for the sandbox. It lives only in this process, so
+/// nothing else that can reach the sandbox (the MCP server, an agent's
+/// shell) can make it run a skill the user didn't approve.
+static APPROVAL_KEY: LazyLock<[u8; 32]> = LazyLock::new(rand::random);
+/// Held while a run moves out of Pending, so it is approved or rejected once
+static RESOLVE_LOCK: Mutex<()> = Mutex::new(());
+const SANDBOX_TIMEOUT: Duration = Duration::from_secs(60);
+/// Runs directory, under the data directory
+const RUNS_DIR: &str = "skill-runs";
+const RUN_ID_PREFIX: &str = "run-";
+const KEEP_RESOLVED: usize = 100;
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Waiting for the user
+    Pending,
+    /// Approved and sent to the sandbox
+    Running,
+    Completed,
+    Failed,
+    Rejected,
+}
+
+impl RunStatus {
+    fn is_resolved(self) -> bool {
+        !matches!(self, RunStatus::Pending | RunStatus::Running)
+    }
+}
+
+/// A skill run an agent asked for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillRun {
+    pub id: String,
+    pub created_at: u64,
+    pub skill_id: String,
+    pub input: Value,
+    /// Agent that asked, when the caller names it
+    pub agent: Option<String>,
+    /// How the request arrived: "api" or "mcp"
+    pub source: String,
+    pub status: RunStatus,
+    /// Ran without asking because an auto-approve rule matched
+    #[serde(default)]
+    pub auto_approved: bool,
+    pub resolved_at: Option<u64>,
+    /// Sandbox response of a completed run
+    pub output: Option<Value>,
+    pub error: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+// Storage
+
+fn runs_dir() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(RUNS_DIR))
+}
+
+fn run_path(id: &str) -> Result<PathBuf, CommandError> {
+    // Ids come from callers; only accept ones this module could have made
+    let valid = id
+        .strip_prefix(RUN_ID_PREFIX)
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid {
+        return Err(unknown_run(id));
+    }
+    Ok(runs_dir()?.join(format!("{}.json", id)))
+}
+
+fn unknown_run(id: &str) -> CommandError {
+    CommandError::not_found(format!("No skill run: {}", id)).with_details(json!({ "id": id }))
+}
+
+fn save(run: &SkillRun) -> Result<(), String> {
+    let dir = runs_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create skill runs directory: {}", e))?;
+    let json = serde_json::to_string_pretty(run).map_err(|e| format!("Failed to serialize skill run: {}", e))?;
+    let tmp_path = dir.join(format!("{}.json.tmp", run.id));
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write skill run: {}", e))?;
+    fs::rename(&tmp_path, dir.join(format!("{}.json", run.id)))
+        .map_err(|e| format!("Failed to write skill run: {}", e))
+}
+
+pub fn load(id: &str) -> Result<SkillRun, CommandError> {
+    let path = run_path(id)?;
+    let content = fs::read_to_string(&path).map_err(|_| unknown_run(id))?;
+    serde_json::from_str(&content).map_err(|e| CommandError::internal(format!("Corrupt skill run {}: {}", id, e)))
+}
+
+/// Every saved run, newest first
+fn all_runs() -> Vec<SkillRun> {
+    let Ok(entries) = runs_dir().and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+
+    let mut runs: Vec<SkillRun> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.created_at));
+    runs
+}
+
+pub fn pending_count() -> u32 {
+    all_runs().iter().filter(|run| run.status == RunStatus::Pending).count() as u32
+}
+
+fn prune() {
+    let Ok(dir) = runs_dir() else { return };
+    for old in all_runs().into_iter().filter(|run| run.status.is_resolved()).skip(KEEP_RESOLVED) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+}
+
+// Rules
+
+/// The app's config, or in MCP mode (no config init) the account's file
+fn current_config() -> Option<HelixConfig> {
+    config::get_config().ok().or_else(|| {
+        let path = crate::accounts::data_dir().ok()?.join("config.json");
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    })
+}
+
+fn auto_approved(skill_id: &str, agent: Option<&str>) -> bool {
+    current_config().is_some_and(|config| {
+        config.skill_approvals.auto_approve.iter().any(|rule| {
+            rule.skill_id.eq_ignore_ascii_case(skill_id.trim()) && rule.agent.as_deref().is_none_or(|rule_agent| Some(rule_agent) == agent)
+        })
+    })
+}
+
+// Running
+
+/// The token `start_skill_sandbox` hands the sandbox, created on first use
+pub fn sandbox_token() -> Result<String, String> {
+    crate::api::token::load_or_create(&crate::accounts::helix_root()?.join(SANDBOX_TOKEN_FILENAME))
+}
+
+/// The approval key, hex, for `start_skill_sandbox` to hand the sandbox
+pub fn approval_key() -> String {
+    hex::encode(*APPROVAL_KEY)
+}
+
+/// HMAC-SHA256 over the run id, skill id and input, as the sandbox checks
+/// it. serde_json sorts object keys, so both sides serialize `input` alike.
+fn approval(run: &SkillRun) -> Result<String, String> {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(&*APPROVAL_KEY).map_err(|e| e.to_string())?;
+    mac.update(run.id.as_bytes());
+    mac.update(b"\n");
+    mac.update(run.skill_id.as_bytes());
+    mac.update(b"\n");
+    mac.update(&serde_json::to_vec(&run.input).map_err(|e| e.to_string())?);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+async fn call_sandbox(run: &SkillRun) -> Result<Value, String> {
+    let user_id = crate::accounts::profiles::active_user_id().ok_or("Sign in to run skills")?;
+    let response = reqwest::Client::builder()
+        .timeout(SANDBOX_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .post(format!("{}/execute", SKILL_SANDBOX_URL))
+        .bearer_auth(sandbox_token()?)
+        .header("X-Helix-User", user_id)
+        .json(&json!({
+            "run_id": run.id,
+            "skill_id": run.skill_id,
+            "input": run.input,
+            "approval": approval(run)?,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Skill sandbox unreachable (is it running?): {}", e))?;
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid skill sandbox response: {}", e))?;
+    if body.get("success").and_then(Value::as_bool) == Some(false) {
+        let error = body.get("error").and_then(Value::as_str).unwrap_or("Skill failed");
+        return Err(error.to_string());
+    }
+    Ok(body)
+}
+
+/// Send an approved run, already saved as Running, to the sandbox and
+/// save the outcome
+async fn execute(mut run: SkillRun) -> Result<SkillRun, String> {
+    match call_sandbox(&run).await {
+        Ok(output) => {
+            run.status = RunStatus::Completed;
+            run.output = Some(output);
+        }
+        Err(e) => {
+            log::warn!("Skill run {} ({}) failed: {}", run.id, run.skill_id, e);
+            run.status = RunStatus::Failed;
+            run.error = Some(e);
+        }
+    }
+    run.resolved_at = Some(now_ms());
+    save(&run)?;
+    prune();
+    Ok(run)
+}
+
+/// Queue a run an agent asked for, or run it now if a rule approves it
+pub async fn request(
+    skill_id: &str,
+    input: Value,
+    agent: Option<String>,
+    source: &str,
+) -> Result<SkillRun, CommandError> {
+    if skill_id.trim().is_empty() {
+        return Err(CommandError::invalid_input("skill_id is required"));
+    }
+
+    let run = SkillRun {
+        id: format!("{}{:016x}", RUN_ID_PREFIX, rand::random::<u64>()),
+        created_at: now_ms(),
+        // Lowercase, as the sandbox writes the id when it checks the approval
+        skill_id: skill_id.trim().to_ascii_lowercase(),
+        input,
+        auto_approved: auto_approved(skill_id, agent.as_deref()),
+        agent,
+        source: source.to_string(),
+        status: RunStatus::Pending,
+        resolved_at: None,
+        output: None,
+        error: None,
+    };
+
+    // The MCP server can't sign the run; the app picks it up and runs it
+    if run.auto_approved && !crate::is_mcp_mode() {
+        log::info!("Skill run {} ({}) auto-approved", run.id, run.skill_id);
+        let run = SkillRun {
+            status: RunStatus::Running,
+            ..run
+        };
+        save(&run)?;
+        return Ok(execute(run).await?);
+    }
+    save(&run)?;
+    log::info!("Skill run {} ({}) queued for approval", run.id, run.skill_id);
+    Ok(run)
+}
+
+/// Wait up to `timeout` for a run to be resolved; returns it as it stands
+pub async fn wait(id: &str, timeout: Duration) -> Result<SkillRun, CommandError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let run = load(id)?;
+        if run.status.is_resolved() || Instant::now() >= deadline {
+            return Ok(run);
+        }
+        tokio::time::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+}
+
+fn notify(app: &AppHandle, runs: &[SkillRun]) {
+    let _ = app.emit("skills:approval-requested", runs);
+    if crate::policy::dnd::status().suppress_notifications {
+        return;
+    }
+
+    let strings = crate::locale::Catalog::new("notifications");
+    let body = match runs {
+        [run] => match run.agent.as_deref() {
+            Some(agent) => strings.get_args(
+                "skill-approval-body",
+                &[("agent", agent.into()), ("skill", run.skill_id.as_str().into())],
+            ),
+            None => strings.get_args("skill-approval-body-any-agent", &[("skill", run.skill_id.as_str().into())]),
+        },
+        _ => strings.get_args("skill-approvals-waiting", &[("count", runs.len().into())]),
+    };
+    announce::announce(app, announce::Source::Approvals, announce::Priority::Polite, body.clone());
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(strings.get("skill-approval-title"))
+        .body(body)
+        .show()
+    {
+        log::debug!("Failed to show skill approval notification: {}", e);
+    }
+}
+
+/// Run a pending request the MCP server saved as auto-approved. The rule is
+/// checked again here, as the file is only as trusted as the directory.
+fn run_queued(app: &AppHandle, id: &str) {
+    let run = match take_pending(id, RunStatus::Running) {
+        Ok(run) => run,
+        Err(e) => {
+            log::debug!("Skill run {} not started: {}", id, e.message);
+            return;
+        }
+    };
+    log::info!("Skill run {} ({}) auto-approved", run.id, run.skill_id);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match execute(run).await {
+            Ok(run) => {
+                let _ = app.emit("skills:run-resolved", &run);
+            }
+            Err(e) => log::warn!("Failed to save skill run: {}", e),
+        }
+    });
+}
+
+/// Announce new requests, and run the MCP server's auto-approved ones, for
+/// the lifetime of the app
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut announced: HashSet<String> = HashSet::new();
+        loop {
+            let (queued, pending): (Vec<SkillRun>, Vec<SkillRun>) = all_runs()
+                .into_iter()
+                .filter(|run| run.status == RunStatus::Pending)
+                .partition(|run| run.auto_approved && auto_approved(&run.skill_id, run.agent.as_deref()));
+            for run in &queued {
+                run_queued(&app, &run.id);
+            }
+            let new: Vec<SkillRun> = pending.iter().filter(|run| !announced.contains(&run.id)).cloned().collect();
+            if !new.is_empty() {
+                notify(&app, &new);
+            }
+            announced = pending.into_iter().map(|run| run.id).collect();
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Move a pending run to `status` and save it, in one step: of two
+/// answers to the same request only the first gets the run
+fn take_pending(id: &str, status: RunStatus) -> Result<SkillRun, CommandError> {
+    let _guard = RESOLVE_LOCK.lock().map_err(|e| CommandError::internal(e.to_string()))?;
+    let mut run = load(id)?;
+    if run.status != RunStatus::Pending {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            format!("Skill run {} is no longer pending", id),
+        )
+        .with_details(json!({ "id": id, "status": run.status })));
+    }
+    run.status = status;
+    if status.is_resolved() {
+        run.resolved_at = Some(now_ms());
+    }
+    save(&run)?;
+    Ok(run)
+}
+
+// Tauri commands
+
+/// Skill runs, newest first; only those with `status` when given
+#[tauri::command]
+pub fn list_skill_runs(status: Option<RunStatus>) -> Result<Vec<SkillRun>, CommandError> {
+    Ok(all_runs()
+        .into_iter()
+        .filter(|run| status.is_none_or(|status| run.status == status))
+        .collect())
+}
+
+/// Run a pending request in the sandbox. `always` also adds an
+/// auto-approve rule for the skill and agent.
+#[tauri::command]
+pub async fn approve_skill_run(app: AppHandle, id: String, always: Option<bool>) -> Result<SkillRun, CommandError> {
+    let run = take_pending(&id, RunStatus::Running)?;
+    // The run is approved either way; a rule that can't be saved is only
+    // asked for again next time
+    if always.unwrap_or(false) {
+        if let Err(e) = set_skill_auto_approve(run.skill_id.clone(), run.agent.clone(), true) {
+            log::warn!("Failed to save auto-approve rule for {}: {}", run.skill_id, e.message);
+        }
+    }
+
+    let run = execute(run).await?;
+    let _ = app.emit("skills:run-resolved", &run);
+    Ok(run)
+}
+
+/// Refuse a pending request; the sandbox is never called
+#[tauri::command]
+pub fn reject_skill_run(app: AppHandle, id: String) -> Result<SkillRun, CommandError> {
+    let run = take_pending(&id, RunStatus::Rejected)?;
+    prune();
+
+    let _ = app.emit("skills:run-resolved", &run);
+    Ok(run)
+}
+
+/// Add or remove the rule that runs `skill_id` without asking, for `agent`
+/// or any agent. Returns the rules now in effect.
+#[tauri::command]
+pub fn set_skill_auto_approve(
+    skill_id: String,
+    agent: Option<String>,
+    enabled: bool,
+) -> Result<Vec<AutoApproveRule>, CommandError> {
+    if skill_id.trim().is_empty() {
+        return Err(CommandError::invalid_input("skill_id is required"));
+    }
+
+    let mut config = config::get_config()?;
+    let rule = AutoApproveRule { skill_id, agent };
+    let rules = &mut config.skill_approvals.auto_approve;
+    rules.retain(|existing| *existing != rule);
+    if enabled {
+        rules.push(rule);
+    }
+
+    let rules = rules.clone();
+    config::set_config(config)?;
+    Ok(rules)
+}
@@ -0,0 +1,305 @@
+// Workspace trust - approval before project-local scripts run
+//
+// The decay and synthesis jobs run whatever Python sits in the data
+// directory's scripts/ folder, and with HELIX_PROJECT_DIR that folder is
+// any checkout the user points Helix at. Like workspace trust in an editor,
+// those scripts only run once the user has trusted the workspace, and the
+// trust is tied to a fingerprint of the folder: a SHA-256 over every file's
+// path and content hash. Adding, editing or removing a script changes the
+// fingerprint, so the next run asks again and the prompt lists what
+// changed. Python would load a stale `__pycache__` over its source, so
+// compiled bytecode under scripts/ is deleted before every check and the
+// scripts run with -B, which keeps them from writing more. Trusted
+// fingerprints live in ~/.helix/workspace-trust.json, one
+// per workspace. A refused run is announced with workspace-trust:required
+// so the frontend can prompt even when a scheduled job asked.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{CommandError, ErrorCode};
+
+const TRUST_FILE: &str = "workspace-trust.json";
+const SCRIPTS_DIR: &str = "scripts";
+/// Tool caches under scripts/ that nothing runs from
+const SKIPPED_DIRS: &[&str] = &[".pytest_cache", ".mypy_cache"];
+/// Where Python keeps compiled bytecode
+const BYTECODE_DIR: &str = "__pycache__";
+
+/// Serializes reads and writes of the trust file
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+/// For workspace-trust:required, which is raised outside of commands
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustState {
+    Trusted,
+    /// Never trusted, or trust was revoked
+    Untrusted,
+    /// Trusted, but the scripts changed since
+    Changed,
+}
+
+/// A workspace the user trusted, as stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrustedWorkspace {
+    workspace: String,
+    fingerprint: String,
+    /// Script path, relative to scripts/, to its SHA-256
+    files: BTreeMap<String, String>,
+    /// Milliseconds since epoch
+    trusted_at: u64,
+}
+
+/// Trust of the current workspace, also the payload of
+/// workspace-trust:required
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceTrust {
+    pub workspace: String,
+    pub scripts_dir: String,
+    pub state: TrustState,
+    /// Of the scripts as they are now; pass it to `trust_workspace`
+    pub fingerprint: String,
+    pub trusted_at: Option<u64>,
+    pub scripts: Vec<String>,
+    /// Against the trusted scripts; empty unless `state` is changed
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn trust_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::helix_root()?.join(TRUST_FILE))
+}
+
+fn read_trusted() -> Result<Vec<TrustedWorkspace>, String> {
+    let path = trust_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read workspace trust: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse workspace trust: {}", e))
+}
+
+fn write_trusted(trusted: &[TrustedWorkspace]) -> Result<(), String> {
+    let path = trust_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(trusted).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write workspace trust: {}", e))
+}
+
+/// Workspace key: the data directory, resolved so one folder has one entry
+fn workspace_key(dir: &Path) -> String {
+    dir.canonicalize()
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// SHA-256 of every file under `dir`, keyed by its path relative to `root`
+/// with forward slashes
+fn hash_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry.file_type().map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                hash_files(root, &path, files)?;
+            }
+            continue;
+        }
+        // Symlinks are hashed by what they point at, which is what runs
+        let content = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let key = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(key, hex::encode(Sha256::digest(&content)));
+    }
+    Ok(())
+}
+
+/// Delete compiled bytecode under `dir`; Python would run it in place of
+/// the trusted source
+fn clear_bytecode(dir: &Path) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry.file_type().map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if file_type.is_dir() && name == BYTECODE_DIR {
+            fs::remove_dir_all(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        } else if file_type.is_dir() {
+            clear_bytecode(&path)?;
+        } else if name.ends_with(".pyc") {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Hash of each script and the fingerprint over all of them
+fn fingerprint(scripts_dir: &Path) -> Result<(BTreeMap<String, String>, String), String> {
+    let mut files = BTreeMap::new();
+    if scripts_dir.is_dir() {
+        hash_files(scripts_dir, scripts_dir, &mut files)?;
+    }
+    let mut hasher = Sha256::new();
+    for (path, hash) in &files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(hash.as_bytes());
+        hasher.update([b'\n']);
+    }
+    Ok((files, hex::encode(hasher.finalize())))
+}
+
+/// Trust of the workspace at `dir`, with the scripts' current hashes
+fn check(dir: &Path) -> Result<(WorkspaceTrust, BTreeMap<String, String>), String> {
+    let scripts_dir = dir.join(SCRIPTS_DIR);
+    clear_bytecode(&scripts_dir)?;
+    let (files, fingerprint) = fingerprint(&scripts_dir)?;
+    let workspace = workspace_key(dir);
+    let trusted = {
+        let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+        read_trusted()?.into_iter().find(|entry| entry.workspace == workspace)
+    };
+
+    let mut status = WorkspaceTrust {
+        workspace,
+        scripts_dir: scripts_dir.to_string_lossy().to_string(),
+        state: TrustState::Untrusted,
+        fingerprint,
+        trusted_at: trusted.as_ref().map(|entry| entry.trusted_at),
+        scripts: files.keys().cloned().collect(),
+        added: Vec::new(),
+        modified: Vec::new(),
+        removed: Vec::new(),
+    };
+    if let Some(entry) = trusted {
+        if entry.fingerprint == status.fingerprint {
+            status.state = TrustState::Trusted;
+        } else {
+            status.state = TrustState::Changed;
+            for (path, hash) in &files {
+                match entry.files.get(path) {
+                    None => status.added.push(path.clone()),
+                    Some(trusted_hash) if trusted_hash != hash => status.modified.push(path.clone()),
+                    Some(_) => {}
+                }
+            }
+            status.removed = entry.files.keys().filter(|path| !files.contains_key(*path)).cloned().collect();
+        }
+    }
+    Ok((status, files))
+}
+
+/// Keep the app handle for workspace-trust:required
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Refuse to go on unless the scripts of the workspace at `dir` are
+/// trusted as they are now
+pub fn ensure_trusted(dir: &Path) -> Result<(), CommandError> {
+    let (status, _) = check(dir).map_err(CommandError::internal)?;
+    let message = match status.state {
+        TrustState::Trusted => return Ok(()),
+        TrustState::Untrusted => format!("Scripts in {} need to be trusted before they run", status.workspace),
+        TrustState::Changed => format!("Scripts in {} changed since they were trusted", status.workspace),
+    };
+
+    log::warn!("{}", message);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("workspace-trust:required", &status);
+    }
+    Err(CommandError::new(ErrorCode::ConfirmationRequired, message).with_details(serde_json::json!({
+        "workspace": status.workspace,
+        "state": status.state,
+        "fingerprint": status.fingerprint,
+    })))
+}
+
+// Tauri commands
+
+/// Trust of the active data directory's scripts
+#[tauri::command]
+pub fn get_workspace_trust() -> Result<WorkspaceTrust, CommandError> {
+    let dir = crate::accounts::data_dir()?;
+    Ok(check(&dir).map_err(CommandError::internal)?.0)
+}
+
+/// Trust the scripts as the user reviewed them; `fingerprint` is the one
+/// they were shown, so scripts that changed in the meantime aren't trusted
+#[tauri::command]
+pub fn trust_workspace(fingerprint: String) -> Result<WorkspaceTrust, CommandError> {
+    let dir = crate::accounts::data_dir()?;
+    let (mut status, files) = check(&dir).map_err(CommandError::internal)?;
+    if status.fingerprint != fingerprint {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            "The scripts changed while you were reviewing them; review them again",
+        )
+        .with_details(serde_json::json!({ "fingerprint": status.fingerprint })));
+    }
+
+    let trusted_at = now_ms();
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut trusted = read_trusted()?;
+    trusted.retain(|entry| entry.workspace != status.workspace);
+    trusted.push(TrustedWorkspace {
+        workspace: status.workspace.clone(),
+        fingerprint,
+        files,
+        trusted_at,
+    });
+    write_trusted(&trusted)?;
+    log::info!("Trusted the scripts in {}", status.workspace);
+
+    status.state = TrustState::Trusted;
+    status.trusted_at = Some(trusted_at);
+    status.added.clear();
+    status.modified.clear();
+    status.removed.clear();
+    Ok(status)
+}
+
+/// Stop trusting the active data directory's scripts
+#[tauri::command]
+pub fn revoke_workspace_trust() -> Result<WorkspaceTrust, CommandError> {
+    let dir = crate::accounts::data_dir()?;
+    let workspace = workspace_key(&dir);
+    {
+        let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut trusted = read_trusted()?;
+        let before = trusted.len();
+        trusted.retain(|entry| entry.workspace != workspace);
+        if trusted.len() != before {
+            write_trusted(&trusted)?;
+            log::info!("Stopped trusting the scripts in {}", workspace);
+        }
+    }
+    get_workspace_trust()
+}
@@ -0,0 +1,4 @@
+// Helix Desktop - Privacy Module (secure wipe, data residency)
+
+pub mod residency;
+pub mod wipe;
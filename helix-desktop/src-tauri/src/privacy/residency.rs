@@ -0,0 +1,165 @@
+// Data residency - where each feature may process data
+//
+// The `data_residency` config section is a small matrix: voice transcription and
+// summarization run "local" or "cloud", and sync is on or off. Code about
+// to send user data off-device asks this module first, never the config
+// directly: providers with a local fallback check `allows`, features
+// without one call `require`, which fails with `disabled`. A config that
+// can't be read keeps data on the device. `explain_data_flow` tells the
+// user what a feature sends where under the current setting.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::commands::config::{self, DataResidencyConfig};
+use crate::commands::{CommandError, ErrorCode};
+
+/// A feature governed by the residency settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFeature {
+    VoiceTranscription,
+    Summarization,
+    Sync,
+}
+
+impl DataFeature {
+    fn label(self) -> &'static str {
+        match self {
+            DataFeature::VoiceTranscription => "Voice transcription",
+            DataFeature::Summarization => "Summarization",
+            DataFeature::Sync => "Sync",
+        }
+    }
+
+    /// Services contacted and what they receive when the feature may leave
+    /// the device
+    fn cloud_flow(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            DataFeature::VoiceTranscription => (&["Deepgram"], &["Audio sent to the voice pipeline for transcription"]),
+            DataFeature::Summarization => (
+                &["Google Gemini"],
+                &["Memory texts and ikigai items, embedded for the psychology analyses when a Google API key is set"],
+            ),
+            DataFeature::Sync => (
+                &["Supabase"],
+                &[
+                    "Device heartbeat and online status",
+                    "Session handoffs, including the recent conversation turns",
+                    "Subscriptions to changes of your memories, devices and plan",
+                    "Data exchanged by the sync coordinator with your other devices",
+                    "Memory searches from MCP clients and requests for your stored patterns",
+                    "Subscription entitlement checks",
+                    "Opted-in usage analytics, sent to the configured telemetry endpoint",
+                ],
+            ),
+        }
+    }
+
+    /// What happens instead when the feature is kept on the device
+    fn local_summary(self) -> &'static str {
+        match self {
+            DataFeature::VoiceTranscription => {
+                "Audio stays on this device; the voice pipeline transcribes it locally with whisper.cpp"
+            }
+            DataFeature::Summarization => "Memories are embedded with local keyword vectors on this device",
+            DataFeature::Sync => {
+                "Nothing is synced: the Realtime feed, session handoff, device heartbeat, sync coordinator, memory search, entitlement checks and analytics uploads are off"
+            }
+        }
+    }
+}
+
+/// What a feature sends where, as returned by explain_data_flow
+#[derive(Debug, Clone, Serialize)]
+pub struct DataFlow {
+    pub feature: DataFeature,
+    pub label: String,
+    /// "local" or "cloud"; "on" or "off" for sync
+    pub setting: String,
+    pub leaves_device: bool,
+    pub destinations: Vec<String>,
+    /// What is sent to the destinations
+    pub data: Vec<String>,
+    pub summary: String,
+}
+
+fn settings() -> Option<DataResidencyConfig> {
+    match config::get_config() {
+        Ok(config) => Some(config.data_residency),
+        Err(e) => {
+            log::warn!("Data residency settings unreadable, keeping data local: {}", e.message);
+            None
+        }
+    }
+}
+
+fn setting(residency: Option<&DataResidencyConfig>, feature: DataFeature) -> &'static str {
+    let Some(residency) = residency else {
+        return if feature == DataFeature::Sync { "off" } else { "local" };
+    };
+    match feature {
+        DataFeature::VoiceTranscription if residency.voice_transcription == "local" => "local",
+        DataFeature::Summarization if residency.summarization == "local" => "local",
+        DataFeature::Sync if residency.sync => "on",
+        DataFeature::Sync => "off",
+        _ => "cloud",
+    }
+}
+
+fn leaves_device(setting: &str) -> bool {
+    matches!(setting, "cloud" | "on")
+}
+
+/// Whether `feature` may send data off the device
+pub fn allows(feature: DataFeature) -> bool {
+    leaves_device(setting(settings().as_ref(), feature))
+}
+
+/// Fail with `disabled` unless `feature` may send data off the device
+pub fn require(feature: DataFeature) -> Result<(), CommandError> {
+    let setting = setting(settings().as_ref(), feature);
+    if leaves_device(setting) {
+        return Ok(());
+    }
+
+    let message = match feature {
+        DataFeature::Sync => "Sync is turned off in the data residency settings".to_string(),
+        _ => format!("{} is set to stay on this device", feature.label()),
+    };
+    Err(CommandError::new(ErrorCode::Disabled, message).with_details(json!({
+        "feature": feature,
+        "setting": setting,
+    })))
+}
+
+fn explain(feature: DataFeature) -> DataFlow {
+    let setting = setting(settings().as_ref(), feature);
+    let (destinations, data) = feature.cloud_flow();
+    let leaves_device = leaves_device(setting);
+
+    let (destinations, data, summary) = if leaves_device {
+        let summary = format!("{} sends data to {}", feature.label(), destinations.join(", "));
+        (destinations.iter().map(|d| d.to_string()).collect(), data.iter().map(|d| d.to_string()).collect(), summary)
+    } else {
+        (Vec::new(), Vec::new(), feature.local_summary().to_string())
+    };
+
+    DataFlow {
+        feature,
+        label: feature.label().to_string(),
+        setting: setting.to_string(),
+        leaves_device,
+        destinations,
+        data,
+        summary,
+    }
+}
+
+// Tauri commands
+
+/// What `feature` sends off the device, and to whom, under the current settings
+#[tauri::command]
+pub fn explain_data_flow(feature: DataFeature) -> Result<DataFlow, CommandError> {
+    Ok(explain(feature))
+}
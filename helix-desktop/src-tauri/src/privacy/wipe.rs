@@ -0,0 +1,357 @@
+// Secure wipe - delete selected local data beyond easy recovery
+//
+// Files are overwritten with random bytes and synced before being removed.
+// On SSDs and copy-on-write filesystems an overwrite may not reach the
+// original blocks, so encrypted psychology data is also crypto-erased by
+// destroying the vault key. Every wipe is appended to ~/.helix/wipe-audit.jsonl
+// (machine-level, so it survives wiping the account's data); the audit lists
+// what was wiped, never file contents.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::accounts;
+use crate::commands::{CommandError, ErrorCode};
+
+/// Phrase the user must type to confirm a wipe
+pub const CONFIRMATION_PHRASE: &str = "WIPE MY DATA";
+/// Audit log file within ~/.helix
+const AUDIT_FILENAME: &str = "wipe-audit.jsonl";
+/// Overwrite buffer size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// What to wipe
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WipeScope {
+    /// Helix and gateway session transcripts
+    #[serde(default)]
+    pub sessions: bool,
+    /// Soul and psychology layer files, queued guardrail writes, migration backups and the vault key
+    #[serde(default)]
+    pub psychology: bool,
+    /// The account's cache directory
+    #[serde(default)]
+    pub caches: bool,
+    /// API keys and webhooks stored in the OS keyring
+    #[serde(default)]
+    pub keyring: bool,
+    /// Ask the backend to delete this account's server-side data
+    #[serde(default)]
+    pub remote: bool,
+}
+
+impl WipeScope {
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        [
+            (self.sessions, "sessions"),
+            (self.psychology, "psychology"),
+            (self.caches, "caches"),
+            (self.keyring, "keyring"),
+        ]
+        .into_iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, name)| name)
+        .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.names().is_empty() && !self.remote
+    }
+}
+
+/// Outcome of a wipe; also the audit record
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WipeReport {
+    pub timestamp: u64,
+    pub user_id: Option<String>,
+    pub scope: WipeScope,
+    /// Top-level paths that were wiped
+    pub paths: Vec<String>,
+    pub files_wiped: usize,
+    pub bytes_overwritten: u64,
+    pub keyring_entries_removed: Vec<String>,
+    /// "requested", "failed" or null when remote deletion wasn't asked for
+    pub remote_request: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// Overwrite a file with random bytes, sync it, then delete it
+fn secure_delete_file(path: &Path) -> Result<u64, String> {
+    let len = fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to stat {:?}: {}", path, e))?
+        .len();
+
+    {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+
+        let mut remaining = len;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        while remaining > 0 {
+            let n = remaining.min(CHUNK_SIZE as u64) as usize;
+            rand::thread_rng().fill_bytes(&mut buffer[..n]);
+            file.write_all(&buffer[..n])
+                .map_err(|e| format!("Failed to overwrite {:?}: {}", path, e))?;
+            remaining -= n as u64;
+        }
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync {:?}: {}", path, e))?;
+    }
+
+    fs::remove_file(path).map_err(|e| format!("Failed to delete {:?}: {}", path, e))?;
+    Ok(len)
+}
+
+/// Securely delete a file or directory tree. Symlinks are removed, not followed.
+fn wipe_path(path: &Path, report: &mut WipeReport) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+
+    if metadata.file_type().is_symlink() {
+        if let Err(e) = fs::remove_file(path) {
+            report.errors.push(format!("Failed to remove link {:?}: {}", path, e));
+        }
+    } else if metadata.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                wipe_path(&entry.path(), report);
+            }
+        }
+        if let Err(e) = fs::remove_dir(path) {
+            report.errors.push(format!("Failed to remove {:?}: {}", path, e));
+        }
+    } else {
+        match secure_delete_file(path) {
+            Ok(bytes) => {
+                report.files_wiped += 1;
+                report.bytes_overwritten += bytes;
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+}
+
+fn wipe_root(path: PathBuf, report: &mut WipeReport) {
+    if fs::symlink_metadata(&path).is_ok() {
+        report.paths.push(path.to_string_lossy().to_string());
+        wipe_path(&path, report);
+    }
+}
+
+/// Helix sessions plus every gateway agent's sessions directory
+fn session_dirs() -> Result<Vec<PathBuf>, String> {
+    let mut dirs = vec![accounts::data_dir()?.join("sessions")];
+
+    if let Some(state_dir) = crate::gateway::config_sync::gateway_state_dir() {
+        if let Ok(agents) = fs::read_dir(state_dir.join("agents")) {
+            for agent in agents.flatten() {
+                dirs.push(agent.path().join("sessions"));
+            }
+        }
+    }
+
+    Ok(dirs)
+}
+
+fn wipe_keyring(report: &mut WipeReport) {
+    for key in crate::commands::keyring::USER_SECRET_KEYS {
+        match crate::commands::keyring::has_secret(key.to_string()) {
+            Ok(false) => continue,
+            Ok(true) => {}
+            Err(e) => {
+                report.errors.push(e.message);
+                continue;
+            }
+        }
+
+        match crate::commands::keyring::delete_secret(key.to_string()) {
+            Ok(()) => report.keyring_entries_removed.push(key.to_string()),
+            Err(e) => report.errors.push(e.message),
+        }
+    }
+}
+
+/// File a deletion request for the account's server-side data
+async fn request_remote_deletion(
+    user_id: &str,
+    access_token: &str,
+    scopes: &[&str],
+) -> Result<(), String> {
+    let (anon_key, _) = crate::commands::auth::get_supabase_credentials()?;
+    let supabase_url = crate::commands::auth::get_supabase_url()?;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/rest/v1/data_deletion_requests", supabase_url))
+        .header("apikey", &anon_key)
+        .header("Authorization", format!("Bearer {}", access_token))
+        .header("Content-Type", "application/json")
+        .timeout(Duration::from_secs(30))
+        .json(&serde_json::json!({
+            "user_id": user_id,
+            "scopes": scopes,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to request remote deletion: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Remote deletion request rejected: {}", response.status()))
+    }
+}
+
+fn audit_path() -> Result<PathBuf, String> {
+    Ok(accounts::helix_root()?.join(AUDIT_FILENAME))
+}
+
+fn append_audit(report: &WipeReport) -> Result<(), String> {
+    let path = audit_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .helix directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(report)
+        .map_err(|e| format!("Failed to serialize wipe record: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open wipe audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write wipe audit log: {}", e))
+}
+
+/// Get current timestamp in milliseconds
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Tauri commands for secure wipe
+
+/// Securely delete the selected local data and optionally request remote
+/// deletion. `confirmation` must equal CONFIRMATION_PHRASE; remote deletion
+/// needs the user's Supabase access token.
+#[tauri::command]
+pub async fn secure_wipe(
+    app: AppHandle,
+    scope: WipeScope,
+    confirmation: String,
+    access_token: Option<String>,
+) -> Result<WipeReport, CommandError> {
+    if confirmation.trim() != CONFIRMATION_PHRASE {
+        return Err(CommandError::new(
+            ErrorCode::ConfirmationRequired,
+            format!("Type \"{}\" to confirm", CONFIRMATION_PHRASE),
+        )
+        .with_details(serde_json::json!({ "phrase": CONFIRMATION_PHRASE })));
+    }
+    if scope.is_empty() {
+        return Err(CommandError::invalid_input("Nothing selected to wipe"));
+    }
+
+    let user_id = accounts::profiles::active_user_id();
+    let access_token = access_token.filter(|t| !t.trim().is_empty());
+    if scope.remote && (user_id.is_none() || access_token.is_none()) {
+        return Err(CommandError::unauthenticated("Remote deletion requires a signed-in account"));
+    }
+
+    wipe(&app, scope, access_token).await.map_err(Into::into)
+}
+
+/// Wipe `scope` of the active account's data, record it in the audit log
+/// and emit privacy:wiped. Callers check the confirmation.
+pub(crate) async fn wipe(app: &AppHandle, scope: WipeScope, access_token: Option<String>) -> Result<WipeReport, String> {
+    let user_id = accounts::profiles::active_user_id();
+    let mut report = WipeReport {
+        timestamp: current_timestamp(),
+        user_id: user_id.clone(),
+        scope: scope.clone(),
+        ..Default::default()
+    };
+    let data_dir = accounts::data_dir()?;
+
+    log::warn!("Secure wipe started: {:?}", scope.names());
+
+    if scope.sessions {
+        for dir in session_dirs()? {
+            wipe_root(dir, &mut report);
+        }
+    }
+
+    if scope.psychology {
+        for dir in crate::vault::store::VAULT_DIRS {
+            wipe_root(data_dir.join(dir), &mut report);
+        }
+        // Queued guardrail writes and migration backups hold psychology content too
+        wipe_root(data_dir.join(crate::policy::guardrails::GUARDRAILS_DIR), &mut report);
+        wipe_root(data_dir.join(crate::psychology::migrations::MIGRATIONS_DIR), &mut report);
+        if let Err(e) = crate::vault::store::destroy() {
+            report.errors.push(e);
+        }
+    }
+
+    if scope.caches {
+        // The response cache keeps its database open
+        crate::cache::responses::close().await;
+        if let Some(cache_dir) = dirs::cache_dir() {
+            wipe_root(accounts::profiles::scoped_os_dir(cache_dir.join("helix")), &mut report);
+        }
+    }
+
+    if scope.keyring {
+        wipe_keyring(&mut report);
+    }
+
+    if let (true, Some(user_id), Some(token)) = (scope.remote, &user_id, &access_token) {
+        match request_remote_deletion(user_id, token, &scope.names()).await {
+            Ok(()) => report.remote_request = Some("requested".to_string()),
+            Err(e) => {
+                report.remote_request = Some("failed".to_string());
+                report.errors.push(e);
+            }
+        }
+    }
+
+    if let Err(e) = append_audit(&report) {
+        log::error!("Failed to record wipe audit: {}", e);
+        report.errors.push(e);
+    }
+
+    log::warn!(
+        "Secure wipe finished: {} files, {} keyring entries, {} errors",
+        report.files_wiped,
+        report.keyring_entries_removed.len(),
+        report.errors.len()
+    );
+
+    let _ = app.emit("privacy:wiped", report.clone());
+    Ok(report)
+}
+
+/// Past wipes recorded on this machine, newest first
+#[tauri::command]
+pub fn get_wipe_history() -> Result<Vec<WipeReport>, CommandError> {
+    let content = match fs::read_to_string(audit_path()?) {
+        Ok(content) => content,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut history: Vec<WipeReport> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    history.reverse();
+    Ok(history)
+}
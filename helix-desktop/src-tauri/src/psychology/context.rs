@@ -0,0 +1,452 @@
+// Context bundles for LLM calls
+//
+// build_context packs what an agent should know about the user into a
+// token budget: the soul's sections, the memories most relevant to the
+// persona (and query), active goals and a summary of the most recent
+// gateway session. The desktop keeps no memory index of its own, so the
+// newest MEMORY_CANDIDATES memories are embedded (psychology/embeddings.rs)
+// and ranked against the persona on the device. Items are taken in
+// priority order - protected soul sections, goals, the session, memories by
+// relevance, then the remaining soul sections by relevance - while they
+// fit. Tokens are counted with the shared tokenizer (helix_shared, the
+// cl100k_base vocabulary shipped in the app's resources), and estimated at
+// four characters each if it is missing. Every item considered
+// is listed in `explanation` with why it was or wasn't included. Bundles
+// are cached for CACHE_TTL per account, persona, query and budget.
+// run_claude_code appends one to the system prompt, and gateway agents get
+// them from the local API and the MCP server.
+
+use helix_shared::Tokenizer;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use super::embeddings;
+use super::goals;
+use super::memories;
+use crate::commands::{config, psychology, CommandError};
+use crate::gateway::client;
+use crate::policy::guardrails::{heading_level, normalize_heading};
+use crate::remote::handoff;
+
+pub const DEFAULT_BUDGET_TOKENS: usize = 4000;
+const MIN_BUDGET_TOKENS: usize = 256;
+const MAX_BUDGET_TOKENS: usize = 200_000;
+const MEMORY_CANDIDATES: usize = 200;
+const MEMORY_TOP_K: usize = 8;
+const MAX_MEMORY_TOKENS: usize = 150;
+const SESSION_TURNS: usize = 6;
+const MAX_TURN_TOKENS: usize = 100;
+const SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextKind {
+    Soul,
+    Goals,
+    Session,
+    Memory,
+}
+
+/// An item that was considered for the bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextItem {
+    pub kind: ContextKind,
+    pub label: String,
+    pub tokens: usize,
+    pub included: bool,
+    pub reason: String,
+}
+
+/// Result of build_context
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextBundle {
+    pub persona: String,
+    pub query: Option<String>,
+    pub budget_tokens: usize,
+    pub used_tokens: usize,
+    /// The context, as markdown
+    pub text: String,
+    pub explanation: Vec<ContextItem>,
+    /// Sources that couldn't be read, and why
+    pub unavailable: Vec<String>,
+    pub generated_at: String,
+    /// Served from the cache
+    pub cached: bool,
+}
+
+type CacheKey = (Option<String>, String, Option<String>, usize);
+
+static CACHE: LazyLock<Mutex<HashMap<CacheKey, (Instant, ContextBundle)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn token_count(text: &str) -> usize {
+    Tokenizer::default().count(text)
+}
+
+/// A candidate piece of context
+struct Candidate {
+    kind: ContextKind,
+    label: String,
+    /// Rendered markdown, heading included
+    text: String,
+    reason: String,
+    /// Position in the final text
+    order: (u8, usize),
+}
+
+struct SoulSection {
+    heading: String,
+    body: String,
+    protected: bool,
+}
+
+/// Level-2 sections of the soul with a body
+fn soul_sections(doc: &str, protected: &[String]) -> Vec<SoulSection> {
+    let protected: Vec<String> = protected.iter().map(|h| normalize_heading(h)).collect();
+    let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in doc.lines() {
+        if heading_level(line).is_some_and(|level| level <= 2) {
+            sections.push((line.to_string(), Vec::new()));
+        } else if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line);
+        }
+    }
+
+    sections
+        .into_iter()
+        .map(|(heading, lines)| SoulSection {
+            protected: protected.contains(&normalize_heading(&heading)),
+            heading: heading.trim_start_matches('#').trim().to_string(),
+            body: lines.join("\n").trim().to_string(),
+        })
+        .filter(|section| !section.body.is_empty())
+        .collect()
+}
+
+fn goals_text() -> Result<Option<String>, CommandError> {
+    let stats = goals::goal_stats()?;
+    let lines: Vec<String> = stats
+        .goals
+        .iter()
+        .filter(|goal| goal.progress < 1.0)
+        .map(|goal| {
+            format!(
+                "- {} ({:.0}% done)",
+                goal.description.as_deref().unwrap_or(&goal.id),
+                goal.progress * 100.0
+            )
+        })
+        .collect();
+    Ok((!lines.is_empty()).then(|| format!("## Active goals\n{}", lines.join("\n"))))
+}
+
+/// Summary and last turns of the most recently updated gateway session
+async fn session_text() -> Result<Option<String>, String> {
+    let list = client::call("sessions.list", json!({})).await?;
+    let Some(session) = list.get("sessions").and_then(Value::as_array).and_then(|sessions| {
+        sessions
+            .iter()
+            .max_by_key(|s| s.get("updatedAt").and_then(Value::as_u64).unwrap_or(0))
+    }) else {
+        return Ok(None);
+    };
+    let key = session.get("key").and_then(Value::as_str).ok_or("Session without a key")?;
+    let title = ["displayName", "derivedTitle"]
+        .iter()
+        .find_map(|field| session.get(*field).and_then(Value::as_str));
+
+    // chat.history counts tool calls and results too, so ask for more
+    let history = client::call("chat.history", json!({ "sessionKey": key, "limit": SESSION_TURNS * 4 })).await?;
+    let messages = history.get("messages").and_then(Value::as_array).cloned().unwrap_or_default();
+    let turns = handoff::recent_turns(&messages, SESSION_TURNS);
+    if turns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut text = format!("## Recent session\n{}\n", handoff::default_summary(title, &turns));
+    for turn in &turns {
+        text.push_str(&format!("\n{}: {}", turn.role, handoff::truncate_tokens(&turn.text, MAX_TURN_TOKENS)));
+    }
+    Ok(Some(text))
+}
+
+/// The memories most relevant to `focus`, best first, with their scores
+async fn relevant_memories(focus: &str) -> Result<Vec<(f64, String, String)>, String> {
+    let records = memories::fetch_latest("id,content,created_at", None, None, MEMORY_CANDIDATES).await?;
+    let records: Vec<_> = records.into_iter().filter(|r| !r.content.trim().is_empty()).collect();
+    if records.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut texts = vec![focus.to_string()];
+    texts.extend(records.iter().map(|r| r.content.clone()));
+    let (_, vectors) = embeddings::embed(&texts).await;
+    let Some((focus_vector, memory_vectors)) = vectors.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut scored: Vec<(f64, String, String)> = records
+        .into_iter()
+        .zip(memory_vectors)
+        .map(|(record, vector)| {
+            let date = record.created_at.chars().take(10).collect();
+            (embeddings::cosine(focus_vector, vector), date, record.content)
+        })
+        .filter(|(score, _, _)| *score > 0.0)
+        .collect();
+    // Stable, so equal scores keep newest first
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(MEMORY_TOP_K);
+    Ok(scored)
+}
+
+async fn build(persona: &str, query: Option<&str>, budget_tokens: usize) -> Result<ContextBundle, CommandError> {
+    let focus = match query {
+        Some(query) => format!("{} {}", persona, query),
+        None => persona.to_string(),
+    };
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut unavailable = Vec::new();
+
+    // Soul: protected sections always lead, the rest follow by relevance
+    let protected = config::get_config().map(|c| c.guardrails.protected_sections).unwrap_or_default();
+    let mut other_soul = Vec::new();
+    match psychology::get_soul() {
+        Ok(soul) => {
+            for (index, section) in soul_sections(&soul.content, &protected).into_iter().enumerate() {
+                let text = format!("## {}\n{}", section.heading, section.body);
+                if section.protected {
+                    candidates.push(Candidate {
+                        kind: ContextKind::Soul,
+                        label: section.heading,
+                        text,
+                        reason: "protected soul section".to_string(),
+                        order: (0, index),
+                    });
+                } else {
+                    other_soul.push((index, section.heading, text));
+                }
+            }
+        }
+        Err(e) => unavailable.push(format!("Soul: {}", e.message)),
+    }
+
+    match goals_text() {
+        Ok(Some(text)) => candidates.push(Candidate {
+            kind: ContextKind::Goals,
+            label: "Active goals".to_string(),
+            text,
+            reason: "goals in progress".to_string(),
+            order: (1, 0),
+        }),
+        Ok(None) => {}
+        Err(e) => unavailable.push(format!("Goals: {}", e.message)),
+    }
+
+    match tokio::time::timeout(SESSION_TIMEOUT, session_text()).await {
+        Ok(Ok(Some(text))) => candidates.push(Candidate {
+            kind: ContextKind::Session,
+            label: "Recent session".to_string(),
+            text,
+            reason: "most recently updated gateway session".to_string(),
+            order: (3, 0),
+        }),
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => unavailable.push(format!("Session: {}", e)),
+        Err(_) => unavailable.push("Session: timed out reading from the gateway".to_string()),
+    }
+
+    match relevant_memories(&focus).await {
+        Ok(found) => {
+            for (rank, (score, date, content)) in found.into_iter().enumerate() {
+                candidates.push(Candidate {
+                    kind: ContextKind::Memory,
+                    label: handoff::truncate(&content, 60),
+                    text: format!("- ({}) {}", date, handoff::truncate_tokens(&content, MAX_MEMORY_TOKENS)),
+                    reason: format!("relevance {:.2}", score),
+                    order: (2, rank),
+                });
+            }
+        }
+        Err(e) => unavailable.push(format!("Memories: {}", e)),
+    }
+
+    if !other_soul.is_empty() {
+        let texts: Vec<String> = std::iter::once(focus.clone())
+            .chain(other_soul.iter().map(|(_, _, text)| text.clone()))
+            .collect();
+        let (_, vectors) = embeddings::embed(&texts).await;
+        let mut ranked: Vec<(f64, usize, String, String)> = other_soul
+            .into_iter()
+            .zip(vectors.iter().skip(1))
+            .map(|((index, heading, text), vector)| (embeddings::cosine(&vectors[0], vector), index, heading, text))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (score, index, heading, text) in ranked {
+            candidates.push(Candidate {
+                kind: ContextKind::Soul,
+                label: heading,
+                text,
+                reason: format!("soul section, relevance {:.2}", score),
+                order: (0, index),
+            });
+        }
+    }
+
+    // Take candidates in priority order while they fit
+    let memory_heading = "## Relevant memories";
+    let mut used_tokens = 0;
+    let mut included: Vec<&Candidate> = Vec::new();
+    let mut explanation = Vec::with_capacity(candidates.len());
+    for candidate in &candidates {
+        let mut tokens = token_count(&candidate.text);
+        let first_memory = candidate.kind == ContextKind::Memory
+            && !included.iter().any(|c| c.kind == ContextKind::Memory);
+        if first_memory {
+            tokens += token_count(memory_heading);
+        }
+
+        let fits = used_tokens + tokens <= budget_tokens;
+        if fits {
+            used_tokens += tokens;
+            included.push(candidate);
+        }
+        explanation.push(ContextItem {
+            kind: candidate.kind,
+            label: candidate.label.clone(),
+            tokens,
+            included: fits,
+            reason: if fits {
+                candidate.reason.clone()
+            } else {
+                format!("{}; over budget ({} tokens left)", candidate.reason, budget_tokens - used_tokens)
+            },
+        });
+    }
+
+    included.sort_by_key(|c| c.order);
+    let mut text = format!("# Helix context for {}\n", persona);
+    let mut memories_open = false;
+    for candidate in included {
+        if candidate.kind == ContextKind::Memory {
+            if !memories_open {
+                text.push_str(&format!("\n{}\n", memory_heading));
+                memories_open = true;
+            }
+            text.push_str(&format!("{}\n", candidate.text));
+        } else {
+            text.push_str(&format!("\n{}\n", candidate.text));
+        }
+    }
+
+    Ok(ContextBundle {
+        persona: persona.to_string(),
+        query: query.map(str::to_string),
+        budget_tokens,
+        used_tokens,
+        text,
+        explanation,
+        unavailable,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        cached: false,
+    })
+}
+
+/// A context bundle for `persona` within `budget_tokens`, from the cache
+/// unless it is stale or `refresh` is set
+pub async fn build_bundle(
+    persona: &str,
+    budget_tokens: usize,
+    query: Option<&str>,
+    refresh: bool,
+) -> Result<ContextBundle, CommandError> {
+    let persona = persona.trim();
+    if persona.is_empty() {
+        return Err(CommandError::invalid_input("persona is required"));
+    }
+    if !(MIN_BUDGET_TOKENS..=MAX_BUDGET_TOKENS).contains(&budget_tokens) {
+        return Err(CommandError::invalid_input(format!(
+            "budget_tokens must be between {} and {}",
+            MIN_BUDGET_TOKENS, MAX_BUDGET_TOKENS
+        )));
+    }
+    let query = query.map(str::trim).filter(|q| !q.is_empty());
+
+    let key: CacheKey = (
+        crate::accounts::profiles::active_user_id(),
+        persona.to_string(),
+        query.map(str::to_string),
+        budget_tokens,
+    );
+    if !refresh {
+        let cache = CACHE.lock().map_err(|e| e.to_string())?;
+        if let Some((built, bundle)) = cache.get(&key) {
+            if built.elapsed() < CACHE_TTL {
+                return Ok(ContextBundle {
+                    cached: true,
+                    ..bundle.clone()
+                });
+            }
+        }
+    }
+
+    let bundle = build(persona, query, budget_tokens).await?;
+    let mut cache = CACHE.lock().map_err(|e| e.to_string())?;
+    cache.retain(|_, (built, _)| built.elapsed() < CACHE_TTL);
+    cache.insert(key, (Instant::now(), bundle.clone()));
+    Ok(bundle)
+}
+
+// Tauri commands
+
+/// Assemble a token-budgeted context package for `persona`; `query` sharpens
+/// which memories and soul sections count as relevant
+#[tauri::command]
+pub async fn build_context(
+    persona: String,
+    budget_tokens: Option<usize>,
+    query: Option<String>,
+    refresh: Option<bool>,
+) -> Result<ContextBundle, CommandError> {
+    build_bundle(
+        &persona,
+        budget_tokens.unwrap_or(DEFAULT_BUDGET_TOKENS),
+        query.as_deref(),
+        refresh.unwrap_or(false),
+    )
+    .await
+}
+
+/// Token counts of a set of texts
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenCounts {
+    /// The vocabulary counted with; None when estimated
+    pub encoding: Option<String>,
+    pub counts: Vec<usize>,
+    pub total: usize,
+}
+
+/// Count the tokens of `texts` as `model` would (cl100k_base by default),
+/// for the session usage views
+#[tauri::command]
+pub async fn count_tokens(texts: Vec<String>, model: Option<String>) -> Result<TokenCounts, CommandError> {
+    // The first count loads the vocabulary
+    tokio::task::spawn_blocking(move || {
+        let tokenizer = model.as_deref().map_or_else(Tokenizer::default, Tokenizer::for_model);
+        let counts: Vec<usize> = texts.iter().map(|text| tokenizer.count(text)).collect();
+        TokenCounts {
+            encoding: match &tokenizer {
+                Tokenizer::Bpe(encoding) => Some(encoding.name().to_string()),
+                Tokenizer::Estimate => None,
+            },
+            total: counts.iter().sum(),
+            counts,
+        }
+    })
+    .await
+    .map_err(|e| CommandError::internal(e.to_string()))
+}
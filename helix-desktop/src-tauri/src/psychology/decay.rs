@@ -0,0 +1,477 @@
+// Memory decay for the integration layer, the in-process port of decay.py
+//
+// Each run fades what hasn't been reinforced:
+//   - emotional tags in psychology/emotional_tags.json lose a share of their
+//     intensity (HELIX_DECAY_RATE is what a day keeps), down to
+//     HELIX_MIN_INTENSITY; critical and high salience tags are left alone
+//     unless HELIX_PRESERVE_HIGH_SALIENCE is off
+//   - trust in psychology/trust_map.json and the per-user
+//     psychology/users/*/trust_profile.json drifts toward the 0.1 baseline,
+//     slower the more secure the attachment stage; the creator and, with
+//     high salience preserved, primary attachments are left alone
+// In soft mode (the default) the decayed value is written next to the
+// original (effective_intensity, effective_trust_score) so `restore` can
+// bring everything back; hard mode overwrites it. A day's retention comes
+// from psychology-decay's models: ExponentialDecay at HELIX_DECAY_RATE for
+// the tags, the attachment stage's model for trust.
+//
+// Settings are the HELIX_* variables decay.py reads, taken from
+// `scripts.env` in config, so either implementation behaves the same.
+// Files go through the vault, so unlike the script this works with
+// encrypted psychology data.
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use psychology_decay::{get_model_for_attachment_stage, DecayModel, ExponentialDecay};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::pass::{load, round3, save, timestamp, PassLog};
+use crate::commands::config;
+
+const EMOTIONAL_TAGS_FILE: &str = "psychology/emotional_tags.json";
+const TRUST_MAP_FILE: &str = "psychology/trust_map.json";
+const USERS_DIR: &str = "psychology/users";
+const TRUST_PROFILE_FILE: &str = "trust_profile.json";
+/// Helix's dispositional trust, which unreinforced trust returns to
+const BASELINE_TRUST: f64 = 0.1;
+const HIGH_SALIENCE_TIERS: &[&str] = &["critical", "high"];
+const CREATOR_IDS: &[&str] = &["rodrigo_specter", "RODRIGO_CREATOR_ID"];
+/// Smaller changes aren't written
+const MIN_CHANGE: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayMode {
+    /// Keep the original value, decay a copy
+    Soft,
+    /// Decay the value itself
+    Hard,
+}
+
+impl DecayMode {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Soft => "SOFT",
+            Self::Hard => "HARD",
+        }
+    }
+}
+
+/// decay.py's HELIX_* settings
+#[derive(Debug, Clone)]
+pub struct DecaySettings {
+    /// Share of emotional intensity a day keeps
+    pub rate: f64,
+    pub min_intensity: f64,
+    pub mode: DecayMode,
+    pub trust_decay: bool,
+    pub preserve_high_salience: bool,
+    pub dry_run: bool,
+}
+
+impl DecaySettings {
+    /// Settings from `scripts.env`, with decay.py's defaults
+    pub fn load(dry_run: bool) -> Result<Self, String> {
+        let env: BTreeMap<String, String> = config::get_config().map(|c| c.scripts.env).unwrap_or_default();
+        let number = |key: &str, default: f64| match env.get(key) {
+            Some(value) => value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{} must be a number, got '{}'", key, value)),
+            None => Ok(default),
+        };
+        let flag = |key: &str, default: bool| {
+            env.get(key)
+                .map_or(default, |value| value.trim().eq_ignore_ascii_case("true"))
+        };
+        let mode = match env.get("HELIX_DECAY_MODE").map(|mode| mode.trim().to_lowercase()).as_deref() {
+            None | Some("soft") => DecayMode::Soft,
+            Some("hard") => DecayMode::Hard,
+            Some(other) => return Err(format!("HELIX_DECAY_MODE must be soft or hard, got '{}'", other)),
+        };
+        Ok(Self {
+            rate: number("HELIX_DECAY_RATE", 0.95)?.clamp(0.0, 1.0),
+            min_intensity: number("HELIX_MIN_INTENSITY", 0.1)?,
+            mode,
+            trust_decay: flag("HELIX_TRUST_DECAY", true),
+            preserve_high_salience: flag("HELIX_PRESERVE_HIGH_SALIENCE", true),
+            dry_run: dry_run || flag("HELIX_DRY_RUN", false),
+        })
+    }
+}
+
+/// Outcome of a run, as decay.py writes to HELIX_RESULT_FILE
+#[derive(Debug, Clone, Serialize)]
+pub struct DecayReport {
+    pub mode: DecayMode,
+    pub dry_run: bool,
+    pub changes: usize,
+    pub skipped: usize,
+}
+
+/// Outcome of `restore`
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub restored: usize,
+}
+
+fn number(object: &Map<String, Value>, key: &str) -> Option<f64> {
+    object.get(key).and_then(Value::as_f64)
+}
+
+fn text<'a>(object: &'a Map<String, Value>, key: &str) -> &'a str {
+    object.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+fn name(object: &Map<String, Value>, key: &str) -> String {
+    object.get(key).and_then(Value::as_str).unwrap_or("unknown").to_string()
+}
+
+/// `value` after `elapsed` under `model`
+fn retained(model: &dyn DecayModel, value: f64, elapsed: Duration) -> f64 {
+    model.calculate_retention(elapsed, value as f32) as f64
+}
+
+/// Whole days since an ISO 8601 time, with or without an offset
+fn days_since(time: &str) -> Option<i64> {
+    let time = time.trim();
+    let at = DateTime::parse_from_rfc3339(time)
+        .map(|at| at.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%.f").map(|at| at.and_utc()))
+        .ok()?;
+    Some((Utc::now() - at).num_days())
+}
+
+/// Trust after a day without reinforcement: the stage's retention of its
+/// distance from BASELINE_TRUST, spread over longer when inactive. Returns
+/// the new trust, the stage retention and the inactivity multiplier.
+fn decayed_trust(current: f64, stage: &str, last_interaction: Option<&str>) -> (f64, f64, f64) {
+    let model = get_model_for_attachment_stage(stage);
+    let stage_rate = round3(retained(model.as_ref(), 1.0, Duration::days(1)));
+    let multiplier = match last_interaction.and_then(days_since) {
+        Some(days) if days > 90 => 2.0,
+        Some(days) if days > 30 => 1.5,
+        _ => 1.0,
+    };
+    // Inactive trust fades as if less time had passed, i.e. more slowly
+    let elapsed = Duration::minutes((24.0 * 60.0 / multiplier) as i64);
+    let distance = retained(model.as_ref(), (current - BASELINE_TRUST).abs(), elapsed);
+    let new = if current >= BASELINE_TRUST {
+        BASELINE_TRUST + distance
+    } else {
+        BASELINE_TRUST - distance
+    };
+    (new.clamp(0.0, 1.0), stage_rate, multiplier)
+}
+
+/// Decay emotional tag intensities; returns (decayed, skipped)
+fn decay_emotional_tags(data: &mut Value, settings: &DecaySettings, log: &mut PassLog) -> (usize, usize) {
+    let (mut count, mut skipped) = (0, 0);
+    let Some(tags) = data.get_mut("tags").and_then(Value::as_array_mut) else {
+        return (0, 0);
+    };
+    let daily = ExponentialDecay::from_daily_retention(settings.rate as f32);
+    for tag in tags.iter_mut().filter_map(Value::as_object_mut) {
+        let tier = text(tag, "salience_tier").to_lowercase();
+        if settings.preserve_high_salience && HIGH_SALIENCE_TIERS.contains(&tier.as_str()) {
+            skipped += 1;
+            if settings.dry_run {
+                log.push(format!("  [SKIP] {}: high salience ({})", name(tag, "name"), tier));
+            }
+            continue;
+        }
+        let Some(intensity) = number(tag, "intensity") else {
+            continue;
+        };
+        let current = match settings.mode {
+            DecayMode::Soft => number(tag, "effective_intensity").unwrap_or(intensity),
+            DecayMode::Hard => intensity,
+        };
+        let new = retained(&daily, current, Duration::days(1)).max(settings.min_intensity);
+        if (new - current).abs() <= MIN_CHANGE {
+            continue;
+        }
+
+        match settings.mode {
+            DecayMode::Soft => {
+                if !tag.contains_key("original_intensity") {
+                    tag.insert("original_intensity".to_string(), intensity.into());
+                }
+                tag.insert("effective_intensity".to_string(), round3(new).into());
+                let cycles = tag.get("decay_cycles").and_then(Value::as_u64).unwrap_or(0);
+                tag.insert("decay_cycles".to_string(), (cycles + 1).into());
+            }
+            DecayMode::Hard => {
+                tag.insert("intensity".to_string(), round3(new).into());
+            }
+        }
+        tag.insert("last_decay".to_string(), timestamp().into());
+        tag.insert("decay_mode".to_string(), serde_json::to_value(settings.mode).unwrap_or_default());
+        count += 1;
+        if settings.dry_run {
+            log.push(format!(
+                "  [DRY RUN] [{}] {}: {:.3} -> {:.3}",
+                settings.mode.label(),
+                name(tag, "name"),
+                current,
+                new
+            ));
+        }
+    }
+
+    if !settings.dry_run && count > 0 {
+        if let Some(data) = data.as_object_mut() {
+            data.insert("_last_decay_run".to_string(), timestamp().into());
+            data.insert("_decay_mode".to_string(), serde_json::to_value(settings.mode).unwrap_or_default());
+            data.insert("_decay_rate".to_string(), settings.rate.into());
+        }
+    }
+    (count, skipped)
+}
+
+/// Decay trust_map.json relationships; returns (decayed, skipped)
+fn decay_trust_scores(data: &mut Value, settings: &DecaySettings, log: &mut PassLog) -> (usize, usize) {
+    let (mut count, mut skipped) = (0, 0);
+    let Some(relationships) = data.get_mut("relationships").and_then(Value::as_array_mut) else {
+        return (0, 0);
+    };
+    for rel in relationships.iter_mut().filter_map(Value::as_object_mut) {
+        let entity = name(rel, "entity");
+        let is_creator = rel.get("is_creator").and_then(Value::as_bool).unwrap_or(false);
+        if entity == CREATOR_IDS[0] || is_creator {
+            skipped += 1;
+            if settings.dry_run {
+                log.push(format!("  [SKIP] {}: creator (immutable)", entity));
+            }
+            continue;
+        }
+        if settings.preserve_high_salience && text(rel, "attachment_type") == "primary" {
+            skipped += 1;
+            if settings.dry_run {
+                log.push(format!("  [SKIP] {}: primary attachment", entity));
+            }
+            continue;
+        }
+        let Some(trust) = number(rel, "trust_score") else {
+            continue;
+        };
+        let current = match settings.mode {
+            DecayMode::Soft => number(rel, "effective_trust_score").unwrap_or(trust),
+            DecayMode::Hard => trust,
+        };
+        let stage = rel.get("attachment_stage").and_then(Value::as_str).unwrap_or("pre_attachment");
+        let last_interaction = rel.get("last_interaction_at").and_then(Value::as_str);
+        let (new, stage_rate, multiplier) = decayed_trust(current, stage, last_interaction);
+        if (new - current).abs() <= MIN_CHANGE {
+            continue;
+        }
+
+        match settings.mode {
+            DecayMode::Soft => {
+                if !rel.contains_key("original_trust_score") {
+                    rel.insert("original_trust_score".to_string(), trust.into());
+                }
+                rel.insert("effective_trust_score".to_string(), round3(new).into());
+                let cycles = rel.get("decay_cycles").and_then(Value::as_u64).unwrap_or(0);
+                rel.insert("decay_cycles".to_string(), (cycles + 1).into());
+            }
+            DecayMode::Hard => {
+                rel.insert("trust_score".to_string(), round3(new).into());
+            }
+        }
+        rel.insert("last_decay".to_string(), timestamp().into());
+        rel.insert("decay_mode".to_string(), serde_json::to_value(settings.mode).unwrap_or_default());
+        rel.insert("stage_decay_rate".to_string(), stage_rate.into());
+        rel.insert("activity_multiplier".to_string(), multiplier.into());
+        count += 1;
+        if settings.dry_run {
+            let inactive = if multiplier > 1.0 {
+                format!(" (inactive {}x)", multiplier)
+            } else {
+                String::new()
+            };
+            log.push(format!(
+                "  [DRY RUN] [{}] {}: {:.3} -> {:.3}{}",
+                settings.mode.label(),
+                entity,
+                current,
+                new,
+                inactive
+            ));
+        }
+    }
+
+    if !settings.dry_run && count > 0 {
+        if let Some(data) = data.as_object_mut() {
+            data.insert("_last_decay_run".to_string(), timestamp().into());
+            data.insert("_decay_mode".to_string(), serde_json::to_value(settings.mode).unwrap_or_default());
+            data.insert("_baseline_trust".to_string(), BASELINE_TRUST.into());
+        }
+    }
+    (count, skipped)
+}
+
+/// Decay composite trust of per-user profiles; returns (decayed, skipped)
+fn decay_user_profiles(helix_dir: &Path, settings: &DecaySettings, log: &mut PassLog) -> Result<(usize, usize), String> {
+    let (mut count, mut skipped) = (0, 0);
+    let users_dir = helix_dir.join(USERS_DIR);
+    if !users_dir.is_dir() {
+        if settings.dry_run {
+            log.push("  [INFO] No users directory yet (multi-user system not initialized)".to_string());
+        }
+        return Ok((0, 0));
+    }
+
+    let mut user_ids: Vec<String> = std::fs::read_dir(&users_dir)
+        .map_err(|e| format!("Failed to read {}: {}", USERS_DIR, e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    user_ids.sort();
+    for user_id in user_ids {
+        if CREATOR_IDS.contains(&user_id.as_str()) {
+            skipped += 1;
+            if settings.dry_run {
+                log.push(format!("  [SKIP] {}: creator profile (immutable)", user_id));
+            }
+            continue;
+        }
+        let file_rel = format!("{}/{}/{}", USERS_DIR, user_id, TRUST_PROFILE_FILE);
+        if !helix_dir.join(&file_rel).is_file() {
+            continue;
+        }
+        let Some(mut profile) = load(helix_dir, &file_rel, log) else {
+            continue;
+        };
+        let Some(object) = profile.as_object_mut() else {
+            continue;
+        };
+
+        let current = number(object, "composite_trust").unwrap_or(BASELINE_TRUST);
+        let stage = object.get("attachment_stage").and_then(Value::as_str).unwrap_or("pre_attachment");
+        let last_interaction = object.get("last_interaction_at").and_then(Value::as_str);
+        let inactive_days = last_interaction.and_then(days_since);
+        let (new, _, _) = decayed_trust(current, stage, last_interaction);
+        if (new - current).abs() <= MIN_CHANGE {
+            continue;
+        }
+
+        count += 1;
+        if settings.dry_run {
+            let inactive = inactive_days.map(|days| format!(" ({}d inactive)", days)).unwrap_or_default();
+            log.push(format!("  [DRY RUN] {}: {:.3} -> {:.3}{}", user_id, current, new, inactive));
+            continue;
+        }
+        object.insert("composite_trust".to_string(), round3(new).into());
+        object.insert("last_decay".to_string(), timestamp().into());
+        save(helix_dir, &file_rel, &profile)?;
+    }
+    Ok((count, skipped))
+}
+
+/// Run a decay pass over the data directory, writing nothing in a dry run.
+/// Progress goes to `log` line by line, as it is made.
+pub fn run(helix_dir: &Path, settings: &DecaySettings, log: &mut PassLog) -> Result<DecayReport, String> {
+    log.push(format!("[HELIX] Layer 5 Decay Process - {}", timestamp()));
+    log.push(format!(
+        "  Rate: {} | Min: {} | Mode: {}",
+        settings.rate,
+        settings.min_intensity,
+        settings.mode.label().to_lowercase()
+    ));
+    log.push(format!(
+        "  Trust Decay: {} | Preserve High Salience: {}",
+        settings.trust_decay, settings.preserve_high_salience
+    ));
+    log.push(format!("  Dry Run: {}", settings.dry_run));
+
+    let (mut changes, mut skipped) = (0, 0);
+
+    log.push(format!("Processing: {}", EMOTIONAL_TAGS_FILE));
+    if let Some(mut data) = load(helix_dir, EMOTIONAL_TAGS_FILE, log) {
+        let (count, skip) = decay_emotional_tags(&mut data, settings, log);
+        changes += count;
+        skipped += skip;
+        log.push(format!("  Decayed {} emotional tag(s), skipped {}", count, skip));
+        if !settings.dry_run && count > 0 {
+            save(helix_dir, EMOTIONAL_TAGS_FILE, &data)?;
+            log.push("  Saved successfully".to_string());
+        }
+    }
+
+    log.push(format!("Processing: {}", TRUST_MAP_FILE));
+    if !settings.trust_decay {
+        log.push("  Trust decay is off".to_string());
+    } else if let Some(mut data) = load(helix_dir, TRUST_MAP_FILE, log) {
+        let (count, skip) = decay_trust_scores(&mut data, settings, log);
+        changes += count;
+        skipped += skip;
+        log.push(format!("  Decayed {} trust score(s), skipped {}", count, skip));
+        if !settings.dry_run && count > 0 {
+            save(helix_dir, TRUST_MAP_FILE, &data)?;
+            log.push("  Saved successfully".to_string());
+        }
+    }
+
+    log.push(format!("Processing: {}/*/{} (per-user trust)", USERS_DIR, TRUST_PROFILE_FILE));
+    let (count, skip) = decay_user_profiles(helix_dir, settings, log)?;
+    changes += count;
+    skipped += skip;
+    log.push(format!("  Decayed {} user profile(s), skipped {}", count, skip));
+
+    log.push(format!("[HELIX] Decay complete. Total changes: {}, Total skipped: {}", changes, skipped));
+    Ok(DecayReport {
+        mode: settings.mode,
+        dry_run: settings.dry_run,
+        changes,
+        skipped,
+    })
+}
+
+/// Put back the originals that soft decay kept, in `items` (tags or
+/// relationships) under `field`; returns how many were restored
+fn restore_items(data: &mut Value, items: &str, field: &str) -> usize {
+    let original = format!("original_{}", field);
+    let effective = format!("effective_{}", field);
+    let mut count = 0;
+    let Some(items) = data.get_mut(items).and_then(Value::as_array_mut) else {
+        return 0;
+    };
+    for item in items.iter_mut().filter_map(Value::as_object_mut) {
+        let Some(value) = item.remove(&original) else {
+            continue;
+        };
+        item.insert(field.to_string(), value);
+        item.remove(&effective);
+        item.remove("decay_cycles");
+        item.insert("restored_at".to_string(), timestamp().into());
+        count += 1;
+    }
+    count
+}
+
+/// Undo soft decay: emotional intensities and trust scores go back to
+/// their originals
+pub fn restore(helix_dir: &Path, log: &mut PassLog) -> Result<RestoreReport, String> {
+    log.push("[HELIX] Restoring from soft decay...".to_string());
+    let mut restored = 0;
+    for (file_rel, items, field, label) in [
+        (EMOTIONAL_TAGS_FILE, "tags", "intensity", "emotional tags"),
+        (TRUST_MAP_FILE, "relationships", "trust_score", "trust scores"),
+    ] {
+        let Some(mut data) = load(helix_dir, file_rel, log) else {
+            continue;
+        };
+        let count = restore_items(&mut data, items, field);
+        if count > 0 {
+            save(helix_dir, file_rel, &data)?;
+            log.push(format!("  Restored {} {}", count, label));
+            restored += count;
+        }
+    }
+    log.push("[HELIX] Restore complete.".to_string());
+    Ok(RestoreReport { restored })
+}
@@ -0,0 +1,183 @@
+// Text embeddings for the psychology analyses
+//
+// Uses Gemini embeddings when a Google API key is in the keyring, the same
+// model the runtime's memory search uses. Without a key, while offline, when
+// the residency settings keep summarization local, or when the API fails,
+// texts get a local hashed bag-of-keywords vector instead: coarser, but it
+// keeps the analyses working offline. Vectors from the two embedders are
+// not comparable, so a batch is always embedded by one. Gemini vectors are
+// kept in the response cache, so unchanged texts aren't embedded again.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::cache::responses;
+use crate::calendar::commitments::keywords;
+use crate::commands::keyring;
+use crate::privacy::residency::{self, DataFeature};
+
+const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const GEMINI_MODEL: &str = "models/gemini-embedding-001";
+const GEMINI_TASK_TYPE: &str = "SEMANTIC_SIMILARITY";
+const GOOGLE_KEY: &str = "google_api_key";
+/// Response cache namespace and lifetime of Gemini vectors
+const CACHE_NAMESPACE: &str = "embedding";
+const CACHE_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// batchEmbedContents accepts at most this many texts per request
+const GEMINI_BATCH: usize = 100;
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+/// Longer texts are truncated before embedding
+const MAX_TEXT_CHARS: usize = 2000;
+const HASHED_DIMENSIONS: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Embedder {
+    Gemini,
+    /// Local hashed keyword vectors
+    Hashed,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiEmbedding {
+    #[serde(default)]
+    values: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiBatchResponse {
+    #[serde(default)]
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_TEXT_CHARS).collect()
+}
+
+/// FNV-1a, stable across builds unlike the std hasher
+fn fnv1a(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn hashed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; HASHED_DIMENSIONS];
+    for keyword in keywords(text) {
+        let hash = fnv1a(&keyword);
+        // The top bit picks the sign so collisions tend to cancel out
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % HASHED_DIMENSIONS as u64) as usize] += sign;
+    }
+    vector
+}
+
+async fn request_gemini(api_key: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(GEMINI_BATCH) {
+        let requests: Vec<_> = batch
+            .iter()
+            .map(|text| {
+                json!({
+                    "model": GEMINI_MODEL,
+                    "content": { "parts": [{ "text": truncate(text) }] },
+                    "taskType": GEMINI_TASK_TYPE,
+                })
+            })
+            .collect();
+
+        let response = client
+            .post(format!("{}/{}:batchEmbedContents", GEMINI_BASE_URL, GEMINI_MODEL))
+            .header("x-goog-api-key", api_key)
+            .json(&json!({ "requests": requests }))
+            .send()
+            .await
+            .map_err(|e| format!("Embedding request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Embedding request failed: HTTP {}", response.status()));
+        }
+
+        let response: GeminiBatchResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid embedding response: {}", e))?;
+        if response.embeddings.len() != batch.len() {
+            return Err(format!(
+                "Embedding response has {} vectors for {} texts",
+                response.embeddings.len(),
+                batch.len()
+            ));
+        }
+        vectors.extend(response.embeddings.into_iter().map(|e| e.values));
+    }
+    Ok(vectors)
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Option<Vec<f32>> {
+    (bytes.len().is_multiple_of(4) && !bytes.is_empty()).then(|| {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    })
+}
+
+/// Gemini vectors of `texts`, requesting only those not in the cache
+async fn gemini(api_key: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let mut vectors: Vec<Option<Vec<f32>>> = Vec::with_capacity(texts.len());
+    let mut keys = Vec::with_capacity(texts.len());
+    for text in texts {
+        let key = responses::key(CACHE_NAMESPACE, &[GEMINI_MODEL, GEMINI_TASK_TYPE, &truncate(text)]);
+        let cached = responses::get(CACHE_NAMESPACE, &key).await.and_then(|bytes| decode_vector(&bytes));
+        vectors.push(cached);
+        keys.push(key);
+    }
+
+    let missing: Vec<usize> = (0..texts.len()).filter(|&i| vectors[i].is_none()).collect();
+    if !missing.is_empty() {
+        let requested: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+        let fetched = request_gemini(api_key, &requested).await?;
+        for (i, vector) in missing.into_iter().zip(fetched) {
+            responses::put(CACHE_NAMESPACE, &keys[i], &encode_vector(&vector), CACHE_TTL).await;
+            vectors[i] = Some(vector);
+        }
+    }
+    Ok(vectors.into_iter().map(Option::unwrap_or_default).collect())
+}
+
+/// Embed every text with the same embedder, in order
+pub async fn embed(texts: &[String]) -> (Embedder, Vec<Vec<f32>>) {
+    // Offline, the API call could only fail after its timeout
+    let api_key = (residency::allows(DataFeature::Summarization) && crate::remote::connectivity::is_online())
+        .then(|| keyring::get_secret(GOOGLE_KEY.to_string()).ok().flatten())
+        .flatten();
+    if let Some(api_key) = api_key.filter(|k| !k.trim().is_empty()) {
+        match gemini(&api_key, texts).await {
+            Ok(vectors) => return (Embedder::Gemini, vectors),
+            Err(e) => log::warn!("Gemini embeddings unavailable, using local vectors: {}", e),
+        }
+    }
+    (Embedder::Hashed, texts.iter().map(|text| hashed(text)).collect())
+}
+
+/// 0 when either vector is empty or zero
+pub fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let (dot, norm_a, norm_b) = a.iter().zip(b).fold((0.0, 0.0, 0.0), |(dot, na, nb), (x, y)| {
+        let (x, y) = (*x as f64, *y as f64);
+        (dot + x * y, na + x * x, nb + y * y)
+    });
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
@@ -0,0 +1,314 @@
+// Goal progress tracking for the prospective layer
+//
+// Core goals in identity/goals.json carry `progress` (0..1) and, since
+// schema 1.1, a `check_ins` history. log_goal_progress appends a check-in
+// and updates progress and milestones. A daily GoalReminder scheduler job
+// emits goals:reminder for goals with no check-in for
+// `goals.reminder_after_days` (or the goal's own `reminder_days`), and
+// goal stats are added to the synthesis (integration) report.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::psychology::{read_layer_file, write_files};
+use crate::commands::scheduler::{self, JobStatus, JobType};
+use crate::commands::scheduler_runner;
+use crate::commands::{config, CommandError};
+
+const GOALS_FILE: &str = "identity/goals.json";
+/// How often the reminder job is checked for being due
+const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+const REMINDER_CRON: &str = "0 9 * * *";
+const REMINDER_PERIOD_SECS: u64 = 24 * 3600;
+
+/// One logged check-in
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckIn {
+    pub at: String,
+    pub progress: Option<f64>,
+    /// Change from the previous progress value
+    pub delta: Option<f64>,
+    pub note: Option<String>,
+    /// Milestone marked achieved with this check-in
+    pub milestone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalSummary {
+    pub id: String,
+    pub description: Option<String>,
+    pub progress: f64,
+    pub check_ins: usize,
+    pub last_check_in: Option<String>,
+    /// Days since the last check-in, or since the file was last updated
+    /// for goals never checked in on
+    pub days_since_check_in: Option<i64>,
+    pub milestones_achieved: usize,
+    pub milestones_total: usize,
+    pub reminder_due: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalStats {
+    pub goals: Vec<GoalSummary>,
+    pub average_progress: f64,
+    /// Check-ins across all goals in the last 7 days
+    pub check_ins_last_week: usize,
+    pub reminders_due: usize,
+    pub generated_at: String,
+}
+
+fn helix_dir() -> Result<std::path::PathBuf, CommandError> {
+    Ok(crate::accounts::data_dir()?)
+}
+
+fn load_goals() -> Result<Value, CommandError> {
+    let helix_dir = helix_dir()?;
+    if !helix_dir.join(GOALS_FILE).exists() {
+        return Err(CommandError::not_found("No goals file in the prospective layer"));
+    }
+    read_layer_file(&helix_dir, GOALS_FILE)
+}
+
+fn reminder_after_days() -> u32 {
+    config::get_config()
+        .map(|c| c.goals.reminder_after_days)
+        .unwrap_or_else(|_| config::GoalsConfig::default().reminder_after_days)
+}
+
+/// RFC 3339 timestamp or plain YYYY-MM-DD date
+fn parse_moment(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| d.and_utc())
+        })
+}
+
+fn summarize(goal: &Map<String, Value>, file_updated: Option<&str>, default_days: u32, now: DateTime<Utc>) -> GoalSummary {
+    let check_ins = goal.get("check_ins").and_then(Value::as_array);
+    let last_check_in = goal.get("last_check_in").and_then(Value::as_str).map(str::to_string);
+    let milestones = goal.get("milestones").and_then(Value::as_array);
+
+    let since = last_check_in.as_deref().or(file_updated).and_then(parse_moment);
+    let days_since_check_in = since.map(|since| (now - since).num_days());
+    let reminder_days = goal
+        .get("reminder_days")
+        .and_then(Value::as_u64)
+        .unwrap_or(default_days as u64) as i64;
+
+    GoalSummary {
+        id: goal.get("id").and_then(Value::as_str).unwrap_or_default().to_string(),
+        description: goal.get("description").and_then(Value::as_str).map(str::to_string),
+        progress: goal.get("progress").and_then(Value::as_f64).unwrap_or(0.0),
+        check_ins: check_ins.map_or(0, Vec::len),
+        last_check_in,
+        days_since_check_in,
+        milestones_achieved: milestones.map_or(0, |m| {
+            m.iter()
+                .filter(|m| m.get("achieved").and_then(Value::as_bool).unwrap_or(false))
+                .count()
+        }),
+        milestones_total: milestones.map_or(0, Vec::len),
+        reminder_due: days_since_check_in.is_some_and(|days| days >= reminder_days),
+    }
+}
+
+/// Progress, check-in and reminder figures for every core goal
+pub fn goal_stats() -> Result<GoalStats, CommandError> {
+    let doc = load_goals()?;
+    let default_days = reminder_after_days();
+    let now = Utc::now();
+    let file_updated = doc.get("last_updated").and_then(Value::as_str);
+
+    let core_goals: Vec<&Map<String, Value>> = doc
+        .get("core_goals")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .collect();
+
+    let week_ago = now - chrono::Duration::days(7);
+    let check_ins_last_week = core_goals
+        .iter()
+        .filter_map(|goal| goal.get("check_ins").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|c| c.get("at").and_then(Value::as_str).and_then(parse_moment))
+        .filter(|at| *at >= week_ago)
+        .count();
+
+    let goals: Vec<GoalSummary> = core_goals
+        .iter()
+        .map(|goal| summarize(goal, file_updated, default_days, now))
+        .collect();
+    let average_progress = if goals.is_empty() {
+        0.0
+    } else {
+        goals.iter().map(|g| g.progress).sum::<f64>() / goals.len() as f64
+    };
+
+    Ok(GoalStats {
+        reminders_due: goals.iter().filter(|g| g.reminder_due).count(),
+        average_progress,
+        check_ins_last_week,
+        goals,
+        generated_at: now.to_rfc3339(),
+    })
+}
+
+// Reminders
+
+/// Payload of goals:reminder
+#[derive(Debug, Clone, Serialize)]
+struct GoalReminder {
+    goals: Vec<GoalSummary>,
+}
+
+/// Run the reminder job if it is due
+async fn run_reminder_job(app: &AppHandle) -> Result<(), CommandError> {
+    let enabled = config::get_config().map(|c| c.goals.reminders).unwrap_or(true);
+    if !enabled {
+        return Ok(());
+    }
+
+    let job = scheduler::ensure_job(JobType::GoalReminder, REMINDER_CRON)?;
+    let now = Utc::now().timestamp().max(0) as u64;
+    if job.status == JobStatus::Paused || job.next_run > now {
+        return Ok(());
+    }
+
+    // Deferred during do-not-disturb; next_run moves to its end
+    let job = scheduler::trigger_job(job.id).await?;
+    if job.status == JobStatus::Deferred {
+        return Ok(());
+    }
+
+    let reminders = async {
+        goal_stats().map(|stats| {
+            let due: Vec<GoalSummary> = stats.goals.into_iter().filter(|g| g.reminder_due).collect();
+            let ids: Vec<String> = due.iter().map(|g| g.id.clone()).collect();
+            if !due.is_empty() {
+                let _ = app.emit("goals:reminder", GoalReminder { goals: due });
+            }
+            ids
+        })
+    };
+    let result = scheduler_runner::run_owned(&job, reminders).await;
+
+    match result {
+        Ok(ids) => scheduler::complete_job(job.id.clone(), Some(json!({ "reminded": ids })))?,
+        Err(e) => scheduler::fail_job(job.id.clone(), e.message)?,
+    }
+    scheduler::reschedule_job(&job.id, now + REMINDER_PERIOD_SECS)
+}
+
+/// Check the reminder job every hour
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_reminder_job(&app).await {
+                log::debug!("Goal reminder check skipped: {}", e);
+            }
+            tokio::time::sleep(REMINDER_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// Tauri commands
+
+/// Progress, check-in and reminder figures for every core goal
+#[tauri::command]
+pub fn get_goal_stats() -> Result<GoalStats, CommandError> {
+    goal_stats()
+}
+
+/// Record a check-in on a goal: new progress (0..1), a note, and/or a
+/// milestone reached
+#[tauri::command]
+pub fn log_goal_progress(
+    goal_id: String,
+    progress: Option<f64>,
+    note: Option<String>,
+    milestone: Option<String>,
+) -> Result<GoalSummary, CommandError> {
+    if let Some(progress) = progress {
+        if !(0.0..=1.0).contains(&progress) {
+            return Err(CommandError::invalid_input("Progress must be between 0 and 1")
+                .with_details(json!({ "progress": progress })));
+        }
+    }
+    let note = note.filter(|n| !n.trim().is_empty());
+    if progress.is_none() && note.is_none() && milestone.is_none() {
+        return Err(CommandError::invalid_input("A check-in needs progress, a note or a milestone"));
+    }
+
+    let mut doc = load_goals()?;
+    let now = Utc::now();
+    let timestamp = now.to_rfc3339();
+
+    let goal = doc
+        .get_mut("core_goals")
+        .and_then(Value::as_array_mut)
+        .and_then(|goals| {
+            goals
+                .iter_mut()
+                .filter_map(Value::as_object_mut)
+                .find(|g| g.get("id").and_then(Value::as_str) == Some(goal_id.as_str()))
+        })
+        .ok_or_else(|| {
+            CommandError::not_found(format!("Unknown goal: {}", goal_id))
+                .with_details(json!({ "goal_id": goal_id }))
+        })?;
+
+    if let Some(name) = &milestone {
+        let entry = goal
+            .get_mut("milestones")
+            .and_then(Value::as_array_mut)
+            .and_then(|m| m.iter_mut().find(|m| m.get("name").and_then(Value::as_str) == Some(name.as_str())))
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| {
+                CommandError::not_found(format!("Goal {} has no milestone {}", goal_id, name))
+                    .with_details(json!({ "goal_id": goal_id, "milestone": name }))
+            })?;
+        entry.insert("achieved".to_string(), Value::Bool(true));
+        entry.insert("date".to_string(), Value::String(now.format("%Y-%m-%d").to_string()));
+    }
+
+    let previous = goal.get("progress").and_then(Value::as_f64);
+    if let Some(progress) = progress {
+        goal.insert("progress".to_string(), json!(progress));
+    }
+
+    let check_in = CheckIn {
+        at: timestamp.clone(),
+        progress,
+        delta: progress.zip(previous).map(|(new, old)| new - old),
+        note,
+        milestone,
+    };
+    let check_in = serde_json::to_value(&check_in).map_err(|e| format!("Failed to serialize check-in: {}", e))?;
+    match goal.get_mut("check_ins").and_then(Value::as_array_mut) {
+        Some(check_ins) => check_ins.push(check_in),
+        None => {
+            goal.insert("check_ins".to_string(), Value::Array(vec![check_in]));
+        }
+    }
+    goal.insert("last_check_in".to_string(), Value::String(timestamp));
+
+    let default_days = reminder_after_days();
+    let summary = summarize(goal, None, default_days, now);
+
+    doc["last_updated"] = Value::String(now.format("%Y-%m-%d").to_string());
+    let content = serde_json::to_string_pretty(&doc).map_err(|e| format!("Failed to serialize goals: {}", e))?;
+    write_files(&helix_dir()?, &[(GOALS_FILE, content)])?;
+
+    Ok(summary)
+}
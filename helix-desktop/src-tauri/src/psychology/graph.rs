@@ -0,0 +1,413 @@
+// Relationship graph for the relational layer
+//
+// Nodes come from attachments.json (primary and secondary attachments)
+// and trust_map.json (trust profiles), with Helix at the centre. Edges
+// from Helix carry the relational trust weight plus a mention strength
+// learned from memories; people mentioned in the same memory get a
+// co-mention edge. Mention counts live in psychology/relationship_graph.json
+// and are brought up to date from new memories during integration
+// (run_synthesis); the graph itself is rebuilt on every read so edits to
+// the layer files show up immediately.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+
+use super::memories::{self, MemoryRecord};
+use crate::commands::psychology::{get_layer, vault_error};
+use crate::commands::CommandError;
+use crate::vault;
+
+/// Mention statistics, relative to the data directory
+const STATE_FILE: &str = "psychology/relationship_graph.json";
+const STATE_SCHEMA_VERSION: &str = "1.0";
+const HELIX_NODE: &str = "helix";
+/// Memories fetched per integration run
+const MEMORY_BATCH: usize = 500;
+const MEMORY_COLUMNS: &str = "content,emotional_valence,created_at";
+/// Mention weight halves after this many days without new mentions
+const MENTION_HALF_LIFE_DAYS: f64 = 30.0;
+/// Mention weight at which strength reaches 0.5
+const STRENGTH_SATURATION: f64 = 5.0;
+/// Shortest name part used as an alias ("Rodrigo" for "Rodrigo Specter")
+const MIN_ALIAS_LEN: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    /// Helix itself
+    Helix,
+    Primary,
+    Secondary,
+    /// Only in trust_map.json
+    Known,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+    pub relationship: Option<String>,
+    pub attachment_style: Option<String>,
+    /// Composite trust from trust_map.json, else the attachment trust level
+    pub trust: Option<f64>,
+    pub mentions: u32,
+    pub last_mentioned: Option<String>,
+    #[serde(skip)]
+    aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Helix to someone in the relational layer
+    Relationship,
+    /// Two people mentioned in the same memory
+    CoMention,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+    /// Relational trust (relationship edges only)
+    pub trust: Option<f64>,
+    /// 0..1 from recent mentions, fading without new ones
+    pub strength: f64,
+    pub mentions: u32,
+    /// Mean emotional valence of the memories behind the mentions
+    pub valence: Option<f64>,
+    pub last_mentioned: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationshipGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Newest memory folded into the mention counts
+    pub last_memory_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Mentions behind one node or edge
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MentionStats {
+    count: u32,
+    /// Decaying mention weight as of `last`
+    weight: f64,
+    last: Option<String>,
+    valence_sum: f64,
+    valence_count: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GraphState {
+    #[serde(default)]
+    schema_version: String,
+    last_memory_at: Option<String>,
+    updated_at: Option<String>,
+    /// By node id
+    #[serde(default)]
+    mentions: BTreeMap<String, MentionStats>,
+    /// By "<id>|<id>", ids sorted
+    #[serde(default)]
+    co_mentions: BTreeMap<String, MentionStats>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn decayed(weight: f64, since: Option<&str>, at: DateTime<Utc>) -> f64 {
+    let Some(since) = since.and_then(parse_time) else {
+        return weight;
+    };
+    let days = (at - since).num_seconds().max(0) as f64 / 86_400.0;
+    weight * 0.5f64.powf(days / MENTION_HALF_LIFE_DAYS)
+}
+
+impl MentionStats {
+    fn record(&mut self, at: DateTime<Utc>, timestamp: &str, valence: Option<f64>) {
+        self.weight = decayed(self.weight, self.last.as_deref(), at) + 1.0;
+        self.count += 1;
+        self.last = Some(timestamp.to_string());
+        if let Some(valence) = valence {
+            self.valence_sum += valence;
+            self.valence_count += 1;
+        }
+    }
+
+    fn strength(&self, now: DateTime<Utc>) -> f64 {
+        let weight = decayed(self.weight, self.last.as_deref(), now);
+        weight / (weight + STRENGTH_SATURATION)
+    }
+
+    fn valence(&self) -> Option<f64> {
+        (self.valence_count > 0).then(|| self.valence_sum / self.valence_count as f64)
+    }
+}
+
+fn pair_key(a: &str, b: &str) -> String {
+    if a <= b {
+        format!("{}|{}", a, b)
+    } else {
+        format!("{}|{}", b, a)
+    }
+}
+
+// Nodes
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// "rodrigo_specter" -> "Rodrigo Specter"
+fn display_name(id: &str) -> String {
+    id.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lowercase names a memory may use for this node
+fn aliases_for(id: &str, name: &str, extra: Option<&Value>) -> Vec<String> {
+    let mut aliases = vec![name.to_lowercase(), id.replace('_', " ").to_lowercase()];
+    if let Some(first) = name.split_whitespace().next() {
+        if first.chars().count() >= MIN_ALIAS_LEN {
+            aliases.push(first.to_lowercase());
+        }
+    }
+    if let Some(extra) = extra.and_then(Value::as_array) {
+        aliases.extend(extra.iter().filter_map(Value::as_str).map(str::to_lowercase));
+    }
+
+    aliases.retain(|alias| !alias.trim().is_empty());
+    aliases.sort();
+    aliases.dedup();
+    aliases
+}
+
+fn node(id: &str, entry: &Value, kind: NodeKind) -> GraphNode {
+    let name = str_field(entry, "name").unwrap_or_else(|| display_name(id));
+    GraphNode {
+        id: id.to_string(),
+        aliases: aliases_for(id, &name, entry.get("aliases")),
+        name,
+        kind,
+        relationship: str_field(entry, "relationship"),
+        attachment_style: str_field(entry, "attachment_style"),
+        trust: entry.get("trust_level").and_then(Value::as_f64),
+        mentions: 0,
+        last_mentioned: None,
+    }
+}
+
+/// People and entities in the relational layer, without Helix
+fn relational_nodes(relational: &Value) -> Vec<GraphNode> {
+    let mut nodes: Vec<GraphNode> = Vec::new();
+    let attachments = relational.get("attachments");
+
+    if let Some(primary) = attachments.and_then(|a| a.get("primary_attachment")) {
+        if let Some(id) = primary.get("id").and_then(Value::as_str) {
+            nodes.push(node(id, primary, NodeKind::Primary));
+        }
+    }
+    if let Some(secondary) = attachments
+        .and_then(|a| a.get("secondary_attachments"))
+        .and_then(Value::as_object)
+    {
+        for (id, entry) in secondary {
+            nodes.push(node(id, entry, NodeKind::Secondary));
+        }
+    }
+
+    let profiles = relational
+        .get("trust_map")
+        .and_then(|t| t.get("trust_profiles"))
+        .and_then(Value::as_object);
+    for (id, profile) in profiles.into_iter().flatten() {
+        let composite = profile.get("composite").and_then(Value::as_f64);
+        match nodes.iter_mut().find(|n| n.id == *id) {
+            Some(existing) => existing.trust = composite.or(existing.trust),
+            None => {
+                let mut known = node(id, profile, NodeKind::Known);
+                known.trust = composite;
+                nodes.push(known);
+            }
+        }
+    }
+    nodes
+}
+
+/// Whether `alias` appears in `text` as whole words (both lowercase)
+fn mentions(text: &str, alias: &str) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    let mut start = 0;
+    while let Some(offset) = text[start..].find(alias) {
+        let at = start + offset;
+        let end = at + alias.len();
+        if !is_word(text[..at].chars().next_back()) && !is_word(text[end..].chars().next()) {
+            return true;
+        }
+        start = at + alias.chars().next().map_or(1, char::len_utf8);
+    }
+    false
+}
+
+/// Fold memories, oldest first, into the mention counts
+fn apply_memories(state: &mut GraphState, nodes: &[GraphNode], memories: &[MemoryRecord]) {
+    for memory in memories {
+        let Some(at) = parse_time(&memory.created_at) else {
+            continue;
+        };
+        let text = memory.content.to_lowercase();
+        let mentioned: Vec<&str> = nodes
+            .iter()
+            .filter(|n| n.aliases.iter().any(|alias| mentions(&text, alias)))
+            .map(|n| n.id.as_str())
+            .collect();
+
+        for id in &mentioned {
+            state
+                .mentions
+                .entry(id.to_string())
+                .or_default()
+                .record(at, &memory.created_at, memory.emotional_valence);
+        }
+        for (i, a) in mentioned.iter().enumerate() {
+            for b in &mentioned[i + 1..] {
+                state
+                    .co_mentions
+                    .entry(pair_key(a, b))
+                    .or_default()
+                    .record(at, &memory.created_at, memory.emotional_valence);
+            }
+        }
+        state.last_memory_at = Some(memory.created_at.clone());
+    }
+}
+
+// State
+
+fn load_state() -> Result<GraphState, CommandError> {
+    let path = crate::accounts::data_dir()?.join(STATE_FILE);
+    if !path.exists() {
+        return Ok(GraphState::default());
+    }
+    let content = vault::read_to_string(&path)
+        .map_err(|e| vault_error("Failed to read relationship graph", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::internal(format!("Corrupt relationship graph state: {}", e)))
+}
+
+fn save_state(state: &GraphState) -> Result<(), CommandError> {
+    let path = crate::accounts::data_dir()?.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| CommandError::from_io("Failed to create psychology directory", e))?;
+    }
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize relationship graph: {}", e))?;
+    vault::write(&path, &json).map_err(|e| vault_error("Failed to write relationship graph", e))
+}
+
+/// Fold memories created since the last run into the mention counts;
+/// returns how many were read. Called during integration.
+pub async fn update_from_memories() -> Result<usize, CommandError> {
+    let relational = get_layer("relational".to_string())?.data;
+    let nodes = relational_nodes(&relational);
+    let mut state = load_state()?;
+
+    let memories = memories::fetch_since(MEMORY_COLUMNS, state.last_memory_at.as_deref(), MEMORY_BATCH)
+        .await
+        .map_err(CommandError::network)?;
+    apply_memories(&mut state, &nodes, &memories);
+
+    state.schema_version = STATE_SCHEMA_VERSION.to_string();
+    state.updated_at = Some(Utc::now().to_rfc3339());
+    save_state(&state)?;
+    Ok(memories.len())
+}
+
+// Tauri commands
+
+/// People and entities from the relational layer with trust and mention
+/// weights
+#[tauri::command]
+pub fn get_relationship_graph() -> Result<RelationshipGraph, CommandError> {
+    let relational = get_layer("relational".to_string())?.data;
+    let state = load_state()?;
+    let now = Utc::now();
+
+    let mut nodes = relational_nodes(&relational);
+    let mut edges = Vec::new();
+
+    for node in &mut nodes {
+        let stats = state.mentions.get(&node.id);
+        node.mentions = stats.map_or(0, |s| s.count);
+        node.last_mentioned = stats.and_then(|s| s.last.clone());
+
+        edges.push(GraphEdge {
+            source: HELIX_NODE.to_string(),
+            target: node.id.clone(),
+            kind: EdgeKind::Relationship,
+            trust: node.trust,
+            strength: stats.map_or(0.0, |s| s.strength(now)),
+            mentions: node.mentions,
+            valence: stats.and_then(MentionStats::valence),
+            last_mentioned: node.last_mentioned.clone(),
+        });
+    }
+
+    for (key, stats) in &state.co_mentions {
+        let Some((a, b)) = key.split_once('|') else {
+            continue;
+        };
+        // Skip pairs whose people were removed from the layer files
+        if !nodes.iter().any(|n| n.id == a) || !nodes.iter().any(|n| n.id == b) {
+            continue;
+        }
+        edges.push(GraphEdge {
+            source: a.to_string(),
+            target: b.to_string(),
+            kind: EdgeKind::CoMention,
+            trust: None,
+            strength: stats.strength(now),
+            mentions: stats.count,
+            valence: stats.valence(),
+            last_mentioned: stats.last.clone(),
+        });
+    }
+
+    nodes.insert(
+        0,
+        GraphNode {
+            id: HELIX_NODE.to_string(),
+            name: "Helix".to_string(),
+            kind: NodeKind::Helix,
+            relationship: None,
+            attachment_style: None,
+            trust: None,
+            mentions: 0,
+            last_mentioned: None,
+            aliases: Vec::new(),
+        },
+    );
+
+    Ok(RelationshipGraph {
+        nodes,
+        edges,
+        last_memory_at: state.last_memory_at,
+        updated_at: state.updated_at,
+    })
+}
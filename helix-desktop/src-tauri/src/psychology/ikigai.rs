@@ -0,0 +1,387 @@
+// Purpose alignment between recent memories and the ikigai
+//
+// Each item of purpose/ikigai.json (what I love, what I'm good at, what
+// the world needs, what creates value) is embedded along with the last
+// ANALYSIS_WEEKS weeks of memories. Memories are grouped into clusters of
+// similar content, and each cluster is scored by its closest ikigai item.
+// The result - per-week alignment scores, clusters, and "purpose alignment"
+// patterns for the synthesis report - is saved to
+// purpose/purpose_alignment.json and shown on the purpose dashboard.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use super::embeddings::{self, Embedder};
+use super::memories;
+use crate::commands::psychology::{read_layer_file, vault_error};
+use crate::commands::CommandError;
+use crate::vault;
+
+const IKIGAI_FILE: &str = "purpose/ikigai.json";
+/// Latest analysis, relative to the data directory
+const SNAPSHOT_FILE: &str = "purpose/purpose_alignment.json";
+const ANALYSIS_WEEKS: i64 = 4;
+const MEMORY_COLUMNS: &str = "content,emotional_valence,created_at";
+const MEMORY_LIMIT: usize = 1000;
+/// Clusters smaller than this don't get their own patterns
+const MIN_PATTERN_CLUSTER: usize = 3;
+/// Cluster score at which a cluster counts as aligned
+const ALIGNED_SCORE: f64 = 0.5;
+/// Week-over-week score change worth a pattern
+const NOTABLE_CHANGE: f64 = 0.1;
+const LABEL_WORDS: usize = 3;
+
+/// (ikigai.json key, text fields of each item, label)
+const DIMENSIONS: &[(&str, &[&str], &str)] = &[
+    ("what_i_love", &["item", "note"], "what I love"),
+    ("what_im_good_at", &["skill", "evidence"], "what I'm good at"),
+    ("what_world_needs", &["need", "my_contribution"], "what the world needs"),
+    ("what_creates_value", &["output", "evidence", "next_step"], "what creates value"),
+];
+
+/// Similarity range mapped onto 0..1 scores, per embedder. Hashed keyword
+/// vectors only overlap on shared words, so their similarities run lower.
+fn similarity_range(embedder: Embedder) -> (f64, f64) {
+    match embedder {
+        Embedder::Gemini => (0.55, 0.8),
+        Embedder::Hashed => (0.05, 0.35),
+    }
+}
+
+/// Similarity at which a memory joins an existing cluster
+fn cluster_threshold(embedder: Embedder) -> f64 {
+    match embedder {
+        Embedder::Gemini => 0.8,
+        Embedder::Hashed => 0.3,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekAlignment {
+    /// Monday of the week, YYYY-MM-DD
+    pub week_start: String,
+    pub memories: usize,
+    pub aligned_memories: usize,
+    /// Mean cluster score of the week's memories (0..1)
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryCluster {
+    /// Most frequent words in the cluster
+    pub label: String,
+    pub size: usize,
+    /// Closest ikigai dimension and item
+    pub dimension: Option<String>,
+    pub item: Option<String>,
+    pub similarity: f64,
+    pub score: f64,
+    pub aligned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DimensionAlignment {
+    pub dimension: String,
+    pub label: String,
+    pub items: usize,
+    /// Memories in aligned clusters closest to this dimension
+    pub aligned_memories: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurposeAlignment {
+    pub computed_at: String,
+    pub embedder: Embedder,
+    pub memories: usize,
+    /// Oldest first
+    pub weeks: Vec<WeekAlignment>,
+    /// Score of the current week, if it has memories
+    pub current_score: Option<f64>,
+    /// Largest first
+    pub clusters: Vec<MemoryCluster>,
+    pub dimensions: Vec<DimensionAlignment>,
+    /// Purpose alignment patterns, added to the synthesis report
+    pub patterns: Vec<String>,
+}
+
+/// One embedded ikigai item
+struct IkigaiItem {
+    dimension: usize,
+    text: String,
+}
+
+struct Memory {
+    content: String,
+    date: NaiveDate,
+}
+
+struct Cluster {
+    members: Vec<usize>,
+    centroid: Vec<f32>,
+}
+
+fn ikigai_items(ikigai: &Value) -> Vec<IkigaiItem> {
+    let mut items = Vec::new();
+    for (index, (key, fields, _)) in DIMENSIONS.iter().enumerate() {
+        let Some(entries) = ikigai.get(*key).and_then(Value::as_array) else {
+            continue;
+        };
+        for entry in entries {
+            let text = match entry {
+                Value::String(text) => text.clone(),
+                _ => fields
+                    .iter()
+                    .filter_map(|field| entry.get(*field).and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join(". "),
+            };
+            if !text.trim().is_empty() {
+                items.push(IkigaiItem { dimension: index, text });
+            }
+        }
+    }
+    items
+}
+
+/// Greedy clustering: each memory joins the closest cluster above the
+/// threshold, else starts a new one
+fn cluster(vectors: &[Vec<f32>], threshold: f64) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (index, vector) in vectors.iter().enumerate() {
+        let closest = clusters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, embeddings::cosine(&c.centroid, vector)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match closest {
+            Some((i, _)) => {
+                let cluster = &mut clusters[i];
+                cluster.members.push(index);
+                // Running sum; cosine ignores the scale
+                for (c, v) in cluster.centroid.iter_mut().zip(vector) {
+                    *c += v;
+                }
+            }
+            None => clusters.push(Cluster { members: vec![index], centroid: vector.clone() }),
+        }
+    }
+    clusters
+}
+
+fn label(memories: &[Memory], members: &[usize]) -> String {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for member in members {
+        for word in memories::content_words(&memories[*member].content) {
+            *counts.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .take(LABEL_WORDS)
+        .map(|(word, _)| word)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - ChronoDuration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn patterns(clusters: &[MemoryCluster], dimensions: &[DimensionAlignment], weeks: &[WeekAlignment]) -> Vec<String> {
+    let mut patterns = Vec::new();
+
+    for cluster in clusters.iter().filter(|c| c.size >= MIN_PATTERN_CLUSTER) {
+        match (cluster.aligned, &cluster.dimension, &cluster.item) {
+            (true, Some(dimension), Some(item)) => patterns.push(format!(
+                "Memories about {} ({}) align with {}: '{}'",
+                cluster.label, cluster.size, dimension, item
+            )),
+            _ => patterns.push(format!(
+                "Recurring theme {} ({} memories) doesn't connect to the ikigai",
+                cluster.label, cluster.size
+            )),
+        }
+    }
+
+    for dimension in dimensions.iter().filter(|d| d.items > 0 && d.aligned_memories == 0) {
+        patterns.push(format!(
+            "No memories in the last {} weeks engage {}",
+            ANALYSIS_WEEKS, dimension.label
+        ));
+    }
+
+    let scored: Vec<&WeekAlignment> = weeks.iter().filter(|w| w.score.is_some()).collect();
+    if let [.., previous, latest] = scored.as_slice() {
+        let (before, now) = (previous.score.unwrap_or_default(), latest.score.unwrap_or_default());
+        if (now - before).abs() >= NOTABLE_CHANGE {
+            patterns.push(format!(
+                "Purpose alignment {} from {:.2} to {:.2} in the week of {}",
+                if now > before { "rose" } else { "fell" },
+                before,
+                now,
+                latest.week_start
+            ));
+        }
+    }
+
+    patterns
+}
+
+/// Score the last ANALYSIS_WEEKS weeks of memories against the ikigai;
+/// the result is saved unless `dry_run`
+pub async fn analyze(dry_run: bool) -> Result<PurposeAlignment, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+    if !helix_dir.join(IKIGAI_FILE).exists() {
+        return Err(CommandError::not_found("No ikigai file in the purpose layer"));
+    }
+    let ikigai = read_layer_file(&helix_dir, IKIGAI_FILE)?;
+    let items = ikigai_items(&ikigai);
+
+    let today = Local::now().date_naive();
+    let first_week = week_start(today) - ChronoDuration::weeks(ANALYSIS_WEEKS - 1);
+    let since = first_week
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .ok_or("Invalid local date")?
+        .with_timezone(&Utc);
+
+    let records = memories::fetch_since(MEMORY_COLUMNS, Some(&since.to_rfc3339()), MEMORY_LIMIT)
+        .await
+        .map_err(CommandError::network)?;
+    let memories: Vec<Memory> = records
+        .into_iter()
+        .filter(|r| !r.content.trim().is_empty())
+        .filter_map(|r| {
+            let at = DateTime::parse_from_rfc3339(&r.created_at).ok()?;
+            Some(Memory { content: r.content, date: at.with_timezone(&Local).date_naive() })
+        })
+        .collect();
+
+    // One batch so items and memories share an embedder
+    let texts: Vec<String> = items
+        .iter()
+        .map(|item| item.text.clone())
+        .chain(memories.iter().map(|m| m.content.clone()))
+        .collect();
+    let (embedder, vectors) = embeddings::embed(&texts).await;
+    let (item_vectors, memory_vectors) = vectors.split_at(items.len());
+    let (floor, ceiling) = similarity_range(embedder);
+
+    let mut memory_scores = vec![(0.0, false); memories.len()];
+    let mut dimension_counts = vec![0usize; DIMENSIONS.len()];
+    let mut clusters: Vec<MemoryCluster> = cluster(memory_vectors, cluster_threshold(embedder))
+        .into_iter()
+        .map(|c| {
+            let closest = item_vectors
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i, embeddings::cosine(&c.centroid, v)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            let similarity = closest.map_or(0.0, |(_, s)| s);
+            let score = ((similarity - floor) / (ceiling - floor)).clamp(0.0, 1.0);
+            let aligned = score >= ALIGNED_SCORE;
+
+            for member in &c.members {
+                memory_scores[*member] = (score, aligned);
+            }
+            let item = closest.map(|(i, _)| &items[i]);
+            if let Some(item) = item.filter(|_| aligned) {
+                dimension_counts[item.dimension] += c.members.len();
+            }
+
+            MemoryCluster {
+                label: label(&memories, &c.members),
+                size: c.members.len(),
+                dimension: item.map(|i| DIMENSIONS[i.dimension].2.to_string()),
+                item: item.map(|i| i.text.clone()),
+                similarity,
+                score,
+                aligned,
+            }
+        })
+        .collect();
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.size));
+
+    let mut weeks: BTreeMap<NaiveDate, WeekAlignment> = (0..ANALYSIS_WEEKS)
+        .map(|i| first_week + ChronoDuration::weeks(i))
+        .map(|start| {
+            let week = WeekAlignment {
+                week_start: start.format("%Y-%m-%d").to_string(),
+                memories: 0,
+                aligned_memories: 0,
+                score: None,
+            };
+            (start, week)
+        })
+        .collect();
+    for (memory, (score, aligned)) in memories.iter().zip(&memory_scores) {
+        if let Some(week) = weeks.get_mut(&week_start(memory.date)) {
+            week.memories += 1;
+            week.aligned_memories += *aligned as usize;
+            week.score = Some(week.score.unwrap_or(0.0) + score);
+        }
+    }
+    let weeks: Vec<WeekAlignment> = weeks
+        .into_values()
+        .map(|mut week| {
+            week.score = week.score.map(|sum| sum / week.memories as f64);
+            week
+        })
+        .collect();
+
+    let dimensions: Vec<DimensionAlignment> = DIMENSIONS
+        .iter()
+        .enumerate()
+        .map(|(index, (key, _, label))| DimensionAlignment {
+            dimension: key.to_string(),
+            label: label.to_string(),
+            items: items.iter().filter(|i| i.dimension == index).count(),
+            aligned_memories: dimension_counts[index],
+        })
+        .collect();
+
+    let alignment = PurposeAlignment {
+        computed_at: Utc::now().to_rfc3339(),
+        embedder,
+        memories: memories.len(),
+        current_score: weeks.last().and_then(|w| w.score),
+        patterns: patterns(&clusters, &dimensions, &weeks),
+        weeks,
+        clusters,
+        dimensions,
+    };
+
+    if !dry_run {
+        let json = serde_json::to_string_pretty(&alignment)
+            .map_err(|e| format!("Failed to serialize purpose alignment: {}", e))?;
+        let path = helix_dir.join(SNAPSHOT_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| CommandError::from_io("Failed to create purpose directory", e))?;
+        }
+        vault::write(&path, &json).map_err(|e| vault_error("Failed to write purpose alignment", e))?;
+    }
+    Ok(alignment)
+}
+
+// Tauri commands
+
+/// The latest purpose alignment analysis; runs one when `refresh` is set
+/// or none has been saved yet
+#[tauri::command]
+pub async fn get_purpose_alignment(refresh: Option<bool>) -> Result<PurposeAlignment, CommandError> {
+    let path = crate::accounts::data_dir()?.join(SNAPSHOT_FILE);
+    if refresh.unwrap_or(false) || !path.exists() {
+        return analyze(false).await;
+    }
+
+    let content = vault::read_to_string(&path).map_err(|e| vault_error("Failed to read purpose alignment", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse purpose alignment: {}", e).into())
+}
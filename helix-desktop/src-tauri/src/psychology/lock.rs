@@ -0,0 +1,72 @@
+// Advisory lock on psychology files
+//
+// Layer files are written by the app (update_layer, update_soul, goal
+// check-ins, approved guardrail writes), by the in-process decay and
+// synthesis passes, and by decay.py / synthesis.py. Each writer holds an
+// exclusive lock on <data_dir>/.psychology.lock while it writes; a pass
+// holds it for its whole run. The scripts take the same lock (flock, or
+// msvcrt.locking on Windows) on the file named by HELIX_LOCK_FILE, through
+// scripts/psychology_lock.py. A writer that can't get the lock in time fails
+// with a `busy` error, which is safe to retry; a script exits with
+// EXIT_LOCKED, which the runner turns into the same error. Readers don't
+// lock: every write replaces the file by rename (vault::write), so a reader
+// sees either the old or the new content.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::commands::{CommandError, ErrorCode};
+
+/// Lock file, relative to the data directory
+pub const LOCK_FILE: &str = ".psychology.lock";
+/// How long a write from the UI waits for the lock
+pub const WRITE_WAIT: Duration = Duration::from_millis(250);
+/// How long a decay or synthesis pass waits for the lock
+pub const PASS_WAIT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+/// Exit status of a script that couldn't get the lock (EX_TEMPFAIL)
+pub const EXIT_LOCKED: i32 = 75;
+
+/// Held lock, released when dropped
+pub struct PsychologyLock {
+    _file: File,
+}
+
+pub fn lock_path(helix_dir: &Path) -> PathBuf {
+    helix_dir.join(LOCK_FILE)
+}
+
+/// Take the lock, waiting up to `wait` for another writer to finish
+pub fn acquire(helix_dir: &Path, wait: Duration) -> Result<PsychologyLock, CommandError> {
+    let path = lock_path(helix_dir);
+    std::fs::create_dir_all(helix_dir).map_err(|e| CommandError::from_io("Failed to create data directory", e))?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| CommandError::from_io("Failed to open psychology lock", e))?;
+
+    let deadline = Instant::now() + wait;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(PsychologyLock { _file: file }),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+            Err(TryLockError::WouldBlock) => return Err(busy()),
+            Err(TryLockError::Error(e)) => return Err(CommandError::from_io("Failed to lock psychology files", e)),
+        }
+    }
+}
+
+/// The error for a writer that gave up waiting for the lock
+pub fn busy() -> CommandError {
+    CommandError::new(
+        ErrorCode::Busy,
+        "Psychology files are being written by another process; try again shortly",
+    )
+    .with_details(serde_json::json!({
+        "lock": LOCK_FILE,
+        "retryable": true,
+    }))
+}
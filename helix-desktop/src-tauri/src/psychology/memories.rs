@@ -0,0 +1,95 @@
+// Memory queries for the psychology modules
+//
+// Reads the active account's rows from the Supabase `memories` table
+// the same way the MCP memory search does: as the signed-in user, so
+// row-level security only lets their own memories through.
+
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::calendar::commitments::STOPWORDS;
+use crate::commands::auth;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct MemoryRecord {
+    /// Empty unless `id` was selected
+    #[serde(default)]
+    pub id: String,
+    /// Empty unless `content` was selected
+    #[serde(default)]
+    pub content: String,
+    pub emotional_valence: Option<f64>,
+    pub created_at: String,
+}
+
+/// Distinct lowercase words of a memory that can name a topic
+pub fn content_words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 4 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Up to `limit` memories created after `since` (RFC 3339), oldest first.
+/// `columns` is a PostgREST select list and must include created_at.
+pub async fn fetch_since(columns: &str, since: Option<&str>, limit: usize) -> Result<Vec<MemoryRecord>, String> {
+    fetch(columns, since, None, limit, "created_at.asc").await
+}
+
+/// The newest `limit` memories created after `since` and no later than
+/// `until`, newest first
+pub async fn fetch_latest(
+    columns: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    limit: usize,
+) -> Result<Vec<MemoryRecord>, String> {
+    fetch(columns, since, until, limit, "created_at.desc").await
+}
+
+async fn fetch(
+    columns: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    limit: usize,
+    order: &str,
+) -> Result<Vec<MemoryRecord>, String> {
+    let user_id = crate::accounts::profiles::active_user_id().ok_or("No active Helix account")?;
+    let supabase_url = auth::get_supabase_url()?;
+
+    let mut filters = vec![
+        ("select", columns.to_string()),
+        ("user_id", format!("eq.{}", user_id)),
+        ("order", order.to_string()),
+        ("limit", limit.to_string()),
+    ];
+    if let Some(since) = since {
+        filters.push(("created_at", format!("gt.{}", since)));
+    }
+    if let Some(until) = until {
+        filters.push(("created_at", format!("lte.{}", until)));
+    }
+
+    let request = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .get(format!("{}/rest/v1/memories", supabase_url));
+    let (request, _) = crate::accounts::session::authorize(request).await?;
+    let response = request
+        .query(&filters)
+        .send()
+        .await
+        .map_err(|e| format!("Memory fetch failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Memory fetch failed: HTTP {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid memory response: {}", e))
+}
@@ -0,0 +1,304 @@
+// Layer file schema migrations
+//
+// Every layer file carries a "major.minor" `schema_version`. MIGRATIONS
+// lists upgrade steps in order; a file is upgraded when it is read, one
+// step at a time until no step starts at its version. Before a migrated
+// file is rewritten the original is copied to
+// <data_dir>/migrations/backups/, and each applied step is appended to
+// <data_dir>/migrations/applied.jsonl. Files at a version newer than any
+// known step (written by a newer app) are left alone.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::commands::psychology::LAYER_FILES;
+use crate::commands::CommandError;
+use crate::vault;
+
+/// Backups and the applied-migration log, under the data directory
+pub const MIGRATIONS_DIR: &str = "migrations";
+const BACKUPS_DIR: &str = "backups";
+const APPLIED_FILE: &str = "applied.jsonl";
+const VERSION_KEY: &str = "schema_version";
+/// Version of files written before schema_version existed
+const UNVERSIONED: &str = "0.0";
+
+/// Serializes upgrades so concurrent reads don't migrate a file twice
+static MIGRATION_LOCK: Mutex<()> = Mutex::new(());
+
+/// One upgrade step for a layer file
+struct Migration {
+    /// Layer file it applies to (as in LAYER_FILES); `None` for all of them
+    file: Option<&'static str>,
+    from: &'static str,
+    to: &'static str,
+    description: &'static str,
+    /// Rewrites the document; schema_version is set afterwards
+    apply: fn(&mut Map<String, Value>) -> Result<(), String>,
+}
+
+/// Upgrade steps, oldest first. Never edit a shipped step; add a new one.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        file: None,
+        from: UNVERSIONED,
+        to: "1.0",
+        description: "Add schema_version to unversioned files",
+        apply: |_| Ok(()),
+    },
+    Migration {
+        file: Some("identity/goals.json"),
+        from: "1.0",
+        to: "1.1",
+        description: "Add check-in history to core goals",
+        apply: add_goal_check_ins,
+    },
+];
+
+/// goals.json 1.1: every core goal gets `check_ins` and `last_check_in`
+fn add_goal_check_ins(doc: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(goals) = doc.get_mut("core_goals") else {
+        return Ok(());
+    };
+    let goals = goals.as_array_mut().ok_or("core_goals is not an array")?;
+
+    for goal in goals.iter_mut().filter_map(Value::as_object_mut) {
+        goal.entry("check_ins").or_insert_with(|| Value::Array(Vec::new()));
+        goal.entry("last_check_in").or_insert(Value::Null);
+    }
+    Ok(())
+}
+
+/// A migration step that was applied to a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub file: String,
+    pub from: String,
+    pub to: String,
+    pub description: String,
+    pub timestamp: u64,
+    /// Copy of the file before this run, relative to the data directory
+    pub backup: Option<String>,
+}
+
+/// Schema state of one layer file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSchema {
+    pub file: String,
+    pub exists: bool,
+    /// `None` if missing or unreadable
+    pub version: Option<String>,
+    pub latest: String,
+    /// Steps that will run on the next read
+    pub pending: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub files: Vec<FileSchema>,
+    /// Applied steps, newest first
+    pub applied: Vec<AppliedMigration>,
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    Some((major.trim().parse().ok()?, minor.trim().parse().ok()?))
+}
+
+fn version_of(doc: &Map<String, Value>) -> String {
+    match doc.get(VERSION_KEY) {
+        Some(Value::String(version)) => version.clone(),
+        // Tolerate hand-edited numeric versions (1 or 1.0)
+        Some(Value::Number(version)) => version.to_string(),
+        _ => UNVERSIONED.to_string(),
+    }
+}
+
+fn steps_for(file: &str) -> impl Iterator<Item = &'static Migration> + '_ {
+    MIGRATIONS
+        .iter()
+        .filter(move |m| m.file.is_none_or(|f| f == file))
+}
+
+/// Newest schema version known for `file`
+fn latest_version(file: &str) -> String {
+    steps_for(file)
+        .filter_map(|m| parse_version(m.to).map(|v| (v, m.to)))
+        .max_by_key(|(v, _)| *v)
+        .map(|(_, to)| to.to_string())
+        .unwrap_or_else(|| UNVERSIONED.to_string())
+}
+
+/// Steps that would run from `version`, in order
+fn plan(file: &str, version: &str) -> Vec<&'static Migration> {
+    let mut steps = Vec::new();
+    let mut current = parse_version(version);
+
+    // Bounded so a mistaken cycle in MIGRATIONS can't loop forever
+    while steps.len() < MIGRATIONS.len() {
+        let Some(step) = steps_for(file).find(|m| parse_version(m.from) == current) else {
+            break;
+        };
+        steps.push(step);
+        current = parse_version(step.to);
+    }
+    steps
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// "psychology/trust_map.json" at 1.0 -> "psychology_trust_map.1.0.<ms>.json"
+fn backup_name(file: &str, version: &str, timestamp: u64) -> String {
+    let stem = file.trim_end_matches(".json").replace(['/', '\\'], "_");
+    format!("{}.{}.{}.json", stem, version, timestamp)
+}
+
+fn append_applied(dir: &Path, applied: &[AppliedMigration]) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(APPLIED_FILE))
+        .map_err(|e| format!("Failed to open migration log: {}", e))?;
+
+    for step in applied {
+        let line = serde_json::to_string(step)
+            .map_err(|e| format!("Failed to serialize migration record: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write migration log: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Bring a layer file read from disk up to its latest schema. When steps
+/// run, the original is backed up and the file rewritten before the
+/// migrated document is returned.
+pub fn upgrade(helix_dir: &Path, file: &str, data: Value) -> Result<Value, String> {
+    let needs_upgrade = data
+        .as_object()
+        .is_some_and(|doc| !plan(file, &version_of(doc)).is_empty());
+    if !needs_upgrade {
+        return Ok(data);
+    }
+
+    let _lock = MIGRATION_LOCK.lock().map_err(|e| e.to_string())?;
+
+    // Re-read under the lock: another reader may have migrated it already
+    let path = helix_dir.join(file);
+    let original = vault::read_to_string(&path)?;
+    let mut data: Value = serde_json::from_str(&original)
+        .map_err(|e| format!("Failed to parse {}: {}", file, e))?;
+    let Some(doc) = data.as_object_mut() else {
+        return Ok(data);
+    };
+
+    let from_version = version_of(doc);
+    let steps = plan(file, &from_version);
+    if steps.is_empty() {
+        return Ok(data);
+    }
+
+    let timestamp = now_ms();
+    let migrations_dir = helix_dir.join(MIGRATIONS_DIR);
+    let backup_rel = PathBuf::from(MIGRATIONS_DIR)
+        .join(BACKUPS_DIR)
+        .join(backup_name(file, &from_version, timestamp));
+    let backup_path = helix_dir.join(&backup_rel);
+    if let Some(parent) = backup_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+    // Backups hold psychology data, so they go through the vault too
+    vault::write(&backup_path, &original)?;
+
+    let mut applied = Vec::new();
+    for step in steps {
+        (step.apply)(doc).map_err(|e| format!("Migration {} -> {} of {} failed: {}", step.from, step.to, file, e))?;
+        doc.insert(VERSION_KEY.to_string(), Value::String(step.to.to_string()));
+        applied.push(AppliedMigration {
+            file: file.to_string(),
+            from: step.from.to_string(),
+            to: step.to.to_string(),
+            description: step.description.to_string(),
+            timestamp,
+            backup: Some(backup_rel.to_string_lossy().replace('\\', "/")),
+        });
+    }
+
+    let content = serde_json::to_string_pretty(&data)
+        .map_err(|e| format!("Failed to serialize {}: {}", file, e))?;
+    vault::write(&path, &content)?;
+    append_applied(&migrations_dir, &applied)?;
+
+    log::info!(
+        "Migrated {} from schema {} to {}",
+        file,
+        from_version,
+        applied.last().map(|a| a.to.as_str()).unwrap_or_default()
+    );
+    Ok(data)
+}
+
+fn file_schema(helix_dir: &Path, file: &str) -> FileSchema {
+    let mut schema = FileSchema {
+        file: file.to_string(),
+        exists: false,
+        version: None,
+        latest: latest_version(file),
+        pending: Vec::new(),
+        error: None,
+    };
+
+    let path = helix_dir.join(file);
+    if !path.exists() {
+        return schema;
+    }
+    schema.exists = true;
+
+    let doc = vault::read_to_string(&path).and_then(|content| {
+        serde_json::from_str::<Map<String, Value>>(&content).map_err(|e| e.to_string())
+    });
+    match doc {
+        Ok(doc) => {
+            let version = version_of(&doc);
+            schema.pending = plan(file, &version)
+                .iter()
+                .map(|m| format!("{} -> {}: {}", m.from, m.to, m.description))
+                .collect();
+            schema.version = Some(version);
+        }
+        Err(e) => schema.error = Some(e),
+    }
+    schema
+}
+
+// Tauri commands
+
+/// Schema versions of every layer file and the migrations applied so far
+#[tauri::command]
+pub fn get_migration_report() -> Result<MigrationReport, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+
+    let files = LAYER_FILES
+        .iter()
+        .flat_map(|(_, files)| files.iter())
+        .map(|file| file_schema(&helix_dir, file))
+        .collect();
+
+    let log_path = helix_dir.join(MIGRATIONS_DIR).join(APPLIED_FILE);
+    let applied = match fs::read_to_string(&log_path) {
+        Ok(content) => content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(CommandError::from_io("Failed to read migration log", e)),
+    };
+
+    Ok(MigrationReport { files, applied })
+}
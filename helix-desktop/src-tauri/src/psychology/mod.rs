@@ -0,0 +1,20 @@
+// Helix Desktop - Psychology Module (layer file schemas, goals, wellness trends, purpose alignment,
+// the relationship graph, transformation milestones, LLM context bundles, the decay and
+// synthesis passes of the integration layer and what they share, soul version history, the
+// lock writers share, and the JSON Schemas layer writes are checked against)
+
+pub mod context;
+pub mod decay;
+pub mod embeddings;
+pub mod goals;
+pub mod lock;
+pub mod graph;
+pub mod ikigai;
+pub mod memories;
+pub mod migrations;
+pub mod pass;
+pub mod schema;
+pub mod soul_history;
+pub mod synthesis;
+pub mod transformation;
+pub mod wellness;
@@ -0,0 +1,83 @@
+// What the decay and synthesis passes share
+//
+// A pass reports its progress as lines in a PassLog. Each line goes to the
+// log's sink as soon as it is written, so a scheduler job can stream it as
+// `scheduler:job-output`, and the whole log is kept for the pass's stdout.
+// Layer files are read and written through the vault, relative to the data
+// directory; a file that is missing or unreadable is noted in the log and
+// skipped, like the scripts do.
+
+use chrono::Utc;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::vault;
+
+/// A pass's progress log
+pub struct PassLog<'a> {
+    lines: Vec<String>,
+    sink: Box<dyn FnMut(&str) + 'a>,
+}
+
+impl<'a> PassLog<'a> {
+    /// A log that hands every line to `sink` as it is written
+    pub fn new(sink: impl FnMut(&str) + 'a) -> Self {
+        Self {
+            lines: Vec::new(),
+            sink: Box::new(sink),
+        }
+    }
+
+    /// A log nobody follows while the pass runs
+    pub fn quiet() -> Self {
+        Self::new(|_| {})
+    }
+
+    pub fn push(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        (self.sink)(&line);
+        self.lines.push(line);
+    }
+
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+}
+
+/// Time in the scripts' format, for the values passes write
+pub fn timestamp() -> String {
+    Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()
+}
+
+pub fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+/// A layer file's JSON, or None when it is missing or unreadable
+pub fn load(helix_dir: &Path, file_rel: &str, log: &mut PassLog) -> Option<Value> {
+    let path = helix_dir.join(file_rel);
+    if !path.exists() {
+        log.push(format!("  {} not found", file_rel));
+        return None;
+    }
+    match vault::read_to_string(&path).map(|content| serde_json::from_str(&content)) {
+        Ok(Ok(data)) => Some(data),
+        Ok(Err(e)) => {
+            log.push(format!("  [ERROR] Failed to parse {}: {}", file_rel, e));
+            None
+        }
+        Err(e) => {
+            log.push(format!("  [ERROR] Failed to load {}: {}", file_rel, e));
+            None
+        }
+    }
+}
+
+pub fn save(helix_dir: &Path, file_rel: &str, data: &Value) -> Result<(), String> {
+    let path = helix_dir.join(file_rel);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
+    vault::write(&path, &content).map_err(|e| format!("Failed to save {}: {}", file_rel, e))
+}
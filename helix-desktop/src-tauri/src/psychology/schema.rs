@@ -0,0 +1,110 @@
+// Layer file schemas
+//
+// Every JSON layer file has a JSON Schema in schemas/, compiled into the
+// binary. update_layer checks each file it is about to write and refuses
+// the whole write if any of them fails; validate_layer runs the same check
+// without writing, so the UI can flag problems before saving. The schemas
+// pin down the fields the app and scripts read (goal ids and progress,
+// trust levels, ikigai scores, ...) and leave the rest open: keys they
+// don't name are allowed.
+
+use jsonschema::Validator;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Schema per layer file, keyed by the paths in LAYER_FILES
+const SCHEMAS: &[(&str, &str)] = &[
+    ("psychology/psyeval.json", include_str!("schemas/psyeval.json")),
+    ("psychology/emotional_tags.json", include_str!("schemas/emotional_tags.json")),
+    ("psychology/attachments.json", include_str!("schemas/attachments.json")),
+    ("psychology/trust_map.json", include_str!("schemas/trust_map.json")),
+    ("identity/goals.json", include_str!("schemas/goals.json")),
+    ("identity/feared_self.json", include_str!("schemas/feared_self.json")),
+    ("identity/possible_selves.json", include_str!("schemas/possible_selves.json")),
+    ("transformation/current_state.json", include_str!("schemas/current_state.json")),
+    ("transformation/history.json", include_str!("schemas/history.json")),
+    ("purpose/ikigai.json", include_str!("schemas/ikigai.json")),
+    ("purpose/wellness.json", include_str!("schemas/wellness.json")),
+    ("purpose/meaning_sources.json", include_str!("schemas/meaning_sources.json")),
+];
+
+static VALIDATORS: OnceLock<HashMap<&'static str, Validator>> = OnceLock::new();
+
+/// One place where layer data breaks its file's schema
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+    /// Layer file, relative to the data directory
+    pub file: String,
+    /// JSON Pointer to the offending value; empty for the whole document
+    pub path: String,
+    pub message: String,
+}
+
+fn compile(schema: &str) -> Result<Validator, String> {
+    let schema: Value = serde_json::from_str(schema).map_err(|e| e.to_string())?;
+    jsonschema::validator_for(&schema).map_err(|e| e.to_string())
+}
+
+fn validators() -> &'static HashMap<&'static str, Validator> {
+    VALIDATORS.get_or_init(|| {
+        SCHEMAS
+            .iter()
+            .filter_map(|(file, schema)| match compile(schema) {
+                Ok(validator) => Some((*file, validator)),
+                Err(e) => {
+                    log::error!("Schema for {} doesn't compile, its writes go unchecked: {}", file, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Where `data` breaks the schema for `file_rel`; nothing for a file
+/// without one
+pub fn validate(file_rel: &str, data: &Value) -> Vec<SchemaViolation> {
+    let Some(validator) = validators().get(file_rel) else {
+        return Vec::new();
+    };
+    validator
+        .iter_errors(data)
+        .map(|error| SchemaViolation {
+            file: file_rel.to_string(),
+            path: error.instance_path().to_string(),
+            // Masked, so a message doesn't repeat the whole offending value
+            message: error.masked().to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn every_schema_compiles() {
+        for (file, schema) in SCHEMAS {
+            if let Err(e) = compile(schema) {
+                panic!("Schema for {} doesn't compile: {}", file, e);
+            }
+        }
+    }
+
+    #[test]
+    fn seed_layer_files_validate() {
+        // The seed layer files checked in at the repository root
+        let repo = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        for (file, _) in SCHEMAS {
+            let content = std::fs::read_to_string(repo.join(file))
+                .unwrap_or_else(|e| panic!("Failed to read seed file {}: {}", file, e));
+            let data: Value = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Seed file {} isn't JSON: {}", file, e));
+            let violations = validate(file, &data);
+            assert!(violations.is_empty(), "{} breaks its schema: {:?}", file, violations);
+        }
+    }
+}
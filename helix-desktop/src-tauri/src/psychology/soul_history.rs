@@ -0,0 +1,223 @@
+// Soul version history
+//
+// Each write of HELIX_SOUL.md by update_soul, restore_soul or an approved
+// guardrail write leaves a copy at <data_dir>/soul/history/<version>.md, the
+// version being the write time in milliseconds. If no version holds the file
+// as it was before the write, that content is kept too, so the first edit in
+// the app and edits made outside it can also be rolled back. Copies go
+// through the vault like the soul itself. Only the newest KEEP_VERSIONS
+// are kept.
+
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::commands::psychology::{vault_error, write_files};
+use crate::commands::CommandError;
+use crate::policy::guardrails::{WriteOutcome, SOUL_FILE};
+use crate::vault;
+
+/// Version copies, under the data directory
+const HISTORY_DIR: &str = "soul/history";
+const VERSION_EXTENSION: &str = "md";
+const KEEP_VERSIONS: usize = 100;
+/// Names the live soul file in diff_soul
+const CURRENT_VERSION: &str = "current";
+/// Unchanged lines shown around each hunk
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Serializes recording so two writes don't both claim a version
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// A stored copy of the soul
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoulVersion {
+    pub version: String,
+    /// Milliseconds since the epoch
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoulDiff {
+    pub from: String,
+    pub to: String,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    /// Unified diff of `from` -> `to`, empty when they're the same
+    pub unified: String,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn history_dir(helix_dir: &Path) -> PathBuf {
+    helix_dir.join(HISTORY_DIR)
+}
+
+fn version_path(helix_dir: &Path, created_at: u64) -> PathBuf {
+    history_dir(helix_dir).join(format!("{}.{}", created_at, VERSION_EXTENSION))
+}
+
+/// Stored versions, oldest first
+fn versions(helix_dir: &Path) -> Result<Vec<u64>, String> {
+    let entries = match fs::read_dir(history_dir(helix_dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read soul history: {}", e)),
+    };
+
+    let mut versions: Vec<u64> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != VERSION_EXTENSION {
+                return None;
+            }
+            path.file_stem()?.to_str()?.parse().ok()
+        })
+        .collect();
+    versions.sort_unstable();
+    Ok(versions)
+}
+
+/// Keep `content` as a new version unless the newest one already holds it
+fn save(helix_dir: &Path, content: &str) -> Result<(), String> {
+    let existing = versions(helix_dir)?;
+    if let Some(newest) = existing.last() {
+        if vault::read_to_string(&version_path(helix_dir, *newest))? == content {
+            return Ok(());
+        }
+    }
+
+    // Versions are named by time; two writes within a millisecond still
+    // need distinct, increasing names
+    let created_at = now_ms().max(existing.last().map_or(0, |newest| newest + 1));
+    let path = version_path(helix_dir, created_at);
+    fs::create_dir_all(history_dir(helix_dir)).map_err(|e| format!("Failed to create soul history directory: {}", e))?;
+    vault::write(&path, content)?;
+
+    let excess = (existing.len() + 1).saturating_sub(KEEP_VERSIONS);
+    for old in existing.iter().take(excess) {
+        if let Err(e) = fs::remove_file(version_path(helix_dir, *old)) {
+            log::warn!("Failed to prune soul version {}: {}", old, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Record a write of the soul: the content it replaced, if no version holds
+/// that yet, then `written`
+pub fn record(helix_dir: &Path, previous: Option<&str>, written: &str) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(previous) = previous {
+        save(helix_dir, previous)?;
+    }
+    save(helix_dir, written)?;
+    Ok(())
+}
+
+/// The live soul file, `None` if there isn't one yet
+pub fn read_current(helix_dir: &Path) -> Result<Option<String>, String> {
+    let path = helix_dir.join(SOUL_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    vault::read_to_string(&path).map(Some)
+}
+
+/// Content of a stored version, or of the live file for CURRENT_VERSION
+fn read_version(helix_dir: &Path, version: &str) -> Result<String, CommandError> {
+    if version == CURRENT_VERSION {
+        return read_current(helix_dir)
+            .map_err(|e| vault_error("Failed to read soul file", e))?
+            .ok_or_else(|| CommandError::not_found("No soul file"));
+    }
+
+    // Numeric only, so a version can't name a path outside the history
+    let created_at: u64 = version.parse().map_err(|_| {
+        CommandError::invalid_input(format!("Invalid soul version: {}", version))
+            .with_details(serde_json::json!({ "version": version }))
+    })?;
+    let path = version_path(helix_dir, created_at);
+    if !path.exists() {
+        return Err(CommandError::not_found(format!("No soul version {}", version))
+            .with_details(serde_json::json!({ "version": version })));
+    }
+    vault::read_to_string(&path).map_err(|e| vault_error(&format!("Failed to read soul version {}", version), e))
+}
+
+/// Line diff of `old` -> `new`
+fn diff(from: &str, to: &str, old: &str, new: &str) -> SoulDiff {
+    let text_diff = TextDiff::from_lines(old, new);
+    let mut added_lines = 0;
+    let mut removed_lines = 0;
+    for change in text_diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added_lines += 1,
+            ChangeTag::Delete => removed_lines += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let unified = text_diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .header(from, to)
+        .to_string();
+    SoulDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        added_lines,
+        removed_lines,
+        unified,
+    }
+}
+
+/// Stored soul versions, newest first
+#[tauri::command]
+pub fn get_soul_history() -> Result<Vec<SoulVersion>, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+    let versions = versions(&helix_dir).map_err(CommandError::internal)?;
+    Ok(versions
+        .into_iter()
+        .rev()
+        .map(|created_at| SoulVersion {
+            version: created_at.to_string(),
+            created_at,
+        })
+        .collect())
+}
+
+/// Diff two versions; either may be "current" for the live file
+#[tauri::command]
+pub fn diff_soul(version_a: String, version_b: String) -> Result<SoulDiff, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+    let old = read_version(&helix_dir, &version_a)?;
+    let new = read_version(&helix_dir, &version_b)?;
+    Ok(diff(&version_a, &version_b, &old, &new))
+}
+
+/// Write a stored version back as the soul. The restore is itself recorded,
+/// so it can be undone the same way.
+#[tauri::command]
+pub fn restore_soul(version: String) -> Result<WriteOutcome, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+    let content = read_version(&helix_dir, &version)?;
+    let previous = read_current(&helix_dir).map_err(|e| vault_error("Failed to read soul file", e))?;
+
+    let outcome = write_files(&helix_dir, &[(SOUL_FILE, content.clone())])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| CommandError::internal("Soul write produced no outcome"))?;
+    if let Err(e) = record(&helix_dir, previous.as_deref(), &content) {
+        log::warn!("Failed to record restored soul version: {}", e);
+    }
+    log::info!("Restored soul version {}", version);
+    Ok(outcome)
+}
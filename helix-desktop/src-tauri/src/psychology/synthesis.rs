@@ -0,0 +1,311 @@
+// Cross-layer synthesis for the integration layer, the in-process port of
+// synthesis.py
+//
+// A run reads the emotional, relational and prospective layers and:
+//   1. analyzes emotional tags: the dominant emotion, volatility (standard
+//      deviation of intensities) and clusters of intense emotions
+//   2. scores relational health from trust scores and the attachment style
+//   3. appends both to `_synthesis` in transformation/current_state.json
+//      (the last SYNTHESIS_ENTRIES are kept)
+//   4. archives them to transformation/history.json (the last
+//      HISTORY_ENTRIES are kept)
+//   5. computes wellness metrics into `_computed_metrics` of
+//      purpose/wellness.json
+// A dry run writes nothing. The mode (HELIX_SYNTHESIS_MODE for the script)
+// is recorded in the report. Files go through the vault, so unlike the
+// script this works with encrypted psychology data.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use super::pass::{load, round3, save, timestamp, PassLog};
+
+const EMOTIONAL_TAGS_FILE: &str = "psychology/emotional_tags.json";
+const ATTACHMENTS_FILE: &str = "psychology/attachments.json";
+const TRUST_MAP_FILE: &str = "psychology/trust_map.json";
+const GOALS_FILE: &str = "identity/goals.json";
+const CURRENT_STATE_FILE: &str = "transformation/current_state.json";
+const HISTORY_FILE: &str = "transformation/history.json";
+const WELLNESS_FILE: &str = "purpose/wellness.json";
+/// Synthesis entries kept in the transformation state
+const SYNTHESIS_ENTRIES: usize = 10;
+const HISTORY_ENTRIES: usize = 100;
+/// Intensity above which emotions count toward a cluster
+const CLUSTER_INTENSITY: f64 = 0.6;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DominantEmotion {
+    pub name: String,
+    pub intensity: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmotionalAnalysis {
+    pub patterns: Vec<Value>,
+    pub dominant: Option<DominantEmotion>,
+    pub volatility: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationalHealth {
+    pub average_trust: f64,
+    pub strong_relationships: usize,
+    pub weak_relationships: usize,
+    /// high, medium, low or unknown
+    pub attachment_security: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WellnessMetrics {
+    pub emotional_balance: f64,
+    pub relational_health: f64,
+    pub purpose_alignment: f64,
+    pub overall_wellness: f64,
+    pub computed_at: String,
+}
+
+/// Outcome of a run, as synthesis.py writes to HELIX_RESULT_FILE
+#[derive(Debug, Clone, Serialize)]
+pub struct SynthesisReport {
+    pub mode: String,
+    pub dry_run: bool,
+    pub emotional: EmotionalAnalysis,
+    pub relational: RelationalHealth,
+    pub wellness: WellnessMetrics,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn array<'a>(data: Option<&'a Value>, key: &str) -> &'a [Value] {
+    data.and_then(|data| data.get(key))
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn analyze_emotional_patterns(emotional: Option<&Value>) -> EmotionalAnalysis {
+    let tags = array(emotional, "tags");
+    if tags.is_empty() {
+        return EmotionalAnalysis {
+            patterns: Vec::new(),
+            dominant: None,
+            volatility: 0.0,
+            analysis_time: None,
+        };
+    }
+
+    let intensities: Vec<(String, f64)> = tags
+        .iter()
+        .map(|tag| {
+            let name = tag.get("name").and_then(Value::as_str).unwrap_or("unknown").to_string();
+            (name, tag.get("intensity").and_then(Value::as_f64).unwrap_or(0.0))
+        })
+        .collect();
+    // The first of the most intense, as a stable sort would give
+    let dominant = intensities
+        .iter()
+        .fold(None::<&(String, f64)>, |best, tag| match best {
+            Some(best) if best.1 >= tag.1 => Some(best),
+            _ => Some(tag),
+        })
+        .map(|(name, intensity)| DominantEmotion {
+            name: name.clone(),
+            intensity: *intensity,
+        });
+
+    let values: Vec<f64> = intensities.iter().map(|(_, i)| *i).filter(|i| *i > 0.0).collect();
+    let volatility = if values.len() > 1 {
+        let mean = mean(&values);
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    let intense: Vec<&str> = intensities
+        .iter()
+        .filter(|(_, intensity)| *intensity > CLUSTER_INTENSITY)
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let mut patterns = Vec::new();
+    if intense.len() > 1 {
+        patterns.push(json!({
+            "type": "co-occurrence",
+            "emotions": intense,
+            "significance": "High intensity emotional cluster",
+        }));
+    }
+
+    EmotionalAnalysis {
+        patterns,
+        dominant,
+        volatility: round3(volatility),
+        analysis_time: Some(timestamp()),
+    }
+}
+
+fn compute_relational_health(trust: Option<&Value>, attachments: Option<&Value>) -> RelationalHealth {
+    let mut health = RelationalHealth {
+        average_trust: 0.5,
+        strong_relationships: 0,
+        weak_relationships: 0,
+        attachment_security: "unknown".to_string(),
+    };
+
+    let scores: Vec<f64> = array(trust, "relationships")
+        .iter()
+        .map(|rel| rel.get("trust_score").and_then(Value::as_f64).unwrap_or(0.5))
+        .collect();
+    if !scores.is_empty() {
+        health.average_trust = round3(mean(&scores));
+        health.strong_relationships = scores.iter().filter(|s| **s > 0.7).count();
+        health.weak_relationships = scores.iter().filter(|s| **s < 0.3).count();
+    }
+
+    if let Some(style) = attachments.and_then(|a| a.get("primary_style")).and_then(Value::as_str) {
+        health.attachment_security = match style {
+            "secure" => "high",
+            "anxious-preoccupied" | "dismissive-avoidant" => "medium",
+            "fearful-avoidant" => "low",
+            _ => "unknown",
+        }
+        .to_string();
+    }
+    health
+}
+
+fn compute_wellness(emotional: &EmotionalAnalysis, relational: &RelationalHealth, goals: Option<&Value>) -> WellnessMetrics {
+    let emotional_balance = round3(1.0 - emotional.volatility.min(1.0));
+    let relational_health = relational.average_trust;
+
+    let progress: Vec<f64> = array(goals, "goals")
+        .iter()
+        .map(|goal| goal.get("progress").and_then(Value::as_f64).unwrap_or(0.0))
+        .collect();
+    let purpose_alignment = if progress.is_empty() { 0.5 } else { round3(mean(&progress)) };
+
+    WellnessMetrics {
+        emotional_balance,
+        relational_health,
+        purpose_alignment,
+        overall_wellness: round3(emotional_balance * 0.3 + relational_health * 0.3 + purpose_alignment * 0.4),
+        computed_at: timestamp(),
+    }
+}
+
+/// Append `entry` to the array at `key` of `data`, keeping the last `keep`
+fn append_capped(data: &mut Map<String, Value>, key: &str, entry: Value, keep: usize) {
+    let entries = data.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+    if !entries.is_array() {
+        *entries = Value::Array(Vec::new());
+    }
+    if let Some(entries) = entries.as_array_mut() {
+        entries.push(entry);
+        let excess = entries.len().saturating_sub(keep);
+        entries.drain(..excess);
+    }
+}
+
+/// Run a synthesis pass over the data directory, writing nothing in a dry
+/// run. Progress goes to `log` line by line, as it is made.
+pub fn run(helix_dir: &Path, mode: &str, dry_run: bool, log: &mut PassLog) -> Result<SynthesisReport, String> {
+    log.push(format!("[HELIX] Layer 5 Synthesis Process - {}", timestamp()));
+    log.push(format!("  Mode: {} | Dry Run: {}", mode, dry_run));
+
+    let emotional_data = load(helix_dir, EMOTIONAL_TAGS_FILE, log);
+    let trust_data = load(helix_dir, TRUST_MAP_FILE, log);
+    let attachment_data = load(helix_dir, ATTACHMENTS_FILE, log);
+    let goals_data = load(helix_dir, GOALS_FILE, log);
+    let current_state = load(helix_dir, CURRENT_STATE_FILE, log);
+    let history = load(helix_dir, HISTORY_FILE, log);
+
+    log.push("[Phase 1] Analyzing emotional patterns...".to_string());
+    let emotional = analyze_emotional_patterns(emotional_data.as_ref());
+    if let Some(dominant) = &emotional.dominant {
+        log.push(format!("  Dominant emotion: {} (intensity: {:.2})", dominant.name, dominant.intensity));
+    }
+    log.push(format!("  Emotional volatility: {:.3}", emotional.volatility));
+
+    log.push("[Phase 2] Computing relational health...".to_string());
+    let relational = compute_relational_health(trust_data.as_ref(), attachment_data.as_ref());
+    log.push(format!("  Average trust: {:.3}", relational.average_trust));
+    log.push(format!("  Strong relationships: {}", relational.strong_relationships));
+    log.push(format!("  Attachment security: {}", relational.attachment_security));
+
+    let emotional_value = serde_json::to_value(&emotional).map_err(|e| e.to_string())?;
+    let relational_value = serde_json::to_value(&relational).map_err(|e| e.to_string())?;
+
+    log.push("[Phase 3] Updating transformation state...".to_string());
+    let now = timestamp();
+    let mut state = match current_state {
+        Some(Value::Object(state)) => state,
+        _ => Map::new(),
+    };
+    let entry = json!({
+        "timestamp": now,
+        "emotional_patterns": emotional_value,
+        "relational_health": relational_value,
+    });
+    append_capped(&mut state, "_synthesis", entry, SYNTHESIS_ENTRIES);
+    state.insert("_last_synthesis".to_string(), now.into());
+    if dry_run {
+        log.push("  [DRY RUN] Would update transformation state".to_string());
+    } else {
+        save(helix_dir, CURRENT_STATE_FILE, &Value::Object(state))?;
+        log.push("  Transformation state updated".to_string());
+    }
+
+    log.push("[Phase 4] Archiving to history...".to_string());
+    let mut history = match history {
+        Some(Value::Object(history)) => history,
+        _ => {
+            let mut history = Map::new();
+            history.insert("schema_version".to_string(), "1.0".into());
+            history
+        }
+    };
+    let entry = json!({
+        "timestamp": timestamp(),
+        "type": "synthesis",
+        "snapshot": { "emotional": emotional_value, "relational": relational_value },
+    });
+    append_capped(&mut history, "entries", entry, HISTORY_ENTRIES);
+    if dry_run {
+        log.push("  [DRY RUN] Would archive to history".to_string());
+    } else {
+        let total = history.get("entries").and_then(Value::as_array).map_or(0, Vec::len);
+        save(helix_dir, HISTORY_FILE, &Value::Object(history))?;
+        log.push(format!("  History updated (total entries: {})", total));
+    }
+
+    log.push("[Phase 5] Computing wellness metrics...".to_string());
+    let wellness = compute_wellness(&emotional, &relational, goals_data.as_ref());
+    log.push(format!("  Emotional balance: {:.3}", wellness.emotional_balance));
+    log.push(format!("  Relational health: {:.3}", wellness.relational_health));
+    log.push(format!("  Purpose alignment: {:.3}", wellness.purpose_alignment));
+    log.push(format!("  Overall wellness: {:.3}", wellness.overall_wellness));
+    if dry_run {
+        log.push("  [DRY RUN] Would save wellness metrics".to_string());
+    } else {
+        let mut wellness_data = match load(helix_dir, WELLNESS_FILE, log) {
+            Some(Value::Object(data)) => data,
+            _ => Map::new(),
+        };
+        let metrics = serde_json::to_value(&wellness).map_err(|e| e.to_string())?;
+        wellness_data.insert("_computed_metrics".to_string(), metrics);
+        save(helix_dir, WELLNESS_FILE, &Value::Object(wellness_data))?;
+        log.push("  Wellness metrics saved".to_string());
+    }
+
+    log.push("[HELIX] Synthesis complete.".to_string());
+    Ok(SynthesisReport {
+        mode: mode.to_string(),
+        dry_run,
+        emotional,
+        relational,
+        wellness,
+    })
+}
@@ -0,0 +1,640 @@
+// Transformation milestone detection
+//
+// The weekly FullIntegration scheduler job refreshes the cross-layer
+// analyses (relationship graph, wellness trends, purpose alignment) and
+// then looks for significant shifts:
+//   - a sustained change in emotional valence (wellness series)
+//   - new dominant topics in recent memories
+//   - attachments whose mentions have faded (relationship graph)
+// Each shift becomes a candidate milestone in
+// transformation/milestone_candidates.json. Nothing reaches
+// transformation/history.json until the user approves the candidate.
+// The job depends on the consolidation and synthesis jobs, so it runs on
+// fresh memories and summaries; while it waits for them it is checked
+// every few minutes. One-shot FullIntegration jobs (schedule_job_once) run
+// the same integration when due, whether or not the weekly one is on.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use super::graph::{self, EdgeKind, NodeKind};
+use super::{ikigai, memories, wellness};
+use crate::commands::psychology::{read_layer_file, vault_error, write_files};
+use crate::commands::scheduler::{self, JobStatus, JobType, SchedulerConfig};
+use crate::commands::scheduler_runner;
+use crate::commands::{CommandError, ErrorCode};
+use crate::vault;
+
+const HISTORY_FILE: &str = "transformation/history.json";
+/// Candidates and decisions, relative to the data directory
+const CANDIDATES_FILE: &str = "transformation/milestone_candidates.json";
+const CANDIDATES_SCHEMA_VERSION: &str = "1.0";
+/// Decided candidates kept for deduplication
+const MAX_DECIDED: usize = 200;
+/// A shift already proposed within this many days isn't proposed again
+const REPROPOSE_AFTER_DAYS: i64 = 30;
+/// How often the FullIntegration job is checked for being due
+const JOB_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Valence: recent window compared with the baseline window before it
+const VALENCE_RECENT_DAYS: i64 = 14;
+const VALENCE_BASELINE_DAYS: i64 = 28;
+/// Days with valence data each window needs
+const VALENCE_MIN_DAYS: usize = 7;
+const VALENCE_SHIFT: f64 = 0.25;
+
+/// Topics: share of recent memories against the weeks before
+const TOPIC_RECENT_DAYS: i64 = 14;
+const TOPIC_BASELINE_DAYS: i64 = 42;
+const TOPIC_MEMORY_LIMIT: usize = 2000;
+const TOPIC_MIN_RECENT_MEMORIES: usize = 10;
+const TOPIC_MIN_MENTIONS: usize = 5;
+/// Share of recent memories a topic needs to count as dominant
+const TOPIC_MIN_SHARE: f64 = 0.15;
+/// Recent share over baseline share
+const TOPIC_GROWTH: f64 = 3.0;
+const MAX_NEW_TOPICS: usize = 3;
+
+/// Attachments: once mentioned this often, then silent this long
+const ATTACHMENT_MIN_MENTIONS: u32 = 5;
+const ATTACHMENT_SILENT_DAYS: i64 = 45;
+const ATTACHMENT_FADED_STRENGTH: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneKind {
+    ValenceShift,
+    NewTopic,
+    FadingAttachment,
+}
+
+impl MilestoneKind {
+    /// `type` of the history entry, in the vocabulary of history.json
+    fn history_type(self) -> &'static str {
+        match self {
+            MilestoneKind::ValenceShift => "shift",
+            MilestoneKind::NewTopic => "emergence",
+            MilestoneKind::FadingAttachment => "release",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneCandidate {
+    pub id: String,
+    /// Identifies the shift ("valence:down", "topic:<word>",
+    /// "attachment:<id>") so it isn't proposed twice
+    pub key: String,
+    pub kind: MilestoneKind,
+    pub detected_at: String,
+    pub description: String,
+    /// What was detected, for the history entry's unfreeze_trigger
+    pub trigger: String,
+    /// Window the shift was observed over
+    pub change_period: String,
+    pub evidence: Value,
+    pub status: CandidateStatus,
+    pub decided_at: Option<String>,
+    /// Entry id in history.json once approved
+    pub history_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CandidatesFile {
+    #[serde(default)]
+    schema_version: String,
+    /// Last detection run, for scheduling across restarts
+    last_run_at: Option<String>,
+    #[serde(default)]
+    candidates: Vec<MilestoneCandidate>,
+}
+
+/// A shift found by one of the detectors
+struct Shift {
+    key: String,
+    kind: MilestoneKind,
+    description: String,
+    trigger: String,
+    change_period: String,
+    evidence: Value,
+}
+
+fn candidates_path() -> Result<PathBuf, CommandError> {
+    Ok(crate::accounts::data_dir()?.join(CANDIDATES_FILE))
+}
+
+fn load_candidates() -> Result<CandidatesFile, CommandError> {
+    let path = candidates_path()?;
+    if !path.exists() {
+        return Ok(CandidatesFile::default());
+    }
+    let content = vault::read_to_string(&path).map_err(|e| vault_error("Failed to read milestone candidates", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse milestone candidates: {}", e).into())
+}
+
+fn save_candidates(file: &mut CandidatesFile) -> Result<(), CommandError> {
+    // Drop the oldest decided candidates; pending ones always stay
+    let decided = file.candidates.iter().filter(|c| c.status != CandidateStatus::Pending).count();
+    let mut excess = decided.saturating_sub(MAX_DECIDED);
+    file.candidates.retain(|c| {
+        let drop = excess > 0 && c.status != CandidateStatus::Pending;
+        excess -= drop as usize;
+        !drop
+    });
+
+    file.schema_version = CANDIDATES_SCHEMA_VERSION.to_string();
+    let path = candidates_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| CommandError::from_io("Failed to create transformation directory", e))?;
+    }
+    let json = serde_json::to_string_pretty(file).map_err(|e| format!("Failed to serialize milestone candidates: {}", e))?;
+    vault::write(&path, &json).map_err(|e| vault_error("Failed to write milestone candidates", e))
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+// Detectors
+
+fn valence_shift(entries: &[wellness::WellnessEntry], today: NaiveDate) -> Option<Shift> {
+    let recent_start = today - ChronoDuration::days(VALENCE_RECENT_DAYS - 1);
+    let baseline_start = recent_start - ChronoDuration::days(VALENCE_BASELINE_DAYS);
+
+    let (mut recent, mut baseline) = (Vec::new(), Vec::new());
+    for entry in entries {
+        let (Ok(date), Some(valence)) = (NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d"), entry.valence) else {
+            continue;
+        };
+        if date >= recent_start && date <= today {
+            recent.push(valence);
+        } else if date >= baseline_start && date < recent_start {
+            baseline.push(valence);
+        }
+    }
+    if recent.len() < VALENCE_MIN_DAYS || baseline.len() < VALENCE_MIN_DAYS {
+        return None;
+    }
+
+    let (before, after) = (mean(&baseline)?, mean(&recent)?);
+    let change = after - before;
+    if change.abs() < VALENCE_SHIFT {
+        return None;
+    }
+
+    let direction = if change > 0.0 { "up" } else { "down" };
+    Some(Shift {
+        key: format!("valence:{}", direction),
+        kind: MilestoneKind::ValenceShift,
+        description: format!(
+            "Emotional tone has been {} for {} days",
+            if change > 0.0 { "markedly more positive" } else { "markedly more negative" },
+            VALENCE_RECENT_DAYS
+        ),
+        trigger: format!(
+            "Mean valence moved from {:.2} to {:.2} over {} days with data",
+            before,
+            after,
+            recent.len()
+        ),
+        change_period: format!("{} days", VALENCE_RECENT_DAYS),
+        evidence: json!({
+            "baseline_valence": before,
+            "recent_valence": after,
+            "change": change,
+            "baseline_days": baseline.len(),
+            "recent_days": recent.len(),
+        }),
+    })
+}
+
+async fn new_topics(now: DateTime<Utc>) -> Result<Vec<Shift>, String> {
+    let recent_start = now - ChronoDuration::days(TOPIC_RECENT_DAYS);
+    let since = recent_start - ChronoDuration::days(TOPIC_BASELINE_DAYS);
+    let records = memories::fetch_since("content,created_at", Some(&since.to_rfc3339()), TOPIC_MEMORY_LIMIT).await?;
+
+    let (mut recent, mut baseline) = (0usize, 0usize);
+    // word -> (recent memories, baseline memories)
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for record in &records {
+        let Some(at) = parse_time(&record.created_at) else {
+            continue;
+        };
+        let is_recent = at >= recent_start;
+        if is_recent {
+            recent += 1;
+        } else {
+            baseline += 1;
+        }
+        for word in memories::content_words(&record.content) {
+            if word.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let count = counts.entry(word).or_default();
+            if is_recent {
+                count.0 += 1;
+            } else {
+                count.1 += 1;
+            }
+        }
+    }
+    if recent < TOPIC_MIN_RECENT_MEMORIES {
+        return Ok(Vec::new());
+    }
+
+    let mut topics: Vec<(String, f64, f64, usize)> = counts
+        .into_iter()
+        .filter(|(_, (r, _))| *r >= TOPIC_MIN_MENTIONS)
+        .map(|(word, (r, b))| {
+            let recent_share = r as f64 / recent as f64;
+            // Smoothed so topics absent from the baseline don't divide by zero
+            let baseline_share = (b as f64 + 1.0) / (baseline as f64 + 1.0);
+            (word, recent_share, baseline_share, r)
+        })
+        .filter(|(_, r, b, _)| *r >= TOPIC_MIN_SHARE && *r >= b * TOPIC_GROWTH)
+        .collect();
+    topics.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(topics
+        .into_iter()
+        .take(MAX_NEW_TOPICS)
+        .map(|(word, recent_share, baseline_share, mentions)| Shift {
+            key: format!("topic:{}", word),
+            kind: MilestoneKind::NewTopic,
+            description: format!("'{}' has become a dominant topic", word),
+            trigger: format!(
+                "In {:.0}% of memories from the last {} days, up from {:.0}% before",
+                recent_share * 100.0,
+                TOPIC_RECENT_DAYS,
+                baseline_share * 100.0
+            ),
+            change_period: format!("{} days", TOPIC_RECENT_DAYS),
+            evidence: json!({
+                "topic": word,
+                "recent_memories": mentions,
+                "recent_share": recent_share,
+                "baseline_share": baseline_share,
+            }),
+        })
+        .collect())
+}
+
+fn fading_attachments(now: DateTime<Utc>) -> Result<Vec<Shift>, CommandError> {
+    let graph = graph::get_relationship_graph()?;
+    let attachments: HashMap<&str, &graph::GraphNode> = graph
+        .nodes
+        .iter()
+        .filter(|n| matches!(n.kind, NodeKind::Primary | NodeKind::Secondary))
+        .map(|n| (n.id.as_str(), n))
+        .collect();
+
+    Ok(graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Relationship && e.mentions >= ATTACHMENT_MIN_MENTIONS)
+        .filter(|e| e.strength < ATTACHMENT_FADED_STRENGTH)
+        .filter_map(|edge| {
+            let node = attachments.get(edge.target.as_str())?;
+            let last = edge.last_mentioned.as_deref().and_then(parse_time)?;
+            let silent_days = (now - last).num_days();
+            (silent_days >= ATTACHMENT_SILENT_DAYS).then(|| Shift {
+                key: format!("attachment:{}", node.id),
+                kind: MilestoneKind::FadingAttachment,
+                description: format!("The attachment to {} is fading", node.name),
+                trigger: format!(
+                    "Not mentioned in {} days after {} mentions",
+                    silent_days, edge.mentions
+                ),
+                change_period: format!("{} days", silent_days),
+                evidence: json!({
+                    "id": node.id,
+                    "mentions": edge.mentions,
+                    "strength": edge.strength,
+                    "last_mentioned": edge.last_mentioned,
+                    "trust": edge.trust,
+                }),
+            })
+        })
+        .collect())
+}
+
+/// Run the detectors and store new shifts as pending candidates; returns
+/// the new candidates
+pub async fn detect() -> Result<Vec<MilestoneCandidate>, CommandError> {
+    let now = Utc::now();
+    let mut shifts = Vec::new();
+
+    match wellness::entries() {
+        Ok(entries) => shifts.extend(valence_shift(&entries, Local::now().date_naive())),
+        Err(e) => log::warn!("Valence shift detection skipped: {}", e),
+    }
+    match new_topics(now).await {
+        Ok(topics) => shifts.extend(topics),
+        Err(e) => log::warn!("Topic detection skipped: {}", e),
+    }
+    match fading_attachments(now) {
+        Ok(fading) => shifts.extend(fading),
+        Err(e) => log::warn!("Attachment detection skipped: {}", e),
+    }
+
+    let mut file = load_candidates()?;
+    let cutoff = now - ChronoDuration::days(REPROPOSE_AFTER_DAYS);
+    let timestamp = now.to_rfc3339();
+
+    let mut added = Vec::new();
+    for shift in shifts {
+        let seen = file.candidates.iter().any(|c| {
+            c.key == shift.key
+                && (c.status == CandidateStatus::Pending
+                    || parse_time(&c.detected_at).is_some_and(|at| at >= cutoff))
+        });
+        if seen {
+            continue;
+        }
+        added.push(MilestoneCandidate {
+            id: format!("milestone_{}_{}", now.timestamp_millis(), file.candidates.len() + added.len()),
+            key: shift.key,
+            kind: shift.kind,
+            detected_at: timestamp.clone(),
+            description: shift.description,
+            trigger: shift.trigger,
+            change_period: shift.change_period,
+            evidence: shift.evidence,
+            status: CandidateStatus::Pending,
+            decided_at: None,
+            history_id: None,
+        });
+    }
+
+    file.candidates.extend(added.iter().cloned());
+    file.last_run_at = Some(timestamp);
+    save_candidates(&mut file)?;
+    Ok(added)
+}
+
+// FullIntegration job
+
+/// The first integration slot (scheduler integration_day at
+/// integration_time, local) after `after`
+fn next_integration(config: &SchedulerConfig, after: DateTime<Utc>) -> DateTime<Utc> {
+    let day = config.integration_day.trim().parse::<u32>().unwrap_or(0) % 7;
+    let time = NaiveTime::parse_from_str(config.integration_time.trim(), "%H:%M")
+        .unwrap_or_else(|_| NaiveTime::from_hms_opt(3, 0, 0).unwrap_or_default());
+
+    let after_local = after.with_timezone(&Local);
+    (0..=7)
+        .filter_map(|offset| {
+            let date = after_local.date_naive() + ChronoDuration::days(offset);
+            let slot = Local.from_local_datetime(&date.and_time(time)).earliest()?;
+            (date.weekday().num_days_from_sunday() == day && slot > after_local).then_some(slot)
+        })
+        .next()
+        .map(|slot| slot.with_timezone(&Utc))
+        .unwrap_or(after + ChronoDuration::weeks(1))
+}
+
+/// "MM HH * * D" for the job listing
+fn integration_cron(config: &SchedulerConfig) -> String {
+    let (hour, minute) = config.integration_time.split_once(':').unwrap_or(("3", "0"));
+    let number = |s: &str| s.trim().parse::<u32>().unwrap_or(0);
+    format!("{} {} * * {}", number(minute), number(hour), number(&config.integration_day) % 7)
+}
+
+/// Refresh the cross-layer analyses, then detect milestones
+async fn full_integration(app: &AppHandle) -> Result<Value, CommandError> {
+    let mut report = serde_json::Map::new();
+
+    let relationships = graph::update_from_memories().await;
+    report.insert("relationship_memories".to_string(), json!(relationships.as_ref().ok()));
+    let wellness = wellness::refresh().await;
+    report.insert("wellness_days".to_string(), json!(wellness.as_ref().ok()));
+    let purpose = ikigai::analyze(false).await;
+    report.insert("purpose_score".to_string(), json!(purpose.as_ref().ok().and_then(|p| p.current_score)));
+    for (name, error) in [
+        ("Relationship graph", relationships.err()),
+        ("Wellness trends", wellness.err()),
+        ("Purpose alignment", purpose.err()),
+    ] {
+        if let Some(e) = error {
+            log::warn!("{} skipped during full integration: {}", name, e);
+        }
+    }
+
+    let candidates = detect().await?;
+    if !candidates.is_empty() {
+        let _ = app.emit("transformation:milestones", &candidates);
+    }
+    report.insert(
+        "milestone_candidates".to_string(),
+        json!(candidates.iter().map(|c| &c.id).collect::<Vec<_>>()),
+    );
+    Ok(Value::Object(report))
+}
+
+/// Run the FullIntegration job if it is due
+async fn run_integration_job(app: &AppHandle) -> Result<(), CommandError> {
+    let config = scheduler::get_scheduler_config()?;
+    if !config.enabled || !config.weekly_full_integration {
+        return Ok(());
+    }
+
+    let mut job = scheduler::ensure_job(JobType::FullIntegration, &integration_cron(&config))?;
+    let now = Utc::now();
+    let now_secs = now.timestamp().max(0) as u64;
+    // Deferred, or waiting to retry a failed run
+    let retrying = job.status == JobStatus::Pending && job.retry_count > 0;
+    let waiting = (job.status == JobStatus::Deferred || retrying) && job.next_run > now_secs;
+    if job.status == JobStatus::Paused || waiting {
+        return Ok(());
+    }
+
+    // Due from the last persisted run, so restarts neither skip nor repeat it.
+    // The slot's jitter is drawn once, when next_run is set for it: only a
+    // next_run further from `due` than the jitter is from an older schedule.
+    let last_run = load_candidates()?.last_run_at.as_deref().and_then(parse_time);
+    let due = last_run.map_or(now, |last| next_integration(&config, last));
+    let due_secs = due.timestamp().max(0) as u64;
+    if due_secs.abs_diff(job.next_run) > config.jitter_minutes as u64 * 60 {
+        scheduler::reschedule_job(&job.id, due_secs)?;
+        job = scheduler::get_job(job.id)?;
+    }
+    if job.next_run > now_secs {
+        return Ok(());
+    }
+
+    // Deferred while Supabase is unreachable, retried after the backoff;
+    // waiting until consolidation and synthesis have completed
+    let job = scheduler::trigger_job(job.id).await?;
+    if job.status != JobStatus::Running {
+        return Ok(());
+    }
+    match scheduler_runner::run_owned(&job, full_integration(app)).await {
+        Ok(report) => scheduler::complete_job(job.id.clone(), Some(report))?,
+        Err(e) => scheduler::fail_job(job.id.clone(), e.message)?,
+    }
+    let next = next_integration(&config, Utc::now());
+    scheduler::reschedule_job(&job.id, next.timestamp().max(0) as u64)
+}
+
+/// Run the one-shot FullIntegration jobs that are due
+async fn run_one_shot_jobs(app: &AppHandle) -> Result<(), CommandError> {
+    if !scheduler::get_scheduler_config()?.enabled {
+        return Ok(());
+    }
+    let now = Utc::now().timestamp().max(0) as u64;
+    let due: Vec<_> = scheduler::get_scheduled_jobs()?
+        .into_iter()
+        .filter(|job| {
+            job.job_type == JobType::FullIntegration
+                && job.is_one_shot()
+                && !job.is_spent()
+                && !matches!(job.status, JobStatus::Paused | JobStatus::Running)
+                && job.next_run <= now
+        })
+        .collect();
+
+    for job in due {
+        let job = scheduler::trigger_job(job.id).await?;
+        if job.status != JobStatus::Running {
+            continue;
+        }
+        match scheduler_runner::run_owned(&job, full_integration(app)).await {
+            Ok(report) => scheduler::complete_job(job.id.clone(), Some(report))?,
+            Err(e) => scheduler::fail_job(job.id.clone(), e.message)?,
+        }
+    }
+    Ok(())
+}
+
+/// Check the FullIntegration jobs every five minutes
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_integration_job(&app).await {
+                log::debug!("Full integration check skipped: {}", e);
+            }
+            if let Err(e) = run_one_shot_jobs(&app).await {
+                log::debug!("One-shot full integration check skipped: {}", e);
+            }
+            tokio::time::sleep(JOB_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// Tauri commands
+
+/// Milestone candidates, newest first; decided ones only when asked
+#[tauri::command]
+pub fn get_milestone_candidates(include_decided: Option<bool>) -> Result<Vec<MilestoneCandidate>, CommandError> {
+    let include_decided = include_decided.unwrap_or(false);
+    Ok(load_candidates()?
+        .candidates
+        .into_iter()
+        .rev()
+        .filter(|c| include_decided || c.status == CandidateStatus::Pending)
+        .collect())
+}
+
+fn take_pending(file: &CandidatesFile, id: &str) -> Result<usize, CommandError> {
+    let index = file
+        .candidates
+        .iter()
+        .position(|c| c.id == id)
+        .ok_or_else(|| {
+            CommandError::not_found(format!("Unknown milestone candidate: {}", id))
+                .with_details(json!({ "id": id }))
+        })?;
+    if file.candidates[index].status != CandidateStatus::Pending {
+        return Err(CommandError::new(
+            ErrorCode::Conflict,
+            format!("Milestone candidate {} was already decided", id),
+        )
+        .with_details(json!({ "id": id })));
+    }
+    Ok(index)
+}
+
+/// Append a candidate to transformation history, optionally with an
+/// edited description; returns the history entry
+#[tauri::command]
+pub fn approve_milestone(id: String, description: Option<String>) -> Result<Value, CommandError> {
+    let helix_dir = crate::accounts::data_dir()?;
+    let mut file = load_candidates()?;
+    let index = take_pending(&file, &id)?;
+    let candidate = &mut file.candidates[index];
+
+    let mut history = if helix_dir.join(HISTORY_FILE).exists() {
+        read_layer_file(&helix_dir, HISTORY_FILE)?
+    } else {
+        json!({ "schema_version": "1.0", "transformations": [] })
+    };
+    let transformations = history
+        .as_object_mut()
+        .ok_or("history.json is not an object")?
+        .entry("transformations")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or("history.json transformations is not an array")?;
+
+    let now = Utc::now();
+    let date = now.with_timezone(&Local).format("%Y-%m-%d").to_string();
+    let history_id = format!(
+        "helix_{}_{}",
+        candidate.kind.history_type(),
+        now.with_timezone(&Local).format("%Y%m%d_%H%M%S")
+    );
+    let entry = json!({
+        "id": history_id,
+        "subject": "helix",
+        "date": date,
+        "type": candidate.kind.history_type(),
+        "description": description.filter(|d| !d.trim().is_empty()).unwrap_or_else(|| candidate.description.clone()),
+        "unfreeze_trigger": candidate.trigger,
+        "change_period": candidate.change_period,
+        "refreeze_outcome": null,
+        "identity_before": null,
+        "identity_after": null,
+        "detected": { "candidate_id": candidate.id, "kind": candidate.kind, "evidence": candidate.evidence },
+    });
+    transformations.push(entry.clone());
+
+    let content = serde_json::to_string_pretty(&history).map_err(|e| format!("Failed to serialize history: {}", e))?;
+    write_files(&helix_dir, &[(HISTORY_FILE, content)])?;
+
+    candidate.status = CandidateStatus::Approved;
+    candidate.decided_at = Some(now.to_rfc3339());
+    candidate.history_id = Some(history_id);
+    save_candidates(&mut file)?;
+
+    Ok(entry)
+}
+
+/// Dismiss a candidate; the same shift isn't proposed again for
+/// REPROPOSE_AFTER_DAYS
+#[tauri::command]
+pub fn reject_milestone(id: String) -> Result<(), CommandError> {
+    let mut file = load_candidates()?;
+    let index = take_pending(&file, &id)?;
+    let candidate = &mut file.candidates[index];
+    candidate.status = CandidateStatus::Rejected;
+    candidate.decided_at = Some(Utc::now().to_rfc3339());
+    save_candidates(&mut file)
+}
@@ -0,0 +1,401 @@
+// Wellness trends for the purpose layer
+//
+// purpose/wellness.json holds hand-written metrics; this module adds a
+// daily time series next to it in purpose/wellness_trends.json. Each day
+// is scored from three signals:
+//   - emotional valence: mean valence of that day's memories
+//   - activity: user messages in session transcripts
+//   - sleep: the longest overnight gap between user messages
+// The series is refreshed every few hours (the last REFRESH_DAYS days are
+// recomputed, since transcripts and memories arrive late) and read back
+// with get_wellness_trends for charting.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::memories;
+use crate::commands::psychology::vault_error;
+use crate::commands::CommandError;
+use crate::vault;
+
+/// Time series, relative to the data directory
+const TRENDS_FILE: &str = "purpose/wellness_trends.json";
+const TRENDS_SCHEMA_VERSION: &str = "1.0";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+/// Days recomputed on each refresh
+const REFRESH_DAYS: i64 = 7;
+/// Days computed when there is no series yet
+const BACKFILL_DAYS: i64 = 30;
+/// Oldest entries are dropped beyond this
+const MAX_ENTRIES: usize = 730;
+const MEMORY_COLUMNS: &str = "emotional_valence,created_at";
+const MEMORY_LIMIT: usize = 5000;
+
+/// Component weights in the daily score
+const VALENCE_WEIGHT: f64 = 0.4;
+const ACTIVITY_WEIGHT: f64 = 0.3;
+const SLEEP_WEIGHT: f64 = 0.3;
+/// User messages per day at which activity scores 0.5
+const ACTIVITY_SATURATION: f64 = 10.0;
+const SLEEP_TARGET_HOURS: f64 = 8.0;
+/// Overnight window a sleep gap must overlap, in local hours around midnight
+const NIGHT_START_HOUR: i64 = -2;
+const NIGHT_END_HOUR: i64 = 6;
+/// Longer gaps mean the app wasn't used, not that anyone slept
+const MAX_SLEEP_HOURS: f64 = 16.0;
+
+/// One day of the series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WellnessEntry {
+    /// Local date, YYYY-MM-DD
+    pub date: String,
+    /// 0..1 weighted from the components below that have data
+    pub score: Option<f64>,
+    /// Mean emotional valence (-1..1)
+    pub valence: Option<f64>,
+    pub memories: usize,
+    /// User messages in session transcripts
+    pub activity: usize,
+    pub activity_score: f64,
+    /// Inferred sleep window (local RFC 3339) ending on this date
+    pub sleep_start: Option<String>,
+    pub sleep_end: Option<String>,
+    pub sleep_hours: Option<f64>,
+    pub sleep_score: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrendsFile {
+    #[serde(default)]
+    schema_version: String,
+    updated_at: Option<String>,
+    #[serde(default)]
+    entries: Vec<WellnessEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WellnessTrends {
+    pub range: String,
+    /// Oldest first
+    pub entries: Vec<WellnessEntry>,
+    pub average_score: Option<f64>,
+    /// Score change per day (least-squares slope over scored days)
+    pub trend: Option<f64>,
+    pub average_sleep_hours: Option<f64>,
+    pub updated_at: Option<String>,
+}
+
+fn trends_path() -> Result<PathBuf, CommandError> {
+    Ok(crate::accounts::data_dir()?.join(TRENDS_FILE))
+}
+
+fn load_trends() -> Result<TrendsFile, CommandError> {
+    let path = trends_path()?;
+    if !path.exists() {
+        return Ok(TrendsFile::default());
+    }
+    let content = vault::read_to_string(&path).map_err(|e| vault_error("Failed to read wellness trends", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse wellness trends: {}", e).into())
+}
+
+fn save_trends(trends: &TrendsFile) -> Result<(), CommandError> {
+    let path = trends_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| CommandError::from_io("Failed to create purpose directory", e))?;
+    }
+    let json = serde_json::to_string_pretty(trends).map_err(|e| format!("Failed to serialize wellness trends: {}", e))?;
+    vault::write(&path, &json).map_err(|e| vault_error("Failed to write wellness trends", e))
+}
+
+fn local_date(at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&Local).date_naive()
+}
+
+/// Local midnight starting `date`, plus `hours`
+fn local_hour(date: NaiveDate, hours: i64) -> Option<DateTime<Utc>> {
+    let midnight = Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+    Some(midnight.with_timezone(&Utc) + ChronoDuration::hours(hours))
+}
+
+// Signals
+
+/// Mean valence and memory count per local day
+fn valence_by_day(records: &[memories::MemoryRecord]) -> BTreeMap<NaiveDate, (f64, usize, usize)> {
+    let mut days: BTreeMap<NaiveDate, (f64, usize, usize)> = BTreeMap::new();
+    for record in records {
+        let Ok(at) = DateTime::parse_from_rfc3339(&record.created_at) else {
+            continue;
+        };
+        let day = days.entry(local_date(at.with_timezone(&Utc))).or_default();
+        day.2 += 1;
+        if let Some(valence) = record.emotional_valence {
+            day.0 += valence.clamp(-1.0, 1.0);
+            day.1 += 1;
+        }
+    }
+    days
+}
+
+/// Session transcript directories, as cleared by secure wipe
+fn session_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(data_dir) = crate::accounts::data_dir() {
+        dirs.push(data_dir.join("sessions"));
+    }
+    if let Some(state_dir) = crate::gateway::config_sync::gateway_state_dir() {
+        if let Ok(agents) = fs::read_dir(state_dir.join("agents")) {
+            dirs.extend(agents.flatten().map(|agent| agent.path().join("sessions")));
+        }
+    }
+    dirs
+}
+
+/// Entry or message timestamp: RFC 3339 string or epoch milliseconds
+fn entry_time(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    match value? {
+        Value::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Utc)),
+        Value::Number(n) => DateTime::from_timestamp_millis(n.as_i64()?),
+        _ => None,
+    }
+}
+
+/// Times of user messages in transcripts touched since `since`, sorted
+fn usage_times(since: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let cutoff = SystemTime::from(since);
+    let mut times = Vec::new();
+
+    for dir in session_dirs() {
+        let Ok(files) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let recent = file.metadata().and_then(|m| m.modified()).is_ok_and(|m| m >= cutoff);
+            if !recent {
+                continue;
+            }
+            let Ok(handle) = fs::File::open(&path) else {
+                continue;
+            };
+
+            for line in BufReader::new(handle).lines().map_while(Result::ok) {
+                let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                let Some(message) = entry.get("message") else {
+                    continue;
+                };
+                if message.get("role").and_then(Value::as_str) != Some("user") {
+                    continue;
+                }
+                let at = entry_time(entry.get("timestamp")).or_else(|| entry_time(message.get("timestamp")));
+                if let Some(at) = at.filter(|at| *at >= since) {
+                    times.push(at);
+                }
+            }
+        }
+    }
+
+    times.sort();
+    times
+}
+
+/// Longest gap between consecutive usage times that overlaps the night
+/// before `date`
+fn sleep_window(times: &[DateTime<Utc>], date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let night_start = local_hour(date, NIGHT_START_HOUR)?;
+    let night_end = local_hour(date, NIGHT_END_HOUR)?;
+    let max_gap = ChronoDuration::seconds((MAX_SLEEP_HOURS * 3600.0) as i64);
+
+    times
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .filter(|(from, to)| *to > night_start && *from < night_end && *to - *from <= max_gap)
+        .max_by_key(|(from, to)| *to - *from)
+}
+
+fn weighted_score(components: &[(Option<f64>, f64)]) -> Option<f64> {
+    let (sum, weights) = components
+        .iter()
+        .filter_map(|(value, weight)| value.map(|v| (v * weight, *weight)))
+        .fold((0.0, 0.0), |(sum, weights), (v, w)| (sum + v, weights + w));
+    (weights > 0.0).then(|| sum / weights)
+}
+
+fn score_day(
+    date: NaiveDate,
+    valence: Option<(f64, usize, usize)>,
+    times: &[DateTime<Utc>],
+) -> WellnessEntry {
+    let activity = times.iter().filter(|at| local_date(**at) == date).count();
+    let activity_score = activity as f64 / (activity as f64 + ACTIVITY_SATURATION);
+
+    let mean_valence = valence.and_then(|(sum, count, _)| (count > 0).then(|| sum / count as f64));
+    let sleep = sleep_window(times, date);
+    let sleep_hours = sleep.map(|(from, to)| (to - from).num_minutes() as f64 / 60.0);
+    let sleep_score = sleep_hours.map(|h| (1.0 - (h - SLEEP_TARGET_HOURS).abs() / SLEEP_TARGET_HOURS).clamp(0.0, 1.0));
+
+    // A day without messages has no activity signal rather than a zero
+    let score = weighted_score(&[
+        (mean_valence.map(|v| (v + 1.0) / 2.0), VALENCE_WEIGHT),
+        ((activity > 0).then_some(activity_score), ACTIVITY_WEIGHT),
+        (sleep_score, SLEEP_WEIGHT),
+    ]);
+
+    WellnessEntry {
+        date: date.format("%Y-%m-%d").to_string(),
+        score,
+        valence: mean_valence,
+        memories: valence.map_or(0, |(_, _, memories)| memories),
+        activity,
+        activity_score,
+        sleep_start: sleep.map(|(from, _)| from.with_timezone(&Local).to_rfc3339()),
+        sleep_end: sleep.map(|(_, to)| to.with_timezone(&Local).to_rfc3339()),
+        sleep_hours,
+        sleep_score,
+    }
+}
+
+/// Recompute recent days and merge them into the series
+pub async fn refresh() -> Result<usize, CommandError> {
+    let mut trends = load_trends()?;
+    let days = if trends.entries.is_empty() { BACKFILL_DAYS } else { REFRESH_DAYS };
+
+    let today = Local::now().date_naive();
+    let first = today - ChronoDuration::days(days - 1);
+    // Sleep for the first day starts the evening before
+    let since = local_hour(first, -24).ok_or("Invalid local date")?;
+
+    let times = usage_times(since);
+    // Without memories, keep the valence already recorded for these days
+    let valence = match memories::fetch_since(MEMORY_COLUMNS, Some(&since.to_rfc3339()), MEMORY_LIMIT).await {
+        Ok(records) => Some(valence_by_day(&records)),
+        Err(e) => {
+            log::debug!("Wellness refresh without memories: {}", e);
+            None
+        }
+    };
+
+    let mut by_date: BTreeMap<String, WellnessEntry> =
+        trends.entries.drain(..).map(|entry| (entry.date.clone(), entry)).collect();
+    for date in first.iter_days().take(days as usize) {
+        let day_valence = match &valence {
+            Some(valence) => valence.get(&date).copied(),
+            None => by_date
+                .get(&date.format("%Y-%m-%d").to_string())
+                .and_then(|old| old.valence.map(|v| (v, 1, old.memories))),
+        };
+        let entry = score_day(date, day_valence, &times);
+        by_date.insert(entry.date.clone(), entry);
+    }
+
+    trends.entries = by_date.into_values().collect();
+    let excess = trends.entries.len().saturating_sub(MAX_ENTRIES);
+    trends.entries.drain(..excess);
+    trends.schema_version = TRENDS_SCHEMA_VERSION.to_string();
+    trends.updated_at = Some(Utc::now().to_rfc3339());
+    save_trends(&trends)?;
+
+    Ok(days as usize)
+}
+
+/// The whole series, oldest first
+pub fn entries() -> Result<Vec<WellnessEntry>, CommandError> {
+    Ok(load_trends()?.entries)
+}
+
+/// Refresh the series now and every REFRESH_INTERVAL
+pub fn start() {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = refresh().await {
+                log::debug!("Wellness refresh skipped: {}", e);
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+// Trends
+
+/// "7d", "30d", "90d", "365d" or "all"; `None` is every day
+fn range_days(range: &str) -> Result<Option<i64>, CommandError> {
+    if range == "all" {
+        return Ok(None);
+    }
+    range
+        .strip_suffix('d')
+        .and_then(|days| days.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .map(Some)
+        .ok_or_else(|| {
+            CommandError::invalid_input(format!("Invalid range: {} (expected e.g. 7d, 30d or all)", range))
+                .with_details(serde_json::json!({ "range": range }))
+        })
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Least-squares slope of score over day index
+fn slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let mean_x = mean(points.iter().map(|p| p.0))?;
+    let mean_y = mean(points.iter().map(|p| p.1))?;
+    let (covariance, variance) = points.iter().fold((0.0, 0.0), |(c, v), (x, y)| {
+        (c + (x - mean_x) * (y - mean_y), v + (x - mean_x).powi(2))
+    });
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+// Tauri commands
+
+/// Daily wellness entries for charting, with the average and trend of
+/// the range (default 30d)
+#[tauri::command]
+pub fn get_wellness_trends(range: Option<String>) -> Result<WellnessTrends, CommandError> {
+    let range = range.unwrap_or_else(|| "30d".to_string());
+    let days = range_days(&range)?;
+    let trends = load_trends()?;
+
+    let today = Local::now().date_naive();
+    let first = days.map(|days| today - ChronoDuration::days(days - 1));
+    let entries: Vec<WellnessEntry> = trends
+        .entries
+        .into_iter()
+        .filter(|entry| {
+            let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok();
+            first.is_none_or(|first| date.is_some_and(|date| date >= first))
+        })
+        .collect();
+
+    let points: Vec<(f64, f64)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok()?;
+            Some(((date - today).num_days() as f64, entry.score?))
+        })
+        .collect();
+
+    Ok(WellnessTrends {
+        average_score: mean(points.iter().map(|p| p.1)),
+        trend: slope(&points),
+        average_sleep_hours: mean(entries.iter().filter_map(|e| e.sleep_hours)),
+        updated_at: trends.updated_at,
+        range,
+        entries,
+    })
+}
@@ -0,0 +1,299 @@
+// Cloud call queue - retries of fire-and-forget Supabase calls
+//
+// Registering this device, its heartbeats and its retirement are calls
+// nobody waits on, so a Supabase outage or a missing network at startup
+// shouldn't lose them. When one fails for a reason that may pass (no
+// connection, a timeout, a 5xx or 429 answer) it is queued in
+// `cloud-queue.json` in the account's data directory and retried with
+// exponential backoff, from a minute up to an hour. Calls are deduplicated
+// by what they're about: a newer registration or heartbeat of a device
+// replaces the queued one, and a call that goes through directly drops it.
+// While offline nothing is tried; when the connection returns every queued
+// call is due at once.
+// Calls Supabase rejects outright, calls older than a week and calls for
+// a Sync feature residency no longer allows are dropped.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::commands::auth::{self, DeviceRegistration};
+use crate::commands::CommandError;
+use crate::privacy::residency::{self, DataFeature};
+use crate::remote::connectivity;
+
+const QUEUE_FILE: &str = "cloud-queue.json";
+/// Wait before the first retry; doubles with each failed attempt
+const BASE_BACKOFF_MS: u64 = 60 * 1000;
+const MAX_BACKOFF_MS: u64 = 60 * 60 * 1000;
+/// Queued calls older than this are given up on
+const MAX_AGE_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+/// Longest sleep between checks, so a queue from another account is noticed
+const IDLE_CHECK: Duration = Duration::from_secs(5 * 60);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// Serializes reads and writes of the queue file
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+/// Wakes the flusher for a newly queued call
+static QUEUED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// A Supabase call that can be retried later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloudCall {
+    RegisterDevice(DeviceRegistration),
+    Heartbeat { device_id: String },
+    RetireDevice { device_id: String },
+}
+
+impl CloudCall {
+    /// Calls with the same key are duplicates; the newest one is kept
+    fn key(&self) -> String {
+        match self {
+            CloudCall::RegisterDevice(registration) => format!("register_device:{}", registration.device_id),
+            CloudCall::Heartbeat { device_id } => format!("heartbeat:{}", device_id),
+            CloudCall::RetireDevice { device_id } => format!("retire_device:{}", device_id),
+        }
+    }
+
+    async fn send(&self) -> Result<(), CallError> {
+        match self {
+            CloudCall::RegisterDevice(registration) => auth::post_device_registration(registration).await,
+            CloudCall::Heartbeat { device_id } => auth::patch_heartbeat(device_id).await,
+            CloudCall::RetireDevice { device_id } => auth::patch_retirement(device_id).await,
+        }
+    }
+
+    fn device_id(&self) -> &str {
+        match self {
+            CloudCall::RegisterDevice(registration) => &registration.device_id,
+            CloudCall::Heartbeat { device_id } | CloudCall::RetireDevice { device_id } => device_id,
+        }
+    }
+}
+
+/// Why a cloud call failed
+#[derive(Debug, Clone)]
+pub enum CallError {
+    /// May succeed later: no connection, a timeout, a server error
+    Transient(String),
+    /// Supabase refused the call; retrying won't help
+    Rejected(String),
+}
+
+impl CallError {
+    /// Classify a non-success HTTP answer
+    pub fn from_status(status: reqwest::StatusCode, body: String) -> Self {
+        if status.is_server_error()
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        {
+            CallError::Transient(format!("{}: {}", status, body))
+        } else {
+            CallError::Rejected(body)
+        }
+    }
+}
+
+/// A queued call and how its retries went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedCall {
+    pub call: CloudCall,
+    pub queued_at: u64,
+    pub attempts: u32,
+    /// When the next attempt is due (ms)
+    pub next_attempt: u64,
+    pub last_error: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn backoff_ms(attempts: u32) -> u64 {
+    BASE_BACKOFF_MS
+        .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF_MS)
+}
+
+fn queue_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(QUEUE_FILE))
+}
+
+fn read_queue() -> Result<Vec<QueuedCall>, String> {
+    let path = queue_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read cloud queue: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse cloud queue: {}", e))
+}
+
+fn write_queue(queue: &[QueuedCall]) -> Result<(), String> {
+    let path = queue_path()?;
+    if queue.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to clear cloud queue: {}", e))?;
+        }
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(queue).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write cloud queue: {}", e))
+}
+
+/// Apply `change` to the stored queue
+fn update<T>(change: impl FnOnce(&mut Vec<QueuedCall>) -> T) -> Result<T, String> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut queue = read_queue()?;
+    let result = change(&mut queue);
+    write_queue(&queue)?;
+    Ok(result)
+}
+
+/// Queue `call` for a retry after it failed with `error`, replacing a
+/// queued duplicate
+pub fn enqueue(call: CloudCall, error: &str) {
+    let key = call.key();
+    let now = now_ms();
+    let result = update(|queue| {
+        let previous = queue.iter().position(|queued| queued.call.key() == key).map(|index| queue.remove(index));
+        let attempts = previous.as_ref().map_or(0, |queued| queued.attempts) + 1;
+        queue.push(QueuedCall {
+            call,
+            queued_at: previous.map_or(now, |queued| queued.queued_at),
+            attempts,
+            next_attempt: now + backoff_ms(attempts),
+            last_error: Some(error.to_string()),
+        });
+    });
+    match result {
+        Ok(()) => {
+            log::info!("Queued {} for retry: {}", key, error);
+            QUEUED.notify_one();
+        }
+        Err(e) => log::warn!("Failed to queue {}: {}", key, e),
+    }
+}
+
+/// Drop a queued duplicate of `call`, which just went through
+pub fn settled(call: &CloudCall) {
+    let key = call.key();
+    if let Err(e) = update(|queue| queue.retain(|queued| queued.call.key() != key)) {
+        log::debug!("Failed to update cloud queue: {}", e);
+    }
+}
+
+/// Drop every queued call about `device_id` (it was retired or revoked)
+pub fn discard_device(device_id: &str) {
+    if let Err(e) = update(|queue| queue.retain(|queued| queued.call.device_id() != device_id)) {
+        log::debug!("Failed to update cloud queue: {}", e);
+    }
+}
+
+/// Make every queued call due now
+fn retry_all_now() {
+    let now = now_ms();
+    let _ = update(|queue| {
+        for queued in queue.iter_mut() {
+            queued.next_attempt = queued.next_attempt.min(now);
+        }
+    });
+}
+
+/// Try the calls that are due; returns how long until the next one is
+async fn flush() -> Duration {
+    let now = now_ms();
+    let due: Vec<QueuedCall> = match update(|queue| {
+        queue.retain(|queued| {
+            let expired = now.saturating_sub(queued.queued_at) > MAX_AGE_MS;
+            if expired {
+                log::warn!("Gave up on {} after {} attempts", queued.call.key(), queued.attempts);
+            }
+            !expired
+        });
+        queue.iter().filter(|queued| queued.next_attempt <= now).cloned().collect()
+    }) {
+        Ok(due) => due,
+        Err(e) => {
+            log::debug!("Cloud queue unavailable: {}", e);
+            return IDLE_CHECK;
+        }
+    };
+
+    for queued in due {
+        if !connectivity::is_online() {
+            break;
+        }
+        let key = queued.call.key();
+        if !residency::allows(DataFeature::Sync) {
+            log::info!("Dropped queued {}: sync is not allowed", key);
+            settled(&queued.call);
+            continue;
+        }
+        match queued.call.send().await {
+            Ok(()) => {
+                log::info!("Queued {} went through after {} attempts", key, queued.attempts);
+                settled(&queued.call);
+            }
+            Err(CallError::Rejected(e)) => {
+                log::warn!("Dropped queued {}: rejected by Supabase: {}", key, e);
+                settled(&queued.call);
+            }
+            Err(CallError::Transient(e)) => {
+                let result = update(|queue| {
+                    if let Some(entry) = queue.iter_mut().find(|entry| entry.call.key() == key) {
+                        entry.attempts += 1;
+                        entry.next_attempt = now_ms() + backoff_ms(entry.attempts);
+                        entry.last_error = Some(e.clone());
+                    }
+                });
+                if let Err(e) = result {
+                    log::debug!("Failed to update cloud queue: {}", e);
+                }
+            }
+        }
+    }
+
+    let next = read_queue()
+        .ok()
+        .and_then(|queue| queue.iter().map(|queued| queued.next_attempt).min());
+    match next {
+        Some(at) => Duration::from_millis(at.saturating_sub(now_ms())).min(IDLE_CHECK),
+        None => IDLE_CHECK,
+    }
+}
+
+/// Retry queued calls for the lifetime of the app (once per process)
+pub fn start() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if !connectivity::is_online() {
+                connectivity::wait_until_online().await;
+                retry_all_now();
+            }
+            let wait = flush().await;
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = QUEUED.notified() => {}
+                _ = connectivity::wait_until_offline() => {}
+            }
+        }
+    });
+}
+
+// Tauri commands
+
+/// Calls waiting for Supabase to be reachable again
+#[tauri::command]
+pub fn get_cloud_queue() -> Result<Vec<QueuedCall>, CommandError> {
+    let _guard = FILE_LOCK.lock().map_err(|e| e.to_string())?;
+    Ok(read_queue()?)
+}
@@ -0,0 +1,204 @@
+// Connectivity monitor
+//
+// Keeps a single online/offline verdict the cloud features consult before
+// they touch the network. Every few seconds the OS routing table is asked
+// for the source address it would use to reach the internet (a connected
+// UDP socket sends nothing); no route means offline, and a changed source
+// address means the machine moved networks and is probed right away.
+// Otherwise Supabase's health endpoint is probed every PROBE_INTERVAL while
+// online and every OFFLINE_PROBE_INTERVAL while offline; any HTTP answer
+// counts as online. The webview forwards the browser's online/offline
+// events through report_network_change so OS network changes are noticed
+// without waiting for the next check. Transitions are emitted as
+// `app:connectivity` and recorded on the activity timeline.
+
+use serde::Serialize;
+use serde_json::json;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{watch, Notify};
+
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{auth, CommandError};
+
+const ROUTE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Public resolvers used only to look up a route; nothing is sent to them
+const ROUTE_TARGETS: [&str; 2] = ["1.1.1.1:53", "[2606:4700:4700::1111]:53"];
+
+/// Current verdict, also the payload of app:connectivity
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    /// Whether the OS has a route to the internet at all
+    pub has_route: bool,
+    /// When `online` last changed, in milliseconds since epoch
+    pub since: u64,
+    pub last_checked: Option<u64>,
+    /// Why the app is offline
+    pub reason: Option<String>,
+}
+
+// Starts out online so nothing waits on the first check
+static STATUS: LazyLock<watch::Sender<ConnectivityStatus>> = LazyLock::new(|| {
+    watch::channel(ConnectivityStatus {
+        online: true,
+        has_route: true,
+        since: current_timestamp(),
+        last_checked: None,
+        reason: None,
+    })
+    .0
+});
+
+/// Wakes the monitor for an immediate probe
+static RECHECK: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+pub fn is_online() -> bool {
+    STATUS.borrow().online
+}
+
+pub fn status() -> ConnectivityStatus {
+    STATUS.borrow().clone()
+}
+
+/// Resolves once the app is online
+pub async fn wait_until_online() {
+    let mut receiver = STATUS.subscribe();
+    let _ = receiver.wait_for(|status| status.online).await;
+}
+
+/// Resolves once the app is offline
+pub async fn wait_until_offline() {
+    let mut receiver = STATUS.subscribe();
+    let _ = receiver.wait_for(|status| !status.online).await;
+}
+
+/// Fail with `offline` while there is no connection; `feature` names what
+/// was refused
+pub fn require_online(feature: &str) -> Result<(), CommandError> {
+    if is_online() {
+        return Ok(());
+    }
+    Err(
+        CommandError::offline(format!("{} needs a network connection", feature))
+            .with_details(json!({ "feature": feature })),
+    )
+}
+
+/// Source address the OS would use to reach the internet, if any
+fn route() -> Option<IpAddr> {
+    ROUTE_TARGETS.iter().find_map(|target| {
+        let target: SocketAddr = target.parse().ok()?;
+        let bind: SocketAddr = if target.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(bind).ok()?;
+        socket.connect(target).ok()?;
+        socket.local_addr().ok().map(|addr| addr.ip())
+    })
+}
+
+async fn probe() -> Result<(), String> {
+    let supabase_url = auth::get_supabase_url()?;
+    reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .get(format!("{}/auth/v1/health", supabase_url.trim_end_matches('/')))
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Supabase unreachable: {}", e))
+}
+
+/// Record a check, announcing a change of verdict
+fn update(app: &AppHandle, online: bool, has_route: bool, reason: Option<String>) {
+    let now = current_timestamp();
+    let changed = STATUS.send_if_modified(|status| {
+        let changed = status.online != online;
+        if changed {
+            status.since = now;
+        }
+        status.online = online;
+        status.has_route = has_route;
+        status.last_checked = Some(now);
+        status.reason = reason.clone();
+        changed
+    });
+    if !changed {
+        return;
+    }
+
+    let status = status();
+    if online {
+        log::info!("Network connection restored");
+        events::record(ServiceKind::Sync, "online", "Network connection restored", None);
+    } else {
+        let reason = reason.unwrap_or_else(|| "Network unavailable".to_string());
+        log::warn!("Offline: {}", reason);
+        events::record(ServiceKind::Sync, "offline", format!("Offline: {}", reason), None);
+    }
+    let _ = app.emit("app:connectivity", status);
+}
+
+/// Watch the connection for the lifetime of the app
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_route: Option<IpAddr> = None;
+        let mut next_probe = Instant::now();
+
+        loop {
+            let current = tauri::async_runtime::spawn_blocking(route).await.ok().flatten();
+            match current {
+                None => update(&app, false, false, Some("No network route".to_string())),
+                Some(addr) => {
+                    if last_route != Some(addr) || Instant::now() >= next_probe {
+                        let result = probe().await;
+                        let online = result.is_ok();
+                        update(&app, online, true, result.err());
+                        next_probe = Instant::now() + if online { PROBE_INTERVAL } else { OFFLINE_PROBE_INTERVAL };
+                    }
+                }
+            }
+            last_route = current;
+
+            tokio::select! {
+                _ = tokio::time::sleep(ROUTE_CHECK_INTERVAL) => {}
+                _ = RECHECK.notified() => next_probe = Instant::now(),
+            }
+        }
+    });
+}
+
+// Tauri commands
+
+/// Whether the app is online and when that last changed
+#[tauri::command]
+pub fn get_connectivity_status() -> Result<ConnectivityStatus, CommandError> {
+    Ok(status())
+}
+
+/// The webview saw the OS go online or offline; offline takes effect at
+/// once, and either way the connection is re-checked now
+#[tauri::command]
+pub fn report_network_change(app: AppHandle, online: bool) -> Result<(), CommandError> {
+    if !online {
+        update(&app, false, false, Some("The system reported no network connection".to_string()));
+    }
+    RECHECK.notify_one();
+    Ok(())
+}
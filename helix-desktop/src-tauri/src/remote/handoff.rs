@@ -0,0 +1,446 @@
+// Session handoff - "continue on other device"
+//
+// create_session_handoff reads the session's recent turns over the gateway
+// RPC (chat.history) and publishes them with a summary to the
+// session_handoffs table. The Realtime bridge hands new rows to `on_change`,
+// which emits `session:handoff` on the account's other devices. Nothing is
+// resumed there until the user accepts the offer; accepting is a
+// conditional update, so only one device can take a handoff and never after
+// it expires. Devices are identified by their user_instances instance_id,
+// which the frontend owns and passes in. The derived summary quotes the
+// last request up to SUMMARY_PREVIEW_TOKENS, counted with the shared
+// tokenizer like the context builder's budgets.
+
+use helix_shared::Tokenizer;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use super::realtime::RemoteChange;
+use crate::accounts::session;
+use crate::commands::{auth, CommandError, ErrorCode};
+use crate::gateway::client;
+use crate::privacy::residency::{self, DataFeature};
+
+const TABLE: &str = "session_handoffs";
+const DEFAULT_TURNS: usize = 20;
+const MAX_TURNS: usize = 50;
+/// Longer turns are cut to keep offers small
+const MAX_TURN_CHARS: usize = 4000;
+const SUMMARY_PREVIEW_TOKENS: usize = 70;
+const DEFAULT_TTL_MINUTES: i64 = 15;
+const MAX_TTL_MINUTES: i64 = 24 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Offers made from this app, so their own Realtime echo isn't shown
+static SENT: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// One message of the handed-off conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoffTurn {
+    /// "user" or "assistant"
+    pub role: String,
+    pub text: String,
+    #[serde(default)]
+    pub timestamp: Option<Value>,
+}
+
+/// A session_handoffs row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionHandoff {
+    pub id: String,
+    pub source_instance_id: String,
+    pub source_device_name: Option<String>,
+    /// None when any of the account's devices may accept
+    pub target_instance_id: Option<String>,
+    pub session_key: String,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub turns: Vec<HandoffTurn>,
+    /// "pending", "accepted", "declined" or "cancelled"
+    pub status: String,
+    pub responded_by: Option<String>,
+    pub expires_at: String,
+    pub created_at: Option<String>,
+}
+
+impl SessionHandoff {
+    fn is_expired(&self) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.expires_at)
+            .map(|at| at <= chrono::Utc::now())
+            .unwrap_or(true)
+    }
+}
+
+/// Payload for session:handoff event: everything but the turns, which are
+/// only handed over on accept
+#[derive(Debug, Clone, Serialize)]
+pub struct HandoffOffer {
+    pub id: String,
+    pub source_instance_id: String,
+    pub source_device_name: Option<String>,
+    pub target_instance_id: Option<String>,
+    pub session_key: String,
+    pub title: Option<String>,
+    pub summary: String,
+    pub turn_count: usize,
+    pub expires_at: String,
+}
+
+impl From<&SessionHandoff> for HandoffOffer {
+    fn from(handoff: &SessionHandoff) -> Self {
+        Self {
+            id: handoff.id.clone(),
+            source_instance_id: handoff.source_instance_id.clone(),
+            source_device_name: handoff.source_device_name.clone(),
+            target_instance_id: handoff.target_instance_id.clone(),
+            session_key: handoff.session_key.clone(),
+            title: handoff.title.clone(),
+            summary: handoff.summary.clone(),
+            turn_count: handoff.turns.len(),
+            expires_at: handoff.expires_at.clone(),
+        }
+    }
+}
+
+/// Payload for session:handoff-resolved event, so open prompts can close
+#[derive(Debug, Clone, Serialize)]
+struct HandoffResolved {
+    id: String,
+    status: String,
+    responded_by: Option<String>,
+}
+
+/// Arguments for create_session_handoff
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewHandoff {
+    pub session_key: String,
+    /// This device's instance_id
+    pub device_id: String,
+    pub device_name: Option<String>,
+    /// Offer to one device only
+    pub target_device_id: Option<String>,
+    /// Written by the user or the UI; derived from the session when absent
+    pub summary: Option<String>,
+    /// How many recent turns to include (default 20, at most 50)
+    pub turns: Option<usize>,
+    /// Minutes until the offer expires (default 15)
+    pub ttl_minutes: Option<i64>,
+}
+
+pub(crate) fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// `truncate`, in tokens of the shared tokenizer
+pub(crate) fn truncate_tokens(text: &str, max_tokens: usize) -> String {
+    let kept = Tokenizer::default().truncate(text, max_tokens);
+    if kept.len() < text.len() {
+        format!("{}…", kept.trim_end())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Plain text of a chat.history message: a string, or its text blocks
+fn message_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// The last `limit` user and assistant turns with text
+pub(crate) fn recent_turns(messages: &[Value], limit: usize) -> Vec<HandoffTurn> {
+    let mut turns: Vec<HandoffTurn> = messages
+        .iter()
+        .filter_map(|message| {
+            let role = message.get("role").and_then(Value::as_str)?;
+            if role != "user" && role != "assistant" {
+                return None;
+            }
+            let text = message_text(message.get("content")?);
+            let text = text.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(HandoffTurn {
+                role: role.to_string(),
+                text: truncate(text, MAX_TURN_CHARS),
+                timestamp: message.get("timestamp").cloned(),
+            })
+        })
+        .collect();
+    turns.drain(..turns.len().saturating_sub(limit));
+    turns
+}
+
+/// Summary for offers made without one: size and the latest request
+pub(crate) fn default_summary(title: Option<&str>, turns: &[HandoffTurn]) -> String {
+    let mut summary = match title {
+        Some(title) => format!("{} ({} recent turns)", title, turns.len()),
+        None => format!("{} recent turns", turns.len()),
+    };
+    if let Some(last) = turns.iter().rev().find(|t| t.role == "user") {
+        summary.push_str(&format!(". Last request: {}", truncate_tokens(&last.text, SUMMARY_PREVIEW_TOKENS)));
+    }
+    summary
+}
+
+/// Session title and recent turns, from the running gateway
+async fn read_session(session_key: &str, limit: usize) -> Result<(Option<String>, Vec<HandoffTurn>), CommandError> {
+    // chat.history counts tool calls and results too, so ask for more
+    let history = client::call("chat.history", json!({ "sessionKey": session_key, "limit": limit * 4 }))
+        .await
+        .map_err(|e| CommandError::unavailable(format!("Gateway is not reachable: {}", e)))?;
+    let messages = history
+        .get("messages")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let title = match client::call("sessions.list", json!({})).await {
+        Ok(list) => list
+            .get("sessions")
+            .and_then(Value::as_array)
+            .and_then(|sessions| {
+                sessions
+                    .iter()
+                    .find(|s| s.get("key").and_then(Value::as_str) == Some(session_key))
+            })
+            .and_then(|s| {
+                ["displayName", "derivedTitle"]
+                    .iter()
+                    .find_map(|field| s.get(*field).and_then(Value::as_str))
+            })
+            .map(str::to_string),
+        Err(e) => {
+            log::debug!("Session title unavailable: {}", e);
+            None
+        }
+    };
+
+    Ok((title, recent_turns(&messages, limit)))
+}
+
+fn active_user() -> Result<String, CommandError> {
+    crate::accounts::profiles::active_user_id()
+        .ok_or_else(|| CommandError::unauthenticated("Sign in to hand off sessions"))
+}
+
+/// A session_handoffs request as the signed-in user; row-level security
+/// keeps it to their own handoffs
+async fn rest(method: reqwest::Method, query: &[(&str, String)], body: Option<Value>) -> Result<Vec<SessionHandoff>, CommandError> {
+    residency::require(DataFeature::Sync)?;
+    super::connectivity::require_online("Session handoff")?;
+    let supabase_url = auth::get_supabase_url()?;
+
+    let request = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::internal(format!("Failed to create HTTP client: {}", e)))?
+        .request(method, format!("{}/rest/v1/{}", supabase_url, TABLE));
+    let (request, _) = session::authorize(request)
+        .await
+        .map_err(CommandError::unauthenticated)?;
+    let mut request = request
+        .header("Prefer", "return=representation")
+        .query(query);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CommandError::network(format!("Session handoff request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(CommandError::network(format!(
+            "Session handoff request failed: HTTP {}",
+            response.status()
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| CommandError::network(format!("Invalid session handoff response: {}", e)))
+}
+
+/// Move a pending, unexpired handoff to `status`; fails when another device
+/// got there first or it has expired. Only the offering device may cancel,
+/// and only the target (if any) may accept or decline.
+async fn respond(id: &str, device_id: &str, status: &str) -> Result<SessionHandoff, CommandError> {
+    let user_id = active_user()?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let mut query = vec![
+        ("id", format!("eq.{}", id)),
+        ("user_id", format!("eq.{}", user_id)),
+        ("status", "eq.pending".to_string()),
+        ("expires_at", format!("gt.{}", now)),
+    ];
+    if status == "cancelled" {
+        query.push(("source_instance_id", format!("eq.{}", device_id)));
+    } else {
+        query.push((
+            "or",
+            format!("(target_instance_id.is.null,target_instance_id.eq.{})", device_id),
+        ));
+    }
+
+    let updated = rest(
+        reqwest::Method::PATCH,
+        &query,
+        Some(json!({ "status": status, "responded_by": device_id, "responded_at": now })),
+    )
+    .await?;
+
+    updated.into_iter().next().ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::Conflict,
+            "This handoff has expired or was already answered on another device",
+        )
+    })
+}
+
+/// Emit handoff events for a change the Realtime bridge received
+pub fn on_change(app: &AppHandle, change: &RemoteChange) {
+    let Some(handoff) = change
+        .record
+        .as_ref()
+        .and_then(|record| serde_json::from_value::<SessionHandoff>(record.clone()).ok())
+    else {
+        return;
+    };
+
+    if handoff.status != "pending" {
+        let _ = app.emit(
+            "session:handoff-resolved",
+            HandoffResolved {
+                id: handoff.id,
+                status: handoff.status,
+                responded_by: handoff.responded_by,
+            },
+        );
+        return;
+    }
+
+    let own = SENT.lock().map(|sent| sent.contains(&handoff.id)).unwrap_or(false);
+    if change.change == "insert" && !own && !handoff.is_expired() {
+        let _ = app.emit("session:handoff", HandoffOffer::from(&handoff));
+    }
+}
+
+// Tauri commands
+
+/// Offer a session to the account's other devices
+#[tauri::command]
+pub async fn create_session_handoff(request: NewHandoff) -> Result<HandoffOffer, CommandError> {
+    let user_id = active_user()?;
+    if request.session_key.trim().is_empty() || request.device_id.trim().is_empty() {
+        return Err(CommandError::invalid_input("session_key and device_id are required"));
+    }
+    let ttl = request.ttl_minutes.unwrap_or(DEFAULT_TTL_MINUTES);
+    if !(1..=MAX_TTL_MINUTES).contains(&ttl) {
+        return Err(CommandError::invalid_input(format!(
+            "ttl_minutes must be between 1 and {}",
+            MAX_TTL_MINUTES
+        )));
+    }
+    let limit = request.turns.unwrap_or(DEFAULT_TURNS).clamp(1, MAX_TURNS);
+
+    let (title, turns) = read_session(&request.session_key, limit).await?;
+    if turns.is_empty() {
+        return Err(CommandError::not_found(format!(
+            "Session {} has no messages to hand off",
+            request.session_key
+        )));
+    }
+    let summary = request
+        .summary
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| default_summary(title.as_deref(), &turns));
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(ttl);
+
+    let created = rest(
+        reqwest::Method::POST,
+        &[],
+        Some(json!({
+            "user_id": user_id,
+            "source_instance_id": request.device_id,
+            "source_device_name": request.device_name,
+            "target_instance_id": request.target_device_id,
+            "session_key": request.session_key,
+            "title": title,
+            "summary": summary,
+            "turns": turns,
+            "expires_at": expires_at.to_rfc3339(),
+        })),
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| CommandError::network("Supabase did not return the created handoff"))?;
+
+    if let Ok(mut sent) = SENT.lock() {
+        sent.insert(created.id.clone());
+    }
+    Ok(HandoffOffer::from(&created))
+}
+
+/// Pending offers this device may accept, newest first
+#[tauri::command]
+pub async fn list_session_handoffs(device_id: String) -> Result<Vec<HandoffOffer>, CommandError> {
+    let user_id = active_user()?;
+    let pending = rest(
+        reqwest::Method::GET,
+        &[
+            ("select", "*".to_string()),
+            ("user_id", format!("eq.{}", user_id)),
+            ("status", "eq.pending".to_string()),
+            ("expires_at", format!("gt.{}", chrono::Utc::now().to_rfc3339())),
+            ("source_instance_id", format!("neq.{}", device_id)),
+            ("order", "created_at.desc".to_string()),
+        ],
+        None,
+    )
+    .await?;
+
+    Ok(pending
+        .iter()
+        .filter(|h| h.target_instance_id.as_ref().is_none_or(|target| *target == device_id))
+        .map(HandoffOffer::from)
+        .collect())
+}
+
+/// Take over a handed-off session; returns its summary and turns to resume from
+#[tauri::command]
+pub async fn accept_session_handoff(id: String, device_id: String) -> Result<SessionHandoff, CommandError> {
+    respond(&id, &device_id, "accepted").await
+}
+
+/// Turn down an offer, closing its prompt on every device
+#[tauri::command]
+pub async fn decline_session_handoff(id: String, device_id: String) -> Result<(), CommandError> {
+    respond(&id, &device_id, "declined").await.map(|_| ())
+}
+
+/// Withdraw an offer made from this device
+#[tauri::command]
+pub async fn cancel_session_handoff(id: String, device_id: String) -> Result<(), CommandError> {
+    respond(&id, &device_id, "cancelled").await.map(|_| ())
+}
@@ -0,0 +1,215 @@
+// Heartbeat publisher
+//
+// Every `discord.heartbeat_interval` ms this device reports that it is
+// alive: to Supabase (send_heartbeat, keeping the device online on the web
+// dashboard) once it has been registered, and as a health summary of the
+// app, the gateway and Supabase sync to the Discord heartbeat webhook
+// when one is set. During do-not-disturb the webhook posts pause, and the
+// Supabase beats slow down to `quiet_hours.heartbeat_interval`, so the
+// device still shows as online. Each wait is jittered by up to a tenth so
+// devices launched together don't beat in step.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::commands::discord::{self, WebhookEmbed, WebhookField, WebhookPayload};
+use crate::commands::{auth, config, keyring};
+use crate::gateway::monitor;
+use crate::policy::dnd;
+use crate::privacy::residency::{self, DataFeature};
+use crate::remote::{connectivity, realtime};
+
+/// This device's registration, in the account's data directory
+const DEVICE_FILE: &str = "device.json";
+/// Keyring entry holding the webhook when it isn't in config
+const WEBHOOK_SECRET: &str = "discord_webhook_heartbeat";
+/// Shortest interval honored, whatever the config says
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+/// Wait before the first beat, for the gateway and connectivity to settle
+const FIRST_BEAT_DELAY: Duration = Duration::from_secs(15);
+/// Largest share of an interval added or taken away at random
+const JITTER: f64 = 0.1;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static LAUNCHED: OnceLock<Instant> = OnceLock::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DeviceRecord {
+    device_id: String,
+}
+
+fn device_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::data_dir()?.join(DEVICE_FILE))
+}
+
+/// Remember the id this device registered with, for the Supabase beats
+pub fn remember_device(device_id: &str) {
+    let record = DeviceRecord {
+        device_id: device_id.to_string(),
+    };
+    let result = device_path().and_then(|path| {
+        let json = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save device registration: {}", e);
+    }
+}
+
+/// The id this device last registered with, if it still is
+pub fn device_id() -> Option<String> {
+    let content = fs::read_to_string(device_path().ok()?).ok()?;
+    let record: DeviceRecord = serde_json::from_str(&content).ok()?;
+    Some(record.device_id).filter(|id| !id.is_empty())
+}
+
+/// Stop beating for the registered device (retired or signed out)
+pub fn forget_device() {
+    if let Ok(path) = device_path() {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("Failed to forget device registration: {}", e);
+            }
+        }
+    }
+}
+
+fn webhook_url() -> Option<String> {
+    let discord = config::get_config().ok()?.discord;
+    if !discord.enabled {
+        return None;
+    }
+    discord
+        .webhooks
+        .heartbeat
+        .or_else(|| keyring::get_secret(WEBHOOK_SECRET.to_string()).ok().flatten())
+        .filter(|url| !url.trim().is_empty())
+}
+
+/// `interval` give or take up to a tenth
+fn jittered(interval: Duration) -> Duration {
+    interval.mul_f64(1.0 + rand::thread_rng().gen_range(-JITTER..=JITTER))
+}
+
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86400, s % 86400 / 3600),
+    }
+}
+
+async fn health_summary() -> WebhookPayload {
+    let app_uptime = LAUNCHED.get().map(|at| at.elapsed().as_secs()).unwrap_or(0);
+    let gateway = monitor::get_gateway_health_details().await.ok();
+    let healthy = gateway.as_ref().is_some_and(|g| g.healthy);
+
+    let gateway_text = match &gateway {
+        Some(g) if g.healthy => {
+            let mut parts = vec!["Healthy".to_string()];
+            if let Some(version) = &g.version {
+                parts.push(format!("v{}", version));
+            }
+            if let Some(uptime) = g.uptime_secs {
+                parts.push(format!("up {}", format_uptime(uptime)));
+            }
+            parts.push(format!("{} channels connected", g.connected_channels.len()));
+            parts.join(", ")
+        }
+        Some(g) => format!("Unhealthy: {}", g.error.as_deref().unwrap_or("not responding")),
+        None => "Unknown".to_string(),
+    };
+    let sync = if !residency::allows(DataFeature::Sync) {
+        "Off".to_string()
+    } else {
+        match realtime::get_realtime_status() {
+            Ok(status) if status.connected => "Connected".to_string(),
+            Ok(status) => match status.last_error {
+                Some(error) => format!("Disconnected: {}", error),
+                None => "Disconnected".to_string(),
+            },
+            Err(_) => "Unknown".to_string(),
+        }
+    };
+    let field = |name: &str, value: String| WebhookField {
+        name: name.to_string(),
+        value,
+        inline: Some(true),
+    };
+    let device = hostname::get()
+        .ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_else(|| "Desktop".to_string());
+
+    WebhookPayload {
+        content: None,
+        embeds: Some(vec![WebhookEmbed {
+            title: Some(format!("Helix heartbeat: {}", device)),
+            description: None,
+            // Green while the gateway answers, amber otherwise
+            color: Some(if healthy { 0x2ecc71 } else { 0xf39c12 }),
+            timestamp: Some(chrono::Utc::now().to_rfc3339()),
+            fields: Some(vec![
+                field(
+                    "App",
+                    format!("v{}, up {}", env!("CARGO_PKG_VERSION"), format_uptime(app_uptime)),
+                ),
+                field("Gateway", gateway_text),
+                field("Sync", sync),
+            ]),
+        }]),
+    }
+}
+
+async fn beat(dnd_active: bool) {
+    if let Some(device_id) = device_id() {
+        if residency::allows(DataFeature::Sync) && connectivity::is_online() {
+            match auth::send_heartbeat(device_id).await {
+                Ok(response) if !response.success => {
+                    log::debug!("Supabase heartbeat rejected: {}", response.error.unwrap_or_default())
+                }
+                Ok(_) => {}
+                Err(e) => log::debug!("Supabase heartbeat failed: {}", e.message),
+            }
+        }
+    }
+
+    if dnd_active || !connectivity::is_online() {
+        return;
+    }
+    if let Some(url) = webhook_url() {
+        if let Err(e) = discord::send_webhook(url, health_summary().await).await {
+            log::warn!("Heartbeat webhook failed: {}", e.message);
+        }
+    }
+}
+
+/// Publish heartbeats for the lifetime of the app (once per process)
+pub fn start() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    LAUNCHED.get_or_init(Instant::now);
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(jittered(FIRST_BEAT_DELAY)).await;
+        loop {
+            let status = tauri::async_runtime::spawn_blocking(dnd::status).await.ok();
+            let dnd_active = status.as_ref().is_some_and(|s| s.active);
+            beat(dnd_active).await;
+
+            // The DND policy picks the interval: the configured one, or the
+            // quiet-hours one while DND is active
+            let interval = status
+                .map(|s| Duration::from_millis(s.heartbeat_interval))
+                .unwrap_or(MIN_INTERVAL)
+                .max(MIN_INTERVAL);
+            tokio::time::sleep(jittered(interval)).await;
+        }
+    });
+}
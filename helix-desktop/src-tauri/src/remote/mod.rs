@@ -0,0 +1,10 @@
+// Helix Desktop - Remote Module (Supabase Realtime bridge, session handoff,
+// connectivity monitor, heartbeat publisher, cloud call retries, remote
+// sign-out)
+
+pub mod cloud_queue;
+pub mod connectivity;
+pub mod handoff;
+pub mod heartbeat;
+pub mod realtime;
+pub mod revocation;
@@ -0,0 +1,396 @@
+// Supabase Realtime bridge
+//
+// Subscribes to Postgres changes on the active account's rows in the
+// memories, subscriptions, user_instances and session_handoffs tables, so
+// edits made from the web dashboard or other devices reach the desktop
+// without a manual refresh. Each change is emitted as `remote:changed` after
+// the local state it affects is updated:
+//   - subscriptions / user_instances: the snapshot behind get_realtime_status
+//   - memories: the relationship graph, rebuilt once changes settle
+//   - session_handoffs: session:handoff prompts (see handoff.rs)
+// This device's own user_instances row being revoked signs it out (see
+// revocation.rs).
+// The connection follows account switches and reconnects with backoff; while
+// offline it waits for the connection to come back instead. It authenticates
+// as the signed-in user, with their access token refreshed as it runs, so
+// only rows they may read arrive.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::{connectivity, handoff, revocation};
+use crate::accounts::{session, tiers};
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::{auth, CommandError};
+use crate::privacy::residency::{self, DataFeature};
+use crate::psychology::graph;
+
+const TABLES: [&str; 4] = ["memories", "subscriptions", "user_instances", "session_handoffs"];
+const CHANNEL_TOPIC: &str = "realtime:helix-desktop";
+/// Phoenix drops sockets that miss heartbeats for 60s
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(25);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+/// Wait before retrying while signed out, unconfigured or with sync off
+const IDLE_DELAY: Duration = Duration::from_secs(60);
+/// Quiet period after the last memory change before the graph is rebuilt
+const GRAPH_DEBOUNCE: Duration = Duration::from_secs(60);
+
+/// Payload for remote:changed event
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteChange {
+    pub table: String,
+    /// "insert", "update" or "delete"
+    pub change: String,
+    pub record: Option<Value>,
+    pub old_record: Option<Value>,
+    pub commit_timestamp: Option<String>,
+}
+
+/// Bridge state and the cached rows it keeps current
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RealtimeStatus {
+    pub connected: bool,
+    pub user_id: Option<String>,
+    pub last_event_at: Option<String>,
+    pub last_error: Option<String>,
+    pub subscription_tier: Option<String>,
+    /// This account's devices, keyed by instance_id
+    pub instances: BTreeMap<String, Value>,
+}
+
+static STATUS: LazyLock<Mutex<RealtimeStatus>> = LazyLock::new(|| Mutex::new(RealtimeStatus::default()));
+
+/// How a session ended
+enum SessionEnd {
+    /// The active account changed; reconnect right away
+    AccountChanged,
+    Closed,
+}
+
+fn update_status(f: impl FnOnce(&mut RealtimeStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        f(&mut status);
+    }
+}
+
+fn realtime_url(supabase_url: &str, key: &str) -> String {
+    let base = supabase_url
+        .trim_end_matches('/')
+        .replacen("https://", "wss://", 1)
+        .replacen("http://", "ws://", 1);
+    format!("{}/realtime/v1/websocket?apikey={}&vsn=1.0.0", base, key)
+}
+
+async fn fetch_rows(table: &str, user_id: &str) -> Result<Vec<Value>, String> {
+    let supabase_url = auth::get_supabase_url()?;
+
+    let request = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .get(format!("{}/rest/v1/{}", supabase_url, table));
+    let (request, _) = session::authorize(request).await?;
+    let response = request
+        .query(&[("select", "*".to_string()), ("user_id", format!("eq.{}", user_id))])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", table, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {}: HTTP {}", table, response.status()));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {} response: {}", table, e))
+}
+
+/// Reload the cached rows, so changes missed while disconnected are picked up
+async fn refresh_cache(user_id: &str) -> Result<(), String> {
+    let subscriptions = fetch_rows("subscriptions", user_id).await?;
+    let instances = fetch_rows("user_instances", user_id).await?;
+
+    let tier = subscriptions
+        .first()
+        .and_then(|s| s.get("tier"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    tiers::reverify();
+
+    update_status(|status| {
+        status.subscription_tier = tier;
+        status.instances = instances
+            .into_iter()
+            .filter_map(|row| Some((row.get("instance_id")?.as_str()?.to_string(), row)))
+            .collect();
+    });
+    Ok(())
+}
+
+/// Apply a change to the cached rows
+fn apply_to_cache(change: &RemoteChange) {
+    update_status(|status| {
+        status.last_event_at = Some(chrono::Utc::now().to_rfc3339());
+        match change.table.as_str() {
+            "subscriptions" => {
+                status.subscription_tier = match change.change.as_str() {
+                    "delete" => None,
+                    _ => change
+                        .record
+                        .as_ref()
+                        .and_then(|r| r.get("tier"))
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                };
+                tiers::reverify();
+            }
+            "user_instances" => {
+                let row = if change.change == "delete" { &change.old_record } else { &change.record };
+                let Some(id) = row
+                    .as_ref()
+                    .and_then(|r| r.get("instance_id"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                else {
+                    return;
+                };
+                match (change.change.as_str(), &change.record) {
+                    ("delete", _) | (_, None) => {
+                        status.instances.remove(&id);
+                    }
+                    (_, Some(record)) => {
+                        status.instances.insert(id, record.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// A postgres_changes message as a RemoteChange
+fn parse_change(message: &Value) -> Option<RemoteChange> {
+    if message.get("event")?.as_str()? != "postgres_changes" {
+        return None;
+    }
+    let data = message.pointer("/payload/data")?;
+    let present = |key: &str| data.get(key).filter(|v| v.as_object().is_some_and(|o| !o.is_empty())).cloned();
+
+    Some(RemoteChange {
+        table: data.get("table")?.as_str()?.to_string(),
+        change: data.get("type")?.as_str()?.to_lowercase(),
+        record: present("record"),
+        old_record: present("old_record"),
+        commit_timestamp: data.get("commit_timestamp").and_then(Value::as_str).map(str::to_string),
+    })
+}
+
+fn join_message(user_id: &str, access_token: &str) -> Value {
+    let changes: Vec<Value> = TABLES
+        .iter()
+        .map(|table| {
+            json!({
+                "event": "*",
+                "schema": "public",
+                "table": table,
+                "filter": format!("user_id=eq.{}", user_id),
+            })
+        })
+        .collect();
+
+    json!({
+        "topic": CHANNEL_TOPIC,
+        "event": "phx_join",
+        "payload": {
+            "config": { "postgres_changes": changes },
+            "access_token": access_token,
+        },
+        "ref": "1",
+        "join_ref": "1",
+    })
+}
+
+/// Rebuild the relationship graph from memories created since its last run
+fn rebuild_graph() {
+    tauri::async_runtime::spawn(async {
+        match graph::update_from_memories().await {
+            Ok(count) => log::info!("Relationship graph updated from {} remote memories", count),
+            Err(e) => log::debug!("Relationship graph update skipped: {}", e.message),
+        }
+    });
+}
+
+/// One connection, until it closes or the account changes
+async fn run_session(app: &AppHandle, user_id: &str) -> Result<SessionEnd, String> {
+    let anon_key = auth::get_supabase_anon_key()?;
+    let supabase_url = auth::get_supabase_url()?;
+    let mut access_token = session::current().await?.access_token;
+
+    let (mut ws, _) = tokio::time::timeout(CONNECT_TIMEOUT, connect_async(realtime_url(&supabase_url, &anon_key)))
+        .await
+        .map_err(|_| "Timed out connecting to Supabase Realtime".to_string())?
+        .map_err(|e| format!("Failed to connect to Supabase Realtime: {}", e))?;
+
+    ws.send(Message::Text(join_message(user_id, &access_token).to_string()))
+        .await
+        .map_err(|e| format!("Failed to join realtime channel: {}", e))?;
+
+    match refresh_cache(user_id).await {
+        Ok(()) => revocation::check_cached(app),
+        Err(e) => log::warn!("Failed to refresh remote cache: {}", e),
+    }
+
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut next_ref = 1u64;
+    let mut memories_changed_at: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if crate::accounts::profiles::active_user_id().as_deref() != Some(user_id) {
+                    return Ok(SessionEnd::AccountChanged);
+                }
+                if memories_changed_at.is_some_and(|at| at.elapsed() >= GRAPH_DEBOUNCE) {
+                    memories_changed_at = None;
+                    rebuild_graph();
+                }
+
+                // The channel checks rows against the user's token; hand it
+                // the refreshed one before the old one expires
+                let current = session::current().await?;
+                if current.access_token != access_token {
+                    access_token = current.access_token;
+                    next_ref += 1;
+                    let update = json!({
+                        "topic": CHANNEL_TOPIC,
+                        "event": "access_token",
+                        "payload": { "access_token": access_token },
+                        "ref": next_ref.to_string(),
+                    });
+                    ws.send(Message::Text(update.to_string()))
+                        .await
+                        .map_err(|e| format!("Failed to update realtime token: {}", e))?;
+                }
+
+                next_ref += 1;
+                let beat = json!({ "topic": "phoenix", "event": "heartbeat", "payload": {}, "ref": next_ref.to_string() });
+                ws.send(Message::Text(beat.to_string()))
+                    .await
+                    .map_err(|e| format!("Realtime heartbeat failed: {}", e))?;
+            }
+            message = ws.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => return Ok(SessionEnd::Closed),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(format!("Realtime connection error: {}", e)),
+                };
+                let Ok(message) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+
+                if message.get("event").and_then(Value::as_str) == Some("phx_reply")
+                    && message.get("topic").and_then(Value::as_str) == Some(CHANNEL_TOPIC)
+                {
+                    if message.pointer("/payload/status").and_then(Value::as_str) != Some("ok") {
+                        return Err(format!("Realtime channel join rejected: {}", message["payload"]["response"]));
+                    }
+                    update_status(|status| {
+                        status.connected = true;
+                        status.user_id = Some(user_id.to_string());
+                        status.last_error = None;
+                    });
+                    events::record(ServiceKind::Sync, "realtime_connected", "Subscribed to remote changes", None);
+                    continue;
+                }
+
+                if let Some(change) = parse_change(&message) {
+                    apply_to_cache(&change);
+                    match change.table.as_str() {
+                        "memories" => memories_changed_at = Some(Instant::now()),
+                        "session_handoffs" => handoff::on_change(app, &change),
+                        "user_instances" => revocation::on_change(app, &change),
+                        _ => {}
+                    }
+                    let _ = app.emit("remote:changed", &change);
+                }
+            }
+        }
+    }
+}
+
+/// Keep the bridge connected for whichever account is active
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut delay = MIN_RECONNECT_DELAY;
+        loop {
+            let user_id = crate::accounts::profiles::active_user_id().filter(|_| residency::allows(DataFeature::Sync));
+            let Some(user_id) = user_id else {
+                tokio::time::sleep(IDLE_DELAY).await;
+                continue;
+            };
+            if !connectivity::is_online() {
+                connectivity::wait_until_online().await;
+                delay = MIN_RECONNECT_DELAY;
+                continue;
+            }
+
+            let result = run_session(&app, &user_id).await;
+            let was_connected = STATUS.lock().map(|s| s.connected).unwrap_or(false);
+            update_status(|status| {
+                status.connected = false;
+                if let Err(e) = &result {
+                    status.last_error = Some(e.clone());
+                }
+            });
+
+            match result {
+                Ok(SessionEnd::AccountChanged) => {
+                    update_status(|status| *status = RealtimeStatus::default());
+                    delay = MIN_RECONNECT_DELAY;
+                    continue;
+                }
+                Ok(SessionEnd::Closed) => {
+                    if was_connected {
+                        events::record(ServiceKind::Sync, "realtime_disconnected", "Remote change feed closed", None);
+                    }
+                    delay = MIN_RECONNECT_DELAY;
+                }
+                Err(e) => {
+                    log::debug!("Supabase Realtime unavailable: {}", e);
+                    if was_connected {
+                        events::record(
+                            ServiceKind::Sync,
+                            "realtime_disconnected",
+                            format!("Remote change feed lost: {}", e),
+                            None,
+                        );
+                    }
+                }
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    });
+}
+
+// Tauri commands
+
+/// Whether remote changes are arriving, plus the cached subscription and devices
+#[tauri::command]
+pub fn get_realtime_status() -> Result<RealtimeStatus, CommandError> {
+    STATUS
+        .lock()
+        .map(|status| status.clone())
+        .map_err(|e| CommandError::internal(e.to_string()))
+}
@@ -0,0 +1,121 @@
+// Remote sign-out - act on this device being revoked from elsewhere
+//
+// Another device or the web dashboard signs this desktop out by setting
+// revoked_at on its user_instances row (or deleting the row). The change
+// arrives through the Realtime bridge, or with the rows it reloads on
+// connecting when the app was closed at the time. The revoked device then
+// stops beating and drops its queued cloud calls, wipes the account's
+// cache directory (responses cached from cloud services) and signs out,
+// which deletes the stored Supabase session and so also ends the Realtime
+// session; `account:revoked` tells the frontend to drop its own session
+// and return to the sign-in screen.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+use super::realtime::{self, RemoteChange};
+use super::{cloud_queue, heartbeat};
+use crate::activity::events::{self, ServiceKind};
+use crate::privacy::wipe::{self, WipeScope};
+
+/// Set while a revocation is being carried out
+static REVOKING: AtomicBool = AtomicBool::new(false);
+
+/// Payload for account:revoked event
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountRevoked {
+    pub user_id: Option<String>,
+    pub device_id: String,
+    /// instance_id of the device that signed this one out, or "web"
+    pub revoked_by: Option<String>,
+    pub files_wiped: usize,
+}
+
+/// Whether `row` revokes this device, and by whom
+fn revoked_by(row: &Value, deleted: bool) -> Option<Option<String>> {
+    let device_id = heartbeat::device_id()?;
+    if row.get("instance_id").and_then(Value::as_str) != Some(device_id.as_str()) {
+        return None;
+    }
+    let revoked = deleted || row.get("revoked_at").is_some_and(|at| !at.is_null());
+    revoked.then(|| row.get("revoked_by").and_then(Value::as_str).map(str::to_string))
+}
+
+/// A user_instances change arrived over Realtime
+pub fn on_change(app: &AppHandle, change: &RemoteChange) {
+    let deleted = change.change == "delete";
+    let row = if deleted { &change.old_record } else { &change.record };
+    if let Some(by) = row.as_ref().and_then(|row| revoked_by(row, deleted)) {
+        revoke(app, by);
+    }
+}
+
+/// The device rows were reloaded; this one may have been revoked meanwhile
+pub fn check_cached(app: &AppHandle) {
+    let Ok(status) = realtime::get_realtime_status() else {
+        return;
+    };
+    let by = status.instances.values().find_map(|row| revoked_by(row, false));
+    if let Some(by) = by {
+        revoke(app, by);
+    }
+}
+
+fn revoke(app: &AppHandle, revoked_by: Option<String>) {
+    if REVOKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        sign_out_revoked(&app, revoked_by).await;
+        REVOKING.store(false, Ordering::SeqCst);
+    });
+}
+
+async fn sign_out_revoked(app: &AppHandle, revoked_by: Option<String>) {
+    let Some(device_id) = heartbeat::device_id() else {
+        return;
+    };
+    let user_id = crate::accounts::profiles::active_user_id();
+    log::warn!(
+        "This device was signed out remotely by {}",
+        revoked_by.as_deref().unwrap_or("another device")
+    );
+
+    heartbeat::forget_device();
+    cloud_queue::discard_device(&device_id);
+
+    // The cache directory is the account's, so wipe before signing out
+    let scope = WipeScope {
+        caches: true,
+        ..Default::default()
+    };
+    let files_wiped = match wipe::wipe(app, scope, None).await {
+        Ok(report) => report.files_wiped,
+        Err(e) => {
+            log::error!("Failed to wipe cloud caches of the revoked device: {}", e);
+            0
+        }
+    };
+    if let Err(e) = crate::accounts::profiles::sign_out(app).await {
+        log::error!("Failed to sign out the revoked device: {}", e);
+    }
+
+    events::record(
+        ServiceKind::Sync,
+        "device_revoked",
+        "Signed out remotely; cached cloud data wiped",
+        None,
+    );
+    let _ = app.emit(
+        "account:revoked",
+        AccountRevoked {
+            user_id,
+            device_id,
+            revoked_by,
+            files_wiped,
+        },
+    );
+}
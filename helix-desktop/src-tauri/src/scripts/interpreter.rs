@@ -0,0 +1,163 @@
+// Python interpreter discovery for the script runner
+//
+// Resolution order:
+// 1. `scripts.python_path` from config
+// 2. Portable Python bundled in the app resources ($RESOURCE/resources/python)
+// 3. Portable Python installed at ~/.helix/runtime/python
+// 4. python3 / python on PATH
+//
+// The portable layout is python-build-standalone's "install_only" archive:
+// python/bin/python3 on macOS and Linux, python/python.exe on Windows.
+// Release builds ship one, unpacked into resources/ by
+// scripts/bundle-python.js.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::process::Command;
+
+use crate::commands::config;
+
+/// Oldest Python the psychology scripts support (decay.py uses `X | None`)
+const MIN_VERSION: (u32, u32) = (3, 10);
+
+/// Resource directory, recorded at startup
+static RESOURCE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// Interpreter resolved by the last successful lookup
+static RESOLVED: Mutex<Option<Interpreter>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterpreterSource {
+    Configured,
+    Bundled,
+    Installed,
+    System,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Interpreter {
+    pub path: PathBuf,
+    pub version: String,
+    pub source: InterpreterSource,
+}
+
+pub fn init(resource_dir: Option<PathBuf>) {
+    if let Ok(mut dir) = RESOURCE_DIR.lock() {
+        *dir = resource_dir;
+    }
+}
+
+fn portable_binary(root: &Path) -> PathBuf {
+    if cfg!(windows) {
+        root.join("python.exe")
+    } else {
+        root.join("bin").join("python3")
+    }
+}
+
+fn path_candidates() -> Vec<PathBuf> {
+    let names: &[&str] = if cfg!(windows) {
+        &["python.exe", "python3.exe"]
+    } else {
+        &["python3", "python"]
+    };
+
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Parse "Python 3.11.4" into (3, 11)
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("Python ")?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Run `--version`; None if the binary is missing, broken or too old
+async fn probe(path: &Path, source: InterpreterSource) -> Option<Interpreter> {
+    let output = Command::new(path).arg("--version").output().await.ok()?;
+    // Python 2 printed its version to stderr
+    let text = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).to_string()
+    } else {
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let version = parse_version(&text)?;
+    if version < MIN_VERSION {
+        log::warn!("Ignoring {:?}: Python {}.{} is too old", path, version.0, version.1);
+        return None;
+    }
+
+    Some(Interpreter {
+        path: path.to_path_buf(),
+        version: text.trim().trim_start_matches("Python ").to_string(),
+        source,
+    })
+}
+
+fn candidates() -> Vec<(PathBuf, InterpreterSource)> {
+    let mut candidates = Vec::new();
+
+    if let Some(path) = config::get_config().ok().and_then(|c| c.scripts.python_path) {
+        candidates.push((PathBuf::from(path), InterpreterSource::Configured));
+    }
+    if let Some(dir) = RESOURCE_DIR.lock().ok().and_then(|d| d.clone()) {
+        // Bundled resources keep their path under src-tauri/, resources/ included
+        candidates.push((
+            portable_binary(&dir.join("resources").join("python")),
+            InterpreterSource::Bundled,
+        ));
+    }
+    if let Ok(root) = crate::accounts::helix_root() {
+        candidates.push((
+            portable_binary(&root.join("runtime").join("python")),
+            InterpreterSource::Installed,
+        ));
+    }
+    for path in path_candidates() {
+        candidates.push((path, InterpreterSource::System));
+    }
+
+    candidates
+}
+
+/// The interpreter scripts run with. Cached; `refresh` forces a new lookup
+/// (after changing `python_path` or installing a portable Python).
+pub async fn resolve(refresh: bool) -> Result<Interpreter, String> {
+    if !refresh {
+        if let Some(cached) = RESOLVED.lock().ok().and_then(|r| r.clone()) {
+            if cached.path.exists() {
+                return Ok(cached);
+            }
+        }
+    }
+
+    let mut found = None;
+    for (path, source) in candidates().into_iter().filter(|(path, _)| path.exists()) {
+        found = probe(&path, source).await;
+        if found.is_some() {
+            break;
+        }
+    }
+    let interpreter = found.ok_or_else(|| {
+        format!(
+            "No Python {}.{}+ found. Install Python, or place a portable Python at ~/.helix/runtime/python",
+            MIN_VERSION.0, MIN_VERSION.1
+        )
+    })?;
+
+    log::info!("Using Python {} ({:?})", interpreter.version, interpreter.source);
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        *resolved = Some(interpreter.clone());
+    }
+    Ok(interpreter)
+}
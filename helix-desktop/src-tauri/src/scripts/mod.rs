@@ -0,0 +1,6 @@
+// Helix Desktop - Script Runner Module (managed Python for psychology scripts)
+
+pub mod interpreter;
+pub mod runner;
+
+pub use runner::{PythonScript, ScriptOutput};
@@ -0,0 +1,188 @@
+// Managed Python script runner
+//
+// Scripts run with the resolved interpreter (see interpreter.rs) in the
+// account's data directory, with a cleared environment: only a short list
+// of system variables, the HELIX_* variables set by the caller and the
+// `scripts.env` entries from config are passed through. Python's own
+// PYTHON* variables and the user site-packages are ignored, and no
+// bytecode is written.
+//
+// Scripts that write psychology files take the lock named by HELIX_LOCK_FILE
+// (see psychology/lock.rs). Besides stdout/stderr, a script may write a JSON
+// result to the file named by HELIX_RESULT_FILE; it is returned as
+// `result`. `run_streaming` also hands stdout over while the script runs.
+// Work ported into the app (decay and synthesis, see psychology/) reports
+// through the same ScriptOutput, without an interpreter.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::interpreter::{self, Interpreter};
+use crate::commands::{config, CommandError};
+use crate::policy::isolation::{self, ProcessKind};
+use crate::psychology::lock;
+use crate::tasks::run_isolated_process;
+
+/// System variables scripts may see
+const PASSTHROUGH_ENV: &[&str] = &[
+    "PATH", "HOME", "USERPROFILE", "SYSTEMROOT", "TEMP", "TMP", "TMPDIR", "LANG", "LC_ALL", "TZ",
+];
+
+/// Structured outcome of a script run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptOutput {
+    pub script: String,
+    /// None when the work ran in-process
+    pub interpreter: Option<Interpreter>,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+    /// JSON the script wrote to HELIX_RESULT_FILE
+    pub result: Option<serde_json::Value>,
+}
+
+impl ScriptOutput {
+    /// Outcome of in-process work that stands in for `script`: its log as
+    /// stdout, and its report or error
+    pub fn native<T: Serialize>(script: &str, started: Instant, log: Vec<String>, outcome: Result<T, String>) -> Self {
+        let (result, stderr) = match outcome.and_then(|report| serde_json::to_value(report).map_err(|e| e.to_string())) {
+            Ok(report) => (Some(report), String::new()),
+            Err(e) => (None, e),
+        };
+        let mut stdout = log.join("\n");
+        stdout.push('\n');
+        Self {
+            script: script.to_string(),
+            interpreter: None,
+            exit_code: None,
+            success: result.is_some(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            stdout,
+            stderr,
+            result,
+        }
+    }
+
+    /// Ok with the structured output on success. A script that found the
+    /// psychology files locked fails as busy, anything else with its stderr.
+    pub fn into_result(self) -> Result<ScriptOutput, CommandError> {
+        if self.success {
+            Ok(self)
+        } else if self.exit_code == Some(lock::EXIT_LOCKED) {
+            Err(lock::busy())
+        } else if self.stderr.trim().is_empty() {
+            Err(CommandError::process(format!("{} exited with code {:?}", self.script, self.exit_code)))
+        } else {
+            Err(CommandError::process(self.stderr))
+        }
+    }
+}
+
+/// A Python script invocation
+pub struct PythonScript {
+    path: PathBuf,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+}
+
+impl PythonScript {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            args: Vec::new(),
+            env: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    fn command(&self, interpreter: &Interpreter, result_file: &Path) -> Result<tokio::process::Command, String> {
+        let helix_dir = crate::accounts::data_dir()?;
+        let configured_env = config::get_config().map(|c| c.scripts.env).unwrap_or_default();
+
+        let mut cmd = tokio::process::Command::from(
+            isolation::command(ProcessKind::Scripts, &interpreter.path, &[]).map_err(|e| e.message)?,
+        );
+        // -E: ignore PYTHON* variables, -s: no user site-packages, -B: no
+        // bytecode next to the trusted scripts (see workspace_trust.rs)
+        cmd.args(["-E", "-s", "-B", "-X", "utf8"])
+            .arg(&self.path)
+            .args(&self.args)
+            .current_dir(&helix_dir)
+            .env_clear();
+
+        for key in PASSTHROUGH_ENV {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
+        }
+        cmd.envs(configured_env)
+            .envs(self.env.iter().cloned())
+            .env("HELIX_DIR", &helix_dir)
+            .env("HELIX_LOCK_FILE", lock::lock_path(&helix_dir))
+            .env("HELIX_RESULT_FILE", result_file);
+
+        Ok(cmd)
+    }
+
+    /// Run to completion. Killed if the calling task is cancelled.
+    pub async fn run(self) -> Result<ScriptOutput, String> {
+        self.run_streaming(|_| {}).await
+    }
+
+    /// `run`, handing stdout to `on_stdout` as the script writes it
+    pub async fn run_streaming(self, on_stdout: impl FnMut(&str)) -> Result<ScriptOutput, String> {
+        if !self.path.exists() {
+            return Err(format!("Script not found: {}", self.path.display()));
+        }
+
+        let interpreter = interpreter::resolve(false).await?;
+        let result_file = std::env::temp_dir().join(format!("helix-result-{:016x}.json", rand::random::<u64>()));
+        let cmd = self.command(&interpreter, &result_file)?;
+
+        let started = Instant::now();
+        let output = run_isolated_process(cmd, ProcessKind::Scripts, on_stdout)
+            .await
+            .map_err(|e| format!("Failed to run {}: {}", self.path.display(), e));
+        let result = tokio::fs::read_to_string(&result_file)
+            .await
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok());
+        let _ = tokio::fs::remove_file(&result_file).await;
+        let output = output?;
+
+        Ok(ScriptOutput {
+            script: self
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            interpreter: Some(interpreter),
+            exit_code: output.status.code(),
+            success: output.status.success(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            result,
+        })
+    }
+}
+
+// Tauri commands for the script runner
+
+/// The interpreter scripts would run with; `refresh` re-runs discovery
+#[tauri::command]
+pub async fn get_python_runtime(refresh: Option<bool>) -> Result<Interpreter, CommandError> {
+    interpreter::resolve(refresh.unwrap_or(false)).await.map_err(CommandError::process)
+}
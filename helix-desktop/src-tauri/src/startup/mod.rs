@@ -0,0 +1,6 @@
+// Helix Desktop - Startup Module
+
+pub mod orphans;
+pub mod safe_mode;
+
+pub use safe_mode::Component;
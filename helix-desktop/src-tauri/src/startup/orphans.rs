@@ -0,0 +1,145 @@
+// Orphan processes - gateways and helpers left behind by a crashed run
+//
+// A crash takes the app down without stopping what it spawned, and those
+// processes go on holding their ports. Their pid files (helix_shared::pidfile)
+// outlive the crash, so at startup every file the app recorded for a
+// child whose process is still the one recorded belongs to a previous
+// run. Files of processes that are gone are dropped, and so are those a
+// process holds for itself (the helix-rust binaries started from a shell),
+// which aren't the app's to stop.
+//
+// `reconcile` runs before anything is spawned. A gateway still listening on
+// its recorded port is adopted, so the app reports and stops it like one it
+// started; any other survivor is terminated (SIGTERM, then SIGKILL after a
+// grace period), since nothing could stop or supervise it otherwise.
+
+use helix_shared::pidfile::{self, PidRecord};
+use std::time::{Duration, Instant};
+
+use crate::activity::events::{self, ServiceKind};
+use crate::commands::gateway;
+
+/// Time an orphan gets to exit on SIGTERM before it is killed
+const TERMINATE_GRACE: Duration = Duration::from_secs(3);
+
+/// Stop the recorded process, checking before each signal that its pid
+/// still belongs to it. Returns whether it is gone.
+#[cfg(unix)]
+pub(crate) fn terminate(record: &PidRecord) -> bool {
+    // SAFETY: kill(2) on a pid just checked to be the recorded process
+    if !record.is_alive() || unsafe { libc::kill(record.pid as libc::pid_t, libc::SIGTERM) } != 0 {
+        return !record.is_alive();
+    }
+    if wait_for_exit(record) {
+        return true;
+    }
+    if record.is_alive() {
+        // SAFETY: as above
+        unsafe { libc::kill(record.pid as libc::pid_t, libc::SIGKILL) };
+    }
+    wait_for_exit(record)
+}
+
+#[cfg(windows)]
+pub(crate) fn terminate(record: &PidRecord) -> bool {
+    if record.is_alive() {
+        windows::terminate(record.pid);
+    }
+    wait_for_exit(record)
+}
+
+/// Wait up to TERMINATE_GRACE for the recorded process to go away
+fn wait_for_exit(record: &PidRecord) -> bool {
+    let deadline = Instant::now() + TERMINATE_GRACE;
+    while record.is_alive() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    true
+}
+
+/// Adopt or terminate the processes left by a previous run. Called from
+/// setup before the gateway is started.
+pub fn reconcile() {
+    let remote = gateway::remote_url().ok().flatten().is_some();
+    let this_run = std::process::id();
+    for record in pidfile::records() {
+        if !record.is_alive() {
+            log::debug!("Dropping stale pid file of {} ({})", record.name, record.pid);
+            pidfile::remove(&record);
+            continue;
+        }
+        if record.parent.is_none_or(|parent| parent == this_run) {
+            continue;
+        }
+
+        let instance = gateway::pidfile_instance(&record.name);
+        let listening = record.port.is_some_and(|port| std::net::TcpListener::bind(("127.0.0.1", port)).is_err());
+        let adoptable = match instance {
+            // A remote default gateway replaces the local one
+            Some(gateway::DEFAULT_INSTANCE) => listening && !remote,
+            Some(_) => listening,
+            None => false,
+        };
+        if let (Some(instance), true) = (instance, adoptable) {
+            let (pid, port) = (record.pid, record.port.unwrap_or_default());
+            match gateway::adopt(instance, record.clone()) {
+                Ok(()) => {
+                    log::info!("Adopted {} ({}) on port {}", gateway::display_name(instance), pid, port);
+                    events::record(
+                        ServiceKind::Gateway,
+                        "adopted",
+                        format!("{} left by the previous run was adopted on port {}", gateway::display_name(instance), port),
+                        Some(serde_json::json!({ "instance": instance, "pid": pid, "port": port })),
+                    );
+                    continue;
+                }
+                Err(e) => log::warn!("Could not adopt {}: {}", gateway::display_name(instance), e),
+            }
+        }
+
+        log::warn!("Terminating {} ({}) left by the previous run", record.name, record.pid);
+        if terminate(&record) {
+            pidfile::remove(&record);
+            if let Some(instance) = instance {
+                events::record(
+                    ServiceKind::Gateway,
+                    "orphan_terminated",
+                    format!("Stopped {} left by the previous run", gateway::display_name(instance)),
+                    Some(serde_json::json!({ "instance": instance, "pid": record.pid, "port": record.port })),
+                );
+            }
+        } else {
+            log::warn!("{} ({}) left by the previous run could not be stopped", record.name, record.pid);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+
+    type Handle = *mut c_void;
+
+    const PROCESS_TERMINATE: u32 = 0x0001;
+
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> Handle;
+        fn TerminateProcess(process: Handle, code: u32) -> i32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    /// There is no SIGTERM for a console process; it is ended outright
+    pub fn terminate(pid: u32) {
+        // SAFETY: plain Win32 calls on a handle this function owns
+        unsafe {
+            let process = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if !process.is_null() {
+                TerminateProcess(process, 1);
+                CloseHandle(process);
+            }
+        }
+    }
+}
@@ -0,0 +1,331 @@
+// Safe mode - crash-loop detection for app startup
+//
+// Every launch is recorded in ~/.helix/startup-state.json before components
+// start. A launch that neither stays up for STABLE_AFTER_SECS nor exits
+// cleanly counts as a crash, blamed on the component that was starting last.
+// After CRASH_LOOP_THRESHOLD consecutive crashes the app starts in safe mode:
+// gateway auto-start, the gateway monitor, the config watcher and the
+// scheduler stay off until re-enabled one by one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::commands::CommandError;
+
+/// Consecutive crashed launches before safe mode kicks in
+const CRASH_LOOP_THRESHOLD: u32 = 3;
+/// Uptime after which a launch counts as successful
+const STABLE_AFTER_SECS: u64 = 60;
+/// Startup state file name within ~/.helix
+const STATE_FILENAME: &str = "startup-state.json";
+
+/// Components safe mode can hold back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Component {
+    Gateway,
+    GatewayMonitor,
+    ConfigWatcher,
+    Scheduler,
+    LocalApi,
+}
+
+impl Component {
+    pub const ALL: [Component; 5] = [
+        Component::Gateway,
+        Component::GatewayMonitor,
+        Component::ConfigWatcher,
+        Component::Scheduler,
+        Component::LocalApi,
+    ];
+}
+
+/// Persisted record of recent launches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StartupState {
+    /// Set while a launch has not yet proven stable
+    #[serde(default)]
+    launch_in_progress: bool,
+    /// Component that was starting most recently
+    #[serde(default)]
+    current_component: Option<Component>,
+    #[serde(default)]
+    consecutive_crashes: u32,
+    /// Crashes blamed on each component since the last stable launch
+    #[serde(default)]
+    failures: HashMap<Component, u32>,
+    /// Message of the last panic, if any
+    #[serde(default)]
+    last_panic: Option<String>,
+}
+
+/// Safe mode status reported to the frontend
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SafeModeStatus {
+    pub active: bool,
+    /// Whether the launch before this one crashed
+    pub previous_launch_crashed: bool,
+    pub failing_component: Option<Component>,
+    pub consecutive_crashes: u32,
+    pub disabled: BTreeSet<Component>,
+    pub last_panic: Option<String>,
+}
+
+/// Current launch's safe mode status
+static STATUS: Mutex<Option<SafeModeStatus>> = Mutex::new(None);
+
+fn get_helix_dir() -> Result<PathBuf, String> {
+    if let Ok(dir) = std::env::var("HELIX_PROJECT_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    crate::accounts::profiles::helix_root()
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(get_helix_dir()?.join(STATE_FILENAME))
+}
+
+fn load_state() -> StartupState {
+    state_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &StartupState) {
+    let result = state_path().and_then(|path| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| e.to_string())
+    });
+
+    if let Err(e) = result {
+        log::warn!("Failed to save startup state: {}", e);
+    }
+}
+
+fn update_state(f: impl FnOnce(&mut StartupState)) {
+    let mut state = load_state();
+    f(&mut state);
+    save_state(&state);
+}
+
+/// Record the start of a launch and decide whether to enter safe mode.
+/// Must run before any component starts.
+pub fn begin_launch() -> SafeModeStatus {
+    let mut state = load_state();
+    let previous_launch_crashed = state.launch_in_progress;
+
+    if previous_launch_crashed {
+        // The previous launch never became stable or exited cleanly
+        state.consecutive_crashes += 1;
+        if let Some(component) = state.current_component {
+            *state.failures.entry(component).or_insert(0) += 1;
+        }
+        log::warn!(
+            "Previous launch did not finish cleanly ({} in a row, last component: {:?})",
+            state.consecutive_crashes,
+            state.current_component
+        );
+    }
+
+    let active = state.consecutive_crashes >= CRASH_LOOP_THRESHOLD;
+    let failing_component = state
+        .failures
+        .iter()
+        .max_by_key(|(_, count)| **count)
+        .map(|(component, _)| *component);
+
+    state.launch_in_progress = true;
+    state.current_component = None;
+    save_state(&state);
+
+    let status = SafeModeStatus {
+        active,
+        previous_launch_crashed,
+        failing_component,
+        consecutive_crashes: state.consecutive_crashes,
+        disabled: if active {
+            Component::ALL.into_iter().collect()
+        } else {
+            BTreeSet::new()
+        },
+        last_panic: state.last_panic.clone(),
+    };
+
+    if active {
+        log::warn!(
+            "Entering safe mode after {} crashed launches (failing component: {:?})",
+            status.consecutive_crashes,
+            status.failing_component
+        );
+    }
+
+    if let Ok(mut current) = STATUS.lock() {
+        *current = Some(status.clone());
+    }
+
+    install_panic_hook();
+    status
+}
+
+/// Record a panic so the next launch can report it
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.to_string();
+        update_state(|state| state.last_panic = Some(message));
+        default_hook(info);
+    }));
+}
+
+/// Note that `component` is about to start
+pub fn enter(component: Component) {
+    update_state(|state| state.current_component = Some(component));
+}
+
+/// Whether `component` may run in this launch
+pub fn is_enabled(component: Component) -> bool {
+    STATUS
+        .lock()
+        .ok()
+        .and_then(|status| status.as_ref().map(|s| !s.disabled.contains(&component)))
+        .unwrap_or(true)
+}
+
+/// Mark the launch successful once the app has stayed up for a while
+pub fn spawn_stability_timer() {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(STABLE_AFTER_SECS)).await;
+        mark_stable();
+    });
+}
+
+/// Clear crash counters; the launch is healthy.
+/// A launch with components still held back proves nothing about them,
+/// so their crash history is kept.
+fn mark_stable() {
+    let fully_enabled = Component::ALL.iter().all(|c| is_enabled(*c));
+
+    update_state(|state| {
+        state.launch_in_progress = false;
+        state.current_component = None;
+        if fully_enabled {
+            state.consecutive_crashes = 0;
+            state.failures.clear();
+        }
+    });
+    log::info!("Launch marked stable");
+}
+
+/// Record a clean shutdown so it isn't mistaken for a crash
+pub fn mark_clean_exit() {
+    update_state(|state| {
+        state.launch_in_progress = false;
+        state.current_component = None;
+    });
+}
+
+/// Emit the safe mode event if this launch is in safe mode
+pub fn notify_frontend(app: &AppHandle, status: &SafeModeStatus) {
+    if status.active {
+        let _ = app.emit("startup:safe-mode", status.clone());
+    }
+}
+
+/// Start a component that safe mode held back
+async fn start_component(app: &AppHandle, component: Component) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+
+    match component {
+        Component::Gateway => {
+            crate::commands::gateway::auto_start_gateway(app)?;
+        }
+        Component::GatewayMonitor => {
+            state.gateway_monitor.read().await.start(app.clone());
+        }
+        Component::ConfigWatcher => {
+            state.config_watcher.write().await.start(app.clone())?;
+        }
+        Component::Scheduler => {
+            // Scheduler commands check `is_enabled` on each call
+        }
+        Component::LocalApi => {
+            crate::api::server::auto_start(app);
+        }
+    }
+
+    Ok(())
+}
+
+// Tauri commands for safe mode
+
+/// Get the safe mode status of this launch
+#[tauri::command]
+pub fn get_safe_mode_status() -> Result<SafeModeStatus, CommandError> {
+    let status = STATUS.lock().map_err(|e| e.to_string())?;
+    Ok(status.clone().unwrap_or_default())
+}
+
+/// Re-enable and start a single component held back by safe mode
+#[tauri::command]
+pub async fn enable_component(app: AppHandle, component: Component) -> Result<SafeModeStatus, CommandError> {
+    {
+        let mut status = STATUS.lock().map_err(|e| e.to_string())?;
+        if let Some(s) = status.as_mut() {
+            if !s.disabled.remove(&component) {
+                return Err(CommandError::invalid_input(format!("{:?} is not disabled", component)));
+            }
+        }
+    }
+
+    log::info!("Re-enabling {:?} from safe mode", component);
+    enter(component);
+    start_component(&app, component).await?;
+
+    let status = get_safe_mode_status()?;
+    let _ = app.emit("startup:component-enabled", status.clone());
+    Ok(status)
+}
+
+/// Leave safe mode: reset crash history and start every held-back component
+#[tauri::command]
+pub async fn exit_safe_mode(app: AppHandle) -> Result<SafeModeStatus, CommandError> {
+    let disabled: Vec<Component> = {
+        let status = STATUS.lock().map_err(|e| e.to_string())?;
+        status
+            .as_ref()
+            .map(|s| s.disabled.iter().copied().collect())
+            .unwrap_or_default()
+    };
+
+    update_state(|state| {
+        state.consecutive_crashes = 0;
+        state.failures.clear();
+        state.last_panic = None;
+    });
+
+    for component in disabled {
+        enable_component(app.clone(), component).await?;
+    }
+
+    {
+        let mut status = STATUS.lock().map_err(|e| e.to_string())?;
+        if let Some(s) = status.as_mut() {
+            s.active = false;
+            s.consecutive_crashes = 0;
+            s.failing_component = None;
+            s.last_panic = None;
+        }
+    }
+
+    get_safe_mode_status()
+}
@@ -0,0 +1,419 @@
+// Task manager - long-running commands with timeouts and cancellation
+//
+// Long commands register a task, return its id immediately and report the
+// outcome through `task:*` events. Dropping a task's future kills any child
+// process it spawned, so cancellation and timeouts clean up the process too.
+//
+// Progress protocol: a task body calls `TaskHandle::progress`, which emits
+// `task:progress` with { task_id, kind, phase, percent, message, timestamp }.
+// `percent` is 0-100, or null when the phase has no measurable progress.
+// The latest progress is also kept on the task for `list_active_tasks`.
+//
+// A failed task's `task:finished` carries its error code next to the
+// message, so a `busy` failure can be told apart and retried.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::process::Output;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+use crate::commands::{CommandError, ErrorCode};
+use crate::policy::isolation::{self, ProcessKind};
+
+/// Lifecycle state of a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+    TimedOut,
+}
+
+/// Snapshot of a registered task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: String,
+    pub status: TaskStatus,
+    pub started_at: u64,
+    pub timeout_secs: Option<u64>,
+    pub phase: Option<String>,
+    pub percent: Option<f32>,
+    pub message: Option<String>,
+}
+
+/// Payload for task:progress event
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskProgressEvent {
+    pub task_id: String,
+    pub kind: String,
+    pub phase: String,
+    pub percent: Option<f32>,
+    pub message: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Payload for task:finished event
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFinishedEvent {
+    pub id: String,
+    pub kind: String,
+    pub status: TaskStatus,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+    pub timestamp: u64,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    cancel_tx: watch::Sender<bool>,
+}
+
+/// Handle passed to a task's body
+#[derive(Clone)]
+pub struct TaskHandle {
+    id: String,
+    kind: String,
+    app: AppHandle,
+    manager: Arc<TaskManager>,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Report progress; `percent` is clamped to 0-100
+    pub fn progress(&self, phase: &str, percent: Option<f32>, message: Option<String>) {
+        let percent = percent.map(|p| p.clamp(0.0, 100.0));
+        self.manager.record_progress(&self.id, phase, percent, message.clone());
+
+        let _ = self.app.emit("task:progress", TaskProgressEvent {
+            task_id: self.id.clone(),
+            kind: self.kind.clone(),
+            phase: phase.to_string(),
+            percent,
+            message,
+            timestamp: current_timestamp(),
+        });
+    }
+}
+
+/// Registry of running background tasks
+pub struct TaskManager {
+    tasks: Mutex<HashMap<String, TaskEntry>>,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskManager {
+    /// Create an empty task manager
+    pub fn new() -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `body` in the background and return the new task id.
+    ///
+    /// The task ends when the body finishes, `timeout` elapses or the task
+    /// is cancelled; the last two drop the body's future.
+    pub fn spawn<F, Fut, E>(
+        self: &Arc<Self>,
+        app: AppHandle,
+        kind: &str,
+        timeout: Option<Duration>,
+        body: F,
+    ) -> Result<String, String>
+    where
+        F: FnOnce(TaskHandle) -> Fut,
+        Fut: Future<Output = Result<String, E>> + Send + 'static,
+        E: Into<CommandError> + 'static,
+    {
+        let (handle, cancel_rx) = self.register(app, kind, timeout)?;
+        let id = handle.id.clone();
+        let future = body(handle.clone());
+
+        tauri::async_runtime::spawn(Self::drive(handle, timeout, cancel_rx, future));
+
+        Ok(id)
+    }
+
+    /// Run `body` as a tracked task and wait for its result.
+    ///
+    /// For commands whose callers expect the result directly but should still
+    /// show up in `list_active_tasks` and be cancellable.
+    pub async fn run<F, Fut, E>(
+        self: &Arc<Self>,
+        app: AppHandle,
+        kind: &str,
+        timeout: Option<Duration>,
+        body: F,
+    ) -> Result<String, CommandError>
+    where
+        F: FnOnce(TaskHandle) -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+        E: Into<CommandError>,
+    {
+        let (handle, cancel_rx) = self.register(app, kind, timeout)?;
+        let future = body(handle.clone());
+
+        Self::drive(handle, timeout, cancel_rx, future).await
+    }
+
+    /// Register a new running task
+    fn register(
+        self: &Arc<Self>,
+        app: AppHandle,
+        kind: &str,
+        timeout: Option<Duration>,
+    ) -> Result<(TaskHandle, watch::Receiver<bool>), String> {
+        let id = generate_task_id();
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+
+        let info = TaskInfo {
+            id: id.clone(),
+            kind: kind.to_string(),
+            status: TaskStatus::Running,
+            started_at: current_timestamp(),
+            timeout_secs: timeout.map(|t| t.as_secs()),
+            phase: None,
+            percent: None,
+            message: None,
+        };
+
+        {
+            let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+            tasks.insert(id.clone(), TaskEntry { info: info.clone(), cancel_tx });
+        }
+
+        let _ = app.emit("task:started", info);
+        crate::telemetry::record_feature(kind);
+
+        let handle = TaskHandle {
+            id,
+            kind: kind.to_string(),
+            app,
+            manager: Arc::clone(self),
+        };
+        Ok((handle, cancel_rx))
+    }
+
+    /// Drive a task's future until it finishes, times out or is cancelled
+    async fn drive<Fut, E>(
+        handle: TaskHandle,
+        timeout: Option<Duration>,
+        mut cancel_rx: watch::Receiver<bool>,
+        future: Fut,
+    ) -> Result<String, CommandError>
+    where
+        Fut: Future<Output = Result<String, E>>,
+        E: Into<CommandError>,
+    {
+        let deadline = async {
+            match timeout {
+                Some(t) => tokio::time::sleep(t).await,
+                None => std::future::pending::<()>().await,
+            }
+        };
+        let cancelled = async {
+            while !*cancel_rx.borrow() {
+                if cancel_rx.changed().await.is_err() {
+                    // Sender dropped without cancelling; never resolve
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+
+        let (status, result) = tokio::select! {
+            result = future => match result {
+                Ok(output) => (TaskStatus::Completed, Ok(output)),
+                Err(e) => (TaskStatus::Failed, Err(e.into())),
+            },
+            _ = cancelled => (TaskStatus::Cancelled, Err(CommandError::internal("Task cancelled"))),
+            _ = deadline => (
+                TaskStatus::TimedOut,
+                Err(CommandError::new(
+                    ErrorCode::Timeout,
+                    format!("Task timed out after {}s", timeout.map(|t| t.as_secs()).unwrap_or(0)),
+                )),
+            ),
+        };
+
+        handle.manager.finish(&handle.id);
+        log::info!("Task {} ({}) finished: {:?}", handle.id, handle.kind, status);
+
+        let _ = handle.app.emit("task:finished", TaskFinishedEvent {
+            id: handle.id.clone(),
+            kind: handle.kind.clone(),
+            status,
+            output: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.message.clone()),
+            error_code: result.as_ref().err().map(|e| e.code),
+            timestamp: current_timestamp(),
+        });
+
+        result
+    }
+
+    /// Request cancellation of a running task
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let entry = tasks
+            .get(id)
+            .ok_or_else(|| format!("Task not found: {}", id))?;
+
+        entry
+            .cancel_tx
+            .send(true)
+            .map_err(|_| format!("Task {} already finished", id))
+    }
+
+    /// Whether a task of the given kind is still running
+    pub fn is_running(&self, kind: &str) -> bool {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.values().any(|t| t.info.kind == kind))
+            .unwrap_or(false)
+    }
+
+    /// Snapshot of all running tasks, oldest first
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let mut list: Vec<TaskInfo> = self
+            .tasks
+            .lock()
+            .map(|tasks| tasks.values().map(|t| t.info.clone()).collect())
+            .unwrap_or_default();
+        list.sort_by_key(|t| t.started_at);
+        list
+    }
+
+    fn record_progress(&self, id: &str, phase: &str, percent: Option<f32>, message: Option<String>) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            if let Some(entry) = tasks.get_mut(id) {
+                entry.info.phase = Some(phase.to_string());
+                entry.info.percent = percent;
+                entry.info.message = message;
+            }
+        }
+    }
+
+    fn finish(&self, id: &str) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.remove(id);
+        }
+    }
+}
+
+/// Run a process to completion; the process is killed if the task is dropped
+pub async fn run_process(mut cmd: tokio::process::Command) -> std::io::Result<Output> {
+    cmd.kill_on_drop(true)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+}
+
+/// Most stdout held back waiting for the end of a line
+const STREAM_LINE_LIMIT: usize = 16 * 1024;
+
+/// `run_process` for a command from `isolation::command`, applying the
+/// limits that need the running process. Stdout is handed to `on_stdout`
+/// as it arrives, in chunks that end at a line break unless a line
+/// outgrows STREAM_LINE_LIMIT; the complete stdout is still in the output.
+pub async fn run_isolated_process<F>(
+    mut cmd: tokio::process::Command,
+    kind: ProcessKind,
+    mut on_stdout: F,
+) -> std::io::Result<Output>
+where
+    F: FnMut(&str),
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut child = cmd
+        .kill_on_drop(true)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    // Killed on drop if it couldn't be confined
+    isolation::attach(kind, child.id()).map_err(|e| std::io::Error::other(e.message))?;
+    let mut stdout_pipe = child.stdout.take().ok_or_else(|| std::io::Error::other("stdout is not piped"))?;
+    let mut stderr_pipe = child.stderr.take().ok_or_else(|| std::io::Error::other("stderr is not piped"))?;
+
+    let read_stdout = async {
+        let mut stdout = Vec::new();
+        // Start of the bytes not yet handed to on_stdout
+        let mut pending = 0;
+        let mut buf = [0u8; 8192];
+        loop {
+            let read = stdout_pipe.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            stdout.extend_from_slice(&buf[..read]);
+            let end = match stdout[pending..].iter().rposition(|byte| *byte == b'\n') {
+                Some(newline) => pending + newline + 1,
+                None if stdout.len() - pending >= STREAM_LINE_LIMIT => stdout.len(),
+                None => continue,
+            };
+            on_stdout(&String::from_utf8_lossy(&stdout[pending..end]));
+            pending = end;
+        }
+        if pending < stdout.len() {
+            on_stdout(&String::from_utf8_lossy(&stdout[pending..]));
+        }
+        Ok::<_, std::io::Error>(stdout)
+    };
+    let read_stderr = async {
+        let mut stderr = Vec::new();
+        stderr_pipe.read_to_end(&mut stderr).await?;
+        Ok::<_, std::io::Error>(stderr)
+    };
+
+    let (stdout, stderr) = tokio::try_join!(read_stdout, read_stderr)?;
+    let status = child.wait().await?;
+    Ok(Output { status, stdout, stderr })
+}
+
+/// Random task id, e.g. `task-1c9f04a2b7d3e815`
+fn generate_task_id() -> String {
+    format!("task-{:016x}", rand::random::<u64>())
+}
+
+/// Get current timestamp in milliseconds
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Tauri commands for task management
+
+/// Cancel a running task by id
+#[tauri::command]
+pub async fn cancel_task(
+    task_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), CommandError> {
+    state.task_manager.cancel(&task_id).map_err(Into::into)
+}
+
+/// List running tasks with their latest progress
+#[tauri::command]
+pub async fn list_active_tasks(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<TaskInfo>, CommandError> {
+    Ok(state.task_manager.list())
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Background Task Module
+
+pub mod manager;
+
+pub use manager::{run_isolated_process, run_process, TaskHandle, TaskManager};
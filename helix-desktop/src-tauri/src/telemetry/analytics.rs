@@ -0,0 +1,369 @@
+// Anonymous usage analytics - strictly opt-in
+//
+// Nothing is recorded unless `telemetry.enabled` is true in
+// ~/.helix/config.json. Feature counts are aggregated in memory and queued in
+// ~/.helix/telemetry/queue.jsonl; the queue is sent to the configured
+// endpoint as one batch per flush interval. Payload schema: docs/TELEMETRY.md
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::commands::config::{self, TelemetryConfig};
+use crate::privacy::residency::{self, DataFeature};
+use crate::startup::safe_mode::SafeModeStatus;
+use crate::commands::CommandError;
+
+/// Version of the upload payload, bumped on incompatible changes
+const SCHEMA_VERSION: u32 = 1;
+/// Queue size cap; oldest events are dropped beyond this
+const MAX_QUEUED_EVENTS: usize = 1000;
+/// Shortest allowed flush interval
+const MIN_FLUSH_INTERVAL_SECS: u64 = 60;
+
+/// A single queued analytics event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// How often a feature was used during a period
+    FeatureUsed {
+        name: String,
+        count: u64,
+        period_start: u64,
+        period_end: u64,
+    },
+    /// Outcome of an app session, reported on the following launch
+    Session { crashed: bool, reported_at: u64 },
+}
+
+/// Batch sent to the collector
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryPayload {
+    pub schema_version: u32,
+    pub install_id: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub sent_at: u64,
+    pub events: Vec<TelemetryEvent>,
+}
+
+/// Telemetry settings and queue state for the settings screen
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub flush_interval_secs: u64,
+    pub queued_events: usize,
+}
+
+/// In-memory feature counters for the current period
+struct FeatureCounters {
+    counts: HashMap<String, u64>,
+    period_start: u64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FLUSH_LOOP_RUNNING: AtomicBool = AtomicBool::new(false);
+static COUNTERS: Mutex<Option<FeatureCounters>> = Mutex::new(None);
+
+fn get_helix_dir() -> Result<PathBuf, String> {
+    if let Ok(dir) = std::env::var("HELIX_PROJECT_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    crate::accounts::profiles::helix_root()
+}
+
+fn telemetry_dir() -> Result<PathBuf, String> {
+    let dir = get_helix_dir()?.join("telemetry");
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create telemetry directory: {}", e))?;
+    Ok(dir)
+}
+
+fn queue_path() -> Result<PathBuf, String> {
+    Ok(telemetry_dir()?.join("queue.jsonl"))
+}
+
+/// Random per-install id, not derived from any user or machine data
+fn install_id() -> Result<String, String> {
+    let path = telemetry_dir()?.join("install-id");
+
+    if let Ok(id) = fs::read_to_string(&path) {
+        let id = id.trim().to_string();
+        if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(id);
+        }
+    }
+
+    let id = format!("{:032x}", rand::random::<u128>());
+    fs::write(&path, &id).map_err(|e| format!("Failed to write install id: {}", e))?;
+    Ok(id)
+}
+
+fn load_config() -> TelemetryConfig {
+    config::get_config()
+        .map(|c| c.telemetry)
+        .unwrap_or_default()
+}
+
+/// Count one use of a feature. No-op unless telemetry is enabled.
+pub fn record_feature(name: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    if let Ok(mut counters) = COUNTERS.lock() {
+        let counters = counters.get_or_insert_with(|| FeatureCounters {
+            counts: HashMap::new(),
+            period_start: current_timestamp(),
+        });
+        *counters.counts.entry(name.to_string()).or_insert(0) += 1;
+    }
+}
+
+fn read_queue() -> Vec<TelemetryEvent> {
+    queue_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_queue(events: &[TelemetryEvent]) -> Result<(), String> {
+    let start = events.len().saturating_sub(MAX_QUEUED_EVENTS);
+    let mut content = String::new();
+    for event in &events[start..] {
+        let line = serde_json::to_string(event)
+            .map_err(|e| format!("Failed to serialize telemetry event: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    fs::write(queue_path()?, content)
+        .map_err(|e| format!("Failed to write telemetry queue: {}", e))
+}
+
+/// Append events to the queue, dropping the oldest beyond the cap
+fn enqueue(events: Vec<TelemetryEvent>) -> Result<(), String> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let mut queued = read_queue();
+    queued.extend(events);
+    write_queue(&queued)
+}
+
+/// Move the current period's feature counts into the queue
+fn drain_counters() -> Result<(), String> {
+    let drained = COUNTERS.lock().map_err(|e| e.to_string())?.take();
+
+    if let Some(counters) = drained {
+        let period_end = current_timestamp();
+        let events = counters
+            .counts
+            .into_iter()
+            .map(|(name, count)| TelemetryEvent::FeatureUsed {
+                name,
+                count,
+                period_start: counters.period_start,
+                period_end,
+            })
+            .collect();
+        enqueue(events)?;
+    }
+
+    Ok(())
+}
+
+/// Build the payload for the currently queued events
+fn build_payload(app: &AppHandle) -> Result<TelemetryPayload, String> {
+    // The install id is only created once the user has opted in
+    let install_id = if ENABLED.load(Ordering::SeqCst) {
+        install_id()?
+    } else {
+        String::new()
+    };
+
+    Ok(TelemetryPayload {
+        schema_version: SCHEMA_VERSION,
+        install_id,
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        sent_at: current_timestamp(),
+        events: read_queue(),
+    })
+}
+
+/// Send queued events; the queue is only cleared after a 2xx response
+async fn flush(app: &AppHandle) -> Result<(), String> {
+    drain_counters()?;
+
+    let config = load_config();
+    if !config.enabled {
+        return Ok(());
+    }
+    let Some(endpoint) = config.endpoint.filter(|e| !e.trim().is_empty()) else {
+        return Ok(());
+    };
+    // Events stay queued while residency keeps data on the device
+    if !residency::allows(DataFeature::Sync) {
+        return Ok(());
+    }
+
+    let payload = build_payload(app)?;
+    if payload.events.is_empty() {
+        return Ok(());
+    }
+    let sent = payload.events.len();
+
+    let response = reqwest::Client::new()
+        .post(&endpoint)
+        .timeout(Duration::from_secs(30))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send telemetry: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Telemetry endpoint returned {}", response.status()));
+    }
+
+    // Keep anything queued while the upload was in flight
+    let remaining: Vec<TelemetryEvent> = read_queue().into_iter().skip(sent).collect();
+    write_queue(&remaining)?;
+
+    log::debug!("Sent {} telemetry events", sent);
+    Ok(())
+}
+
+/// Delete everything recorded locally
+fn clear_local_data() -> Result<(), String> {
+    if let Ok(mut counters) = COUNTERS.lock() {
+        *counters = None;
+    }
+
+    let path = queue_path()?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete telemetry queue: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Start telemetry if the user opted in (called from setup)
+pub fn init(app: &AppHandle, startup: &SafeModeStatus) {
+    let config = load_config();
+    ENABLED.store(config.enabled, Ordering::SeqCst);
+
+    if !config.enabled {
+        return;
+    }
+
+    if let Err(e) = enqueue(vec![TelemetryEvent::Session {
+        crashed: startup.previous_launch_crashed,
+        reported_at: current_timestamp(),
+    }]) {
+        log::warn!("Failed to record session telemetry: {}", e);
+    }
+
+    start_flush_loop(app);
+    log::info!("Telemetry enabled");
+}
+
+/// Periodically flush the queue until telemetry is disabled
+fn start_flush_loop(app: &AppHandle) {
+    if FLUSH_LOOP_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval = load_config().flush_interval_secs.max(MIN_FLUSH_INTERVAL_SECS);
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+
+            if !ENABLED.load(Ordering::SeqCst) {
+                break;
+            }
+            if let Err(e) = flush(&handle).await {
+                log::debug!("Telemetry flush failed: {}", e);
+            }
+        }
+        FLUSH_LOOP_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Get current timestamp in milliseconds
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Tauri commands for telemetry settings
+
+/// Get telemetry settings and the number of queued events
+#[tauri::command]
+pub fn get_telemetry_status() -> Result<TelemetryStatus, CommandError> {
+    let config = load_config();
+    Ok(TelemetryStatus {
+        enabled: config.enabled,
+        endpoint: config.endpoint,
+        flush_interval_secs: config.flush_interval_secs,
+        queued_events: if config.enabled { read_queue().len() } else { 0 },
+    })
+}
+
+/// Opt in or out of telemetry. Opting out deletes all locally queued data.
+#[tauri::command]
+pub fn set_telemetry_settings(
+    app: AppHandle,
+    enabled: bool,
+    endpoint: Option<String>,
+) -> Result<TelemetryStatus, CommandError> {
+    let mut helix_config = config::get_config()?;
+    let was_enabled = helix_config.telemetry.enabled;
+
+    helix_config.telemetry.enabled = enabled;
+    helix_config.telemetry.endpoint = endpoint.filter(|e| !e.trim().is_empty());
+    config::set_config(helix_config)?;
+
+    ENABLED.store(enabled, Ordering::SeqCst);
+
+    if !enabled {
+        clear_local_data()?;
+    } else if !was_enabled {
+        start_flush_loop(&app);
+    }
+
+    get_telemetry_status()
+}
+
+/// Exactly what the next upload would contain, for review before opting in
+#[tauri::command]
+pub fn preview_telemetry_payload(app: AppHandle) -> Result<TelemetryPayload, CommandError> {
+    drain_counters()?;
+    build_payload(&app).map_err(Into::into)
+}
+
+/// Count a feature used in the frontend
+#[tauri::command]
+pub fn record_feature_usage(feature: String) -> Result<(), CommandError> {
+    record_feature(&feature);
+    Ok(())
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Telemetry Module
+
+pub mod analytics;
+
+pub use analytics::record_feature;
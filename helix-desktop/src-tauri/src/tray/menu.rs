@@ -1,13 +1,17 @@
 // Helix Desktop - Enhanced Tray Menu Definition (Phase J2)
 //
 // Rich system tray menu with gateway status, agent/channel submenus,
-// quick actions, and dynamic updates.
+// scheduler maintenance, quick actions, and dynamic updates.
 
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::{
     menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     AppHandle, Emitter, Runtime,
 };
 
+use crate::commands::scheduler::{self, JobType, SchedulerPaused};
+use crate::locale::Catalog;
+
 // ── Menu item IDs ──────────────────────────────────────────────────────────────
 
 // Header
@@ -21,6 +25,15 @@ pub const MENU_TALK_MODE: &str = "talk-mode";
 // Submenus (parent IDs)
 pub const SUBMENU_AGENTS: &str = "agents-submenu";
 pub const SUBMENU_CHANNELS: &str = "channels-submenu";
+pub const SUBMENU_MAINTENANCE: &str = "maintenance-submenu";
+
+// Maintenance (scheduler quick actions)
+pub const MENU_RUN_CONSOLIDATION: &str = "maintenance:run-consolidation";
+pub const MENU_RUN_SYNTHESIS: &str = "maintenance:run-synthesis";
+pub const MENU_PAUSE_SCHEDULER_1H: &str = "maintenance:pause-1h";
+pub const MENU_PAUSE_SCHEDULER_24H: &str = "maintenance:pause-24h";
+pub const MENU_RESUME_SCHEDULER: &str = "maintenance:resume";
+pub const MENU_OPEN_SCHEDULER: &str = "maintenance:open-scheduler";
 
 // Quick Links
 pub const MENU_SETTINGS: &str = "settings";
@@ -34,6 +47,7 @@ pub const MENU_QUIT: &str = "quit";
 // Prefixes for dynamic items within submenus
 pub const AGENT_PREFIX: &str = "agent:";
 pub const CHANNEL_PREFIX: &str = "channel:";
+pub const MAINTENANCE_PREFIX: &str = "maintenance:";
 
 // ── Data types for dynamic tray state ──────────────────────────────────────────
 
@@ -46,6 +60,43 @@ pub struct TrayMenuState {
     pub pending_approvals: u32,
     pub window_visible: bool,
     pub talk_mode_active: bool,
+    pub gateway_restart_pending: bool,
+    pub maintenance: MaintenanceState,
+}
+
+/// Scheduler state behind the Maintenance submenu, read from the backend
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceState {
+    /// Next consolidation run (seconds since epoch), `None` when off
+    pub next_consolidation: Option<u64>,
+    pub next_synthesis: Option<u64>,
+    /// End of a scheduler-wide pause
+    pub paused_until: Option<u64>,
+    /// Jobs failing often enough to have raised a failure alert
+    pub failing_jobs: u32,
+    /// Maintenance mode holds all jobs back
+    pub suspended: bool,
+}
+
+impl MaintenanceState {
+    /// Current scheduler state
+    pub fn current() -> Self {
+        let upcoming = scheduler::upcoming_maintenance();
+        let next = |job_type: JobType| {
+            upcoming
+                .iter()
+                .find(|run| run.job_type == job_type)
+                .and_then(|run| run.next_run)
+        };
+
+        Self {
+            next_consolidation: next(JobType::Consolidation),
+            next_synthesis: next(JobType::Synthesis),
+            paused_until: scheduler::paused_until(),
+            failing_jobs: scheduler::failing_job_count(),
+            suspended: scheduler::maintenance_mode().is_some(),
+        }
+    }
 }
 
 // ── Menu construction ──────────────────────────────────────────────────────────
@@ -54,73 +105,83 @@ pub struct TrayMenuState {
 pub fn create_tray_menu<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<Menu<R>, Box<dyn std::error::Error>> {
-    let state = TrayMenuState::default();
+    let state = TrayMenuState {
+        maintenance: MaintenanceState::current(),
+        ..TrayMenuState::default()
+    };
     build_tray_menu(app, &state)
 }
 
-/// Build the full tray menu from the provided state.
+/// Build the full tray menu from the provided state, in the current locale.
 ///
-/// Layout:
+/// Layout (English):
 ///   Helix                         (disabled header)
-///   Gateway: Running / Stopped    (disabled status indicator)
+///   Gateway: Running / Stopped    (disabled status indicator, notes pending restart)
 ///   ────────────────
 ///   New Chat
 ///   Talk Mode
 ///   ────────────────
 ///   Agents >
 ///   Channels >
+///   Maintenance >                 ("Maintenance (N failing)" with failing jobs,
+///                                  "Maintenance (suspended)" in maintenance mode)
 ///   ────────────────
 ///   Settings
 ///   Approvals (N)
 ///   ────────────────
 ///   Show Window / Hide Window
-///   Restart Gateway
+///   Restart Gateway (pending)
 ///   ────────────────
 ///   Quit Helix
 pub fn build_tray_menu<R: Runtime>(
     app: &AppHandle<R>,
     state: &TrayMenuState,
 ) -> Result<Menu<R>, Box<dyn std::error::Error>> {
+    let strings = Catalog::new("tray");
+
     // ── Header section ─────────────────────────────────────────────────────
-    let header = MenuItem::with_id(app, MENU_HEADER, "Helix", false, None::<&str>)?;
+    let header = MenuItem::with_id(app, MENU_HEADER, strings.get("app-name"), false, None::<&str>)?;
 
-    let gateway_label = if state.gateway_running {
-        "Gateway: Running"
-    } else {
-        "Gateway: Stopped"
+    let gateway_label = match (state.gateway_running, state.gateway_restart_pending) {
+        (true, true) => strings.get("gateway-running-restart-pending"),
+        (true, false) => strings.get("gateway-running"),
+        (false, _) => strings.get("gateway-stopped"),
     };
     let gateway_status =
-        MenuItem::with_id(app, MENU_GATEWAY_STATUS, gateway_label, false, None::<&str>)?;
+        MenuItem::with_id(app, MENU_GATEWAY_STATUS, &gateway_label, false, None::<&str>)?;
 
     let sep1 = PredefinedMenuItem::separator(app)?;
 
     // ── Quick actions ──────────────────────────────────────────────────────
-    let new_chat = MenuItem::with_id(app, MENU_NEW_CHAT, "New Chat", true, None::<&str>)?;
+    let new_chat = MenuItem::with_id(app, MENU_NEW_CHAT, strings.get("new-chat"), true, None::<&str>)?;
 
     let talk_label = if state.talk_mode_active {
-        "Talk Mode (on)"
+        strings.get("talk-mode-on")
     } else {
-        "Talk Mode"
+        strings.get("talk-mode")
     };
-    let talk_mode = MenuItem::with_id(app, MENU_TALK_MODE, talk_label, true, None::<&str>)?;
+    let talk_mode = MenuItem::with_id(app, MENU_TALK_MODE, &talk_label, true, None::<&str>)?;
 
     let sep2 = PredefinedMenuItem::separator(app)?;
 
     // ── Agents submenu ─────────────────────────────────────────────────────
-    let agents_submenu = build_agents_submenu(app, &state.agents)?;
+    let agents_submenu = build_agents_submenu(app, &strings, &state.agents)?;
 
     // ── Channels submenu ───────────────────────────────────────────────────
-    let channels_submenu = build_channels_submenu(app, &state.channels)?;
+    let channels_submenu = build_channels_submenu(app, &strings, &state.channels)?;
+
+    // ── Maintenance submenu ────────────────────────────────────────────────
+    let maintenance_submenu = build_maintenance_submenu(app, &strings, &state.maintenance)?;
 
     let sep3 = PredefinedMenuItem::separator(app)?;
 
     // ── Quick links ────────────────────────────────────────────────────────
-    let settings = MenuItem::with_id(app, MENU_SETTINGS, "Settings", true, None::<&str>)?;
+    let settings = MenuItem::with_id(app, MENU_SETTINGS, strings.get("settings"), true, None::<&str>)?;
 
     let approvals_label = if state.pending_approvals > 0 {
-        format!("Approvals ({})", state.pending_approvals)
+        strings.get_args("approvals-pending", &[("count", state.pending_approvals.into())])
     } else {
-        "Approvals".to_string()
+        strings.get("approvals")
     };
     let approvals =
         MenuItem::with_id(app, MENU_APPROVALS, &approvals_label, true, None::<&str>)?;
@@ -129,18 +190,23 @@ pub fn build_tray_menu<R: Runtime>(
 
     // ── System section ─────────────────────────────────────────────────────
     let show_hide_label = if state.window_visible {
-        "Hide Window"
+        strings.get("hide-window")
     } else {
-        "Show Window"
+        strings.get("show-window")
     };
     let show_window =
-        MenuItem::with_id(app, MENU_SHOW_WINDOW, show_hide_label, true, None::<&str>)?;
+        MenuItem::with_id(app, MENU_SHOW_WINDOW, &show_hide_label, true, None::<&str>)?;
+    let restart_label = if state.gateway_restart_pending {
+        strings.get("restart-gateway-pending")
+    } else {
+        strings.get("restart-gateway")
+    };
     let restart_gateway =
-        MenuItem::with_id(app, MENU_RESTART_GATEWAY, "Restart Gateway", true, None::<&str>)?;
+        MenuItem::with_id(app, MENU_RESTART_GATEWAY, &restart_label, true, None::<&str>)?;
 
     let sep5 = PredefinedMenuItem::separator(app)?;
 
-    let quit = MenuItem::with_id(app, MENU_QUIT, "Quit Helix", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, strings.get("quit"), true, None::<&str>)?;
 
     // ── Assemble ───────────────────────────────────────────────────────────
     let menu = Menu::with_items(
@@ -154,6 +220,7 @@ pub fn build_tray_menu<R: Runtime>(
             &sep2,
             &agents_submenu,
             &channels_submenu,
+            &maintenance_submenu,
             &sep3,
             &settings,
             &approvals,
@@ -171,22 +238,23 @@ pub fn build_tray_menu<R: Runtime>(
 /// Build the "Agents" submenu from a list of (name, status) pairs.
 fn build_agents_submenu<R: Runtime>(
     app: &AppHandle<R>,
+    strings: &Catalog,
     agents: &[(String, String)],
 ) -> Result<Submenu<R>, Box<dyn std::error::Error>> {
-    let submenu = Submenu::with_id(app, SUBMENU_AGENTS, "Agents", true)?;
+    let submenu = Submenu::with_id(app, SUBMENU_AGENTS, strings.get("agents"), true)?;
 
     if agents.is_empty() {
         let placeholder = MenuItem::with_id(
             app,
             "agent:none",
-            "No agents configured",
+            strings.get("agents-none"),
             false,
             None::<&str>,
         )?;
         submenu.append(&placeholder)?;
     } else {
         for (name, status) in agents {
-            let (indicator, status_text) = format_status_indicator(status);
+            let (indicator, status_text) = format_status_indicator(strings, status);
             let label = format!("{} {} ({})", indicator, name, status_text);
             let id = format!("{}{}", AGENT_PREFIX, name);
             let item = MenuItem::with_id(app, &id, &label, false, None::<&str>)?;
@@ -200,22 +268,23 @@ fn build_agents_submenu<R: Runtime>(
 /// Build the "Channels" submenu from a list of (name, status) pairs.
 fn build_channels_submenu<R: Runtime>(
     app: &AppHandle<R>,
+    strings: &Catalog,
     channels: &[(String, String)],
 ) -> Result<Submenu<R>, Box<dyn std::error::Error>> {
-    let submenu = Submenu::with_id(app, SUBMENU_CHANNELS, "Channels", true)?;
+    let submenu = Submenu::with_id(app, SUBMENU_CHANNELS, strings.get("channels"), true)?;
 
     if channels.is_empty() {
         let placeholder = MenuItem::with_id(
             app,
             "channel:none",
-            "No channels configured",
+            strings.get("channels-none"),
             false,
             None::<&str>,
         )?;
         submenu.append(&placeholder)?;
     } else {
         for (name, status) in channels {
-            let (indicator, status_text) = format_status_indicator(status);
+            let (indicator, status_text) = format_status_indicator(strings, status);
             let label = format!("{} {} ({})", indicator, name, status_text);
             let id = format!("{}{}", CHANNEL_PREFIX, name);
             let item = MenuItem::with_id(app, &id, &label, false, None::<&str>)?;
@@ -226,19 +295,180 @@ fn build_channels_submenu<R: Runtime>(
     Ok(submenu)
 }
 
+/// Build the "Maintenance" submenu: upcoming scheduler runs and quick actions.
+///
+/// Layout:
+///   Next consolidation: in 3h 20m   (disabled)
+///   Next synthesis: in 9h 5m        (disabled)
+///   ────────────────
+///   Run Consolidation Now
+///   Run Synthesis Now
+///   ────────────────
+///   Pause Scheduler for 1 Hour      (or "Resume Scheduler (paused, 40m left)")
+///   Pause Scheduler for 24 Hours
+///   Open Scheduler
+fn build_maintenance_submenu<R: Runtime>(
+    app: &AppHandle<R>,
+    strings: &Catalog,
+    state: &MaintenanceState,
+) -> Result<Submenu<R>, Box<dyn std::error::Error>> {
+    let label = match state.failing_jobs {
+        _ if state.suspended => strings.get("maintenance-suspended"),
+        0 => strings.get("maintenance"),
+        count => strings.get_args("maintenance-failing", &[("count", count.into())]),
+    };
+    let submenu = Submenu::with_id(app, SUBMENU_MAINTENANCE, label, true)?;
+    let now = now_secs();
+
+    for (id, job, next_run) in [
+        (
+            "maintenance:next-consolidation",
+            "job-consolidation",
+            state.next_consolidation,
+        ),
+        (
+            "maintenance:next-synthesis",
+            "job-synthesis",
+            state.next_synthesis,
+        ),
+    ] {
+        let job = strings.get(job);
+        let label = match (state.paused_until, next_run) {
+            (_, None) => strings.get_args("next-run-off", &[("job", job.into())]),
+            // Runs due during the pause wait for it to end
+            (Some(until), Some(next)) if next <= until => strings.get_args(
+                "next-run-after-pause",
+                &[
+                    ("job", job.into()),
+                    ("when", format_relative(strings, until.saturating_sub(now)).into()),
+                ],
+            ),
+            (_, Some(next)) => strings.get_args(
+                "next-run",
+                &[
+                    ("job", job.into()),
+                    ("when", format_relative(strings, next.saturating_sub(now)).into()),
+                ],
+            ),
+        };
+        submenu.append(&MenuItem::with_id(app, id, &label, false, None::<&str>)?)?;
+    }
+
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    submenu.append(&MenuItem::with_id(
+        app,
+        MENU_RUN_CONSOLIDATION,
+        strings.get("run-consolidation"),
+        true,
+        None::<&str>,
+    )?)?;
+    submenu.append(&MenuItem::with_id(
+        app,
+        MENU_RUN_SYNTHESIS,
+        strings.get("run-synthesis"),
+        true,
+        None::<&str>,
+    )?)?;
+
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    match state.paused_until {
+        Some(until) => {
+            let label = strings.get_args(
+                "resume-scheduler",
+                &[("remaining", format_duration(until.saturating_sub(now)).into())],
+            );
+            submenu.append(&MenuItem::with_id(
+                app,
+                MENU_RESUME_SCHEDULER,
+                &label,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        None => {
+            submenu.append(&MenuItem::with_id(
+                app,
+                MENU_PAUSE_SCHEDULER_1H,
+                strings.get("pause-scheduler-1h"),
+                true,
+                None::<&str>,
+            )?)?;
+            submenu.append(&MenuItem::with_id(
+                app,
+                MENU_PAUSE_SCHEDULER_24H,
+                strings.get("pause-scheduler-24h"),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+    }
+    submenu.append(&MenuItem::with_id(
+        app,
+        MENU_OPEN_SCHEDULER,
+        strings.get("open-scheduler"),
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(submenu)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Compact duration: "45m", "3h 20m", "2d 4h"
+fn format_duration(secs: u64) -> String {
+    let minutes = secs.div_ceil(60);
+    match minutes {
+        0..=59 => format!("{}m", minutes.max(1)),
+        60..=1439 if minutes.is_multiple_of(60) => format!("{}h", minutes / 60),
+        60..=1439 => format!("{}h {}m", minutes / 60, minutes % 60),
+        _ if (minutes / 60).is_multiple_of(24) => format!("{}d", minutes / 1440),
+        _ => format!("{}d {}h", minutes / 1440, (minutes / 60) % 24),
+    }
+}
+
+/// Time until a run: "in 3h 20m", or "due now" once it is past
+fn format_relative(strings: &Catalog, secs_from_now: u64) -> String {
+    if secs_from_now == 0 {
+        strings.get("due-now")
+    } else {
+        strings.get_args("in-duration", &[("duration", format_duration(secs_from_now).into())])
+    }
+}
+
+/// Pause the scheduler from the tray and tell the frontend, which rebuilds
+/// the menu in response
+fn pause_scheduler<R: Runtime>(app: &AppHandle<R>, duration_secs: Option<u64>) {
+    let paused = SchedulerPaused {
+        paused_until: duration_secs.map(|secs| now_secs() + secs),
+    };
+    match scheduler::set_paused_until(paused.paused_until) {
+        Ok(()) => {
+            let _ = app.emit("scheduler:paused", paused);
+        }
+        Err(e) => log::warn!("Failed to change scheduler pause from tray: {}", e),
+    }
+}
+
 /// Map a status string to a bullet indicator and display text.
 ///
 /// Returns `("filled-circle", "display-text")`.
 /// Active / connected statuses get a filled circle, others get an open circle.
-fn format_status_indicator(status: &str) -> (&'static str, &'static str) {
-    match status.to_lowercase().as_str() {
-        "active" | "running" => ("\u{25CF}", "active"),       // ●
-        "connected" => ("\u{25CF}", "connected"),              // ●
-        "idle" | "standby" => ("\u{25CB}", "idle"),            // ○
-        "disconnected" | "offline" => ("\u{25CB}", "disconnected"), // ○
-        "error" | "failed" => ("\u{25CB}", "error"),           // ○
-        _ => ("\u{25CB}", "idle"),                              // ○
-    }
+fn format_status_indicator(strings: &Catalog, status: &str) -> (&'static str, String) {
+    let (indicator, id) = match status.to_lowercase().as_str() {
+        "active" | "running" => ("\u{25CF}", "status-active"),       // ●
+        "connected" => ("\u{25CF}", "status-connected"),              // ●
+        "idle" | "standby" => ("\u{25CB}", "status-idle"),            // ○
+        "disconnected" | "offline" => ("\u{25CB}", "status-disconnected"), // ○
+        "error" | "failed" => ("\u{25CB}", "status-error"),           // ○
+        _ => ("\u{25CB}", "status-idle"),                              // ○
+    };
+    (indicator, strings.get(id))
 }
 
 // ── Menu event handling ────────────────────────────────────────────────────────
@@ -254,7 +484,8 @@ pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
             let _ = app.emit("tray:new-chat", ());
         }
         MENU_TALK_MODE => {
-            let _ = app.emit("tray:toggle-talk-mode", ());
+            let active = !crate::integrations::presence::talk_mode_active();
+            let _ = app.emit("tray:toggle-talk-mode", serde_json::json!({ "active": active }));
         }
 
         // ── Quick links ────────────────────────────────────────────────────
@@ -267,6 +498,21 @@ pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
             let _ = app.emit("tray:open-approvals", ());
         }
 
+        // ── Maintenance ────────────────────────────────────────────────────
+        MENU_RUN_CONSOLIDATION => {
+            let _ = app.emit("tray:run-maintenance", "consolidation");
+        }
+        MENU_RUN_SYNTHESIS => {
+            let _ = app.emit("tray:run-maintenance", "synthesis");
+        }
+        MENU_PAUSE_SCHEDULER_1H => pause_scheduler(app, Some(3600)),
+        MENU_PAUSE_SCHEDULER_24H => pause_scheduler(app, Some(24 * 3600)),
+        MENU_RESUME_SCHEDULER => pause_scheduler(app, None),
+        MENU_OPEN_SCHEDULER => {
+            super::show_window(app);
+            let _ = app.emit("tray:open-scheduler", ());
+        }
+
         // ── System ─────────────────────────────────────────────────────────
         MENU_SHOW_WINDOW => {
             super::toggle_window(app);
@@ -281,9 +527,12 @@ pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
         // ── Disabled / informational items (no-op) ─────────────────────────
         MENU_HEADER | MENU_GATEWAY_STATUS => {}
 
-        // ── Dynamic agent / channel items (informational, no-op) ───────────
+        // ── Dynamic agent / channel / maintenance items (informational, no-op)
         other => {
-            if other.starts_with(AGENT_PREFIX) || other.starts_with(CHANNEL_PREFIX) {
+            if other.starts_with(AGENT_PREFIX)
+                || other.starts_with(CHANNEL_PREFIX)
+                || other.starts_with(MAINTENANCE_PREFIX)
+            {
                 // Currently informational only; could emit events in the future
             } else {
                 log::debug!("Unhandled tray menu event: {}", other);
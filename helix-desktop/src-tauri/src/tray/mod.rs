@@ -2,18 +2,23 @@
 
 pub mod menu;
 
+use std::sync::Mutex;
 use tauri::{
     tray::{TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager, Runtime,
 };
 
-use crate::tray::menu::{build_tray_menu, create_tray_menu, TrayMenuState};
+use crate::tray::menu::{build_tray_menu, create_tray_menu, MaintenanceState, TrayMenuState};
+use crate::commands::CommandError;
 
 // ── Tray icon ID ───────────────────────────────────────────────────────────────
 
 /// The well-known ID for the Helix tray icon so we can look it up later.
 const TRAY_ID: &str = "helix-tray";
 
+/// Latest gateway status announcement, kept in the tooltip
+static STATUS_SUMMARY: Mutex<Option<String>> = Mutex::new(None);
+
 // ── Initialization ─────────────────────────────────────────────────────────────
 
 /// Initialize the system tray with the default menu.
@@ -24,7 +29,7 @@ pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, Box<dyn std::
         .icon(app.default_window_icon().cloned().unwrap())
         .menu(&menu)
         .show_menu_on_left_click(false)
-        .tooltip("Helix")
+        .tooltip(crate::locale::t("tray", "tooltip"))
         .on_tray_icon_event(|tray, event| {
             handle_tray_event(tray, event);
         })
@@ -91,18 +96,61 @@ pub fn toggle_window<R: Runtime>(app: &AppHandle<R>) {
     }
 }
 
+// ── Tooltip ────────────────────────────────────────────────────────────────────
+
+/// Set the tooltip from the pending restart flag and the latest status
+/// summary, which screen readers read out for the tray icon.
+fn refresh_tooltip<R: Runtime>(app: &AppHandle<R>, restart_pending: bool) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = if restart_pending {
+        crate::locale::t("tray", "tooltip-restart-pending")
+    } else {
+        crate::locale::t("tray", "tooltip")
+    };
+    let summary = STATUS_SUMMARY.lock().ok().and_then(|summary| summary.clone());
+    let tooltip = match summary {
+        Some(status) => crate::locale::Catalog::new("tray")
+            .get_args("tooltip-status", &[("tooltip", tooltip.into()), ("status", status.into())]),
+        None => tooltip,
+    };
+    if let Err(e) = tray.set_tooltip(Some(&tooltip)) {
+        log::warn!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+/// Reflect a pending gateway restart in the tray tooltip.
+///
+/// The menu labels follow on the next `update_tray_menu` call, which the
+/// frontend makes when it sees the `gateway:restart-pending` event.
+pub fn set_restart_pending_indicator<R: Runtime>(app: &AppHandle<R>, pending: bool) {
+    refresh_tooltip(app, pending);
+}
+
+/// Show a plain-text status summary (see accessibility/announce.rs) in
+/// the tray tooltip.
+pub fn set_status_summary<R: Runtime>(app: &AppHandle<R>, summary: &str) {
+    if let Ok(mut current) = STATUS_SUMMARY.lock() {
+        *current = Some(summary.to_string());
+    }
+    refresh_tooltip(app, crate::gateway::config_sync::is_restart_pending());
+}
+
 // ── Dynamic tray update (Tauri command) ────────────────────────────────────────
 
 /// Rebuild the system tray menu with updated state from the frontend.
 ///
 /// The frontend calls this command whenever gateway status, agent list,
-/// channel list, or pending approvals change.
+/// channel list, pending approvals, or the scheduler pause change. The
+/// Maintenance submenu is read from the scheduler here.
 ///
 /// Arguments:
 /// - `gateway_status` - "running" | "stopped" (case-insensitive)
 /// - `agents` - list of `[name, status]` pairs
 /// - `channels` - list of `[name, status]` pairs
-/// - `pending_approvals` - number of pending approval items
+/// - `pending_approvals` - number of pending approval items; queued skill
+///   runs are added here
 #[tauri::command]
 pub async fn update_tray_menu(
     app: tauri::AppHandle,
@@ -110,7 +158,7 @@ pub async fn update_tray_menu(
     agents: Vec<(String, String)>,
     channels: Vec<(String, String)>,
     pending_approvals: u32,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Determine window visibility for the Show/Hide label
     let window_visible = app
         .get_webview_window("main")
@@ -121,9 +169,11 @@ pub async fn update_tray_menu(
         gateway_running: gateway_status.eq_ignore_ascii_case("running"),
         agents,
         channels,
-        pending_approvals,
+        pending_approvals: pending_approvals + crate::policy::skill_approvals::pending_count(),
         window_visible,
-        talk_mode_active: false, // Frontend can extend this later
+        talk_mode_active: crate::integrations::presence::talk_mode_active(),
+        gateway_restart_pending: crate::gateway::config_sync::is_restart_pending(),
+        maintenance: MaintenanceState::current(),
     };
 
     // Build the new menu
@@ -140,7 +190,7 @@ pub async fn update_tray_menu(
         })?;
     } else {
         log::warn!("Tray icon '{}' not found; cannot update menu", TRAY_ID);
-        return Err(format!("Tray icon '{}' not found", TRAY_ID));
+        return Err(CommandError::not_found(format!("Tray icon '{}' not found", TRAY_ID)));
     }
 
     Ok(())
@@ -0,0 +1,305 @@
+// Resumable update downloads
+//
+// The package is streamed to ~/.helix/update-download/update.part next to a
+// small manifest naming the version, URL and signature it belongs to. A
+// paused or interrupted download continues from the bytes already on disk
+// with an HTTP range request, including after an app restart; a server that
+// ignores the range gets the download restarted. The speed cap from the
+// `updates` config section is applied between chunks. The finished package
+// is verified against the updater public key before it is handed back.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::Update;
+use tokio::io::AsyncWriteExt;
+
+use super::UpdateStatus;
+use crate::tasks::TaskHandle;
+
+const DOWNLOAD_DIR: &str = "update-download";
+const PART_FILENAME: &str = "update.part";
+const MANIFEST_FILENAME: &str = "update.json";
+/// Manifest is rewritten after this many bytes so a crash loses little
+const MANIFEST_EVERY_BYTES: u64 = 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Set while a download is running
+static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+/// Asks the running download to stop after its current chunk
+static PAUSE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// What the partial file on disk belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialDownload {
+    pub version: String,
+    pub download_url: String,
+    pub signature: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// Paused by the user rather than interrupted
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl PartialDownload {
+    fn progress(&self) -> f32 {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.downloaded as f32 / total as f32) * 100.0)
+            .unwrap_or(0.0)
+    }
+
+    pub fn paused_status(&self) -> UpdateStatus {
+        UpdateStatus::Paused {
+            progress: self.progress(),
+            downloaded: self.downloaded,
+            total: self.total,
+        }
+    }
+}
+
+/// How a download run ended
+pub enum Download {
+    /// Verified package bytes
+    Complete(Vec<u8>),
+    Paused,
+}
+
+fn download_dir() -> Result<PathBuf, String> {
+    Ok(crate::accounts::profiles::helix_root()?.join(DOWNLOAD_DIR))
+}
+
+/// The partial download left on disk, if any
+pub fn partial() -> Option<PartialDownload> {
+    let dir = download_dir().ok()?;
+    let content = std::fs::read_to_string(dir.join(MANIFEST_FILENAME)).ok()?;
+    let mut partial: PartialDownload = serde_json::from_str(&content).ok()?;
+    // The part file is the truth; the manifest may lag behind it
+    partial.downloaded = std::fs::metadata(dir.join(PART_FILENAME)).ok()?.len();
+    Some(partial)
+}
+
+fn save_manifest(partial: &PartialDownload) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(partial).map_err(|e| e.to_string())?;
+    std::fs::write(download_dir()?.join(MANIFEST_FILENAME), json)
+        .map_err(|e| format!("Failed to write download manifest: {}", e))
+}
+
+fn discard() {
+    if let Ok(dir) = download_dir() {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+pub fn is_downloading() -> bool {
+    DOWNLOADING.load(Ordering::SeqCst)
+}
+
+/// Ask the running download to pause; false if none is running
+pub fn request_pause() -> bool {
+    if !is_downloading() {
+        return false;
+    }
+    PAUSE_REQUESTED.store(true, Ordering::SeqCst);
+    true
+}
+
+/// Clears DOWNLOADING when a download run ends, however it ends
+struct DownloadGuard;
+
+impl DownloadGuard {
+    fn acquire() -> Result<Self, String> {
+        DOWNLOADING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map_err(|_| "An update download is already in progress".to_string())?;
+        PAUSE_REQUESTED.store(false, Ordering::SeqCst);
+        Ok(Self)
+    }
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        DOWNLOADING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Updater public key from tauri.conf.json
+pub(crate) fn updater_pubkey(app: &AppHandle) -> Result<String, String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .map(String::from)
+        .ok_or_else(|| "Updater public key is not configured".to_string())
+}
+
+pub(crate) fn decode_base64(value: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// Check the package against the release signature, as the updater plugin
+/// does for the downloads it makes itself
+fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let public_key = minisign_verify::PublicKey::decode(&decode_base64(pubkey)?)
+        .map_err(|e| format!("Invalid updater public key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&decode_base64(signature)?)
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|e| format!("Update signature verification failed: {}", e))
+}
+
+/// Download `update`, continuing a partial download of the same package.
+/// `limit_kbps` caps the speed in KiB/s.
+pub async fn fetch(
+    app: &AppHandle,
+    task: &TaskHandle,
+    update: &Update,
+    limit_kbps: Option<u32>,
+) -> Result<Download, String> {
+    let _guard = DownloadGuard::acquire()?;
+    let dir = download_dir()?;
+    let part_path = dir.join(PART_FILENAME);
+
+    let mut state = match partial() {
+        Some(partial)
+            if partial.version == update.version
+                && partial.download_url == update.download_url.as_str()
+                && partial.signature == update.signature =>
+        {
+            partial
+        }
+        _ => {
+            discard();
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .map_err(|e| format!("Failed to create download directory: {}", e))?;
+            PartialDownload {
+                version: update.version.clone(),
+                download_url: update.download_url.to_string(),
+                signature: update.signature.clone(),
+                downloaded: 0,
+                total: None,
+                paused: false,
+            }
+        }
+    };
+    state.paused = false;
+
+    let mut client = reqwest::Client::builder().connect_timeout(CONNECT_TIMEOUT);
+    if let Some(proxy) = &update.proxy {
+        client = client.proxy(reqwest::Proxy::all(proxy.as_str()).map_err(|e| e.to_string())?);
+    }
+    let client = client.build().map_err(|e| e.to_string())?;
+
+    let mut request = client
+        .get(update.download_url.as_str())
+        .header(reqwest::header::ACCEPT, "application/octet-stream");
+    for (name, value) in update.headers.iter() {
+        if let Ok(value) = value.to_str() {
+            request = request.header(name.as_str(), value);
+        }
+    }
+    if state.downloaded > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", state.downloaded));
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Download request failed: {}", e))?;
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    if !resumed && !response.status().is_success() {
+        return Err(format!("Download request failed with status: {}", response.status()));
+    }
+    if !resumed && state.downloaded > 0 {
+        log::info!("Update server ignored the range request; restarting the download");
+        state.downloaded = 0;
+    }
+    if resumed {
+        log::info!("Resuming update download at {} bytes", state.downloaded);
+    }
+    state.total = response.content_length().map(|len| len + state.downloaded);
+    save_manifest(&state)?;
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part_path)
+        .await
+        .map_err(|e| format!("Failed to open download file: {}", e))?;
+
+    let started = Instant::now();
+    let mut received: u64 = 0;
+    let mut since_manifest: u64 = 0;
+    let bytes_per_sec = limit_kbps.filter(|kbps| *kbps > 0).map(|kbps| kbps as f64 * 1024.0);
+
+    loop {
+        if PAUSE_REQUESTED.swap(false, Ordering::SeqCst) {
+            file.flush().await.map_err(|e| e.to_string())?;
+            state.paused = true;
+            save_manifest(&state)?;
+            let _ = app.emit("update:status", state.paused_status());
+            task.progress("paused", Some(state.progress()), None);
+            log::info!("Update download paused at {} bytes", state.downloaded);
+            return Ok(Download::Paused);
+        }
+
+        let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Download interrupted: {}", e))?
+        else {
+            break;
+        };
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write download file: {}", e))?;
+
+        let len = chunk.len() as u64;
+        received += len;
+        since_manifest += len;
+        state.downloaded += len;
+        if since_manifest >= MANIFEST_EVERY_BYTES {
+            since_manifest = 0;
+            file.flush().await.map_err(|e| e.to_string())?;
+            save_manifest(&state)?;
+        }
+
+        let progress = state.progress();
+        let _ = app.emit("update:status", UpdateStatus::Downloading { progress });
+        task.progress("downloading", state.total.map(|_| progress), None);
+
+        // Sleep off any lead over the cap
+        if let Some(rate) = bytes_per_sec {
+            let due = Duration::from_secs_f64(received as f64 / rate);
+            if let Some(ahead) = due.checked_sub(started.elapsed()) {
+                tokio::time::sleep(ahead).await;
+            }
+        }
+    }
+    file.flush().await.map_err(|e| e.to_string())?;
+    drop(file);
+
+    let data = tokio::fs::read(&part_path)
+        .await
+        .map_err(|e| format!("Failed to read downloaded update: {}", e))?;
+    let verified = updater_pubkey(app).and_then(|pubkey| verify_signature(&data, &update.signature, &pubkey));
+    // A corrupt or tampered package can't be resumed into a good one
+    discard();
+    verified?;
+
+    log::info!("Update download complete ({} bytes)", data.len());
+    Ok(Download::Complete(data))
+}
@@ -0,0 +1,292 @@
+// Post-update migration hooks
+//
+// HOOKS lists data migrations that ship with an app version. On the first
+// launch of a new version, before config init and before any component
+// starts, every hook introduced after the previously launched version runs
+// in order against the active account's data directory. If one fails,
+// ~/.helix is restored from the snapshot install_update took (see
+// snapshot.rs) and the remaining hooks are skipped. Progress is kept in
+// ~/.helix/update-state.json.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use super::snapshot::{self, Snapshot};
+use crate::commands::config::HelixConfig;
+use crate::commands::scheduler::SchedulerConfig;
+use crate::automation::hooks::{self, HookEvent};
+use crate::commands::CommandError;
+
+/// Update state file name within ~/.helix
+pub const STATE_FILENAME: &str = "update-state.json";
+
+/// One data migration run after an update
+struct PostUpdateHook {
+    id: &'static str,
+    /// First app version that ships the hook
+    since: &'static str,
+    description: &'static str,
+    /// Migrates the data directory; must be safe to run on data it has
+    /// already migrated
+    run: fn(&Path) -> Result<(), String>,
+}
+
+/// Hooks, oldest first. Never edit a shipped hook; add a new one.
+const HOOKS: &[PostUpdateHook] = &[
+    PostUpdateHook {
+        id: "config-defaults",
+        since: "1.1.0",
+        description: "Write defaults for settings added to config.json",
+        run: migrate_config,
+    },
+    PostUpdateHook {
+        id: "scheduler-config-defaults",
+        since: "1.1.0",
+        description: "Write defaults for settings added to the scheduler config",
+        run: migrate_scheduler_config,
+    },
+];
+
+/// Add keys missing from `doc` with their value in `defaults`, recursing
+/// into objects. Existing values, including keys the app doesn't know,
+/// are left alone.
+fn fill_defaults(doc: &mut Value, defaults: &Value) {
+    let (Some(doc), Some(defaults)) = (doc.as_object_mut(), defaults.as_object()) else {
+        return;
+    };
+    for (key, default) in defaults {
+        match doc.get_mut(key) {
+            Some(value) => fill_defaults(value, default),
+            None => {
+                doc.insert(key.clone(), default.clone());
+            }
+        }
+    }
+}
+
+/// Rewrite a JSON settings file with the defaults `T` fills in
+fn fill_file_defaults<T>(path: &Path) -> Result<(), String>
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+
+    let mut doc: Value = serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))?;
+    let typed: T = serde_json::from_value(doc.clone()).map_err(|e| format!("Invalid {:?}: {}", path, e))?;
+    let defaults = serde_json::to_value(&typed).map_err(|e| e.to_string())?;
+
+    let before = doc.clone();
+    fill_defaults(&mut doc, &defaults);
+    if doc == before {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+}
+
+fn migrate_config(data_dir: &Path) -> Result<(), String> {
+    fill_file_defaults::<HelixConfig>(&data_dir.join("config.json"))
+}
+
+fn migrate_scheduler_config(data_dir: &Path) -> Result<(), String> {
+    fill_file_defaults::<SchedulerConfig>(&data_dir.join("config").join("scheduler.json"))
+}
+
+/// A hook that failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedHook {
+    pub id: String,
+    pub description: String,
+    pub error: String,
+}
+
+/// Outcome of the hooks run on a version's first launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostUpdateRun {
+    /// Version launched before; `None` if unknown
+    pub from_version: Option<String>,
+    pub to_version: String,
+    /// Ids of the hooks that completed
+    pub applied: Vec<String>,
+    pub failed: Option<FailedHook>,
+    /// Snapshot ~/.helix was restored from after a failure
+    pub rolled_back_to: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Persisted in ~/.helix/update-state.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UpdateState {
+    /// Version of the last launch
+    #[serde(default)]
+    last_version: Option<String>,
+    /// Snapshot taken before the update that is being installed
+    #[serde(default)]
+    snapshot: Option<Snapshot>,
+    #[serde(default)]
+    last_run: Option<PostUpdateRun>,
+}
+
+/// Post-update status reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct PostUpdateStatus {
+    pub last_run: Option<PostUpdateRun>,
+    /// Snapshots on disk, newest first
+    pub snapshots: Vec<Snapshot>,
+}
+
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(crate::accounts::profiles::helix_root()?.join(STATE_FILENAME))
+}
+
+fn load_state() -> UpdateState {
+    state_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &UpdateState) -> Result<(), String> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// "1.2.3" (pre-release and build suffixes ignored)
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next().flatten().unwrap_or(0), parts.next().flatten().unwrap_or(0)))
+}
+
+/// Hooks introduced after `from` up to and including `to`
+fn pending_hooks(from: Option<&str>, to: &str) -> Vec<&'static PostUpdateHook> {
+    let from = from.and_then(parse_version).unwrap_or((0, 0, 0));
+    let Some(to) = parse_version(to) else {
+        return Vec::new();
+    };
+
+    HOOKS
+        .iter()
+        .filter(|hook| parse_version(hook.since).is_some_and(|since| since > from && since <= to))
+        .collect()
+}
+
+/// Remember the snapshot taken before an update is installed
+pub fn record_snapshot(snapshot: Snapshot) -> Result<(), String> {
+    let mut state = load_state();
+    state.snapshot = Some(snapshot);
+    save_state(&state)
+}
+
+/// Run the hooks due on the first launch of this version. Must run after
+/// the active account is restored and before config init.
+pub fn run_pending(app: &AppHandle) {
+    let version = app.package_info().version.to_string();
+    let mut state = load_state();
+    if state.last_version.as_deref() == Some(version.as_str()) {
+        return;
+    }
+
+    let hooks = pending_hooks(state.last_version.as_deref(), &version);
+    let mut run = PostUpdateRun {
+        from_version: state.last_version.clone(),
+        to_version: version.clone(),
+        applied: Vec::new(),
+        failed: None,
+        rolled_back_to: None,
+        timestamp: current_timestamp(),
+    };
+
+    if !hooks.is_empty() {
+        match crate::accounts::data_dir() {
+            Ok(data_dir) => run_hooks(&hooks, &data_dir, &mut run, state.snapshot.as_ref()),
+            Err(e) => log::warn!("Post-update hooks skipped: {}", e),
+        }
+    }
+
+    log::info!(
+        "First launch of {} (previous: {}); {} post-update hook(s) applied",
+        version,
+        run.from_version.as_deref().unwrap_or("unknown"),
+        run.applied.len()
+    );
+    if run.failed.is_some() {
+        let _ = app.emit("update:migration-failed", run.clone());
+    }
+    // Not on a fresh install, which has no previous version
+    if let Some(from_version) = &run.from_version {
+        hooks::fire(
+            HookEvent::UpdateInstalled,
+            serde_json::json!({ "from_version": from_version, "to_version": run.to_version }),
+        );
+    }
+
+    // A failed hook is not retried: the restored data is what the old
+    // version left, which the new one reads with defaults
+    state.last_version = Some(version);
+    state.snapshot = None;
+    state.last_run = Some(run);
+    if let Err(e) = save_state(&state) {
+        log::warn!("Failed to save update state: {}", e);
+    }
+}
+
+fn run_hooks(hooks: &[&PostUpdateHook], data_dir: &Path, run: &mut PostUpdateRun, snapshot: Option<&Snapshot>) {
+    for hook in hooks {
+        if let Err(error) = (hook.run)(data_dir) {
+            log::error!("Post-update hook {} failed: {}", hook.id, error);
+            run.failed = Some(FailedHook {
+                id: hook.id.to_string(),
+                description: hook.description.to_string(),
+                error,
+            });
+            break;
+        }
+        log::info!("Post-update hook {} applied: {}", hook.id, hook.description);
+        run.applied.push(hook.id.to_string());
+    }
+
+    if run.failed.is_none() {
+        return;
+    }
+    match snapshot {
+        Some(snapshot) => match snapshot::restore(snapshot) {
+            Ok(()) => run.rolled_back_to = Some(snapshot.id.clone()),
+            Err(e) => log::error!("Failed to roll back to snapshot {}: {}", snapshot.id, e),
+        },
+        None => log::warn!("No pre-update snapshot to roll back to"),
+    }
+}
+
+// Tauri commands
+
+/// Outcome of the last post-update run and the snapshots available
+#[tauri::command]
+pub fn get_post_update_status() -> Result<PostUpdateStatus, CommandError> {
+    Ok(PostUpdateStatus {
+        last_run: load_state().last_run,
+        snapshots: snapshot::list(),
+    })
+}
@@ -1,9 +1,22 @@
 // Auto-updater module - handles application updates using Tauri updater
+//
+// Updates are downloaded by download.rs so they can be paused, resumed
+// and capped in speed. Installing one first snapshots ~/.helix; the new
+// version's first launch runs its post-update migration hooks and rolls
+// back to that snapshot if one fails.
+
+pub mod download;
+pub mod migrations;
+pub mod snapshot;
 
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_updater::UpdaterExt;
 
+use crate::tasks::TaskHandle;
+use crate::commands::{CommandError, ErrorCode};
+use download::Download;
+
 /// Update information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -22,6 +35,8 @@ pub enum UpdateStatus {
     Available { info: UpdateInfo },
     NotAvailable { current_version: String },
     Downloading { progress: f32 },
+    /// Download stopped part way; it continues from `downloaded` bytes
+    Paused { progress: f32, downloaded: u64, total: Option<u64> },
     ReadyToInstall,
     Installing,
     Error { message: String },
@@ -93,14 +108,16 @@ pub async fn check_for_updates(app_handle: &AppHandle) -> Result<Option<UpdateIn
     }
 }
 
-/// Download and install the update
-pub async fn download_and_install(app_handle: &AppHandle) -> Result<(), String> {
+/// Download and install the update, reporting progress on `task`.
+/// Returns false if the download was paused before it finished.
+pub async fn download_and_install(app_handle: &AppHandle, task: &TaskHandle) -> Result<bool, String> {
     // Get the updater
     let updater = app_handle
         .updater()
         .map_err(|e| format!("Failed to get updater: {}", e))?;
 
     // Check for updates first
+    task.progress("checking", None, None);
     let update = updater
         .check()
         .await
@@ -108,37 +125,48 @@ pub async fn download_and_install(app_handle: &AppHandle) -> Result<(), String>
         .ok_or_else(|| "No update available".to_string())?;
 
     // Emit downloading status
+    let resume_from = download::partial()
+        .filter(|partial| partial.version == update.version)
+        .map(|partial| partial.downloaded)
+        .unwrap_or(0);
     let _ = app_handle.emit("update:status", UpdateStatus::Downloading { progress: 0.0 });
+    task.progress(
+        "downloading",
+        None,
+        Some(if resume_from > 0 {
+            format!("Resuming {} at {} bytes", update.version, resume_from)
+        } else {
+            format!("Downloading {}", update.version)
+        }),
+    );
 
-    // Download and install with progress tracking
-    let app_handle_clone = app_handle.clone();
+    let limit_kbps = crate::commands::config::get_config()
+        .map(|config| config.updates.bandwidth_limit_kbps)
+        .unwrap_or_default();
+    let bytes = match download::fetch(app_handle, task, &update, limit_kbps).await? {
+        Download::Complete(bytes) => bytes,
+        Download::Paused => return Ok(false),
+    };
+    let _ = app_handle.emit("update:status", UpdateStatus::ReadyToInstall);
 
-    update
-        .download_and_install(
-            move |chunk_length, content_length| {
-                // Calculate progress
-                let progress = content_length
-                    .map(|total| (chunk_length as f32 / total as f32) * 100.0)
-                    .unwrap_or(0.0);
-
-                let _ = app_handle_clone.emit(
-                    "update:status",
-                    UpdateStatus::Downloading { progress },
-                );
-            },
-            || {
-                // Download complete, ready to install
-                log::info!("Update download complete, preparing to install");
-            },
-        )
+    // Snapshot ~/.helix so the new version can roll back a failed migration
+    task.progress("snapshot", None, Some("Backing up Helix data".to_string()));
+    let current_version = app_handle.package_info().version.to_string();
+    let snapshot = tauri::async_runtime::spawn_blocking(move || snapshot::create(&current_version))
         .await
-        .map_err(|e| format!("Failed to download/install update: {}", e))?;
+        .map_err(|e| format!("Snapshot task failed: {}", e))?
+        .map_err(|e| format!("Failed to snapshot Helix data before updating: {}", e))?;
+    migrations::record_snapshot(snapshot)?;
 
     // Emit installing status
     let _ = app_handle.emit("update:status", UpdateStatus::Installing);
+    task.progress("installing", Some(100.0), None);
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
 
     log::info!("Update installed successfully. Restart required.");
-    Ok(())
+    Ok(true)
 }
 
 /// Initialize updater on app startup
@@ -153,6 +181,26 @@ pub fn init(app_handle: &AppHandle) {
         if let Err(e) = check_for_updates(&handle).await {
             log::warn!("Startup update check failed: {}", e);
         }
+
+        // A download the user paused waits for resume_update; one cut
+        // short by quitting the app picks up where it stopped
+        match download::partial() {
+            Some(partial) if partial.paused => {
+                let _ = handle.emit("update:status", partial.paused_status());
+            }
+            Some(_) => {
+                log::info!("Resuming interrupted update download");
+                let state = handle.state::<crate::AppState>();
+                let body_handle = handle.clone();
+                let spawned = state.task_manager.spawn(handle.clone(), "update-download", None, |task| async move {
+                    download_task(&body_handle, &task).await
+                });
+                if let Err(e) = spawned {
+                    log::warn!("Failed to resume update download: {}", e);
+                }
+            }
+            None => {}
+        }
     });
 
     log::info!("Updater initialized");
@@ -162,14 +210,58 @@ pub fn init(app_handle: &AppHandle) {
 
 /// Check for application updates
 #[tauri::command]
-pub async fn check_for_update(app_handle: AppHandle) -> Result<Option<UpdateInfo>, String> {
-    check_for_updates(&app_handle).await
+pub async fn check_for_update(app_handle: AppHandle) -> Result<Option<UpdateInfo>, CommandError> {
+    check_for_updates(&app_handle).await.map_err(Into::into)
 }
 
 /// Download and install available update
 #[tauri::command]
-pub async fn install_update(app_handle: AppHandle) -> Result<(), String> {
-    download_and_install(&app_handle).await
+pub async fn install_update(
+    app_handle: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), CommandError> {
+    let handle = app_handle.clone();
+    state
+        .task_manager
+        .run(app_handle, "update-download", None, |task| async move {
+            download_task(&handle, &task).await
+        })
+        .await
+        .map(|_| ())
+}
+
+/// Body of the update-download task
+async fn download_task(app_handle: &AppHandle, task: &TaskHandle) -> Result<String, String> {
+    if download_and_install(app_handle, task).await? {
+        Ok("Update installed".to_string())
+    } else {
+        Ok("Update download paused".to_string())
+    }
+}
+
+/// Pause the running update download after its current chunk
+#[tauri::command]
+pub fn pause_update() -> Result<(), CommandError> {
+    if download::request_pause() {
+        Ok(())
+    } else {
+        Err(CommandError::new(ErrorCode::Conflict, "No update download in progress"))
+    }
+}
+
+/// Continue a paused or interrupted update download and install it
+#[tauri::command]
+pub async fn resume_update(
+    app_handle: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), CommandError> {
+    if download::is_downloading() {
+        return Err(CommandError::new(ErrorCode::Conflict, "Update download is already running"));
+    }
+    if download::partial().is_none() {
+        return Err(CommandError::not_found("No paused update download"));
+    }
+    install_update(app_handle, state).await
 }
 
 /// Get current application version
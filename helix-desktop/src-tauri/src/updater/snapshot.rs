@@ -0,0 +1,177 @@
+// Pre-update snapshots of ~/.helix
+//
+// install_update copies ~/.helix into ~/.helix/update-snapshots/<id>/ before
+// the new version is downloaded, so a post-update migration hook that fails
+// on first launch can put the data back the way the old version left it.
+// Regenerable runtimes are not copied, and only the newest KEEP_SNAPSHOTS
+// snapshots are kept.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Snapshot directory within ~/.helix
+pub const SNAPSHOTS_DIR: &str = "update-snapshots";
+const MANIFEST_FILENAME: &str = "snapshot.json";
+const KEEP_SNAPSHOTS: usize = 3;
+/// Separates the snapshot id from the timestamp in a restore's
+/// moved-aside directory, <id>.replaced-<ms>
+const REPLACED_MARKER: &str = ".replaced-";
+
+/// Entries of ~/.helix that are never copied into a snapshot
+const SKIPPED_ENTRIES: &[&str] = &[SNAPSHOTS_DIR, "runtime", "helix-runtime", "update-download"];
+
+/// Entries a restore leaves as they are: launch bookkeeping must describe
+/// this launch, not the one the snapshot was taken in
+const KEPT_ON_RESTORE: &[&str] = &["startup-state.json", super::migrations::STATE_FILENAME];
+
+/// A copy of ~/.helix taken before an update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Directory name under update-snapshots/
+    pub id: String,
+    /// App version that wrote the data
+    pub app_version: String,
+    pub created_at: u64,
+    pub size_bytes: u64,
+}
+
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn snapshots_dir() -> Result<PathBuf, String> {
+    Ok(crate::accounts::profiles::helix_root()?.join(SNAPSHOTS_DIR))
+}
+
+/// Copy `from` into `to` recursively, returning the bytes copied.
+/// Symlinks are skipped rather than followed out of ~/.helix.
+fn copy_dir(from: &Path, to: &Path, skip: &[&str]) -> Result<u64, String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {:?}: {}", to, e))?;
+
+    let mut copied = 0;
+    for entry in fs::read_dir(from).map_err(|e| format!("Failed to read {:?}: {}", from, e))? {
+        let entry = entry.map_err(|e| format!("Failed to read {:?}: {}", from, e))?;
+        if skip.iter().any(|s| entry.file_name() == *s) {
+            continue;
+        }
+
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        let destination = to.join(entry.file_name());
+        if file_type.is_dir() {
+            copied += copy_dir(&entry.path(), &destination, &[])?;
+        } else if file_type.is_file() {
+            copied += fs::copy(entry.path(), &destination)
+                .map_err(|e| format!("Failed to copy {:?}: {}", entry.path(), e))?;
+        }
+    }
+    Ok(copied)
+}
+
+/// Snapshot ~/.helix as written by `app_version`
+pub fn create(app_version: &str) -> Result<Snapshot, String> {
+    let root = crate::accounts::profiles::helix_root()?;
+    let created_at = current_timestamp();
+    let id = format!("{}-{}", app_version, created_at);
+    let dir = snapshots_dir()?.join(&id);
+
+    let size_bytes = match copy_dir(&root, &dir, SKIPPED_ENTRIES) {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+
+    let snapshot = Snapshot {
+        id,
+        app_version: app_version.to_string(),
+        created_at,
+        size_bytes,
+    };
+    let manifest = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot manifest: {}", e))?;
+    fs::write(dir.join(MANIFEST_FILENAME), manifest)
+        .map_err(|e| format!("Failed to write snapshot manifest: {}", e))?;
+
+    log::info!("Snapshot {} of ~/.helix taken ({} bytes)", snapshot.id, size_bytes);
+    prune();
+    Ok(snapshot)
+}
+
+/// Snapshots on disk, newest first
+pub fn list() -> Vec<Snapshot> {
+    let Ok(entries) = snapshots_dir().and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| fs::read_to_string(entry.path().join(MANIFEST_FILENAME)).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    snapshots
+}
+
+/// Drop all but the newest KEEP_SNAPSHOTS snapshots, along with the data
+/// a restore from any dropped snapshot moved aside
+fn prune() {
+    let Ok(dir) = snapshots_dir() else { return };
+    let mut snapshots = list();
+    let old = snapshots.split_off(snapshots.len().min(KEEP_SNAPSHOTS));
+    for snapshot in &old {
+        if let Err(e) = fs::remove_dir_all(dir.join(&snapshot.id)) {
+            log::warn!("Failed to remove old snapshot {}: {}", snapshot.id, e);
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(&dir) else { return };
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((id, _)) = name.split_once(REPLACED_MARKER) else { continue };
+        if snapshots.iter().any(|kept| kept.id == id) {
+            continue;
+        }
+        if let Err(e) = fs::remove_dir_all(entry.path()) {
+            log::warn!("Failed to remove replaced data {}: {}", name, e);
+        }
+    }
+}
+
+/// Put ~/.helix back as it was in `snapshot`. The data being replaced is
+/// moved aside to update-snapshots/<id>.replaced-<ms>/ rather than deleted.
+pub fn restore(snapshot: &Snapshot) -> Result<(), String> {
+    let root = crate::accounts::profiles::helix_root()?;
+    let source = snapshots_dir()?.join(&snapshot.id);
+    if !source.join(MANIFEST_FILENAME).exists() {
+        return Err(format!("Snapshot {} not found", snapshot.id));
+    }
+
+    let replaced = snapshots_dir()?.join(format!("{}{}{}", snapshot.id, REPLACED_MARKER, current_timestamp()));
+    fs::create_dir_all(&replaced).map_err(|e| format!("Failed to create {:?}: {}", replaced, e))?;
+
+    for entry in fs::read_dir(&root).map_err(|e| format!("Failed to read ~/.helix: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read ~/.helix: {}", e))?;
+        let name = entry.file_name();
+        if SKIPPED_ENTRIES.iter().chain(KEPT_ON_RESTORE).any(|s| name == *s) {
+            continue;
+        }
+        fs::rename(entry.path(), replaced.join(&name))
+            .map_err(|e| format!("Failed to move {:?} aside: {}", name, e))?;
+    }
+
+    let mut skip = vec![MANIFEST_FILENAME];
+    skip.extend(KEPT_ON_RESTORE);
+    copy_dir(&source, &root, &skip)?;
+
+    log::warn!(
+        "Restored ~/.helix from snapshot {}; replaced data kept in {:?}",
+        snapshot.id,
+        replaced
+    );
+    Ok(())
+}
@@ -0,0 +1,5 @@
+// Helix Desktop - Vault Module (encrypted psychology storage)
+
+pub mod store;
+
+pub use store::{is_enabled, lock, read_to_string, write};
@@ -0,0 +1,399 @@
+// Vault - optional at-rest encryption for psychology and soul files
+//
+// When enabled, files under the vault directories are stored as
+// MAGIC + 12-byte nonce + AES-256-GCM ciphertext and keep their names.
+// The key either lives in the OS keyring (unlocked automatically) or is
+// derived from a passphrase with Argon2id and held in memory after unlock.
+// Vault settings are per account, in <data dir>/.vault.json. Writes,
+// encrypted or not, go to a temporary file that then replaces the target.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::accounts;
+use crate::commands::CommandError;
+
+/// Header marking an encrypted file
+const MAGIC: &[u8] = b"HELIXENC1\n";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+/// Vault settings file within the data directory
+const VAULT_FILENAME: &str = ".vault.json";
+/// Known plaintext encrypted with the key, to verify a passphrase
+const KEY_CHECK_PLAINTEXT: &[u8] = b"helix-vault-check";
+const KEYRING_SERVICE: &str = "helix-desktop";
+/// Directories within the data directory covered by the vault; queued
+/// guardrail writes hold soul content and migration backups hold copies of
+/// layer files
+pub const VAULT_DIRS: &[&str] = &[
+    "soul",
+    "psychology",
+    "identity",
+    "transformation",
+    "purpose",
+    "guardrails/pending",
+    "migrations/backups",
+];
+/// Error from reads and writes while a passphrase vault is locked
+pub const LOCKED_MESSAGE: &str = "Vault is locked; unlock it with your passphrase";
+
+/// Where the vault key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultMode {
+    Keyring,
+    Passphrase,
+}
+
+/// Persisted vault settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultConfig {
+    mode: VaultMode,
+    /// Argon2 salt (hex), passphrase mode only
+    #[serde(default)]
+    salt: Option<String>,
+    /// MAGIC-less encryption of KEY_CHECK_PLAINTEXT (hex)
+    key_check: String,
+}
+
+/// Vault status for the settings screen
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    pub enabled: bool,
+    pub mode: Option<VaultMode>,
+    pub unlocked: bool,
+    /// Files under the vault directories still stored in plaintext
+    pub plaintext_files: usize,
+}
+
+/// Key of the active account's vault, once unlocked
+static KEY: Mutex<Option<[u8; KEY_LEN]>> = Mutex::new(None);
+
+fn vault_config_path() -> Result<PathBuf, String> {
+    Ok(accounts::data_dir()?.join(VAULT_FILENAME))
+}
+
+fn load_vault_config() -> Option<VaultConfig> {
+    vault_config_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save_vault_config(config: &VaultConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize vault settings: {}", e))?;
+    write_atomic(&vault_config_path()?, json.as_bytes())
+}
+
+/// Keyring entry name for the active account's vault key
+fn keyring_key_name() -> String {
+    match accounts::profiles::active_user_id() {
+        Some(user_id) => format!("vault-key-{}", user_id),
+        None => "vault-key".to_string(),
+    }
+}
+
+fn keyring_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, &keyring_key_name())
+        .map_err(|e| format!("Failed to create keyring entry: {}", e))
+}
+
+fn load_keyring_key() -> Result<[u8; KEY_LEN], String> {
+    let stored = keyring_entry()?
+        .get_password()
+        .map_err(|e| format!("Failed to read vault key from keyring: {}", e))?;
+    let bytes = hex::decode(stored.trim())
+        .map_err(|e| format!("Invalid vault key in keyring: {}", e))?;
+
+    bytes
+        .try_into()
+        .map_err(|_| "Invalid vault key length in keyring".to_string())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN], String> {
+    let mut key = [0u8; KEY_LEN];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive vault key: {}", e))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce_bytes: [u8; NONCE_LEN] = rand::random();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce_bytes);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &[u8; KEY_LEN], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("Encrypted data is truncated".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Decryption failed (wrong key or corrupted file)".to_string())
+}
+
+fn verify_key(config: &VaultConfig, key: &[u8; KEY_LEN]) -> bool {
+    hex::decode(&config.key_check)
+        .ok()
+        .and_then(|check| open(key, &check).ok())
+        .is_some_and(|plain| plain == KEY_CHECK_PLAINTEXT)
+}
+
+/// The vault key, unlocking from the keyring if needed.
+/// `None` when the vault is disabled.
+fn current_key() -> Result<Option<[u8; KEY_LEN]>, String> {
+    let Some(config) = load_vault_config() else {
+        return Ok(None);
+    };
+
+    if let Some(key) = *KEY.lock().map_err(|e| e.to_string())? {
+        return Ok(Some(key));
+    }
+
+    match config.mode {
+        VaultMode::Keyring => {
+            let key = load_keyring_key()?;
+            if !verify_key(&config, &key) {
+                return Err("Vault key in keyring does not match this vault".to_string());
+            }
+            *KEY.lock().map_err(|e| e.to_string())? = Some(key);
+            Ok(Some(key))
+        }
+        VaultMode::Passphrase => Err(LOCKED_MESSAGE.to_string()),
+    }
+}
+
+/// Whether the active account's vault is enabled
+pub fn is_enabled() -> bool {
+    load_vault_config().is_some()
+}
+
+/// Whether `content` carries the encrypted-file header
+pub fn is_encrypted(content: &[u8]) -> bool {
+    content.starts_with(MAGIC)
+}
+
+/// Read a file as text, decrypting it if it is encrypted
+pub fn read_to_string(path: &Path) -> Result<String, String> {
+    let raw = fs::read(path).map_err(|e| e.to_string())?;
+
+    let plain = if is_encrypted(&raw) {
+        let key = current_key()?
+            .ok_or_else(|| "File is encrypted but no vault is configured".to_string())?;
+        open(&key, &raw[MAGIC.len()..])?
+    } else {
+        raw
+    };
+
+    String::from_utf8(plain).map_err(|e| format!("File is not valid UTF-8: {}", e))
+}
+
+/// Write a file, encrypting it when the vault is enabled
+pub fn write(path: &Path, content: &str) -> Result<(), String> {
+    match current_key()? {
+        Some(key) => {
+            let mut data = MAGIC.to_vec();
+            data.extend(seal(&key, content.as_bytes())?);
+            write_atomic(path, &data)
+        }
+        None => write_atomic(path, content.as_bytes()),
+    }
+}
+
+/// Write via a temporary file so a crash never leaves a half-written file.
+/// The temporary name is unique so concurrent writers don't share one.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Not a file path: {:?}", path))?
+        .to_string_lossy();
+    let tmp = path.with_file_name(format!(".{}.{:08x}.vault-tmp", file_name, rand::random::<u32>()));
+
+    let written = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .map_err(|e| format!("Failed to write {:?}: {}", tmp, e))
+        .and_then(|()| fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e)));
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// All regular files under the vault directories
+fn vault_files(data_dir: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, files);
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for dir in VAULT_DIRS {
+        walk(&data_dir.join(dir), &mut files);
+    }
+    files
+}
+
+fn is_plaintext_file(path: &Path) -> bool {
+    fs::read(path).map(|raw| !is_encrypted(&raw)).unwrap_or(false)
+}
+
+/// Clear the in-memory key (also used on account switch)
+pub fn lock() {
+    if let Ok(mut key) = KEY.lock() {
+        *key = None;
+    }
+}
+
+/// Destroy the vault key and settings, leaving any encrypted files unreadable
+pub fn destroy() -> Result<(), String> {
+    lock();
+
+    if let Ok(entry) = keyring_entry() {
+        match entry.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(format!("Failed to delete vault key: {}", e)),
+        }
+    }
+
+    let path = vault_config_path()?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove vault settings: {}", e))?;
+    }
+    Ok(())
+}
+
+// Tauri commands for the vault
+
+/// Get the vault status of the active account
+#[tauri::command]
+pub fn get_vault_status() -> Result<VaultStatus, CommandError> {
+    let config = load_vault_config();
+    let unlocked = config.is_some() && current_key().is_ok_and(|k| k.is_some());
+    let data_dir = accounts::data_dir()?;
+
+    Ok(VaultStatus {
+        enabled: config.is_some(),
+        mode: config.map(|c| c.mode),
+        unlocked,
+        plaintext_files: vault_files(&data_dir)
+            .iter()
+            .filter(|p| is_plaintext_file(p))
+            .count(),
+    })
+}
+
+/// Enable the vault (if needed) and encrypt every plaintext file under the
+/// vault directories. Safe to re-run; already encrypted files are skipped.
+#[tauri::command]
+pub fn encrypt_psychology_data(
+    mode: VaultMode,
+    passphrase: Option<String>,
+) -> Result<VaultStatus, CommandError> {
+    let data_dir = accounts::data_dir()?;
+
+    if !is_enabled() {
+        let (key, salt) = match mode {
+            VaultMode::Keyring => {
+                let key: [u8; KEY_LEN] = rand::random();
+                keyring_entry()?
+                    .set_password(&hex::encode(key))
+                    .map_err(|e| format!("Failed to store vault key in keyring: {}", e))?;
+                (key, None)
+            }
+            VaultMode::Passphrase => {
+                let passphrase = passphrase
+                    .filter(|p| !p.is_empty())
+                    .ok_or_else(|| "A passphrase is required".to_string())?;
+                let salt: [u8; SALT_LEN] = rand::random();
+                (derive_key(&passphrase, &salt)?, Some(hex::encode(salt)))
+            }
+        };
+
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        save_vault_config(&VaultConfig {
+            mode,
+            salt,
+            key_check: hex::encode(seal(&key, KEY_CHECK_PLAINTEXT)?),
+        })?;
+        *KEY.lock().map_err(|e| e.to_string())? = Some(key);
+        log::info!("Vault enabled ({:?} mode)", mode);
+    }
+
+    let mut encrypted = 0;
+    for path in vault_files(&data_dir) {
+        if !is_plaintext_file(&path) {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        write(&path, &content)?;
+        encrypted += 1;
+    }
+
+    log::info!("Encrypted {} psychology files", encrypted);
+    get_vault_status()
+}
+
+/// Unlock a passphrase vault for this session
+#[tauri::command]
+pub fn unlock_vault(passphrase: String) -> Result<VaultStatus, CommandError> {
+    let config = load_vault_config().ok_or_else(|| "Vault is not enabled".to_string())?;
+
+    let key = match config.mode {
+        VaultMode::Keyring => load_keyring_key()?,
+        VaultMode::Passphrase => {
+            let salt = config
+                .salt
+                .as_deref()
+                .and_then(|s| hex::decode(s).ok())
+                .ok_or_else(|| "Vault settings are missing the salt".to_string())?;
+            derive_key(&passphrase, &salt)?
+        }
+    };
+
+    if !verify_key(&config, &key) {
+        return Err(CommandError::invalid_input("Incorrect passphrase"));
+    }
+
+    *KEY.lock().map_err(|e| e.to_string())? = Some(key);
+    get_vault_status()
+}
+
+/// Forget the in-memory key; passphrase vaults need unlocking again
+#[tauri::command]
+pub fn lock_vault() -> Result<VaultStatus, CommandError> {
+    lock();
+    get_vault_status()
+}
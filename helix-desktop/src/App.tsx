@@ -4,8 +4,11 @@ import { useSystem } from './hooks/useSystem';
 import { useGateway } from './hooks/useGateway';
 import { useTheme } from './hooks/useTheme';
 import { useDeepLink } from './hooks/useDeepLink';
-import { Onboarding } from './components/onboarding/Onboarding';
+import { Onboarding, ONBOARDING_STORAGE_KEY } from './components/onboarding/Onboarding';
 import { TenantProvider } from './lib/tenant-context';
+import { clearStoredAuth } from './lib/auth-context';
+import { getSupabaseDesktopClient } from './lib/supabase-desktop-client';
+import { listen } from './lib/tauri-compat';
 import { router } from './routes';
 import './App.css';
 
@@ -46,6 +49,26 @@ function App() {
     return () => window.removeEventListener('keydown', handleKeyDown);
   }, []);
 
+  // Signed out from another device: the backend has dropped its session,
+  // so forget ours too and start over at sign-in
+  useEffect(() => {
+    const unlisten = listen('account:revoked', async () => {
+      clearStoredAuth();
+      localStorage.removeItem(ONBOARDING_STORAGE_KEY);
+      try {
+        await getSupabaseDesktopClient()?.signOut();
+      } catch (err) {
+        console.warn('Failed to end the Supabase session:', err);
+      }
+      setShowOnboarding(true);
+      setAutoTriggeredOnboarding(true);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Determine if we should show onboarding (with safeguard)
   useEffect(() => {
     if (isFirstRun !== null && !showOnboarding && !autoTriggeredOnboarding) {
@@ -0,0 +1,123 @@
+/**
+ * Workspace Trust Prompt
+ *
+ * Asks before the psychology scripts in a workspace run for the first
+ * time, and again whenever they change. The backend raises
+ * `workspace-trust:required` when it refuses a run (see
+ * src-tauri/src/policy/workspace_trust.rs); trusting records the
+ * fingerprint of the scripts exactly as listed here.
+ */
+
+import { useEffect, useState } from 'react';
+import { invoke, listen } from '../../lib/tauri-compat';
+import { toCommandError } from '../../lib/command-error';
+import { Modal } from './Modal';
+import { Button } from './Button';
+
+export interface WorkspaceTrust {
+  workspace: string;
+  scripts_dir: string;
+  state: 'trusted' | 'untrusted' | 'changed';
+  fingerprint: string;
+  trusted_at: number | null;
+  scripts: string[];
+  added: string[];
+  modified: string[];
+  removed: string[];
+}
+
+function FileList({ label, files }: { label: string; files: string[] }) {
+  if (files.length === 0) return null;
+  return (
+    <div className="workspace-trust__files">
+      <strong>{label}</strong>
+      <ul>
+        {files.map((file) => (
+          <li key={file}>
+            <code>{file}</code>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
+
+export function WorkspaceTrustPrompt() {
+  const [request, setRequest] = useState<WorkspaceTrust | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<WorkspaceTrust>('workspace-trust:required', (event) => {
+      setRequest(event.payload);
+      setError(null);
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  const close = () => setRequest(null);
+
+  const trust = async () => {
+    if (!request) return;
+    setSaving(true);
+    try {
+      await invoke('trust_workspace', { fingerprint: request.fingerprint });
+      setRequest(null);
+    } catch (err) {
+      const commandError = toCommandError(err);
+      setError(commandError.message);
+      // The scripts changed under the prompt; show them as they are now
+      if (commandError.code === 'conflict') {
+        invoke<WorkspaceTrust>('get_workspace_trust').then(setRequest).catch(() => undefined);
+      }
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const changed = request?.state === 'changed';
+
+  return (
+    <Modal
+      isOpen={request !== null}
+      onClose={close}
+      title={changed ? 'Workspace scripts changed' : 'Trust workspace scripts?'}
+      footer={
+        <>
+          <Button variant="ghost" onClick={close} disabled={saving}>
+            Not now
+          </Button>
+          <Button variant="primary" onClick={trust} loading={saving}>
+            Trust these scripts
+          </Button>
+        </>
+      }
+    >
+      {request && (
+        <div className="workspace-trust">
+          <p>
+            {changed
+              ? 'The scripts Helix runs for memory decay and synthesis changed since you trusted them. They run with your permissions, so only trust them if you know where the changes came from.'
+              : 'Helix runs these scripts for memory decay and synthesis, with your permissions. Only trust them if you know where they came from.'}
+          </p>
+          <p>
+            <code>{request.scripts_dir}</code>
+          </p>
+          {changed ? (
+            <>
+              <FileList label="Added" files={request.added} />
+              <FileList label="Modified" files={request.modified} />
+              <FileList label="Removed" files={request.removed} />
+            </>
+          ) : (
+            <FileList label="Scripts" files={request.scripts} />
+          )}
+          {error && <p className="workspace-trust__error">{error}</p>}
+        </div>
+      )}
+    </Modal>
+  );
+}
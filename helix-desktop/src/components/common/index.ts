@@ -36,3 +36,4 @@ export {
   type CommandAction,
 } from './CommandPalette';
 export { UpdateNotification } from './UpdateNotification';
+export { WorkspaceTrustPrompt, type WorkspaceTrust } from './WorkspaceTrustPrompt';
@@ -5,6 +5,7 @@ import { TitleBar } from './TitleBar';
 import { NavSidebar } from './NavSidebar';
 import { StatusBar } from './StatusBar';
 import { CommandPalette } from '../common/CommandPalette';
+import { WorkspaceTrustPrompt } from '../common/WorkspaceTrustPrompt';
 import { useGatewayConfig } from '../../hooks/useGatewayConfig';
 import { useDeepLink } from '../../hooks/useDeepLink';
 import { useTraySync } from '../../hooks/useTraySync';
@@ -85,6 +86,7 @@ export function AppLayout({ children }: AppLayoutProps) {
         isOpen={commandPaletteOpen}
         onClose={closeCommandPalette}
       />
+      <WorkspaceTrustPrompt />
     </div>
   );
 }
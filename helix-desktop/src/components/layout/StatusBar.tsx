@@ -1,8 +1,33 @@
-import { useGateway } from '../../hooks/useGateway';
+import { useState } from 'react';
+import { useGateway, handshakeAction, type GatewayHandshakeAction } from '../../hooks/useGateway';
+import { useConnectivity } from '../../hooks/useConnectivity';
 import './StatusBar.css';
 
+/** What to tell the user when the gateway failed the handshake */
+const ACTION_LABELS: Record<GatewayHandshakeAction, string> = {
+  update_runtime: 'Gateway runtime is out of date',
+  update_helix: 'Update Helix to use this gateway',
+  update_remote_gateway: 'Update helix-runtime on the remote gateway',
+  check_token: "Gateway rejected this device's token",
+  install_node: 'Install Node.js to start the gateway',
+};
+
 export function StatusBar() {
-  const { status, connected } = useGateway();
+  const { status, connected, health, startError, updateRuntime } = useGateway();
+  const { status: connectivity, online } = useConnectivity();
+  const [updating, setUpdating] = useState(false);
+  const action = connected ? null : handshakeAction(startError);
+
+  const handleUpdateRuntime = async () => {
+    setUpdating(true);
+    try {
+      await updateRuntime();
+    } catch (error) {
+      console.error('Failed to update helix-runtime:', error);
+    } finally {
+      setUpdating(false);
+    }
+  };
 
   return (
     <div className="status-bar">
@@ -14,8 +39,38 @@ export function StatusBar() {
         {status.port && (
           <span className="status-port">Port: {status.port}</span>
         )}
+        {status.running && health?.latency_ms != null && (
+          <span
+            className="status-port"
+            title={health.version ? `Gateway ${health.version}` : undefined}
+          >
+            {health.latency_ms}ms
+          </span>
+        )}
+        {action && (
+          <span className="status-gateway-action" title={String(startError)}>
+            {ACTION_LABELS[action]}
+            {action === 'update_runtime' && (
+              <button
+                className="status-action-button"
+                onClick={handleUpdateRuntime}
+                disabled={updating}
+              >
+                {updating ? 'Updating...' : 'Update'}
+              </button>
+            )}
+          </span>
+        )}
       </div>
       <div className="status-bar-right">
+        {!online && (
+          <span
+            className="status-offline"
+            title={connectivity?.reason ?? 'No network connection'}
+          >
+            Offline: cloud sync paused
+          </span>
+        )}
         <span className="status-version">Helix Desktop</span>
       </div>
     </div>
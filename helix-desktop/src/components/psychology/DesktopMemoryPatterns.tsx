@@ -1,103 +1,64 @@
 /**
  * Desktop Memory Patterns Component
  * Tauri-based version of web MemoryPatterns page
- * Uses Tauri IPC for backend communication
+ * Pages through the account's stored syntheses with useSyntheses; the type
+ * filter runs on the server, search and sort on the loaded pages
  */
 
-import React, { useState, useEffect } from 'react';
-import { invoke } from '@tauri-apps/api/core';
-import { Brain, Heart, Users, Target, TrendingUp, Search, Filter, AlertCircle } from 'lucide-react';
+import React, { useState } from 'react';
+import { Brain, Heart, Frown, Clock, Network, Search, Filter, AlertCircle } from 'lucide-react';
+import { useSyntheses, type StoredSynthesis } from '../../hooks/useSyntheses';
+
+/** Stored as `{category}_{pattern_type}` by memory-synthesis */
+const patternTypes = [
+  { value: 'emotional_emotional_positive', label: 'Positive Emotions', icon: Heart },
+  { value: 'emotional_emotional_negative', label: 'Difficult Emotions', icon: Frown },
+  { value: 'temporal_temporal_cluster', label: 'Time Clusters', icon: Clock },
+  { value: 'semantic_semantic_cluster', label: 'Related Topics', icon: Network },
+];
+
+const patternStyles: Record<string, { icon: React.ReactNode; color: string }> = {
+  emotional_emotional_positive: {
+    icon: <Heart className="w-5 h-5 text-green-400" />,
+    color: 'bg-green-500/10 border-green-500/20',
+  },
+  emotional_emotional_negative: {
+    icon: <Frown className="w-5 h-5 text-red-400" />,
+    color: 'bg-red-500/10 border-red-500/20',
+  },
+  temporal_temporal_cluster: {
+    icon: <Clock className="w-5 h-5 text-blue-400" />,
+    color: 'bg-blue-500/10 border-blue-500/20',
+  },
+  semantic_semantic_cluster: {
+    icon: <Network className="w-5 h-5 text-indigo-400" />,
+    color: 'bg-indigo-500/10 border-indigo-500/20',
+  },
+};
 
-interface Pattern {
-  patternId: string;
-  type:
-    | 'emotional_trigger'
-    | 'relational_pattern'
-    | 'prospective_fear'
-    | 'prospective_possibility'
-    | 'transformation_trajectory'
-    | 'purpose_alignment';
-  description: string;
-  confidence: number;
-  salience: number;
-  recommendations: string[];
-  evidence: string[];
-}
+const defaultStyle = {
+  icon: <Brain className="w-5 h-5 text-purple-400" />,
+  color: 'bg-purple-500/10 border-purple-500/20',
+};
 
 export const DesktopMemoryPatterns: React.FC = () => {
-  const [patterns, setPatterns] = useState<Pattern[]>([]);
-  const [loading, setLoading] = useState(true);
   const [filterType, setFilterType] = useState<string | null>(null);
   const [searchQuery, setSearchQuery] = useState('');
-  const [sortBy, setSortBy] = useState<'confidence' | 'salience' | 'recent'>('salience');
-
-  useEffect(() => {
-    loadPatterns();
-  }, []);
-
-  const loadPatterns = async () => {
-    try {
-      setLoading(true);
-      // Tauri IPC call to backend
-      const data = await invoke<Pattern[]>('get_memory_patterns', {});
-      setPatterns(data);
-    } catch (error) {
-      console.error('Failed to load patterns:', error);
-    } finally {
-      setLoading(false);
-    }
-  };
-
-  const getPatternIcon = (type: Pattern['type']) => {
-    const iconMap: Record<Pattern['type'], React.ReactNode> = {
-      emotional_trigger: <Heart className="w-5 h-5 text-red-400" />,
-      relational_pattern: <Users className="w-5 h-5 text-blue-400" />,
-      prospective_fear: <AlertCircle className="w-5 h-5 text-orange-400" />,
-      prospective_possibility: <Target className="w-5 h-5 text-green-400" />,
-      transformation_trajectory: <TrendingUp className="w-5 h-5 text-purple-400" />,
-      purpose_alignment: <Brain className="w-5 h-5 text-indigo-400" />,
-    };
-    return iconMap[type];
-  };
-
-  const getPatternColor = (type: Pattern['type']) => {
-    const colorMap: Record<Pattern['type'], string> = {
-      emotional_trigger: 'bg-red-500/10 border-red-500/20',
-      relational_pattern: 'bg-blue-500/10 border-blue-500/20',
-      prospective_fear: 'bg-orange-500/10 border-orange-500/20',
-      prospective_possibility: 'bg-green-500/10 border-green-500/20',
-      transformation_trajectory: 'bg-purple-500/10 border-purple-500/20',
-      purpose_alignment: 'bg-indigo-500/10 border-indigo-500/20',
-    };
-    return colorMap[type];
-  };
-
-  const filteredPatterns = patterns
-    .filter(p => !filterType || p.type === filterType)
-    .filter(p => !searchQuery || p.description.toLowerCase().includes(searchQuery.toLowerCase()))
-    .sort((a, b) => {
-      switch (sortBy) {
-        case 'confidence':
-          return b.confidence - a.confidence;
-        case 'salience':
-          return b.salience - a.salience;
-        case 'recent':
-          return 0;
-        default:
-          return 0;
-      }
-    });
-
-  const patternTypes = [
-    { value: 'emotional_trigger', label: 'Emotional Triggers', icon: Heart },
-    { value: 'relational_pattern', label: 'Relational Patterns', icon: Users },
-    { value: 'prospective_fear', label: 'Fears', icon: AlertCircle },
-    { value: 'prospective_possibility', label: 'Goals & Possibilities', icon: Target },
-    { value: 'transformation_trajectory', label: 'Growth Trajectories', icon: TrendingUp },
-    { value: 'purpose_alignment', label: 'Purpose Alignment', icon: Brain },
-  ];
+  const [sortBy, setSortBy] = useState<'confidence' | 'recent'>('recent');
+
+  const { items, total, hasMore, loading, error, loadMore, refresh } = useSyntheses({
+    patternType: filterType ?? undefined,
+  });
+
+  const filteredPatterns = items
+    .filter(p => !searchQuery || p.synthesisContent.toLowerCase().includes(searchQuery.toLowerCase()))
+    .sort((a, b) =>
+      sortBy === 'confidence'
+        ? b.confidenceScore - a.confidenceScore
+        : b.createdAt.localeCompare(a.createdAt)
+    );
 
-  if (loading) {
+  if (loading && items.length === 0) {
     return (
       <div className="flex items-center justify-center h-full">
         <div className="text-center">
@@ -120,12 +81,13 @@ export const DesktopMemoryPatterns: React.FC = () => {
                 Your Memory Patterns
               </h1>
               <p className="text-gray-400 mt-2">
-                Insights from {patterns.length} detected patterns across your memories
+                Insights from {total} detected patterns across your memories
               </p>
             </div>
             <button
-              onClick={loadPatterns}
-              className="px-4 py-2 bg-purple-600 hover:bg-purple-700 text-white rounded-lg transition"
+              onClick={refresh}
+              disabled={loading}
+              className="px-4 py-2 bg-purple-600 hover:bg-purple-700 disabled:opacity-50 text-white rounded-lg transition"
             >
               Refresh
             </button>
@@ -138,7 +100,7 @@ export const DesktopMemoryPatterns: React.FC = () => {
               <Search className="absolute left-3 top-3 w-5 h-5 text-gray-500" />
               <input
                 type="text"
-                placeholder="Search patterns..."
+                placeholder="Search loaded patterns..."
                 value={searchQuery}
                 onChange={e => setSearchQuery(e.target.value)}
                 className="w-full pl-10 pr-4 py-2 bg-slate-800 border border-slate-700 rounded-lg text-white placeholder-gray-500 focus:border-purple-500 focus:outline-none"
@@ -177,12 +139,11 @@ export const DesktopMemoryPatterns: React.FC = () => {
               <Filter className="w-5 h-5 text-gray-500" />
               <select
                 value={sortBy}
-                onChange={e => setSortBy(e.target.value as 'confidence' | 'salience' | 'recent')}
+                onChange={e => setSortBy(e.target.value as 'confidence' | 'recent')}
                 className="bg-slate-800 border border-slate-700 rounded-lg px-3 py-1 text-white text-sm focus:border-purple-500 focus:outline-none"
               >
-                <option value="salience">Sort by Importance</option>
-                <option value="confidence">Sort by Confidence</option>
                 <option value="recent">Sort by Recent</option>
+                <option value="confidence">Sort by Confidence</option>
               </select>
             </div>
           </div>
@@ -191,6 +152,13 @@ export const DesktopMemoryPatterns: React.FC = () => {
 
       {/* Patterns Grid */}
       <div className="max-w-6xl mx-auto p-6">
+        {error && (
+          <div className="flex items-center gap-2 mb-4 p-3 rounded-lg bg-red-500/10 border border-red-500/20 text-red-300 text-sm">
+            <AlertCircle className="w-4 h-4 flex-shrink-0" />
+            <span>Failed to load patterns: {error}</span>
+          </div>
+        )}
+
         {filteredPatterns.length === 0 ? (
           <div className="text-center py-12">
             <Brain className="w-12 h-12 text-gray-600 mx-auto mb-4" />
@@ -199,28 +167,35 @@ export const DesktopMemoryPatterns: React.FC = () => {
         ) : (
           <div className="grid gap-4">
             {filteredPatterns.map(pattern => (
-              <PatternCard
-                key={pattern.patternId}
-                pattern={pattern}
-                icon={getPatternIcon(pattern.type)}
-                color={getPatternColor(pattern.type)}
-              />
+              <PatternCard key={pattern.id} pattern={pattern} />
             ))}
           </div>
         )}
+
+        {hasMore && (
+          <div className="flex justify-center mt-6">
+            <button
+              onClick={loadMore}
+              disabled={loading}
+              className="px-4 py-2 bg-slate-800 hover:bg-slate-700 disabled:opacity-50 text-gray-300 rounded-lg text-sm transition"
+            >
+              {loading ? 'Loading...' : `Load more (${items.length} of ${total})`}
+            </button>
+          </div>
+        )}
       </div>
     </div>
   );
 };
 
 interface PatternCardProps {
-  pattern: Pattern;
-  icon: React.ReactNode;
-  color: string;
+  pattern: StoredSynthesis;
 }
 
-const PatternCard: React.FC<PatternCardProps> = ({ pattern, icon, color }) => {
+const PatternCard: React.FC<PatternCardProps> = ({ pattern }) => {
   const [expanded, setExpanded] = useState(false);
+  const { icon, color } = patternStyles[pattern.patternType] ?? defaultStyle;
+  const label = patternTypes.find(t => t.value === pattern.patternType)?.label ?? pattern.patternType;
 
   return (
     <div className={`border rounded-lg p-4 transition ${color}`}>
@@ -229,8 +204,10 @@ const PatternCard: React.FC<PatternCardProps> = ({ pattern, icon, color }) => {
           <div className="flex items-center gap-3 mb-2">
             {icon}
             <div>
-              <h3 className="font-semibold text-white">{pattern.description}</h3>
-              <p className="text-xs text-gray-400 mt-1">Pattern ID: {pattern.patternId}</p>
+              <h3 className="font-semibold text-white">{pattern.synthesisContent}</h3>
+              <p className="text-xs text-gray-400 mt-1">
+                {label} · {new Date(pattern.createdAt).toLocaleDateString()}
+              </p>
             </div>
           </div>
         </div>
@@ -250,22 +227,10 @@ const PatternCard: React.FC<PatternCardProps> = ({ pattern, icon, color }) => {
             <div className="w-24 h-2 bg-slate-700 rounded-full overflow-hidden">
               <div
                 className="h-full bg-purple-500"
-                style={{ width: `${pattern.confidence * 100}%` }}
+                style={{ width: `${pattern.confidenceScore * 100}%` }}
               />
             </div>
-            <span className="text-white font-semibold">{(pattern.confidence * 100).toFixed(0)}%</span>
-          </div>
-        </div>
-        <div>
-          <p className="text-gray-400">Importance</p>
-          <div className="flex items-center gap-2 mt-1">
-            <div className="w-24 h-2 bg-slate-700 rounded-full overflow-hidden">
-              <div
-                className="h-full bg-amber-500"
-                style={{ width: `${pattern.salience * 100}%` }}
-              />
-            </div>
-            <span className="text-white font-semibold">{(pattern.salience * 100).toFixed(0)}%</span>
+            <span className="text-white font-semibold">{(pattern.confidenceScore * 100).toFixed(0)}%</span>
           </div>
         </div>
       </div>
@@ -273,28 +238,8 @@ const PatternCard: React.FC<PatternCardProps> = ({ pattern, icon, color }) => {
       {/* Expanded Details */}
       {expanded && (
         <div className="mt-4 pt-4 border-t border-gray-500/30">
-          {/* Recommendations */}
-          {pattern.recommendations.length > 0 && (
-            <div className="mb-4">
-              <h4 className="font-semibold text-white mb-2">💡 Suggestions</h4>
-              <ul className="space-y-2">
-                {pattern.recommendations.map((rec, i) => (
-                  <li key={i} className="text-sm text-gray-300 flex gap-2">
-                    <span className="text-purple-400">•</span>
-                    <span>{rec}</span>
-                  </li>
-                ))}
-              </ul>
-            </div>
-          )}
-
-          {/* Evidence */}
-          {pattern.evidence.length > 0 && (
-            <div>
-              <h4 className="font-semibold text-white mb-2">📚 Supporting Memories</h4>
-              <p className="text-xs text-gray-400">Based on {pattern.evidence.length} memories</p>
-            </div>
-          )}
+          <h4 className="font-semibold text-white mb-2">📚 Supporting Memories</h4>
+          <p className="text-xs text-gray-400">Based on {pattern.memoryIds.length} memories</p>
         </div>
       )}
     </div>
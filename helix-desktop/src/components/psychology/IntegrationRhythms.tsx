@@ -0,0 +1,185 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, listen } from '../../lib/tauri-compat';
+import { useJobOutput } from '../../hooks/useJobOutput';
+import './IntegrationRhythms.css';
+
+export type SchedulerJobStatus =
+  | 'pending'
+  | 'running'
+  | 'completed'
+  | 'failed'
+  | 'paused'
+  | 'deferred'
+  | 'waiting'
+  | 'archived';
+
+export interface SchedulerJobRun {
+  number: number;
+  started_at: number;
+  finished_at: number | null;
+  status: SchedulerJobStatus;
+  error: string | null;
+  duration_ms: number | null;
+  exit_code: number | null;
+  stdout: string | null;
+  stderr: string | null;
+}
+
+export interface SchedulerJob {
+  id: string;
+  job_type: string;
+  status: SchedulerJobStatus;
+  next_run: number;
+  last_run: number | null;
+  duration_ms: number | null;
+  error: string | null;
+  runs: SchedulerJobRun[];
+}
+
+const JOB_LABELS: Record<string, string> = {
+  consolidation: 'Memory consolidation',
+  synthesis: 'Synthesis',
+  full_integration: 'Full integration',
+  memory_fadeout: 'Memory decay',
+  pattern_analysis: 'Pattern analysis',
+  recommendation_generation: 'Recommendations',
+  goal_reminder: 'Goal reminders',
+  custom: 'Macro',
+};
+
+/** Job statuses worth listening for, besides the job running */
+const REFRESH_EVENTS = ['scheduler:job-timeout', 'scheduler:job-failure-alert', 'scheduler:job-recovered'];
+const REFRESH_INTERVAL_MS = 10_000;
+
+function formatTime(secs: number | null): string {
+  return secs ? new Date(secs * 1000).toLocaleString() : '—';
+}
+
+function formatDuration(ms: number | null): string {
+  if (ms === null) return '—';
+  return ms < 1000 ? `${ms}ms` : `${(ms / 1000).toFixed(1)}s`;
+}
+
+/**
+ * Integration Rhythms (Layer 5): the scheduler's consolidation, synthesis
+ * and decay jobs. The selected job's detail shows its live log while it
+ * runs (scheduler:job-output, see useJobOutput) and the output tail of its
+ * last run otherwise.
+ */
+export function IntegrationRhythms() {
+  const [jobs, setJobs] = useState<SchedulerJob[]>([]);
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setJobs(await invoke<SchedulerJob[]>('get_scheduled_jobs'));
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const interval = setInterval(refresh, REFRESH_INTERVAL_MS);
+    const unlisteners = REFRESH_EVENTS.map((event) => listen(event, () => refresh()));
+    return () => {
+      clearInterval(interval);
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, [refresh]);
+
+  const selected = jobs.find((job) => job.id === selectedId) ?? null;
+
+  return (
+    <div className="integration-rhythms">
+      <header className="integration-header">
+        <h2>Integration Rhythms</h2>
+        <p>Memory consolidation, synthesis and decay cycles</p>
+      </header>
+
+      {error && <div className="integration-error">Failed to load jobs: {error}</div>}
+
+      <div className="integration-body">
+        <ul className="integration-jobs">
+          {jobs.length === 0 && <li className="integration-empty">No scheduled jobs yet</li>}
+          {jobs.map((job) => (
+            <li key={job.id}>
+              <button
+                className={`integration-job ${job.id === selectedId ? 'selected' : ''}`}
+                onClick={() => setSelectedId(job.id)}
+              >
+                <span className="integration-job-name">{JOB_LABELS[job.job_type] ?? job.job_type}</span>
+                <span className={`integration-status status-${job.status}`}>{job.status}</span>
+              </button>
+            </li>
+          ))}
+        </ul>
+
+        {selected ? (
+          <JobDetail job={selected} onChanged={refresh} />
+        ) : (
+          <div className="integration-detail integration-empty">Select a job to see its runs</div>
+        )}
+      </div>
+    </div>
+  );
+}
+
+interface JobDetailProps {
+  job: SchedulerJob;
+  onChanged: () => void;
+}
+
+function JobDetail({ job, onChanged }: JobDetailProps) {
+  const { log } = useJobOutput(job.id);
+  const [triggering, setTriggering] = useState(false);
+  const [triggerError, setTriggerError] = useState<string | null>(null);
+  const lastRun = job.runs[job.runs.length - 1] ?? null;
+  const running = job.status === 'running';
+
+  const runNow = async () => {
+    setTriggering(true);
+    setTriggerError(null);
+    try {
+      await invoke('trigger_job', { jobId: job.id });
+      onChanged();
+    } catch (err) {
+      setTriggerError(String(err));
+    } finally {
+      setTriggering(false);
+    }
+  };
+
+  // The live log while it runs or until the refreshed record has the tail
+  const output = log || lastRun?.stdout || '';
+
+  return (
+    <div className="integration-detail">
+      <div className="integration-detail-header">
+        <h3>{JOB_LABELS[job.job_type] ?? job.job_type}</h3>
+        <button className="integration-run" onClick={runNow} disabled={running || triggering}>
+          {running ? 'Running…' : 'Run now'}
+        </button>
+      </div>
+
+      <dl className="integration-meta">
+        <dt>Status</dt>
+        <dd>{job.status}</dd>
+        <dt>Last run</dt>
+        <dd>{formatTime(job.last_run)}</dd>
+        <dt>Duration</dt>
+        <dd>{formatDuration(job.duration_ms)}</dd>
+        <dt>Next run</dt>
+        <dd>{formatTime(job.next_run)}</dd>
+      </dl>
+
+      {(triggerError || job.error) && <div className="integration-error">{triggerError ?? job.error}</div>}
+
+      <h4>{running ? 'Live output' : 'Output'}</h4>
+      <pre className="integration-log">{output || 'No output yet'}</pre>
+      {!running && lastRun?.stderr && <pre className="integration-log stderr">{lastRun.stderr}</pre>}
+    </div>
+  );
+}
@@ -15,9 +15,25 @@ export interface MeaningSource {
   examples?: string[];
 }
 
+/** Output of the get_purpose_alignment command */
+export interface PurposeAlignment {
+  computed_at: string;
+  embedder: 'gemini' | 'hashed';
+  memories: number;
+  weeks: Array<{
+    week_start: string;
+    memories: number;
+    aligned_memories: number;
+    score: number | null;
+  }>;
+  current_score: number | null;
+  patterns: string[];
+}
+
 interface PurposeEngineProps {
   ikigai: IkigaiElement[];
   meaningSources: MeaningSource[];
+  alignment?: PurposeAlignment | null;
   onElementClick?: (element: IkigaiElement | MeaningSource) => void;
 }
 
@@ -34,7 +50,7 @@ const SIGNIFICANCE_CONFIG = {
   emerging: { label: 'Emerging', className: 'significance-emerging' },
 };
 
-export function PurposeEngine({ ikigai, meaningSources, onElementClick }: PurposeEngineProps) {
+export function PurposeEngine({ ikigai, meaningSources, alignment, onElementClick }: PurposeEngineProps) {
   // Group ikigai elements by category
   const groupedIkigai = ikigai.reduce((acc, element) => {
     if (!acc[element.category]) acc[element.category] = [];
@@ -120,6 +136,45 @@ export function PurposeEngine({ ikigai, meaningSources, onElementClick }: Purpos
           </div>
         </section>
 
+        {alignment && (
+          <section className="alignment-section">
+            <h3>Purpose Alignment</h3>
+            <p className="alignment-summary">
+              {alignment.current_score !== null
+                ? `This week: ${Math.round(alignment.current_score * 100)}%`
+                : 'No memories this week'}
+              {' · '}
+              {alignment.memories} memories over {alignment.weeks.length} weeks
+            </p>
+
+            <div className="alignment-weeks">
+              {alignment.weeks.map((week) => (
+                <div
+                  key={week.week_start}
+                  className="alignment-week"
+                  title={`${week.aligned_memories}/${week.memories} memories aligned`}
+                >
+                  <div className="alignment-bar">
+                    <div
+                      className="alignment-bar-fill"
+                      style={{ height: `${(week.score ?? 0) * 100}%` }}
+                    />
+                  </div>
+                  <span className="alignment-week-label">{week.week_start.slice(5)}</span>
+                </div>
+              ))}
+            </div>
+
+            {alignment.patterns.length > 0 && (
+              <ul className="alignment-patterns">
+                {alignment.patterns.map((pattern, index) => (
+                  <li key={index}>{pattern}</li>
+                ))}
+              </ul>
+            )}
+          </section>
+        )}
+
         <section className="meaning-section">
           <h3>Sources of Meaning</h3>
 
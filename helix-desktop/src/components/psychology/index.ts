@@ -17,4 +17,7 @@ export { TransformationHistory, PLACEHOLDER_TRANSFORMATION_EVENTS } from './Tran
 export type { TransformationEvent } from './TransformationHistory';
 
 export { PurposeEngine, PLACEHOLDER_IKIGAI, PLACEHOLDER_MEANING_SOURCES } from './PurposeEngine';
-export type { IkigaiElement, MeaningSource } from './PurposeEngine';
+export type { IkigaiElement, MeaningSource, PurposeAlignment } from './PurposeEngine';
+
+export { IntegrationRhythms } from './IntegrationRhythms';
+export type { SchedulerJob, SchedulerJobRun, SchedulerJobStatus } from './IntegrationRhythms';
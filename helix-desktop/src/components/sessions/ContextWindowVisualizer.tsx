@@ -7,6 +7,7 @@
 
 import { useState, useEffect, useCallback, useMemo } from 'react';
 import { useGateway } from '../../hooks/useGateway';
+import { invoke } from '../../lib/tauri-compat';
 
 interface ContextMessage {
   id: string;
@@ -16,6 +17,12 @@ interface ContextMessage {
   timestamp: string;
 }
 
+interface TokenCounts {
+  encoding: string | null;
+  counts: number[];
+  total: number;
+}
+
 interface ContextWindowData {
   sessionKey: string;
   totalTokens: number;
@@ -56,16 +63,17 @@ export function ContextWindowVisualizer({ sessionKey }: { sessionKey?: string })
       })) as any;
 
       if (historyResult?.messages) {
-        // Estimate tokens for each message
-        const messages: ContextMessage[] = historyResult.messages.map((msg: any) => ({
+        // Count tokens for each message with the app's tokenizer
+        const contents: string[] = historyResult.messages.map((msg: any) => msg.content || '');
+        const { counts, total: totalTokens } = await invoke<TokenCounts>('count_tokens', { texts: contents });
+        const messages: ContextMessage[] = historyResult.messages.map((msg: any, i: number) => ({
           id: msg.id || `msg-${Math.random().toString(36).slice(7)}`,
           role: msg.role || 'user',
-          content: msg.content || '',
-          tokens: Math.ceil((msg.content?.length || 0) / 4), // 4 chars ≈ 1 token
+          content: contents[i],
+          tokens: counts[i] ?? 0,
           timestamp: msg.timestamp || new Date().toISOString(),
         }));
 
-        const totalTokens = messages.reduce((sum, msg) => sum + msg.tokens, 0);
         const maxTokens = 100000; // Default context window
 
         setContextData({
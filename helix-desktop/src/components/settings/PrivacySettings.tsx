@@ -1,15 +1,63 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { invoke } from '../../lib/tauri-compat';
 import { useConfigStore, type PrivacyConfig } from '../../stores/configStore';
+import { useConfig, type DataResidencyConfig } from '../../hooks/useConfig';
+
+type DataFeature = 'voice_transcription' | 'summarization' | 'sync';
+
+interface DataFlow {
+  feature: DataFeature;
+  label: string;
+  setting: string;
+  leaves_device: boolean;
+  destinations: string[];
+  data: string[];
+  summary: string;
+}
+
+const DATA_FEATURES: DataFeature[] = ['voice_transcription', 'summarization', 'sync'];
+
+const DEFAULT_RESIDENCY: DataResidencyConfig = {
+  voice_transcription: 'cloud',
+  summarization: 'cloud',
+  sync: true,
+};
 
 export function PrivacySettings() {
   const { config, updateConfig } = useConfigStore();
+  const { config: helixConfig, updateConfig: updateHelixConfig } = useConfig();
   const [showClearConfirm, setShowClearConfirm] = useState(false);
+  const [flows, setFlows] = useState<Partial<Record<DataFeature, DataFlow>>>({});
+
+  const residency = helixConfig?.data_residency ?? DEFAULT_RESIDENCY;
+
+  useEffect(() => {
+    if (!helixConfig) return;
+    Promise.all(
+      DATA_FEATURES.map((feature) => invoke<DataFlow>('explain_data_flow', { feature }))
+    )
+      .then((results) => setFlows(Object.fromEntries(results.map((flow) => [flow.feature, flow]))))
+      .catch((error) => console.error('Failed to explain data flows:', error));
+  }, [helixConfig]);
 
   const handleChange = (field: keyof PrivacyConfig, value: boolean) => {
     updateConfig('privacy', { [field]: value });
   };
 
+  const handleResidencyChange = (updates: Partial<DataResidencyConfig>) => {
+    updateHelixConfig({ data_residency: { ...residency, ...updates } }).catch((error) =>
+      console.error('Failed to save data residency settings:', error)
+    );
+  };
+
+  const flowDescription = (feature: DataFeature) => {
+    const flow = flows[feature];
+    if (!flow) return null;
+    return flow.leaves_device && flow.data.length > 0
+      ? `${flow.summary}: ${flow.data.join('; ')}`
+      : flow.summary;
+  };
+
   const handleExportData = async () => {
     try {
       const data = await invoke('export_all_data');
@@ -123,6 +171,65 @@ export function PrivacySettings() {
         </div>
       </div>
 
+      <div className="settings-group">
+        <h3>Data Residency</h3>
+
+        <div className="settings-item">
+          <div className="settings-item-info">
+            <div className="settings-item-label">Voice Transcription</div>
+            <div className="settings-item-description">
+              {flowDescription('voice_transcription') ?? 'Where recorded audio is transcribed'}
+            </div>
+          </div>
+          <select
+            className="settings-select"
+            value={residency.voice_transcription}
+            onChange={(e) =>
+              handleResidencyChange({ voice_transcription: e.target.value as 'local' | 'cloud' })
+            }
+          >
+            <option value="cloud">Cloud</option>
+            <option value="local">Local only</option>
+          </select>
+        </div>
+
+        <div className="settings-item">
+          <div className="settings-item-info">
+            <div className="settings-item-label">Summarization</div>
+            <div className="settings-item-description">
+              {flowDescription('summarization') ?? 'Where memories are processed for analyses'}
+            </div>
+          </div>
+          <select
+            className="settings-select"
+            value={residency.summarization}
+            onChange={(e) =>
+              handleResidencyChange({ summarization: e.target.value as 'local' | 'cloud' })
+            }
+          >
+            <option value="cloud">Cloud</option>
+            <option value="local">Local only</option>
+          </select>
+        </div>
+
+        <div className="settings-item">
+          <div className="settings-item-info">
+            <div className="settings-item-label">Sync</div>
+            <div className="settings-item-description">
+              {flowDescription('sync') ?? 'Sync sessions and devices through your account'}
+            </div>
+          </div>
+          <label className="toggle">
+            <input
+              type="checkbox"
+              checked={residency.sync}
+              onChange={(e) => handleResidencyChange({ sync: e.target.checked })}
+            />
+            <span className="toggle-slider" />
+          </label>
+        </div>
+      </div>
+
       <div className="settings-group">
         <h3>Data Management</h3>
 
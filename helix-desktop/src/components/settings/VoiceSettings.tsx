@@ -6,11 +6,15 @@
  *
  * Gateway methods:
  *   - config.patch (messages.tts.*) - TTS configuration
+ *
+ * Voice profiles for speaker verification are enrolled in
+ * SpeakerEnrollment (Tauri commands over the backend's gateway connection).
  */
 
 import { useState, useEffect, useCallback, useRef, useMemo } from 'react';
 import { useGateway } from '../../hooks/useGateway';
 import { useGatewayConfig } from '../../hooks/useGatewayConfig';
+import { SpeakerEnrollment } from '../voice/SpeakerEnrollment';
 
 /* ═══════════════════════════════════════════
    Types
@@ -692,6 +696,12 @@ export function VoiceSettings({ onBack }: VoiceSettingsProps) {
         </div>
       </section>
 
+      {/* Section 6: Voice Profiles */}
+      <section className="vs-section">
+        <h3 className="vs-section-title">Voice Profiles</h3>
+        <SpeakerEnrollment />
+      </section>
+
       {/* Save bar */}
       {isDirty && (
         <div className="vs-save-bar">
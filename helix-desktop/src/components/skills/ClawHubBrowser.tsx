@@ -21,6 +21,7 @@ import {
 import { getClawHubClient } from '../../lib/clawhub-client';
 import type { ClawHubSkill, ClawHubCategory, ClawHubSearchOptions } from '../../lib/clawhub-client';
 import { getGatewayClient } from '../../lib/gateway-client';
+import { invoke } from '../../lib/tauri-compat';
 import { SkillCard, type SkillCardSkill } from './SkillCard';
 
 type SortOption = 'rating' | 'downloads' | 'recent' | 'updated';
@@ -147,6 +148,8 @@ export function ClawHubBrowser({ onInstall, installedSkillNames }: ClawHubBrowse
   // Install handler
   const handleInstall = useCallback(
     async (name: string) => {
+      // Rejects with a tier_required error below the required plan
+      await invoke('check_feature_access', { feature: 'skills_marketplace' });
       if (onInstall) {
         await onInstall(name);
       } else {
@@ -0,0 +1,209 @@
+/**
+ * Speaker Enrollment
+ *
+ * Enrolls household members' voices for speaker verification: sensitive
+ * voice commands (delete, send, pay, ...) run only for an enrolled voice.
+ * Each sample is a few seconds of speech, recorded here and converted to
+ * 16-bit mono PCM at 16 kHz, the rate the voice pipeline listens at. The
+ * gateway builds the voiceprint and keeps it; it never comes back.
+ *
+ * Tauri commands (see gateway/speakers.rs):
+ *   - list_voice_speakers
+ *   - enroll_voice_speaker { name, samples }
+ *   - remove_voice_speaker { id }
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '../../lib/tauri-compat';
+import { toCommandError } from '../../lib/command-error';
+import '../../styles/components/voice.css';
+
+export interface VoiceSpeaker {
+  id: string;
+  name: string;
+  samples: number;
+  enrolledAt: string;
+}
+
+const SAMPLE_RATE = 16000;
+const SAMPLE_SECONDS = 3;
+/** Samples the gateway needs by default (minEnrollmentSamples) */
+const SAMPLES_NEEDED = 3;
+
+/** Phrases to read, so the samples cover different sounds */
+const PROMPTS = [
+  'The quick brown fox jumps over the lazy dog.',
+  'Helix, what is on my calendar for tomorrow?',
+  'Please remind me to water the plants this evening.',
+];
+
+/**
+ * Record `seconds` of microphone audio as base64 16-bit mono PCM at 16 kHz
+ */
+async function recordSample(seconds: number): Promise<string> {
+  const stream = await navigator.mediaDevices.getUserMedia({ audio: { channelCount: 1 } });
+  try {
+    const recorder = new MediaRecorder(stream);
+    const chunks: Blob[] = [];
+    recorder.ondataavailable = (event) => chunks.push(event.data);
+    const stopped = new Promise<void>((resolve) => {
+      recorder.onstop = () => resolve();
+    });
+    recorder.start();
+    await new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+    recorder.stop();
+    await stopped;
+
+    // Decode at the device rate, then resample to mono 16 kHz
+    const context = new AudioContext();
+    const decoded = await context.decodeAudioData(await new Blob(chunks).arrayBuffer());
+    await context.close();
+    const offline = new OfflineAudioContext(1, Math.ceil(decoded.duration * SAMPLE_RATE), SAMPLE_RATE);
+    const source = offline.createBufferSource();
+    source.buffer = decoded;
+    source.connect(offline.destination);
+    source.start();
+    const samples = (await offline.startRendering()).getChannelData(0);
+
+    const pcm = new DataView(new ArrayBuffer(samples.length * 2));
+    samples.forEach((sample, i) => {
+      pcm.setInt16(i * 2, Math.max(-1, Math.min(1, sample)) * 0x7fff, true);
+    });
+    const bytes = new Uint8Array(pcm.buffer);
+    let binary = '';
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+      binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+    }
+    return btoa(binary);
+  } finally {
+    stream.getTracks().forEach((track) => track.stop());
+  }
+}
+
+export function SpeakerEnrollment() {
+  const [speakers, setSpeakers] = useState<VoiceSpeaker[]>([]);
+  const [name, setName] = useState('');
+  const [samples, setSamples] = useState<string[]>([]);
+  const [recording, setRecording] = useState(false);
+  const [enrolling, setEnrolling] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setSpeakers(await invoke<VoiceSpeaker[]>('list_voice_speakers'));
+      setError(null);
+    } catch (err) {
+      setError(toCommandError(err).message);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  const record = async () => {
+    setRecording(true);
+    setError(null);
+    try {
+      const sample = await recordSample(SAMPLE_SECONDS);
+      setSamples((prev) => [...prev, sample]);
+    } catch (err) {
+      setError(`Recording failed: ${err instanceof Error ? err.message : String(err)}`);
+    } finally {
+      setRecording(false);
+    }
+  };
+
+  const enroll = async () => {
+    setEnrolling(true);
+    setError(null);
+    try {
+      await invoke<VoiceSpeaker>('enroll_voice_speaker', { name: name.trim(), samples });
+      setName('');
+      setSamples([]);
+      await refresh();
+    } catch (err) {
+      // Usually samples without enough speech; start the samples over
+      setSamples([]);
+      setError(toCommandError(err).message);
+    } finally {
+      setEnrolling(false);
+    }
+  };
+
+  const remove = async (id: string) => {
+    try {
+      await invoke<boolean>('remove_voice_speaker', { id });
+      await refresh();
+    } catch (err) {
+      setError(toCommandError(err).message);
+    }
+  };
+
+  const ready = samples.length >= SAMPLES_NEEDED;
+
+  return (
+    <div className="speaker-enrollment">
+      <p className="speaker-hint">
+        Sensitive voice commands (delete, send, pay, ...) run only for an enrolled voice. Voiceprints
+        stay on this machine.
+      </p>
+
+      <ul className="speaker-list">
+        {speakers.length === 0 && <li className="speaker-empty">No voices enrolled yet</li>}
+        {speakers.map((speaker) => (
+          <li key={speaker.id} className="speaker-item">
+            <span className="speaker-name">{speaker.name}</span>
+            <span className="speaker-meta">
+              {speaker.samples} samples, {new Date(speaker.enrolledAt).toLocaleDateString()}
+            </span>
+            <button className="speaker-remove" onClick={() => remove(speaker.id)} type="button">
+              Remove
+            </button>
+          </li>
+        ))}
+      </ul>
+
+      <div className="speaker-form">
+        <input
+          className="speaker-input"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="Name"
+          disabled={recording || enrolling}
+        />
+        {!ready && (
+          <p className="speaker-prompt">
+            Sample {samples.length + 1} of {SAMPLES_NEEDED}: read aloud "
+            {PROMPTS[samples.length % PROMPTS.length]}"
+          </p>
+        )}
+        <div className="speaker-actions">
+          <button
+            className="speaker-record"
+            onClick={record}
+            disabled={recording || enrolling || ready}
+            type="button"
+          >
+            {recording ? 'Recording…' : `Record sample (${SAMPLE_SECONDS}s)`}
+          </button>
+          <button
+            className="speaker-enroll"
+            onClick={enroll}
+            disabled={!ready || !name.trim() || enrolling}
+            type="button"
+          >
+            {enrolling ? 'Enrolling…' : 'Enroll'}
+          </button>
+          {samples.length > 0 && !enrolling && (
+            <button className="speaker-reset" onClick={() => setSamples([])} type="button">
+              Start over
+            </button>
+          )}
+        </div>
+      </div>
+
+      {error && <div className="speaker-error">{error}</div>}
+    </div>
+  );
+}
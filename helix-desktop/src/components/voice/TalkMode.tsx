@@ -16,6 +16,14 @@
  *   - talk-mode.transcript (user speech text from gateway)
  *   - talk-mode.state-change (gateway-driven state transitions)
  *
+ * Voice intents:
+ *   Each transcript goes to route_voice_intent, which runs the action a
+ *   gateway agent or skill declared when the transcript names it. An
+ *   action that needs params, or several equally good matches, open a
+ *   prompt; its params are sent with execute_action, which validates them.
+ *   The transcript's speaker check goes along: sensitive requests (delete,
+ *   send, pay, ...) are refused unless an enrolled voice made them.
+ *
  * Keyboard shortcuts:
  *   - Space: toggle mute
  *   - Escape: close talk mode
@@ -24,7 +32,9 @@
  */
 
 import { useState, useEffect, useCallback, useRef } from 'react';
-import { useGateway } from '../../hooks/useGateway';
+import { useGateway, type TranscriptSpeaker } from '../../hooks/useGateway';
+import { invoke } from '../../lib/tauri-compat';
+import { toCommandError } from '../../lib/command-error';
 import { VoiceOverlay } from './VoiceOverlay';
 
 /* =====================================================================
@@ -54,6 +64,40 @@ interface VoiceSettings {
   stability: number;
 }
 
+/** JSON Schema of a declared action's params (the subset the backend checks) */
+interface ParamsSchema {
+  type?: string;
+  title?: string;
+  description?: string;
+  enum?: unknown[];
+  properties?: Record<string, ParamsSchema>;
+  required?: string[];
+}
+
+/** A declared action, as palette/actions.rs returns it */
+interface IntentAction {
+  id: string;
+  title: string;
+  subtitle: string | null;
+  params: ParamsSchema | null;
+}
+
+interface ActionOutcome {
+  id: string;
+  message: string;
+}
+
+/** What route_voice_intent made of a transcript (palette/intent.rs) */
+type VoiceIntent =
+  | { kind: 'ran'; outcome: ActionOutcome }
+  | { kind: 'needs_params'; action: IntentAction }
+  | { kind: 'ambiguous'; actions: IntentAction[] }
+  | { kind: 'no_match' }
+  | { kind: 'refused'; actions: IntentAction[] };
+
+/** An intent the user still has to settle */
+type PendingIntent = Extract<VoiceIntent, { kind: 'needs_params' | 'ambiguous' }>;
+
 /* =====================================================================
    Inline styles
    ===================================================================== */
@@ -261,6 +305,36 @@ const STYLES = `
   cursor: not-allowed;
 }
 
+/* Voice intent prompt */
+.tm-input {
+  width: 100%;
+  padding: 8px 12px;
+  background: rgba(255, 255, 255, 0.05);
+  border: 1px solid rgba(255, 255, 255, 0.1);
+  border-radius: 8px;
+  color: #e0e0ff;
+  font-size: 0.8rem;
+  box-sizing: border-box;
+}
+
+.tm-input:focus {
+  outline: none;
+  border-color: #6366f1;
+  box-shadow: 0 0 0 2px rgba(99, 102, 241, 0.15);
+}
+
+.tm-intent__hint {
+  font-size: 0.75rem;
+  color: #606080;
+  margin: 0;
+}
+
+.tm-intent__error {
+  font-size: 0.75rem;
+  color: #ef4444;
+  margin: 0;
+}
+
 /* Settings gear button (floating) */
 .tm-gear {
   position: fixed;
@@ -382,6 +456,7 @@ export function TalkMode({
   const [muted, setMuted] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [previewing, setPreviewing] = useState(false);
+  const [pendingIntent, setPendingIntent] = useState<PendingIntent | null>(null);
 
   // Voice settings
   const [voices, setVoices] = useState<VoiceOption[]>(DEFAULT_VOICES);
@@ -415,10 +490,13 @@ export function TalkMode({
 
     activeRef.current = true;
     startTalkMode();
+    // The backend shows talk mode in the tray and publishes it over MQTT
+    invoke('set_talk_mode_active', { active: true }).catch(() => {});
 
     return () => {
       activeRef.current = false;
       stopTalkMode();
+      invoke('set_talk_mode_active', { active: false }).catch(() => {});
     };
   }, [connected]);
 
@@ -431,6 +509,13 @@ export function TalkMode({
     const latest = messages[messages.length - 1];
 
     switch (latest.type) {
+      case 'transcript':
+        if (latest.content) {
+          setTranscript(latest.content);
+          routeIntent(latest.content, latest.speaker);
+        }
+        break;
+
       case 'thinking':
         if (stateRef.current === 'listening' || stateRef.current === 'idle') {
           setTalkState('thinking');
@@ -536,6 +621,31 @@ export function TalkMode({
     }
   }
 
+  // -----------------------------------------------------------------
+  // Voice intents
+  // -----------------------------------------------------------------
+
+  async function routeIntent(text: string, speaker?: TranscriptSpeaker) {
+    try {
+      const intent = await invoke<VoiceIntent>('route_voice_intent', {
+        transcript: text,
+        speaker: speaker ?? null,
+      });
+      if (!activeRef.current) return;
+      if (intent.kind === 'ran') {
+        setResponse(intent.outcome.message);
+      } else if (intent.kind === 'refused') {
+        const titles = intent.actions.map((action) => action.title).join(', ');
+        setResponse(`Only an enrolled voice can ask for ${titles}. Use the command palette instead.`);
+      } else if (intent.kind !== 'no_match') {
+        setPendingIntent(intent);
+      }
+    } catch (err) {
+      console.error('[TalkMode] Voice intent failed:', err);
+      setResponse(toCommandError(err).message);
+    }
+  }
+
   // -----------------------------------------------------------------
   // User actions
   // -----------------------------------------------------------------
@@ -668,6 +778,18 @@ export function TalkMode({
         <GearIcon size={16} />
       </button>
 
+      {/* Voice intent prompt */}
+      {pendingIntent && (
+        <IntentPrompt
+          intent={pendingIntent}
+          onDone={(message) => {
+            setPendingIntent(null);
+            setResponse(message);
+          }}
+          onDismiss={() => setPendingIntent(null)}
+        />
+      )}
+
       {/* Settings modal */}
       {showSettings && (
         <div
@@ -760,4 +882,149 @@ export function TalkMode({
   );
 }
 
+/* =====================================================================
+   Voice intent prompt
+   ===================================================================== */
+
+interface IntentPromptProps {
+  intent: PendingIntent;
+  onDone: (message: string) => void;
+  onDismiss: () => void;
+}
+
+/** Param values typed in the form, as the schema wants them */
+function toParams(schema: ParamsSchema, values: Record<string, string | boolean>): Record<string, unknown> {
+  const params: Record<string, unknown> = {};
+  for (const [name, prop] of Object.entries(schema.properties ?? {})) {
+    const value = values[name];
+    if (value === undefined || value === '') continue;
+    if (prop.type === 'number' || prop.type === 'integer') {
+      params[name] = Number(value);
+    } else {
+      params[name] = value;
+    }
+  }
+  return params;
+}
+
+/**
+ * Asks which action was meant, then for the params the action needs
+ */
+function IntentPrompt({ intent, onDone, onDismiss }: IntentPromptProps) {
+  const [action, setAction] = useState<IntentAction | null>(
+    intent.kind === 'needs_params' ? intent.action : null
+  );
+  const [values, setValues] = useState<Record<string, string | boolean>>({});
+  const [running, setRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function run(chosen: IntentAction, params: Record<string, unknown>) {
+    setRunning(true);
+    setError(null);
+    try {
+      const outcome = await invoke<ActionOutcome>('execute_action', { id: chosen.id, params });
+      onDone(outcome.message);
+    } catch (err) {
+      setError(toCommandError(err).message);
+    } finally {
+      setRunning(false);
+    }
+  }
+
+  function choose(chosen: IntentAction) {
+    if (chosen.params) {
+      setAction(chosen);
+    } else {
+      run(chosen, {});
+    }
+  }
+
+  const schema = action?.params ?? null;
+  const required = new Set(schema?.required ?? []);
+
+  return (
+    <div
+      className="tm-settings-backdrop"
+      onClick={(e) => {
+        if (e.target === e.currentTarget) onDismiss();
+      }}
+    >
+      <div className="tm-settings" role="dialog" aria-label="Voice action">
+        <div className="tm-settings__header">
+          <h3 className="tm-settings__title">{action ? action.title : 'Which action?'}</h3>
+          <button className="tm-settings__close" onClick={onDismiss} aria-label="Dismiss">
+            &times;
+          </button>
+        </div>
+
+        <div className="tm-settings__body">
+          {!action &&
+            intent.kind === 'ambiguous' &&
+            intent.actions.map((candidate) => (
+              <button
+                key={candidate.id}
+                className="tm-preview-btn"
+                onClick={() => choose(candidate)}
+                disabled={running}
+              >
+                {candidate.title}
+              </button>
+            ))}
+
+          {action && schema && (
+            <>
+              {action.subtitle && <p className="tm-intent__hint">{action.subtitle}</p>}
+              {Object.entries(schema.properties ?? {}).map(([name, prop]) => (
+                <div className="tm-field" key={name}>
+                  <label className="tm-field__label">
+                    {prop.title ?? name}
+                    {required.has(name) ? ' *' : ''}
+                  </label>
+                  {prop.type === 'boolean' ? (
+                    <input
+                      type="checkbox"
+                      checked={values[name] === true}
+                      onChange={(e) => setValues((prev) => ({ ...prev, [name]: e.target.checked }))}
+                    />
+                  ) : prop.enum ? (
+                    <select
+                      className="tm-select"
+                      value={String(values[name] ?? '')}
+                      onChange={(e) => setValues((prev) => ({ ...prev, [name]: e.target.value }))}
+                    >
+                      <option value="">—</option>
+                      {prop.enum.map((option) => (
+                        <option key={String(option)} value={String(option)}>
+                          {String(option)}
+                        </option>
+                      ))}
+                    </select>
+                  ) : (
+                    <input
+                      className="tm-input"
+                      type={prop.type === 'number' || prop.type === 'integer' ? 'number' : 'text'}
+                      value={String(values[name] ?? '')}
+                      placeholder={prop.description}
+                      onChange={(e) => setValues((prev) => ({ ...prev, [name]: e.target.value }))}
+                    />
+                  )}
+                </div>
+              ))}
+              <button
+                className="tm-preview-btn"
+                onClick={() => run(action, toParams(schema, values))}
+                disabled={running}
+              >
+                {running ? 'Running...' : 'Run'}
+              </button>
+            </>
+          )}
+
+          {error && <p className="tm-intent__error">{error}</p>}
+        </div>
+      </div>
+    </div>
+  );
+}
+
 export default TalkMode;
@@ -10,3 +10,4 @@ export { VoiceConversation } from './VoiceConversation';
 export { TalkMode } from './TalkMode';
 export { VoiceOverlay } from './VoiceOverlay';
 export { WaveformVisualizer } from './WaveformVisualizer';
+export { SpeakerEnrollment } from './SpeakerEnrollment';
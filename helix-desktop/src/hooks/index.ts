@@ -16,6 +16,7 @@ export { useSecretsData, type SecretsDataResult } from './useSecretsData';
 export { useCustomTools } from './useCustomTools';
 export { useCompositeSkills } from './useCompositeSkills';
 export { useMemorySynthesis } from './useMemorySynthesis';
+export { useSyntheses, listSyntheses, type StoredSynthesis, type SynthesisPage } from './useSyntheses';
 
 // Phase 4.1: Voice Features (Real-time Voice, Recording, Commands)
 export { useVoiceRecorder } from './useVoiceRecorder';
@@ -31,6 +32,12 @@ export { useGatewayConfig } from './useGatewayConfig';
 // Phase J2: System Tray live state sync
 export { useTraySync } from './useTraySync';
 
+// Online/offline state from the connectivity monitor
+export { useConnectivity, type ConnectivityStatus } from './useConnectivity';
+
+// Live stdout of running scheduler jobs
+export { useJobOutput, type JobOutputChunk } from './useJobOutput';
+
 // Phase J: Deep Linking
 export { useDeepLink } from './useDeepLink';
 
@@ -38,6 +38,22 @@ export interface BrandingConfig {
   tagline: string;
 }
 
+export interface DataResidencyConfig {
+  voice_transcription: 'local' | 'cloud';
+  summarization: 'local' | 'cloud';
+  sync: boolean;
+}
+
+export interface AutoApproveRule {
+  skill_id: string;
+  /** Only runs requested by this agent; any agent when null */
+  agent?: string | null;
+}
+
+export interface SkillApprovalsConfig {
+  auto_approve: AutoApproveRule[];
+}
+
 export interface HelixConfig {
   agents: Record<string, unknown>;
   models: Record<string, unknown>;
@@ -45,6 +61,8 @@ export interface HelixConfig {
   psychology: PsychologyConfig;
   hash_chain: HashChainConfig;
   branding: BrandingConfig;
+  data_residency?: DataResidencyConfig;
+  skill_approvals?: SkillApprovalsConfig;
 }
 
 export function useConfig() {
@@ -0,0 +1,61 @@
+/**
+ * Connectivity Hook
+ *
+ * Tracks the backend's online/offline verdict (app:connectivity) and
+ * forwards the browser's online/offline events so the backend re-checks
+ * the connection as soon as the OS reports a network change.
+ */
+
+import { useEffect, useState } from 'react';
+import { invoke, listen, isTauri } from '../lib/tauri-compat';
+
+export interface ConnectivityStatus {
+  online: boolean;
+  has_route: boolean;
+  /** When `online` last changed (ms since epoch) */
+  since: number;
+  last_checked: number | null;
+  reason: string | null;
+}
+
+export function useConnectivity() {
+  const [status, setStatus] = useState<ConnectivityStatus | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+
+    invoke<ConnectivityStatus>('get_connectivity_status')
+      .then((initial) => {
+        if (!cancelled) setStatus(initial);
+      })
+      .catch((err: unknown) => console.debug('[connectivity] status unavailable:', err));
+
+    const unlisten = listen<ConnectivityStatus>('app:connectivity', (event) => {
+      setStatus(event.payload);
+    });
+
+    const report = (online: boolean) => () => {
+      if (!isTauri) return;
+      invoke('report_network_change', { online }).catch((err: unknown) => {
+        console.debug('[connectivity] report failed:', err);
+      });
+    };
+    const onOnline = report(true);
+    const onOffline = report(false);
+    window.addEventListener('online', onOnline);
+    window.addEventListener('offline', onOffline);
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+      window.removeEventListener('online', onOnline);
+      window.removeEventListener('offline', onOffline);
+    };
+  }, []);
+
+  return {
+    status,
+    // Assume online until the backend says otherwise
+    online: status?.online ?? true,
+  };
+}
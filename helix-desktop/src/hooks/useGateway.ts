@@ -5,6 +5,7 @@
 
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke, listen } from '../lib/tauri-compat';
+import { isCommandError, toCommandError, type CommandErrorPayload } from '../lib/command-error';
 import type { UnlistenFn } from '@tauri-apps/api/event';
 import {
   GatewayClient,
@@ -35,18 +36,67 @@ async function getGatewayToken(): Promise<string> {
   }
 }
 
+/**
+ * What a gateway that failed the startup handshake needs (`details.action`
+ * of the start_gateway error, see gateway/handshake.rs). `install_node`
+ * means no new enough Node.js was found to start it with
+ * (get_node_environment has the suggestions).
+ */
+export type GatewayHandshakeAction =
+  | 'update_runtime'
+  | 'update_helix'
+  | 'update_remote_gateway'
+  | 'check_token'
+  | 'install_node';
+
+/** The handshake action a start_gateway error asks for, if any */
+export function handshakeAction(error: unknown): GatewayHandshakeAction | null {
+  if (!isCommandError(error)) return null;
+  const action = error.details?.action;
+  return typeof action === 'string' ? (action as GatewayHandshakeAction) : null;
+}
+
+interface RuntimeUpdate {
+  path: string;
+  previous_version: string | null;
+  version: string | null;
+}
+
+// Named gateway instances run alongside; this hook follows the default one
+const DEFAULT_INSTANCE = 'default';
+
 interface GatewayStatus {
   running: boolean;
   port: number | null;
   pid: number | null;
   url: string | null;
+  // Connected to a gateway on another host (gateway.remote_url)
+  remote?: boolean;
+}
+
+export interface GatewayHealthDetails {
+  healthy: boolean;
+  port: number;
+  latency_ms: number | null;
+  last_checked: number | null;
+  last_success: number | null;
+  uptime_secs: number | null;
+  version: string | null;
+  connected_channels: string[];
+  error: string | null;
 }
 
 interface GatewayStartedPayload {
+  instance: string;
   port: number;
   url: string;
 }
 
+interface GatewayStartFailedPayload {
+  instance: string;
+  error: CommandErrorPayload;
+}
+
 // Chat event types from OpenClaw
 interface ChatEvent {
   type: 'chat';
@@ -60,14 +110,29 @@ interface ChatEvent {
   error?: string;
 }
 
+// Who said a talk mode transcript, as the gateway checked it
+export interface TranscriptSpeaker {
+  sensitive: boolean;
+  verified: boolean;
+  name: string | null;
+}
+
+// Talk mode transcript of what the user said
+interface TalkTranscriptEvent {
+  text: string;
+  sessionKey?: string;
+  speaker?: TranscriptSpeaker;
+}
+
 export interface GatewayMessage {
-  type: 'thinking' | 'tool_call' | 'tool_result' | 'message' | 'error' | 'complete';
+  type: 'thinking' | 'tool_call' | 'tool_result' | 'message' | 'error' | 'complete' | 'transcript';
   content?: string;
   toolName?: string;
   toolInput?: unknown;
   toolOutput?: unknown;
   error?: string;
   runId?: string;
+  speaker?: TranscriptSpeaker;
 }
 
 export function useGateway() {
@@ -80,6 +145,9 @@ export function useGateway() {
   const [connected, setConnected] = useState(false);
   const [messages, setMessages] = useState<GatewayMessage[]>([]);
   const [hello, setHello] = useState<GatewayHelloOk | null>(null);
+  const [health, setHealth] = useState<GatewayHealthDetails | null>(null);
+  // Why the last start, or the launch-time attach, failed
+  const [startError, setStartError] = useState<unknown>(null);
   const clientRef = useRef<GatewayClient | null>(null);
 
   // Check gateway status
@@ -99,13 +167,23 @@ export function useGateway() {
     try {
       const result = await invoke<GatewayStartedPayload>('start_gateway');
       setStatus({ running: true, port: result.port, pid: null, url: result.url });
+      setStartError(null);
       return result;
     } catch (error) {
       console.error('Failed to start gateway:', error);
+      setStartError(error);
       throw error;
     }
   }, []);
 
+  // Update the helix-runtime checkout or managed install after a handshake
+  // found it too old, then start the gateway again
+  const updateRuntime = useCallback(async () => {
+    const update = await invoke<RuntimeUpdate>('update_runtime');
+    await start();
+    return update;
+  }, [start]);
+
   // Stop gateway
   const stop = useCallback(async () => {
     try {
@@ -160,6 +238,17 @@ export function useGateway() {
         setMessages((prev) => [...prev, message]);
       }
     }
+
+    // Talk mode transcripts, for the voice intent router (see TalkMode)
+    if (evt.event === 'talk-mode.transcript') {
+      const transcriptEvt = evt.payload as TalkTranscriptEvent;
+      if (transcriptEvt?.text?.trim()) {
+        setMessages((prev) => [
+          ...prev,
+          { type: 'transcript', content: transcriptEvt.text, speaker: transcriptEvt.speaker },
+        ]);
+      }
+    }
   }, []);
 
   // Fetch platform-specific node capabilities from the Tauri backend
@@ -273,8 +362,10 @@ export function useGateway() {
 
     (async () => {
       unlisten = await listen<GatewayStartedPayload>('gateway:started', async (event) => {
-        const { port, url } = event.payload;
+        const { instance, port, url } = event.payload;
+        if (instance !== DEFAULT_INSTANCE) return;
         setStatus((prev) => ({ ...prev, running: true, port, url }));
+        setStartError(null);
         try {
           const token = await getGatewayToken();
           await connect(url, token);
@@ -294,7 +385,8 @@ export function useGateway() {
     let unlisten: UnlistenFn;
 
     (async () => {
-      unlisten = await listen('gateway:stopped', () => {
+      unlisten = await listen<{ instance: string }>('gateway:stopped', (event) => {
+        if (event.payload.instance !== DEFAULT_INSTANCE) return;
         setStatus({ running: false, port: null, pid: null, url: null });
         disconnect();
       });
@@ -305,6 +397,43 @@ export function useGateway() {
     };
   }, [disconnect]);
 
+  // A gateway that couldn't be started or attached to at launch
+  useEffect(() => {
+    let unlisten: UnlistenFn;
+
+    (async () => {
+      unlisten = await listen<GatewayStartFailedPayload>('gateway:start_failed', (event) => {
+        if (event.payload.instance !== DEFAULT_INSTANCE) return;
+        setStartError(toCommandError(event.payload.error));
+      });
+    })();
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // Health details from the monitor's periodic checks
+  useEffect(() => {
+    let unlisten: UnlistenFn;
+
+    invoke<GatewayHealthDetails>('get_gateway_health_details')
+      .then(setHealth)
+      .catch(() => {
+        // No details until the first check
+      });
+
+    (async () => {
+      unlisten = await listen<GatewayHealthDetails>('gateway:health', (event) => {
+        setHealth(event.payload);
+      });
+    })();
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
   // Initial status check and auto-connect (non-blocking)
   useEffect(() => {
     // Don't block on gateway status - it's optional
@@ -332,10 +461,13 @@ export function useGateway() {
   return {
     status,
     connected,
+    health,
+    startError,
     messages,
     hello,
     start,
     stop,
+    updateRuntime,
     connect,
     disconnect,
     sendMessage,
@@ -0,0 +1,48 @@
+/**
+ * Job Output Hook
+ *
+ * Live log of a running scheduler job, built from the stdout chunks the
+ * backend sends as scheduler:job-output. A chunk with seq 0 starts a new
+ * run and clears the log; the full output tail lands in the job record
+ * once the run ends.
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { listen } from '../lib/tauri-compat';
+
+export interface JobOutputChunk {
+  job_id: string;
+  job_type: string;
+  /** 0 for the first chunk of a run */
+  seq: number;
+  chunk: string;
+}
+
+/** Characters of output kept; older output is dropped */
+const MAX_LOG_CHARS = 200_000;
+
+export function useJobOutput(jobId: string | null) {
+  const [log, setLog] = useState('');
+
+  useEffect(() => {
+    setLog('');
+    if (!jobId) return;
+
+    const unlisten = listen<JobOutputChunk>('scheduler:job-output', (event) => {
+      const { job_id, seq, chunk } = event.payload;
+      if (job_id !== jobId) return;
+      setLog((current) => {
+        const next = seq === 0 ? chunk : current + chunk;
+        return next.length > MAX_LOG_CHARS ? next.slice(-MAX_LOG_CHARS) : next;
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [jobId]);
+
+  const clear = useCallback(() => setLog(''), []);
+
+  return { log, clear };
+}
@@ -19,6 +19,38 @@ interface SoulResponse {
   lastModified: number;
 }
 
+/** A stored copy of HELIX_SOUL.md */
+export interface SoulVersion {
+  version: string;
+  createdAt: number;
+}
+
+export interface SoulDiff {
+  from: string;
+  to: string;
+  addedLines: number;
+  removedLines: number;
+  unified: string;
+}
+
+/** Names the live soul file in diffSoul */
+export const CURRENT_SOUL_VERSION = 'current';
+
+/** Where layer data breaks its file's JSON Schema */
+export interface SchemaViolation {
+  /** Layer file, relative to the data directory */
+  file: string;
+  /** JSON Pointer to the offending value; empty for the whole document */
+  path: string;
+  message: string;
+}
+
+export interface LayerValidation {
+  layer: string;
+  valid: boolean;
+  violations: SchemaViolation[];
+}
+
 interface LayerResponse {
   layer: string;
   data: unknown;
@@ -35,7 +67,7 @@ const LAYER_NAMES = [
   'purpose',
 ] as const;
 
-type LayerName = (typeof LAYER_NAMES)[number];
+export type LayerName = (typeof LAYER_NAMES)[number];
 
 /**
  * Hook to load and observe psychology layers from the Helix system
@@ -129,6 +161,30 @@ export function usePsychology() {
     }
   }, []);
 
+  const getSoulHistory = useCallback(() => invoke<SoulVersion[]>('get_soul_history'), []);
+
+  const diffSoul = useCallback(
+    (versionA: string, versionB: string = CURRENT_SOUL_VERSION) =>
+      invoke<SoulDiff>('diff_soul', { versionA, versionB }),
+    []
+  );
+
+  /**
+   * Write a stored version back as the soul and reload it
+   */
+  const restoreSoul = useCallback(
+    async (version: string) => {
+      try {
+        await invoke('restore_soul', { version });
+        await loadSoul();
+      } catch (error) {
+        console.error(`Failed to restore soul version ${version}:`, error);
+        throw error;
+      }
+    },
+    [loadSoul]
+  );
+
   const updateLayer = useCallback(async (layerName: LayerName, data: unknown) => {
     try {
       await invoke('update_layer', { layer: layerName, data });
@@ -145,6 +201,16 @@ export function usePsychology() {
     }
   }, []);
 
+  /**
+   * Check layer data against its schemas without writing it. updateLayer
+   * rejects the same violations with an invalid_input error.
+   */
+  const validateLayer = useCallback(
+    (layerName: LayerName, data: unknown) =>
+      invoke<LayerValidation>('validate_layer', { layer: layerName, data }),
+    []
+  );
+
   // Initial load
   useEffect(() => {
     const init = async () => {
@@ -164,7 +230,11 @@ export function usePsychology() {
     error: state.error,
     refresh,
     updateSoul,
+    getSoulHistory,
+    diffSoul,
+    restoreSoul,
     updateLayer,
+    validateLayer,
     loadLayer,
   };
 }
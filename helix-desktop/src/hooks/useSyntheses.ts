@@ -0,0 +1,109 @@
+/**
+ * Stored Syntheses Hook
+ *
+ * Pages through the signed-in account's patterns from memory_synthesis,
+ * newest first, via the list_syntheses command (served by
+ * `memory-synthesis --serve`, which the backend starts on first use).
+ * Changing the filter or the account starts over at page 1; loadMore
+ * appends the next page.
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, listen } from '../lib/tauri-compat';
+
+export interface StoredSynthesis {
+  id: string;
+  userId: string;
+  patternType: string;
+  memoryIds: string[];
+  synthesisContent: string;
+  confidenceScore: number;
+  createdAt: string;
+}
+
+export interface SynthesisPage {
+  items: StoredSynthesis[];
+  /** 1-based */
+  page: number;
+  pageSize: number;
+  /** Matches across all pages */
+  total: number;
+  hasMore: boolean;
+}
+
+export interface SynthesisFilter {
+  /** Exact pattern type, e.g. "emotional_emotional_positive" */
+  patternType?: string;
+  /** 0 to 1 */
+  minConfidence?: number;
+}
+
+export function listSyntheses(
+  filter: SynthesisFilter = {},
+  page = 1,
+  pageSize?: number
+): Promise<SynthesisPage> {
+  return invoke<SynthesisPage>('list_syntheses', {
+    patternType: filter.patternType,
+    minConfidence: filter.minConfidence,
+    page,
+    pageSize,
+  });
+}
+
+export function useSyntheses(filter: SynthesisFilter = {}, pageSize?: number) {
+  const [items, setItems] = useState<StoredSynthesis[]>([]);
+  const [page, setPage] = useState(0);
+  const [total, setTotal] = useState(0);
+  const [hasMore, setHasMore] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const { patternType, minConfidence } = filter;
+
+  const load = useCallback(
+    async (nextPage: number) => {
+      setLoading(true);
+      setError(null);
+      try {
+        const result = await listSyntheses({ patternType, minConfidence }, nextPage, pageSize);
+        setItems((current) => (nextPage === 1 ? result.items : [...current, ...result.items]));
+        setPage(result.page);
+        setTotal(result.total);
+        setHasMore(result.hasMore);
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err));
+      } finally {
+        setLoading(false);
+      }
+    },
+    [patternType, minConfidence, pageSize]
+  );
+
+  useEffect(() => {
+    setItems([]);
+    setPage(0);
+    load(1);
+  }, [load]);
+
+  // The server answers for whichever account is active
+  useEffect(() => {
+    const unlisten = listen('account:switched', () => {
+      setItems([]);
+      setPage(0);
+      load(1);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  const loadMore = useCallback(() => {
+    if (hasMore && !loading) load(page + 1);
+  }, [hasMore, loading, load, page]);
+
+  const refresh = useCallback(() => load(1), [load]);
+
+  return { items, page, total, hasMore, loading, error, loadMore, refresh };
+}
@@ -4,8 +4,8 @@
  * Watches gateway status, agents, channels, and pending approvals,
  * then pushes live state to the native system tray menu via Tauri IPC.
  *
- * Also listens for tray menu events (new-chat, settings, approvals, etc.)
- * and dispatches navigation/actions accordingly.
+ * Also listens for tray menu events (new-chat, settings, approvals,
+ * maintenance, etc.) and dispatches navigation/actions accordingly.
  */
 
 import { useEffect, useRef, useCallback, useState } from 'react';
@@ -117,6 +117,39 @@ export function useTraySync() {
     };
   }, [pushTrayState]);
 
+  // Rebuild the menu when a config change leaves a gateway restart pending,
+  // when the scheduler is paused, resumed or put in maintenance mode, when
+  // a job starts or stops failing (the Maintenance label counts failing
+  // jobs), when agent skill runs are queued or resolved (the backend adds
+  // them to the approvals count), when the backend locale changes, and when
+  // talk mode starts or stops
+  useEffect(() => {
+    if (!isTauri) return;
+
+    const unlisteners: Array<() => void> = [];
+    for (const event of [
+      'gateway:restart-pending',
+      'scheduler:paused',
+      'scheduler:maintenance-mode',
+      'scheduler:job-failure-alert',
+      'scheduler:job-recovered',
+      'skills:approval-requested',
+      'skills:run-resolved',
+      'locale:changed',
+      'talk-mode:changed',
+    ]) {
+      listen(event, () => {
+        pushTrayState();
+      }).then((fn) => {
+        unlisteners.push(fn);
+      });
+    }
+
+    return () => {
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [pushTrayState]);
+
   // Listen for tray menu events emitted by the Rust backend
   useEffect(() => {
     if (!isTauri) return;
@@ -143,8 +176,9 @@ export function useTraySync() {
       );
 
       unlisteners.push(
-        await listen('tray:toggle-talk-mode', () => {
-          navigate(ROUTES.VOICE);
+        await listen<{ active?: boolean } | null>('tray:toggle-talk-mode', (event) => {
+          // Leaving the voice view stops talk mode
+          navigate(event.payload?.active === false ? ROUTES.CHAT : ROUTES.VOICE);
         })
       );
 
@@ -158,6 +192,23 @@ export function useTraySync() {
             });
         })
       );
+
+      unlisteners.push(
+        await listen<string>('tray:run-maintenance', (event) => {
+          const command = event.payload === 'synthesis' ? 'run_synthesis' : 'run_decay';
+          invoke(command, { dryRun: false })
+            .catch((err: unknown) => {
+              console.error(`[tray-sync] ${event.payload} run failed:`, err);
+            })
+            .finally(() => pushTrayState());
+        })
+      );
+
+      unlisteners.push(
+        await listen('tray:open-scheduler', () => {
+          navigate(ROUTES.PSYCHOLOGY_LAYER('integration'));
+        })
+      );
     };
 
     setup();
@@ -165,5 +216,5 @@ export function useTraySync() {
     return () => {
       unlisteners.forEach((fn) => fn());
     };
-  }, [navigate]);
+  }, [navigate, pushTrayState]);
 }
@@ -19,6 +19,20 @@ export interface AuthContextType {
 
 const AuthContext = createContext<AuthContextType | undefined>(undefined);
 
+/**
+ * Forget every stored sign-in: the app's token and user, and the session
+ * supabase-js persists under `sb-<project>-auth-token`
+ */
+export function clearStoredAuth(): void {
+  localStorage.removeItem('auth_token');
+  localStorage.removeItem('auth_user');
+  for (const key of Object.keys(localStorage)) {
+    if (key.startsWith('sb-') && key.endsWith('-auth-token')) {
+      localStorage.removeItem(key);
+    }
+  }
+}
+
 export function useAuth(): AuthContextType {
   const context = useContext(AuthContext);
   if (!context) {
@@ -59,8 +73,7 @@ export function AuthProvider({ children }: { children: ReactNode }) {
   const logout = useCallback(() => {
     setToken(null);
     setUser(null);
-    localStorage.removeItem('auth_token');
-    localStorage.removeItem('auth_user');
+    clearStoredAuth();
   }, []);
 
   const value: AuthContextType = {
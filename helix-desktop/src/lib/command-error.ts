@@ -0,0 +1,78 @@
+/**
+ * Command Errors
+ * Typed errors from Tauri commands (src-tauri/src/commands/error.rs).
+ * Branch on `code`, never on `message`: codes are stable, messages are an
+ * English fallback. `details` holds values for translated messages.
+ */
+
+/** Error-code catalog; keep in sync with `ErrorCode` in commands/error.rs */
+export const ERROR_CODES = [
+  'internal',
+  'invalid_input',
+  'not_found',
+  'already_exists',
+  'unauthenticated',
+  'permission_denied',
+  'confirmation_required',
+  'io',
+  'network',
+  'timeout',
+  'config',
+  'keyring',
+  'vault_locked',
+  'service_unavailable',
+  'disabled',
+  'process_failed',
+  'conflict',
+  'unsupported',
+  'tier_required',
+  'offline',
+  'busy',
+] as const;
+
+export type ErrorCode = (typeof ERROR_CODES)[number];
+
+export interface CommandErrorPayload {
+  code: ErrorCode;
+  message: string;
+  details?: Record<string, unknown>;
+}
+
+export class CommandError extends Error {
+  readonly code: ErrorCode;
+  readonly details?: Record<string, unknown>;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.message);
+    this.name = 'CommandError';
+    this.code = payload.code;
+    this.details = payload.details;
+  }
+
+  /** Plain message, so `String(err)` reads the same as before */
+  toString(): string {
+    return this.message;
+  }
+}
+
+function isPayload(value: unknown): value is CommandErrorPayload {
+  if (typeof value !== 'object' || value === null) return false;
+  const v = value as Record<string, unknown>;
+  return (
+    typeof v.message === 'string' &&
+    typeof v.code === 'string' &&
+    (ERROR_CODES as readonly string[]).includes(v.code)
+  );
+}
+
+/** Normalize anything an invoke rejects with into a CommandError */
+export function toCommandError(err: unknown): CommandError {
+  if (err instanceof CommandError) return err;
+  if (isPayload(err)) return new CommandError(err);
+  if (err instanceof Error) return new CommandError({ code: 'internal', message: err.message });
+  return new CommandError({ code: 'internal', message: String(err) });
+}
+
+export function isCommandError(err: unknown, code?: ErrorCode): err is CommandError {
+  return err instanceof CommandError && (code === undefined || err.code === code);
+}
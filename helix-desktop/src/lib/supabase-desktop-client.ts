@@ -374,6 +374,16 @@ export class SupabaseDesktopClient {
     return [...this.messageQueue];
   }
 
+  /**
+   * End the Supabase session on this device and disconnect
+   */
+  async signOut(): Promise<void> {
+    this.disconnect();
+    this.messageQueue = [];
+    this.userId = null;
+    await this.supabase?.auth.signOut({ scope: 'local' });
+  }
+
   /**
    * Disconnect and cleanup
    */
@@ -3,6 +3,8 @@
  * Provides mock implementations when running in browser (not Tauri)
  */
 
+import { toCommandError } from './command-error';
+
 // Detect if running in Tauri
 export const isTauri = typeof window !== 'undefined' && '__TAURI__' in window;
 
@@ -25,10 +27,12 @@ const MOCK_PATHS = {
 };
 
 const MOCK_GATEWAY_STATUS = {
+  instance: 'default',
   running: false,
   port: null,
   pid: null,
   url: null,
+  remote: false,
 };
 
 const MOCK_CONFIG = {
@@ -53,6 +57,96 @@ const MOCK_CONFIG = {
     name: 'Helix',
     tagline: 'AI Consciousness System',
   },
+  telemetry: {
+    enabled: false,
+    endpoint: null,
+    flush_interval_secs: 3600,
+  },
+  calendar: {
+    sources: [],
+    lookahead_days: 14,
+  },
+  email: {
+    enabled: false,
+    host: '',
+    port: 993,
+    username: '',
+    folder: 'Helix',
+    poll_interval_secs: 300,
+    redaction_rules: [],
+  },
+  api: {
+    enabled: false,
+    port: 18793,
+  },
+  scripts: {
+    python_path: null,
+    env: {},
+    python_psychology: false,
+  },
+  quiet_hours: {
+    enabled: false,
+    start: '22:00',
+    end: '07:00',
+    days: [],
+    suppress_notifications: true,
+    defer_jobs: true,
+    heartbeat_interval: 900000,
+    detect_os_focus: true,
+  },
+  guardrails: {
+    enabled: true,
+    protected_sections: ['CORE VALUES', "WHAT I DON'T DO", 'THE TRANSPARENCY AGREEMENT', 'Trust Configuration'],
+    protected_paths: ['psychology/trust_map.json#/trust_thresholds'],
+    on_violation: 'queue',
+  },
+  goals: {
+    reminders: true,
+    reminder_after_days: 7,
+  },
+  gateway: {
+    restart_policy: 'on_failure',
+    max_restarts: 5,
+    restart_window_secs: 600,
+    token_handoff: 'stdin',
+    nightly_restart_at: null,
+    nightly_restart_window_mins: 60,
+    runtime_path: null,
+    remote_url: null,
+    port: null,
+    last_port: null,
+    env: {},
+    instances: {},
+  },
+  updates: {
+    bandwidth_limit_kbps: null,
+  },
+  data_residency: {
+    voice_transcription: 'cloud',
+    summarization: 'cloud',
+    sync: true,
+  },
+  skill_approvals: {
+    auto_approve: [],
+  },
+  response_cache: {
+    enabled: true,
+    max_size_mb: 256,
+  },
+  ui: {
+    locale: null,
+  },
+};
+
+const MOCK_RESPONSE_CACHE_STATS = {
+  enabled: true,
+  hits: 0,
+  misses: 0,
+  hit_rate: null,
+  entries: 0,
+  size_bytes: 0,
+  max_size_bytes: 256 * 1024 * 1024,
+  namespaces: [],
 };
 
 // In-memory mock storage for browser dev
@@ -71,8 +165,59 @@ const mockInvokeHandlers: Record<string, InvokeHandler> = {
 
   // Gateway
   gateway_status: () => MOCK_GATEWAY_STATUS,
-  start_gateway: () => ({ port: 3000, url: 'ws://localhost:3000' }),
+  start_gateway: () => ({ instance: 'default', port: 3000, url: 'ws://localhost:3000' }),
   stop_gateway: () => undefined,
+  set_gateway_port: () => undefined,
+  set_gateway_remote_url: () => undefined,
+  detect_runtime_paths: () => [],
+  set_runtime_path: () => {
+    throw new Error('Runtime paths can only be set in the desktop app');
+  },
+  get_runtime_install: () => ({
+    path: '~/.helix/helix-runtime',
+    pinned_version: '2026.2.3',
+    installed_version: null,
+    in_use: false,
+    update_available: false,
+    installing: false,
+    unmanaged: false,
+  }),
+  get_node_environment: () => ({
+    status: 'missing',
+    path: null,
+    version: null,
+    source: null,
+    npm_path: null,
+    required: '>=22.12.0',
+    candidates: [],
+    suggestions: ['Node.js is detected in the desktop app'],
+  }),
+  install_runtime: () => {
+    throw new Error('helix-runtime can only be installed in the desktop app');
+  },
+  list_gateway_instances: () => [MOCK_GATEWAY_STATUS],
+  get_gateway_logs: () => [],
+  get_agent_metrics: () => ({
+    connected: false,
+    updated_at: null,
+    agents: [],
+    error: null,
+    response_cache: MOCK_RESPONSE_CACHE_STATS,
+  }),
+  get_response_cache_stats: () => MOCK_RESPONSE_CACHE_STATS,
+  clear_response_cache: () => 0,
+  get_gateway_health_details: () => ({
+    healthy: false,
+    port: 3000,
+    latency_ms: null,
+    last_checked: null,
+    last_success: null,
+    uptime_secs: null,
+    version: null,
+    connected_channels: [],
+    active_sessions: null,
+    error: 'Gateway is not running',
+  }),
 
   // Config
   get_config: () => mockConfig,
@@ -80,6 +225,23 @@ const mockInvokeHandlers: Record<string, InvokeHandler> = {
     const { config } = args as { config: typeof MOCK_CONFIG };
     mockConfig = config;
   },
+  // Object paths only; enough for settings pages in browser dev
+  patch_config: (args: unknown) => {
+    const { ops } = args as { ops: Array<{ op: string; path: string; value?: unknown }> };
+    const next = JSON.parse(JSON.stringify(mockConfig));
+    const changes = ops.map(({ op, path, value }) => {
+      const keys = path.split('/').slice(1).map((k) => k.replace(/~1/g, '/').replace(/~0/g, '~'));
+      const last = keys.pop() as string;
+      const parent = keys.reduce((node, key) => node[key], next);
+      const old = parent[last];
+      if (op === 'remove') delete parent[last];
+      else if (op === 'add' || op === 'replace') parent[last] = value;
+      return { path, old, new: parent[last] };
+    });
+    mockConfig = next;
+    emitMockEvent('config:changed', { path: MOCK_PATHS.config_path, timestamp: Date.now(), source: 'patch', changes });
+    return { config: mockConfig, changes };
+  },
 
   // Keyring
   store_secret: (args: unknown) => {
@@ -116,8 +278,103 @@ const mockInvokeHandlers: Record<string, InvokeHandler> = {
   get_soul: () => ({ content: '# Helix Soul\n\nBrowser development mode.', lastModified: Date.now() }),
   get_layer: () => ({ layer: '', data: {}, lastModified: Date.now() }),
   get_all_layers: () => ({}),
-  update_soul: () => undefined,
-  update_layer: () => undefined,
+  update_soul: () => ({ status: 'written', path: 'soul/HELIX_SOUL.md' }),
+  get_soul_history: () => [],
+  diff_soul: (args: unknown) => {
+    const { versionA = '', versionB = '' } = (args as { versionA?: string; versionB?: string } | undefined) ?? {};
+    return { from: versionA, to: versionB, addedLines: 0, removedLines: 0, unified: '' };
+  },
+  restore_soul: () => {
+    throw new Error('Soul history requires the desktop app');
+  },
+  update_layer: () => [],
+  validate_layer: (args: unknown) => {
+    const { layer } = args as { layer: string };
+    return { layer, valid: true, violations: [] };
+  },
+  list_syntheses: (args: unknown) => {
+    const { page, pageSize } = args as { page?: number; pageSize?: number };
+    return { items: [], page: page ?? 1, pageSize: pageSize ?? 50, total: 0, hasMore: false };
+  },
+  get_migration_report: () => ({ files: [], applied: [] }),
+  get_goal_stats: () => ({ goals: [], average_progress: 0, check_ins_last_week: 0, reminders_due: 0, generated_at: new Date().toISOString() }),
+  log_goal_progress: () => {
+    throw new Error('Goal check-ins require the desktop app');
+  },
+  build_context: (args: unknown) => {
+    const { persona = '', budgetTokens = 4000, query = null } =
+      (args as { persona?: string; budgetTokens?: number; query?: string | null } | undefined) ?? {};
+    return {
+      persona,
+      query,
+      budget_tokens: budgetTokens,
+      used_tokens: 0,
+      text: '',
+      explanation: [],
+      unavailable: ['Context bundles require the desktop app'],
+      generated_at: new Date().toISOString(),
+      cached: false,
+    };
+  },
+  count_tokens: (args: unknown) => {
+    // Browser mode has no vocabulary: estimate at four characters per token
+    const { texts = [] } = (args as { texts?: string[] } | undefined) ?? {};
+    const counts = texts.map((text) => Math.ceil(text.length / 4));
+    return { encoding: null, counts, total: counts.reduce((sum, n) => sum + n, 0) };
+  },
+  get_wellness_trends: (args: unknown) => ({
+    range: (args as { range?: string } | undefined)?.range ?? '30d',
+    entries: [],
+    average_score: null,
+    trend: null,
+    average_sleep_hours: null,
+    updated_at: null,
+  }),
+  get_purpose_alignment: () => ({
+    computed_at: new Date().toISOString(),
+    embedder: 'hashed',
+    memories: 0,
+    weeks: [],
+    current_score: null,
+    clusters: [],
+    dimensions: [],
+    patterns: [],
+  }),
+  get_milestone_candidates: () => [],
+  approve_milestone: () => {
+    throw new Error('Milestone approval requires the desktop app');
+  },
+  reject_milestone: () => undefined,
+  get_relationship_graph: () => ({
+    nodes: [{ id: 'helix', name: 'Helix', kind: 'helix', relationship: null, attachment_style: null, trust: null, mentions: 0, last_mentioned: null }],
+    edges: [],
+    last_memory_at: null,
+    updated_at: null,
+  }),
+
+  // Scheduler
+  get_scheduled_jobs: () => [],
+  trigger_job: () => {
+    throw new Error('Running scheduler jobs requires the desktop app');
+  },
+  get_service_availability: () => [
+    { precondition: 'gateway_healthy', met: false, detail: 'Gateway not available in browser mode', checked_at: Math.floor(Date.now() / 1000) },
+    { precondition: 'supabase_reachable', met: false, detail: 'Supabase not available in browser mode', checked_at: Math.floor(Date.now() / 1000) },
+    { precondition: 'voice_pipeline_up', met: false, detail: 'voice-pipeline is not running', checked_at: Math.floor(Date.now() / 1000) },
+  ],
+  pause_scheduler: (args: unknown) => {
+    const { durationSecs } = args as { durationSecs: number };
+    return { paused_until: Math.floor(Date.now() / 1000) + durationSecs };
+  },
+  resume_scheduler: () => undefined,
+  scheduler_maintenance_mode: (args: unknown) => {
+    const { enabled, until } = args as { enabled: boolean; until?: number | null };
+    return { enabled, until: enabled ? until ?? null : null };
+  },
+  get_upcoming_maintenance: () => [
+    { job_type: 'consolidation', next_run: null },
+    { job_type: 'synthesis', next_run: null },
+  ],
 
   // Memory
   search_memories: () => [],
@@ -139,12 +396,289 @@ const mockInvokeHandlers: Record<string, InvokeHandler> = {
 
   // Phase J2: System Tray (no-op in browser)
   update_tray_menu: () => undefined,
+  get_locale: () => ({ locale: 'en', configured: null, system: 'en-US', available: ['en', 'es'] }),
+  get_locale_strings: (args?: unknown) => ({
+    locale: 'en',
+    namespace: (args as { ns?: string } | undefined)?.ns ?? 'tray',
+    strings: {},
+  }),
+  set_locale: () => 'en',
 
   // Phase J4: Auto-Updater (no-op in browser)
   check_for_update: () => null,
   install_update: () => undefined,
+  pause_update: () => undefined,
+  resume_update: () => undefined,
+  get_post_update_status: () => ({ last_run: null, snapshots: [] }),
   get_app_version: () => '1.0.0',
 
+  // Account profiles (single mock account in browser)
+  list_accounts: () => [],
+  get_active_account: () => null,
+
+  // Safe mode (never active in browser)
+  get_safe_mode_status: () => ({
+    active: false,
+    failing_component: null,
+    consecutive_crashes: 0,
+    disabled: [],
+    last_panic: null,
+  }),
+
+  // Telemetry (always off in browser)
+  get_telemetry_status: () => ({
+    enabled: false,
+    endpoint: null,
+    flush_interval_secs: 3600,
+    queued_events: 0,
+  }),
+  record_feature_usage: () => undefined,
+
+  // Psychology vault (never enabled in browser)
+  get_vault_status: () => ({
+    enabled: false,
+    mode: null,
+    unlocked: false,
+    plaintext_files: 0,
+  }),
+
+  // Secure wipe (nothing to wipe in browser)
+  get_wipe_history: () => [],
+
+  // Data residency (summaries only; the desktop lists what is sent)
+  explain_data_flow: (args: unknown) => {
+    const { feature } = args as { feature: 'voice_transcription' | 'summarization' | 'sync' };
+    const residency = mockConfig.data_residency;
+    const setting =
+      feature === 'sync' ? (residency.sync ? 'on' : 'off') : residency[feature];
+    const leaves = setting === 'cloud' || setting === 'on';
+    return {
+      feature,
+      label: feature,
+      setting,
+      leaves_device: leaves,
+      destinations: [],
+      data: [],
+      summary: leaves ? 'Data may leave this device' : 'Data stays on this device',
+    };
+  },
+
+  // Activity timeline (no services run in browser)
+  get_activity_timeline: () => ({ events: [], next_cursor: null, unavailable: [] }),
+
+  // Subscription tiers (the web app gates features itself)
+  get_subscription_status: () => ({
+    tier: 'core',
+    verified_at: null,
+    expires_at: null,
+    stale: true,
+    unverified: { reason: 'missing' },
+    features: [
+      { feature: 'sync_coordinator', required_tier: 'phantom', allowed: false },
+      { feature: 'skills_marketplace', required_tier: 'overseer', allowed: false },
+    ],
+  }),
+  check_feature_access: () => undefined,
+
+  // Supabase Realtime bridge (the web app subscribes directly)
+  get_realtime_status: () => ({
+    connected: false,
+    user_id: null,
+    last_event_at: null,
+    last_error: null,
+    subscription_tier: null,
+    instances: {},
+  }),
+
+  // Connectivity monitor (the browser's own view stands in)
+  get_connectivity_status: () => ({
+    online: navigator.onLine,
+    has_route: navigator.onLine,
+    since: Date.now(),
+    last_checked: null,
+    reason: navigator.onLine ? null : 'The system reported no network connection',
+  }),
+  report_network_change: () => undefined,
+  get_cloud_queue: () => [],
+
+  // Session handoff (needs a signed-in desktop and a running gateway)
+  create_session_handoff: () => {
+    throw new Error('Session handoff requires the desktop app');
+  },
+  list_session_handoffs: () => [],
+  accept_session_handoff: () => {
+    throw new Error('Session handoff requires the desktop app');
+  },
+  decline_session_handoff: () => undefined,
+  cancel_session_handoff: () => undefined,
+
+  // Local REST API (not available in browser)
+  get_api_status: () => ({ running: false, port: null, url: null, token_path: '' }),
+  list_api_reader_tokens: () => [],
+
+  // MCP server (the desktop binary serves MCP; nothing to launch in browser)
+  get_mcp_config: () => ({ mcpServers: {} }),
+
+  // Python script runner (scripts run only in the desktop app)
+  get_python_runtime: () => {
+    throw new Error('Python scripts require the desktop app');
+  },
+
+  // Accessibility (the browser's media queries apply instead)
+  get_accessibility_state: () => ({
+    screen_reader: null,
+    reduced_motion: null,
+    high_contrast: null,
+  }),
+
+  // Backend gateway connection (the browser uses its own socket)
+  gateway_rpc: () => {
+    throw new Error('Backend gateway RPC requires the desktop app');
+  },
+
+  // Nightly gateway restart (no gateway in browser)
+  get_nightly_restart: () => ({
+    enabled: false,
+    at: null,
+    window_mins: 60,
+    next_run: null,
+    last_run: null,
+    last_outcome: null,
+    last_message: null,
+  }),
+  set_nightly_restart: () => {
+    throw new Error('Nightly gateway restart requires the desktop app');
+  },
+
+  // Do-not-disturb (never active in browser)
+  get_dnd_status: () => ({
+    active: false,
+    reason: null,
+    until: null,
+    manual_until: null,
+    quiet_hours_enabled: false,
+    os_focus: null,
+    suppress_notifications: false,
+    defer_jobs: false,
+    heartbeat_interval: 60000,
+  }),
+  set_dnd: () => {
+    throw new Error('Do-not-disturb requires the desktop app');
+  },
+
+  // Home automation integrations (no MQTT bridge in browser)
+  set_talk_mode_active: () => undefined,
+  get_mqtt_status: () => ({
+    enabled: false,
+    connected: false,
+    broker: null,
+    connected_at: null,
+    last_published: null,
+    last_command: null,
+    last_error: null,
+  }),
+
+  // Soul guardrails (nothing is queued in browser)
+  list_pending_writes: () => [],
+  approve_pending_write: () => {
+    throw new Error('Pending writes require the desktop app');
+  },
+  reject_pending_write: () => {
+    throw new Error('Pending writes require the desktop app');
+  },
+  get_guardrail_decisions: () => [],
+
+  // Skill run approvals (agents can't request runs in browser)
+  list_skill_runs: () => [],
+  approve_skill_run: () => {
+    throw new Error('Skill runs require the desktop app');
+  },
+  reject_skill_run: () => {
+    throw new Error('Skill runs require the desktop app');
+  },
+  set_skill_auto_approve: () => {
+    throw new Error('Skill runs require the desktop app');
+  },
+
+  // Command palette (backend actions only exist in the desktop app)
+  query_actions: () => [],
+  execute_action: () => {
+    throw new Error('Palette actions require the desktop app');
+  },
+  list_gateway_actions: () => [],
+  declare_gateway_actions: () => {
+    throw new Error('Gateway actions require the desktop app');
+  },
+  invoke_gateway_action: () => {
+    throw new Error('Gateway actions require the desktop app');
+  },
+  route_voice_intent: () => ({ kind: 'no_match' }),
+
+  // Voice profiles (enrolled through the desktop's gateway connection)
+  list_voice_speakers: () => [],
+  enroll_voice_speaker: () => {
+    throw new Error('Voice enrollment requires the desktop app');
+  },
+  remove_voice_speaker: () => false,
+
+  // Command macros (backend commands can't be recorded in browser)
+  start_macro_recording: () => {
+    throw new Error('Macro recording requires the desktop app');
+  },
+  stop_macro_recording: () => null,
+  get_macro_recording: () => null,
+  list_macros: () => [],
+  save_macro: () => {
+    throw new Error('Macros require the desktop app');
+  },
+  delete_macro: () => undefined,
+  run_macro: () => {
+    throw new Error('Macros require the desktop app');
+  },
+
+  // Lifecycle hooks (nothing runs in browser)
+  list_hooks: () => [],
+  save_hook: () => {
+    throw new Error('Hooks require the desktop app');
+  },
+  delete_hook: () => undefined,
+  test_hook: () => {
+    throw new Error('Hooks require the desktop app');
+  },
+  get_hook_runs: () => [],
+
+  // Process isolation (nothing is spawned in browser)
+  get_isolation_support: () => ({
+    platform: 'browser',
+    resource_limits: false,
+    filesystem: false,
+    detail: 'Process isolation requires the desktop app',
+  }),
+
+  // Workspace trust (no project scripts in browser)
+  get_workspace_trust: () => ({
+    workspace: '',
+    scripts_dir: '',
+    state: 'untrusted',
+    fingerprint: '',
+    trusted_at: null,
+    scripts: [],
+    added: [],
+    modified: [],
+    removed: [],
+  }),
+  trust_workspace: () => {
+    throw new Error('Workspace trust requires the desktop app');
+  },
+  revoke_workspace_trust: () => undefined,
+
+  // Calendar (no sources in browser)
+  get_upcoming_commitments: () => ({ from: new Date().toISOString(), to: new Date().toISOString(), lookahead_days: 14, commitments: [], alignment: [], patterns: [], errors: [] }),
+
+  // Background tasks (no-op in browser)
+  list_active_tasks: () => [],
+  cancel_task: () => undefined,
+
   // Deep Linking (Phase J)
   handle_deep_link: (args: unknown) => {
     const { url } = args as { url: string };
@@ -179,7 +713,10 @@ export async function invoke<T>(cmd: string, args?: unknown): Promise<T> {
     const tauriInvoke = await getTauriInvoke();
     if (tauriInvoke) {
       try {
-        return tauriInvoke(cmd, args as Record<string, unknown>) as Promise<T>;
+        // Commands reject with { code, message, details }; surface it as an Error
+        return (tauriInvoke(cmd, args as Record<string, unknown>) as Promise<T>).catch((err: unknown) => {
+          throw toCommandError(err);
+        });
       } catch (err) {
         console.warn(`[Tauri Compat] Tauri invoke failed for ${cmd}: ${err}`);
         // Fall through to mock handler
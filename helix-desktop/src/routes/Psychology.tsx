@@ -1,5 +1,8 @@
+import { useEffect, useState } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { usePsychology } from '../hooks/usePsychology';
+import type { LayerName, SchemaViolation } from '../hooks/usePsychology';
+import { invoke } from '../lib/tauri-compat';
 import {
   LayerOverview,
   SoulViewer,
@@ -8,6 +11,7 @@ import {
   GoalsTimeline,
   TransformationHistory,
   PurposeEngine,
+  IntegrationRhythms,
   PLACEHOLDER_LAYERS,
   PLACEHOLDER_SOUL_CONTENT,
   PLACEHOLDER_EMOTIONAL_TAGS,
@@ -17,6 +21,7 @@ import {
   PLACEHOLDER_IKIGAI,
   PLACEHOLDER_MEANING_SOURCES,
 } from '../components/psychology';
+import type { PurposeAlignment } from '../components/psychology';
 import './Psychology.css';
 
 type PsychologyLayer =
@@ -29,6 +34,11 @@ type PsychologyLayer =
   | 'transformation' // Layer 6
   | 'purpose';    // Layer 7
 
+/** Layers backed by JSON files, which have schemas */
+const SCHEMA_LAYERS: LayerName[] = ['narrative', 'emotional', 'relational', 'prospective', 'transformation', 'purpose'];
+/** Layers stored in one file */
+const SINGLE_FILE_LAYERS: LayerName[] = ['narrative', 'emotional'];
+
 const LAYER_INFO: Record<PsychologyLayer, { name: string; description: string; theory: string }> = {
   overview: {
     name: 'Overview',
@@ -78,11 +88,35 @@ const LAYER_INFO: Record<PsychologyLayer, { name: string; description: string; t
 export default function Psychology() {
   const { layer = 'overview' } = useParams<{ layer?: string }>();
   const navigate = useNavigate();
-  const { soul, layers, loading, error } = usePsychology();
+  const { soul, layers, loading, error, validateLayer } = usePsychology();
+  const [alignment, setAlignment] = useState<PurposeAlignment | null>(null);
+  const [violations, setViolations] = useState<SchemaViolation[]>([]);
 
   const normalizedLayer = layer as PsychologyLayer;
   const layerInfo = LAYER_INFO[normalizedLayer] || LAYER_INFO.overview;
 
+  useEffect(() => {
+    if (normalizedLayer !== 'purpose') return;
+    invoke<PurposeAlignment>('get_purpose_alignment')
+      .then(setAlignment)
+      .catch((err) => console.error('Failed to load purpose alignment:', err));
+  }, [normalizedLayer]);
+
+  // Flag layer files that scripts or hand edits have pushed out of schema;
+  // saving them as they are would be refused
+  useEffect(() => {
+    setViolations([]);
+    if (!SCHEMA_LAYERS.includes(normalizedLayer as LayerName)) return;
+    const data = layers[normalizedLayer] as Record<string, unknown> | undefined;
+    if (!data) return;
+    // Loaded data is keyed by file; a single-file layer validates the file itself
+    const payload = SINGLE_FILE_LAYERS.includes(normalizedLayer as LayerName) ? Object.values(data)[0] : data;
+    if (payload === undefined) return;
+    validateLayer(normalizedLayer as LayerName, payload)
+      .then((result) => setViolations(result.violations))
+      .catch((err) => console.error('Failed to validate layer:', err));
+  }, [normalizedLayer, layers, validateLayer]);
+
   const handleLayerClick = (layerId: string) => {
     navigate(`/psychology/${layerId}`);
   };
@@ -149,16 +183,7 @@ export default function Psychology() {
         );
 
       case 'integration':
-        return (
-          <div className="layer-placeholder">
-            <span className="placeholder-icon">🔄</span>
-            <h3>Integration Rhythms</h3>
-            <p>Memory consolidation and synthesis cycles</p>
-            <p className="placeholder-hint">
-              This layer manages automatic memory reconsolidation processes.
-            </p>
-          </div>
-        );
+        return <IntegrationRhythms />;
 
       case 'transformation':
         return (
@@ -174,6 +199,7 @@ export default function Psychology() {
           <PurposeEngine
             ikigai={((layers.purpose as any)?.ikigai) || PLACEHOLDER_IKIGAI}
             meaningSources={((layers.purpose as any)?.meaningSources) || PLACEHOLDER_MEANING_SOURCES}
+            alignment={alignment}
             onElementClick={(element) => console.log('Element clicked:', element)}
           />
         );
@@ -208,6 +234,22 @@ export default function Psychology() {
       </nav>
 
       <main className="psychology-content">
+        {violations.length > 0 && (
+          <div className="psychology-schema-warning" role="alert">
+            <p>Some of this layer's data doesn't match its schema, so it can't be saved as it is:</p>
+            <ul>
+              {violations.map((violation, i) => (
+                <li key={i}>
+                  <code>
+                    {violation.file}
+                    {violation.path}
+                  </code>{' '}
+                  {violation.message}
+                </li>
+              ))}
+            </ul>
+          </div>
+        )}
         {renderLayerContent()}
       </main>
     </div>
@@ -62,6 +62,13 @@ export async function showNotification(
   _notificationType: NotificationType = 'info'
 ): Promise<void> {
   try {
+    // Quiet hours / do-not-disturb (policy/dnd.rs) hold notifications back
+    const dnd = await invoke<{ suppress_notifications: boolean }>('get_dnd_status').catch(() => null);
+    if (dnd?.suppress_notifications) {
+      console.log('[Notification] Suppressed by do-not-disturb');
+      return;
+    }
+
     const notifModule = await getNotificationModule();
     if (notifModule?.sendNotification) {
       await notifModule.sendNotification({
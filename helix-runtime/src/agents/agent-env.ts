@@ -0,0 +1,60 @@
+/**
+ * HELIX_AGENT_<ID>_* variables: Helix Desktop hands the gateway each
+ * agent's settings and secrets this way, and every process an agent starts
+ * gets only its own. Kept apart from agent-scope so process/exec can use
+ * it without importing the agent workspace code, which imports process/exec.
+ */
+
+import { normalizeAgentId } from "../routing/session-key.js";
+
+const AGENT_ENV_PREFIX = "HELIX_AGENT_";
+
+/**
+ * Comma-separated ids of the agents Helix Desktop passed HELIX_AGENT_<ID>_*
+ * variables for when it spawned the gateway.
+ */
+export const HELIX_AGENTS_ENV = "HELIX_AGENTS";
+
+/** Prefix of an agent's variables, e.g. HELIX_AGENT_MY_AGENT_ for "my-agent". */
+export function agentEnvPrefix(agentId: string): string {
+  return `${AGENT_ENV_PREFIX}${normalizeAgentId(agentId).toUpperCase().replace(/-/g, "_")}_`;
+}
+
+/**
+ * `env` for a process an agent starts: the gateway holds every agent's
+ * HELIX_AGENT_<ID>_* variables, but the process only gets its own agent's.
+ * A variable belongs to the listed agent with the longest matching prefix
+ * (Helix Desktop refuses settings where that would be ambiguous); one that
+ * belongs to no listed agent is dropped, as are all of them without an agent.
+ */
+export function scopeAgentEnv(
+  env: Record<string, string>,
+  agentId: string | undefined,
+): Record<string, string> {
+  const own = agentId ? agentEnvPrefix(agentId) : undefined;
+  const prefixes = (env[HELIX_AGENTS_ENV] ?? "")
+    .split(",")
+    .map((id) => id.trim())
+    .filter(Boolean)
+    .map(agentEnvPrefix);
+  if (own) {
+    prefixes.push(own);
+  }
+
+  const scoped: Record<string, string> = {};
+  for (const [key, value] of Object.entries(env)) {
+    if (key.startsWith(AGENT_ENV_PREFIX)) {
+      let owner: string | undefined;
+      for (const prefix of prefixes) {
+        if (key.startsWith(prefix) && (!owner || prefix.length > owner.length)) {
+          owner = prefix;
+        }
+      }
+      if (!own || owner !== own) {
+        continue;
+      }
+    }
+    scoped[key] = value;
+  }
+  return scoped;
+}
@@ -1,9 +1,12 @@
 import { describe, expect, it } from "vitest";
 import type { OpenClawConfig } from "../config/config.js";
 import {
+  agentWorkspaceEnvVar,
   resolveAgentConfig,
   resolveAgentModelFallbacksOverride,
   resolveAgentModelPrimary,
+  resolveAgentWorkspaceDir,
+  scopeAgentEnv,
 } from "./agent-scope.js";
 
 describe("resolveAgentConfig", () => {
@@ -201,3 +204,49 @@ describe("resolveAgentConfig", () => {
     expect(result?.workspace).toBe("~/openclaw");
   });
 });
+
+describe("resolveAgentWorkspaceDir", () => {
+  it("names the workspace variable after the agent id", () => {
+    expect(agentWorkspaceEnvVar("my-agent")).toBe("HELIX_AGENT_MY_AGENT_WORKSPACE");
+  });
+
+  it("prefers the workspace passed in the environment", () => {
+    const cfg: OpenClawConfig = {
+      agents: { list: [{ id: "coder", workspace: "/srv/configured" }] },
+    };
+    expect(
+      resolveAgentWorkspaceDir(cfg, "coder", { HELIX_AGENT_CODER_WORKSPACE: "/srv/passed" }),
+    ).toBe("/srv/passed");
+    expect(resolveAgentWorkspaceDir(cfg, "coder", {})).toBe("/srv/configured");
+  });
+});
+
+describe("scopeAgentEnv", () => {
+  const env = {
+    PATH: "/usr/bin",
+    HELIX_AGENTS: "coder,coder-review",
+    HELIX_AGENT_CODER_TOKEN: "coder-secret",
+    HELIX_AGENT_CODER_REVIEW_TOKEN: "review-secret",
+    HELIX_AGENT_OTHER_TOKEN: "unlisted",
+  };
+
+  it("keeps only the agent's own variables", () => {
+    expect(scopeAgentEnv(env, "coder")).toEqual({
+      PATH: "/usr/bin",
+      HELIX_AGENTS: "coder,coder-review",
+      HELIX_AGENT_CODER_TOKEN: "coder-secret",
+    });
+    expect(scopeAgentEnv(env, "coder-review")).toEqual({
+      PATH: "/usr/bin",
+      HELIX_AGENTS: "coder,coder-review",
+      HELIX_AGENT_CODER_REVIEW_TOKEN: "review-secret",
+    });
+  });
+
+  it("drops every agent's variables without an agent", () => {
+    expect(scopeAgentEnv(env, undefined)).toEqual({
+      PATH: "/usr/bin",
+      HELIX_AGENTS: "coder,coder-review",
+    });
+  });
+});
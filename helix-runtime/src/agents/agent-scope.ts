@@ -9,6 +9,7 @@ import {
   parseAgentSessionKey,
 } from "../routing/session-key.js";
 import { resolveUserPath } from "../utils.js";
+import { agentEnvPrefix } from "./agent-env.js";
 import { DEFAULT_AGENT_WORKSPACE_DIR } from "./workspace.js";
 
 export { resolveAgentIdFromSessionKey } from "../routing/session-key.js";
@@ -151,8 +152,27 @@ export function resolveAgentModelFallbacksOverride(
   return Array.isArray(raw.fallbacks) ? raw.fallbacks : undefined;
 }
 
-export function resolveAgentWorkspaceDir(cfg: OpenClawConfig, agentId: string) {
+export { HELIX_AGENTS_ENV, agentEnvPrefix, scopeAgentEnv } from "./agent-env.js";
+
+/**
+ * Variable through which Helix Desktop passes an agent's working directory
+ * when it spawns the gateway, e.g. HELIX_AGENT_MY_AGENT_WORKSPACE for
+ * "my-agent". It takes precedence over the configured workspace.
+ */
+export function agentWorkspaceEnvVar(agentId: string): string {
+  return `${agentEnvPrefix(agentId)}WORKSPACE`;
+}
+
+export function resolveAgentWorkspaceDir(
+  cfg: OpenClawConfig,
+  agentId: string,
+  env: NodeJS.ProcessEnv = process.env,
+) {
   const id = normalizeAgentId(agentId);
+  const passed = env[agentWorkspaceEnvVar(id)]?.trim();
+  if (passed) {
+    return resolveUserPath(passed);
+  }
   const configured = resolveAgentConfig(cfg, id)?.workspace?.trim();
   if (configured) {
     return resolveUserPath(configured);
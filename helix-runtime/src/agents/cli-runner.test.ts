@@ -1,4 +1,4 @@
-import { beforeEach, describe, expect, it, vi } from "vitest";
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 
 import type { CliBackendConfig } from "../config/types.js";
 import { runCliAgent } from "./cli-runner.js";
@@ -61,6 +61,66 @@ describe("runCliAgent resume cleanup", () => {
   });
 });
 
+describe("runCliAgent env", () => {
+  const agentEnv = {
+    HELIX_AGENTS: "coder,review",
+    HELIX_AGENT_CODER_TOKEN: "coder-secret",
+    HELIX_AGENT_REVIEW_TOKEN: "review-secret",
+  };
+  let previous: Record<string, string | undefined> = {};
+
+  beforeEach(() => {
+    runCommandWithTimeoutMock.mockReset();
+    runExecMock.mockReset();
+    runExecMock.mockResolvedValue({ stdout: "", stderr: "" });
+    previous = {};
+    for (const [key, value] of Object.entries(agentEnv)) {
+      previous[key] = process.env[key];
+      process.env[key] = value;
+    }
+  });
+
+  afterEach(() => {
+    for (const [key, value] of Object.entries(previous)) {
+      if (value === undefined) {
+        delete process.env[key];
+      } else {
+        process.env[key] = value;
+      }
+    }
+  });
+
+  it("passes only the session agent's variables to the CLI", async () => {
+    runCommandWithTimeoutMock.mockResolvedValueOnce({
+      stdout: "ok",
+      stderr: "",
+      code: 0,
+      signal: null,
+      killed: false,
+    });
+
+    await runCliAgent({
+      sessionId: "s1",
+      sessionKey: "agent:coder:main",
+      sessionFile: "/tmp/session.jsonl",
+      workspaceDir: "/tmp",
+      prompt: "hi",
+      provider: "codex-cli",
+      model: "gpt-5.2-codex",
+      timeoutMs: 1_000,
+      runId: "run-1",
+    });
+
+    const options = runCommandWithTimeoutMock.mock.calls[0]?.[1] as {
+      env: Record<string, string>;
+      agentId?: string;
+    };
+    expect(options.agentId).toBe("coder");
+    expect(options.env.HELIX_AGENT_CODER_TOKEN).toBe("coder-secret");
+    expect(options.env).not.toHaveProperty("HELIX_AGENT_REVIEW_TOKEN");
+  });
+});
+
 describe("cleanupSuspendedCliProcesses", () => {
   beforeEach(() => {
     runExecMock.mockReset();
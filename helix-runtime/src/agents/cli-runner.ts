@@ -8,7 +8,8 @@ import { createSubsystemLogger } from "../logging/subsystem.js";
 import { runCommandWithTimeout } from "../process/exec.js";
 import { resolveUserPath } from "../utils.js";
 import { resolveOpenClawDocsPath } from "./docs-path.js";
-import { resolveSessionAgentIds } from "./agent-scope.js";
+import { resolveSessionAgentIds, scopeAgentEnv } from "./agent-scope.js";
+import { coerceEnv } from "./bash-tools.shared.js";
 import { makeBootstrapWarn, resolveBootstrapContextForRun } from "./bootstrap-files.js";
 import { resolveCliBackendConfig } from "./cli-backends.js";
 import {
@@ -205,7 +206,11 @@ export async function runCliAgent(params: {
       }
 
       const env = (() => {
-        const next = { ...process.env, ...backend.env };
+        // Only this agent's HELIX_AGENT_<ID>_* variables, not every agent's
+        const next: Record<string, string> = {
+          ...scopeAgentEnv(coerceEnv(process.env), sessionAgentId),
+          ...backend.env,
+        };
         for (const key of backend.clearEnv ?? []) {
           delete next[key];
         }
@@ -222,6 +227,7 @@ export async function runCliAgent(params: {
         timeoutMs: params.timeoutMs,
         cwd: workspaceDir,
         env,
+        agentId: sessionAgentId,
         input: stdinPayload,
       });
 
@@ -1,6 +1,7 @@
 import { describe, expect, it } from "vitest";
 
-import { resolveSkillInvocationPolicy } from "./frontmatter.js";
+import { MANIFEST_KEY } from "../../compat/legacy-names.js";
+import { resolveOpenClawMetadata, resolveSkillInvocationPolicy } from "./frontmatter.js";
 
 describe("resolveSkillInvocationPolicy", () => {
   it("defaults to enabled behaviors", () => {
@@ -18,3 +19,34 @@ describe("resolveSkillInvocationPolicy", () => {
     expect(policy.disableModelInvocation).toBe(true);
   });
 });
+
+describe("resolveOpenClawMetadata actions", () => {
+  it("keeps actions with a name and a method", () => {
+    const metadata = resolveOpenClawMetadata({
+      metadata: JSON.stringify({
+        [MANIFEST_KEY]: {
+          actions: [
+            {
+              name: "check-inbox",
+              title: "Check inbox",
+              method: "chat.send",
+              params: { type: "object", properties: { query: { type: "string" } } },
+              keywords: "mail, email",
+            },
+            { name: "no-method" },
+            { method: "chat.send" },
+          ],
+        },
+      }),
+    });
+    expect(metadata?.actions).toEqual([
+      {
+        name: "check-inbox",
+        title: "Check inbox",
+        method: "chat.send",
+        params: { type: "object", properties: { query: { type: "string" } } },
+        keywords: ["mail", "email"],
+      },
+    ]);
+  });
+});
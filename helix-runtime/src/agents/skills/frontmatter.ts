@@ -7,6 +7,7 @@ import { parseBooleanValue } from "../../utils/boolean.js";
 import type {
   OpenClawSkillMetadata,
   ParsedSkillFrontmatter,
+  SkillActionSpec,
   SkillEntry,
   SkillInstallSpec,
   SkillInvocationPolicy,
@@ -100,6 +101,36 @@ function parseFrontmatterBool(value: string | undefined, fallback: boolean): boo
   return parsed === undefined ? fallback : parsed;
 }
 
+function parseActionSpec(input: unknown): SkillActionSpec | undefined {
+  if (!input || typeof input !== "object") {
+    return undefined;
+  }
+  const raw = input as Record<string, unknown>;
+  const name = typeof raw.name === "string" ? raw.name.trim() : "";
+  const method = typeof raw.method === "string" ? raw.method.trim() : "";
+  if (!name || !method) {
+    return undefined;
+  }
+
+  const spec: SkillActionSpec = { name, method };
+
+  if (typeof raw.title === "string") {
+    spec.title = raw.title;
+  }
+  if (typeof raw.description === "string") {
+    spec.description = raw.description;
+  }
+  if (raw.params && typeof raw.params === "object" && !Array.isArray(raw.params)) {
+    spec.params = raw.params as Record<string, unknown>;
+  }
+  const keywords = normalizeStringList(raw.keywords);
+  if (keywords.length > 0) {
+    spec.keywords = keywords;
+  }
+
+  return spec;
+}
+
 export function resolveOpenClawMetadata(
   frontmatter: ParsedSkillFrontmatter,
 ): OpenClawSkillMetadata | undefined {
@@ -133,6 +164,10 @@ export function resolveOpenClawMetadata(
     const install = installRaw
       .map((entry) => parseInstallSpec(entry))
       .filter((entry): entry is SkillInstallSpec => Boolean(entry));
+    const actionsRaw = Array.isArray(metadataObj.actions) ? (metadataObj.actions as unknown[]) : [];
+    const actions = actionsRaw
+      .map((entry) => parseActionSpec(entry))
+      .filter((entry): entry is SkillActionSpec => Boolean(entry));
     const osRaw = normalizeStringList(metadataObj.os);
     return {
       always: typeof metadataObj.always === "boolean" ? metadataObj.always : undefined,
@@ -150,6 +185,7 @@ export function resolveOpenClawMetadata(
           }
         : undefined,
       install: install.length > 0 ? install : undefined,
+      actions: actions.length > 0 ? actions : undefined,
     };
   } catch {
     return undefined;
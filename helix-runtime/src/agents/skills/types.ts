@@ -16,6 +16,18 @@ export type SkillInstallSpec = {
   targetDir?: string;
 };
 
+/** An action the skill makes invokable from Helix Desktop's palette and voice intents */
+export type SkillActionSpec = {
+  name: string;
+  title?: string;
+  description?: string;
+  /** Gateway method that carries the action out */
+  method: string;
+  /** JSON Schema of the method's params */
+  params?: Record<string, unknown>;
+  keywords?: string[];
+};
+
 export type OpenClawSkillMetadata = {
   always?: boolean;
   skillKey?: string;
@@ -30,6 +42,7 @@ export type OpenClawSkillMetadata = {
     config?: string[];
   };
   install?: SkillInstallSpec[];
+  actions?: SkillActionSpec[];
 };
 
 export type SkillInvocationPolicy = {
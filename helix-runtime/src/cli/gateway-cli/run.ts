@@ -34,6 +34,8 @@ type GatewayRunOpts = {
   port?: unknown;
   bind?: unknown;
   token?: unknown;
+  tokenStdin?: boolean;
+  tokenFile?: unknown;
   auth?: unknown;
   password?: unknown;
   tailscale?: unknown;
@@ -52,6 +54,14 @@ type GatewayRunOpts = {
 
 const gatewayLog = createSubsystemLogger("gateway");
 
+async function readStdin(): Promise<string> {
+  const chunks: Buffer[] = [];
+  for await (const chunk of process.stdin) {
+    chunks.push(Buffer.isBuffer(chunk) ? chunk : Buffer.from(String(chunk)));
+  }
+  return Buffer.concat(chunks).toString("utf8");
+}
+
 async function runGatewayCommand(opts: GatewayRunOpts) {
   const isDevProfile = process.env.OPENCLAW_PROFILE?.trim().toLowerCase() === "dev";
   const devMode = Boolean(opts.dev) || isDevProfile;
@@ -132,6 +142,31 @@ async function runGatewayCommand(opts: GatewayRunOpts) {
       return;
     }
   }
+  // --token-stdin / --token-file keep the secret out of process listings
+  const tokenFile = toOptionString(opts.tokenFile);
+  if (opts.tokenStdin || tokenFile) {
+    if ([opts.token, opts.tokenStdin, tokenFile].filter(Boolean).length > 1) {
+      defaultRuntime.error("Use only one of --token, --token-stdin and --token-file.");
+      defaultRuntime.exit(1);
+      return;
+    }
+    let token: string;
+    try {
+      token = (tokenFile ? fs.readFileSync(tokenFile, "utf8") : await readStdin()).trim();
+    } catch (err) {
+      defaultRuntime.error(`Failed to read gateway token: ${String(err)}`);
+      defaultRuntime.exit(1);
+      return;
+    }
+    if (!token) {
+      defaultRuntime.error(
+        tokenFile ? `Gateway token file is empty: ${tokenFile}` : "No gateway token on stdin.",
+      );
+      defaultRuntime.exit(1);
+      return;
+    }
+    opts.token = token;
+  }
   if (opts.token) {
     const token = toOptionString(opts.token);
     if (token) {
@@ -320,6 +355,8 @@ export function addGatewayRunCommand(cmd: Command): Command {
       "--token <token>",
       "Shared token required in connect.params.auth.token (default: OPENCLAW_GATEWAY_TOKEN env if set)",
     )
+    .option("--token-stdin", "Read the token from stdin instead of the command line", false)
+    .option("--token-file <path>", "Read the token from a file instead of the command line")
     .option("--auth <mode>", 'Gateway auth mode ("token"|"password")')
     .option("--password <password>", "Password for auth mode=password")
     .option("--tailscale <mode>", 'Tailscale exposure mode ("off"|"serve"|"funnel")')
@@ -291,6 +291,8 @@ const FIELD_LABELS: Record<string, string> = {
   "messages.ackReactionScope": "Ack Reaction Scope",
   "messages.inbound.debounceMs": "Inbound Message Debounce (ms)",
   "talk.apiKey": "Talk API Key",
+  "talk.speakerVerification.enabled": "Talk Speaker Verification",
+  "talk.speakerVerification.profilesPath": "Talk Voice Profiles Path",
   "channels.whatsapp": "WhatsApp",
   "channels.telegram": "Telegram",
   "channels.telegram.customCommands": "Telegram Custom Commands",
@@ -631,6 +633,10 @@ const FIELD_HELP: Record<string, string> = {
     'When to send ack reactions ("group-mentions", "group-all", "direct", "all").',
   "messages.inbound.debounceMs":
     "Debounce window (ms) for batching rapid inbound messages from the same sender (0 to disable).",
+  "talk.speakerVerification.enabled":
+    "Refuse sensitive voice commands (delete, send, pay, ...) unless an enrolled voice said them.",
+  "talk.speakerVerification.profilesPath":
+    "Where enrolled voiceprints are kept (default: helix-voice-profiles.json in the state dir). They never leave the machine.",
   "channels.telegram.dmPolicy":
     'Direct message access control ("pairing" recommended). "open" requires channels.telegram.allowFrom=["*"].',
   "channels.telegram.streamMode":
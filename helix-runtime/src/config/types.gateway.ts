@@ -59,6 +59,21 @@ export type TalkConfig = {
   apiKey?: string;
   /** Stop speaking when user starts talking (default: true). */
   interruptOnSpeech?: boolean;
+  /** Check who is speaking before sensitive voice commands. */
+  speakerVerification?: TalkSpeakerVerificationConfig;
+};
+
+export type TalkSpeakerVerificationConfig = {
+  /** Identify speakers and refuse sensitive commands from anyone else (default: false). */
+  enabled?: boolean;
+  /** Cosine similarity to an enrolled voice needed to count as that speaker (default: 0.85). */
+  threshold?: number;
+  /** Samples with enough speech needed to enroll a voice (default: 3). */
+  minEnrollmentSamples?: number;
+  /** Words or phrases that make a command sensitive (default: delete, send, pay, ...). */
+  sensitiveCommands?: string[];
+  /** Where enrolled voices are stored (default: helix-voice-profiles.json in the state dir). */
+  profilesPath?: string;
 };
 
 export type GatewayControlUiConfig = {
@@ -298,6 +298,16 @@ export const OpenClawSchema = z
         outputFormat: z.string().optional(),
         apiKey: z.string().optional(),
         interruptOnSpeech: z.boolean().optional(),
+        speakerVerification: z
+          .object({
+            enabled: z.boolean().optional(),
+            threshold: z.number().min(0).max(1).optional(),
+            minEnrollmentSamples: z.number().int().min(1).max(10).optional(),
+            sensitiveCommands: z.array(z.string()).optional(),
+            profilesPath: z.string().optional(),
+          })
+          .strict()
+          .optional(),
       })
       .strict()
       .optional(),
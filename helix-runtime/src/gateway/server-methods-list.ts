@@ -42,6 +42,9 @@ const BASE_METHODS = [
   "update.run",
   "voicewake.get",
   "voicewake.set",
+  "voice.speakers.list",
+  "voice.speakers.enroll",
+  "voice.speakers.remove",
   "sessions.list",
   "sessions.preview",
   "sessions.patch",
@@ -127,6 +130,7 @@ export const GATEWAY_EVENTS = [
   "presence",
   "tick",
   "talk.mode",
+  "talk-mode.transcript",
   "shutdown",
   "health",
   "heartbeat",
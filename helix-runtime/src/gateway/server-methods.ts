@@ -32,6 +32,7 @@ import type { GatewayRequestHandlers, GatewayRequestOptions } from './server-met
 import { updateHandlers } from './server-methods/update.js';
 import { usageHandlers } from './server-methods/usage.js';
 import { voiceHandlers } from './server-methods/voice.js';
+import { voiceSpeakersHandlers } from './server-methods/voice-speakers.js';
 import { voicewakeHandlers } from './server-methods/voicewake.js';
 import { webHandlers } from './server-methods/web.js';
 import { wizardHandlers } from './server-methods/wizard.js';
@@ -72,6 +73,7 @@ const READ_METHODS = new Set([
   'agent.identity.get',
   'skills.status',
   'voicewake.get',
+  'voice.speakers.list',
   'sessions.list',
   'sessions.preview',
   'cron.list',
@@ -105,6 +107,8 @@ const WRITE_METHODS = new Set([
   'tts.convert',
   'tts.setProvider',
   'voicewake.set',
+  'voice.speakers.enroll',
+  'voice.speakers.remove',
   'node.invoke',
   'chat.send',
   'chat.abort',
@@ -256,6 +260,7 @@ export const coreGatewayHandlers: GatewayRequestHandlers = {
   ...authProfilesHandlers,
   ...memorySynthesisHandlers,
   ...voiceHandlers,
+  ...voiceSpeakersHandlers,
   ...browserHandlers,
   ...emailHandlers,
   ...calendarHandlers,
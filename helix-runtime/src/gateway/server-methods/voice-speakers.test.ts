@@ -0,0 +1,132 @@
+import fs from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+
+import { sharedSpeakerVerifier } from "../../helix/voice/speaker-verification.js";
+import { voiceSpeakersHandlers } from "./voice-speakers.js";
+
+const mocks = vi.hoisted(() => ({
+  config: {} as Record<string, unknown>,
+}));
+
+vi.mock("../../config/config.js", () => ({
+  loadConfig: () => mocks.config,
+}));
+
+const noop = () => false;
+
+const alice = {
+  id: "alice",
+  name: "Alice",
+  voiceprint: [0.1, 0.2],
+  samples: 3,
+  enrolledAt: "2026-01-01",
+};
+
+async function call(method: keyof typeof voiceSpeakersHandlers, params: Record<string, unknown>) {
+  const respond = vi.fn();
+  await voiceSpeakersHandlers[method]({
+    params,
+    respond,
+    context: {} as unknown as Parameters<(typeof voiceSpeakersHandlers)[typeof method]>[0]["context"],
+    client: null,
+    req: { id: "req-1", type: "req", method },
+    isWebchatConnect: noop,
+  });
+  return respond;
+}
+
+describe("voice.speakers", () => {
+  let stateDir: string;
+  const previousStateDir = process.env.OPENCLAW_STATE_DIR;
+
+  beforeEach(async () => {
+    stateDir = await fs.mkdtemp(path.join(os.tmpdir(), "openclaw-speakers-"));
+    process.env.OPENCLAW_STATE_DIR = stateDir;
+    mocks.config = {};
+  });
+
+  afterEach(async () => {
+    if (previousStateDir === undefined) {
+      delete process.env.OPENCLAW_STATE_DIR;
+    } else {
+      process.env.OPENCLAW_STATE_DIR = previousStateDir;
+    }
+    await fs.rm(stateDir, { recursive: true, force: true });
+  });
+
+  it("lists profiles without their voiceprints", async () => {
+    await fs.writeFile(
+      path.join(stateDir, "helix-voice-profiles.json"),
+      JSON.stringify({ profiles: [alice] }),
+    );
+
+    const respond = await call("voice.speakers.list", {});
+
+    expect(respond).toHaveBeenCalledWith(true, {
+      profiles: [{ id: "alice", name: "Alice", samples: 3, enrolledAt: "2026-01-01" }],
+    });
+  });
+
+  it("rejects samples that are not 16-bit audio", async () => {
+    const respond = await call("voice.speakers.enroll", {
+      name: "Alice",
+      samples: [{ pcm: Buffer.alloc(3).toString("base64"), sampleRate: 16000 }],
+    });
+
+    expect(respond).toHaveBeenCalledWith(
+      false,
+      undefined,
+      expect.objectContaining({ message: expect.stringContaining("not 16-bit audio") }),
+    );
+  });
+
+  it("reports samples without enough speech", async () => {
+    const silence = { pcm: Buffer.alloc(16000 * 2).toString("base64"), sampleRate: 16000 };
+    const respond = await call("voice.speakers.enroll", {
+      name: "Alice",
+      samples: [silence, silence, silence],
+    });
+
+    expect(respond).toHaveBeenCalledWith(
+      false,
+      undefined,
+      expect.objectContaining({ message: expect.stringContaining("samples with enough speech") }),
+    );
+  });
+
+  it("removes nothing for an unknown id", async () => {
+    const respond = await call("voice.speakers.remove", { id: "nobody" });
+
+    expect(respond).toHaveBeenCalledWith(true, { removed: false });
+  });
+
+  it("reads profiles from talk.speakerVerification.profilesPath", async () => {
+    const profilesPath = path.join(stateDir, "household", "voices.json");
+    await fs.mkdir(path.dirname(profilesPath));
+    await fs.writeFile(profilesPath, JSON.stringify({ profiles: [alice] }));
+    mocks.config = { talk: { speakerVerification: { enabled: true, profilesPath } } };
+
+    const respond = await call("voice.speakers.list", {});
+
+    expect(respond).toHaveBeenCalledWith(true, {
+      profiles: [{ id: "alice", name: "Alice", samples: 3, enrolledAt: "2026-01-01" }],
+    });
+  });
+
+  it("removes from the verifier the voice engine uses", async () => {
+    await fs.writeFile(
+      path.join(stateDir, "helix-voice-profiles.json"),
+      JSON.stringify({ profiles: [alice] }),
+    );
+    const engineVerifier = await sharedSpeakerVerifier({ enabled: true });
+    expect(engineVerifier.hasProfiles()).toBe(true);
+
+    const respond = await call("voice.speakers.remove", { id: "alice" });
+
+    expect(respond).toHaveBeenCalledWith(true, { removed: true });
+    expect(engineVerifier.hasProfiles()).toBe(false);
+  });
+});
@@ -0,0 +1,168 @@
+import { loadConfig } from "../../config/config.js";
+import {
+  type SpeakerVerifier,
+  sharedSpeakerVerifier,
+} from "../../helix/voice/speaker-verification.js";
+import type { AudioChunk, SpeakerProfile } from "../../helix/voice/types.js";
+import { ErrorCodes, errorShape } from "../protocol/index.js";
+import { formatForLog } from "../ws-log.js";
+import type { GatewayRequestHandlers } from "./types.js";
+
+/** Enrollment samples accepted in one request */
+const MAX_SAMPLES = 10;
+/** A sample longer than this is not one utterance */
+const MAX_SAMPLE_SECONDS = 15;
+const MIN_SAMPLE_RATE = 8000;
+const MAX_SAMPLE_RATE = 48000;
+
+/** What clients see of a profile; the voiceprint never leaves the machine */
+function describeProfile(profile: SpeakerProfile) {
+  return {
+    id: profile.id,
+    name: profile.name,
+    samples: profile.samples,
+    enrolledAt: profile.enrolledAt,
+  };
+}
+
+/**
+ * The verifier for `talk.speakerVerification`, the one the voice engine and
+ * transcript checks use, so changes count without a restart
+ */
+function loadVerifier(): Promise<SpeakerVerifier> {
+  return sharedSpeakerVerifier(loadConfig().talk?.speakerVerification ?? {});
+}
+
+/**
+ * Audio from a `{ pcm, sampleRate }` entry, pcm being base64 16-bit
+ * little-endian mono; a string describing the problem otherwise
+ */
+function parseSample(entry: unknown): AudioChunk[] | string {
+  const sample = entry as { pcm?: unknown; sampleRate?: unknown } | null;
+  const sampleRate = sample?.sampleRate;
+  if (
+    typeof sampleRate !== "number" ||
+    !Number.isInteger(sampleRate) ||
+    sampleRate < MIN_SAMPLE_RATE ||
+    sampleRate > MAX_SAMPLE_RATE
+  ) {
+    return `sampleRate must be an integer from ${MIN_SAMPLE_RATE} to ${MAX_SAMPLE_RATE}`;
+  }
+  if (typeof sample?.pcm !== "string" || !sample.pcm) {
+    return "pcm must be base64 audio";
+  }
+  const pcm = Buffer.from(sample.pcm, "base64");
+  if (pcm.length === 0 || pcm.length % 2 !== 0) {
+    return "pcm is not 16-bit audio";
+  }
+  if (pcm.length / 2 / sampleRate > MAX_SAMPLE_SECONDS) {
+    return `longer than ${MAX_SAMPLE_SECONDS} seconds`;
+  }
+  return [{ data: pcm, sampleRate, channels: 1, timestamp: Date.now() }];
+}
+
+/** Enrollment samples from `{ pcm, sampleRate }` entries (see parseSample) */
+function parseSamples(raw: unknown): AudioChunk[][] | string {
+  if (!Array.isArray(raw) || raw.length === 0) {
+    return "samples must be a non-empty array";
+  }
+  if (raw.length > MAX_SAMPLES) {
+    return `at most ${MAX_SAMPLES} samples are accepted`;
+  }
+  const samples: AudioChunk[][] = [];
+  for (const [index, entry] of raw.entries()) {
+    const sample = parseSample(entry);
+    if (typeof sample === "string") {
+      return `sample ${index + 1}: ${sample}`;
+    }
+    samples.push(sample);
+  }
+  return samples;
+}
+
+/** Who said a talk mode transcript, as clients see it */
+export type TranscriptSpeaker = {
+  /** Asks for something only an enrolled voice may do (talk.speakerVerification.sensitiveCommands) */
+  sensitive: boolean;
+  /** An enrolled voice matched; false without verification, audio or profiles */
+  verified: boolean;
+  /** The enrolled voice it matched */
+  name: string | null;
+};
+
+/**
+ * Check a transcript's speaker against the enrolled voices, from the
+ * utterance's audio (`{ pcm, sampleRate }`) when the node sent it
+ */
+export async function checkTranscriptSpeaker(
+  text: string,
+  audio: unknown,
+): Promise<TranscriptSpeaker> {
+  const config = loadConfig().talk?.speakerVerification ?? {};
+  const verifier = await sharedSpeakerVerifier(config);
+  const checked: TranscriptSpeaker = { sensitive: verifier.isSensitive(text), verified: false, name: null };
+  if (!config.enabled || audio === undefined || audio === null) {
+    return checked;
+  }
+  const chunks = parseSample(audio);
+  if (typeof chunks === "string") {
+    return checked;
+  }
+  const match = verifier.identify(chunks);
+  return { ...checked, verified: match?.verified ?? false, name: match?.verified ? match.name : null };
+}
+
+export const voiceSpeakersHandlers: GatewayRequestHandlers = {
+  "voice.speakers.list": async ({ respond }) => {
+    try {
+      const verifier = await loadVerifier();
+      respond(true, { profiles: verifier.listProfiles().map(describeProfile) });
+    } catch (err) {
+      respond(false, undefined, errorShape(ErrorCodes.UNAVAILABLE, formatForLog(err)));
+    }
+  },
+  "voice.speakers.enroll": async ({ params, respond }) => {
+    const name = typeof params.name === "string" ? params.name.trim() : "";
+    if (!name) {
+      respond(
+        false,
+        undefined,
+        errorShape(ErrorCodes.INVALID_REQUEST, "voice.speakers.enroll requires name: string"),
+      );
+      return;
+    }
+    const samples = parseSamples(params.samples);
+    if (typeof samples === "string") {
+      respond(
+        false,
+        undefined,
+        errorShape(ErrorCodes.INVALID_REQUEST, `invalid voice.speakers.enroll params: ${samples}`),
+      );
+      return;
+    }
+    try {
+      const verifier = await loadVerifier();
+      const profile = await verifier.enroll(name, samples);
+      respond(true, { profile: describeProfile(profile) });
+    } catch (err) {
+      // Mostly samples without enough speech, which the user can re-record
+      respond(false, undefined, errorShape(ErrorCodes.INVALID_REQUEST, formatForLog(err)));
+    }
+  },
+  "voice.speakers.remove": async ({ params, respond }) => {
+    if (typeof params.id !== "string" || !params.id) {
+      respond(
+        false,
+        undefined,
+        errorShape(ErrorCodes.INVALID_REQUEST, "voice.speakers.remove requires id: string"),
+      );
+      return;
+    }
+    try {
+      const verifier = await loadVerifier();
+      respond(true, { removed: await verifier.remove(params.id) });
+    } catch (err) {
+      respond(false, undefined, errorShape(ErrorCodes.UNAVAILABLE, formatForLog(err)));
+    }
+  },
+};
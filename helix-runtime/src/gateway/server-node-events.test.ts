@@ -1,4 +1,13 @@
-import { beforeEach, describe, expect, it, vi } from "vitest";
+import fs from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+
+const mocks = vi.hoisted(() => ({
+  config: {} as Record<string, unknown>,
+  agentCommand: vi.fn(),
+}));
 
 vi.mock("../infra/system-events.js", () => ({
   enqueueSystemEvent: vi.fn(),
@@ -6,9 +15,23 @@ vi.mock("../infra/system-events.js", () => ({
 vi.mock("../infra/heartbeat-wake.js", () => ({
   requestHeartbeatNow: vi.fn(),
 }));
+vi.mock("../config/config.js", () => ({
+  loadConfig: () => mocks.config,
+}));
+vi.mock("../commands/agent.js", () => ({
+  agentCommand: mocks.agentCommand,
+}));
+vi.mock("./session-utils.js", () => ({
+  loadSessionEntry: (sessionKey: string) => ({
+    storePath: undefined,
+    entry: undefined,
+    canonicalKey: sessionKey,
+  }),
+}));
 
 import { enqueueSystemEvent } from "../infra/system-events.js";
 import { requestHeartbeatNow } from "../infra/heartbeat-wake.js";
+import { extractVoiceprint } from "../helix/voice/speaker-verification.js";
 import { handleNodeEvent } from "./server-node-events.js";
 import type { NodeEventContext } from "./server-node-events-types.js";
 import type { HealthSummary } from "../commands/health.js";
@@ -102,3 +125,108 @@ describe("node exec events", () => {
     expect(requestHeartbeatNowMock).toHaveBeenCalledWith({ reason: "exec-event" });
   });
 });
+
+/** A second of a voice-like sound: harmonics under a syllable envelope */
+function speech(pitch: number): Buffer {
+  const sampleRate = 16000;
+  const pcm = Buffer.alloc(sampleRate * 2);
+  for (let i = 0; i < sampleRate; i++) {
+    const t = i / sampleRate;
+    let value = 0;
+    for (let h = 1; pitch * h < 4000; h++) {
+      value += Math.sin(2 * Math.PI * pitch * h * t) / h;
+    }
+    value *= 0.5 + 0.5 * Math.sin(2 * Math.PI * 3 * t) ** 2;
+    pcm.writeInt16LE(Math.round(value * 5000), i * 2);
+  }
+  return pcm;
+}
+
+describe("node voice transcripts", () => {
+  let stateDir: string;
+  const previousStateDir = process.env.OPENCLAW_STATE_DIR;
+
+  beforeEach(async () => {
+    stateDir = await fs.mkdtemp(path.join(os.tmpdir(), "openclaw-transcripts-"));
+    process.env.OPENCLAW_STATE_DIR = stateDir;
+    mocks.config = {};
+    mocks.agentCommand.mockReset();
+    mocks.agentCommand.mockResolvedValue(undefined);
+  });
+
+  afterEach(async () => {
+    if (previousStateDir === undefined) {
+      delete process.env.OPENCLAW_STATE_DIR;
+    } else {
+      process.env.OPENCLAW_STATE_DIR = previousStateDir;
+    }
+    await fs.rm(stateDir, { recursive: true, force: true });
+  });
+
+  async function transcript(payload: Record<string, unknown>) {
+    const broadcast = vi.fn();
+    await handleNodeEvent({ ...buildCtx(), broadcast }, "node-1", {
+      event: "voice.transcript",
+      payloadJSON: JSON.stringify({ sessionKey: "agent:main:main", ...payload }),
+    });
+    const sent = broadcast.mock.calls.find(([event]) => event === "talk-mode.transcript");
+    return sent?.[1] as { text: string; speaker: Record<string, unknown> } | undefined;
+  }
+
+  it("broadcasts the transcript with who said it", async () => {
+    const sent = await transcript({ text: "what is on my calendar" });
+
+    expect(sent).toMatchObject({
+      text: "what is on my calendar",
+      sessionKey: "agent:main:main",
+      nodeId: "node-1",
+      speaker: { sensitive: false, verified: false, name: null },
+    });
+    expect(mocks.agentCommand).toHaveBeenCalledTimes(1);
+  });
+
+  it("marks sensitive commands unverified without verification", async () => {
+    const sent = await transcript({ text: "delete my emails" });
+
+    expect(sent?.speaker).toEqual({ sensitive: true, verified: false, name: null });
+    // Verification is off, so the agent still gets it as before
+    expect(mocks.agentCommand).toHaveBeenCalledTimes(1);
+  });
+
+  it("refuses sensitive commands from an unverified speaker", async () => {
+    mocks.config = { talk: { speakerVerification: { enabled: true } } };
+
+    const sent = await transcript({ text: "delete my emails" });
+
+    expect(sent?.speaker).toEqual({ sensitive: true, verified: false, name: null });
+    expect(mocks.agentCommand).not.toHaveBeenCalled();
+  });
+
+  it("verifies the speaker from the utterance's audio", async () => {
+    const profilesPath = path.join(stateDir, "voices.json");
+    const pcm = speech(180);
+    await fs.writeFile(
+      profilesPath,
+      JSON.stringify({
+        profiles: [
+          {
+            id: "alice",
+            name: "Alice",
+            voiceprint: extractVoiceprint(pcm, 16000),
+            samples: 1,
+            enrolledAt: "2026-01-01",
+          },
+        ],
+      }),
+    );
+    mocks.config = { talk: { speakerVerification: { enabled: true, profilesPath } } };
+
+    const sent = await transcript({
+      text: "delete my emails",
+      audio: { pcm: pcm.toString("base64"), sampleRate: 16000 },
+    });
+
+    expect(sent?.speaker).toEqual({ sensitive: true, verified: true, name: "Alice" });
+    expect(mocks.agentCommand).toHaveBeenCalledTimes(1);
+  });
+});
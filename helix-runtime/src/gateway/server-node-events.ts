@@ -8,6 +8,7 @@ import { enqueueSystemEvent } from "../infra/system-events.js";
 import { normalizeMainKey } from "../routing/session-key.js";
 import { defaultRuntime } from "../runtime.js";
 import type { NodeEvent, NodeEventContext } from "./server-node-events-types.js";
+import { checkTranscriptSpeaker } from "./server-methods/voice-speakers.js";
 import { loadSessionEntry } from "./session-utils.js";
 import { formatForLog } from "./ws-log.js";
 
@@ -36,6 +37,20 @@ export const handleNodeEvent = async (ctx: NodeEventContext, nodeId: string, evt
       const cfg = loadConfig();
       const rawMainKey = normalizeMainKey(cfg.session?.mainKey);
       const sessionKey = sessionKeyRaw.length > 0 ? sessionKeyRaw : rawMainKey;
+
+      // Operators (Helix Desktop's talk mode) route transcripts to actions;
+      // they refuse sensitive ones unless an enrolled voice spoke
+      const speaker = await checkTranscriptSpeaker(text, obj.audio);
+      ctx.broadcast(
+        "talk-mode.transcript",
+        { text, sessionKey, nodeId, speaker, ts: Date.now() },
+        { dropIfSlow: true },
+      );
+      if (cfg.talk?.speakerVerification?.enabled && speaker.sensitive && !speaker.verified) {
+        ctx.logGateway.warn(`blocked sensitive voice command from unverified speaker node=${nodeId}`);
+        return;
+      }
+
       const { storePath, entry, canonicalKey } = loadSessionEntry(sessionKey);
       const now = Date.now();
       const sessionId = entry?.sessionId ?? randomUUID();
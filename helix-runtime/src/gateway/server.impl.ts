@@ -44,6 +44,8 @@ import {
 import { startGatewayDiscovery } from "./server-discovery-runtime.js";
 import { ExecApprovalManager } from "./exec-approval-manager.js";
 import { createExecApprovalHandlers } from "./server-methods/exec-approval.js";
+import { listWorkspaceDirs } from "./server-methods/skills.js";
+import { syncDesktopActions } from "../helix/desktop-actions.js";
 import { createExecApprovalForwarder } from "../infra/exec-approval-forwarder.js";
 import type { startBrowserControlServerIfEnabled } from "./server-browser.js";
 import { createChannelManager } from "./server-channels.js";
@@ -360,6 +362,7 @@ export async function startGatewayServer(
 
   setSkillsRemoteRegistry(nodeRegistry);
   void primeRemoteSkillsCache();
+  void syncDesktopActions({ workspaceDirs: listWorkspaceDirs(cfgAtStart), config: cfgAtStart });
   // Debounce skills-triggered node probes to avoid feedback loops and rapid-fire invokes.
   // Skills changes can happen in bursts (e.g., file watcher events), and each probe
   // takes time to complete. A 30-second delay ensures we batch changes together.
@@ -376,6 +379,7 @@ export async function startGatewayServer(
       skillsRefreshTimer = null;
       const latest = loadConfig();
       void refreshRemoteBinsForConnectedNodes(latest);
+      void syncDesktopActions({ workspaceDirs: listWorkspaceDirs(latest), config: latest });
     }, skillsRefreshDelayMs);
   });
 
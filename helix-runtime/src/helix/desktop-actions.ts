@@ -0,0 +1,155 @@
+/**
+ * HELIX DESKTOP ACTIONS
+ * Declares the actions skills make invokable to Helix Desktop
+ *
+ * A skill lists actions in its metadata (`actions`: name, gateway method,
+ * params schema). The gateway PUTs each skill's list to the desktop's local
+ * API, where it replaces what the skill declared before, and removes the
+ * actions of skills that are gone or filtered out. The desktop lists them in
+ * its command palette and routes voice intents to them.
+ *
+ * The desktop passes the API's address in HELIX_API_URL (and the token file
+ * in HELIX_API_TOKEN_FILE) when it starts the gateway; without them nothing
+ * is declared.
+ */
+
+import fs from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import { filterWorkspaceSkillEntries, loadWorkspaceSkillEntries } from "../agents/skills.js";
+import { resolveSkillKey } from "../agents/skills/frontmatter.js";
+import type { SkillActionSpec } from "../agents/skills/types.js";
+import type { OpenClawConfig } from "../config/config.js";
+import { createSubsystemLogger } from "../logging/subsystem.js";
+
+const log = createSubsystemLogger("helix/desktop-actions");
+
+/** Actions the desktop accepts from one owner (MAX_ACTIONS_PER_OWNER there) */
+const MAX_ACTIONS_PER_SKILL = 50;
+const REQUEST_TIMEOUT_MS = 5_000;
+
+type DesktopApi = {
+  url: string;
+  token: string;
+};
+
+/** A declared action as the desktop lists it */
+type DeclaredAction = {
+  source: string;
+  owner: string;
+};
+
+async function resolveDesktopApi(): Promise<DesktopApi | null> {
+  const url = process.env.HELIX_API_URL?.trim();
+  if (!url) {
+    return null;
+  }
+  const tokenFile =
+    process.env.HELIX_API_TOKEN_FILE?.trim() || path.join(os.homedir(), ".helix", "api-token");
+  try {
+    const token = (await fs.readFile(tokenFile, "utf8")).trim();
+    return token ? { url: url.replace(/\/+$/, ""), token } : null;
+  } catch (err) {
+    log.warn(`Cannot read the desktop API token from ${tokenFile}: ${String(err)}`);
+    return null;
+  }
+}
+
+async function request(
+  api: DesktopApi,
+  method: "GET" | "PUT" | "DELETE",
+  route: string,
+  body?: unknown,
+): Promise<unknown> {
+  const response = await fetch(`${api.url}${route}`, {
+    method,
+    headers: {
+      Authorization: `Bearer ${api.token}`,
+      "Content-Type": "application/json",
+    },
+    body: body === undefined ? undefined : JSON.stringify(body),
+    signal: AbortSignal.timeout(REQUEST_TIMEOUT_MS),
+  });
+  if (!response.ok) {
+    throw new Error(`${method} ${route}: ${response.status} ${await response.text()}`);
+  }
+  return response.json();
+}
+
+/**
+ * Actions of the skills enabled in `workspaceDirs`, by skill key
+ */
+export function collectSkillActions(
+  workspaceDirs: string[],
+  config?: OpenClawConfig,
+): Map<string, SkillActionSpec[]> {
+  const actions = new Map<string, SkillActionSpec[]>();
+  for (const workspaceDir of workspaceDirs) {
+    const entries = filterWorkspaceSkillEntries(
+      loadWorkspaceSkillEntries(workspaceDir, { config }),
+      config,
+    );
+    for (const entry of entries) {
+      const specs = entry.metadata?.actions;
+      const skillKey = resolveSkillKey(entry.skill, entry);
+      if (!specs || specs.length === 0 || actions.has(skillKey)) {
+        continue;
+      }
+      if (specs.length > MAX_ACTIONS_PER_SKILL) {
+        log.warn(
+          `Skill ${skillKey} declares ${specs.length} actions; only the first ${MAX_ACTIONS_PER_SKILL} are offered`,
+        );
+      }
+      actions.set(skillKey, specs.slice(0, MAX_ACTIONS_PER_SKILL));
+    }
+  }
+  return actions;
+}
+
+/**
+ * Bring the desktop's skill actions in line with the skills in `workspaceDirs`
+ */
+export async function syncDesktopActions(params: {
+  workspaceDirs: string[];
+  config?: OpenClawConfig;
+}): Promise<void> {
+  const api = await resolveDesktopApi();
+  if (!api) {
+    return;
+  }
+  const declared = collectSkillActions(params.workspaceDirs, params.config);
+
+  let existing: DeclaredAction[];
+  try {
+    existing = (await request(api, "GET", "/actions")) as DeclaredAction[];
+  } catch (err) {
+    log.warn(`Helix Desktop is unreachable; skill actions not declared: ${String(err)}`);
+    return;
+  }
+
+  const stale = new Set(
+    existing
+      .filter((action) => action.source === "skill" && !declared.has(action.owner))
+      .map((action) => action.owner),
+  );
+  for (const owner of stale) {
+    try {
+      await request(api, "DELETE", `/actions/skill/${encodeURIComponent(owner)}`);
+    } catch (err) {
+      log.warn(`Failed to remove the actions of skill ${owner}: ${String(err)}`);
+    }
+  }
+
+  // One skill's invalid declaration must not keep the others out
+  for (const [skillKey, actions] of declared) {
+    try {
+      await request(api, "PUT", `/actions/skill/${encodeURIComponent(skillKey)}`, { actions });
+    } catch (err) {
+      log.warn(`Failed to declare the actions of skill ${skillKey}: ${String(err)}`);
+    }
+  }
+
+  log.info(`Declared actions of ${declared.size} skills to Helix Desktop`, {
+    removed: stale.size,
+  });
+}
@@ -124,6 +124,19 @@ export const ConversationConfigSchema = Type.Object({
   greetingMessage: Type.Optional(Type.String()),
 });
 
+/**
+ * Speaker verification configuration schema
+ */
+export const SpeakerVerificationConfigSchema = Type.Object({
+  enabled: Type.Boolean({ default: false }),
+  threshold: Type.Number({ minimum: 0, maximum: 1, default: 0.85 }),
+  minEnrollmentSamples: Type.Number({ minimum: 1, maximum: 10, default: 3 }),
+  sensitiveCommands: Type.Array(Type.String(), {
+    default: ["delete", "remove", "send", "pay", "purchase", "buy", "transfer", "unlock", "password"],
+  }),
+  profilesPath: Type.Optional(Type.String()),
+});
+
 /**
  * Audio device configuration schema
  */
@@ -143,6 +156,7 @@ export const VoiceConfigSchema = Type.Object({
   tts: Type.Optional(TTSConfigSchema),
   vad: Type.Optional(VADConfigSchema),
   conversation: Type.Optional(ConversationConfigSchema),
+  speakerVerification: Type.Optional(SpeakerVerificationConfigSchema),
   audio: AudioConfigSchema,
 });
 
@@ -176,6 +190,7 @@ export type STTConfig = Static<typeof STTConfigSchema>;
 export type TTSConfig = Static<typeof TTSConfigSchema>;
 export type VADConfig = Static<typeof VADConfigSchema>;
 export type ConversationConfig = Static<typeof ConversationConfigSchema>;
+export type SpeakerVerificationConfig = Static<typeof SpeakerVerificationConfigSchema>;
 export type AudioConfig = Static<typeof AudioConfigSchema>;
 export type VoiceConfig = Static<typeof VoiceConfigSchema>;
 export type SyncConfig = Static<typeof SyncConfigSchema>;
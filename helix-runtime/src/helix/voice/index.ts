@@ -31,13 +31,16 @@ import {
   type VoiceTranscript,
   type AudioChunk,
   type VoiceEngineEvents,
+  type SpeakerProfile,
   DEFAULT_VOICE_CONFIG,
+  DEFAULT_SPEAKER_VERIFICATION_CONFIG,
 } from "./types.js";
 import { AudioRecorder, AudioPlayer, isAudioAvailable } from "./audio-devices.js";
 import { VoiceActivityDetector, combineAudioChunks, toWavBuffer } from "./voice-activity.js";
 import { WakeWordDetector, playConfirmationSound } from "./wake-word.js";
 import { SpeechToText } from "./speech-to-text.js";
 import { TextToSpeech } from "./text-to-speech.js";
+import { type SpeakerVerifier, sharedSpeakerVerifier } from "./speaker-verification.js";
 
 const log = createSubsystemLogger("helix:voice");
 
@@ -48,6 +51,12 @@ export { VoiceActivityDetector, combineAudioChunks, toWavBuffer } from "./voice-
 export { WakeWordDetector, playConfirmationSound } from "./wake-word.js";
 export { SpeechToText } from "./speech-to-text.js";
 export { TextToSpeech, listElevenLabsVoices } from "./text-to-speech.js";
+export {
+  SpeakerVerifier,
+  extractVoiceprint,
+  cosineSimilarity,
+  sharedSpeakerVerifier,
+} from "./speaker-verification.js";
 
 /**
  * Main Voice Engine class
@@ -65,6 +74,7 @@ export class VoiceEngine extends EventEmitter {
   private wakeWord: WakeWordDetector | null = null;
   private stt: SpeechToText | null = null;
   private tts: TextToSpeech | null = null;
+  private speakers: SpeakerVerifier | null = null;
 
   // State tracking
   private startTime: number = 0;
@@ -86,6 +96,7 @@ export class VoiceEngine extends EventEmitter {
       tts: { ...DEFAULT_VOICE_CONFIG.tts, ...config.tts },
       vad: { ...DEFAULT_VOICE_CONFIG.vad, ...config.vad },
       conversation: { ...DEFAULT_VOICE_CONFIG.conversation, ...config.conversation },
+      speakerVerification: { ...DEFAULT_SPEAKER_VERIFICATION_CONFIG, ...config.speakerVerification },
       audio: config.audio,
     };
 
@@ -99,6 +110,7 @@ export class VoiceEngine extends EventEmitter {
       totalSpeechDuration: 0,
       totalTTSDuration: 0,
       wakeWordDetections: 0,
+      blockedCommands: 0,
       errors: 0,
       lastActivity: null,
     };
@@ -136,6 +148,12 @@ export class VoiceEngine extends EventEmitter {
     this.vad = new VoiceActivityDetector(this.config.vad);
     this.setupVADListeners();
 
+    // Initialize speaker verification
+    // The process's shared verifier, so enrollments through the gateway count at once
+    if (this.config.speakerVerification?.enabled) {
+      this.speakers = await sharedSpeakerVerifier(this.config.speakerVerification);
+    }
+
     // Initialize wake word detector
     if (this.config.wakeWord.enabled && this.mode === "wake-word") {
       this.wakeWord = new WakeWordDetector(this.config.wakeWord);
@@ -342,6 +360,31 @@ export class VoiceEngine extends EventEmitter {
     this.onTranscriptCallback = callback;
   }
 
+  /**
+   * Enroll a household member from a few recorded utterances
+   * (speaker verification must be enabled)
+   */
+  async enrollSpeaker(name: string, samples: AudioChunk[][]): Promise<SpeakerProfile> {
+    if (!this.speakers) {
+      throw new Error("Speaker verification is not enabled");
+    }
+    return this.speakers.enroll(name, samples);
+  }
+
+  /**
+   * Remove an enrolled voice profile
+   */
+  async removeSpeaker(id: string): Promise<boolean> {
+    return (await this.speakers?.remove(id)) ?? false;
+  }
+
+  /**
+   * Enrolled voice profiles
+   */
+  listSpeakers(): SpeakerProfile[] {
+    return this.speakers?.listProfiles() ?? [];
+  }
+
   /**
    * Set voice mode
    */
@@ -510,9 +553,33 @@ export class VoiceEngine extends EventEmitter {
       this.stats.totalSpeechDuration += transcript.duration;
       this.stats.lastActivity = new Date();
 
-      log.info(`Transcript: "${transcript.text}"`);
+      if (this.speakers) {
+        transcript.speaker = this.speakers.identify(chunks);
+      }
+
+      log.info(`Transcript: "${transcript.text}"`, { speaker: transcript.speaker?.name });
       this.emit("transcript:final", transcript);
 
+      // Sensitive commands run only for a verified enrolled speaker
+      if (this.speakers?.isSensitive(transcript.text) && !transcript.speaker?.verified) {
+        const reason = this.speakers.hasProfiles() ? "unverified" : "no-profiles";
+        log.warn(`Blocked sensitive voice command (${reason})`, {
+          similarity: transcript.speaker?.similarity,
+        });
+        this.stats.blockedCommands++;
+        this.emit("command:blocked", {
+          text: transcript.text,
+          reason,
+          speaker: transcript.speaker ?? null,
+        });
+        if (this.mode === "always-on") {
+          await this.startListening();
+        } else {
+          this.transitionTo("idle");
+        }
+        return;
+      }
+
       // Process with callback if set
       if (this.onTranscriptCallback) {
         this.transitionTo("thinking");
@@ -0,0 +1,130 @@
+/**
+ * Test Suite: Speaker Verification
+ *
+ * Voiceprints from synthetic voices (harmonics shaped by formants), local
+ * enrollment and identification, and sensitive command matching.
+ */
+
+import fs from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import { describe, it, expect, beforeEach, afterEach } from "vitest";
+import { SpeakerVerifier, cosineSimilarity, extractVoiceprint } from "./speaker-verification.js";
+import type { AudioChunk } from "./types.js";
+
+const SAMPLE_RATE = 16000;
+
+interface Voice {
+  pitch: number; // Hz
+  formants: number[]; // Hz
+}
+
+const ALICE: Voice = { pitch: 210, formants: [850, 1600, 2900] };
+const BOB: Voice = { pitch: 110, formants: [450, 1100, 2300] };
+
+/**
+ * One second of a voice: pitch jitter varies between takes, like a person
+ * saying different words in the same voice
+ */
+function utterance(voice: Voice, take: number): AudioChunk[] {
+  const samples = SAMPLE_RATE;
+  const pcm = Buffer.alloc(samples * 2);
+  const pitch = voice.pitch * (1 + 0.03 * Math.sin(take * 1.7));
+  for (let i = 0; i < samples; i++) {
+    const t = i / SAMPLE_RATE;
+    let value = 0;
+    for (let h = 1; pitch * h < SAMPLE_RATE / 2; h++) {
+      const freq = pitch * h;
+      const gain = voice.formants.reduce(
+        (sum, formant) => sum + 1 / (1 + ((freq - formant) / 120) ** 2),
+        0,
+      );
+      value += (gain / h) * Math.sin(2 * Math.PI * freq * t + take * h);
+    }
+    // Syllable-like loudness envelope
+    value *= 0.5 + 0.5 * Math.sin(2 * Math.PI * 3 * t + take) ** 2;
+    pcm.writeInt16LE(Math.max(-32768, Math.min(32767, Math.round(value * 3000))), i * 2);
+  }
+  return [{ data: pcm, sampleRate: SAMPLE_RATE, channels: 1, timestamp: take }];
+}
+
+function voiceprint(voice: Voice, take: number): number[] {
+  const [chunk] = utterance(voice, take);
+  return extractVoiceprint(chunk.data as Buffer, SAMPLE_RATE)!;
+}
+
+describe("extractVoiceprint", () => {
+  it("returns null for silence or too little audio", () => {
+    expect(extractVoiceprint(Buffer.alloc(SAMPLE_RATE * 2), SAMPLE_RATE)).toBeNull();
+    expect(extractVoiceprint(Buffer.alloc(100), SAMPLE_RATE)).toBeNull();
+  });
+
+  it("is closer for the same voice than for another", () => {
+    const same = cosineSimilarity(voiceprint(ALICE, 1), voiceprint(ALICE, 2));
+    const other = cosineSimilarity(voiceprint(ALICE, 1), voiceprint(BOB, 2));
+    expect(same).toBeGreaterThan(other);
+  });
+});
+
+describe("SpeakerVerifier", () => {
+  let dir: string;
+  let profilesPath: string;
+
+  beforeEach(async () => {
+    dir = await fs.mkdtemp(path.join(os.tmpdir(), "helix-speakers-"));
+    profilesPath = path.join(dir, "profiles.json");
+  });
+
+  afterEach(async () => {
+    await fs.rm(dir, { recursive: true, force: true });
+  });
+
+  it("requires enough enrollment samples", async () => {
+    const verifier = new SpeakerVerifier({ profilesPath, minEnrollmentSamples: 3 });
+    await expect(verifier.enroll("Alice", [utterance(ALICE, 1)])).rejects.toThrow(/3 samples/);
+  });
+
+  it("identifies enrolled speakers and persists their profiles", async () => {
+    const verifier = new SpeakerVerifier({ profilesPath, threshold: 0 });
+    await verifier.enroll("Alice", [1, 2, 3].map((take) => utterance(ALICE, take)));
+    await verifier.enroll("Bob", [1, 2, 3].map((take) => utterance(BOB, take)));
+
+    expect(verifier.identify(utterance(ALICE, 4))?.id).toBe("alice");
+    expect(verifier.identify(utterance(BOB, 4))?.id).toBe("bob");
+
+    const reloaded = new SpeakerVerifier({ profilesPath });
+    await reloaded.load();
+    expect(reloaded.listProfiles().map((p) => p.name)).toEqual(["Alice", "Bob"]);
+  });
+
+  it("does not verify a match below the threshold", async () => {
+    const verifier = new SpeakerVerifier({ profilesPath, threshold: 1.01 });
+    await verifier.enroll("Alice", [1, 2, 3].map((take) => utterance(ALICE, take)));
+
+    const match = verifier.identify(utterance(ALICE, 4));
+    expect(match?.id).toBe("alice");
+    expect(match?.verified).toBe(false);
+  });
+
+  it("identifies no one without profiles", () => {
+    const verifier = new SpeakerVerifier({ profilesPath });
+    expect(verifier.identify(utterance(ALICE, 1))).toBeNull();
+    expect(verifier.hasProfiles()).toBe(false);
+  });
+
+  it("removes profiles", async () => {
+    const verifier = new SpeakerVerifier({ profilesPath });
+    await verifier.enroll("Alice", [1, 2, 3].map((take) => utterance(ALICE, take)));
+    expect(await verifier.remove("alice")).toBe(true);
+    expect(await verifier.remove("alice")).toBe(false);
+    expect(verifier.listProfiles()).toEqual([]);
+  });
+
+  it("matches sensitive commands on whole words", () => {
+    const verifier = new SpeakerVerifier({ profilesPath, sensitiveCommands: ["send", "pay"] });
+    expect(verifier.isSensitive("Send the report to Sam")).toBe(true);
+    expect(verifier.isSensitive("pay the electricity bill")).toBe(true);
+    expect(verifier.isSensitive("what's on my calendar")).toBe(false);
+    expect(verifier.isSensitive("who was the sender of that repayment")).toBe(false);
+  });
+});
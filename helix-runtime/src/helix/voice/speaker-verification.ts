@@ -0,0 +1,425 @@
+/**
+ * HELIX VOICE ENGINE - SPEAKER VERIFICATION
+ * Recognizes enrolled household members by voice, locally
+ *
+ * A voiceprint is the mean and spread of the mel-cepstrum over the voiced
+ * frames of an utterance. Enrollment averages the voiceprints of a few
+ * samples into a profile; an utterance is attributed to the most similar
+ * profile when the cosine similarity reaches the threshold. This tells
+ * household members apart; it is not meant to stop a deliberate imitation
+ * or a recording. Profiles never leave the machine.
+ */
+
+import fs from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import { createSubsystemLogger } from "../../logging/subsystem.js";
+import {
+  type AudioChunk,
+  type SpeakerMatch,
+  type SpeakerProfile,
+  type SpeakerVerificationConfig,
+  DEFAULT_SPEAKER_VERIFICATION_CONFIG,
+} from "./types.js";
+import { combineAudioChunks } from "./voice-activity.js";
+
+const log = createSubsystemLogger("helix:voice:speaker");
+
+// Analysis parameters (25ms frames every 10ms, the usual for speech)
+const FRAME_MS = 25;
+const HOP_MS = 10;
+const MEL_BANDS = 26;
+/** Cepstral coefficients kept, after dropping c0 (loudness) */
+const CEPSTRA = 12;
+/** Frames quieter than this fraction of the loudest are treated as silence */
+const VOICED_FLOOR = 0.05;
+/** Less voiced audio than this gives no voiceprint */
+const MIN_VOICED_FRAMES = 30;
+
+/**
+ * Default location of enrolled profiles, next to the other runtime state
+ */
+export function defaultProfilesPath(): string {
+  const stateDir = process.env.OPENCLAW_STATE_DIR || path.join(os.homedir(), ".openclaw", "state");
+  return path.join(stateDir, "helix-voice-profiles.json");
+}
+
+/**
+ * Samples of 16-bit little-endian PCM as floats in [-1, 1]
+ */
+function toSamples(pcm: Buffer): Float32Array {
+  const samples = new Float32Array(Math.floor(pcm.length / 2));
+  for (let i = 0; i < samples.length; i++) {
+    samples[i] = pcm.readInt16LE(i * 2) / 32768;
+  }
+  return samples;
+}
+
+/**
+ * Power spectrum of a real frame, zero-padded to `size` (a power of two)
+ */
+function powerSpectrum(frame: Float32Array, size: number): Float64Array {
+  const re = new Float64Array(size);
+  const im = new Float64Array(size);
+  re.set(frame);
+
+  // Iterative radix-2 FFT
+  for (let i = 1, j = 0; i < size; i++) {
+    let bit = size >> 1;
+    for (; j & bit; bit >>= 1) {
+      j ^= bit;
+    }
+    j ^= bit;
+    if (i < j) {
+      [re[i], re[j]] = [re[j], re[i]];
+      [im[i], im[j]] = [im[j], im[i]];
+    }
+  }
+  for (let len = 2; len <= size; len <<= 1) {
+    const angle = (-2 * Math.PI) / len;
+    for (let start = 0; start < size; start += len) {
+      for (let k = 0; k < len / 2; k++) {
+        const cos = Math.cos(angle * k);
+        const sin = Math.sin(angle * k);
+        const a = start + k;
+        const b = a + len / 2;
+        const tre = re[b] * cos - im[b] * sin;
+        const tim = re[b] * sin + im[b] * cos;
+        re[b] = re[a] - tre;
+        im[b] = im[a] - tim;
+        re[a] += tre;
+        im[a] += tim;
+      }
+    }
+  }
+
+  const power = new Float64Array(size / 2 + 1);
+  for (let i = 0; i < power.length; i++) {
+    power[i] = re[i] * re[i] + im[i] * im[i];
+  }
+  return power;
+}
+
+function hzToMel(hz: number): number {
+  return 2595 * Math.log10(1 + hz / 700);
+}
+
+function melToHz(mel: number): number {
+  return 700 * (10 ** (mel / 2595) - 1);
+}
+
+/**
+ * Triangular mel filters over the bins of a `size`-point FFT
+ */
+function melFilterbank(size: number, sampleRate: number): Float64Array[] {
+  const maxMel = hzToMel(sampleRate / 2);
+  const edges = Array.from({ length: MEL_BANDS + 2 }, (_, i) =>
+    Math.floor(((size + 1) * melToHz((maxMel * i) / (MEL_BANDS + 1))) / sampleRate),
+  );
+
+  return Array.from({ length: MEL_BANDS }, (_, band) => {
+    const filter = new Float64Array(size / 2 + 1);
+    const [left, center, right] = [edges[band], edges[band + 1], edges[band + 2]];
+    for (let bin = left; bin < center; bin++) {
+      filter[bin] = (bin - left) / Math.max(1, center - left);
+    }
+    for (let bin = center; bin <= right && bin < filter.length; bin++) {
+      filter[bin] = (right - bin) / Math.max(1, right - center);
+    }
+    return filter;
+  });
+}
+
+/**
+ * Voiceprint of an utterance: mean and standard deviation of cepstra 1-12
+ * over its voiced frames, normalized to unit length. Null when the audio
+ * holds too little speech to characterize the speaker.
+ */
+export function extractVoiceprint(pcm: Buffer, sampleRate: number): number[] | null {
+  const samples = toSamples(pcm);
+  const frameLength = Math.round((sampleRate * FRAME_MS) / 1000);
+  const hop = Math.round((sampleRate * HOP_MS) / 1000);
+  if (samples.length < frameLength) {
+    return null;
+  }
+
+  let fftSize = 1;
+  while (fftSize < frameLength) {
+    fftSize <<= 1;
+  }
+  const filters = melFilterbank(fftSize, sampleRate);
+  const window = Float32Array.from(
+    { length: frameLength },
+    (_, i) => 0.54 - 0.46 * Math.cos((2 * Math.PI * i) / (frameLength - 1)),
+  );
+
+  const frames: { energy: number; cepstra: number[] }[] = [];
+  const frame = new Float32Array(frameLength);
+  for (let start = 0; start + frameLength <= samples.length; start += hop) {
+    let energy = 0;
+    for (let i = 0; i < frameLength; i++) {
+      // Pre-emphasis lifts the high frequencies that carry most speaker detail
+      const previous = start + i > 0 ? samples[start + i - 1] : 0;
+      frame[i] = (samples[start + i] - 0.97 * previous) * window[i];
+      energy += frame[i] * frame[i];
+    }
+
+    const power = powerSpectrum(frame, fftSize);
+    const logMel = filters.map((filter) => {
+      let sum = 0;
+      for (let bin = 0; bin < power.length; bin++) {
+        sum += filter[bin] * power[bin];
+      }
+      return Math.log(sum + 1e-10);
+    });
+
+    // DCT-II of the log mel energies
+    const cepstra = Array.from({ length: CEPSTRA }, (_, k) => {
+      let sum = 0;
+      for (let band = 0; band < MEL_BANDS; band++) {
+        sum += logMel[band] * Math.cos((Math.PI * (k + 1) * (band + 0.5)) / MEL_BANDS);
+      }
+      return sum;
+    });
+    frames.push({ energy, cepstra });
+  }
+
+  const loudest = frames.reduce((max, f) => Math.max(max, f.energy), 0);
+  const voiced = frames.filter((f) => f.energy > loudest * VOICED_FLOOR && f.energy > 0);
+  if (voiced.length < MIN_VOICED_FRAMES) {
+    return null;
+  }
+
+  const mean = new Array<number>(CEPSTRA).fill(0);
+  const spread = new Array<number>(CEPSTRA).fill(0);
+  for (const { cepstra } of voiced) {
+    cepstra.forEach((c, k) => (mean[k] += c / voiced.length));
+  }
+  for (const { cepstra } of voiced) {
+    cepstra.forEach((c, k) => (spread[k] += (c - mean[k]) ** 2 / voiced.length));
+  }
+  return normalize([...mean, ...spread.map(Math.sqrt)]);
+}
+
+function normalize(vector: number[]): number[] {
+  const norm = Math.sqrt(vector.reduce((sum, v) => sum + v * v, 0));
+  return norm > 0 ? vector.map((v) => v / norm) : vector;
+}
+
+/**
+ * Cosine similarity of two voiceprints
+ */
+export function cosineSimilarity(a: number[], b: number[]): number {
+  if (a.length !== b.length || a.length === 0) {
+    return 0;
+  }
+  let dot = 0;
+  let normA = 0;
+  let normB = 0;
+  for (let i = 0; i < a.length; i++) {
+    dot += a[i] * b[i];
+    normA += a[i] * a[i];
+    normB += b[i] * b[i];
+  }
+  return normA > 0 && normB > 0 ? dot / Math.sqrt(normA * normB) : 0;
+}
+
+function escapeRegExp(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
+
+function slugify(name: string): string {
+  return (
+    name
+      .toLowerCase()
+      .replace(/[^a-z0-9]+/g, "-")
+      .replace(/^-+|-+$/g, "") || "speaker"
+  );
+}
+
+/**
+ * Enrolled voice profiles and the checks against them
+ */
+export class SpeakerVerifier {
+  private config: SpeakerVerificationConfig;
+  private profilesPath: string;
+  private profiles: SpeakerProfile[] = [];
+  private sensitivePatterns: RegExp[] = [];
+
+  constructor(config: Partial<SpeakerVerificationConfig> = {}) {
+    this.profilesPath = config.profilesPath ?? defaultProfilesPath();
+    this.config = { ...DEFAULT_SPEAKER_VERIFICATION_CONFIG, ...config };
+    this.configure(config);
+  }
+
+  /**
+   * Take new threshold and command settings; the profiles stay where they are
+   */
+  configure(config: Partial<SpeakerVerificationConfig>): void {
+    this.config = {
+      ...DEFAULT_SPEAKER_VERIFICATION_CONFIG,
+      ...config,
+      profilesPath: this.profilesPath,
+    };
+    this.sensitivePatterns = this.config.sensitiveCommands
+      .map((phrase) => phrase.trim())
+      .filter(Boolean)
+      .map((phrase) => new RegExp(`\\b${escapeRegExp(phrase)}\\b`, "i"));
+  }
+
+  /**
+   * Load enrolled profiles; none yet is not an error
+   */
+  async load(): Promise<void> {
+    try {
+      const raw = await fs.readFile(this.profilesPath, "utf-8");
+      const parsed = JSON.parse(raw) as { profiles?: SpeakerProfile[] };
+      this.profiles = Array.isArray(parsed.profiles) ? parsed.profiles : [];
+      log.info(`Loaded ${this.profiles.length} voice profile(s)`);
+    } catch (err) {
+      if ((err as NodeJS.ErrnoException).code !== "ENOENT") {
+        log.warn("Failed to load voice profiles:", { error: String(err) });
+      }
+      this.profiles = [];
+    }
+  }
+
+  private async save(): Promise<void> {
+    await fs.mkdir(path.dirname(this.profilesPath), { recursive: true });
+    const tmp = `${this.profilesPath}.tmp`;
+    // Voiceprints are biometric data: readable by the owner only
+    await fs.writeFile(tmp, JSON.stringify({ profiles: this.profiles }, null, 2), { mode: 0o600 });
+    await fs.rename(tmp, this.profilesPath);
+  }
+
+  /**
+   * Enrolled profiles
+   */
+  listProfiles(): SpeakerProfile[] {
+    return this.profiles.map((profile) => ({ ...profile }));
+  }
+
+  /**
+   * Enroll `name` from a few recorded samples, replacing an earlier
+   * profile of the same name
+   */
+  async enroll(name: string, samples: AudioChunk[][]): Promise<SpeakerProfile> {
+    const trimmed = name.trim();
+    if (!trimmed) {
+      throw new Error("Speaker name is required");
+    }
+
+    const voiceprints = samples
+      .map((chunks) => {
+        if (chunks.length === 0) return null;
+        return extractVoiceprint(combineAudioChunks(chunks), chunks[0].sampleRate);
+      })
+      .filter((v): v is number[] => v !== null);
+    if (voiceprints.length < this.config.minEnrollmentSamples) {
+      throw new Error(
+        `Enrollment needs ${this.config.minEnrollmentSamples} samples with enough speech, got ${voiceprints.length}`,
+      );
+    }
+
+    const mean = voiceprints[0].map(
+      (_, i) => voiceprints.reduce((sum, v) => sum + v[i], 0) / voiceprints.length,
+    );
+    const profile: SpeakerProfile = {
+      id: slugify(trimmed),
+      name: trimmed,
+      voiceprint: normalize(mean),
+      samples: voiceprints.length,
+      enrolledAt: new Date().toISOString(),
+    };
+
+    this.profiles = [...this.profiles.filter((p) => p.id !== profile.id), profile];
+    await this.save();
+    log.info(`Enrolled voice profile "${profile.name}" from ${profile.samples} samples`);
+    return { ...profile };
+  }
+
+  /**
+   * Remove a profile; false when there was none
+   */
+  async remove(id: string): Promise<boolean> {
+    const remaining = this.profiles.filter((p) => p.id !== id);
+    if (remaining.length === this.profiles.length) {
+      return false;
+    }
+    this.profiles = remaining;
+    await this.save();
+    log.info(`Removed voice profile "${id}"`);
+    return true;
+  }
+
+  /**
+   * The enrolled speaker most like the utterance. Null without profiles or
+   * enough speech; `verified` is false when even the best match is below
+   * the threshold.
+   */
+  identify(chunks: AudioChunk[]): SpeakerMatch | null {
+    if (this.profiles.length === 0 || chunks.length === 0) {
+      return null;
+    }
+    const voiceprint = extractVoiceprint(combineAudioChunks(chunks), chunks[0].sampleRate);
+    if (!voiceprint) {
+      return null;
+    }
+
+    let best: SpeakerMatch | null = null;
+    for (const profile of this.profiles) {
+      const similarity = cosineSimilarity(voiceprint, profile.voiceprint);
+      if (!best || similarity > best.similarity) {
+        best = { id: profile.id, name: profile.name, similarity, verified: false };
+      }
+    }
+    if (best) {
+      best.verified = best.similarity >= this.config.threshold;
+    }
+    return best;
+  }
+
+  /**
+   * Whether a transcript asks for something only an enrolled speaker may do
+   */
+  isSensitive(text: string): boolean {
+    return this.sensitivePatterns.some((pattern) => pattern.test(text));
+  }
+
+  /**
+   * Whether sensitive commands can be checked at all; with no one enrolled
+   * there is nobody to verify against
+   */
+  hasProfiles(): boolean {
+    return this.profiles.length > 0;
+  }
+}
+
+/** Verifiers by profiles path, so everything in the process sees one set of profiles */
+const sharedVerifiers = new Map<string, Promise<SpeakerVerifier>>();
+
+/**
+ * The process's verifier for the configured profiles, loaded once. The voice
+ * engine and the gateway's enrollment methods share it, so a profile
+ * enrolled or removed counts for the next utterance.
+ */
+export function sharedSpeakerVerifier(
+  config: Partial<SpeakerVerificationConfig> = {},
+): Promise<SpeakerVerifier> {
+  const profilesPath = config.profilesPath ?? defaultProfilesPath();
+  let verifier = sharedVerifiers.get(profilesPath);
+  if (!verifier) {
+    verifier = (async () => {
+      const created = new SpeakerVerifier({ ...config, profilesPath });
+      await created.load();
+      return created;
+    })();
+    sharedVerifiers.set(profilesPath, verifier);
+    // A failed load is tried again next time
+    verifier.catch(() => sharedVerifiers.delete(profilesPath));
+  }
+  return verifier.then((shared) => {
+    shared.configure(config);
+    return shared;
+  });
+}
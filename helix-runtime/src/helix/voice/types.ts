@@ -138,6 +138,26 @@ export interface VADConfig {
   backend?: "webrtc" | "silero" | "energy";
 }
 
+/**
+ * Speaker verification configuration (see speaker-verification.ts)
+ */
+export interface SpeakerVerificationConfig {
+  /** Identify speakers and gate sensitive commands */
+  enabled: boolean; // Default: false
+
+  /** Cosine similarity to a profile needed to count as that speaker */
+  threshold: number; // Default: 0.85
+
+  /** Samples with enough speech required to enroll a profile */
+  minEnrollmentSamples: number; // Default: 3
+
+  /** Words or phrases that make a command sensitive */
+  sensitiveCommands: string[];
+
+  /** Where profiles are stored; defaults to the runtime state directory */
+  profilesPath?: string;
+}
+
 /**
  * Voice conversation settings
  */
@@ -184,6 +204,9 @@ export interface VoiceConfig {
   /** Conversation settings */
   conversation: ConversationConfig;
 
+  /** Speaker verification for shared households */
+  speakerVerification?: SpeakerVerificationConfig;
+
   /** Audio device preferences */
   audio?: {
     inputDevice?: string; // Specific mic device ID
@@ -192,6 +215,16 @@ export interface VoiceConfig {
   };
 }
 
+/**
+ * Default speaker verification configuration
+ */
+export const DEFAULT_SPEAKER_VERIFICATION_CONFIG: SpeakerVerificationConfig = {
+  enabled: false,
+  threshold: 0.85,
+  minEnrollmentSamples: 3,
+  sensitiveCommands: ["delete", "remove", "send", "pay", "purchase", "buy", "transfer", "unlock", "password"],
+};
+
 /**
  * Default voice configuration
  */
@@ -239,6 +272,8 @@ export const DEFAULT_VOICE_CONFIG: VoiceConfig = {
     playTypingSound: false,
     greetOnStartup: false,
   },
+
+  speakerVerification: DEFAULT_SPEAKER_VERIFICATION_CONFIG,
 };
 
 /**
@@ -251,6 +286,30 @@ export interface VoiceTranscript {
   duration: number; // ms
   timestamp: Date;
   isFinal: boolean;
+  /** Enrolled speaker the utterance was attributed to, when verification is on */
+  speaker?: SpeakerMatch | null;
+}
+
+/**
+ * Enrolled voice profile
+ */
+export interface SpeakerProfile {
+  id: string;
+  name: string;
+  /** Averaged voiceprint of the enrollment samples */
+  voiceprint: number[];
+  samples: number;
+  enrolledAt: string;
+}
+
+/**
+ * Closest enrolled speaker to an utterance
+ */
+export interface SpeakerMatch {
+  id: string;
+  name: string;
+  similarity: number; // Cosine similarity, -1 to 1
+  verified: boolean; // similarity reached the threshold
 }
 
 /**
@@ -273,6 +332,11 @@ export interface VoiceEngineEvents {
   "speech:end": { duration: number };
   "transcript:partial": { text: string };
   "transcript:final": VoiceTranscript;
+  "command:blocked": {
+    text: string;
+    reason: "no-profiles" | "unverified";
+    speaker: SpeakerMatch | null;
+  };
   "tts:start": { text: string };
   "tts:chunk": { audio: AudioChunk };
   "tts:end": { duration: number };
@@ -291,6 +355,7 @@ export interface VoiceStats {
   totalSpeechDuration: number; // ms
   totalTTSDuration: number; // ms
   wakeWordDetections: number;
+  blockedCommands: number;
   errors: number;
   lastActivity: Date | null;
 }
@@ -42,4 +42,44 @@ describe("runCommandWithTimeout", () => {
       }
     }
   });
+
+  it("inherits only the given agent's HELIX_AGENT_* variables", async () => {
+    const agentEnv = {
+      HELIX_AGENTS: "coder,review",
+      HELIX_AGENT_CODER_TOKEN: "coder-secret",
+      HELIX_AGENT_REVIEW_TOKEN: "review-secret",
+    };
+    const previous: Record<string, string | undefined> = {};
+    for (const [key, value] of Object.entries(agentEnv)) {
+      previous[key] = process.env[key];
+      process.env[key] = value;
+    }
+    const argv = [
+      process.execPath,
+      "-e",
+      'process.stdout.write((process.env.HELIX_AGENT_CODER_TOKEN ?? "") + "|" + (process.env.HELIX_AGENT_REVIEW_TOKEN ?? ""))',
+    ];
+    try {
+      const coder = await runCommandWithTimeout(argv, { timeoutMs: 5_000, agentId: "coder" });
+      expect(coder.stdout).toBe("coder-secret|");
+
+      const noAgent = await runCommandWithTimeout(argv, { timeoutMs: 5_000 });
+      expect(noAgent.stdout).toBe("|");
+
+      const copied = await runCommandWithTimeout(argv, {
+        timeoutMs: 5_000,
+        env: { ...process.env },
+        agentId: "review",
+      });
+      expect(copied.stdout).toBe("|review-secret");
+    } finally {
+      for (const [key, value] of Object.entries(previous)) {
+        if (value === undefined) {
+          delete process.env[key];
+        } else {
+          process.env[key] = value;
+        }
+      }
+    }
+  });
 });
@@ -2,6 +2,7 @@ import { execFile, spawn } from "node:child_process";
 import path from "node:path";
 import { promisify } from "node:util";
 
+import { scopeAgentEnv } from "../agents/agent-env.js";
 import { danger, shouldLogVerbose } from "../globals.js";
 import { logDebug, logError } from "../logger.js";
 import { resolveCommandStdio } from "./spawn-utils.js";
@@ -54,6 +55,8 @@ export type CommandOptions = {
   cwd?: string;
   input?: string;
   env?: NodeJS.ProcessEnv;
+  /** Agent the command runs for; it inherits only that agent's HELIX_AGENT_<ID>_* variables. */
+  agentId?: string;
   windowsVerbatimArguments?: boolean;
 };
 
@@ -63,7 +66,7 @@ export async function runCommandWithTimeout(
 ): Promise<SpawnResult> {
   const options: CommandOptions =
     typeof optionsOrTimeout === "number" ? { timeoutMs: optionsOrTimeout } : optionsOrTimeout;
-  const { timeoutMs, cwd, input, env } = options;
+  const { timeoutMs, cwd, input, env, agentId } = options;
   const { windowsVerbatimArguments } = options;
   const hasInput = input !== undefined;
 
@@ -79,7 +82,15 @@ export async function runCommandWithTimeout(
     return false;
   })();
 
-  const resolvedEnv = env ? { ...process.env, ...env } : { ...process.env };
+  // Scoped after merging, so an `env` copied from process.env can't carry
+  // other agents' HELIX_AGENT_<ID>_* variables either
+  const merged: Record<string, string> = {};
+  for (const [key, value] of Object.entries({ ...process.env, ...env })) {
+    if (typeof value === "string") {
+      merged[key] = value;
+    }
+  }
+  const resolvedEnv: NodeJS.ProcessEnv = scopeAgentEnv(merged, agentId);
   if (shouldSuppressNpmFund) {
     if (resolvedEnv.NPM_CONFIG_FUND == null) {
       resolvedEnv.NPM_CONFIG_FUND = "false";
@@ -0,0 +1,84 @@
+use chrono::Utc;
+use helix_shared::{Memory, MemoryType};
+use memory_import::estimate_valence;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::message::EmailMessage;
+use crate::redact::Redactor;
+
+/// Counts from one poll, printed as JSON by the binary in --once mode
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IngestSummary {
+    pub messages_fetched: usize,
+    pub memories_created: usize,
+    pub duplicates_skipped: usize,
+    /// Messages with no text after cleaning
+    pub empty_skipped: usize,
+    pub redactions: usize,
+    /// Memories created per sender, the relational context of the channel
+    pub senders: BTreeMap<String, usize>,
+}
+
+/// Memory for an email: sender and subject, then the redacted body.
+/// Returns the memory and the number of redactions made, or None when
+/// the email has no text.
+pub fn to_memory(message: &EmailMessage, user_id: Uuid, redactor: &Redactor) -> Option<(Memory, usize)> {
+    if message.body.trim().is_empty() {
+        return None;
+    }
+
+    let (subject, subject_redactions) = redactor.redact(&message.subject);
+    let (body, body_redactions) = redactor.redact(&message.body);
+    let content = format!("Email from {}: {}\n\n{}", message.sender(), subject, body);
+
+    let memory = Memory {
+        id: Uuid::new_v4(),
+        user_id,
+        memory_type: MemoryType::Episodic,
+        content,
+        embedding: None,
+        emotional_valence: estimate_valence(&body),
+        created_at: message.date.unwrap_or_else(Utc::now),
+        last_accessed: None,
+    };
+
+    Some((memory, subject_redactions + body_redactions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(body: &str) -> EmailMessage {
+        EmailMessage {
+            message_id: Some("<1@example.com>".to_string()),
+            subject: "Dinner with sam@example.com".to_string(),
+            sender_name: None,
+            sender_address: Some("alex@example.com".to_string()),
+            date: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_to_memory_redacts_and_names_sender() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let (memory, redactions) =
+            to_memory(&message("Call me at 555-123-4567"), Uuid::new_v4(), &redactor).unwrap();
+
+        assert_eq!(redactions, 2);
+        assert_eq!(
+            memory.content,
+            "Email from alex: Dinner with [redacted email]\n\nCall me at [redacted phone]"
+        );
+        assert!(!memory.content.contains("alex@example.com"));
+    }
+
+    #[test]
+    fn test_empty_body_skipped() {
+        let redactor = Redactor::new(&[]).unwrap();
+        assert!(to_memory(&message("  \n"), Uuid::new_v4(), &redactor).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod ingest;
+pub mod mailbox;
+pub mod message;
+pub mod redact;
+pub mod text;
+
+pub use ingest::{to_memory, IngestSummary};
+pub use mailbox::{ImapSettings, MailboxState};
+pub use message::EmailMessage;
+pub use redact::{RedactionRule, Redactor};
@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Connection details for the IMAP account
+#[derive(Debug, Clone)]
+pub struct ImapSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    /// Dedicated folder (Gmail label) to ingest from
+    pub folder: String,
+}
+
+/// Which messages have already been ingested. The folder is opened
+/// read-only, so progress is tracked by UID rather than by \Seen flags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MailboxState {
+    pub uid_validity: Option<u32>,
+    pub last_uid: u32,
+}
+
+impl MailboxState {
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Start over if the server renumbered the folder
+    pub fn check_validity(&mut self, uid_validity: Option<u32>) {
+        if self.uid_validity != uid_validity {
+            if self.uid_validity.is_some() {
+                info!("UIDVALIDITY changed, re-scanning folder");
+            }
+            *self = Self {
+                uid_validity,
+                last_uid: 0,
+            };
+        }
+    }
+
+    /// UIDs not yet ingested, oldest first. `UID n:*` always returns the
+    /// newest message even when its UID is below n, so filter again here.
+    pub fn new_uids(&self, found: impl IntoIterator<Item = u32>) -> Vec<u32> {
+        let mut uids: Vec<u32> = found.into_iter().filter(|uid| *uid > self.last_uid).collect();
+        uids.sort_unstable();
+        uids
+    }
+}
+
+/// Fetch up to `limit` new raw messages as (uid, RFC 5322 bytes), oldest first.
+/// Blocking; run it off the async runtime.
+pub fn fetch_new(settings: &ImapSettings, state: &mut MailboxState, limit: usize) -> Result<Vec<(u32, Vec<u8>)>> {
+    let tls = native_tls::TlsConnector::new().context("Failed to create TLS connector")?;
+    let client = imap::connect((settings.host.as_str(), settings.port), &settings.host, &tls)
+        .with_context(|| format!("Failed to connect to {}:{}", settings.host, settings.port))?;
+    let mut session = client
+        .login(&settings.username, &settings.password)
+        .map_err(|(e, _)| e)
+        .context("IMAP login failed")?;
+
+    let mailbox = session
+        .examine(&settings.folder)
+        .with_context(|| format!("Failed to open folder '{}'", settings.folder))?;
+    state.check_validity(mailbox.uid_validity);
+
+    let found = session
+        .uid_search(format!("UID {}:*", state.last_uid + 1))
+        .context("IMAP search failed")?;
+    let uids: Vec<u32> = state.new_uids(found).into_iter().take(limit).collect();
+    debug!("{} new messages in '{}'", uids.len(), settings.folder);
+
+    let mut messages = Vec::new();
+    if !uids.is_empty() {
+        let set = uids.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+        let fetches = session
+            .uid_fetch(set, "(UID BODY.PEEK[])")
+            .context("IMAP fetch failed")?;
+        for fetch in fetches.iter() {
+            if let (Some(uid), Some(body)) = (fetch.uid, fetch.body()) {
+                messages.push((uid, body.to_vec()));
+            }
+        }
+        messages.sort_by_key(|(uid, _)| *uid);
+    }
+
+    session.logout().ok();
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_uids_and_validity() {
+        let mut state = MailboxState {
+            uid_validity: Some(7),
+            last_uid: 10,
+        };
+        assert_eq!(state.new_uids([12, 10, 11]), vec![11, 12]);
+
+        state.check_validity(Some(7));
+        assert_eq!(state.last_uid, 10);
+
+        state.check_validity(Some(8));
+        assert_eq!(state, MailboxState { uid_validity: Some(8), last_uid: 0 });
+    }
+}
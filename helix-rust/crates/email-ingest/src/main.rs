@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use email_ingest::{to_memory, EmailMessage, ImapSettings, IngestSummary, MailboxState, RedactionRule, Redactor};
+use helix_shared::{bulk, BulkOptions, Memory, SupabaseClient};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Environment variable carrying the IMAP password, so it never shows up in argv
+const PASSWORD_ENV: &str = "HELIX_IMAP_PASSWORD";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// User ID to create memories for
+    #[arg(short, long)]
+    user_id: Uuid,
+
+    /// IMAP server host
+    #[arg(long)]
+    host: String,
+
+    /// IMAP server port (TLS)
+    #[arg(long, default_value_t = 993)]
+    port: u16,
+
+    /// IMAP username
+    #[arg(long)]
+    username: String,
+
+    /// Folder or label to ingest from
+    #[arg(short, long, default_value = "Helix")]
+    folder: String,
+
+    /// File tracking the last ingested message
+    #[arg(long)]
+    state_file: PathBuf,
+
+    /// Extra redaction rule as name=regex (repeatable)
+    #[arg(long = "redact")]
+    redact: Vec<String>,
+
+    /// Most messages to ingest per poll
+    #[arg(long, default_value_t = 50)]
+    batch: usize,
+
+    /// Seconds between polls
+    #[arg(short, long, default_value_t = 300)]
+    interval: u64,
+
+    /// Poll once and exit instead of running continuously
+    #[arg(long, default_value_t = false)]
+    once: bool,
+}
+
+/// Fetch new messages and ingest them. A poll's memories are written in one
+/// transaction and progress is saved once it commits, so a failure leaves
+/// the whole batch to be fetched again.
+async fn poll(
+    args: &Args,
+    settings: &ImapSettings,
+    redactor: &Redactor,
+    client: &SupabaseClient,
+) -> Result<IngestSummary> {
+    let mut summary = IngestSummary::default();
+
+    let fetch_settings = settings.clone();
+    let state_file = args.state_file.clone();
+    let batch = args.batch;
+    let (messages, mut state) = tokio::task::spawn_blocking(move || {
+        let mut state = MailboxState::load(&state_file);
+        email_ingest::mailbox::fetch_new(&fetch_settings, &mut state, batch).map(|m| (m, state))
+    })
+    .await??;
+    summary.messages_fetched = messages.len();
+
+    let mut memories: Vec<Memory> = Vec::new();
+    let mut senders = Vec::new();
+    let mut last_uid = None;
+    for (uid, raw) in messages {
+        match EmailMessage::parse(&raw) {
+            Ok(message) => match to_memory(&message, args.user_id, redactor) {
+                Some((memory, redactions)) => {
+                    summary.redactions += redactions;
+                    senders.push((memory.id, message.sender()));
+                    memories.push(memory);
+                }
+                None => summary.empty_skipped += 1,
+            },
+            Err(e) => warn!("Skipping message {}: {:#}", uid, e),
+        }
+        last_uid = Some(uid);
+    }
+
+    let inserted: HashSet<Uuid> = bulk::insert_memories(client.pool(), &memories, BulkOptions::from_env())
+        .await
+        .context("Failed to insert email memories")?
+        .into_iter()
+        .collect();
+    for (id, sender) in senders {
+        if inserted.contains(&id) {
+            summary.memories_created += 1;
+            *summary.senders.entry(sender).or_insert(0) += 1;
+        } else {
+            summary.duplicates_skipped += 1;
+        }
+    }
+
+    if let Some(uid) = last_uid {
+        state.last_uid = uid;
+    }
+    // Also persists a changed UIDVALIDITY when nothing was fetched
+    state.save(&args.state_file)?;
+
+    if summary.memories_created > 0 {
+        info!(
+            "Ingested {} emails ({} redactions)",
+            summary.memories_created, summary.redactions
+        );
+    }
+    Ok(summary)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Logs go to stderr; stdout carries only the JSON summary in --once mode
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+
+    let args = Args::parse();
+
+    let password = std::env::var(PASSWORD_ENV)
+        .with_context(|| format!("{} is not set", PASSWORD_ENV))?;
+    let settings = ImapSettings {
+        host: args.host.clone(),
+        port: args.port,
+        username: args.username.clone(),
+        password,
+        folder: args.folder.clone(),
+    };
+
+    let rules = args
+        .redact
+        .iter()
+        .map(String::as_str)
+        .map(RedactionRule::parse_arg)
+        .collect::<Result<Vec<_>>>()?;
+    let redactor = Redactor::new(&rules)?;
+    let client = SupabaseClient::new().await?;
+
+    if args.once {
+        let summary = poll(&args, &settings, &redactor, &client).await?;
+        println!("{}", serde_json::to_string(&summary)?);
+        return Ok(());
+    }
+
+    info!("Polling '{}' on {} every {}s", args.folder, args.host, args.interval);
+    let mut interval = tokio::time::interval(Duration::from_secs(args.interval.max(60)));
+    loop {
+        interval.tick().await;
+        if let Err(e) = poll(&args, &settings, &redactor, &client).await {
+            error!("Email poll failed: {:#}", e);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use mailparse::{DispositionType, MailAddr, MailHeaderMap, ParsedMail};
+
+use crate::text::{clean_body, html_to_text};
+
+/// The parts of an email that become a memory
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub message_id: Option<String>,
+    pub subject: String,
+    pub sender_name: Option<String>,
+    pub sender_address: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    /// New text only: quoted replies and signature removed
+    pub body: String,
+}
+
+impl EmailMessage {
+    /// Parse a raw RFC 5322 message
+    pub fn parse(raw: &[u8]) -> Result<Self> {
+        let mail = mailparse::parse_mail(raw).context("Failed to parse email")?;
+        let headers = &mail.headers;
+
+        let (sender_name, sender_address) = headers
+            .get_first_value("From")
+            .and_then(|from| mailparse::addrparse(&from).ok())
+            .and_then(|list| {
+                list.iter().find_map(|addr| match addr {
+                    MailAddr::Single(info) => Some((info.display_name.clone(), Some(info.addr.clone()))),
+                    MailAddr::Group(group) => group
+                        .addrs
+                        .first()
+                        .map(|info| (info.display_name.clone(), Some(info.addr.clone()))),
+                })
+            })
+            .unwrap_or((None, None));
+
+        let date = headers
+            .get_first_value("Date")
+            .and_then(|d| mailparse::dateparse(&d).ok())
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+
+        Ok(Self {
+            message_id: headers.get_first_value("Message-ID").map(|id| id.trim().to_string()),
+            subject: headers
+                .get_first_value("Subject")
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "(no subject)".to_string()),
+            sender_name: sender_name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            sender_address,
+            date,
+            body: clean_body(&find_body(&mail).unwrap_or_default()),
+        })
+    }
+
+    /// Who sent the email: display name, else the address's local part
+    pub fn sender(&self) -> String {
+        if let Some(name) = &self.sender_name {
+            return name.clone();
+        }
+        self.sender_address
+            .as_deref()
+            .and_then(|a| a.split('@').next())
+            .filter(|local| !local.is_empty())
+            .unwrap_or("unknown sender")
+            .to_string()
+    }
+}
+
+fn is_attachment(part: &ParsedMail) -> bool {
+    part.get_content_disposition().disposition == DispositionType::Attachment
+}
+
+/// The first text/plain part, else the first text/html part as text
+fn find_body(mail: &ParsedMail) -> Option<String> {
+    fn find(part: &ParsedMail, mimetype: &str) -> Option<String> {
+        if is_attachment(part) {
+            return None;
+        }
+        if part.subparts.is_empty() {
+            return (part.ctype.mimetype == mimetype)
+                .then(|| part.get_body().ok())
+                .flatten();
+        }
+        part.subparts.iter().find_map(|sub| find(sub, mimetype))
+    }
+
+    find(mail, "text/plain").or_else(|| find(mail, "text/html").map(|html| html_to_text(&html)))
+}
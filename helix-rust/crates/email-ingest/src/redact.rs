@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+
+/// Built-in rules, applied before any custom ones. Secrets come first so
+/// a token containing digits isn't half-matched as a phone number.
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    ("jwt", r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+"),
+    ("bearer_token", r"(?i)bearer\s+[A-Za-z0-9._~+/-]{16,}=*"),
+    ("api_key", r"\b(?:sk|pk|rk)[-_][A-Za-z0-9_-]{16,}"),
+    ("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("iban", r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){3,7}(?: ?[A-Z0-9]{1,3})?\b"),
+    ("ssn", r"\b\d{3}-\d{2}-\d{4}\b"),
+    ("card_number", r"\b\d(?:[ -]?\d){12,18}\b"),
+    ("phone", r"(?:\+\d{1,3}[\s.-]?)?\(?\b\d{3}\)?[\s.-]\d{3}[\s.-]\d{4}\b"),
+];
+
+/// A user-defined redaction rule (regex)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+impl RedactionRule {
+    /// Parse a `name=pattern` command line argument
+    pub fn parse_arg(arg: &str) -> Result<Self> {
+        let (name, pattern) = arg
+            .split_once('=')
+            .with_context(|| format!("Redaction rule must be name=pattern: {}", arg))?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+/// Replaces sensitive text with `[redacted <rule>]` markers.
+/// Applied to everything that leaves the machine.
+pub struct Redactor {
+    rules: Vec<(String, Regex)>,
+}
+
+impl Redactor {
+    /// Built-in rules plus `custom`
+    pub fn new(custom: &[RedactionRule]) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (name, pattern) in BUILTIN_RULES {
+            rules.push((name.to_string(), Regex::new(pattern)?));
+        }
+        for rule in custom {
+            let regex = Regex::new(&rule.pattern)
+                .with_context(|| format!("Invalid redaction rule '{}'", rule.name))?;
+            rules.push((rule.name.clone(), regex));
+        }
+        Ok(Self { rules })
+    }
+
+    /// Redacted text and the number of replacements made
+    pub fn redact(&self, text: &str) -> (String, usize) {
+        let mut out = text.to_string();
+        let mut count = 0;
+
+        for (name, regex) in &self.rules {
+            let matches = regex.find_iter(&out).count();
+            if matches == 0 {
+                continue;
+            }
+            count += matches;
+            out = regex
+                .replace_all(&out, format!("[redacted {}]", name).as_str())
+                .into_owned();
+        }
+
+        (out, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let (text, count) = redactor.redact(
+            "Mail jane.doe@example.com or call (555) 123-4567. Card 4111 1111 1111 1111, SSN 123-45-6789.",
+        );
+
+        assert_eq!(count, 4);
+        assert!(text.contains("Mail [redacted email] or call"));
+        assert!(text.contains("[redacted phone]"));
+        assert!(text.contains("Card [redacted card_number],"));
+        assert!(text.contains("SSN [redacted ssn]."));
+    }
+
+    #[test]
+    fn test_dates_and_times_survive() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let input = "Meeting on 2024-03-09 at 10:30, order #4521";
+        assert_eq!(redactor.redact(input), (input.to_string(), 0));
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let rule = RedactionRule::parse_arg("project=(?i)project\\s+falcon").unwrap();
+        let redactor = Redactor::new(&[rule]).unwrap();
+        let (text, count) = redactor.redact("Update on Project Falcon");
+
+        assert_eq!(count, 1);
+        assert_eq!(text, "Update on [redacted project]");
+        assert!(RedactionRule::parse_arg("no-equals").is_err());
+        assert!(Redactor::new(&[RedactionRule::parse_arg("bad=(").unwrap()]).is_err());
+    }
+}
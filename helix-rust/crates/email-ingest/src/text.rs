@@ -0,0 +1,107 @@
+/// Longest body kept from one email
+pub const MAX_BODY_CHARS: usize = 6000;
+
+/// Plain text from an HTML body: drops tags, scripts and styles and
+/// decodes the common entities
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let tag_text = &rest[start..];
+        let Some(end) = tag_text.find('>') else {
+            rest = "";
+            break;
+        };
+
+        let tag = tag_text[1..end].trim().to_ascii_lowercase();
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').find(|s| !s.is_empty());
+        rest = &tag_text[end + 1..];
+
+        match name {
+            Some(skip @ ("script" | "style" | "head")) if !tag.starts_with('/') => {
+                let close = format!("</{}", skip);
+                let lower = rest.to_ascii_lowercase();
+                rest = match lower.find(&close) {
+                    Some(i) => rest[i..].find('>').map_or("", |j| &rest[i + j + 1..]),
+                    None => "",
+                };
+            }
+            Some("br" | "p" | "/p" | "div" | "/div" | "tr" | "li" | "h1" | "h2" | "h3") => {
+                out.push('\n')
+            }
+            _ => {}
+        }
+    }
+    out.push_str(rest);
+
+    let decoded = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    decoded
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether a line introduces quoted reply text ("On <date>, X wrote:")
+fn is_reply_header(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("On ") && line.ends_with("wrote:")
+        || line.starts_with("-----Original Message-----")
+}
+
+/// The new text of an email: drops quoted replies and the signature,
+/// collapses blank runs and truncates
+pub fn clean_body(body: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in body.lines() {
+        let trimmed_end = line.trim_end();
+        if trimmed_end == "--" || is_reply_header(trimmed_end) {
+            break;
+        }
+        if trimmed_end.trim_start().starts_with('>') {
+            continue;
+        }
+        if trimmed_end.is_empty() && lines.last().is_none_or(|l: &&str| l.is_empty()) {
+            continue;
+        }
+        lines.push(trimmed_end);
+    }
+
+    let text = lines.join("\n").trim().to_string();
+    match text.char_indices().nth(MAX_BODY_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><title>x</title></head><body><p>Hello&nbsp;there</p>\
+                    <style>p { color: red }</style><div>A &amp; B<br>done</div></body></html>";
+        let text = html_to_text(html);
+        assert_eq!(clean_body(&text), "Hello there\n\nA & B\ndone");
+    }
+
+    #[test]
+    fn test_clean_body_drops_quotes_and_signature() {
+        let body = "Sounds good.\n\n\n\nSee you Friday.\n> earlier\n\nOn Mon, Mar 4, 2024, Sam wrote:\n> hi";
+        assert_eq!(clean_body(body), "Sounds good.\n\nSee you Friday.");
+
+        let signed = "Thanks!\n--\nJane\nCEO";
+        assert_eq!(clean_body(signed), "Thanks!");
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::conversation::{Conversation, ExportSource, Message, Role};
+
+// ChatGPT's conversations.json stores each conversation as a tree of nodes
+// (edits and regenerations branch). The shown thread is the path from
+// `current_node` back to the root.
+
+#[derive(Debug, Deserialize)]
+struct RawConversation {
+    title: Option<String>,
+    create_time: Option<f64>,
+    #[serde(default)]
+    mapping: HashMap<String, RawNode>,
+    current_node: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawNode {
+    message: Option<RawMessage>,
+    parent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMessage {
+    author: RawAuthor,
+    content: Option<RawContent>,
+    create_time: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawAuthor {
+    role: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawContent {
+    #[serde(default)]
+    parts: Vec<serde_json::Value>,
+}
+
+fn timestamp(secs: Option<f64>) -> Option<DateTime<Utc>> {
+    let secs = secs?;
+    Utc.timestamp_opt(secs.trunc() as i64, (secs.fract() * 1e9) as u32)
+        .single()
+}
+
+/// Node ids from the root to the leaf of the visible thread
+fn thread_ids(raw: &RawConversation) -> Vec<String> {
+    let leaf = raw.current_node.clone().or_else(|| {
+        raw.mapping
+            .iter()
+            .filter_map(|(id, node)| Some((id, node.message.as_ref()?.create_time?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id.clone())
+    });
+
+    let mut ids = Vec::new();
+    let mut next = leaf;
+    // Bounded by the node count in case of a malformed cycle
+    while let Some(id) = next {
+        if ids.len() > raw.mapping.len() {
+            break;
+        }
+        next = raw.mapping.get(&id).and_then(|n| n.parent.clone());
+        ids.push(id);
+    }
+    ids.reverse();
+    ids
+}
+
+fn convert(raw: RawConversation) -> Option<Conversation> {
+    let mut messages = Vec::new();
+
+    for id in thread_ids(&raw) {
+        let Some(message) = raw.mapping.get(&id).and_then(|n| n.message.as_ref()) else {
+            continue;
+        };
+        let role = match message.author.role.as_str() {
+            "user" => Role::User,
+            "assistant" => Role::Assistant,
+            _ => continue, // system and tool messages
+        };
+
+        // Non-text parts (images, files) are objects; keep only text
+        let text = message
+            .content
+            .as_ref()
+            .map(|c| {
+                c.parts
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        messages.push(Message {
+            role,
+            text,
+            created_at: timestamp(message.create_time),
+        });
+    }
+
+    let created_at = timestamp(raw.create_time)
+        .or_else(|| messages.iter().find_map(|m| m.created_at))?;
+
+    Some(Conversation {
+        source: ExportSource::ChatGpt,
+        title: raw.title.unwrap_or_else(|| "Untitled".to_string()),
+        created_at,
+        messages,
+    })
+}
+
+/// Parse a ChatGPT conversations.json. Conversations without a timestamp are skipped.
+pub fn parse(json: &str) -> Result<Vec<Conversation>> {
+    let raw: Vec<RawConversation> =
+        serde_json::from_str(json).context("Invalid ChatGPT conversations.json")?;
+
+    Ok(raw.into_iter().filter_map(convert).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"[{
+        "title": "Trip planning",
+        "create_time": 1700000000.5,
+        "current_node": "c",
+        "mapping": {
+            "root": {"message": null, "parent": null},
+            "a": {"message": {"author": {"role": "user"}, "content": {"parts": ["Plan a trip"]}, "create_time": 1700000001.0}, "parent": "root"},
+            "b-old": {"message": {"author": {"role": "assistant"}, "content": {"parts": ["Discarded"]}, "create_time": 1700000002.0}, "parent": "a"},
+            "c": {"message": {"author": {"role": "assistant"}, "content": {"parts": ["Sure!", {"asset": "img"}]}, "create_time": 1700000003.0}, "parent": "a"}
+        }
+    }]"#;
+
+    #[test]
+    fn test_follows_current_branch() {
+        let conversations = parse(EXPORT).unwrap();
+        assert_eq!(conversations.len(), 1);
+
+        let conversation = &conversations[0];
+        assert_eq!(conversation.title, "Trip planning");
+        assert_eq!(conversation.created_at.timestamp(), 1700000000);
+
+        let texts: Vec<&str> = conversation.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["Plan a trip", "Sure!"]);
+        assert_eq!(conversation.messages[0].role, Role::User);
+    }
+
+    #[test]
+    fn test_cycle_does_not_hang() {
+        let json = r#"[{"title": "Loop", "create_time": 1.0, "current_node": "a", "mapping": {
+            "a": {"message": null, "parent": "b"},
+            "b": {"message": null, "parent": "a"}
+        }}]"#;
+        let conversations = parse(json).unwrap();
+        assert!(conversations[0].messages.is_empty());
+    }
+}
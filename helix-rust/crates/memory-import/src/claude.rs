@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::conversation::{Conversation, ExportSource, Message, Role};
+
+// Claude's conversations.json is a flat list of conversations, each with its
+// messages in order under `chat_messages`.
+
+#[derive(Debug, Deserialize)]
+struct RawConversation {
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    chat_messages: Vec<RawMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMessage {
+    sender: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    content: Vec<RawContentBlock>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+}
+
+impl RawMessage {
+    /// Newer exports leave `text` empty and put text in content blocks
+    fn text(&self) -> String {
+        if !self.text.trim().is_empty() {
+            return self.text.clone();
+        }
+
+        self.content
+            .iter()
+            .filter(|b| b.kind == "text")
+            .filter_map(|b| b.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn convert(raw: RawConversation) -> Conversation {
+    let messages = raw
+        .chat_messages
+        .iter()
+        .filter_map(|m| {
+            let role = match m.sender.as_str() {
+                "human" => Role::User,
+                "assistant" => Role::Assistant,
+                _ => return None,
+            };
+            let text = m.text();
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(Message {
+                role,
+                text,
+                created_at: m.created_at,
+            })
+        })
+        .collect();
+
+    Conversation {
+        source: ExportSource::Claude,
+        title: raw
+            .name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| "Untitled".to_string()),
+        created_at: raw.created_at,
+        messages,
+    }
+}
+
+/// Parse a Claude conversations.json
+pub fn parse(json: &str) -> Result<Vec<Conversation>> {
+    let raw: Vec<RawConversation> =
+        serde_json::from_str(json).context("Invalid Claude conversations.json")?;
+
+    Ok(raw.into_iter().map(convert).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_text_and_content_blocks() {
+        let json = r#"[{
+            "uuid": "1",
+            "name": "",
+            "created_at": "2024-05-01T10:00:00Z",
+            "chat_messages": [
+                {"sender": "human", "text": "Hello there", "created_at": "2024-05-01T10:00:01Z"},
+                {"sender": "assistant", "text": "", "content": [
+                    {"type": "text", "text": "Hi!"},
+                    {"type": "tool_use"}
+                ]}
+            ]
+        }]"#;
+
+        let conversations = parse(json).unwrap();
+        let conversation = &conversations[0];
+        assert_eq!(conversation.title, "Untitled");
+        assert_eq!(conversation.messages.len(), 2);
+        assert_eq!(conversation.messages[0].role, Role::User);
+        assert_eq!(conversation.messages[1].text, "Hi!");
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Which assistant produced the export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportSource {
+    ChatGpt,
+    Claude,
+}
+
+impl ExportSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportSource::ChatGpt => "ChatGPT",
+            ExportSource::Claude => "Claude",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub role: Role,
+    pub text: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A conversation from either export format, messages in order
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub source: ExportSource,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub messages: Vec<Message>,
+}
+
+impl Conversation {
+    /// Everything the user wrote, for valence estimation
+    pub fn user_text(&self) -> String {
+        self.messages
+            .iter()
+            .filter(|m| m.role == Role::User)
+            .map(|m| m.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
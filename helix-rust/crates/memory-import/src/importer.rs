@@ -0,0 +1,238 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use helix_shared::{Memory, MemoryType};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::conversation::{Conversation, ExportSource, Role};
+use crate::valence::estimate_valence;
+use crate::{chatgpt, claude};
+
+/// Longest memory content kept from one conversation
+const MAX_CONTENT_CHARS: usize = 8000;
+/// Both export formats keep conversations in this file
+const CONVERSATIONS_FILE: &str = "conversations.json";
+
+/// Summary of an import run, printed as JSON by the binary
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub source: Option<ExportSource>,
+    pub conversations_found: usize,
+    pub memories_created: usize,
+    pub duplicates_skipped: usize,
+    /// Conversations with no user or assistant text
+    pub empty_skipped: usize,
+    pub earliest: Option<DateTime<Utc>>,
+    pub latest: Option<DateTime<Utc>>,
+    pub average_valence: Option<f32>,
+}
+
+#[derive(Debug)]
+pub struct ImportResult {
+    pub memories: Vec<Memory>,
+    pub summary: ImportSummary,
+}
+
+/// Read conversations.json from an export ZIP, or a bare conversations.json
+fn read_conversations_json(path: &Path) -> Result<String> {
+    let is_json = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+
+    if is_json {
+        return std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()));
+    }
+
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).context("Not a valid ZIP archive")?;
+
+    let name = archive
+        .file_names()
+        .filter(|n| n.rsplit('/').next() == Some(CONVERSATIONS_FILE))
+        .min_by_key(|n| n.len())
+        .map(String::from)
+        .with_context(|| format!("No {} in archive", CONVERSATIONS_FILE))?;
+
+    let mut json = String::new();
+    archive
+        .by_name(&name)?
+        .read_to_string(&mut json)
+        .with_context(|| format!("Failed to read {} from archive", name))?;
+    Ok(json)
+}
+
+/// Tell the formats apart by the shape of the first conversation
+pub fn detect_source(json: &str) -> Result<ExportSource> {
+    let value: serde_json::Value = serde_json::from_str(json).context("Invalid conversations.json")?;
+    let Some(first) = value.as_array().and_then(|a| a.first()) else {
+        bail!("conversations.json contains no conversations");
+    };
+
+    if first.get("mapping").is_some() {
+        Ok(ExportSource::ChatGpt)
+    } else if first.get("chat_messages").is_some() {
+        Ok(ExportSource::Claude)
+    } else {
+        bail!("Unrecognized export format")
+    }
+}
+
+/// Cut `text` to at most `max` characters on a char boundary
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+/// Turn a conversation into an episodic memory; `None` if it has no text
+pub fn to_memory(conversation: &Conversation, user_id: Uuid) -> Option<Memory> {
+    if conversation.messages.is_empty() {
+        return None;
+    }
+
+    let mut content = format!(
+        "{} conversation: {}\n",
+        conversation.source.label(),
+        conversation.title
+    );
+    for message in &conversation.messages {
+        let speaker = match message.role {
+            Role::User => "User",
+            Role::Assistant => conversation.source.label(),
+        };
+        content.push_str(&format!("\n{}: {}\n", speaker, message.text.trim()));
+    }
+
+    Some(Memory {
+        id: Uuid::new_v4(),
+        user_id,
+        memory_type: MemoryType::Episodic,
+        content: truncate_chars(&content, MAX_CONTENT_CHARS),
+        embedding: None,
+        emotional_valence: estimate_valence(&conversation.user_text()),
+        created_at: conversation.created_at,
+        last_accessed: None,
+    })
+}
+
+/// Key under which two memories count as duplicates
+pub fn dedupe_key(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Convert conversations to memories, dropping empty and duplicate ones
+pub fn convert(conversations: &[Conversation], user_id: Uuid) -> ImportResult {
+    let mut summary = ImportSummary {
+        source: conversations.first().map(|c| c.source),
+        conversations_found: conversations.len(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut memories = Vec::new();
+
+    for conversation in conversations {
+        let Some(memory) = to_memory(conversation, user_id) else {
+            summary.empty_skipped += 1;
+            continue;
+        };
+
+        if !seen.insert(dedupe_key(&memory.content)) {
+            summary.duplicates_skipped += 1;
+            continue;
+        }
+
+        let created_at = memory.created_at;
+        summary.earliest = Some(summary.earliest.map_or(created_at, |e| e.min(created_at)));
+        summary.latest = Some(summary.latest.map_or(created_at, |l| l.max(created_at)));
+        memories.push(memory);
+    }
+
+    let valences: Vec<f32> = memories.iter().filter_map(|m| m.emotional_valence).collect();
+    if !valences.is_empty() {
+        summary.average_valence = Some(valences.iter().sum::<f32>() / valences.len() as f32);
+    }
+    summary.memories_created = memories.len();
+
+    ImportResult { memories, summary }
+}
+
+/// Parse a ChatGPT or Claude export and convert it to memories for `user_id`
+pub fn import_archive(path: &Path, user_id: Uuid) -> Result<ImportResult> {
+    let json = read_conversations_json(path)?;
+    let source = detect_source(&json)?;
+    info!("Importing {} export from {}", source.label(), path.display());
+
+    let conversations = match source {
+        ExportSource::ChatGpt => chatgpt::parse(&json)?,
+        ExportSource::Claude => claude::parse(&json)?,
+    };
+    debug!("Parsed {} conversations", conversations.len());
+
+    Ok(convert(&conversations, user_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::Message;
+    use chrono::TimeZone;
+
+    fn conversation(title: &str, text: &str) -> Conversation {
+        Conversation {
+            source: ExportSource::Claude,
+            title: title.to_string(),
+            created_at: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            messages: vec![Message {
+                role: Role::User,
+                text: text.to_string(),
+                created_at: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_detect_source() {
+        assert_eq!(detect_source(r#"[{"mapping": {}}]"#).unwrap(), ExportSource::ChatGpt);
+        assert_eq!(detect_source(r#"[{"chat_messages": []}]"#).unwrap(), ExportSource::Claude);
+        assert!(detect_source("[]").is_err());
+        assert!(detect_source(r#"[{"other": 1}]"#).is_err());
+    }
+
+    #[test]
+    fn test_convert_dedupes_and_skips_empty() {
+        let mut empty = conversation("Empty", "");
+        empty.messages.clear();
+
+        let conversations = vec![
+            conversation("Day", "I had a great day"),
+            conversation("Day", "I had a  great day"),
+            empty,
+        ];
+        let result = convert(&conversations, Uuid::new_v4());
+
+        assert_eq!(result.memories.len(), 1);
+        assert_eq!(result.summary.conversations_found, 3);
+        assert_eq!(result.summary.duplicates_skipped, 1);
+        assert_eq!(result.summary.empty_skipped, 1);
+        assert!(result.summary.average_valence.unwrap() > 0.0);
+        assert_eq!(result.summary.earliest, result.summary.latest);
+    }
+
+    #[test]
+    fn test_truncate_chars_respects_boundaries() {
+        assert_eq!(truncate_chars("héllo", 2), "hé…");
+        assert_eq!(truncate_chars("hi", 5), "hi");
+    }
+}
@@ -0,0 +1,9 @@
+pub mod chatgpt;
+pub mod claude;
+pub mod conversation;
+pub mod importer;
+pub mod valence;
+
+pub use conversation::{Conversation, ExportSource, Message, Role};
+pub use importer::{import_archive, ImportResult, ImportSummary};
+pub use valence::estimate_valence;
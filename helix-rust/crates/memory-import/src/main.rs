@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use helix_shared::{bulk, BulkOptions, Commit, SupabaseClient};
+use memory_import::import_archive;
+use std::path::PathBuf;
+use tracing::{error, info};
+use uuid::Uuid;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// User ID to import memories for
+    #[arg(short, long)]
+    user_id: Uuid,
+
+    /// ChatGPT or Claude export ZIP (or its conversations.json)
+    #[arg(short, long)]
+    archive: PathBuf,
+
+    /// Parse and summarize without writing to Supabase
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Memories per INSERT (default: HELIX_BULK_BATCH_SIZE or 500). Each
+    /// batch commits on its own: an import that fails partway keeps what it
+    /// wrote, and running it again skips those as duplicates.
+    #[arg(long)]
+    batch_size: Option<usize>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Logs go to stderr; stdout carries only the JSON summary
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+
+    let args = Args::parse();
+
+    let mut result = match import_archive(&args.archive, args.user_id) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Import failed: {:#}", e);
+            return Err(e);
+        }
+    };
+
+    if !args.dry_run {
+        let client = SupabaseClient::new().await?;
+        let mut options = BulkOptions::from_env().commit(Commit::PerBatch);
+        if let Some(batch_size) = args.batch_size {
+            options = options.batch_size(batch_size);
+        }
+
+        let inserted = bulk::insert_memories(client.pool(), &result.memories, options)
+            .await
+            .context("Failed to insert imported memories")?
+            .len();
+
+        result.summary.duplicates_skipped += result.memories.len() - inserted;
+        result.summary.memories_created = inserted;
+        info!("Imported {} memories for user {}", inserted, args.user_id);
+    }
+
+    println!("{}", serde_json::to_string(&result.summary)?);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// Lexicon-based emotional valence estimate.
+// Deliberately simple: imported memories only need a rough starting valence,
+// which later synthesis passes refine.
+
+const POSITIVE: &[&str] = &[
+    "happy", "glad", "great", "good", "love", "loved", "excited", "grateful",
+    "thankful", "thanks", "proud", "hope", "hopeful", "calm", "relieved",
+    "enjoy", "enjoyed", "fun", "amazing", "wonderful", "awesome", "nice",
+    "better", "best", "success", "win", "won", "laugh", "beautiful", "perfect",
+];
+
+const NEGATIVE: &[&str] = &[
+    "sad", "angry", "upset", "hate", "hated", "afraid", "scared", "anxious",
+    "worried", "stress", "stressed", "tired", "lonely", "hurt", "pain",
+    "depressed", "frustrated", "annoyed", "terrible", "awful", "bad", "worse",
+    "worst", "fail", "failed", "lost", "cry", "crying", "guilty", "ashamed",
+];
+
+const NEGATORS: &[&str] = &["not", "no", "never", "dont", "don't", "isnt", "isn't", "wasnt", "wasn't"];
+
+/// Estimate valence of `text` in [-1, 1]; `None` when there are no words.
+///
+/// A sentiment word right after a negator flips polarity ("not happy").
+pub fn estimate_valence(text: &str) -> Option<f32> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut positive = 0i32;
+    let mut negative = 0i32;
+
+    for (i, word) in words.iter().enumerate() {
+        let polarity = if POSITIVE.contains(&word.as_str()) {
+            1
+        } else if NEGATIVE.contains(&word.as_str()) {
+            -1
+        } else {
+            continue;
+        };
+
+        let negated = i > 0 && NEGATORS.contains(&words[i - 1].as_str());
+        if (polarity > 0) != negated {
+            positive += 1;
+        } else {
+            negative += 1;
+        }
+    }
+
+    // Damped so a single sentiment word doesn't saturate the score
+    let score = (positive - negative) as f32 / (positive + negative + 2) as f32;
+    Some(score.clamp(-1.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polarity() {
+        assert!(estimate_valence("I am so happy and grateful today").unwrap() > 0.0);
+        assert!(estimate_valence("I feel anxious and tired").unwrap() < 0.0);
+        assert_eq!(estimate_valence("The meeting is at noon"), Some(0.0));
+        assert_eq!(estimate_valence("  ... "), None);
+    }
+
+    #[test]
+    fn test_negation_flips_polarity() {
+        assert!(estimate_valence("I am not happy").unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_score_is_bounded() {
+        let text = "great ".repeat(1000);
+        let score = estimate_valence(&text).unwrap();
+        assert!(score > 0.9 && score <= 1.0);
+    }
+}
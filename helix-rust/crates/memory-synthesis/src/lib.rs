@@ -1,5 +1,7 @@
 pub mod pattern_detection;
 pub mod clustering;
+pub mod repository;
 
 pub use pattern_detection::PatternDetector;
 pub use clustering::Cluster;
+pub use repository::{list_syntheses, PageRequest, SynthesisFilter, SynthesisPage};
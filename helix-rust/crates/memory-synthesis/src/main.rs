@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use helix_shared::schema::{self, SchemaSpec};
 use helix_shared::SupabaseClient;
 use tracing::{info, error};
 use tracing_subscriber;
@@ -7,15 +8,23 @@ use uuid::Uuid;
 
 mod pattern_detection;
 mod clustering;
+mod repository;
+mod rpc_server;
 
 use pattern_detection::PatternDetector;
 
+/// Tables, columns and extensions synthesize_patterns uses
+const SCHEMA: SchemaSpec<'static> = SchemaSpec {
+    tables: &[schema::MEMORIES, schema::MEMORY_SYNTHESIS],
+    extensions: &[schema::PGVECTOR],
+};
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// User ID to synthesize memories for
-    #[arg(short, long)]
-    user_id: Uuid,
+    #[arg(short, long, required_unless_present_any = ["check_schema", "serve"])]
+    user_id: Option<Uuid>,
 
     /// Number of recent memories to analyze
     #[arg(short, long, default_value_t = 100)]
@@ -24,6 +33,20 @@ struct Args {
     /// Minimum confidence score threshold
     #[arg(short, long, default_value_t = 0.7)]
     confidence: f32,
+
+    /// Print the schema compatibility matrix as JSON and exit, with status 1
+    /// when the schema is incompatible
+    #[arg(long)]
+    check_schema: bool,
+
+    /// Serve synthesis reads (GET /syntheses) instead of running a synthesis;
+    /// requires HELIX_SYNTHESIS_TOKEN
+    #[arg(long)]
+    serve: bool,
+
+    /// Port for --serve
+    #[arg(short, long, default_value_t = 18788)]
+    port: u16,
 }
 
 #[tokio::main]
@@ -32,12 +55,24 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    info!("Starting memory synthesis for user {}", args.user_id);
-
     let client = SupabaseClient::new().await?;
+    if args.check_schema {
+        let compatibility = client.check_schema(&SCHEMA).await?;
+        println!("{}", serde_json::to_string_pretty(&compatibility)?);
+        std::process::exit(if compatibility.compatible { 0 } else { 1 });
+    }
+    if args.serve {
+        client.require_schema(&SCHEMA).await?;
+        return rpc_server::start_rpc_server(client, args.port).await;
+    }
+    let user_id = args.user_id.context("--user-id is required")?;
+
+    info!("Starting memory synthesis for user {}", user_id);
+
+    client.require_schema(&SCHEMA).await?;
     let detector = PatternDetector::new(client.clone(), args.confidence);
 
-    match detector.synthesize_patterns(args.user_id, args.limit).await {
+    match detector.synthesize_patterns(user_id, args.limit).await {
         Ok(count) => {
             info!("Successfully created {} synthesis patterns", count);
             Ok(())
@@ -1,27 +1,59 @@
 use anyhow::{Context, Result};
-use helix_shared::{Memory, MemorySynthesis, SupabaseClient};
-use sqlx::Row;
+use helix_shared::{bulk, transaction, BulkOptions, Memory, MemorySynthesis, RetryPolicy, SupabaseClient, Tokenizer};
+use sqlx::{PgConnection, Row};
+use std::collections::HashSet;
 use uuid::Uuid;
 use tracing::{debug, info};
 use chrono::Utc;
 
 use crate::clustering::cluster_memories;
 
+/// Longest synthesis text written for one pattern
+pub const MAX_SYNTHESIS_TOKENS: usize = 256;
+
+#[derive(Clone)]
 pub struct PatternDetector {
     client: SupabaseClient,
     min_confidence: f32,
+    tokenizer: Tokenizer,
+    bulk: BulkOptions,
+    retry: RetryPolicy,
 }
 
 impl PatternDetector {
     pub fn new(client: SupabaseClient, min_confidence: f32) -> Self {
-        Self { client, min_confidence }
+        Self {
+            client,
+            min_confidence,
+            tokenizer: Tokenizer::default(),
+            bulk: BulkOptions::from_env(),
+            retry: RetryPolicy::default(),
+        }
     }
 
+    /// Detect and store a user's patterns. The memory read, the check
+    /// against patterns already stored and the insert run in one
+    /// SERIALIZABLE transaction, so two overlapping runs for the same user
+    /// conflict instead of both writing the same set; the loser reruns and
+    /// finds the winner's rows.
     pub async fn synthesize_patterns(&self, user_id: Uuid, limit: i32) -> Result<usize> {
         info!("Fetching recent {} memories for user {}", limit, user_id);
 
+        let written = transaction::serializable(self.client.pool(), self.retry, |conn| {
+            let detector = self.clone();
+            Box::pin(async move { detector.synthesize_in(conn, user_id, limit).await })
+        })
+        .await
+        .context("Failed to write synthesis to Supabase")?;
+
+        info!("Wrote {} patterns to Supabase", written);
+        Ok(written)
+    }
+
+    /// One attempt of synthesize_patterns on its transaction
+    async fn synthesize_in(&self, conn: &mut PgConnection, user_id: Uuid, limit: i32) -> Result<usize> {
         // 1. Fetch recent memories from Supabase
-        let memories = self.fetch_recent_memories(user_id, limit).await?;
+        let memories = self.fetch_recent_memories(conn, user_id, limit).await?;
 
         if memories.is_empty() {
             info!("No memories found for synthesis");
@@ -39,16 +71,20 @@ impl PatternDetector {
         // 4. Detect emotional patterns
         let emotional = self.detect_emotional_patterns(&memories)?;
 
-        // 5. Write synthesis results to Supabase
-        let mut count = 0;
-        count += self.write_patterns(user_id, "temporal", temporal).await?;
-        count += self.write_patterns(user_id, "semantic", semantic).await?;
-        count += self.write_patterns(user_id, "emotional", emotional).await?;
+        // 5. Drop patterns an earlier run already stored
+        let mut syntheses = self.to_syntheses(user_id, "temporal", temporal);
+        syntheses.extend(self.to_syntheses(user_id, "semantic", semantic));
+        syntheses.extend(self.to_syntheses(user_id, "emotional", emotional));
+
+        let existing = self.stored_patterns(conn, user_id).await?;
+        syntheses.retain(|synthesis| !existing.contains(&pattern_key(&synthesis.pattern_type, &synthesis.memory_ids)));
 
-        Ok(count)
+        // 6. Write the rest
+        let written = bulk::insert_syntheses_in(conn, &syntheses, self.bulk.batch_size).await?;
+        Ok(written.len())
     }
 
-    async fn fetch_recent_memories(&self, user_id: Uuid, limit: i32) -> Result<Vec<Memory>> {
+    async fn fetch_recent_memories(&self, conn: &mut PgConnection, user_id: Uuid, limit: i32) -> Result<Vec<Memory>> {
         let rows = sqlx::query(
             "SELECT id, user_id, type, content, embedding, emotional_valence, created_at, last_accessed
              FROM memories
@@ -58,7 +94,7 @@ impl PatternDetector {
         )
         .bind(user_id)
         .bind(limit)
-        .fetch_all(self.client.pool())
+        .fetch_all(&mut *conn)
         .await
         .context("Failed to fetch memories from Supabase")?;
 
@@ -78,6 +114,20 @@ impl PatternDetector {
         Ok(memories)
     }
 
+    /// The patterns already stored for a user, by type and memory set
+    async fn stored_patterns(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<HashSet<(String, Vec<Uuid>)>> {
+        let rows = sqlx::query("SELECT pattern_type, memory_ids FROM memory_synthesis WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to fetch stored patterns from Supabase")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| pattern_key(&row.get::<String, _>("pattern_type"), &row.get::<Vec<Uuid>, _>("memory_ids")))
+            .collect())
+    }
+
     fn detect_temporal_patterns(&self, memories: &[Memory]) -> Result<Vec<Pattern>> {
         // Group memories by time windows (daily, weekly)
         let mut patterns = Vec::new();
@@ -173,47 +223,34 @@ impl PatternDetector {
         Ok(patterns)
     }
 
-    async fn write_patterns(&self, user_id: Uuid, category: &str, patterns: Vec<Pattern>) -> Result<usize> {
-        let mut count = 0;
-
-        for pattern in patterns {
-            if pattern.confidence < self.min_confidence {
-                continue;
-            }
-
-            let synthesis = MemorySynthesis {
+    /// Synthesis rows for the patterns confident enough to keep
+    fn to_syntheses(&self, user_id: Uuid, category: &str, patterns: Vec<Pattern>) -> Vec<MemorySynthesis> {
+        let syntheses: Vec<MemorySynthesis> = patterns
+            .into_iter()
+            .filter(|pattern| pattern.confidence >= self.min_confidence)
+            .map(|pattern| MemorySynthesis {
                 id: Uuid::new_v4(),
                 user_id,
                 pattern_type: format!("{}_{}", category, pattern.pattern_type),
-                memory_ids: pattern.memory_ids.clone(),
-                synthesis_content: pattern.synthesis.clone(),
+                memory_ids: pattern.memory_ids,
+                synthesis_content: self.tokenizer.truncate(&pattern.synthesis, MAX_SYNTHESIS_TOKENS).to_string(),
                 confidence_score: pattern.confidence,
                 created_at: Utc::now(),
-            };
-
-            sqlx::query(
-                "INSERT INTO memory_synthesis (id, user_id, pattern_type, memory_ids, synthesis_content, confidence_score, created_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7)"
-            )
-            .bind(synthesis.id)
-            .bind(synthesis.user_id)
-            .bind(&synthesis.pattern_type)
-            .bind(&synthesis.memory_ids)
-            .bind(&synthesis.synthesis_content)
-            .bind(synthesis.confidence_score)
-            .bind(synthesis.created_at)
-            .execute(self.client.pool())
-            .await
-            .context("Failed to write synthesis to Supabase")?;
-
-            count += 1;
-        }
+            })
+            .collect();
 
-        info!("Wrote {} {} patterns to Supabase", count, category);
-        Ok(count)
+        debug!("{} {} patterns to write", syntheses.len(), category);
+        syntheses
     }
 }
 
+/// A pattern's identity: its type and the memories it covers, in any order
+fn pattern_key(pattern_type: &str, memory_ids: &[Uuid]) -> (String, Vec<Uuid>) {
+    let mut memory_ids = memory_ids.to_vec();
+    memory_ids.sort_unstable();
+    (pattern_type.to_string(), memory_ids)
+}
+
 #[derive(Debug)]
 struct Pattern {
     memory_ids: Vec<Uuid>,
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use helix_shared::MemorySynthesis;
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+/// Which syntheses to list; unset fields don't filter
+#[derive(Debug, Clone, Default)]
+pub struct SynthesisFilter {
+    /// Exact pattern type, e.g. "emotional_emotional_positive"
+    pub pattern_type: Option<String>,
+    pub min_confidence: Option<f32>,
+}
+
+/// A 1-based page of results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl PageRequest {
+    /// Page 0 is treated as 1; the size is kept within 1..=MAX_PAGE_SIZE
+    pub fn new(page: u32, page_size: u32) -> Self {
+        Self {
+            page: page.max(1),
+            page_size: page_size.clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    fn offset(&self) -> i64 {
+        i64::from(self.page - 1) * i64::from(self.page_size)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SynthesisPage {
+    /// Newest first
+    pub items: Vec<MemorySynthesis>,
+    pub page: u32,
+    pub page_size: u32,
+    /// Rows matching the filter, across all pages
+    pub total: i64,
+    pub has_more: bool,
+}
+
+/// One page of a user's syntheses matching `filter`, newest first
+pub async fn list_syntheses(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &SynthesisFilter,
+    page: PageRequest,
+) -> Result<SynthesisPage> {
+    let total: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM memory_synthesis
+         WHERE user_id = $1
+           AND ($2::text IS NULL OR pattern_type = $2)
+           AND ($3::real IS NULL OR confidence_score >= $3)",
+    )
+    .bind(user_id)
+    .bind(filter.pattern_type.as_deref())
+    .bind(filter.min_confidence)
+    .fetch_one(pool)
+    .await
+    .context("Failed to count syntheses")?;
+
+    let rows = sqlx::query(
+        "SELECT id, user_id, pattern_type, memory_ids, synthesis_content, confidence_score, created_at
+         FROM memory_synthesis
+         WHERE user_id = $1
+           AND ($2::text IS NULL OR pattern_type = $2)
+           AND ($3::real IS NULL OR confidence_score >= $3)
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4 OFFSET $5",
+    )
+    .bind(user_id)
+    .bind(filter.pattern_type.as_deref())
+    .bind(filter.min_confidence)
+    .bind(i64::from(page.page_size))
+    .bind(page.offset())
+    .fetch_all(pool)
+    .await
+    .context("Failed to list syntheses")?;
+
+    let items = rows
+        .iter()
+        .map(|row| {
+            Ok(MemorySynthesis {
+                id: row.try_get("id")?,
+                user_id: row.try_get("user_id")?,
+                pattern_type: row.try_get("pattern_type")?,
+                memory_ids: row.try_get("memory_ids")?,
+                synthesis_content: row.try_get("synthesis_content")?,
+                confidence_score: row.try_get("confidence_score")?,
+                created_at: row.try_get("created_at")?,
+            })
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()
+        .context("Failed to read synthesis row")?;
+
+    let has_more = page.offset() + (items.len() as i64) < total;
+    Ok(SynthesisPage {
+        items,
+        page: page.page,
+        page_size: page.page_size,
+        total,
+        has_more,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_request_is_clamped() {
+        assert_eq!(PageRequest::new(0, 0), PageRequest { page: 1, page_size: 1 });
+        assert_eq!(PageRequest::new(3, 10_000).page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn offset_skips_earlier_pages() {
+        assert_eq!(PageRequest::default().offset(), 0);
+        assert_eq!(PageRequest::new(3, 25).offset(), 50);
+        assert_eq!(PageRequest::new(u32::MAX, MAX_PAGE_SIZE).offset(), i64::from(u32::MAX - 1) * 200);
+    }
+}
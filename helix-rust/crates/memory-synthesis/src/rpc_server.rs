@@ -0,0 +1,127 @@
+use anyhow::Result;
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use helix_shared::SupabaseClient;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::repository::{self, PageRequest, SynthesisFilter, DEFAULT_PAGE_SIZE};
+
+/// Token the app generates for each server it starts; every request must
+/// carry it
+pub const TOKEN_ENV: &str = "HELIX_SYNTHESIS_TOKEN";
+
+#[derive(Clone)]
+struct AppState {
+    client: SupabaseClient,
+    token: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    user_id: Uuid,
+    // Not a flattened SynthesisFilter: query strings can't feed numbers
+    // through serde(flatten)
+    pattern_type: Option<String>,
+    min_confidence: Option<f32>,
+    #[serde(default = "first_page")]
+    page: u32,
+    #[serde(default = "default_page_size")]
+    page_size: u32,
+}
+
+fn first_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    DEFAULT_PAGE_SIZE
+}
+
+/// Serve synthesis reads. Binds to loopback and requires the token in
+/// HELIX_SYNTHESIS_TOKEN: the endpoints take a user id, so only the app
+/// that started the server may call them.
+pub async fn start_rpc_server(client: SupabaseClient, port: u16) -> Result<()> {
+    let token = std::env::var(TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is not set; the server won't run unauthenticated",
+                TOKEN_ENV
+            )
+        })?;
+    let state = AppState {
+        client,
+        token: token.into(),
+    };
+
+    let app = Router::new()
+        .route("/status", get(status))
+        .route("/syntheses", get(list_syntheses))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    info!("Memory synthesis RPC server listening on port {}", port);
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Compare in constant time so response timing doesn't leak the token
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Reject requests without the bearer token
+async fn require_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let given = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match given {
+        Some(given) if token_matches(&state.token, given) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid synthesis server token",
+        )
+            .into_response(),
+    }
+}
+
+async fn status() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "healthy" }))
+}
+
+async fn list_syntheses(State(state): State<AppState>, Query(query): Query<ListQuery>) -> Response {
+    let filter = SynthesisFilter {
+        pattern_type: query.pattern_type,
+        min_confidence: query.min_confidence,
+    };
+    let page = PageRequest::new(query.page, query.page_size);
+    match repository::list_syntheses(state.client.pool(), query.user_id, &filter, page).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => {
+            error!(
+                "Failed to list syntheses for user {}: {:#}",
+                query.user_id, e
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)).into_response()
+        }
+    }
+}
@@ -63,3 +63,52 @@ async fn test_memory_synthesis_integration() {
         .await
         .expect("Cleanup failed");
 }
+
+#[tokio::test]
+async fn test_bulk_insert_memories() {
+    use helix_shared::{bulk, BulkOptions, Commit};
+
+    let client = SupabaseClient::new().await.expect("Failed to create client");
+    let test_user_id = Uuid::new_v4();
+
+    // Five memories, one repeating another's content, over three batches
+    let memories: Vec<Memory> = ["a", "b", "c", "d", "a"]
+        .iter()
+        .map(|content| Memory {
+            id: Uuid::new_v4(),
+            user_id: test_user_id,
+            memory_type: MemoryType::Episodic,
+            content: format!("Bulk memory {}", content),
+            embedding: Some(vec![0.1; 1536]),
+            emotional_valence: Some(0.5),
+            created_at: Utc::now(),
+            last_accessed: None,
+        })
+        .collect();
+    let options = BulkOptions::default().batch_size(2).commit(Commit::PerBatch);
+
+    let inserted = bulk::insert_memories(client.pool(), &memories, options)
+        .await
+        .expect("Failed to bulk insert memories");
+    assert_eq!(inserted.len(), 4, "The repeated content should be skipped");
+
+    let (stored,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM memories WHERE user_id = $1")
+        .bind(test_user_id)
+        .fetch_one(client.pool())
+        .await
+        .expect("Count failed");
+    assert_eq!(stored, 4);
+
+    // Content the user already has is not written again
+    let again = bulk::insert_memories(client.pool(), &memories, options)
+        .await
+        .expect("Failed to bulk insert memories again");
+    assert!(again.is_empty());
+
+    // Cleanup
+    sqlx::query("DELETE FROM memories WHERE user_id = $1")
+        .bind(test_user_id)
+        .execute(client.pool())
+        .await
+        .expect("Cleanup failed");
+}
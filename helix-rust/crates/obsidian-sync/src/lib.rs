@@ -0,0 +1,8 @@
+pub mod note;
+pub mod render;
+pub mod sync;
+pub mod vault;
+
+pub use note::Note;
+pub use sync::SyncEngine;
+pub use vault::Vault;
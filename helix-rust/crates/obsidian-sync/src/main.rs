@@ -0,0 +1,107 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use helix_shared::schema::{self, SchemaSpec};
+use helix_shared::SupabaseClient;
+use notify::{RecursiveMode, Watcher};
+use obsidian_sync::vault::MEMORIES_FOLDER;
+use obsidian_sync::{SyncEngine, Vault};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Quiet period after a file event before ingesting, so editor autosaves batch up
+const DEBOUNCE_MS: u64 = 2000;
+/// Tables and columns the sync engine uses
+const SCHEMA: SchemaSpec<'static> = SchemaSpec {
+    tables: &[schema::MEMORIES, schema::MEMORY_SYNTHESIS],
+    extensions: &[],
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// User ID whose memories to sync
+    #[arg(short, long)]
+    user_id: Uuid,
+
+    /// Obsidian vault (or any Markdown folder)
+    #[arg(short, long)]
+    vault: PathBuf,
+
+    /// Helix data directory, for the integration report
+    #[arg(long)]
+    helix_dir: Option<PathBuf>,
+
+    /// Most recent memories and syntheses to export
+    #[arg(short, long, default_value_t = 5000)]
+    limit: i64,
+
+    /// Seconds between full syncs in watch mode
+    #[arg(short, long, default_value_t = 300)]
+    interval: u64,
+
+    /// Sync once and exit instead of watching the vault
+    #[arg(long, default_value_t = false)]
+    once: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Logs go to stderr; stdout carries only the JSON stats in --once mode
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+
+    let args = Args::parse();
+
+    let vault = Vault::open(&args.vault)?;
+    let client = SupabaseClient::new().await?;
+    // Syncing overwrites memories; don't start against a schema it can't finish on
+    client.require_schema(&SCHEMA).await?;
+    let engine = SyncEngine::new(client, args.user_id, vault, args.helix_dir, args.limit);
+
+    let stats = engine.full_sync().await?;
+    if args.once {
+        println!("{}", serde_json::to_string(&stats)?);
+        return Ok(());
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event.paths);
+        }
+    })
+    .context("Failed to create vault watcher")?;
+    watcher
+        .watch(&engine.vault().folder(MEMORIES_FOLDER), RecursiveMode::Recursive)
+        .context("Failed to watch vault")?;
+
+    info!("Watching {} for changes", args.vault.display());
+
+    let mut interval = tokio::time::interval(Duration::from_secs(args.interval.max(30)));
+    interval.tick().await; // first tick fires immediately; the initial sync just ran
+
+    loop {
+        tokio::select! {
+            Some(first) = rx.recv() => {
+                let mut paths: BTreeSet<PathBuf> = first.into_iter().collect();
+                tokio::time::sleep(Duration::from_millis(DEBOUNCE_MS)).await;
+                while let Ok(more) = rx.try_recv() {
+                    paths.extend(more);
+                }
+
+                let paths: Vec<PathBuf> = paths.into_iter().collect();
+                if let Err(e) = engine.ingest_paths(&paths).await {
+                    error!("Incremental sync failed: {:#}", e);
+                }
+            }
+            _ = interval.tick() => {
+                if let Err(e) = engine.full_sync().await {
+                    error!("Periodic sync failed: {:#}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+// Markdown note with a flat `key: value` frontmatter block. Helix keeps its
+// bookkeeping in `helix_*` keys; any other keys the user adds (tags, aliases)
+// are preserved verbatim.
+
+/// Id of the memory, synthesis or report the note mirrors
+pub const ID_KEY: &str = "helix_id";
+/// memory | synthesis | report
+pub const TYPE_KEY: &str = "helix_type";
+/// Body hash at the last sync; a mismatch means the note was edited
+pub const HASH_KEY: &str = "helix_hash";
+/// Notes regenerated from Helix on every sync; local edits are overwritten
+pub const READONLY_KEY: &str = "helix_readonly";
+pub const CREATED_KEY: &str = "created";
+pub const VALENCE_KEY: &str = "valence";
+
+const DELIMITER: &str = "---";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Note {
+    pub fields: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Note {
+    pub fn new(body: impl Into<String>) -> Self {
+        Self {
+            fields: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Parse a note; text without a frontmatter block is all body
+    pub fn parse(text: &str) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut lines = text.split_inclusive('\n');
+
+        let Some(first) = lines.next().filter(|l| l.trim_end() == DELIMITER) else {
+            return Self::new(text);
+        };
+
+        let mut fields = Vec::new();
+        let mut header_len = first.len();
+        let mut closed = false;
+
+        for line in lines {
+            header_len += line.len();
+            if line.trim_end() == DELIMITER {
+                closed = true;
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                fields.push((key.trim().to_string(), unquote(value.trim()).to_string()));
+            }
+        }
+
+        if !closed {
+            return Self::new(text);
+        }
+
+        let body = &text[header_len..];
+        Self {
+            fields,
+            body: body.trim_start_matches(['\r', '\n']).to_string(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        if self.fields.is_empty() {
+            return self.body.clone();
+        }
+
+        let mut text = format!("{}\n", DELIMITER);
+        for (key, value) in &self.fields {
+            text.push_str(&format!("{}: {}\n", key, value));
+        }
+        text.push_str(&format!("{}\n\n{}", DELIMITER, self.body));
+        text
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.fields.iter_mut().find(|(k, _)| k == key) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn id(&self) -> Option<Uuid> {
+        self.get(ID_KEY).and_then(|id| Uuid::parse_str(id).ok())
+    }
+
+    pub fn is_readonly(&self) -> bool {
+        self.get(READONLY_KEY) == Some("true")
+    }
+
+    /// Whether the body changed since Helix last wrote or ingested it
+    pub fn is_edited(&self) -> bool {
+        self.get(HASH_KEY) != Some(content_hash(&self.body).as_str())
+    }
+
+    /// Record the current body as synced
+    pub fn mark_synced(&mut self) {
+        let hash = content_hash(&self.body);
+        self.set(HASH_KEY, hash);
+    }
+
+    /// Body as memory content
+    pub fn content(&self) -> String {
+        self.body.trim().to_string()
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Short hash of the trimmed body, insensitive to trailing whitespace
+pub fn content_hash(body: &str) -> String {
+    let digest = Sha256::digest(body.trim().as_bytes());
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// File-name-safe slug of the first line of `text`
+pub fn slug(text: &str, max_chars: usize) -> String {
+    let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let cleaned: String = first_line
+        .trim_start_matches(['#', ' '])
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { ' ' })
+        .collect();
+
+    let slug = cleaned
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(max_chars)
+        .collect::<String>();
+
+    if slug.is_empty() {
+        "Untitled".to_string()
+    } else {
+        slug.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_preserves_user_fields() {
+        let text = "---\nhelix_id: 6f1c\ntags: [journal]\n---\n\nHello\n";
+        let mut note = Note::parse(text);
+        assert_eq!(note.get("tags"), Some("[journal]"));
+        assert_eq!(note.body, "Hello\n");
+
+        note.set(HASH_KEY, "abc");
+        let rendered = note.render();
+        assert!(rendered.contains("tags: [journal]\n"));
+        assert_eq!(Note::parse(&rendered), note);
+    }
+
+    #[test]
+    fn test_without_frontmatter() {
+        let note = Note::parse("Just a note\n---\nmore");
+        assert!(note.fields.is_empty());
+        assert_eq!(note.body, "Just a note\n---\nmore");
+
+        let unclosed = Note::parse("---\nkey: value\nno end");
+        assert!(unclosed.fields.is_empty());
+    }
+
+    #[test]
+    fn test_edit_detection() {
+        let mut note = Note::new("Original\n");
+        assert!(note.is_edited());
+
+        note.mark_synced();
+        assert!(!note.is_edited());
+
+        note.body.push_str("  \n");
+        assert!(!note.is_edited(), "trailing whitespace is not an edit");
+
+        note.body = "Changed".to_string();
+        assert!(note.is_edited());
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("# Met Sam at the café! \nrest", 40), "Met Sam at the café");
+        assert_eq!(slug("a/b:c", 40), "a b c");
+        assert_eq!(slug("   ", 40), "Untitled");
+        assert_eq!(slug("abcdef", 3), "abc");
+    }
+}
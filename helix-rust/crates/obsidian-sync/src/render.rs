@@ -0,0 +1,160 @@
+use helix_shared::{Memory, MemorySynthesis};
+use serde_json::Value;
+
+use crate::note::{self, Note, CREATED_KEY, ID_KEY, READONLY_KEY, TYPE_KEY, VALENCE_KEY};
+
+/// Longest slug used in a note file name
+const SLUG_CHARS: usize = 60;
+/// Stable id for the single integration report note
+pub const REPORT_STEM: &str = "Integration Report";
+
+/// File name (without extension) for a memory note
+pub fn memory_stem(memory: &Memory) -> String {
+    format!(
+        "{} {}",
+        memory.created_at.format("%Y-%m-%d"),
+        note::slug(&memory.content, SLUG_CHARS)
+    )
+}
+
+pub fn synthesis_stem(synthesis: &MemorySynthesis) -> String {
+    format!(
+        "{} {}",
+        synthesis.created_at.format("%Y-%m-%d"),
+        synthesis.pattern_type.replace('_', " ")
+    )
+}
+
+/// Update (or create) a memory note from the stored memory.
+/// User fields in `existing` are kept.
+pub fn memory_note(memory: &Memory, existing: Option<Note>) -> Note {
+    let mut note = existing.unwrap_or_default();
+    note.set(ID_KEY, memory.id.to_string());
+    note.set(TYPE_KEY, "memory");
+    note.set(CREATED_KEY, memory.created_at.to_rfc3339());
+    if let Some(valence) = memory.emotional_valence {
+        note.set(VALENCE_KEY, format!("{:.2}", valence));
+    }
+    note.body = format!("{}\n", memory.content.trim());
+    note.mark_synced();
+    note
+}
+
+/// Read-only note for a synthesis; `memory_links` are note stems of its memories
+pub fn synthesis_note(synthesis: &MemorySynthesis, memory_links: &[String]) -> Note {
+    let mut note = Note::default();
+    note.set(ID_KEY, synthesis.id.to_string());
+    note.set(TYPE_KEY, "synthesis");
+    note.set(CREATED_KEY, synthesis.created_at.to_rfc3339());
+    note.set(READONLY_KEY, "true");
+
+    let mut body = format!(
+        "# {}\n\n{}\n\nConfidence: {:.0}%\n",
+        synthesis.pattern_type.replace('_', " "),
+        synthesis.synthesis_content.trim(),
+        synthesis.confidence_score * 100.0
+    );
+    if !memory_links.is_empty() {
+        body.push_str("\n## Memories\n\n");
+        for link in memory_links {
+            body.push_str(&format!("- [[{}]]\n", link));
+        }
+    }
+
+    note.body = body;
+    note.mark_synced();
+    note
+}
+
+fn list_section(body: &mut String, title: &str, items: Option<&Value>) {
+    let items: Vec<&str> = items
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|i| i.as_str()).collect())
+        .unwrap_or_default();
+
+    body.push_str(&format!("\n## {}\n\n", title));
+    if items.is_empty() {
+        body.push_str("_None_\n");
+    }
+    for item in items {
+        body.push_str(&format!("- {}\n", item));
+    }
+}
+
+/// Read-only note for the Layer 5 integration report (synthesis_report.json)
+pub fn report_note(report: &Value) -> Note {
+    let mut note = Note::default();
+    note.set(TYPE_KEY, "report");
+    note.set(READONLY_KEY, "true");
+    if let Some(timestamp) = report.get("timestamp").and_then(|t| t.as_str()) {
+        note.set(CREATED_KEY, timestamp);
+    }
+
+    let count = |key: &str| report.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let confidence = report.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+    let mut body = format!("# {}\n\nConfidence: {:.0}%\n", REPORT_STEM, confidence * 100.0);
+    body.push_str("\n| Layer | Items |\n| --- | --- |\n");
+    body.push_str(&format!("| Emotional memories | {} |\n", count("layer_2_memories")));
+    body.push_str(&format!("| Relational memories | {} |\n", count("layer_3_memories")));
+    body.push_str(&format!("| Prospective goals | {} |\n", count("layer_4_goals")));
+    list_section(&mut body, "Patterns", report.get("synthesized_patterns"));
+    list_section(&mut body, "Recommended actions", report.get("recommended_actions"));
+
+    note.body = body;
+    note.mark_synced();
+    note
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use helix_shared::MemoryType;
+    use uuid::Uuid;
+
+    fn memory(content: &str) -> Memory {
+        Memory {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            memory_type: MemoryType::Episodic,
+            content: content.to_string(),
+            embedding: None,
+            emotional_valence: Some(0.5),
+            created_at: Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap(),
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn test_memory_note_keeps_user_fields() {
+        let memory = memory("Walked by the river");
+        let mut existing = Note::new("old body");
+        existing.set("tags", "[walks]");
+
+        let note = memory_note(&memory, Some(existing));
+        assert_eq!(note.get("tags"), Some("[walks]"));
+        assert_eq!(note.id(), Some(memory.id));
+        assert_eq!(note.get(VALENCE_KEY), Some("0.50"));
+        assert!(!note.is_edited());
+        assert_eq!(memory_stem(&memory), "2024-03-09 Walked by the river");
+    }
+
+    #[test]
+    fn test_report_note() {
+        let report = serde_json::json!({
+            "timestamp": "2024-03-09T12:00:00",
+            "layer_2_memories": 4,
+            "synthesized_patterns": ["Pattern A"],
+            "recommended_actions": [],
+            "confidence": 0.75
+        });
+
+        let note = report_note(&report);
+        assert!(note.is_readonly());
+        assert!(note.body.contains("Confidence: 75%"));
+        assert!(note.body.contains("| Emotional memories | 4 |"));
+        assert!(note.body.contains("- Pattern A"));
+        assert!(note.body.contains("_None_"));
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use helix_shared::{Memory, MemorySynthesis, MemoryType, SupabaseClient};
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::note::{CREATED_KEY, ID_KEY, TYPE_KEY};
+use crate::render::{self, REPORT_STEM};
+use crate::vault::{read_note, write_note, Vault, MEMORIES_FOLDER, REPORTS_FOLDER, SYNTHESES_FOLDER};
+
+/// Counts from one sync pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncStats {
+    pub notes_created: usize,
+    pub notes_updated: usize,
+    pub memories_created: usize,
+    pub memories_updated: usize,
+    /// Notes with unsynced local edits that export left alone
+    pub local_edits_kept: usize,
+}
+
+impl SyncStats {
+    fn add(&mut self, other: &SyncStats) {
+        self.notes_created += other.notes_created;
+        self.notes_updated += other.notes_updated;
+        self.memories_created += other.memories_created;
+        self.memories_updated += other.memories_updated;
+        self.local_edits_kept += other.local_edits_kept;
+    }
+}
+
+/// Two-way sync between a user's memories and a Markdown vault.
+///
+/// Local edits win: a full sync ingests edited notes before exporting, and
+/// export never overwrites a note whose body changed since the last sync.
+pub struct SyncEngine {
+    client: SupabaseClient,
+    user_id: Uuid,
+    vault: Vault,
+    /// Helix data directory holding transformation/synthesis_report.json
+    helix_dir: Option<PathBuf>,
+    limit: i64,
+}
+
+impl SyncEngine {
+    pub fn new(
+        client: SupabaseClient,
+        user_id: Uuid,
+        vault: Vault,
+        helix_dir: Option<PathBuf>,
+        limit: i64,
+    ) -> Self {
+        Self { client, user_id, vault, helix_dir, limit }
+    }
+
+    pub fn vault(&self) -> &Vault {
+        &self.vault
+    }
+
+    /// Ingest every memory note, then export everything
+    pub async fn full_sync(&self) -> Result<SyncStats> {
+        let mut stats = self.ingest_all().await?;
+        stats.add(&self.export_all().await?);
+        Ok(stats)
+    }
+
+    pub async fn export_all(&self) -> Result<SyncStats> {
+        let mut stats = SyncStats::default();
+        let memories = self.fetch_memories().await?;
+
+        // Memory note stems, for wikilinks from synthesis notes
+        let mut stems: HashMap<Uuid, String> = HashMap::new();
+        let index = self.vault.index(MEMORIES_FOLDER);
+
+        for memory in &memories {
+            let path = match index.get(&memory.id) {
+                Some(path) => {
+                    let existing = read_note(path)?;
+                    if existing.is_edited() {
+                        stats.local_edits_kept += 1;
+                    } else if write_note(path, &render::memory_note(memory, Some(existing)))? {
+                        stats.notes_updated += 1;
+                    }
+                    path.clone()
+                }
+                None => {
+                    let path = self.vault.new_note_path(
+                        MEMORIES_FOLDER,
+                        &render::memory_stem(memory),
+                        memory.id,
+                    );
+                    write_note(&path, &render::memory_note(memory, None))?;
+                    stats.notes_created += 1;
+                    path
+                }
+            };
+
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                stems.insert(memory.id, stem.to_string());
+            }
+        }
+
+        let syntheses = self.fetch_syntheses().await?;
+        let index = self.vault.index(SYNTHESES_FOLDER);
+
+        for synthesis in &syntheses {
+            let links: Vec<String> = synthesis
+                .memory_ids
+                .iter()
+                .filter_map(|id| stems.get(id).cloned())
+                .collect();
+            let note = render::synthesis_note(synthesis, &links);
+
+            match index.get(&synthesis.id) {
+                Some(path) => {
+                    if write_note(path, &note)? {
+                        stats.notes_updated += 1;
+                    }
+                }
+                None => {
+                    let path = self.vault.new_note_path(
+                        SYNTHESES_FOLDER,
+                        &render::synthesis_stem(synthesis),
+                        synthesis.id,
+                    );
+                    write_note(&path, &note)?;
+                    stats.notes_created += 1;
+                }
+            }
+        }
+
+        if let Some(report) = self.load_report() {
+            let path = self.vault.folder(REPORTS_FOLDER).join(format!("{}.md", REPORT_STEM));
+            let created = !path.exists();
+            if write_note(&path, &render::report_note(&report))? {
+                if created {
+                    stats.notes_created += 1;
+                } else {
+                    stats.notes_updated += 1;
+                }
+            }
+        }
+
+        info!(
+            "Exported {} memories and {} syntheses ({} new notes, {} updated)",
+            memories.len(),
+            syntheses.len(),
+            stats.notes_created,
+            stats.notes_updated
+        );
+        Ok(stats)
+    }
+
+    pub async fn ingest_all(&self) -> Result<SyncStats> {
+        let paths = self.vault.notes_in(MEMORIES_FOLDER);
+        self.ingest_paths(&paths).await
+    }
+
+    /// Ingest new and edited memory notes among `paths`; other paths are ignored
+    pub async fn ingest_paths(&self, paths: &[PathBuf]) -> Result<SyncStats> {
+        let mut stats = SyncStats::default();
+
+        for path in paths {
+            if !self.vault.is_memory_note(path) || !path.is_file() {
+                continue;
+            }
+            if let Err(e) = self.ingest_note(path, &mut stats).await {
+                warn!("Failed to ingest {}: {:#}", path.display(), e);
+            }
+        }
+
+        if stats.memories_created + stats.memories_updated > 0 {
+            info!(
+                "Ingested {} new and {} edited notes",
+                stats.memories_created, stats.memories_updated
+            );
+        }
+        Ok(stats)
+    }
+
+    async fn ingest_note(&self, path: &Path, stats: &mut SyncStats) -> Result<()> {
+        let mut note = read_note(path)?;
+        if note.is_readonly() || (note.id().is_some() && !note.is_edited()) {
+            return Ok(());
+        }
+
+        let content = note.content();
+        if content.is_empty() {
+            return Ok(());
+        }
+
+        match note.id() {
+            Some(id) => {
+                self.upsert_memory(id, &content).await?;
+                stats.memories_updated += 1;
+            }
+            None => {
+                let id = Uuid::new_v4();
+                self.upsert_memory(id, &content).await?;
+                note.set(ID_KEY, id.to_string());
+                note.set(TYPE_KEY, "memory");
+                note.set(CREATED_KEY, Utc::now().to_rfc3339());
+                stats.memories_created += 1;
+            }
+        }
+
+        debug!("Ingested {}", path.display());
+        note.mark_synced();
+        write_note(path, &note)?;
+        Ok(())
+    }
+
+    /// Create the memory, or replace its content if it exists.
+    /// Re-creates memories deleted remotely while their note was being edited.
+    async fn upsert_memory(&self, id: Uuid, content: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO memories (id, user_id, type, content, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET content = EXCLUDED.content
+             WHERE memories.user_id = EXCLUDED.user_id"
+        )
+        .bind(id)
+        .bind(self.user_id)
+        .bind(serde_json::to_string(&MemoryType::Episodic)?)
+        .bind(content)
+        .bind(Utc::now())
+        .execute(self.client.pool())
+        .await
+        .context("Failed to write memory from note")?;
+
+        Ok(())
+    }
+
+    async fn fetch_memories(&self) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, type, content, embedding, emotional_valence, created_at, last_accessed
+             FROM memories
+             WHERE user_id = $1
+             ORDER BY created_at DESC
+             LIMIT $2"
+        )
+        .bind(self.user_id)
+        .bind(self.limit)
+        .fetch_all(self.client.pool())
+        .await
+        .context("Failed to fetch memories from Supabase")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Memory {
+                id: row.get("id"),
+                user_id: row.get("user_id"),
+                memory_type: serde_json::from_str(&row.get::<String, _>("type"))
+                    .unwrap_or(MemoryType::Episodic),
+                content: row.get("content"),
+                embedding: row.try_get("embedding").ok(),
+                emotional_valence: row.try_get("emotional_valence").ok(),
+                created_at: row.get("created_at"),
+                last_accessed: row.try_get("last_accessed").ok(),
+            })
+            .collect())
+    }
+
+    async fn fetch_syntheses(&self) -> Result<Vec<MemorySynthesis>> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, pattern_type, memory_ids, synthesis_content, confidence_score, created_at
+             FROM memory_synthesis
+             WHERE user_id = $1
+             ORDER BY created_at DESC
+             LIMIT $2"
+        )
+        .bind(self.user_id)
+        .bind(self.limit)
+        .fetch_all(self.client.pool())
+        .await
+        .context("Failed to fetch syntheses from Supabase")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| MemorySynthesis {
+                id: row.get("id"),
+                user_id: row.get("user_id"),
+                pattern_type: row.get("pattern_type"),
+                memory_ids: row.get("memory_ids"),
+                synthesis_content: row.get("synthesis_content"),
+                confidence_score: row.get("confidence_score"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    fn load_report(&self) -> Option<serde_json::Value> {
+        let path = self
+            .helix_dir
+            .as_ref()?
+            .join("transformation")
+            .join("synthesis_report.json");
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
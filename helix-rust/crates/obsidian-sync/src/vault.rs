@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::note::Note;
+
+/// Folder inside the vault that Helix manages
+pub const ROOT_FOLDER: &str = "Helix";
+pub const MEMORIES_FOLDER: &str = "Memories";
+pub const SYNTHESES_FOLDER: &str = "Syntheses";
+pub const REPORTS_FOLDER: &str = "Reports";
+
+/// An Obsidian vault (or any folder of Markdown notes)
+pub struct Vault {
+    root: PathBuf,
+}
+
+impl Vault {
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            anyhow::bail!("Vault folder not found: {}", path.display());
+        }
+
+        let vault = Self {
+            root: path.to_path_buf(),
+        };
+        for folder in [MEMORIES_FOLDER, SYNTHESES_FOLDER, REPORTS_FOLDER] {
+            fs::create_dir_all(vault.folder(folder))
+                .with_context(|| format!("Failed to create {} folder", folder))?;
+        }
+        Ok(vault)
+    }
+
+    pub fn folder(&self, name: &str) -> PathBuf {
+        self.root.join(ROOT_FOLDER).join(name)
+    }
+
+    /// Whether `path` is a memory note (new notes may be added anywhere in the folder)
+    pub fn is_memory_note(&self, path: &Path) -> bool {
+        path.starts_with(self.folder(MEMORIES_FOLDER))
+            && path.extension().and_then(|e| e.to_str()) == Some("md")
+    }
+
+    /// All Markdown notes under a Helix folder, recursively
+    pub fn notes_in(&self, folder: &str) -> Vec<PathBuf> {
+        fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path, out);
+                } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
+                    out.push(path);
+                }
+            }
+        }
+
+        let mut notes = Vec::new();
+        walk(&self.folder(folder), &mut notes);
+        notes
+    }
+
+    /// Map of helix_id to note path for a folder
+    pub fn index(&self, folder: &str) -> HashMap<Uuid, PathBuf> {
+        self.notes_in(folder)
+            .into_iter()
+            .filter_map(|path| {
+                let id = read_note(&path).ok()?.id()?;
+                Some((id, path))
+            })
+            .collect()
+    }
+
+    /// A free path for a new note named `stem` in `folder`
+    pub fn new_note_path(&self, folder: &str, stem: &str, id: Uuid) -> PathBuf {
+        let dir = self.folder(folder);
+        let path = dir.join(format!("{}.md", stem));
+        if !path.exists() {
+            return path;
+        }
+        // Same title twice: disambiguate with the id prefix
+        let short_id = id.simple().to_string();
+        dir.join(format!("{} {}.md", stem, &short_id[..8]))
+    }
+}
+
+pub fn read_note(path: &Path) -> Result<Note> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(Note::parse(&text))
+}
+
+/// Write a note, skipping the write when nothing changed so the watcher
+/// isn't woken by no-op syncs
+pub fn write_note(path: &Path, note: &Note) -> Result<bool> {
+    let rendered = note.render();
+    if fs::read_to_string(path).ok().as_deref() == Some(rendered.as_str()) {
+        return Ok(false);
+    }
+
+    let tmp = path.with_extension("md.tmp");
+    fs::write(&tmp, &rendered).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(true)
+}
@@ -43,6 +43,27 @@ impl DecayModel for ExponentialDecay {
     }
 }
 
+impl ExponentialDecay {
+    /// The half-life that leaves `rate` of a value after a day
+    pub fn from_daily_retention(rate: f32) -> Self {
+        Self { half_life_hours: 24.0 * 0.5f32.ln() / rate.ln() }
+    }
+}
+
+/// How fast trust unreinforced for a day fades, by attachment stage: the
+/// more secure the stage, the more of it a day keeps
+pub fn get_model_for_attachment_stage(stage: &str) -> Box<dyn DecayModel> {
+    let daily_retention = match stage {
+        "pre_attachment" => 0.80,
+        "early_trust" => 0.85,
+        "attachment_forming" => 0.88,
+        "deep_secure" => 0.95,
+        "primary_attachment" => 0.98,
+        _ => 0.92, // secure_attachment, and the default
+    };
+    Box::new(ExponentialDecay::from_daily_retention(daily_retention))
+}
+
 pub fn get_model_for_layer(layer_number: i32) -> Box<dyn DecayModel> {
     match layer_number {
         1 => Box::new(ExponentialDecay { half_life_hours: 720.0 }), // 30 days for Narrative Core
@@ -109,6 +130,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_daily_retention() {
+        let model = ExponentialDecay::from_daily_retention(0.95);
+        let retention = model.calculate_retention(Duration::days(1), 0.8);
+        assert!((retention - 0.76).abs() < 0.001);
+
+        let kept = ExponentialDecay::from_daily_retention(1.0);
+        assert!((kept.calculate_retention(Duration::days(30), 0.8) - 0.8).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_attachment_stage_models() {
+        let day = |stage| get_model_for_attachment_stage(stage).calculate_retention(Duration::days(1), 1.0);
+        assert!((day("pre_attachment") - 0.80).abs() < 0.001);
+        assert!((day("primary_attachment") - 0.98).abs() < 0.001);
+        assert!((day("unknown_stage") - day("secure_attachment")).abs() < 0.001);
+        assert!(day("pre_attachment") < day("deep_secure"));
+    }
+
     #[test]
     fn test_default_model() {
         let model = get_model_for_layer(999); // Unknown layer
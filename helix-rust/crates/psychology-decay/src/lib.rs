@@ -0,0 +1,5 @@
+pub mod decay_models;
+
+pub use decay_models::{
+    get_model_for_attachment_stage, get_model_for_layer, DecayModel, EbbinghausCurve, ExponentialDecay, PowerLawDecay,
+};
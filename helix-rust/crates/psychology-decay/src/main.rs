@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use helix_shared::SupabaseClient;
+use helix_shared::schema::{self, SchemaSpec};
+use helix_shared::{PidFile, SupabaseClient};
 use sqlx::Row;
 use tokio_cron_scheduler::{JobScheduler, Job};
 use tracing::{info, error};
@@ -8,9 +9,13 @@ use tracing_subscriber;
 use chrono::Utc;
 use uuid::Uuid;
 
-mod decay_models;
+use psychology_decay::get_model_for_layer;
 
-use decay_models::get_model_for_layer;
+/// Tables and columns calculate_all_decay uses
+const SCHEMA: SchemaSpec<'static> = SchemaSpec {
+    tables: &[schema::PSYCHOLOGY_LAYERS],
+    extensions: &[],
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,6 +27,11 @@ struct Args {
     /// Cron schedule (default: hourly)
     #[arg(long, default_value = "0 0 * * * *")]
     schedule: String,
+
+    /// Print the schema compatibility matrix as JSON and exit, with status 1
+    /// when the schema is incompatible
+    #[arg(long)]
+    check_schema: bool,
 }
 
 #[tokio::main]
@@ -30,6 +40,20 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    if args.check_schema {
+        let client = SupabaseClient::new().await?;
+        let compatibility = client.check_schema(&SCHEMA).await?;
+        println!("{}", serde_json::to_string_pretty(&compatibility)?);
+        std::process::exit(if compatibility.compatible { 0 } else { 1 });
+    }
+
+    // One copy at a time, whether the desktop scheduler or a shell started it
+    let _pidfile = PidFile::lock("psychology-decay")?;
+
+    // Decay rewrites every layer; refuse to start against a schema it
+    // would fail on halfway through
+    SupabaseClient::new().await?.require_schema(&SCHEMA).await?;
+
     if args.once {
         info!("Running decay calculation once");
         let client = SupabaseClient::new().await?;
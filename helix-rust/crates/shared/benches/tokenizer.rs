@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use helix_shared::tokenizer::{Encoding, Tokenizer, CL100K_BASE};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SAMPLE: &str = "I walked along the river this morning and thought about the conversation \
+with my sister. We haven't talked like that in years; it's strange how 15 minutes can change \
+the tone of a whole week. Goals for Q3: finish the draft (chapters 4-7), sleep before 23:30, \
+and call Dad on Sundays.\n\n";
+
+/// Every byte plus every prefix of the sample's words, with and without a
+/// leading space, so merges run several levels deep
+fn sample_encoding() -> Encoding {
+    let mut ranks: HashMap<Vec<u8>, u32> = (0..=u8::MAX).map(|b| (vec![b], b as u32)).collect();
+    for word in SAMPLE.split_whitespace() {
+        for spaced in [word.to_string(), format!(" {}", word)] {
+            for end in 2..=spaced.len() {
+                if spaced.is_char_boundary(end) {
+                    let next = ranks.len() as u32;
+                    ranks.entry(spaced.as_bytes()[..end].to_vec()).or_insert(next);
+                }
+            }
+        }
+    }
+    Encoding::new("sample", ranks).expect("sample vocabulary covers every byte")
+}
+
+fn bench_count(c: &mut Criterion) {
+    let text = SAMPLE.repeat(40);
+    let sample = Tokenizer::Bpe(Arc::new(sample_encoding()));
+
+    c.bench_function("count/sample_bpe", |b| b.iter(|| sample.count(black_box(&text))));
+    c.bench_function("count/estimate", |b| b.iter(|| Tokenizer::Estimate.count(black_box(&text))));
+    c.bench_function("truncate/sample_bpe", |b| b.iter(|| sample.truncate(black_box(&text), 500).len()));
+
+    // The real vocabulary, when installed in the tokenizer directory
+    let cl100k = Tokenizer::for_encoding(CL100K_BASE);
+    if cl100k.is_exact() {
+        c.bench_function("count/cl100k_base", |b| b.iter(|| cl100k.count(black_box(&text))));
+    }
+}
+
+criterion_group!(benches, bench_count);
+criterion_main!(benches);
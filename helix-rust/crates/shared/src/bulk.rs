@@ -0,0 +1,285 @@
+use anyhow::{Context, Result};
+use sqlx::{PgConnection, PgPool, Postgres, Row, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::types::{Memory, MemorySynthesis};
+
+/// Overrides DEFAULT_BATCH_SIZE
+pub const BATCH_SIZE_ENV: &str = "HELIX_BULK_BATCH_SIZE";
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// When bulk-inserted rows are committed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Commit {
+    /// One transaction for the whole call: every row is written or none is
+    All,
+    /// Each batch commits on its own, so a failure keeps earlier batches
+    PerBatch,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BulkOptions {
+    /// Rows per INSERT statement
+    pub batch_size: usize,
+    pub commit: Commit,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            commit: Commit::All,
+        }
+    }
+}
+
+impl BulkOptions {
+    /// Defaults, with the batch size from HELIX_BULK_BATCH_SIZE if set
+    pub fn from_env() -> Self {
+        let batch_size = std::env::var(BATCH_SIZE_ENV)
+            .ok()
+            .and_then(|size| size.trim().parse().ok())
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+        Self::default().batch_size(batch_size)
+    }
+
+    /// Rows per statement; 0 is treated as 1
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn commit(mut self, commit: Commit) -> Self {
+        self.commit = commit;
+        self
+    }
+}
+
+/// pgvector's text form, "[0.1,0.2]"
+fn vector_literal(embedding: &[f32]) -> String {
+    let values: Vec<String> = embedding.iter().map(f32::to_string).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Postgres array literal for a uuid[] column, "{a,b}"
+fn uuid_array_literal(ids: &[Uuid]) -> String {
+    let ids: Vec<String> = ids.iter().map(Uuid::to_string).collect();
+    format!("{{{}}}", ids.join(","))
+}
+
+/// First memory per (user, content), as the per-row insert would have kept
+fn distinct_by_content(memories: &[Memory]) -> Vec<&Memory> {
+    let mut seen = HashSet::new();
+    memories
+        .iter()
+        .filter(|memory| seen.insert((memory.user_id, memory.content.as_str())))
+        .collect()
+}
+
+/// A bulk insert's transaction, committed after each batch or at the end
+struct BulkTx<'p> {
+    pool: &'p PgPool,
+    tx: Transaction<'static, Postgres>,
+    commit: Commit,
+}
+
+impl<'p> BulkTx<'p> {
+    async fn begin(pool: &'p PgPool, commit: Commit) -> Result<Self> {
+        let tx = pool.begin().await.context("Failed to start bulk insert")?;
+        Ok(Self { pool, tx, commit })
+    }
+
+    fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    /// Per batch, commit what's written so far before opening the next
+    /// transaction, so the two never hold connections at once
+    async fn batch_done(self) -> Result<Self> {
+        match self.commit {
+            Commit::All => Ok(self),
+            Commit::PerBatch => {
+                self.tx
+                    .commit()
+                    .await
+                    .context("Failed to commit bulk insert batch")?;
+                Self::begin(self.pool, self.commit).await
+            }
+        }
+    }
+
+    async fn finish(self) -> Result<()> {
+        self.tx.commit().await.context("Failed to commit bulk insert")
+    }
+}
+
+async fn insert_memory_batch(conn: &mut PgConnection, batch: &[&Memory]) -> Result<Vec<Uuid>> {
+    let mut ids = Vec::with_capacity(batch.len());
+    let mut user_ids = Vec::with_capacity(batch.len());
+    let mut types = Vec::with_capacity(batch.len());
+    let mut contents = Vec::with_capacity(batch.len());
+    let mut embeddings = Vec::with_capacity(batch.len());
+    let mut valences = Vec::with_capacity(batch.len());
+    let mut created = Vec::with_capacity(batch.len());
+    for memory in batch {
+        ids.push(memory.id);
+        user_ids.push(memory.user_id);
+        types.push(serde_json::to_string(&memory.memory_type)?);
+        contents.push(memory.content.as_str());
+        embeddings.push(memory.embedding.as_deref().map(vector_literal));
+        valences.push(memory.emotional_valence);
+        created.push(memory.created_at);
+    }
+
+    let rows = sqlx::query(
+        "INSERT INTO memories (id, user_id, type, content, embedding, emotional_valence, created_at)
+         SELECT r.id, r.user_id, r.type, r.content, r.embedding::vector, r.emotional_valence, r.created_at
+         FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::text[], $5::text[], $6::real[], $7::timestamptz[])
+              AS r(id, user_id, type, content, embedding, emotional_valence, created_at)
+         WHERE NOT EXISTS (
+             SELECT 1 FROM memories m WHERE m.user_id = r.user_id AND m.content = r.content
+         )
+         RETURNING id",
+    )
+    .bind(&ids)
+    .bind(&user_ids)
+    .bind(&types)
+    .bind(&contents)
+    .bind(&embeddings)
+    .bind(&valences)
+    .bind(&created)
+    .fetch_all(conn)
+    .await
+    .context("Failed to bulk insert memories")?;
+
+    rows.iter().map(|row| row.try_get("id").map_err(Into::into)).collect()
+}
+
+/// Insert memories in batches, skipping any whose user already has a
+/// memory with the same content. Returns the ids of the rows written.
+pub async fn insert_memories(pool: &PgPool, memories: &[Memory], options: BulkOptions) -> Result<Vec<Uuid>> {
+    let memories = distinct_by_content(memories);
+    if memories.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tx = BulkTx::begin(pool, options.commit).await?;
+    let mut written = Vec::new();
+    for batch in memories.chunks(options.batch_size.max(1)) {
+        written.extend(insert_memory_batch(tx.conn(), batch).await?);
+        tx = tx.batch_done().await?;
+    }
+    tx.finish().await?;
+    Ok(written)
+}
+
+async fn insert_synthesis_batch(conn: &mut PgConnection, batch: &[MemorySynthesis]) -> Result<Vec<Uuid>> {
+    let ids: Vec<Uuid> = batch.iter().map(|row| row.id).collect();
+    let user_ids: Vec<Uuid> = batch.iter().map(|row| row.user_id).collect();
+    let pattern_types: Vec<&str> = batch.iter().map(|row| row.pattern_type.as_str()).collect();
+    // uuid[] per row can't go through UNNEST as a 2-D array, so each one
+    // travels as its array literal
+    let memory_ids: Vec<String> = batch.iter().map(|row| uuid_array_literal(&row.memory_ids)).collect();
+    let contents: Vec<&str> = batch.iter().map(|row| row.synthesis_content.as_str()).collect();
+    let confidences: Vec<f32> = batch.iter().map(|row| row.confidence_score).collect();
+    let created: Vec<_> = batch.iter().map(|row| row.created_at).collect();
+
+    sqlx::query(
+        "INSERT INTO memory_synthesis (id, user_id, pattern_type, memory_ids, synthesis_content, confidence_score, created_at)
+         SELECT r.id, r.user_id, r.pattern_type, r.memory_ids::uuid[], r.synthesis_content, r.confidence_score, r.created_at
+         FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::text[], $5::text[], $6::real[], $7::timestamptz[])
+              AS r(id, user_id, pattern_type, memory_ids, synthesis_content, confidence_score, created_at)",
+    )
+    .bind(&ids)
+    .bind(&user_ids)
+    .bind(&pattern_types)
+    .bind(&memory_ids)
+    .bind(&contents)
+    .bind(&confidences)
+    .bind(&created)
+    .execute(conn)
+    .await
+    .context("Failed to bulk insert syntheses")?;
+
+    Ok(ids)
+}
+
+/// Insert synthesis rows in batches on a transaction the caller commits,
+/// such as one from transaction::serializable. Returns the ids written.
+pub async fn insert_syntheses_in(conn: &mut PgConnection, rows: &[MemorySynthesis], batch_size: usize) -> Result<Vec<Uuid>> {
+    let mut written = Vec::with_capacity(rows.len());
+    for batch in rows.chunks(batch_size.max(1)) {
+        written.extend(insert_synthesis_batch(conn, batch).await?);
+    }
+    Ok(written)
+}
+
+/// Insert synthesis rows in batches. Returns the ids written.
+pub async fn insert_syntheses(pool: &PgPool, rows: &[MemorySynthesis], options: BulkOptions) -> Result<Vec<Uuid>> {
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tx = BulkTx::begin(pool, options.commit).await?;
+    let mut written = Vec::new();
+    for batch in rows.chunks(options.batch_size.max(1)) {
+        written.extend(insert_synthesis_batch(tx.conn(), batch).await?);
+        tx = tx.batch_done().await?;
+    }
+    tx.finish().await?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+    use chrono::Utc;
+
+    fn memory(user_id: Uuid, content: &str) -> Memory {
+        Memory {
+            id: Uuid::new_v4(),
+            user_id,
+            memory_type: MemoryType::Episodic,
+            content: content.to_string(),
+            embedding: None,
+            emotional_valence: None,
+            created_at: Utc::now(),
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn literals_match_postgres_text_forms() {
+        assert_eq!(vector_literal(&[0.5, -1.0, 0.25]), "[0.5,-1,0.25]");
+        assert_eq!(vector_literal(&[]), "[]");
+
+        let id = Uuid::nil();
+        assert_eq!(
+            uuid_array_literal(&[id, id]),
+            "{00000000-0000-0000-0000-000000000000,00000000-0000-0000-0000-000000000000}"
+        );
+        assert_eq!(uuid_array_literal(&[]), "{}");
+    }
+
+    #[test]
+    fn duplicate_content_is_dropped_per_user() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let memories = vec![
+            memory(alice, "same"),
+            memory(alice, "same"),
+            memory(bob, "same"),
+            memory(alice, "other"),
+        ];
+
+        let distinct = distinct_by_content(&memories);
+        assert_eq!(distinct.len(), 3);
+        assert_eq!(distinct[0].id, memories[0].id);
+    }
+
+    #[test]
+    fn batch_size_is_at_least_one() {
+        let options = BulkOptions::default().batch_size(0).commit(Commit::PerBatch);
+        assert_eq!(options.batch_size, 1);
+        assert_eq!(options.commit, Commit::PerBatch);
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::{anyhow, Result};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use uuid::Uuid;
+
+/// Overrides DEFAULT_TTL, in seconds
+pub const TTL_ENV: &str = "HELIX_CACHE_TTL_SECS";
+/// Postgres channel the cache invalidation triggers notify on; the payload
+/// is a Realtime-style `{"table": ..., "record": {...}}`
+pub const INVALIDATE_CHANNEL: &str = "cache_invalidate";
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const MAX_ENTRIES: u64 = 10_000;
+/// What is cached per skill
+const SKILL_KINDS: &[CacheKind] = &[CacheKind::SkillManifest, CacheKind::CompiledSkill];
+
+/// What a cached lookup is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheKind {
+    SubscriptionTier,
+    UserInstances,
+    SkillManifest,
+    /// A service's compiled form of a skill, such as skill-sandbox's module
+    CompiledSkill,
+}
+
+impl CacheKind {
+    const ALL: [CacheKind; 4] = [
+        Self::SubscriptionTier,
+        Self::UserInstances,
+        Self::SkillManifest,
+        Self::CompiledSkill,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub kind: CacheKind,
+    /// User id, or skill id for SkillManifest and CompiledSkill
+    pub id: Uuid,
+}
+
+/// A device row from user_instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInstance {
+    pub instance_id: String,
+    pub device_name: Option<String>,
+    pub device_type: String,
+    pub platform: Option<String>,
+    pub is_online: bool,
+}
+
+/// A cached lookup result
+#[derive(Debug, Clone)]
+pub enum Cached {
+    Tier(String),
+    Instances(Arc<Vec<UserInstance>>),
+    /// None when the skill has no manifest or doesn't exist
+    Manifest(Option<Arc<Value>>),
+    /// Whatever the service compiled the skill into
+    Compiled(Arc<dyn Any + Send + Sync>),
+}
+
+/// Hits and misses of one kind
+#[derive(Debug, Clone, Serialize)]
+pub struct KindStats {
+    pub kind: CacheKind,
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub entries: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits over lookups, 0 before the first lookup
+    pub hit_rate: f64,
+    pub kinds: Vec<KindStats>,
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// In-process TTL cache for hot read paths of [`SupabaseClient`](crate::SupabaseClient).
+/// Entries expire after the TTL; writes elsewhere reach it through
+/// [`ReadCache::on_change`], which
+/// [`SupabaseClient::spawn_cache_listener`](crate::SupabaseClient::spawn_cache_listener)
+/// feeds with the database's change notifications.
+pub struct ReadCache {
+    entries: Cache<CacheKey, Cached>,
+    counters: [Counters; 4],
+    /// Bumped by every invalidation, so a fetch that started before one
+    /// knows its row may be stale
+    generation: AtomicU64,
+}
+
+impl ReadCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Cache::builder().max_capacity(MAX_ENTRIES).time_to_live(ttl).build(),
+            counters: Default::default(),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// TTL from HELIX_CACHE_TTL_SECS, else DEFAULT_TTL; 0 turns caching off
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(TTL_ENV)
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .map_or(DEFAULT_TTL, Duration::from_secs);
+        Self::new(ttl)
+    }
+
+    fn counters(&self, kind: CacheKind) -> &Counters {
+        &self.counters[kind.index()]
+    }
+
+    /// The cached value for `key`, or what `fetch` returns, which is cached.
+    /// Concurrent misses on one key share a single fetch.
+    pub async fn get_or_fetch<F>(&self, key: CacheKey, fetch: F) -> Result<Cached>
+    where
+        F: Future<Output = Result<Cached>>,
+    {
+        let counters = self.counters(key.kind);
+        if let Some(value) = self.entries.get(&key).await {
+            counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        counters.misses.fetch_add(1, Ordering::Relaxed);
+
+        let generation = self.generation.load(Ordering::SeqCst);
+        let value = self
+            .entries
+            .try_get_with(key, fetch)
+            .await
+            .map_err(|e: Arc<anyhow::Error>| anyhow!("{:#}", e))?;
+        // An invalidation that landed while this was being fetched may be
+        // for a newer version of it: use the value this once, don't keep it
+        if self.generation.load(Ordering::SeqCst) != generation {
+            debug!("Cached {:?} for {} changed while loading, not keeping it", key.kind, key.id);
+            self.entries.remove(&key).await;
+        }
+        Ok(value)
+    }
+
+    pub async fn invalidate(&self, key: CacheKey) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if self.entries.remove(&key).await.is_some() {
+            self.counters(key.kind).invalidations.fetch_add(1, Ordering::Relaxed);
+            debug!("Invalidated cached {:?} for {}", key.kind, key.id);
+        }
+    }
+
+    /// Drop everything cached for a skill, e.g. right after writing it
+    pub async fn invalidate_skill(&self, skill_id: Uuid) {
+        for &kind in SKILL_KINDS {
+            self.invalidate(CacheKey { kind, id: skill_id }).await;
+        }
+    }
+
+    /// Drop every entry, e.g. when invalidations may have been missed
+    pub fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.entries.invalidate_all();
+    }
+
+    /// Invalidation hook for a change to `table`, given the changed row (or
+    /// the old one for a delete) as Supabase Realtime delivers it. Returns
+    /// whether the table backs a cached lookup.
+    pub async fn on_change(&self, table: &str, record: &Value) -> bool {
+        let field = |name: &str| record.get(name).and_then(Value::as_str).and_then(|id| Uuid::parse_str(id).ok());
+        let (kinds, id): (&[CacheKind], _) = match table {
+            "subscriptions" => (&[CacheKind::SubscriptionTier], field("user_id")),
+            "user_instances" => (&[CacheKind::UserInstances], field("user_id")),
+            "skills" => (SKILL_KINDS, field("id")),
+            _ => return false,
+        };
+        match id {
+            Some(id) => {
+                for &kind in kinds {
+                    self.invalidate(CacheKey { kind, id }).await;
+                }
+            }
+            // A row without the id it is cached by; don't guess which entry
+            None => self.clear(),
+        }
+        true
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let kinds: Vec<KindStats> = CacheKind::ALL
+            .iter()
+            .map(|kind| {
+                let counters = self.counters(*kind);
+                KindStats {
+                    kind: *kind,
+                    hits: counters.hits.load(Ordering::Relaxed),
+                    misses: counters.misses.load(Ordering::Relaxed),
+                    invalidations: counters.invalidations.load(Ordering::Relaxed),
+                }
+            })
+            .collect();
+        let hits: u64 = kinds.iter().map(|kind| kind.hits).sum();
+        let misses: u64 = kinds.iter().map(|kind| kind.misses).sum();
+        let lookups = hits + misses;
+        CacheStats {
+            entries: self.entries.entry_count(),
+            hits,
+            misses,
+            hit_rate: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+            kinds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier_key(id: Uuid) -> CacheKey {
+        CacheKey {
+            kind: CacheKind::SubscriptionTier,
+            id,
+        }
+    }
+
+    async fn tier(cache: &ReadCache, key: CacheKey, fetched: &str) -> String {
+        let fetched = fetched.to_string();
+        match cache.get_or_fetch(key, async move { Ok(Cached::Tier(fetched)) }).await.unwrap() {
+            Cached::Tier(tier) => tier,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn second_lookup_is_a_hit() {
+        let cache = ReadCache::new(Duration::from_secs(60));
+        let key = tier_key(Uuid::new_v4());
+
+        assert_eq!(tier(&cache, key, "pro").await, "pro");
+        assert_eq!(tier(&cache, key, "free").await, "pro");
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.hit_rate, 0.5);
+    }
+
+    #[tokio::test]
+    async fn realtime_change_invalidates_the_user() {
+        let cache = ReadCache::new(Duration::from_secs(60));
+        let user = Uuid::new_v4();
+        let key = tier_key(user);
+        tier(&cache, key, "free").await;
+
+        let record = serde_json::json!({ "user_id": user.to_string(), "tier": "pro" });
+        assert!(cache.on_change("subscriptions", &record).await);
+        assert_eq!(tier(&cache, key, "pro").await, "pro");
+        assert_eq!(cache.stats().kinds[0].invalidations, 1);
+
+        assert!(!cache.on_change("memories", &record).await);
+    }
+
+    #[tokio::test]
+    async fn skill_change_drops_the_compiled_skill() {
+        let cache = ReadCache::new(Duration::from_secs(60));
+        let skill = Uuid::new_v4();
+        let key = CacheKey {
+            kind: CacheKind::CompiledSkill,
+            id: skill,
+        };
+        let compiled = Arc::new(7u32) as Arc<dyn Any + Send + Sync>;
+        cache
+            .get_or_fetch(key, async move { Ok(Cached::Compiled(compiled)) })
+            .await
+            .unwrap();
+
+        let record = serde_json::json!({ "id": skill.to_string() });
+        assert!(cache.on_change("skills", &record).await);
+        assert_eq!(cache.stats().kinds[CacheKind::CompiledSkill.index()].invalidations, 1);
+    }
+
+    #[tokio::test]
+    async fn value_invalidated_while_loading_is_not_kept() {
+        let cache = ReadCache::new(Duration::from_secs(60));
+        let key = tier_key(Uuid::new_v4());
+
+        let fetched = cache
+            .get_or_fetch(key, async {
+                cache.invalidate(key).await;
+                Ok(Cached::Tier("free".to_string()))
+            })
+            .await
+            .unwrap();
+        assert!(matches!(fetched, Cached::Tier(tier) if tier == "free"));
+        assert_eq!(tier(&cache, key, "pro").await, "pro");
+    }
+
+    #[tokio::test]
+    async fn failed_fetch_is_not_cached() {
+        let cache = ReadCache::new(Duration::from_secs(60));
+        let key = tier_key(Uuid::new_v4());
+
+        let failed = cache.get_or_fetch(key, async { Err(anyhow!("connection reset")) }).await;
+        assert!(failed.unwrap_err().to_string().contains("connection reset"));
+        assert_eq!(tier(&cache, key, "team").await, "team");
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_fetched_again() {
+        let cache = ReadCache::new(Duration::from_millis(50));
+        let key = tier_key(Uuid::new_v4());
+        tier(&cache, key, "free").await;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(tier(&cache, key, "pro").await, "pro");
+        assert_eq!(cache.stats().misses, 2);
+    }
+}
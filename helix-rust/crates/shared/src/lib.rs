@@ -1,5 +1,29 @@
+#[cfg(feature = "db")]
+pub mod bulk;
+#[cfg(feature = "db")]
+pub mod cache;
+pub mod pidfile;
+#[cfg(feature = "db")]
+pub mod schema;
+#[cfg(feature = "db")]
 pub mod supabase;
+pub mod tokenizer;
+#[cfg(feature = "db")]
+pub mod transaction;
+#[cfg(feature = "db")]
 pub mod types;
 
+#[cfg(feature = "db")]
+pub use bulk::{BulkOptions, Commit};
+#[cfg(feature = "db")]
+pub use cache::{CacheStats, ReadCache};
+pub use pidfile::{AlreadyRunning, PidFile};
+#[cfg(feature = "db")]
+pub use schema::{Compatibility, IncompatibleSchema, SchemaSpec};
+#[cfg(feature = "db")]
 pub use supabase::SupabaseClient;
+pub use tokenizer::{TokenBudget, Tokenizer};
+#[cfg(feature = "db")]
+pub use transaction::RetryPolicy;
+#[cfg(feature = "db")]
 pub use types::*;
@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Directory holding the pid files, overriding ~/.helix/run
+pub const RUN_DIR_ENV: &str = "HELIX_RUN_DIR";
+
+/// Attempts at taking a pid file over from a stale one before giving up
+const ACQUIRE_ATTEMPTS: usize = 3;
+
+/// Where pid files live: $HELIX_RUN_DIR, else ~/.helix/run. The desktop
+/// app records the processes it spawns in the same place, so each side
+/// sees the other's.
+pub fn run_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(RUN_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".helix").join("run"))
+}
+
+/// Contents of `<name>.pid`. The start time tells a live holder from an
+/// unrelated process that got its pid after it exited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PidRecord {
+    pub name: String,
+    pub pid: u32,
+    /// Start time as the OS reports it, compared as is
+    pub started: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// The process that spawned and recorded this one; None when the
+    /// process holds its own lock
+    #[serde(default)]
+    pub parent: Option<u32>,
+    /// Milliseconds since epoch
+    pub recorded_at: u64,
+}
+
+impl PidRecord {
+    /// Whether the recorded process is still the one running under its pid
+    pub fn is_alive(&self) -> bool {
+        start_time(self.pid).is_some_and(|started| started == self.started)
+    }
+}
+
+/// The name is held by a live process
+#[derive(Debug)]
+pub struct AlreadyRunning(pub PidRecord);
+
+impl fmt::Display for AlreadyRunning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is already running (pid {})", self.0.name, self.0.pid)
+    }
+}
+
+impl std::error::Error for AlreadyRunning {}
+
+/// A held pid file, removed on drop if it is still this holder's
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    record: PidRecord,
+}
+
+impl PidFile {
+    /// Hold `name` for the current process, so a second copy started from
+    /// the desktop app or a shell fails with [`AlreadyRunning`]
+    pub fn lock(name: &str) -> Result<Self> {
+        let dir = run_dir().context("Could not determine the pid file directory")?;
+        Self::acquire_in(&dir, name, std::process::id(), None, None)
+    }
+
+    /// Hold `name` for `pid`, a process the current one spawned and
+    /// answers for
+    pub fn spawned(name: &str, pid: u32, port: Option<u16>) -> Result<Self> {
+        let dir = run_dir().context("Could not determine the pid file directory")?;
+        Self::acquire_in(&dir, name, pid, port, Some(std::process::id()))
+    }
+
+    /// Take over the pid file of a live process left by a previous run, so
+    /// dropping it removes the file like any other holder's
+    pub fn adopt(record: PidRecord) -> Result<Self> {
+        let dir = run_dir().context("Could not determine the pid file directory")?;
+        Ok(Self { path: dir.join(format!("{}.pid", record.name)), record })
+    }
+
+    /// Create `<dir>/<name>.pid` for `pid`. A file left by a process that
+    /// is gone is replaced; one of a live process is an [`AlreadyRunning`]
+    /// error.
+    pub fn acquire_in(dir: &Path, name: &str, pid: u32, port: Option<u16>, parent: Option<u32>) -> Result<Self> {
+        let started = start_time(pid).with_context(|| format!("Process {} is not running", pid))?;
+        let record = PidRecord {
+            name: name.to_string(),
+            pid,
+            started,
+            port,
+            parent,
+            recorded_at: chrono::Utc::now().timestamp_millis().max(0) as u64,
+        };
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}.pid", name));
+        let json = serde_json::to_string_pretty(&record)?;
+
+        for _ in 0..ACQUIRE_ATTEMPTS {
+            if create_exclusive(&path, &json)? {
+                return Ok(Self { path, record });
+            }
+            match read_path(&path) {
+                Some(holder) if holder.is_alive() => return Err(AlreadyRunning(holder).into()),
+                Some(holder) => {
+                    debug!("Replacing stale pid file of {} ({})", holder.name, holder.pid);
+                    remove_if_unchanged(&path, &holder);
+                }
+                // Gone in the meantime, or not a pid file at all
+                None => {
+                    if path.exists() {
+                        warn!("Replacing unreadable pid file {}", path.display());
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+        }
+        anyhow::bail!("Could not take over {}", path.display())
+    }
+
+    pub fn record(&self) -> &PidRecord {
+        &self.record
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Only our own: a stale file may have been taken over since
+        remove_if_unchanged(&self.path, &self.record);
+    }
+}
+
+/// Write `content` to `path` unless it exists, in one step: the content is
+/// written beside it first and linked into place, so no reader sees a
+/// half-written file. Returns false if `path` exists.
+fn create_exclusive(path: &Path, content: &str) -> Result<bool> {
+    let staging = path.with_extension(format!("pid.{}.tmp", std::process::id()));
+    fs::write(&staging, content).with_context(|| format!("Failed to write {}", staging.display()))?;
+    let linked = fs::hard_link(&staging, path);
+    let _ = fs::remove_file(&staging);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to create {}", path.display())),
+    }
+}
+
+fn remove_if_unchanged(path: &Path, record: &PidRecord) {
+    if read_path(path).as_ref() == Some(record) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn read_path(path: &Path) -> Option<PidRecord> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// The record of `<dir>/<name>.pid`, live or not
+pub fn read(dir: &Path, name: &str) -> Option<PidRecord> {
+    read_path(&dir.join(format!("{}.pid", name)))
+}
+
+/// The live process holding `name`, if any
+pub fn holder(name: &str) -> Option<PidRecord> {
+    read(&run_dir()?, name).filter(PidRecord::is_alive)
+}
+
+/// Every readable pid file, live or stale
+pub fn records() -> Vec<PidRecord> {
+    let Some(Ok(entries)) = run_dir().map(fs::read_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pid"))
+        .filter_map(|path| read_path(&path))
+        .collect()
+}
+
+/// Remove the pid file of `record`, unless it has been taken over since
+pub fn remove(record: &PidRecord) {
+    if let Some(dir) = run_dir() {
+        remove_if_unchanged(&dir.join(format!("{}.pid", record.name)), record);
+    }
+}
+
+/// Start time of `pid`: field 22 of /proc/<pid>/stat, in clock ticks since
+/// boot. Zombies count as gone.
+#[cfg(target_os = "linux")]
+pub fn start_time(pid: u32) -> Option<String> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name may hold spaces; the fields after it don't
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+    if fields.first() == Some(&"Z") {
+        return None;
+    }
+    fields.get(19).map(|ticks| ticks.to_string())
+}
+
+/// Start time of `pid` as ps prints it
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn start_time(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !started.is_empty()).then_some(started)
+}
+
+/// Creation time of `pid`, in 100ns units since 1601
+#[cfg(windows)]
+pub fn start_time(pid: u32) -> Option<String> {
+    use std::ffi::c_void;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct FileTime {
+        low: u32,
+        high: u32,
+    }
+
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn GetProcessTimes(
+            process: *mut c_void,
+            creation: *mut FileTime,
+            exit: *mut FileTime,
+            kernel: *mut FileTime,
+            user: *mut FileTime,
+        ) -> i32;
+        fn GetExitCodeProcess(process: *mut c_void, code: *mut u32) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    // SAFETY: plain Win32 calls on a handle this function owns
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut code = 0u32;
+        let running = GetExitCodeProcess(process, &mut code) != 0 && code == STILL_ACTIVE;
+        let (mut creation, mut exit, mut kernel, mut user) =
+            (FileTime::default(), FileTime::default(), FileTime::default(), FileTime::default());
+        let ok = GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user) != 0;
+        CloseHandle(process);
+        (running && ok).then(|| ((u64::from(creation.high) << 32) | u64::from(creation.low)).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("helix-pidfile-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_second_acquire_is_refused_while_held() {
+        let dir = scratch_dir("held");
+        let held = PidFile::acquire_in(&dir, "decay", std::process::id(), None, None).unwrap();
+
+        let err = PidFile::acquire_in(&dir, "decay", std::process::id(), None, None).unwrap_err();
+        let running = err.downcast_ref::<AlreadyRunning>().expect("AlreadyRunning");
+        assert_eq!(running.0.pid, std::process::id());
+        assert_eq!(read(&dir, "decay").as_ref(), Some(held.record()));
+    }
+
+    #[test]
+    fn test_drop_removes_the_file() {
+        let dir = scratch_dir("drop");
+        let held = PidFile::acquire_in(&dir, "gateway-default", std::process::id(), Some(18789), Some(1)).unwrap();
+        let path = held.path().to_path_buf();
+        assert!(path.exists());
+        drop(held);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_stale_file_is_replaced() {
+        let dir = scratch_dir("stale");
+        fs::create_dir_all(&dir).unwrap();
+        // Our pid, but a start time it never had: a reused pid
+        let stale = PidRecord {
+            name: "sync-coordinator".to_string(),
+            pid: std::process::id(),
+            started: "never".to_string(),
+            port: None,
+            parent: None,
+            recorded_at: 0,
+        };
+        fs::write(dir.join("sync-coordinator.pid"), serde_json::to_string(&stale).unwrap()).unwrap();
+        assert!(!stale.is_alive());
+
+        let held = PidFile::acquire_in(&dir, "sync-coordinator", std::process::id(), Some(18792), None).unwrap();
+        assert_eq!(held.record().port, Some(18792));
+        assert!(held.record().is_alive());
+    }
+
+    #[test]
+    fn test_drop_leaves_a_file_taken_over_since() {
+        let dir = scratch_dir("takeover");
+        let held = PidFile::acquire_in(&dir, "decay", std::process::id(), None, None).unwrap();
+        let mut other = held.record().clone();
+        other.recorded_at += 1;
+        fs::write(held.path(), serde_json::to_string(&other).unwrap()).unwrap();
+
+        let path = held.path().to_path_buf();
+        drop(held);
+        assert_eq!(read_path(&path), Some(other));
+    }
+}
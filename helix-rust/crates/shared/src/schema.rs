@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::fmt;
+
+/// A table a service reads or writes, with the columns it uses
+#[derive(Debug, Clone, Copy)]
+pub struct TableSpec {
+    pub name: &'static str,
+    pub columns: &'static [&'static str],
+}
+
+/// What a service needs of the database
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaSpec<'a> {
+    pub tables: &'a [TableSpec],
+    pub extensions: &'a [&'a str],
+}
+
+/// pgvector, for memory embeddings
+pub const PGVECTOR: &str = "vector";
+
+pub const MEMORIES: TableSpec = TableSpec {
+    name: "memories",
+    columns: &[
+        "id",
+        "user_id",
+        "type",
+        "content",
+        "embedding",
+        "emotional_valence",
+        "created_at",
+        "last_accessed",
+    ],
+};
+
+pub const MEMORY_SYNTHESIS: TableSpec = TableSpec {
+    name: "memory_synthesis",
+    columns: &[
+        "id",
+        "user_id",
+        "pattern_type",
+        "memory_ids",
+        "synthesis_content",
+        "confidence_score",
+        "created_at",
+    ],
+};
+
+pub const PSYCHOLOGY_LAYERS: TableSpec = TableSpec {
+    name: "psychology_layers",
+    columns: &["id", "user_id", "layer_number", "layer_name", "data", "decay_rate", "last_updated"],
+};
+
+/// Tables are looked up in this schema
+const SCHEMA: &str = "public";
+
+/// One required column
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnCheck {
+    pub column: String,
+    pub present: bool,
+    /// As information_schema reports it, when present
+    pub data_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCheck {
+    pub table: String,
+    pub present: bool,
+    pub columns: Vec<ColumnCheck>,
+}
+
+impl TableCheck {
+    pub fn is_compatible(&self) -> bool {
+        self.present && self.columns.iter().all(|column| column.present)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionCheck {
+    pub extension: String,
+    pub installed: bool,
+    pub version: Option<String>,
+}
+
+/// Which of a [`SchemaSpec`]'s requirements the database meets
+#[derive(Debug, Clone, Serialize)]
+pub struct Compatibility {
+    pub compatible: bool,
+    pub tables: Vec<TableCheck>,
+    pub extensions: Vec<ExtensionCheck>,
+}
+
+impl Compatibility {
+    /// Build the matrix from what introspection found: present tables,
+    /// (table, column, data type) triples and (extension, version) pairs
+    pub fn evaluate(
+        spec: &SchemaSpec<'_>,
+        tables: &[String],
+        columns: &[(String, String, String)],
+        extensions: &[(String, String)],
+    ) -> Self {
+        let tables: Vec<TableCheck> = spec
+            .tables
+            .iter()
+            .map(|table| TableCheck {
+                table: table.name.to_string(),
+                present: tables.iter().any(|name| name == table.name),
+                columns: table
+                    .columns
+                    .iter()
+                    .map(|column| {
+                        let data_type = columns
+                            .iter()
+                            .find(|(t, c, _)| t == table.name && c == column)
+                            .map(|(_, _, data_type)| data_type.clone());
+                        ColumnCheck {
+                            column: column.to_string(),
+                            present: data_type.is_some(),
+                            data_type,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        let extensions: Vec<ExtensionCheck> = spec
+            .extensions
+            .iter()
+            .map(|extension| {
+                let version = extensions
+                    .iter()
+                    .find(|(name, _)| name == extension)
+                    .map(|(_, version)| version.clone());
+                ExtensionCheck {
+                    extension: extension.to_string(),
+                    installed: version.is_some(),
+                    version,
+                }
+            })
+            .collect();
+        let compatible = tables.iter().all(TableCheck::is_compatible) && extensions.iter().all(|e| e.installed);
+        Self {
+            compatible,
+            tables,
+            extensions,
+        }
+    }
+
+    /// What is missing, one entry each: "table memories",
+    /// "column memories.embedding", "extension vector"
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for table in &self.tables {
+            if !table.present {
+                problems.push(format!("table {}", table.table));
+                continue;
+            }
+            for column in table.columns.iter().filter(|column| !column.present) {
+                problems.push(format!("column {}.{}", table.table, column.column));
+            }
+        }
+        for extension in self.extensions.iter().filter(|extension| !extension.installed) {
+            problems.push(format!("extension {}", extension.extension));
+        }
+        problems
+    }
+
+    /// Err with [`IncompatibleSchema`] unless every requirement is met
+    pub fn ensure(self) -> Result<Self> {
+        if self.compatible {
+            Ok(self)
+        } else {
+            Err(IncompatibleSchema(self).into())
+        }
+    }
+}
+
+/// The database lacks tables, columns or extensions a service needs
+#[derive(Debug)]
+pub struct IncompatibleSchema(pub Compatibility);
+
+impl fmt::Display for IncompatibleSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Supabase schema is incompatible, missing: {}", self.0.problems().join(", "))
+    }
+}
+
+impl std::error::Error for IncompatibleSchema {}
+
+/// Introspect the database for what `spec` requires
+pub async fn check(pool: &PgPool, spec: &SchemaSpec<'_>) -> Result<Compatibility> {
+    let names: Vec<String> = spec.tables.iter().map(|table| table.name.to_string()).collect();
+    let extension_names: Vec<String> = spec.extensions.iter().map(|extension| extension.to_string()).collect();
+
+    let tables: Vec<String> = sqlx::query(
+        "SELECT table_name::text AS table_name FROM information_schema.tables
+         WHERE table_schema = $1 AND table_name = ANY($2)",
+    )
+    .bind(SCHEMA)
+    .bind(&names)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list tables")?
+    .iter()
+    .map(|row| row.try_get("table_name"))
+    .collect::<Result<_, _>>()?;
+
+    let columns: Vec<(String, String, String)> = sqlx::query(
+        "SELECT table_name::text AS table_name, column_name::text AS column_name,
+                data_type::text AS data_type
+         FROM information_schema.columns
+         WHERE table_schema = $1 AND table_name = ANY($2)",
+    )
+    .bind(SCHEMA)
+    .bind(&names)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list columns")?
+    .iter()
+    .map(|row| Ok((row.try_get("table_name")?, row.try_get("column_name")?, row.try_get("data_type")?)))
+    .collect::<Result<_, sqlx::Error>>()?;
+
+    let extensions: Vec<(String, String)> = sqlx::query(
+        "SELECT extname::text AS extname, extversion::text AS extversion FROM pg_extension
+         WHERE extname = ANY($1)",
+    )
+    .bind(&extension_names)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list extensions")?
+    .iter()
+    .map(|row| Ok((row.try_get("extname")?, row.try_get("extversion")?)))
+    .collect::<Result<_, sqlx::Error>>()?;
+
+    Ok(Compatibility::evaluate(spec, &tables, &columns, &extensions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: SchemaSpec<'static> = SchemaSpec {
+        tables: &[MEMORIES, PSYCHOLOGY_LAYERS],
+        extensions: &[PGVECTOR],
+    };
+
+    fn columns_of(table: &TableSpec) -> Vec<(String, String, String)> {
+        table
+            .columns
+            .iter()
+            .map(|column| (table.name.to_string(), column.to_string(), "text".to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn complete_schema_is_compatible() {
+        let tables = vec!["memories".to_string(), "psychology_layers".to_string()];
+        let mut columns = columns_of(&MEMORIES);
+        columns.extend(columns_of(&PSYCHOLOGY_LAYERS));
+        let extensions = vec![("vector".to_string(), "0.7.0".to_string())];
+
+        let compatibility = Compatibility::evaluate(&SPEC, &tables, &columns, &extensions);
+        assert!(compatibility.compatible);
+        assert!(compatibility.problems().is_empty());
+        assert_eq!(compatibility.extensions[0].version.as_deref(), Some("0.7.0"));
+        assert!(compatibility.ensure().is_ok());
+    }
+
+    #[test]
+    fn missing_pieces_are_listed() {
+        let tables = vec!["memories".to_string()];
+        let columns: Vec<_> = columns_of(&MEMORIES)
+            .into_iter()
+            .filter(|(_, column, _)| column != "embedding")
+            .collect();
+
+        let compatibility = Compatibility::evaluate(&SPEC, &tables, &columns, &[]);
+        assert!(!compatibility.compatible);
+        assert_eq!(
+            compatibility.problems(),
+            vec!["column memories.embedding", "table psychology_layers", "extension vector"]
+        );
+        let memories = &compatibility.tables[0];
+        assert!(memories.present);
+        assert!(!memories.is_compatible());
+    }
+
+    #[test]
+    fn incompatible_schema_refuses() {
+        let compatibility = Compatibility::evaluate(&SPEC, &[], &[], &[]);
+        let error = compatibility.ensure().unwrap_err();
+        let incompatible = error.downcast_ref::<IncompatibleSchema>().expect("an IncompatibleSchema error");
+        assert!(incompatible.to_string().contains("table memories"));
+    }
+}
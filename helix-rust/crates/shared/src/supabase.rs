@@ -1,12 +1,30 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use postgrest::Postgrest;
-use sqlx::{PgPool, postgres::PgPoolOptions};
+use serde_json::Value;
+use sqlx::{PgPool, Row, postgres::{PgListener, PgPoolOptions}};
+use std::any::Any;
 use std::env;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::cache::{self, CacheKey, CacheKind, Cached, ReadCache, UserInstance};
+use crate::schema::{self, Compatibility, SchemaSpec};
+
+/// Tier of users without a subscription row
+const DEFAULT_TIER: &str = "core";
+/// Channel the skills trigger notifies on with the bare skill id
+const SKILL_CHANGED_CHANNEL: &str = "skill_changed";
+/// Delay before re-listening after the notification connection drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 pub struct SupabaseClient {
     rest_client: Postgrest,
     pool: PgPool,
+    cache: Arc<ReadCache>,
 }
 
 impl SupabaseClient {
@@ -28,7 +46,11 @@ impl SupabaseClient {
             .await
             .context("Failed to connect to Supabase PostgreSQL")?;
 
-        Ok(Self { rest_client, pool })
+        Ok(Self {
+            rest_client,
+            pool,
+            cache: Arc::new(ReadCache::from_env()),
+        })
     }
 
     pub fn rest(&self) -> &Postgrest {
@@ -38,4 +60,200 @@ impl SupabaseClient {
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Compare the database with what `spec` requires, logging anything
+    /// missing
+    pub async fn check_schema(&self, spec: &SchemaSpec<'_>) -> Result<Compatibility> {
+        let compatibility = schema::check(&self.pool, spec).await?;
+        if compatibility.compatible {
+            info!("Supabase schema is compatible");
+        } else {
+            for problem in compatibility.problems() {
+                warn!("Supabase schema is missing {}", problem);
+            }
+        }
+        Ok(compatibility)
+    }
+
+    /// `check_schema`, failing with [`IncompatibleSchema`](crate::IncompatibleSchema)
+    /// unless everything is there. Services call it at startup, before any
+    /// job that writes.
+    pub async fn require_schema(&self, spec: &SchemaSpec<'_>) -> Result<Compatibility> {
+        self.check_schema(spec).await?.ensure()
+    }
+
+    /// The read cache behind the lookups below, for its stats
+    pub fn cache(&self) -> &ReadCache {
+        &self.cache
+    }
+
+    /// The user's subscription tier, "free" without a subscription
+    pub async fn subscription_tier(&self, user_id: Uuid) -> Result<String> {
+        let key = CacheKey {
+            kind: CacheKind::SubscriptionTier,
+            id: user_id,
+        };
+        let cached = self
+            .cache
+            .get_or_fetch(key, async {
+                let tier: Option<String> =
+                    sqlx::query_scalar("SELECT tier::text FROM subscriptions WHERE user_id = $1")
+                        .bind(user_id)
+                        .fetch_optional(&self.pool)
+                        .await
+                        .context("Failed to fetch subscription tier")?
+                        .flatten();
+                Ok(Cached::Tier(tier.unwrap_or_else(|| DEFAULT_TIER.to_string())))
+            })
+            .await?;
+        match cached {
+            Cached::Tier(tier) => Ok(tier),
+            other => bail!("Cached {:?} is not a tier", other),
+        }
+    }
+
+    /// The user's registered devices
+    pub async fn user_instances(&self, user_id: Uuid) -> Result<Arc<Vec<UserInstance>>> {
+        let key = CacheKey {
+            kind: CacheKind::UserInstances,
+            id: user_id,
+        };
+        let cached = self
+            .cache
+            .get_or_fetch(key, async {
+                let instances = sqlx::query(
+                    "SELECT instance_id, device_name, device_type, platform,
+                            COALESCE(is_online, false) AS is_online
+                     FROM user_instances WHERE user_id = $1 ORDER BY created_at",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to fetch user instances")?
+                .iter()
+                .map(|row| {
+                    Ok(UserInstance {
+                        instance_id: row.try_get("instance_id")?,
+                        device_name: row.try_get("device_name")?,
+                        device_type: row.try_get("device_type")?,
+                        platform: row.try_get("platform")?,
+                        is_online: row.try_get("is_online")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, sqlx::Error>>()?;
+                Ok(Cached::Instances(Arc::new(instances)))
+            })
+            .await?;
+        match cached {
+            Cached::Instances(instances) => Ok(instances),
+            other => bail!("Cached {:?} is not a device list", other),
+        }
+    }
+
+    /// A skill's manifest, None when the skill has none or doesn't exist
+    pub async fn skill_manifest(&self, skill_id: Uuid) -> Result<Option<Arc<Value>>> {
+        let key = CacheKey {
+            kind: CacheKind::SkillManifest,
+            id: skill_id,
+        };
+        let cached = self
+            .cache
+            .get_or_fetch(key, async {
+                let manifest: Option<Value> = sqlx::query_scalar("SELECT manifest FROM skills WHERE id = $1")
+                    .bind(skill_id)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .context("Failed to fetch skill manifest")?
+                    .flatten();
+                Ok(Cached::Manifest(manifest.map(Arc::new)))
+            })
+            .await?;
+        match cached {
+            Cached::Manifest(manifest) => Ok(manifest),
+            other => bail!("Cached {:?} is not a manifest", other),
+        }
+    }
+
+    /// A skill compiled by the calling service, compiling it with `compile`
+    /// on a miss. Dropped with the skill's manifest when the skill changes.
+    pub async fn compiled_skill<T, F>(&self, skill_id: Uuid, compile: F) -> Result<Arc<T>>
+    where
+        T: Any + Send + Sync,
+        F: Future<Output = Result<T>>,
+    {
+        let key = CacheKey {
+            kind: CacheKind::CompiledSkill,
+            id: skill_id,
+        };
+        let cached = self
+            .cache
+            .get_or_fetch(key, async { Ok(Cached::Compiled(Arc::new(compile.await?))) })
+            .await?;
+        match cached {
+            Cached::Compiled(compiled) => compiled
+                .downcast::<T>()
+                .map_err(|_| anyhow!("Cached skill {} was compiled into another type", skill_id)),
+            other => bail!("Cached {:?} is not a compiled skill", other),
+        }
+    }
+
+    /// Keep the read cache current from the database's change
+    /// notifications (migrations 006 and 008). Services using the lookups
+    /// above call this once at startup.
+    ///
+    /// Notifications may be missed while the connection is down, so the whole
+    /// cache is cleared whenever the listener (re)connects.
+    pub fn spawn_cache_listener(&self) {
+        let client = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = client.listen_for_changes().await {
+                    error!("Cache invalidation listener failed: {}", e);
+                }
+
+                client.cache.clear();
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    async fn listen_for_changes(&self) -> Result<()> {
+        let mut listener = PgListener::connect_with(&self.pool)
+            .await
+            .context("Failed to connect cache invalidation listener")?;
+        listener
+            .listen_all([cache::INVALIDATE_CHANNEL, SKILL_CHANGED_CHANNEL])
+            .await?;
+
+        info!("Listening for cache invalidations");
+        self.cache.clear();
+
+        loop {
+            let notification = listener.recv().await?;
+            let payload = notification.payload();
+            if notification.channel() == SKILL_CHANGED_CHANNEL {
+                match Uuid::parse_str(payload) {
+                    Ok(id) => self.cache.invalidate_skill(id).await,
+                    Err(_) => {
+                        warn!("Unrecognized skill change payload: {}", payload);
+                        self.cache.clear();
+                    }
+                }
+                continue;
+            }
+
+            let change: Value = match serde_json::from_str(payload) {
+                Ok(change) => change,
+                Err(_) => {
+                    warn!("Unrecognized cache invalidation payload: {}", payload);
+                    continue;
+                }
+            };
+            let table = change.get("table").and_then(Value::as_str).unwrap_or_default();
+            let record = change.get("record").unwrap_or(&Value::Null);
+            if !self.cache.on_change(table, record).await {
+                warn!("Cache invalidation for uncached table '{}'", table);
+            }
+        }
+    }
 }
@@ -0,0 +1,484 @@
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{debug, warn};
+
+/// Encoding of GPT-4, GPT-3.5 and the text-embedding-3 models; also the
+/// approximation used for Claude and Gemini, whose vocabularies aren't public
+pub const CL100K_BASE: &str = "cl100k_base";
+/// Encoding of GPT-4o and the o-series models
+pub const O200K_BASE: &str = "o200k_base";
+/// Directory holding `<encoding>.tiktoken` vocabularies, looked in before
+/// the bundled directory and ~/.helix/tokenizers
+pub const TOKENIZER_DIR_ENV: &str = "HELIX_TOKENIZER_DIR";
+
+/// Vocabularies shipped with the application, see `set_bundled_dir`
+static BUNDLED_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Characters per token assumed when no vocabulary is available
+const ESTIMATE_CHARS_PER_TOKEN: usize = 4;
+
+/// The encoding a model's tokens are counted with
+pub fn encoding_for_model(model: &str) -> &'static str {
+    // Provider prefixes like "openai/gpt-4o"
+    let model = model.rsplit('/').next().unwrap_or(model).to_ascii_lowercase();
+    let o200k = ["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "chatgpt-", "o1", "o3", "o4"];
+    if o200k.iter().any(|prefix| model.starts_with(prefix)) {
+        O200K_BASE
+    } else {
+        CL100K_BASE
+    }
+}
+
+/// A byte-pair encoding loaded from a tiktoken vocabulary
+#[derive(Debug)]
+pub struct Encoding {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+}
+
+impl Encoding {
+    /// An encoding from its merge ranks; every single byte must have one
+    pub fn new(name: impl Into<String>, ranks: HashMap<Vec<u8>, u32>) -> Result<Self> {
+        let name = name.into();
+        if let Some(byte) = (0..=u8::MAX).find(|byte| !ranks.contains_key(&[*byte][..])) {
+            bail!("{} is not a byte-level BPE vocabulary: byte {:#04x} has no rank", name, byte);
+        }
+        Ok(Self { name, ranks })
+    }
+
+    /// Parse a `.tiktoken` file: one base64 token and its rank per line
+    pub fn parse(name: impl Into<String>, contents: &str) -> Result<Self> {
+        let mut ranks = HashMap::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (token, rank) = line
+                .split_once(' ')
+                .with_context(|| format!("Line {} is not '<base64 token> <rank>'", number + 1))?;
+            let token = BASE64
+                .decode(token)
+                .with_context(|| format!("Invalid base64 token on line {}", number + 1))?;
+            let rank = rank
+                .trim()
+                .parse::<u32>()
+                .with_context(|| format!("Invalid rank on line {}", number + 1))?;
+            ranks.insert(token, rank);
+        }
+        Self::new(name, ranks)
+    }
+
+    pub fn load(name: impl Into<String>, path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tokenizer vocabulary {}", path.display()))?;
+        Self::parse(name, &contents)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        let mut tokens = Vec::new();
+        for piece in pre_tokenize(text) {
+            let piece = piece.as_bytes();
+            match self.ranks.get(piece) {
+                Some(rank) => tokens.push(*rank),
+                None => tokens.extend(byte_pair_merge(&self.ranks, piece).windows(2).map(|w| {
+                    // Every part is a single byte or a merge found in the ranks
+                    self.ranks[&piece[w[0]..w[1]]]
+                })),
+            }
+        }
+        tokens
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        pre_tokenize(text).map(|piece| self.count_piece(piece)).sum()
+    }
+
+    fn count_piece(&self, piece: &str) -> usize {
+        let bytes = piece.as_bytes();
+        if self.ranks.contains_key(bytes) {
+            1
+        } else {
+            byte_pair_merge(&self.ranks, bytes).len() - 1
+        }
+    }
+}
+
+/// Boundaries of the parts `piece` merges into, following tiktoken:
+/// repeatedly merge the adjacent pair with the lowest rank
+fn byte_pair_merge(ranks: &HashMap<Vec<u8>, u32>, piece: &[u8]) -> Vec<usize> {
+    // (start of part, rank of merging it with the next part)
+    let mut parts: Vec<(usize, u32)> = Vec::with_capacity(piece.len() + 1);
+    for i in 0..piece.len().saturating_sub(1) {
+        parts.push((i, ranks.get(&piece[i..i + 2]).copied().unwrap_or(u32::MAX)));
+    }
+    if !piece.is_empty() {
+        parts.push((piece.len() - 1, u32::MAX));
+    }
+    parts.push((piece.len(), u32::MAX));
+
+    let rank_at = |parts: &[(usize, u32)], i: usize| {
+        if i + 3 < parts.len() {
+            ranks.get(&piece[parts[i].0..parts[i + 3].0]).copied().unwrap_or(u32::MAX)
+        } else {
+            u32::MAX
+        }
+    };
+
+    while let Some((i, _)) = parts[..parts.len() - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, rank))| *rank != u32::MAX)
+        .min_by_key(|(i, (_, rank))| (*rank, *i))
+    {
+        if i > 0 {
+            parts[i - 1].1 = rank_at(&parts, i - 1);
+        }
+        parts[i].1 = rank_at(&parts, i);
+        parts.remove(i + 1);
+    }
+    parts.into_iter().map(|(start, _)| start).collect()
+}
+
+/// Split text the way cl100k_base's pattern does before merging:
+/// contractions, words with one leading non-letter, runs of up to three
+/// digits, punctuation with an optional leading space, and whitespace,
+/// leaving the last space of a run to the word after it. o200k_base splits
+/// mixed-case words differently, so its counts may be off by a few tokens.
+pub fn pre_tokenize(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let len = next_piece_len(rest);
+        let (piece, tail) = rest.split_at(len);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+fn is_newline(c: char) -> bool {
+    c == '\r' || c == '\n'
+}
+
+/// Neither whitespace, a letter nor a number
+fn is_symbol(c: char) -> bool {
+    !c.is_whitespace() && !c.is_alphabetic() && !c.is_numeric()
+}
+
+/// Byte length of the piece `text` starts with; never zero
+fn next_piece_len(text: &str) -> usize {
+    let first = text.chars().next().expect("text is not empty");
+    let second = text[first.len_utf8()..].chars().next();
+
+    // 's 't 're 've 'm 'll 'd, in any case
+    if first == '\'' {
+        let after = &text[1..];
+        for suffix in ["s", "t", "re", "ve", "m", "ll", "d"] {
+            if after.len() >= suffix.len()
+                && after.is_char_boundary(suffix.len())
+                && after[..suffix.len()].eq_ignore_ascii_case(suffix)
+            {
+                return 1 + suffix.len();
+            }
+        }
+    }
+
+    // A word, with one leading non-letter, non-number, non-newline
+    let word_start = if first.is_alphabetic() {
+        Some(0)
+    } else if !is_newline(first) && !first.is_numeric() && second.is_some_and(char::is_alphabetic) {
+        Some(first.len_utf8())
+    } else {
+        None
+    };
+    if let Some(start) = word_start {
+        return start + run_len(&text[start..], char::is_alphabetic);
+    }
+
+    if first.is_numeric() {
+        return text.char_indices().take_while(|(_, c)| c.is_numeric()).take(3).map(|(_, c)| c.len_utf8()).sum();
+    }
+
+    // Punctuation, with an optional leading space and trailing newlines
+    let symbol_start = if is_symbol(first) {
+        Some(0)
+    } else if first == ' ' && second.is_some_and(is_symbol) {
+        Some(1)
+    } else {
+        None
+    };
+    if let Some(start) = symbol_start {
+        let end = start + run_len(&text[start..], is_symbol);
+        return end + run_len(&text[end..], is_newline);
+    }
+
+    // Whitespace: through its last newline if it has one; otherwise all
+    // of it at the end of the text, or all but the last character before
+    // a non-space, which then leads the next piece
+    let run = run_len(text, char::is_whitespace);
+    if let Some((i, c)) = text[..run].char_indices().rfind(|(_, c)| is_newline(*c)) {
+        return i + c.len_utf8();
+    }
+    if run == text.len() {
+        return run;
+    }
+    match text[..run].char_indices().last() {
+        Some((last, _)) if last > 0 => last,
+        _ => run,
+    }
+}
+
+/// Byte length of the leading run of characters matching `pred`
+fn run_len(text: &str, pred: impl Fn(char) -> bool) -> usize {
+    text.char_indices().find(|(_, c)| !pred(*c)).map_or(text.len(), |(i, _)| i)
+}
+
+/// Counts tokens for a model: exactly when its vocabulary is installed,
+/// otherwise estimated from the length of the text
+#[derive(Debug, Clone)]
+pub enum Tokenizer {
+    Bpe(Arc<Encoding>),
+    Estimate,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::for_encoding(CL100K_BASE)
+    }
+}
+
+impl Tokenizer {
+    pub fn for_model(model: &str) -> Self {
+        Self::for_encoding(encoding_for_model(model))
+    }
+
+    /// The named encoding from the tokenizer directories, loaded once
+    pub fn for_encoding(name: &str) -> Self {
+        static LOADED: OnceLock<Mutex<HashMap<String, Option<Arc<Encoding>>>>> = OnceLock::new();
+        let mut loaded = LOADED.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        let encoding = loaded.entry(name.to_string()).or_insert_with(|| {
+            let path = vocabulary_path(name)?;
+            match Encoding::load(name, &path) {
+                Ok(encoding) => {
+                    debug!("Loaded {} ({} tokens) from {}", name, encoding.vocab_size(), path.display());
+                    Some(Arc::new(encoding))
+                }
+                Err(e) => {
+                    warn!("Estimating token counts, {} unavailable: {:#}", name, e);
+                    None
+                }
+            }
+        });
+        encoding.clone().map_or(Self::Estimate, Self::Bpe)
+    }
+
+    /// Whether counts are exact rather than estimated
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Bpe(_))
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Self::Bpe(encoding) => encoding.count(text),
+            Self::Estimate => text.chars().count().div_ceil(ESTIMATE_CHARS_PER_TOKEN),
+        }
+    }
+
+    /// The longest prefix of `text` within `max_tokens`, cut between
+    /// pre-tokenized pieces (or characters, when estimating)
+    pub fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> &'a str {
+        match self {
+            Self::Bpe(encoding) => {
+                let mut used = 0;
+                let mut end = 0;
+                for piece in pre_tokenize(text) {
+                    used += encoding.count_piece(piece);
+                    if used > max_tokens {
+                        break;
+                    }
+                    end += piece.len();
+                }
+                &text[..end]
+            }
+            Self::Estimate => {
+                let max_chars = max_tokens.saturating_mul(ESTIMATE_CHARS_PER_TOKEN);
+                text.char_indices().nth(max_chars).map_or(text, |(i, _)| &text[..i])
+            }
+        }
+    }
+}
+
+/// Where the application ships its vocabularies (Helix Desktop: its
+/// resources). Set once, before the first count.
+pub fn set_bundled_dir(dir: PathBuf) {
+    let _ = BUNDLED_DIR.set(dir);
+}
+
+pub fn bundled_dir() -> Option<&'static Path> {
+    BUNDLED_DIR.get().map(PathBuf::as_path)
+}
+
+/// HELIX_TOKENIZER_DIR, the bundled directory and ~/.helix/tokenizers, in
+/// the order they are looked in
+fn tokenizer_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os(TOKENIZER_DIR_ENV).map(PathBuf::from).into_iter().collect();
+    dirs.extend(bundled_dir().map(Path::to_path_buf));
+    if let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        dirs.push(PathBuf::from(home).join(".helix").join("tokenizers"));
+    }
+    dirs
+}
+
+/// `<name>.tiktoken` in the first tokenizer directory holding it, else
+/// where the last one would hold it
+fn vocabulary_path(name: &str) -> Option<PathBuf> {
+    let file = format!("{}.tiktoken", name);
+    let dirs = tokenizer_dirs();
+    dirs.iter()
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+        .or_else(|| dirs.last().map(|dir| dir.join(&file)))
+}
+
+/// Tokens left while assembling text against a limit
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    tokenizer: Tokenizer,
+    limit: usize,
+    used: usize,
+}
+
+impl TokenBudget {
+    pub fn new(tokenizer: Tokenizer, limit: usize) -> Self {
+        Self { tokenizer, limit, used: 0 }
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// Count `text` against the budget if all of it fits
+    pub fn try_add(&mut self, text: &str) -> bool {
+        let tokens = self.tokenizer.count(text);
+        if tokens > self.remaining() {
+            return false;
+        }
+        self.used += tokens;
+        true
+    }
+
+    /// As much of `text` as fits, counted against the budget
+    pub fn take<'a>(&mut self, text: &'a str) -> &'a str {
+        let kept = self.tokenizer.truncate(text, self.remaining());
+        self.used += self.tokenizer.count(kept);
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every byte, plus merges building "hello" and " world"
+    fn test_encoding() -> Encoding {
+        let mut ranks: HashMap<Vec<u8>, u32> = (0..=u8::MAX).map(|b| (vec![b], b as u32)).collect();
+        for (rank, token) in ["he", "ll", "hell", "hello", " w", "or", " wor", "ld", " world"].iter().enumerate() {
+            ranks.insert(token.as_bytes().to_vec(), 256 + rank as u32);
+        }
+        Encoding::new("test", ranks).unwrap()
+    }
+
+    fn pieces(text: &str) -> Vec<&str> {
+        pre_tokenize(text).collect()
+    }
+
+    #[test]
+    fn pre_tokenizes_like_cl100k() {
+        assert_eq!(pieces("Hello world"), ["Hello", " world"]);
+        assert_eq!(pieces("it's I'LL"), ["it", "'s", " I", "'LL"]);
+        assert_eq!(pieces("12345 x"), ["123", "45", " x"]);
+        assert_eq!(pieces("a  b"), ["a", " ", " b"]);
+        assert_eq!(pieces("end.\n\nNext"), ["end", ".\n\n", "Next"]);
+        assert_eq!(pieces("x \n  y"), ["x", " \n", " ", " y"]);
+        assert_eq!(pieces("(ok) "), ["(ok", ")", " "]);
+        assert_eq!(pieces(" 42"), [" ", "42"]);
+        assert_eq!(pieces("café ünïcode"), ["café", " ünïcode"]);
+        assert_eq!(pieces("").len(), 0);
+    }
+
+    #[test]
+    fn pieces_cover_the_text() {
+        let text = "Mixed: 3.14159, «quotes» and\ttabs\r\n  trailing   ";
+        assert_eq!(pieces(text).concat(), text);
+    }
+
+    #[test]
+    fn merges_lowest_rank_first() {
+        let encoding = test_encoding();
+        assert_eq!(encoding.encode("hello world"), [259, 264]);
+        assert_eq!(encoding.count("hello world"), 2);
+        // "help": "he" merges, "lp" has no rank
+        assert_eq!(encoding.encode("help"), [256, b'l' as u32, b'p' as u32]);
+        assert_eq!(encoding.count("help"), 3);
+    }
+
+    #[test]
+    fn parses_tiktoken_files() {
+        let mut contents: String = (0..=u8::MAX).map(|b| format!("{} {}\n", BASE64.encode([b]), b)).collect();
+        contents.push_str(&format!("{} 256\n", BASE64.encode("he")));
+        let encoding = Encoding::parse("file", &contents).unwrap();
+        assert_eq!(encoding.vocab_size(), 257);
+        assert_eq!(encoding.encode("hey"), [256, b'y' as u32]);
+
+        assert!(Encoding::parse("missing-bytes", "aGU= 0\n").is_err());
+        assert!(Encoding::parse("bad-line", "aGU=\n").is_err());
+    }
+
+    #[test]
+    fn picks_encoding_by_model() {
+        assert_eq!(encoding_for_model("gpt-4o-mini"), O200K_BASE);
+        assert_eq!(encoding_for_model("openai/o3-mini"), O200K_BASE);
+        assert_eq!(encoding_for_model("gpt-4-turbo"), CL100K_BASE);
+        assert_eq!(encoding_for_model("claude-sonnet-4"), CL100K_BASE);
+    }
+
+    #[test]
+    fn truncates_between_pieces() {
+        let bpe = Tokenizer::Bpe(Arc::new(test_encoding()));
+        assert_eq!(bpe.truncate("hello world hello", 2), "hello world");
+        assert_eq!(bpe.truncate("hello", 0), "");
+
+        let estimate = Tokenizer::Estimate;
+        assert_eq!(estimate.count("abcdefghi"), 3);
+        assert_eq!(estimate.truncate("abcdefghi", 2), "abcdefgh");
+        assert_eq!(estimate.truncate("abc", 2), "abc");
+    }
+
+    #[test]
+    fn budget_tracks_what_fits() {
+        let mut budget = TokenBudget::new(Tokenizer::Bpe(Arc::new(test_encoding())), 3);
+        assert!(budget.try_add("hello world"));
+        assert!(!budget.try_add("hello world"));
+        assert_eq!(budget.take(" world hello"), " world");
+        assert_eq!(budget.remaining(), 0);
+        assert_eq!(budget.used(), 3);
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use sqlx::{PgConnection, PgPool};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tracing::warn;
+
+/// SQLSTATEs for a transaction that lost a conflict and can simply run again
+const RETRYABLE_CODES: &[&str] = &[
+    "40001", // serialization_failure
+    "40P01", // deadlock_detected
+];
+/// Cap on the backoff doubling, so a large max_attempts can't overflow it
+const MAX_BACKOFF_DOUBLINGS: u32 = 10;
+
+/// The work a transaction runs, borrowing its connection
+pub type TxFuture<'c, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'c>>;
+
+/// How often, and how patiently, a conflicted transaction is run again
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Tries in all, counting the first
+    pub max_attempts: u32,
+    /// Wait before the second try; doubles for each try after that
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Tries in all; 0 is treated as 1
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Wait after failed try number `attempt` (1-based)
+    fn delay(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(MAX_BACKOFF_DOUBLINGS);
+        self.backoff.saturating_mul(1 << doublings)
+    }
+}
+
+/// Whether `error` comes from a serialization failure or deadlock, which
+/// running the whole transaction again can get past
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<sqlx::Error>())
+        .any(|e| match e {
+            sqlx::Error::Database(db) => db.code().is_some_and(|code| RETRYABLE_CODES.contains(&code.as_ref())),
+            _ => false,
+        })
+}
+
+async fn run_once<T, F>(pool: &PgPool, work: &mut F) -> Result<T>
+where
+    F: for<'c> FnMut(&'c mut PgConnection) -> TxFuture<'c, T>,
+{
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .execute(&mut *tx)
+        .await
+        .context("Failed to set transaction isolation")?;
+    // An error drops the transaction, which rolls it back
+    let value = work(&mut tx).await?;
+    tx.commit().await.context("Failed to commit transaction")?;
+    Ok(value)
+}
+
+/// Run `work` in one SERIALIZABLE transaction: every write it makes commits
+/// together or not at all. When the transaction fails on a serialization
+/// conflict or deadlock, `work` runs again from the start in a fresh one, up
+/// to the policy's max_attempts. `work` must therefore only write through
+/// the connection it is given and be safe to repeat.
+pub async fn serializable<T, F>(pool: &PgPool, policy: RetryPolicy, mut work: F) -> Result<T>
+where
+    F: for<'c> FnMut(&'c mut PgConnection) -> TxFuture<'c, T>,
+{
+    let mut attempt = 1;
+    loop {
+        match run_once(pool, &mut work).await {
+            Err(e) if attempt < policy.max_attempts && is_retryable(&e) => {
+                let delay = policy.delay(attempt);
+                warn!(
+                    "Transaction conflict on attempt {}/{}, retrying in {:?}: {:#}",
+                    attempt, policy.max_attempts, delay, e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_per_attempt() {
+        let policy = RetryPolicy::default().backoff(Duration::from_millis(10));
+        assert_eq!(policy.delay(1), Duration::from_millis(10));
+        assert_eq!(policy.delay(2), Duration::from_millis(20));
+        assert_eq!(policy.delay(4), Duration::from_millis(80));
+        assert_eq!(policy.delay(100), Duration::from_millis(10 << MAX_BACKOFF_DOUBLINGS));
+    }
+
+    #[test]
+    fn at_least_one_attempt() {
+        assert_eq!(RetryPolicy::default().max_attempts(0).max_attempts, 1);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let error = anyhow::Error::new(sqlx::Error::RowNotFound).context("Failed to write");
+        assert!(!is_retryable(&error));
+        assert!(!is_retryable(&anyhow::anyhow!("40001")));
+    }
+}
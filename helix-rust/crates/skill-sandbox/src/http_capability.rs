@@ -0,0 +1,317 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+use wasmtime::{Caller, Linker};
+
+use crate::manifest::HttpPermissions;
+use crate::wasm_runtime::SkillCtx;
+
+/// Redirect hops followed before giving up
+const MAX_REDIRECTS: usize = 5;
+/// Per-request timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Rate limit window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Error codes returned to the guest as negative values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpFetchError {
+    Denied = -1,
+    RateLimited = -2,
+    RequestFailed = -3,
+    BufferTooSmall = -4,
+    InvalidRequest = -5,
+    ResponseTooLarge = -6,
+}
+
+impl HttpFetchError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Denied => "denied",
+            Self::RateLimited => "rate_limited",
+            Self::RequestFailed => "request_failed",
+            Self::BufferTooSmall => "buffer_too_small",
+            Self::InvalidRequest => "invalid_request",
+            Self::ResponseTooLarge => "response_too_large",
+        }
+    }
+}
+
+/// Request a skill passes to `helix.http_fetch` as JSON
+#[derive(Debug, Deserialize)]
+pub struct HttpFetchRequest {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+}
+
+fn default_method() -> String { "GET".to_string() }
+
+/// Response written back into guest memory as JSON
+#[derive(Debug, Serialize)]
+pub struct HttpFetchResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// One outbound request, recorded whether or not it was allowed
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpAuditEntry {
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub outcome: String,
+    pub response_bytes: u64,
+    pub duration_ms: u64,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Per-skill fixed-window rate limiter shared across executions
+#[derive(Default)]
+pub struct HttpRateLimiter {
+    windows: Mutex<HashMap<Uuid, (Instant, u32)>>,
+}
+
+impl HttpRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn try_acquire(&self, skill_id: Uuid, limit_per_minute: u32) -> bool {
+        let mut windows = match self.windows.lock() {
+            Ok(w) => w,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let now = Instant::now();
+        let window = windows.entry(skill_id).or_insert((now, 0));
+        if now.duration_since(window.0) >= RATE_WINDOW {
+            *window = (now, 0);
+        }
+
+        if window.1 >= limit_per_minute {
+            return false;
+        }
+
+        window.1 += 1;
+        true
+    }
+}
+
+/// HTTP access granted to one execution of a skill
+pub struct HttpCapability {
+    skill_id: Uuid,
+    execution_id: Uuid,
+    permissions: HttpPermissions,
+    limiter: Arc<HttpRateLimiter>,
+    client: reqwest::Client,
+    audit: Vec<HttpAuditEntry>,
+}
+
+impl HttpCapability {
+    pub fn new(
+        skill_id: Uuid,
+        execution_id: Uuid,
+        permissions: HttpPermissions,
+        limiter: Arc<HttpRateLimiter>,
+    ) -> Result<Self> {
+        // Redirects must stay inside the allow-list too
+        let redirect_perms = permissions.clone();
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                let allowed = attempt.url().scheme() == "https"
+                    && attempt.url().host_str().map(|h| redirect_perms.allows_host(h)).unwrap_or(false);
+                if allowed && attempt.previous().len() < MAX_REDIRECTS {
+                    attempt.follow()
+                } else {
+                    attempt.stop()
+                }
+            }))
+            .build()?;
+
+        Ok(Self {
+            skill_id,
+            execution_id,
+            permissions,
+            limiter,
+            client,
+            audit: Vec::new(),
+        })
+    }
+
+    /// Requests made during this execution, for the audit trail
+    pub fn into_audit(self) -> Vec<HttpAuditEntry> {
+        self.audit
+    }
+
+    fn fetch(&mut self, request: HttpFetchRequest) -> Result<HttpFetchResponse, HttpFetchError> {
+        let started = Instant::now();
+        let requested_at = Utc::now();
+        let result = self.perform(&request);
+
+        let (status, response_bytes, outcome) = match &result {
+            Ok(response) => (Some(response.status), response.body.len() as u64, "ok"),
+            Err(e) => (None, 0, e.as_str()),
+        };
+
+        info!(
+            "Skill {} (execution {}) http_fetch {} {} -> {}",
+            self.skill_id, self.execution_id, request.method, request.url, outcome
+        );
+
+        self.audit.push(HttpAuditEntry {
+            method: request.method.to_uppercase(),
+            url: request.url.clone(),
+            status,
+            outcome: outcome.to_string(),
+            response_bytes,
+            duration_ms: started.elapsed().as_millis() as u64,
+            requested_at,
+        });
+
+        result
+    }
+
+    fn perform(&self, request: &HttpFetchRequest) -> Result<HttpFetchResponse, HttpFetchError> {
+        let url = reqwest::Url::parse(&request.url).map_err(|_| HttpFetchError::InvalidRequest)?;
+        if url.scheme() != "https" {
+            return Err(HttpFetchError::Denied);
+        }
+
+        let host = url.host_str().ok_or(HttpFetchError::InvalidRequest)?;
+        if !self.permissions.allows_host(host) {
+            return Err(HttpFetchError::Denied);
+        }
+
+        if !self.limiter.try_acquire(self.skill_id, self.permissions.rate_limit_per_minute) {
+            return Err(HttpFetchError::RateLimited);
+        }
+
+        let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+            .map_err(|_| HttpFetchError::InvalidRequest)?;
+
+        let mut builder = self.client.request(method, url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let max_bytes = self.permissions.max_response_bytes;
+
+        // Host calls are synchronous; block this worker while the request runs
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async move {
+                let mut response = builder.send().await.map_err(|e| {
+                    warn!("http_fetch request failed: {}", e);
+                    HttpFetchError::RequestFailed
+                })?;
+
+                let status = response.status().as_u16();
+                let headers = response
+                    .headers()
+                    .iter()
+                    .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+                    .collect();
+
+                let mut body = Vec::new();
+                while let Some(chunk) = response.chunk().await.map_err(|_| HttpFetchError::RequestFailed)? {
+                    if body.len() as u64 + chunk.len() as u64 > max_bytes {
+                        return Err(HttpFetchError::ResponseTooLarge);
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+
+                Ok(HttpFetchResponse {
+                    status,
+                    headers,
+                    body: String::from_utf8_lossy(&body).to_string(),
+                })
+            })
+        })
+    }
+}
+
+/// Register `helix.http_fetch(req_ptr, req_len, resp_ptr, resp_cap) -> i64`.
+///
+/// The request is a JSON [`HttpFetchRequest`]; on success the JSON response is
+/// written to `resp_ptr` and its length returned. Failures return a negative
+/// [`HttpFetchError`] code.
+pub fn add_to_linker(linker: &mut Linker<SkillCtx>) -> Result<()> {
+    linker.func_wrap(
+        "helix",
+        "http_fetch",
+        |mut caller: Caller<'_, SkillCtx>, req_ptr: u32, req_len: u32, resp_ptr: u32, resp_cap: u32| -> i64 {
+            match http_fetch(&mut caller, req_ptr, req_len, resp_ptr, resp_cap) {
+                Ok(written) => written as i64,
+                Err(e) => e as i64,
+            }
+        },
+    )?;
+    Ok(())
+}
+
+fn http_fetch(
+    caller: &mut Caller<'_, SkillCtx>,
+    req_ptr: u32,
+    req_len: u32,
+    resp_ptr: u32,
+    resp_cap: u32,
+) -> Result<usize, HttpFetchError> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .ok_or(HttpFetchError::InvalidRequest)?;
+
+    let mut request_bytes = vec![0u8; req_len as usize];
+    memory
+        .read(&*caller, req_ptr as usize, &mut request_bytes)
+        .map_err(|_| HttpFetchError::InvalidRequest)?;
+
+    let request: HttpFetchRequest =
+        serde_json::from_slice(&request_bytes).map_err(|_| HttpFetchError::InvalidRequest)?;
+
+    // Skills without an `http` manifest entry never get a capability
+    let capability = caller.data_mut().http.as_mut().ok_or(HttpFetchError::Denied)?;
+    let response = capability.fetch(request)?;
+
+    let response_bytes = serde_json::to_vec(&response).map_err(|_| HttpFetchError::RequestFailed)?;
+    if response_bytes.len() > resp_cap as usize {
+        return Err(HttpFetchError::BufferTooSmall);
+    }
+
+    memory
+        .write(&mut *caller, resp_ptr as usize, &response_bytes)
+        .map_err(|_| HttpFetchError::InvalidRequest)?;
+
+    Ok(response_bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_per_skill() {
+        let limiter = HttpRateLimiter::new();
+        let skill_a = Uuid::new_v4();
+        let skill_b = Uuid::new_v4();
+
+        assert!(limiter.try_acquire(skill_a, 2));
+        assert!(limiter.try_acquire(skill_a, 2));
+        assert!(!limiter.try_acquire(skill_a, 2));
+        assert!(limiter.try_acquire(skill_b, 2));
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// File name of the bundled QuickJS interpreter (WASI build)
+const QUICKJS_WASM: &str = "quickjs.wasm";
+/// File name of the bundled MicroPython interpreter (WASI build)
+const MICROPYTHON_WASM: &str = "micropython.wasm";
+
+/// Language a skill was authored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillLanguage {
+    Wasm,
+    Javascript,
+    Python,
+}
+
+impl SkillLanguage {
+    /// Infer the language from an uploaded file name
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let ext = Path::new(filename).extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "wasm" => Some(Self::Wasm),
+            "js" | "mjs" => Some(Self::Javascript),
+            "py" => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    /// Parse the value stored in the `skills.language` column
+    pub fn from_db(value: Option<&str>) -> Self {
+        match value {
+            Some("javascript") => Self::Javascript,
+            Some("python") => Self::Python,
+            _ => Self::Wasm,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wasm => "wasm",
+            Self::Javascript => "javascript",
+            Self::Python => "python",
+        }
+    }
+
+    pub fn is_interpreted(&self) -> bool {
+        !matches!(self, Self::Wasm)
+    }
+
+    /// WASI argv that makes the interpreter evaluate `source`.
+    /// Input is delivered on stdin and the result is read back from stdout.
+    pub fn interpreter_args(&self, source: &str) -> Vec<String> {
+        match self {
+            Self::Wasm => Vec::new(),
+            Self::Javascript => vec![
+                "qjs".to_string(),
+                "--std".to_string(),
+                "-e".to_string(),
+                source.to_string(),
+            ],
+            Self::Python => vec![
+                "micropython".to_string(),
+                "-c".to_string(),
+                source.to_string(),
+            ],
+        }
+    }
+}
+
+/// A skill ready to be stored in Supabase
+pub struct WrappedSkill {
+    pub language: SkillLanguage,
+    pub wasm_module: Vec<u8>,
+    pub source: Option<String>,
+}
+
+/// Bundled interpreter modules used to run plain .js/.py skills
+pub struct Interpreters {
+    quickjs: Option<Vec<u8>>,
+    micropython: Option<Vec<u8>>,
+}
+
+impl Interpreters {
+    /// Directory holding the interpreter modules.
+    /// Defaults to `interpreters/` next to the executable.
+    pub fn default_dir() -> PathBuf {
+        if let Ok(dir) = env::var("HELIX_INTERPRETER_DIR") {
+            return PathBuf::from(dir);
+        }
+
+        env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|p| p.join("interpreters")))
+            .unwrap_or_else(|| PathBuf::from("interpreters"))
+    }
+
+    /// Load whichever interpreters are present in `dir`.
+    /// Missing interpreters are not fatal: skills in that language are rejected at upload.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let quickjs = Self::read_optional(&dir.join(QUICKJS_WASM))?;
+        let micropython = Self::read_optional(&dir.join(MICROPYTHON_WASM))?;
+
+        info!(
+            "Interpreters loaded from {:?} (quickjs: {}, micropython: {})",
+            dir,
+            quickjs.is_some(),
+            micropython.is_some()
+        );
+
+        Ok(Self { quickjs, micropython })
+    }
+
+    fn read_optional(path: &Path) -> Result<Option<Vec<u8>>> {
+        if !path.exists() {
+            warn!("Interpreter not found at {:?}", path);
+            return Ok(None);
+        }
+
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read interpreter {:?}", path))?;
+        Ok(Some(bytes))
+    }
+
+    /// Interpreter module for a language
+    pub fn module_for(&self, language: SkillLanguage) -> Result<&[u8]> {
+        let module = match language {
+            SkillLanguage::Wasm => bail!("WASM skills do not use an interpreter"),
+            SkillLanguage::Javascript => self.quickjs.as_deref(),
+            SkillLanguage::Python => self.micropython.as_deref(),
+        };
+
+        module.with_context(|| format!("No {} interpreter bundled", language.as_str()))
+    }
+
+    /// Wrap an uploaded skill file into the module stored in Supabase.
+    ///
+    /// Interpreted skills store the interpreter they were wrapped with as the
+    /// module, so later interpreter upgrades don't change an existing skill.
+    pub fn wrap(&self, language: SkillLanguage, payload: Vec<u8>) -> Result<WrappedSkill> {
+        if !language.is_interpreted() {
+            return Ok(WrappedSkill {
+                language,
+                wasm_module: payload,
+                source: None,
+            });
+        }
+
+        let source = String::from_utf8(payload).context("Skill source is not valid UTF-8")?;
+        if source.trim().is_empty() {
+            bail!("Skill source is empty");
+        }
+
+        Ok(WrappedSkill {
+            language,
+            wasm_module: self.module_for(language)?.to_vec(),
+            source: Some(source),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_filename() {
+        assert_eq!(SkillLanguage::from_filename("skill.js"), Some(SkillLanguage::Javascript));
+        assert_eq!(SkillLanguage::from_filename("skill.PY"), Some(SkillLanguage::Python));
+        assert_eq!(SkillLanguage::from_filename("skill.wasm"), Some(SkillLanguage::Wasm));
+        assert_eq!(SkillLanguage::from_filename("skill.rb"), None);
+    }
+
+    #[test]
+    fn test_wrap_without_interpreter_fails() {
+        let interpreters = Interpreters { quickjs: None, micropython: None };
+        let result = interpreters.wrap(SkillLanguage::Javascript, b"print(1)".to_vec());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_wrap_interpreted_skill() {
+        let interpreters = Interpreters {
+            quickjs: Some(vec![0, 97, 115, 109]),
+            micropython: None,
+        };
+        let wrapped = interpreters
+            .wrap(SkillLanguage::Javascript, b"console.log('{}')".to_vec())
+            .unwrap();
+        assert_eq!(wrapped.wasm_module, vec![0, 97, 115, 109]);
+        assert_eq!(wrapped.source.as_deref(), Some("console.log('{}')"));
+    }
+}
@@ -2,6 +2,11 @@ use anyhow::Result;
 use clap::Parser;
 use tracing_subscriber;
 
+mod http_capability;
+mod interpreters;
+mod manifest;
+mod scratch;
+mod skills;
 mod wasm_runtime;
 mod rpc_server;
 
@@ -13,6 +18,10 @@ struct Args {
     /// Port for RPC server
     #[arg(short, long, default_value_t = 18790)]
     port: u16,
+
+    /// Scratch filesystem quota per execution, in megabytes
+    #[arg(long, default_value_t = 64)]
+    scratch_quota_mb: u64,
 }
 
 #[tokio::main]
@@ -21,6 +30,6 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    start_rpc_server(args.port).await?;
+    start_rpc_server(args.port, args.scratch_quota_mb * 1024 * 1024).await?;
     Ok(())
 }
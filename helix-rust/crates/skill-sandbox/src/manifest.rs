@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// Default requests per minute for a skill with HTTP access
+const DEFAULT_HTTP_RATE_LIMIT: u32 = 30;
+/// Default response body cap: 1 MB
+const DEFAULT_MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+/// Capabilities a skill declares in the `skills.manifest` column
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillManifest {
+    #[serde(default)]
+    pub http: Option<HttpPermissions>,
+}
+
+/// Outbound HTTP access granted to a skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpPermissions {
+    /// Allowed hosts; `*.example.com` also matches subdomains
+    pub domains: Vec<String>,
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit_per_minute: u32,
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: u64,
+}
+
+fn default_rate_limit() -> u32 { DEFAULT_HTTP_RATE_LIMIT }
+fn default_max_response_bytes() -> u64 { DEFAULT_MAX_RESPONSE_BYTES }
+
+impl SkillManifest {
+    /// Parse the stored manifest, treating a missing or malformed one as "no capabilities"
+    pub fn from_db(value: Option<serde_json::Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl HttpPermissions {
+    pub fn allows_host(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.domains.iter().any(|domain| {
+            let domain = domain.to_lowercase();
+            match domain.strip_prefix("*.") {
+                Some(base) => host == base || host.ends_with(&format!(".{}", base)),
+                None => host == domain,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_host() {
+        let perms = HttpPermissions {
+            domains: vec!["api.example.com".to_string(), "*.weather.gov".to_string()],
+            rate_limit_per_minute: 10,
+            max_response_bytes: 1024,
+        };
+
+        assert!(perms.allows_host("api.example.com"));
+        assert!(perms.allows_host("API.Example.com"));
+        assert!(!perms.allows_host("evil.example.com"));
+        assert!(perms.allows_host("weather.gov"));
+        assert!(perms.allows_host("forecast.weather.gov"));
+        assert!(!perms.allows_host("notweather.gov"));
+    }
+
+    #[test]
+    fn test_manifest_defaults() {
+        let manifest = SkillManifest::from_db(Some(serde_json::json!({
+            "http": { "domains": ["example.com"] }
+        })));
+        let http = manifest.http.unwrap();
+        assert_eq!(http.rate_limit_per_minute, DEFAULT_HTTP_RATE_LIMIT);
+        assert_eq!(http.max_response_bytes, DEFAULT_MAX_RESPONSE_BYTES);
+
+        assert!(SkillManifest::from_db(None).http.is_none());
+    }
+}
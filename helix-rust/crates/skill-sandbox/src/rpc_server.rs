@@ -1,103 +1,502 @@
 use anyhow::Result;
 use axum::{
-    extract::{State, Json},
-    routing::post,
+    extract::{Path, Request, State, Json},
+    middleware::{self, Next},
+    routing::{get, post},
     Router,
-    response::IntoResponse,
-    http::StatusCode,
+    response::{IntoResponse, Response},
+    http::{header, HeaderMap, StatusCode},
 };
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use helix_shared::SupabaseClient;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use helix_shared::{CacheStats, SupabaseClient};
 use uuid::Uuid;
 use tracing::{info, error};
 use sqlx::Row;
 
-use crate::wasm_runtime::WasmSandbox;
+use crate::http_capability::HttpAuditEntry;
+use crate::interpreters::{Interpreters, SkillLanguage};
+use crate::scratch::Artifact;
+use crate::skills;
+use crate::wasm_runtime::{ExecutionContext, WasmSandbox};
+
+/// Per-install token the app passes in; every request must carry it
+pub const TOKEN_ENV: &str = "HELIX_SANDBOX_TOKEN";
+/// Key the app signs approved runs with, hex; only that app launch has it
+pub const APPROVAL_KEY_ENV: &str = "HELIX_SANDBOX_APPROVAL_KEY";
+/// Account the request is made for; skills and their output are only
+/// served to their owner
+const USER_HEADER: &str = "x-helix-user";
 
 #[derive(Clone)]
 struct AppState {
     sandbox: Arc<WasmSandbox>,
+    interpreters: Arc<Interpreters>,
     supabase: SupabaseClient,
+    token: Arc<str>,
+    approval_key: Arc<[u8]>,
+    /// Runs already executed; an approval is good for one execution
+    approved_runs: Arc<Mutex<HashSet<String>>>,
 }
 
 #[derive(Deserialize)]
 struct ExecuteRequest {
+    /// The app's id for the approved run
+    run_id: String,
     skill_id: Uuid,
     input: serde_json::Value,
+    /// Hex HMAC-SHA256 of run_id, skill_id and input with the approval key
+    approval: String,
 }
 
 #[derive(Serialize)]
 struct ExecuteResponse {
     success: bool,
+    execution_id: Uuid,
     output: Option<serde_json::Value>,
+    artifacts: Vec<ArtifactInfo>,
+    error: Option<String>,
+}
+
+/// Artifact metadata returned by the executions API
+#[derive(Serialize)]
+struct ArtifactInfo {
+    name: String,
+    size_bytes: i64,
+}
+
+#[derive(Deserialize)]
+struct UploadRequest {
+    skill_id: Uuid,
+    /// Original file name, used to infer the language (`.wasm`, `.js`, `.py`)
+    filename: String,
+    /// Source text for .js/.py skills
+    source: Option<String>,
+    /// Compiled module bytes for .wasm skills
+    wasm_bytecode: Option<Vec<u8>>,
+}
+
+#[derive(Serialize)]
+struct UploadResponse {
+    success: bool,
+    language: Option<SkillLanguage>,
     error: Option<String>,
 }
 
-pub async fn start_rpc_server(port: u16) -> Result<()> {
-    let sandbox = Arc::new(WasmSandbox::new()?);
+pub async fn start_rpc_server(port: u16, scratch_quota_bytes: u64) -> Result<()> {
+    let token = std::env::var(TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} is not set; the sandbox won't run unauthenticated", TOKEN_ENV))?;
+    let approval_key = std::env::var(APPROVAL_KEY_ENV)
+        .ok()
+        .and_then(|key| hex::decode(key.trim()).ok())
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} is not set; the sandbox won't run unapproved skills", APPROVAL_KEY_ENV))?;
+    let sandbox = Arc::new(WasmSandbox::new()?.with_scratch_quota(scratch_quota_bytes));
+    let interpreters = Arc::new(Interpreters::load(&Interpreters::default_dir())?);
     let supabase = SupabaseClient::new().await?;
 
-    let state = AppState { sandbox, supabase };
+    // Pick up skill edits without a restart
+    supabase.spawn_cache_listener();
+
+    let state = AppState {
+        sandbox,
+        interpreters,
+        supabase,
+        token: token.trim().into(),
+        approval_key: approval_key.into(),
+        approved_runs: Arc::new(Mutex::new(HashSet::new())),
+    };
 
     let app = Router::new()
         .route("/execute", post(execute_skill))
+        .route("/upload", post(upload_skill))
+        .route("/executions/:execution_id/artifacts", get(list_artifacts))
+        .route("/executions/:execution_id/artifacts/:name", get(download_artifact))
+        .route("/cache/stats", get(cache_stats))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
         .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
-    info!("Skill sandbox RPC server listening on port {}", port);
+    // Loopback only: the app is the one client
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    info!("Skill sandbox RPC server listening on 127.0.0.1:{}", port);
 
     axum::serve(listener, app).await?;
     Ok(())
 }
 
+/// Compare in constant time so response timing doesn't leak the token
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Reject requests without the install's bearer token
+async fn require_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let given = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match given {
+        Some(given) if token_matches(&state.token, given) => next.run(request).await,
+        _ => (StatusCode::UNAUTHORIZED, "Missing or invalid sandbox token").into_response(),
+    }
+}
+
+/// Whether `req` carries the app's approval for exactly this run. serde_json
+/// sorts object keys, so the input serializes as it did when signed.
+fn approval_valid(key: &[u8], req: &ExecuteRequest) -> bool {
+    let Ok(given) = hex::decode(&req.approval) else {
+        return false;
+    };
+    let Ok(input) = serde_json::to_vec(&req.input) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<sha2::Sha256>::new_from_slice(key) else {
+        return false;
+    };
+    mac.update(req.run_id.as_bytes());
+    mac.update(b"\n");
+    mac.update(req.skill_id.to_string().as_bytes());
+    mac.update(b"\n");
+    mac.update(&input);
+    mac.verify_slice(&given).is_ok()
+}
+
+/// Whether `user_id` owns the skill; a missing skill is nobody's
+async fn owns_skill(state: &AppState, skill_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let owner = sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM skills WHERE id = $1")
+        .bind(skill_id)
+        .fetch_optional(state.supabase.pool())
+        .await?;
+    Ok(owner == Some(user_id))
+}
+
+/// The account a request is made for, from the X-Helix-User header
+fn caller(headers: &HeaderMap) -> Result<Uuid, Response> {
+    headers
+        .get(USER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Missing or invalid X-Helix-User").into_response())
+}
+
 async fn execute_skill(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ExecuteRequest>,
-) -> impl IntoResponse {
-    info!("Executing skill {}", req.skill_id);
+) -> Response {
+    let execution_id = Uuid::new_v4();
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(rejected) => return rejected,
+    };
+    let rejected = |status: StatusCode, error: String| {
+        (status, Json(ExecuteResponse {
+            success: false,
+            execution_id,
+            output: None,
+            artifacts: Vec::new(),
+            error: Some(error),
+        })).into_response()
+    };
 
-    // 1. Fetch skill WASM from Supabase
-    let wasm_bytes = match fetch_skill_wasm(&state.supabase, req.skill_id).await {
-        Ok(bytes) => bytes,
+    if !approval_valid(&state.approval_key, &req) {
+        return rejected(StatusCode::FORBIDDEN, format!("Run {} was not approved", req.run_id));
+    }
+    match owns_skill(&state, req.skill_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return rejected(StatusCode::NOT_FOUND, format!("Skill not found: {}", req.skill_id)),
+        Err(e) => {
+            error!("Failed to look up skill {}: {}", req.skill_id, e);
+            return rejected(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+    let first_use = state
+        .approved_runs
+        .lock()
+        .map(|mut runs| runs.insert(req.run_id.clone()))
+        .unwrap_or(false);
+    if !first_use {
+        return rejected(StatusCode::CONFLICT, format!("Run {} has already been executed", req.run_id));
+    }
+    info!("Executing skill {} (execution {}, run {})", req.skill_id, execution_id, req.run_id);
+
+    // 1. Fetch skill from Supabase (compiled module is cached)
+    let skill = match skills::load(&state.sandbox, &state.supabase, req.skill_id).await {
+        Ok(skill) => skill,
         Err(e) => {
             error!("Failed to fetch skill WASM: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(e.to_string()),
-            }));
+            return rejected(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
         }
     };
 
     // 2. Execute in sandbox
-    match state.sandbox.execute(&wasm_bytes, req.input).await {
-        Ok(output) => {
+    let ctx = ExecutionContext {
+        execution_id,
+        skill_id: req.skill_id,
+        manifest: skill.manifest.clone(),
+    };
+
+    let result = if skill.language.is_interpreted() {
+        let source = skill.source_code.as_deref().unwrap_or_default();
+        let args = skill.language.interpreter_args(source);
+        state.sandbox.execute_interpreted(&ctx, &skill.module, &args, req.input).await
+    } else {
+        state.sandbox.execute(&ctx, &skill.module, req.input).await
+    };
+
+    match result {
+        Ok(outcome) => {
+            // 3. Keep exported files so they can be downloaded later
+            if let Err(e) = store_artifacts(&state.supabase, execution_id, req.skill_id, &outcome.artifacts).await {
+                error!("Failed to store artifacts for execution {}: {}", execution_id, e);
+            }
+
+            // 4. Record outbound requests in the audit trail
+            if let Err(e) = store_http_audit(&state.supabase, execution_id, req.skill_id, &outcome.http_requests).await {
+                error!("Failed to store HTTP audit for execution {}: {}", execution_id, e);
+            }
+
+            let artifacts = outcome.artifacts.iter().map(|a| ArtifactInfo {
+                name: a.name.clone(),
+                size_bytes: a.content.len() as i64,
+            }).collect();
+
             (StatusCode::OK, Json(ExecuteResponse {
                 success: true,
-                output: Some(output),
+                execution_id,
+                output: Some(outcome.output),
+                artifacts,
                 error: None,
-            }))
+            })).into_response()
         }
         Err(e) => {
             error!("Skill execution failed: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(e.to_string()),
-            }))
+            rejected(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
+/// Hits and misses of the read cache, compiled skills included
+async fn cache_stats(State(state): State<AppState>) -> Json<CacheStats> {
+    Json(state.supabase.cache().stats())
+}
+
+/// Artifacts of one execution, only for the owner of the skill that ran
+async fn list_artifacts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(execution_id): Path<Uuid>,
+) -> Response {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(rejected) => return rejected,
+    };
+    let rows = sqlx::query(
+        "SELECT a.name, a.size_bytes FROM skill_execution_artifacts a
+         JOIN skills s ON s.id = a.skill_id
+         WHERE a.execution_id = $1 AND s.user_id = $2
+         ORDER BY a.name"
+    )
+    .bind(execution_id)
+    .bind(user_id)
+    .fetch_all(state.supabase.pool())
+    .await;
+
+    match rows {
+        Ok(rows) => {
+            let artifacts: Vec<ArtifactInfo> = rows.iter().map(|row| ArtifactInfo {
+                name: row.get("name"),
+                size_bytes: row.get("size_bytes"),
+            }).collect();
+            (StatusCode::OK, Json(artifacts)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to list artifacts for execution {}: {}", execution_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
         }
     }
 }
 
-async fn fetch_skill_wasm(client: &SupabaseClient, skill_id: Uuid) -> Result<Vec<u8>> {
+/// One artifact's content; someone else's reads as not found
+async fn download_artifact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((execution_id, name)): Path<(Uuid, String)>,
+) -> Response {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(rejected) => return rejected,
+    };
     let row = sqlx::query(
-        "SELECT wasm_bytecode FROM skills WHERE id = $1"
+        "SELECT a.content FROM skill_execution_artifacts a
+         JOIN skills s ON s.id = a.skill_id
+         WHERE a.execution_id = $1 AND a.name = $2 AND s.user_id = $3"
+    )
+    .bind(execution_id)
+    .bind(&name)
+    .bind(user_id)
+    .fetch_optional(state.supabase.pool())
+    .await;
+
+    match row {
+        Ok(Some(row)) => {
+            let content: Vec<u8> = row.get("content");
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", name)),
+                ],
+                content,
+            ).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, format!("Artifact not found: {}", name)).into_response(),
+        Err(e) => {
+            error!("Failed to fetch artifact {} for execution {}: {}", name, execution_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+async fn store_artifacts(
+    client: &SupabaseClient,
+    execution_id: Uuid,
+    skill_id: Uuid,
+    artifacts: &[Artifact],
+) -> Result<()> {
+    for artifact in artifacts {
+        sqlx::query(
+            "INSERT INTO skill_execution_artifacts (execution_id, skill_id, name, content, size_bytes)
+             VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(execution_id)
+        .bind(skill_id)
+        .bind(&artifact.name)
+        .bind(&artifact.content)
+        .bind(artifact.content.len() as i64)
+        .execute(client.pool())
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn upload_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<UploadRequest>,
+) -> Response {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(rejected) => return rejected,
+    };
+    info!("Uploading skill {} ({})", req.skill_id, req.filename);
+
+    // Nothing is wrapped or written for a skill the caller doesn't own
+    match owns_skill(&state, req.skill_id, user_id).await {
+        Ok(true) => {}
+        // Someone else's skill looks the same as a missing one
+        Ok(false) => return upload_rejected(StatusCode::NOT_FOUND, None, format!("Skill not found: {}", req.skill_id)),
+        Err(e) => {
+            error!("Failed to look up skill {}: {}", req.skill_id, e);
+            return upload_rejected(StatusCode::INTERNAL_SERVER_ERROR, None, e.to_string());
+        }
+    }
+
+    let language = match SkillLanguage::from_filename(&req.filename) {
+        Some(language) => language,
+        None => {
+            return upload_rejected(StatusCode::BAD_REQUEST, None, format!("Unsupported skill file: {}", req.filename));
+        }
+    };
+
+    let payload = if language.is_interpreted() {
+        req.source.map(String::into_bytes)
+    } else {
+        req.wasm_bytecode
+    };
+
+    let Some(payload) = payload else {
+        return upload_rejected(StatusCode::BAD_REQUEST, Some(language), "Missing skill payload".to_string());
+    };
+
+    let wrapped = match state.interpreters.wrap(language, payload) {
+        Ok(wrapped) => wrapped,
+        Err(e) => {
+            error!("Failed to wrap skill: {}", e);
+            return upload_rejected(StatusCode::BAD_REQUEST, Some(language), e.to_string());
+        }
+    };
+
+    let result = sqlx::query(
+        "UPDATE skills
+         SET wasm_bytecode = $2, language = $3, source_code = $4, updated_at = now()
+         WHERE id = $1 AND user_id = $5"
     )
-    .bind(skill_id)
-    .fetch_one(client.pool())
-    .await?;
+    .bind(req.skill_id)
+    .bind(&wrapped.wasm_module)
+    .bind(wrapped.language.as_str())
+    .bind(&wrapped.source)
+    .bind(user_id)
+    .execute(state.supabase.pool())
+    .await;
+
+    match result {
+        Ok(done) if done.rows_affected() > 0 => {
+            // The change trigger also notifies us, but don't wait for the round trip
+            state.supabase.cache().invalidate_skill(req.skill_id).await;
+            (StatusCode::OK, Json(UploadResponse {
+                success: true,
+                language: Some(language),
+                error: None,
+            })).into_response()
+        }
+        Ok(_) => upload_rejected(StatusCode::NOT_FOUND, Some(language), format!("Skill not found: {}", req.skill_id)),
+        Err(e) => {
+            error!("Failed to store skill: {}", e);
+            upload_rejected(StatusCode::INTERNAL_SERVER_ERROR, Some(language), e.to_string())
+        }
+    }
+}
 
-    let bytes: Vec<u8> = row.try_get("wasm_bytecode")?;
-    Ok(bytes)
+fn upload_rejected(status: StatusCode, language: Option<SkillLanguage>, error: String) -> Response {
+    (status, Json(UploadResponse {
+        success: false,
+        language,
+        error: Some(error),
+    })).into_response()
+}
+
+async fn store_http_audit(
+    client: &SupabaseClient,
+    execution_id: Uuid,
+    skill_id: Uuid,
+    entries: &[HttpAuditEntry],
+) -> Result<()> {
+    for entry in entries {
+        sqlx::query(
+            "INSERT INTO skill_http_audit
+               (execution_id, skill_id, method, url, status, outcome, response_bytes, duration_ms, requested_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+        )
+        .bind(execution_id)
+        .bind(skill_id)
+        .bind(&entry.method)
+        .bind(&entry.url)
+        .bind(entry.status.map(|s| s as i32))
+        .bind(&entry.outcome)
+        .bind(entry.response_bytes as i64)
+        .bind(entry.duration_ms as i64)
+        .bind(entry.requested_at)
+        .execute(client.pool())
+        .await?;
+    }
+
+    Ok(())
 }
@@ -0,0 +1,409 @@
+use anyhow::{Context, Result};
+use std::any::Any;
+use std::fs;
+use std::io::{IoSlice, IoSliceMut, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+use wasi_common::dir::{OpenResult, ReaddirCursor, ReaddirEntity, WasiDir};
+use wasi_common::file::{Advice, FdFlags, FileType, Filestat, OFlags, WasiFile};
+use wasi_common::{Error, ErrorExt, SystemTimeSpec};
+
+/// Path the scratch directory is mounted at inside the guest
+pub const SCRATCH_GUEST_PATH: &str = "/scratch";
+/// Subdirectory of the scratch dir whose files are kept as artifacts
+pub const ARTIFACTS_DIR: &str = "artifacts";
+/// Default scratch space per execution: 64 MB
+pub const DEFAULT_SCRATCH_QUOTA_BYTES: u64 = 64 * 1024 * 1024;
+
+/// A file a skill exported from its scratch directory
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+/// Isolated host directory backing one execution's scratch space.
+/// The directory is removed when this value is dropped.
+pub struct ScratchDir {
+    path: PathBuf,
+    quota: Arc<Quota>,
+}
+
+impl ScratchDir {
+    pub fn create(execution_id: Uuid, limit_bytes: u64) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("helix-skill-{}", execution_id));
+        fs::create_dir_all(path.join(ARTIFACTS_DIR))
+            .with_context(|| format!("Failed to create scratch dir {:?}", path))?;
+
+        Ok(Self {
+            path,
+            quota: Arc::new(Quota::new(limit_bytes)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open the scratch dir as a quota-enforcing WASI directory
+    pub fn open_wasi_dir(&self) -> Result<Box<dyn WasiDir>> {
+        let dir = wasi_common::sync::Dir::open_ambient_dir(
+            &self.path,
+            wasi_common::sync::ambient_authority(),
+        )
+        .context("Failed to open scratch dir")?;
+
+        let inner = wasi_common::sync::dir::Dir::from_cap_std(dir);
+        Ok(Box::new(QuotaDir::new(Box::new(inner), self.quota.clone())))
+    }
+
+    pub fn bytes_used(&self) -> u64 {
+        self.quota.used()
+    }
+
+    /// Collect every regular file the skill left in `artifacts/`
+    pub fn collect_artifacts(&self) -> Result<Vec<Artifact>> {
+        let mut artifacts = Vec::new();
+
+        for entry in fs::read_dir(self.path.join(ARTIFACTS_DIR))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            artifacts.push(Artifact {
+                name: entry.file_name().to_string_lossy().to_string(),
+                content: fs::read(entry.path())?,
+            });
+        }
+
+        Ok(artifacts)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            warn!("Failed to wipe scratch dir {:?}: {}", self.path, e);
+        } else {
+            debug!("Wiped scratch dir {:?}", self.path);
+        }
+    }
+}
+
+/// Byte budget shared by every file opened under one scratch dir
+pub struct Quota {
+    limit: u64,
+    used: AtomicU64,
+}
+
+impl Quota {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    /// Reserve `bytes`, failing with EFBIG if the budget would be exceeded
+    fn reserve(&self, bytes: u64) -> Result<(), Error> {
+        let mut current = self.used.load(Ordering::SeqCst);
+        loop {
+            let next = current.checked_add(bytes).ok_or_else(Error::too_big)?;
+            if next > self.limit {
+                return Err(Error::too_big().context("scratch quota exceeded"));
+            }
+            match self.used.compare_exchange(current, next, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return Ok(()),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    fn release(&self, bytes: u64) {
+        let _ = self
+            .used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some(v.saturating_sub(bytes)));
+    }
+}
+
+/// WASI directory wrapper that hands out quota-tracked files
+pub struct QuotaDir {
+    inner: Box<dyn WasiDir>,
+    quota: Arc<Quota>,
+}
+
+impl QuotaDir {
+    pub fn new(inner: Box<dyn WasiDir>, quota: Arc<Quota>) -> Self {
+        Self { inner, quota }
+    }
+}
+
+/// Resolve a possibly-wrapped destination dir to the host dir it wraps
+fn unwrap_dir(dir: &dyn WasiDir) -> &dyn WasiDir {
+    match dir.as_any().downcast_ref::<QuotaDir>() {
+        Some(quota_dir) => quota_dir.inner.as_ref(),
+        None => dir,
+    }
+}
+
+#[async_trait::async_trait]
+impl WasiDir for QuotaDir {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn open_file(
+        &self,
+        symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<OpenResult, Error> {
+        // Truncation frees whatever the file held before
+        let previous_size = if oflags.contains(OFlags::TRUNCATE) {
+            self.inner
+                .get_path_filestat(path, symlink_follow)
+                .await
+                .map(|stat| stat.size)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let opened = self
+            .inner
+            .open_file(symlink_follow, path, oflags, read, write, fdflags)
+            .await?;
+        self.quota.release(previous_size);
+
+        Ok(match opened {
+            OpenResult::File(file) => OpenResult::File(Box::new(QuotaFile {
+                inner: file,
+                quota: self.quota.clone(),
+            })),
+            OpenResult::Dir(dir) => OpenResult::Dir(Box::new(QuotaDir::new(dir, self.quota.clone()))),
+        })
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Error> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, Error>> + Send>, Error> {
+        self.inner.readdir(cursor).await
+    }
+
+    async fn symlink(&self, old_path: &str, new_path: &str) -> Result<(), Error> {
+        self.inner.symlink(old_path, new_path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> Result<(), Error> {
+        self.inner.remove_dir(path).await
+    }
+
+    async fn unlink_file(&self, path: &str) -> Result<(), Error> {
+        let size = self
+            .inner
+            .get_path_filestat(path, false)
+            .await
+            .map(|stat| stat.size)
+            .unwrap_or(0);
+
+        self.inner.unlink_file(path).await?;
+        self.quota.release(size);
+        Ok(())
+    }
+
+    async fn read_link(&self, path: &str) -> Result<PathBuf, Error> {
+        self.inner.read_link(path).await
+    }
+
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        self.inner.get_filestat().await
+    }
+
+    async fn get_path_filestat(&self, path: &str, follow_symlinks: bool) -> Result<Filestat, Error> {
+        self.inner.get_path_filestat(path, follow_symlinks).await
+    }
+
+    async fn rename(&self, path: &str, dest_dir: &dyn WasiDir, dest_path: &str) -> Result<(), Error> {
+        self.inner.rename(path, unwrap_dir(dest_dir), dest_path).await
+    }
+
+    async fn hard_link(&self, path: &str, target_dir: &dyn WasiDir, target_path: &str) -> Result<(), Error> {
+        self.inner.hard_link(path, unwrap_dir(target_dir), target_path).await
+    }
+
+    async fn set_times(
+        &self,
+        path: &str,
+        atime: Option<SystemTimeSpec>,
+        mtime: Option<SystemTimeSpec>,
+        follow_symlinks: bool,
+    ) -> Result<(), Error> {
+        self.inner.set_times(path, atime, mtime, follow_symlinks).await
+    }
+}
+
+/// WASI file wrapper that charges file growth against the scratch quota
+pub struct QuotaFile {
+    inner: Box<dyn WasiFile>,
+    quota: Arc<Quota>,
+}
+
+impl QuotaFile {
+    /// Reserve the bytes a write of `len` at `offset` would add past end of file
+    async fn reserve_growth(&self, offset: u64, len: u64) -> Result<(), Error> {
+        let size = self.inner.get_filestat().await?.size;
+        let end = offset.saturating_add(len);
+        if end > size {
+            self.quota.reserve(end - size)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WasiFile for QuotaFile {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn get_filetype(&self) -> Result<FileType, Error> {
+        self.inner.get_filetype().await
+    }
+
+    async fn datasync(&self) -> Result<(), Error> {
+        self.inner.datasync().await
+    }
+
+    async fn sync(&self) -> Result<(), Error> {
+        self.inner.sync().await
+    }
+
+    async fn get_fdflags(&self) -> Result<FdFlags, Error> {
+        self.inner.get_fdflags().await
+    }
+
+    async fn set_fdflags(&mut self, flags: FdFlags) -> Result<(), Error> {
+        self.inner.set_fdflags(flags).await
+    }
+
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        self.inner.get_filestat().await
+    }
+
+    async fn set_filestat_size(&self, size: u64) -> Result<(), Error> {
+        let current = self.inner.get_filestat().await?.size;
+        if size > current {
+            self.quota.reserve(size - current)?;
+        }
+        self.inner.set_filestat_size(size).await?;
+        if size < current {
+            self.quota.release(current - size);
+        }
+        Ok(())
+    }
+
+    async fn advise(&self, offset: u64, len: u64, advice: Advice) -> Result<(), Error> {
+        self.inner.advise(offset, len, advice).await
+    }
+
+    async fn set_times(
+        &self,
+        atime: Option<SystemTimeSpec>,
+        mtime: Option<SystemTimeSpec>,
+    ) -> Result<(), Error> {
+        self.inner.set_times(atime, mtime).await
+    }
+
+    async fn read_vectored<'a>(&self, bufs: &mut [IoSliceMut<'a>]) -> Result<u64, Error> {
+        self.inner.read_vectored(bufs).await
+    }
+
+    async fn read_vectored_at<'a>(&self, bufs: &mut [IoSliceMut<'a>], offset: u64) -> Result<u64, Error> {
+        self.inner.read_vectored_at(bufs, offset).await
+    }
+
+    async fn write_vectored<'a>(&self, bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+        let len: u64 = bufs.iter().map(|b| b.len() as u64).sum();
+        let offset = if self.inner.get_fdflags().await?.contains(FdFlags::APPEND) {
+            self.inner.get_filestat().await?.size
+        } else {
+            self.inner.seek(SeekFrom::Current(0)).await?
+        };
+
+        self.reserve_growth(offset, len).await?;
+        self.inner.write_vectored(bufs).await
+    }
+
+    async fn write_vectored_at<'a>(&self, bufs: &[IoSlice<'a>], offset: u64) -> Result<u64, Error> {
+        let len: u64 = bufs.iter().map(|b| b.len() as u64).sum();
+        self.reserve_growth(offset, len).await?;
+        self.inner.write_vectored_at(bufs, offset).await
+    }
+
+    async fn seek(&self, pos: SeekFrom) -> Result<u64, Error> {
+        self.inner.seek(pos).await
+    }
+
+    async fn peek(&self, buf: &mut [u8]) -> Result<u64, Error> {
+        self.inner.peek(buf).await
+    }
+
+    fn num_ready_bytes(&self) -> Result<u64, Error> {
+        self.inner.num_ready_bytes()
+    }
+
+    async fn readable(&self) -> Result<(), Error> {
+        self.inner.readable().await
+    }
+
+    async fn writable(&self) -> Result<(), Error> {
+        self.inner.writable().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_reserve_and_release() {
+        let quota = Quota::new(100);
+        assert!(quota.reserve(60).is_ok());
+        assert!(quota.reserve(50).is_err());
+        assert_eq!(quota.used(), 60);
+
+        quota.release(30);
+        assert!(quota.reserve(50).is_ok());
+        assert_eq!(quota.used(), 80);
+    }
+
+    #[test]
+    fn test_scratch_dir_wiped_on_drop() {
+        let scratch = ScratchDir::create(Uuid::new_v4(), 1024).unwrap();
+        let path = scratch.path().to_path_buf();
+        fs::write(path.join(ARTIFACTS_DIR).join("report.txt"), b"done").unwrap();
+
+        let artifacts = scratch.collect_artifacts().unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].name, "report.txt");
+
+        drop(scratch);
+        assert!(!path.exists());
+    }
+}
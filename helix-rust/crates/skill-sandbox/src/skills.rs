@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use helix_shared::SupabaseClient;
+use sqlx::Row;
+use std::sync::Arc;
+use uuid::Uuid;
+use wasmtime::Module;
+
+use crate::interpreters::SkillLanguage;
+use crate::manifest::SkillManifest;
+use crate::wasm_runtime::WasmSandbox;
+
+/// A skill row with its module already compiled
+pub struct CompiledSkill {
+    pub language: SkillLanguage,
+    pub module: Module,
+    pub source_code: Option<String>,
+    pub manifest: SkillManifest,
+}
+
+/// The skill ready to run, compiled on a miss. Kept in the shared read
+/// cache, which drops it when the skill's row changes.
+pub async fn load(sandbox: &WasmSandbox, client: &SupabaseClient, skill_id: Uuid) -> Result<Arc<CompiledSkill>> {
+    client
+        .compiled_skill(skill_id, async {
+            let row = sqlx::query("SELECT wasm_bytecode, language, source_code FROM skills WHERE id = $1")
+                .bind(skill_id)
+                .fetch_one(client.pool())
+                .await
+                .with_context(|| format!("Failed to fetch skill {}", skill_id))?;
+
+            let wasm_bytecode: Vec<u8> = row.try_get("wasm_bytecode")?;
+            let language: Option<String> = row.try_get("language")?;
+            let manifest = client.skill_manifest(skill_id).await?;
+
+            Ok(CompiledSkill {
+                language: SkillLanguage::from_db(language.as_deref()),
+                module: sandbox.compile(&wasm_bytecode)?,
+                source_code: row.try_get("source_code")?,
+                manifest: SkillManifest::from_db(manifest.map(|manifest| (*manifest).clone())),
+            })
+        })
+        .await
+}
@@ -1,10 +1,41 @@
 use anyhow::{Context, Result};
+use std::sync::Arc;
+use uuid::Uuid;
 use wasmtime::*;
 use wasmtime_wasi::add_to_linker;
+use wasi_common::pipe::{ReadPipe, WritePipe};
 use wasi_common::sync::WasiCtxBuilder;
+use wasi_common::{I32Exit, WasiCtx};
+
+use crate::http_capability::{self, HttpAuditEntry, HttpCapability, HttpRateLimiter};
+use crate::manifest::SkillManifest;
+use crate::scratch::{Artifact, ScratchDir, DEFAULT_SCRATCH_QUOTA_BYTES, SCRATCH_GUEST_PATH};
+
+/// Per-store state: WASI plus the host capabilities granted by the manifest
+pub struct SkillCtx {
+    pub wasi: WasiCtx,
+    pub http: Option<HttpCapability>,
+}
+
+/// Identifies one run of a skill
+pub struct ExecutionContext {
+    pub execution_id: Uuid,
+    pub skill_id: Uuid,
+    pub manifest: SkillManifest,
+}
+
+/// Result of a single skill execution
+pub struct ExecutionOutcome {
+    pub output: serde_json::Value,
+    pub artifacts: Vec<Artifact>,
+    pub scratch_bytes_used: u64,
+    pub http_requests: Vec<HttpAuditEntry>,
+}
 
 pub struct WasmSandbox {
     engine: Engine,
+    scratch_quota_bytes: u64,
+    http_limiter: Arc<HttpRateLimiter>,
 }
 
 impl WasmSandbox {
@@ -15,43 +46,140 @@ impl WasmSandbox {
         config.wasm_bulk_memory(true);
 
         let engine = Engine::new(&config)?;
-        Ok(Self { engine })
+        Ok(Self {
+            engine,
+            scratch_quota_bytes: DEFAULT_SCRATCH_QUOTA_BYTES,
+            http_limiter: Arc::new(HttpRateLimiter::new()),
+        })
     }
 
-    pub async fn execute(&self, wasm_bytes: &[u8], _input: serde_json::Value) -> Result<serde_json::Value> {
-        let module = Module::new(&self.engine, wasm_bytes)
-            .context("Failed to compile WASM module")?;
+    /// Cap the scratch space each execution may use
+    pub fn with_scratch_quota(mut self, bytes: u64) -> Self {
+        self.scratch_quota_bytes = bytes;
+        self
+    }
+
+    /// Compile a skill module; callers cache the result
+    pub fn compile(&self, wasm_bytes: &[u8]) -> Result<Module> {
+        Module::new(&self.engine, wasm_bytes)
+            .context("Failed to compile WASM module")
+    }
+
+    /// Run a compiled skill module through its `execute` export
+    pub async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        module: &Module,
+        input: serde_json::Value,
+    ) -> Result<ExecutionOutcome> {
+        self.run(ctx, module, &[], "execute", input)
+    }
+
+    /// Run an interpreter module (QuickJS, MicroPython) through its WASI `_start`,
+    /// with the skill source passed in `args`
+    pub async fn execute_interpreted(
+        &self,
+        ctx: &ExecutionContext,
+        interpreter: &Module,
+        args: &[String],
+        input: serde_json::Value,
+    ) -> Result<ExecutionOutcome> {
+        self.run(ctx, interpreter, args, "_start", input)
+    }
 
+    fn run(
+        &self,
+        ctx: &ExecutionContext,
+        module: &Module,
+        args: &[String],
+        entry_point: &str,
+        input: serde_json::Value,
+    ) -> Result<ExecutionOutcome> {
         let mut linker = Linker::new(&self.engine);
 
+        // Input goes in on stdin, output comes back on stdout
+        let stdin = ReadPipe::from(serde_json::to_vec(&input)?);
+        let stdout = WritePipe::new_in_memory();
+
+        // Isolated scratch space, wiped when `scratch` goes out of scope
+        let scratch = ScratchDir::create(ctx.execution_id, self.scratch_quota_bytes)?;
+
         // Create WASI context
-        let wasi = WasiCtxBuilder::new()
-            .inherit_stdout()
+        let mut wasi = WasiCtxBuilder::new()
+            .stdin(Box::new(stdin))
+            .stdout(Box::new(stdout.clone()))
             .inherit_stderr()
+            .args(args)
+            .context("Invalid WASI arguments")?
             .build();
 
-        add_to_linker(&mut linker, |s| s)?;
+        wasi.push_preopened_dir(scratch.open_wasi_dir()?, SCRATCH_GUEST_PATH)
+            .context("Failed to preopen scratch dir")?;
+
+        // Outbound HTTP only for skills whose manifest lists domains
+        let http = match &ctx.manifest.http {
+            Some(perms) if !perms.domains.is_empty() => Some(HttpCapability::new(
+                ctx.skill_id,
+                ctx.execution_id,
+                perms.clone(),
+                self.http_limiter.clone(),
+            )?),
+            _ => None,
+        };
+
+        add_to_linker(&mut linker, |s: &mut SkillCtx| &mut s.wasi)?;
+        http_capability::add_to_linker(&mut linker)?;
 
-        let mut store = Store::new(&self.engine, wasi);
+        let mut store = Store::new(&self.engine, SkillCtx { wasi, http });
 
         // Set timeout: 5 seconds max
         store.set_epoch_deadline(1);
 
-        let instance = linker.instantiate(&mut store, &module)
+        let instance = linker.instantiate(&mut store, module)
             .context("Failed to instantiate WASM module")?;
 
-        // Call the "execute" function
-        let execute_fn = instance.get_typed_func::<(), ()>(&mut store, "execute")
-            .context("WASM module missing 'execute' function")?;
+        let entry_fn = instance.get_typed_func::<(), ()>(&mut store, entry_point)
+            .with_context(|| format!("WASM module missing '{}' function", entry_point))?;
 
-        // TODO: Pass input via WASI stdin, read output from stdout
-        execute_fn.call(&mut store, ())
-            .context("WASM execution failed")?;
+        if let Err(e) = entry_fn.call(&mut store, ()) {
+            // Interpreters exit through proc_exit; a zero exit code is a normal return
+            match e.downcast_ref::<I32Exit>() {
+                Some(exit) if exit.0 == 0 => {}
+                _ => return Err(e).context("WASM execution failed"),
+            }
+        }
 
-        Ok(serde_json::json!({"status": "success"}))
+        let SkillCtx { wasi, http } = store.into_data();
+        drop(wasi);
+        let http_requests = http.map(HttpCapability::into_audit).unwrap_or_default();
+
+        let output = stdout
+            .try_into_inner()
+            .map_err(|_| anyhow::anyhow!("Skill stdout still in use"))?
+            .into_inner();
+
+        Ok(ExecutionOutcome {
+            output: parse_output(&output),
+            artifacts: scratch.collect_artifacts().context("Failed to collect artifacts")?,
+            scratch_bytes_used: scratch.bytes_used(),
+            http_requests,
+        })
     }
 }
 
+/// Interpret skill stdout: JSON if it parses, plain text otherwise
+fn parse_output(output: &[u8]) -> serde_json::Value {
+    let text = String::from_utf8_lossy(output);
+    let trimmed = text.trim();
+
+    if trimmed.is_empty() {
+        return serde_json::json!({"status": "success"});
+    }
+
+    serde_json::from_str(trimmed)
+        .unwrap_or_else(|_| serde_json::Value::String(trimmed.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +189,11 @@ mod tests {
         let sandbox = WasmSandbox::new();
         assert!(sandbox.is_ok());
     }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(parse_output(b""), serde_json::json!({"status": "success"}));
+        assert_eq!(parse_output(b"{\"answer\": 42}\n"), serde_json::json!({"answer": 42}));
+        assert_eq!(parse_output(b"hello"), serde_json::json!("hello"));
+    }
 }
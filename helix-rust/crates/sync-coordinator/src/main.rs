@@ -1,19 +1,20 @@
 use anyhow::Result;
 use axum::{
     extract::ws::{WebSocket, WebSocketUpgrade},
-    extract::State,
-    response::IntoResponse,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::get,
-    Router,
+    Json, Router,
 };
 use clap::Parser;
 use dashmap::DashMap;
 use futures_util::{SinkExt, StreamExt};
-use helix_shared::SupabaseClient;
+use helix_shared::{CacheStats, SupabaseClient};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{error, info};
 use tracing_subscriber;
 use uuid::Uuid;
 
@@ -23,13 +24,31 @@ mod conflict_resolution;
 use vector_clock::VectorClock;
 use conflict_resolution::SyncEntity;
 
+/// Tiers that include the sync coordinator
+const SYNC_TIERS: &[&str] = &["phantom", "overseer", "architect"];
+
 #[derive(Clone)]
 struct AppState {
     supabase: SupabaseClient,
-    broadcast_tx: broadcast::Sender<SyncMessage>,
+    broadcast_tx: broadcast::Sender<Envelope>,
     connected_clients: Arc<DashMap<String, ClientInfo>>,
 }
 
+/// Who is connecting: an account and one of its registered devices
+#[derive(Deserialize)]
+struct ConnectQuery {
+    user_id: Uuid,
+    device_id: String,
+}
+
+/// A message on its way to the sender's other devices
+#[derive(Clone, Debug)]
+struct Envelope {
+    user_id: Uuid,
+    from_device: String,
+    message: SyncMessage,
+}
+
 #[derive(Clone, Debug)]
 struct ClientInfo {
     device_id: String,
@@ -67,6 +86,8 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     let supabase = SupabaseClient::new().await?;
+    // Revoked devices and downgraded plans are noticed before the TTL runs out
+    supabase.spawn_cache_listener();
     let (broadcast_tx, _) = broadcast::channel(100);
     let connected_clients = Arc::new(DashMap::new());
 
@@ -78,6 +99,7 @@ async fn main() -> Result<()> {
 
     let app = Router::new()
         .route("/ws", get(ws_handler))
+        .route("/cache/stats", get(cache_stats))
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", args.port)).await?;
@@ -89,22 +111,62 @@ async fn main() -> Result<()> {
 
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<ConnectQuery>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+) -> Response {
+    if let Err(rejected) = admit(&state, &query).await {
+        return rejected;
+    }
+    ws.on_upgrade(move |socket| handle_socket(socket, state, query))
+}
+
+/// Only registered devices of accounts whose plan includes sync connect
+async fn admit(state: &AppState, query: &ConnectQuery) -> Result<(), Response> {
+    let lookup_failed = |e: anyhow::Error| {
+        error!("Failed to check device {}: {:#}", query.device_id, e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    };
+
+    let instances = state.supabase.user_instances(query.user_id).await.map_err(lookup_failed)?;
+    if !instances.iter().any(|instance| instance.instance_id == query.device_id) {
+        return Err((StatusCode::FORBIDDEN, "Device is not registered to this account").into_response());
+    }
+
+    let tier = state.supabase.subscription_tier(query.user_id).await.map_err(lookup_failed)?;
+    if !SYNC_TIERS.contains(&tier.as_str()) {
+        return Err((
+            StatusCode::PAYMENT_REQUIRED,
+            format!("Sync needs the phantom plan or above; this account is on {}", tier),
+        )
+            .into_response());
+    }
+    Ok(())
+}
+
+/// Hits and misses of the device and plan lookups
+async fn cache_stats(State(state): State<AppState>) -> Json<CacheStats> {
+    Json(state.supabase.cache().stats())
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState) {
+async fn handle_socket(socket: WebSocket, state: AppState, query: ConnectQuery) {
     let (mut sender, mut receiver) = socket.split();
     let mut broadcast_rx = state.broadcast_tx.subscribe();
 
-    let device_id = Uuid::new_v4().to_string();
-    info!("Client connected: {}", device_id);
+    let ConnectQuery { user_id, device_id } = query;
+    state.connected_clients.insert(device_id.clone(), ClientInfo {
+        device_id: device_id.clone(),
+        user_id,
+    });
+    info!("Client connected: {} ({})", device_id, user_id);
 
-    // Broadcast task
+    // Broadcast task: the account's other devices only
+    let own_device = device_id.clone();
     let broadcast_task = tokio::spawn(async move {
-        while let Ok(msg) = broadcast_rx.recv().await {
-            let json = serde_json::to_string(&msg).unwrap();
+        while let Ok(envelope) = broadcast_rx.recv().await {
+            if envelope.user_id != user_id || envelope.from_device == own_device {
+                continue;
+            }
+            let json = serde_json::to_string(&envelope.message).unwrap();
             if sender.send(axum::extract::ws::Message::Text(json)).await.is_err() {
                 break;
             }
@@ -115,12 +177,16 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     while let Some(Ok(msg)) = receiver.next().await {
         if let axum::extract::ws::Message::Text(text) = msg {
             if let Ok(sync_msg) = serde_json::from_str::<SyncMessage>(&text) {
-                // Broadcast to all other clients
-                let _ = state.broadcast_tx.send(sync_msg);
+                let _ = state.broadcast_tx.send(Envelope {
+                    user_id,
+                    from_device: device_id.clone(),
+                    message: sync_msg,
+                });
             }
         }
     }
 
     info!("Client disconnected: {}", device_id);
+    state.connected_clients.remove(&device_id);
     broadcast_task.abort();
 }
@@ -0,0 +1,66 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::path::PathBuf;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Transcribes on this machine with whisper.cpp, for when audio must not
+/// leave the device
+pub struct LocalWhisper {
+    binary: PathBuf,
+    model: PathBuf,
+}
+
+impl LocalWhisper {
+    pub fn new() -> Result<Self> {
+        let binary = env::var("WHISPER_CPP_BINARY").unwrap_or_else(|_| "whisper-cli".to_string());
+        let model = env::var("WHISPER_CPP_MODEL").context(
+            "WHISPER_CPP_MODEL not set; local transcription needs a whisper.cpp model file",
+        )?;
+        let model = PathBuf::from(model);
+        if !model.is_file() {
+            bail!("whisper.cpp model {} not found", model.display());
+        }
+
+        Ok(Self {
+            binary: PathBuf::from(binary),
+            model,
+        })
+    }
+
+    pub async fn transcribe_audio(&self, wav_bytes: &[u8]) -> Result<String> {
+        let path = env::temp_dir().join(format!("helix-voice-{}.wav", Uuid::new_v4()));
+        tokio::fs::write(&path, wav_bytes)
+            .await
+            .context("Failed to write audio for whisper.cpp")?;
+
+        // -nt: no timestamps, -np: only the transcript on stdout
+        let output = Command::new(&self.binary)
+            .arg("-m")
+            .arg(&self.model)
+            .arg("-f")
+            .arg(&path)
+            .args(["-l", "auto", "-nt", "-np"])
+            .kill_on_drop(true)
+            .output()
+            .await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let output = output.with_context(|| format!("Failed to run {}", self.binary.display()))?;
+        if !output.status.success() {
+            bail!(
+                "whisper.cpp failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let transcript = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(transcript)
+    }
+}
@@ -8,7 +8,7 @@ use axum::{
     Json,
     body::Bytes,
 };
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use helix_shared::SupabaseClient;
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
@@ -19,15 +19,41 @@ use chrono::Utc;
 
 mod audio_processing;
 mod deepgram_client;
+mod local_engine;
 
 use audio_processing::AudioProcessor;
 use deepgram_client::DeepgramClient;
+use local_engine::LocalWhisper;
+
+/// Where speech is turned into text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Engine {
+    /// Deepgram; recordings are stored in Supabase
+    Cloud,
+    /// whisper.cpp on this machine; nothing leaves the device
+    Local,
+}
+
+enum Transcriber {
+    Deepgram(DeepgramClient),
+    Local(LocalWhisper),
+}
+
+impl Transcriber {
+    async fn transcribe_audio(&self, wav_bytes: &[u8]) -> Result<String> {
+        match self {
+            Transcriber::Deepgram(client) => client.transcribe_audio(wav_bytes).await,
+            Transcriber::Local(whisper) => whisper.transcribe_audio(wav_bytes).await,
+        }
+    }
+}
 
 #[derive(Clone)]
 struct AppState {
     audio_processor: Arc<AudioProcessor>,
-    deepgram: Arc<DeepgramClient>,
-    supabase: SupabaseClient,
+    transcriber: Arc<Transcriber>,
+    /// None with the local engine, which keeps recordings off the cloud
+    supabase: Option<SupabaseClient>,
 }
 
 #[derive(Serialize)]
@@ -47,6 +73,9 @@ struct TranscribeRequest {
 struct Args {
     #[arg(short, long, default_value_t = 18791)]
     port: u16,
+
+    #[arg(long, value_enum, default_value_t = Engine::Cloud)]
+    engine: Engine,
 }
 
 #[tokio::main]
@@ -56,12 +85,18 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     let audio_processor = Arc::new(AudioProcessor::new());
-    let deepgram = Arc::new(DeepgramClient::new()?);
-    let supabase = SupabaseClient::new().await?;
+    let (transcriber, supabase) = match args.engine {
+        Engine::Cloud => (
+            Transcriber::Deepgram(DeepgramClient::new()?),
+            Some(SupabaseClient::new().await?),
+        ),
+        Engine::Local => (Transcriber::Local(LocalWhisper::new()?), None),
+    };
+    info!("Transcribing with the {:?} engine", args.engine);
 
     let state = AppState {
         audio_processor,
-        deepgram,
+        transcriber: Arc::new(transcriber),
         supabase,
     };
 
@@ -123,8 +158,8 @@ async fn transcribe(
         }
     };
 
-    // 2. Transcribe with Deepgram
-    let transcript = match state.deepgram.transcribe_audio(&wav_bytes).await {
+    // 2. Transcribe with the configured engine
+    let transcript = match state.transcriber.transcribe_audio(&wav_bytes).await {
         Ok(text) => text,
         Err(e) => {
             error!("Transcription failed: {}", e);
@@ -136,7 +171,14 @@ async fn transcribe(
         }
     };
 
-    // 3. Store in Supabase
+    // 3. Store in Supabase, unless the audio is to stay on this device
+    let Some(supabase) = &state.supabase else {
+        return (StatusCode::OK, Json(TranscriptionResponse {
+            success: true,
+            transcript: Some(transcript),
+            error: None,
+        }));
+    };
     let recording_id = Uuid::new_v4();
     if let Err(e) = sqlx::query(
         "INSERT INTO voice_recordings (id, user_id, transcript, audio_data, created_at)
@@ -147,7 +189,7 @@ async fn transcribe(
     .bind(&transcript)
     .bind(&wav_bytes)
     .bind(Utc::now())
-    .execute(state.supabase.pool())
+    .execute(supabase.pool())
     .await {
         error!("Failed to store recording: {}", e);
     }
@@ -16,6 +16,8 @@ Configuration via environment:
   HELIX_TRUST_DECAY=true         # Whether trust scores decay
   HELIX_PRESERVE_HIGH_SALIENCE=true  # Never decay critical/high salience
   HELIX_DRY_RUN=true             # Don't write changes, just report
+  HELIX_RESULT_FILE=/path.json   # Set by Helix Desktop; receives a JSON summary
+  HELIX_LOCK_FILE=/path.lock   # Set by Helix Desktop; lock shared with the app
 """
 
 import json
@@ -25,6 +27,8 @@ from datetime import datetime
 from pathlib import Path
 from typing import Any
 
+from psychology_lock import EXIT_LOCKED, LOCK_FILE, psychology_lock
+
 # Configuration
 DECAY_RATE = float(os.getenv("HELIX_DECAY_RATE", "0.95"))
 MIN_INTENSITY = float(os.getenv("HELIX_MIN_INTENSITY", "0.1"))
@@ -32,6 +36,7 @@ DECAY_MODE = os.getenv("HELIX_DECAY_MODE", "soft").lower()  # soft or hard
 TRUST_DECAY_ENABLED = os.getenv("HELIX_TRUST_DECAY", "true").lower() == "true"
 PRESERVE_HIGH_SALIENCE = os.getenv("HELIX_PRESERVE_HIGH_SALIENCE", "true").lower() == "true"
 DRY_RUN = os.getenv("HELIX_DRY_RUN", "false").lower() == "true"
+RESULT_FILE = os.getenv("HELIX_RESULT_FILE")
 
 # High salience tiers that should never decay
 HIGH_SALIENCE_TIERS = {"critical", "high"}
@@ -56,13 +61,18 @@ def load_json(path: Path) -> dict[str, Any] | None:
 
 
 def save_json(path: Path, data: dict[str, Any]) -> bool:
-    """Save JSON file, return success status."""
+    """Save JSON file via a temporary file, so a crash never leaves it half-written."""
+    tmp = path.with_name(f".{path.name}.{os.getpid()}.tmp")
     try:
-        with open(path, "w", encoding="utf-8") as f:
+        with open(tmp, "w", encoding="utf-8") as f:
             json.dump(data, f, indent=2, ensure_ascii=False)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp, path)
         return True
-    except IOError as e:
+    except OSError as e:
         print(f"[ERROR] Failed to save {path}: {e}", file=sys.stderr)
+        tmp.unlink(missing_ok=True)
         return False
 
 
@@ -479,29 +489,49 @@ def main() -> int:
     print()
 
     print(f"[HELIX] Decay complete. Total changes: {total_changes}, Total skipped: {total_skipped}")
+    if RESULT_FILE:
+        save_json(Path(RESULT_FILE), {
+            "mode": DECAY_MODE,
+            "dry_run": DRY_RUN,
+            "changes": total_changes,
+            "skipped": total_skipped,
+        })
+    return 0
+
+
+def restore() -> int:
+    """Put back what soft decay faded."""
+    print("[HELIX] Restoring from soft decay...")
+    restored = 0
+
+    emotional_data = load_json(EMOTIONAL_TAGS_FILE)
+    if emotional_data:
+        emotional_data, count = restore_from_soft_decay(emotional_data, "emotional")
+        if count > 0:
+            save_json(EMOTIONAL_TAGS_FILE, emotional_data)
+            print(f"  Restored {count} emotional tags")
+            restored += count
+
+    trust_data = load_json(TRUST_MAP_FILE)
+    if trust_data:
+        trust_data, count = restore_from_soft_decay(trust_data, "trust")
+        if count > 0:
+            save_json(TRUST_MAP_FILE, trust_data)
+            print(f"  Restored {count} trust scores")
+            restored += count
+
+    print("[HELIX] Restore complete.")
+    if RESULT_FILE:
+        save_json(Path(RESULT_FILE), {"restored": restored})
     return 0
 
 
 if __name__ == "__main__":
-    # Check for restore command
-    if len(sys.argv) > 1 and sys.argv[1] == "--restore":
-        print("[HELIX] Restoring from soft decay...")
-
-        emotional_data = load_json(EMOTIONAL_TAGS_FILE)
-        if emotional_data:
-            emotional_data, count = restore_from_soft_decay(emotional_data, "emotional")
-            if count > 0:
-                save_json(EMOTIONAL_TAGS_FILE, emotional_data)
-                print(f"  Restored {count} emotional tags")
-
-        trust_data = load_json(TRUST_MAP_FILE)
-        if trust_data:
-            trust_data, count = restore_from_soft_decay(trust_data, "trust")
-            if count > 0:
-                save_json(TRUST_MAP_FILE, trust_data)
-                print(f"  Restored {count} trust scores")
-
-        print("[HELIX] Restore complete.")
-        sys.exit(0)
-
-    sys.exit(main())
+    with psychology_lock() as locked:
+        if not locked:
+            print(f"[ERROR] {LOCK_FILE} is held by another writer; try again later", file=sys.stderr)
+            sys.exit(EXIT_LOCKED)
+        # Check for restore command
+        if len(sys.argv) > 1 and sys.argv[1] == "--restore":
+            sys.exit(restore())
+        sys.exit(main())
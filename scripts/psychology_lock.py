@@ -0,0 +1,69 @@
+"""
+HELIX PSYCHOLOGY LOCK
+Advisory lock shared by decay.py, synthesis.py and Helix Desktop
+
+Every writer of psychology files holds an exclusive lock on the lock file
+(flock, or msvcrt.locking on Windows) while it writes; the scripts hold it
+for their whole run. A script that can't get it exits with EXIT_LOCKED,
+which Helix Desktop reports as a busy, retryable error.
+
+Configuration via environment:
+  HELIX_LOCK_FILE=/path.lock   # Set by Helix Desktop; lock shared with the app
+"""
+
+import os
+import time
+from contextlib import contextmanager
+from pathlib import Path
+from typing import BinaryIO, Iterator
+
+PROJECT_ROOT = Path(__file__).parent.resolve().parent
+
+LOCK_FILE = Path(os.getenv("HELIX_LOCK_FILE") or PROJECT_ROOT / ".psychology.lock")
+LOCK_WAIT_SECONDS = 30
+# Exit status when the lock is busy (EX_TEMPFAIL): safe to retry later
+EXIT_LOCKED = 75
+
+
+@contextmanager
+def psychology_lock() -> Iterator[bool]:
+    """Hold the advisory lock Helix Desktop takes around psychology writes.
+
+    Yields False if another writer kept it for LOCK_WAIT_SECONDS.
+    """
+    LOCK_FILE.parent.mkdir(parents=True, exist_ok=True)
+    with open(LOCK_FILE, "a+b") as handle:
+        deadline = time.monotonic() + LOCK_WAIT_SECONDS
+        while True:
+            try:
+                _lock(handle)
+                break
+            except OSError:
+                if time.monotonic() >= deadline:
+                    yield False
+                    return
+                time.sleep(0.1)
+        try:
+            yield True
+        finally:
+            _unlock(handle)
+
+
+def _lock(handle: BinaryIO) -> None:
+    if os.name == "nt":
+        import msvcrt
+        handle.seek(0)
+        msvcrt.locking(handle.fileno(), msvcrt.LK_NBLCK, 1)
+    else:
+        import fcntl
+        fcntl.flock(handle.fileno(), fcntl.LOCK_EX | fcntl.LOCK_NB)
+
+
+def _unlock(handle: BinaryIO) -> None:
+    if os.name == "nt":
+        import msvcrt
+        handle.seek(0)
+        msvcrt.locking(handle.fileno(), msvcrt.LK_UNLCK, 1)
+    else:
+        import fcntl
+        fcntl.flock(handle.fileno(), fcntl.LOCK_UN)
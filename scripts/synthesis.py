@@ -17,6 +17,8 @@ Designed to be run via cron (weekly):
 Configuration via environment:
   HELIX_SYNTHESIS_MODE=full   # full, emotional, transformation, wellness
   HELIX_DRY_RUN=true          # Don't write changes, just report
+  HELIX_RESULT_FILE=/path.json  # Set by Helix Desktop; receives a JSON summary
+  HELIX_LOCK_FILE=/path.lock  # Set by Helix Desktop; lock shared with the app
 """
 
 import json
@@ -26,9 +28,12 @@ from datetime import datetime
 from pathlib import Path
 from typing import Any
 
+from psychology_lock import EXIT_LOCKED, LOCK_FILE, psychology_lock
+
 # Configuration
 SYNTHESIS_MODE = os.getenv("HELIX_SYNTHESIS_MODE", "full")
 DRY_RUN = os.getenv("HELIX_DRY_RUN", "false").lower() == "true"
+RESULT_FILE = os.getenv("HELIX_RESULT_FILE")
 
 # Paths
 SCRIPT_DIR = Path(__file__).parent.resolve()
@@ -55,13 +60,18 @@ def load_json(path: Path) -> dict[str, Any] | None:
 
 
 def save_json(path: Path, data: dict[str, Any]) -> bool:
-    """Save JSON file with formatting."""
+    """Save JSON file via a temporary file, so a crash never leaves it half-written."""
+    tmp = path.with_name(f".{path.name}.{os.getpid()}.tmp")
     try:
-        with open(path, "w", encoding="utf-8") as f:
+        with open(tmp, "w", encoding="utf-8") as f:
             json.dump(data, f, indent=2, ensure_ascii=False)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp, path)
         return True
-    except IOError as e:
+    except OSError as e:
         print(f"[ERROR] Failed to save {path}: {e}", file=sys.stderr)
+        tmp.unlink(missing_ok=True)
         return False
 
 
@@ -343,8 +353,20 @@ def main() -> int:
     print()
 
     print("[HELIX] Synthesis complete.")
+    if RESULT_FILE:
+        save_json(Path(RESULT_FILE), {
+            "mode": SYNTHESIS_MODE,
+            "dry_run": DRY_RUN,
+            "emotional": emotional_analysis,
+            "relational": relational_health,
+            "wellness": wellness_metrics,
+        })
     return 0
 
 
 if __name__ == "__main__":
-    sys.exit(main())
+    with psychology_lock() as locked:
+        if not locked:
+            print(f"[ERROR] {LOCK_FILE} is held by another writer; try again later", file=sys.stderr)
+            sys.exit(EXIT_LOCKED)
+        sys.exit(main())
@@ -0,0 +1,22 @@
+-- Skill Sandbox: Interpreted (JavaScript / Python) skills
+-- Created: 2026-10-16
+-- Purpose: Let skills be uploaded as plain .js/.py files. The sandbox wraps them
+-- with a bundled QuickJS / MicroPython WASM interpreter at upload time and keeps
+-- the original source next to the generated module.
+
+CREATE TABLE IF NOT EXISTS skills (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL,
+  name VARCHAR NOT NULL,
+  wasm_bytecode BYTEA,
+  created_at TIMESTAMPTZ DEFAULT now(),
+  updated_at TIMESTAMPTZ DEFAULT now()
+);
+
+ALTER TABLE skills ADD COLUMN IF NOT EXISTS language VARCHAR NOT NULL DEFAULT 'wasm';  -- 'wasm', 'javascript', 'python'
+ALTER TABLE skills ADD COLUMN IF NOT EXISTS source_code TEXT;                          -- Original source for interpreted skills
+ALTER TABLE skills ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ DEFAULT now();
+
+ALTER TABLE skills DROP CONSTRAINT IF EXISTS skills_language_check;
+ALTER TABLE skills ADD CONSTRAINT skills_language_check
+  CHECK (language IN ('wasm', 'javascript', 'python'));
@@ -0,0 +1,20 @@
+-- Skill Sandbox: Execution artifacts
+-- Created: 2026-10-16
+-- Purpose: Each skill execution gets an isolated, size-capped scratch directory
+-- that is wiped after the run. Files the skill writes to /scratch/artifacts are
+-- kept here and served by GET /executions/:id/artifacts[/:name].
+
+CREATE TABLE IF NOT EXISTS skill_execution_artifacts (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  execution_id UUID NOT NULL,
+  skill_id UUID NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+  name VARCHAR NOT NULL,
+  content BYTEA NOT NULL,
+  size_bytes BIGINT NOT NULL,
+  created_at TIMESTAMPTZ DEFAULT now(),
+
+  UNIQUE (execution_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_execution_artifacts_execution
+  ON skill_execution_artifacts(execution_id);
@@ -0,0 +1,31 @@
+-- Skill Sandbox: Outbound HTTP capability
+-- Created: 2026-10-16
+-- Purpose: Skills may call the `helix.http_fetch` host function only when their
+-- manifest lists allowed domains, e.g.
+--   {"http": {"domains": ["api.example.com", "*.weather.gov"],
+--             "rate_limit_per_minute": 30, "max_response_bytes": 1048576}}
+-- Every request (allowed or not) is written to skill_http_audit.
+
+ALTER TABLE skills ADD COLUMN IF NOT EXISTS manifest JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE TABLE IF NOT EXISTS skill_http_audit (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  execution_id UUID NOT NULL,
+  skill_id UUID NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+
+  -- Request
+  method VARCHAR NOT NULL,
+  url TEXT NOT NULL,
+
+  -- Result
+  status INTEGER,                      -- HTTP status, NULL if the request never completed
+  outcome VARCHAR NOT NULL,            -- 'ok', 'denied', 'rate_limited', 'request_failed', ...
+  response_bytes BIGINT DEFAULT 0,
+  duration_ms BIGINT DEFAULT 0,
+
+  requested_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_http_audit_skill ON skill_http_audit(skill_id, requested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_skill_http_audit_execution ON skill_http_audit(execution_id);
@@ -0,0 +1,19 @@
+-- Skill Sandbox: Hot reload on skill change
+-- Created: 2026-10-16
+-- Purpose: Notify the skill-sandbox (LISTEN skill_changed) whenever a skill row
+-- changes so it drops the cached compiled module and manifest. The payload is
+-- the skill id.
+
+CREATE OR REPLACE FUNCTION notify_skill_changed()
+RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_notify('skill_changed', COALESCE(NEW.id, OLD.id)::text);
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS skills_notify_changed ON skills;
+CREATE TRIGGER skills_notify_changed
+  AFTER UPDATE OR DELETE ON skills
+  FOR EACH ROW
+  EXECUTE FUNCTION notify_skill_changed();
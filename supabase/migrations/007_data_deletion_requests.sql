@@ -0,0 +1,33 @@
+-- Data deletion requests from the desktop panic-wipe
+-- Created: 2026-10-16
+-- Purpose: Record a user's request to delete their server-side data. The
+-- desktop `secure_wipe` command inserts a row with the user's own token; a
+-- backend job processes pending rows and marks them completed.
+
+CREATE TABLE IF NOT EXISTS data_deletion_requests (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+
+  -- What to delete
+  scopes TEXT[] NOT NULL DEFAULT '{}',         -- e.g. {"sessions","psychology"}
+  device_id VARCHAR,                           -- Requesting device, if known
+
+  -- Processing
+  status VARCHAR NOT NULL DEFAULT 'pending',
+  requested_at TIMESTAMP DEFAULT NOW(),
+  completed_at TIMESTAMP,
+
+  CONSTRAINT deletion_status_check
+    CHECK (status IN ('pending', 'processing', 'completed', 'failed'))
+);
+
+CREATE INDEX idx_deletion_requests_user ON data_deletion_requests(user_id);
+CREATE INDEX idx_deletion_requests_status ON data_deletion_requests(status);
+
+ALTER TABLE IF EXISTS data_deletion_requests ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users request own deletion" ON data_deletion_requests
+  FOR INSERT WITH CHECK (auth.uid() = user_id);
+
+CREATE POLICY "Users see own deletion requests" ON data_deletion_requests
+  FOR SELECT USING (auth.uid() = user_id);
@@ -0,0 +1,35 @@
+-- Read cache invalidation
+-- Created: 2026-10-16
+-- Purpose: Notify services (LISTEN cache_invalidate) when a row behind a
+-- cached lookup in the shared SupabaseClient changes, so the entry is dropped
+-- before its TTL runs out. The payload mirrors a Realtime change:
+-- {"table": ..., "record": {...}} with the new row, or the old one on delete.
+-- Skills already notify on skill_changed (006).
+
+CREATE OR REPLACE FUNCTION notify_cache_invalidate()
+RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_notify(
+    'cache_invalidate',
+    json_build_object(
+      'table', TG_TABLE_NAME,
+      'record', json_build_object(
+        'user_id', COALESCE(NEW.user_id, OLD.user_id)
+      )
+    )::text
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS subscriptions_notify_cache ON subscriptions;
+CREATE TRIGGER subscriptions_notify_cache
+  AFTER INSERT OR UPDATE OR DELETE ON subscriptions
+  FOR EACH ROW
+  EXECUTE FUNCTION notify_cache_invalidate();
+
+DROP TRIGGER IF EXISTS user_instances_notify_cache ON user_instances;
+CREATE TRIGGER user_instances_notify_cache
+  AFTER INSERT OR UPDATE OR DELETE ON user_instances
+  FOR EACH ROW
+  EXECUTE FUNCTION notify_cache_invalidate();
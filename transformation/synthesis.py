@@ -122,6 +122,22 @@ def synthesize_prospective_goals(
         return patterns
 
 
+def synthesize_schedule_alignment(commitments: Optional[dict]) -> List[str]:
+    """
+    Synthesize Layer 4 goals with the calendar.
+
+    Reads the snapshot the desktop app writes when it fetches upcoming
+    commitments, which already carries schedule-goal alignment patterns.
+    """
+    if not commitments:
+        return []
+
+    try:
+        return [p for p in commitments.get("patterns", []) if isinstance(p, str)]
+    except (KeyError, TypeError, AttributeError):
+        return []
+
+
 def generate_recommended_actions(
     patterns: List[str],
     current_state: Optional[dict]
@@ -143,7 +159,11 @@ def generate_recommended_actions(
 
     try:
         for pattern in patterns:
-            if "Strong" in pattern and "toward" in pattern:
+            if "no scheduled time" in pattern:
+                actions.append("Block calendar time for neglected high-priority goals")
+            elif "not linked to any goal" in pattern:
+                actions.append("Audit calendar for time that serves no goal")
+            elif "Strong" in pattern and "toward" in pattern:
                 actions.append("Strengthen relationship through regular engagement")
             elif "Goal" in pattern and "supported" in pattern:
                 actions.append("Schedule collaborative goal-work session")
@@ -195,6 +215,7 @@ def synthesize_all_layers(layer_dir: Path = Path("transformation")) -> Synthesis
         layer_3_trust = load_layer_file(layer_dir / "psychology" / "trust_map.json")
         layer_4 = load_layer_file(layer_dir / "identity" / "goals.json")
         layer_4_feared = load_layer_file(layer_dir / "identity" / "feared_self.json")
+        layer_4_calendar = load_layer_file(layer_dir / "identity" / "upcoming_commitments.json")
         layer_6 = load_layer_file(layer_dir / "transformation" / "current_state.json")
 
         # Count memories/goals
@@ -219,6 +240,9 @@ def synthesize_all_layers(layer_dir: Path = Path("transformation")) -> Synthesis
         # 3-4 synthesis
         patterns.extend(synthesize_prospective_goals(layer_4, layer_3_attach, layer_4_feared))
 
+        # 4-calendar synthesis
+        patterns.extend(synthesize_schedule_alignment(layer_4_calendar))
+
         report["synthesized_patterns"] = patterns
 
         # Generate actions
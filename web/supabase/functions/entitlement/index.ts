@@ -0,0 +1,101 @@
+// Entitlement Edge Function
+// Issues signed subscription entitlements the desktop app caches so tier
+// checks keep working offline for a grace period.
+//
+// Token: base64url(JSON payload) + "." + base64url(Ed25519 signature of the
+// payload part). Payload: { v, sub, tier, iat, exp } with times in seconds.
+//
+// ENTITLEMENT_SIGNING_KEY holds the Ed25519 private key as base64 PKCS#8.
+// Generate one with:
+//   openssl genpkey -algorithm ed25519 -outform DER | base64
+// and build the desktop with HELIX_ENTITLEMENT_PUBLIC_KEY set to the raw
+// 32-byte public key in base64:
+//   openssl pkey -in key.pem -pubout -outform DER | tail -c 32 | base64
+
+import { serve } from 'https://deno.land/std@0.177.0/http/server.ts'
+import { createClient } from 'https://esm.sh/@supabase/supabase-js@2.39.0'
+import {
+  handleCorsPreflightRequest,
+  corsJsonResponse,
+  corsErrorResponse,
+} from '../_shared/cors.ts'
+
+const TOKEN_VERSION = 1
+/** How long a token keeps working without reaching this function */
+const GRACE_PERIOD_SECS = 14 * 24 * 60 * 60
+
+function base64url(bytes: Uint8Array): string {
+  return btoa(String.fromCharCode(...bytes))
+    .replace(/\+/g, '-')
+    .replace(/\//g, '_')
+    .replace(/=+$/, '')
+}
+
+async function signingKey(): Promise<CryptoKey> {
+  const encoded = Deno.env.get('ENTITLEMENT_SIGNING_KEY')
+  if (!encoded) throw new Error('ENTITLEMENT_SIGNING_KEY is not set')
+  const der = Uint8Array.from(atob(encoded), c => c.charCodeAt(0))
+  return crypto.subtle.importKey('pkcs8', der, { name: 'Ed25519' }, false, ['sign'])
+}
+
+serve(async (req) => {
+  if (req.method === 'OPTIONS') {
+    return handleCorsPreflightRequest(req)
+  }
+
+  try {
+    const serviceKey = Deno.env.get('SUPABASE_SERVICE_ROLE_KEY') ?? ''
+    const supabase = createClient(Deno.env.get('SUPABASE_URL') ?? '', serviceKey)
+
+    const authHeader = req.headers.get('authorization')
+    if (!authHeader?.startsWith('Bearer ')) {
+      return corsErrorResponse(req, 'Unauthorized', 401)
+    }
+    // The entitlement is always for the caller's own account
+    const { data: { user }, error: authError } = await supabase.auth.getUser(authHeader.slice(7))
+    if (authError || !user) {
+      return corsErrorResponse(req, 'Invalid token', 401)
+    }
+    const userId = user.id
+
+    const { data: subscription } = await supabase
+      .from('subscriptions')
+      .select('tier, current_period_end')
+      .eq('user_id', userId)
+      .maybeSingle()
+
+    const now = Math.floor(Date.now() / 1000)
+    let tier: string = subscription?.tier ?? 'core'
+    // A lapsed paid period drops to the free tier
+    const periodEnd = subscription?.current_period_end
+      ? Math.floor(new Date(subscription.current_period_end).getTime() / 1000)
+      : null
+    if (tier !== 'core' && periodEnd !== null && periodEnd < now) {
+      tier = 'core'
+    }
+
+    const payload = {
+      v: TOKEN_VERSION,
+      sub: userId,
+      tier,
+      iat: now,
+      exp: now + GRACE_PERIOD_SECS,
+    }
+    const payloadPart = base64url(new TextEncoder().encode(JSON.stringify(payload)))
+    const signature = await crypto.subtle.sign(
+      { name: 'Ed25519' },
+      await signingKey(),
+      new TextEncoder().encode(payloadPart)
+    )
+
+    return corsJsonResponse(req, {
+      token: `${payloadPart}.${base64url(new Uint8Array(signature))}`,
+      tier,
+      issued_at: payload.iat,
+      expires_at: payload.exp,
+    })
+  } catch (error) {
+    console.error('Entitlement error:', error)
+    return corsErrorResponse(req, 'Failed to issue entitlement', 500)
+  }
+})
@@ -0,0 +1,78 @@
+-- Supabase Migration: Session Handoff
+-- "Continue on other device": a device publishes the context of an active
+-- chat session (summary + recent turns), the user's other devices are told
+-- through Realtime and may explicitly accept it before it expires.
+-- Date: 2026-02-08
+
+BEGIN;
+
+-- ==============================================================================
+-- TABLE: Session Handoffs
+-- ==============================================================================
+-- One row per offered session. A handoff is accepted by at most one device:
+-- clients move it out of 'pending' with a conditional update.
+
+CREATE TABLE IF NOT EXISTS session_handoffs (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+
+  -- Offering device (user_instances.instance_id) and optional target
+  source_instance_id TEXT NOT NULL,
+  source_device_name TEXT,
+  target_instance_id TEXT, -- NULL: any of the user's devices
+
+  -- Session context
+  session_key TEXT NOT NULL,
+  title TEXT,
+  summary TEXT NOT NULL DEFAULT '',
+  turns JSONB NOT NULL DEFAULT '[]'::jsonb, -- [{role, text, timestamp}, ...]
+
+  -- Lifecycle
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'cancelled')),
+  responded_by TEXT, -- instance_id that accepted or declined
+  responded_at TIMESTAMPTZ,
+  expires_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_handoffs_user_pending ON session_handoffs(user_id, expires_at DESC) WHERE status = 'pending';
+
+-- RLS: Users can view and manage own handoffs
+ALTER TABLE session_handoffs ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "users_view_own_session_handoffs"
+  ON session_handoffs FOR SELECT
+  USING (auth.uid() = user_id);
+
+CREATE POLICY "users_insert_own_session_handoffs"
+  ON session_handoffs FOR INSERT
+  WITH CHECK (auth.uid() = user_id);
+
+CREATE POLICY "users_update_own_session_handoffs"
+  ON session_handoffs FOR UPDATE
+  USING (auth.uid() = user_id);
+
+CREATE POLICY "users_delete_own_session_handoffs"
+  ON session_handoffs FOR DELETE
+  USING (auth.uid() = user_id);
+
+-- Other devices learn about offers through Realtime
+ALTER PUBLICATION supabase_realtime ADD TABLE session_handoffs;
+
+-- Handoffs carry transcript excerpts; drop them a day after they expire
+CREATE OR REPLACE FUNCTION cleanup_expired_session_handoffs()
+RETURNS TABLE (
+  deleted_count INTEGER
+) AS $$
+DECLARE
+  v_count INTEGER;
+BEGIN
+  DELETE FROM session_handoffs
+  WHERE expires_at < NOW() - INTERVAL '1 day';
+
+  GET DIAGNOSTICS v_count = ROW_COUNT;
+  RETURN QUERY SELECT v_count;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMIT;
@@ -0,0 +1,23 @@
+-- Supabase Migration: Device Retirement and Remote Sign-out
+-- A device can retire itself (deregistration: it stops reporting and drops
+-- off the device list), or be signed out from another device or the web
+-- dashboard by setting revoked_at; the revoked desktop learns of it through
+-- Realtime, signs out and wipes what it cached from the cloud.
+-- Date: 2026-02-09
+
+BEGIN;
+
+ALTER TABLE user_instances
+  ADD COLUMN IF NOT EXISTS retired_at TIMESTAMPTZ,
+  ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ,
+  ADD COLUMN IF NOT EXISTS revoked_by TEXT; -- instance_id, or 'web'
+
+CREATE INDEX IF NOT EXISTS idx_user_instances_active
+  ON user_instances(user_id)
+  WHERE retired_at IS NULL AND revoked_at IS NULL;
+
+-- Revoked devices must see their own row change; Realtime sends the old
+-- row on deletes only with full replica identity
+ALTER TABLE user_instances REPLICA IDENTITY FULL;
+
+COMMIT;
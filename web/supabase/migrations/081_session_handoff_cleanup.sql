@@ -0,0 +1,20 @@
+-- Supabase Migration: Session Handoff Cleanup
+-- Runs cleanup_expired_session_handoffs (079) hourly with pg_cron, so
+-- transcript excerpts in expired handoffs don't outlive their day of grace.
+-- Only the scheduler runs it: clients work through row-level security and
+-- have no business deleting handoffs in bulk.
+-- Date: 2026-02-10
+
+BEGIN;
+
+CREATE EXTENSION IF NOT EXISTS pg_cron;
+
+REVOKE EXECUTE ON FUNCTION cleanup_expired_session_handoffs() FROM PUBLIC, anon, authenticated;
+
+SELECT cron.schedule(
+  'cleanup-expired-session-handoffs',
+  '17 * * * *',
+  $$SELECT cleanup_expired_session_handoffs()$$
+);
+
+COMMIT;